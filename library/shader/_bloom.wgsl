@@ -0,0 +1,147 @@
+struct BloomStep_std140_0
+{
+    source_offset_0 : u32,
+    source_width_0 : u32,
+    source_height_0 : u32,
+    destination_offset_0 : u32,
+    destination_width_0 : u32,
+    destination_height_0 : u32,
+    horizontal_0 : u32,
+    threshold_0 : f32,
+    inverse_frame_number_0 : f32,
+    empty_slot_0_0 : f32,
+    empty_slot_1_0 : f32,
+    empty_slot_2_0 : f32,
+};
+
+@binding(0) @group(0) var<uniform> bloom_step_0 : BloomStep_std140_0;
+@binding(2) @group(0) var<storage, read_write> bloom_chain_0 : array<vec4<f32>>;
+
+@binding(1) @group(0) var<storage, read> pixel_color_buffer_0 : array<vec4<f32>>;
+
+fn outside_destination_0( pixel_0 : vec2<u32>) -> bool
+{
+    var _S1 : bool;
+    if((pixel_0.x) >= (bloom_step_0.destination_width_0))
+    {
+        _S1 = true;
+    }
+    else
+    {
+        _S1 = (pixel_0.y) >= (bloom_step_0.destination_height_0);
+    }
+    return _S1;
+}
+
+fn destination_index_0( pixel_1 : vec2<u32>) -> u32
+{
+    return bloom_step_0.destination_offset_0 + pixel_1.y * bloom_step_0.destination_width_0 + pixel_1.x;
+}
+
+fn source_texel_0( texel_0 : vec2<i32>) -> vec3<f32>
+{
+    var clamped_0 : vec2<i32> = clamp(texel_0, vec2<i32>(i32(0)), vec2<i32>(i32(bloom_step_0.source_width_0), i32(bloom_step_0.source_height_0)) - vec2<i32>(i32(1)));
+    return bloom_chain_0[bloom_step_0.source_offset_0 + u32(clamped_0.y) * bloom_step_0.source_width_0 + u32(clamped_0.x)].xyz;
+}
+
+fn source_box_0( pixel_2 : vec2<u32>) -> vec3<f32>
+{
+    var corner_0 : vec2<i32> = vec2<i32>(pixel_2 * vec2<u32>(2u));
+    return vec3<f32>(0.25f) * (source_texel_0(corner_0) + source_texel_0(corner_0 + vec2<i32>(i32(1), i32(0))) + source_texel_0(corner_0 + vec2<i32>(i32(0), i32(1))) + source_texel_0(corner_0 + vec2<i32>(i32(1), i32(1))));
+}
+
+fn bright_part_0( pixel_3 : vec2<u32>) -> vec3<f32>
+{
+    var clamped_1 : vec2<u32> = min(pixel_3, vec2<u32>(bloom_step_0.source_width_0, bloom_step_0.source_height_0) - vec2<u32>(1u));
+    var radiance_0 : vec3<f32> = pixel_color_buffer_0[clamped_1.y * bloom_step_0.source_width_0 + clamped_1.x].xyz * vec3<f32>(bloom_step_0.inverse_frame_number_0);
+    var luminance_0 : f32 = dot(radiance_0, vec3<f32>(0.21259999275207520f, 0.71520000696182251f, 0.07220000028610229f));
+    return radiance_0 * vec3<f32>((max(luminance_0 - bloom_step_0.threshold_0, 0.0f) / max(luminance_0, 0.00009999999747379f)));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn bloom_bright_pass(@builtin(global_invocation_id) global_invocation_id_0 : vec3<u32>)
+{
+    var pixel_4 : vec2<u32> = global_invocation_id_0.xy;
+    if(outside_destination_0(pixel_4))
+    {
+        return;
+    }
+    var corner_1 : vec2<u32> = pixel_4 * vec2<u32>(2u);
+    bloom_chain_0[destination_index_0(pixel_4)] = vec4<f32>(vec3<f32>(0.25f) * (bright_part_0(corner_1) + bright_part_0(corner_1 + vec2<u32>(u32(1), u32(0))) + bright_part_0(corner_1 + vec2<u32>(u32(0), u32(1))) + bright_part_0(corner_1 + vec2<u32>(u32(1), u32(1)))), 1.0f);
+    return;
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn bloom_downsample(@builtin(global_invocation_id) global_invocation_id_1 : vec3<u32>)
+{
+    var pixel_5 : vec2<u32> = global_invocation_id_1.xy;
+    if(outside_destination_0(pixel_5))
+    {
+        return;
+    }
+    bloom_chain_0[destination_index_0(pixel_5)] = vec4<f32>(source_box_0(pixel_5), 1.0f);
+    return;
+}
+
+const BLUR_WEIGHTS_0 : array<f32, i32(5)> = array<f32, i32(5)>( 0.22702699899673462f, 0.19459460675716400f, 0.12162160128355026f, 0.05405399948358536f, 0.01621600054204464f );
+
+@compute
+@workgroup_size(8, 8, 1)
+fn bloom_blur(@builtin(global_invocation_id) global_invocation_id_2 : vec3<u32>)
+{
+    var pixel_6 : vec2<u32> = global_invocation_id_2.xy;
+    if(outside_destination_0(pixel_6))
+    {
+        return;
+    }
+    var direction_0 : vec2<i32>;
+    if(u32(0) == (bloom_step_0.horizontal_0))
+    {
+        direction_0 = vec2<i32>(i32(0), i32(1));
+    }
+    else
+    {
+        direction_0 = vec2<i32>(i32(1), i32(0));
+    }
+    var _S2 : vec2<i32> = vec2<i32>(pixel_6);
+    var blurred_0 : vec3<f32> = vec3<f32>(BLUR_WEIGHTS_0[i32(0)]) * source_texel_0(_S2);
+    var i_0 : i32 = i32(1);
+    for(;;)
+    {
+        if(i_0 < i32(5))
+        {
+        }
+        else
+        {
+            break;
+        }
+        var _S3 : vec2<i32> = direction_0 * vec2<i32>(i_0);
+        blurred_0 = blurred_0 + vec3<f32>(BLUR_WEIGHTS_0[i_0]) * (source_texel_0(_S2 + _S3) + source_texel_0(_S2 - _S3));
+        i_0 = i_0 + i32(1);
+    }
+    bloom_chain_0[destination_index_0(pixel_6)] = vec4<f32>(blurred_0, 1.0f);
+    return;
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn bloom_upsample(@builtin(global_invocation_id) global_invocation_id_3 : vec3<u32>)
+{
+    var pixel_7 : vec2<u32> = global_invocation_id_3.xy;
+    if(outside_destination_0(pixel_7))
+    {
+        return;
+    }
+    var position_0 : vec2<f32> = (vec2<f32>(pixel_7) + vec2<f32>(0.5f)) * vec2<f32>(0.5f) - vec2<f32>(0.5f);
+    var corner_2 : vec2<f32> = floor(position_0);
+    var weight_0 : vec2<f32> = position_0 - corner_2;
+    var texel_1 : vec2<i32> = vec2<i32>(corner_2);
+    var top_0 : vec3<f32> = mix(source_texel_0(texel_1), source_texel_0(texel_1 + vec2<i32>(i32(1), i32(0))), vec3<f32>(weight_0.x));
+    var bottom_0 : vec3<f32> = mix(source_texel_0(texel_1 + vec2<i32>(i32(0), i32(1))), source_texel_0(texel_1 + vec2<i32>(i32(1), i32(1))), vec3<f32>(weight_0.x));
+    var index_0 : u32 = destination_index_0(pixel_7);
+    bloom_chain_0[index_0] = vec4<f32>(bloom_chain_0[index_0].xyz + mix(top_0, bottom_0, vec3<f32>(weight_0.y)), 1.0f);
+    return;
+}
+
@@ -2,6 +2,27 @@
 
 $slangcPath = Get-SlangCompilerPath
 
+function Remove-AlignmentAttributes($compiledShaderFileName) {
+    if (Test-Path $compiledShaderFileName) {
+        Write-Host "Removing alignment attributes from $compiledShaderFileName..."
+
+        $content = Get-Content $compiledShaderFileName -Raw
+
+        # Those @align are not supported in WebGPU/WGSL
+        $content = $content.Replace('@align(16) ', '')
+        $content = $content.Replace('@align(8) ', '')
+        $content = $content.Replace('@align(4) ', '')
+        $content = $content.Replace('@align(16)', '')
+        $content = $content.Replace('@align(8)', '')
+        $content = $content.Replace('@align(4)', '')
+
+        # Normalize line endings to LF
+        $content = $content.Replace("`r`n", "`n").Replace("`r", "`n")
+
+        Set-Content $compiledShaderFileName $content -NoNewline
+    }
+}
+
 $fileToCompile = "tracer.slang"
 
 $compiledShaderFileName = "_tracer.wgsl"
@@ -9,21 +30,35 @@ $reflectionFileName = "_reflection.json"
 
 & $slangcPath $fileToCompile -target wgsl -o $compiledShaderFileName -reflection-json $reflectionFileName -warnings-as-errors all -matrix-layout-column-major -no-mangle
 
-if (Test-Path $compiledShaderFileName) {
-    Write-Host "Removing alignment attributes from $compiledShaderFileName..."
-    
-    $content = Get-Content $compiledShaderFileName -Raw
-    
-    # Those @align are not supported in WebGPU/WGSL
-    $content = $content.Replace('@align(16) ', '')
-    $content = $content.Replace('@align(8) ', '')
-    $content = $content.Replace('@align(4) ', '')
-    $content = $content.Replace('@align(16)', '')
-    $content = $content.Replace('@align(8)', '')
-    $content = $content.Replace('@align(4)', '')
-    
-    # Normalize line endings to LF
-    $content = $content.Replace("`r`n", "`n").Replace("`r", "`n")
-    
-    Set-Content $compiledShaderFileName $content -NoNewline
-}
+Remove-AlignmentAttributes $compiledShaderFileName
+
+# the overlay, the selection outline, the bloom, the denoised blend and the picking are run by pipelines of their own, independent of the scene code
+$overlayShaderFileName = "_overlay.wgsl"
+
+& $slangcPath "overlay.slang" -target wgsl -o $overlayShaderFileName -warnings-as-errors all -no-mangle
+
+Remove-AlignmentAttributes $overlayShaderFileName
+
+$selectionOutlineShaderFileName = "_selection_outline.wgsl"
+
+& $slangcPath "selection_outline.slang" -target wgsl -o $selectionOutlineShaderFileName -warnings-as-errors all -no-mangle
+
+Remove-AlignmentAttributes $selectionOutlineShaderFileName
+
+$bloomShaderFileName = "_bloom.wgsl"
+
+& $slangcPath "bloom.slang" -target wgsl -o $bloomShaderFileName -warnings-as-errors all -no-mangle
+
+Remove-AlignmentAttributes $bloomShaderFileName
+
+$denoisedBlendShaderFileName = "_denoised_blend.wgsl"
+
+& $slangcPath "denoised_blend.slang" -target wgsl -o $denoisedBlendShaderFileName -warnings-as-errors all -no-mangle
+
+Remove-AlignmentAttributes $denoisedBlendShaderFileName
+
+$pickingShaderFileName = "_picking.wgsl"
+
+& $slangcPath "picking.slang" -target wgsl -o $pickingShaderFileName -warnings-as-errors all -no-mangle
+
+Remove-AlignmentAttributes $pickingShaderFileName
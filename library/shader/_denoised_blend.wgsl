@@ -0,0 +1,38 @@
+struct DenoisedBlend_std140_0
+{
+    frame_width_0 : u32,
+    frame_height_0 : u32,
+    noisy_weight_0 : f32,
+    empty_slot_0_0 : f32,
+};
+
+@binding(0) @group(0) var<uniform> denoised_blend_parameters_0 : DenoisedBlend_std140_0;
+@binding(3) @group(0) var<storage, read_write> blended_pixel_color_buffer_0 : array<vec4<f32>>;
+
+@binding(2) @group(0) var<storage, read> denoised_pixel_color_buffer_0 : array<vec4<f32>>;
+
+@binding(1) @group(0) var<storage, read> pixel_color_buffer_0 : array<vec4<f32>>;
+
+@compute
+@workgroup_size(8, 8, 1)
+fn denoised_blend(@builtin(global_invocation_id) global_invocation_id_0 : vec3<u32>)
+{
+    var pixel_0 : vec2<u32> = global_invocation_id_0.xy;
+    var _S1 : bool;
+    if((pixel_0.x) >= (denoised_blend_parameters_0.frame_width_0))
+    {
+        _S1 = true;
+    }
+    else
+    {
+        _S1 = (pixel_0.y) >= (denoised_blend_parameters_0.frame_height_0);
+    }
+    if(_S1)
+    {
+        return;
+    }
+    var index_0 : u32 = pixel_0.y * denoised_blend_parameters_0.frame_width_0 + pixel_0.x;
+    blended_pixel_color_buffer_0[index_0] = vec4<f32>(denoised_pixel_color_buffer_0[index_0].xyz + vec3<f32>(denoised_blend_parameters_0.noisy_weight_0) * pixel_color_buffer_0[index_0].xyz, 1.0f);
+    return;
+}
+
@@ -0,0 +1,59 @@
+struct OverlayUniforms_std140_0
+{
+    target_size_0 : vec2<f32>,
+    padding_0 : vec2<f32>,
+};
+
+@binding(0) @group(0) var<uniform> overlay_uniforms_0 : OverlayUniforms_std140_0;
+@binding(2) @group(0) var glyph_atlas_0 : texture_2d<f32>;
+
+@binding(1) @group(0) var glyph_atlas_sampler_0 : sampler;
+
+struct VSOutput_0
+{
+    @builtin(position) position_0 : vec4<f32>,
+    @location(0) uv_0 : vec2<f32>,
+    @location(1) color_0 : vec4<f32>,
+};
+
+struct vertexInput_0
+{
+    @location(0) position_1 : vec2<f32>,
+    @location(1) uv_1 : vec2<f32>,
+    @location(2) color_1 : vec4<f32>,
+};
+
+@vertex
+fn vs( _S1 : vertexInput_0) -> VSOutput_0
+{
+    var normalized_0 : vec2<f32> = _S1.position_1 / overlay_uniforms_0.target_size_0;
+    var output_0 : VSOutput_0;
+    output_0.position_0 = vec4<f32>(normalized_0.x * 2.0f - 1.0f, 1.0f - normalized_0.y * 2.0f, 0.0f, 1.0f);
+    output_0.uv_0 = _S1.uv_1;
+    output_0.color_0 = _S1.color_1;
+    return output_0;
+}
+
+struct pixelOutput_0
+{
+    @location(0) output_1 : vec4<f32>,
+};
+
+struct pixelInput_0
+{
+    @location(0) uv_2 : vec2<f32>,
+    @location(1) color_2 : vec4<f32>,
+};
+
+@fragment
+fn fs( _S2 : pixelInput_0, @builtin(position) position_2 : vec4<f32>) -> pixelOutput_0
+{
+    var coverage_0 : f32 = (textureSampleLevel((glyph_atlas_0), (glyph_atlas_sampler_0), (max(_S2.uv_2, vec2<f32>(0.0f))), (0.0f))).w;
+    if((_S2.uv_2.x) < 0.0f)
+    {
+        coverage_0 = 1.0f;
+    }
+    var _S3 : pixelOutput_0 = pixelOutput_0( vec4<f32>(_S2.color_2.xyz, _S2.color_2.w * coverage_0) );
+    return _S3;
+}
+
@@ -0,0 +1,30 @@
+struct PickRequest_std140_0
+{
+    pixel_index_0 : u32,
+    frame_buffer_area_0 : u32,
+    empty_slot_0_0 : u32,
+    empty_slot_1_0 : u32,
+};
+
+@binding(0) @group(0) var<uniform> pick_request_0 : PickRequest_std140_0;
+@binding(1) @group(0) var<storage, read> object_id_buffer_0 : array<u32>;
+
+@binding(2) @group(0) var<storage, read_write> picked_object_id_0 : array<u32>;
+
+@compute
+@workgroup_size(1, 1, 1)
+fn pick_object_id()
+{
+    var _S1 : u32;
+    if((pick_request_0.pixel_index_0) < (pick_request_0.frame_buffer_area_0))
+    {
+        _S1 = object_id_buffer_0[pick_request_0.pixel_index_0];
+    }
+    else
+    {
+        _S1 = 0u;
+    }
+    picked_object_id_0[i32(0)] = _S1;
+    return;
+}
+
@@ -0,0 +1,133 @@
+struct SelectionOutlineUniforms_std140_0
+{
+    color_0 : vec4<f32>,
+    frame_buffer_size_0 : vec2<u32>,
+    highlighted_count_0 : u32,
+    thickness_0 : u32,
+};
+
+@binding(0) @group(0) var<uniform> outline_uniforms_0 : SelectionOutlineUniforms_std140_0;
+@binding(1) @group(0) var<storage, read> object_id_buffer_0 : array<u32>;
+
+@binding(2) @group(0) var<storage, read> highlighted_objects_0 : array<u32>;
+
+const full_screen_quad_positions_0 : array<vec2<f32>, i32(6)> = array<vec2<f32>, i32(6)>( vec2<f32>(-1.0f, -1.0f), vec2<f32>(1.0f, -1.0f), vec2<f32>(-1.0f, 1.0f), vec2<f32>(-1.0f, 1.0f), vec2<f32>(1.0f, -1.0f), vec2<f32>(1.0f, 1.0f) );
+struct VSOutput_0
+{
+    @builtin(position) position_0 : vec4<f32>,
+    @location(0) frame_uv_0 : vec2<f32>,
+};
+
+@vertex
+fn vs(@builtin(vertex_index) in_vertex_index_0 : u32) -> VSOutput_0
+{
+    var _S1 : vec2<f32> = full_screen_quad_positions_0[in_vertex_index_0];
+    var output_0 : VSOutput_0;
+    output_0.position_0 = vec4<f32>(_S1, 0.0f, 1.0f);
+    output_0.frame_uv_0 = vec2<f32>(0.5f, -0.5f) * _S1 + vec2<f32>(0.5f);
+    return output_0;
+}
+
+fn object_at_0( pixel_0 : vec2<i32>) -> u32
+{
+    return object_id_buffer_0[u32(pixel_0.y) * outline_uniforms_0.frame_buffer_size_0.x + u32(pixel_0.x)];
+}
+
+fn highlighted_0( object_uid_0 : u32) -> bool
+{
+    if(u32(0) == object_uid_0)
+    {
+        return false;
+    }
+    var i_0 : u32 = u32(0);
+    for(;;)
+    {
+        if(i_0 < (outline_uniforms_0.highlighted_count_0))
+        {
+        }
+        else
+        {
+            break;
+        }
+        if((highlighted_objects_0[i_0]) == object_uid_0)
+        {
+            return true;
+        }
+        i_0 = i_0 + u32(1);
+    }
+    return false;
+}
+
+struct pixelOutput_0
+{
+    @location(0) output_1 : vec4<f32>,
+};
+
+struct pixelInput_0
+{
+    @location(0) frame_uv_1 : vec2<f32>,
+};
+
+@fragment
+fn fs( _S2 : pixelInput_0, @builtin(position) position_1 : vec4<f32>) -> pixelOutput_0
+{
+    var size_0 : vec2<i32> = vec2<i32>(outline_uniforms_0.frame_buffer_size_0);
+    var pixel_1 : vec2<i32> = min(vec2<i32>(_S2.frame_uv_1 * vec2<f32>(size_0)), size_0 - vec2<i32>(i32(1)));
+    var own_0 : u32 = object_at_0(pixel_1);
+    var thickness_1 : i32 = i32(outline_uniforms_0.thickness_0);
+    var dy_0 : i32 = - thickness_1;
+    for(;;)
+    {
+        if(dy_0 <= thickness_1)
+        {
+        }
+        else
+        {
+            break;
+        }
+        var dx_0 : i32 = - thickness_1;
+        for(;;)
+        {
+            if(dx_0 <= thickness_1)
+            {
+            }
+            else
+            {
+                break;
+            }
+            var neighbour_0 : vec2<i32> = pixel_1 + vec2<i32>(dx_0, dy_0);
+            var _S3 : bool;
+            if(any(neighbour_0 < vec2<i32>(i32(0))))
+            {
+                _S3 = true;
+            }
+            else
+            {
+                _S3 = any(neighbour_0 >= size_0);
+            }
+            if(!_S3)
+            {
+                var other_0 : u32 = object_at_0(neighbour_0);
+                if(other_0 != own_0)
+                {
+                    _S3 = highlighted_0(other_0);
+                }
+                else
+                {
+                    _S3 = false;
+                }
+                if(_S3)
+                {
+                    var _S4 : pixelOutput_0 = pixelOutput_0( outline_uniforms_0.color_0 );
+                    return _S4;
+                }
+            }
+            dx_0 = dx_0 + i32(1);
+        }
+        dy_0 = dy_0 + i32(1);
+    }
+    discard;
+    var _S5 : pixelOutput_0 = pixelOutput_0( vec4<f32>(0.0f) );
+    return _S5;
+}
+
@@ -5,7 +5,7 @@ struct Uniforms_std140_0
     frame_buffer_aspect_0 : f32,
     inverted_frame_buffer_size_0 : vec2<f32>,
     frame_number_0 : f32,
-    empty_slot_1_0 : f32,
+    background_uid_0 : u32,
     view_matrix_col_0_0 : vec4<f32>,
     view_matrix_col_1_0 : vec4<f32>,
     view_matrix_col_2_0 : vec4<f32>,
@@ -19,11 +19,70 @@ struct Uniforms_std140_0
     pixel_side_subdivision_0 : u32,
     global_time_seconds_0 : f32,
     thread_grid_size_0 : vec3<u32>,
-    empty_slot_2_0 : f32,
+    camera_fov_factor_0 : f32,
+    probe_grid_min_0 : vec3<f32>,
+    probe_grid_probes_count_0 : u32,
+    probe_grid_cell_size_0 : vec3<f32>,
+    empty_slot_3_0 : f32,
+    probe_grid_resolution_0 : vec3<u32>,
+    empty_slot_4_0 : f32,
+    grading_white_balance_0 : vec3<f32>,
+    grading_exposure_scale_0 : f32,
+    grading_contrast_0 : f32,
+    grading_saturation_0 : f32,
+    empty_slot_5_0 : f32,
+    empty_slot_6_0 : f32,
+    auto_exposure_enabled_0 : u32,
+    auto_exposure_min_scale_0 : f32,
+    auto_exposure_max_scale_0 : f32,
+    auto_exposure_adaptation_speed_0 : f32,
+    reprojection_history_frames_0 : f32,
+    reprojection_history_scale_0 : f32,
+    previous_camera_fov_factor_0 : f32,
+    empty_slot_8_0 : f32,
+    previous_world_to_camera_col_0_0 : vec4<f32>,
+    previous_world_to_camera_col_1_0 : vec4<f32>,
+    previous_world_to_camera_col_2_0 : vec4<f32>,
+    previous_world_to_camera_col_3_0 : vec4<f32>,
+    previous_view_ray_origin_matrix_col_0_0 : vec4<f32>,
+    previous_view_ray_origin_matrix_col_1_0 : vec4<f32>,
+    previous_view_ray_origin_matrix_col_2_0 : vec4<f32>,
+    previous_view_ray_origin_matrix_col_3_0 : vec4<f32>,
+    clear_color_0 : vec4<f32>,
+    geometry_quantized_0 : u32,
+    magnifier_center_x_0 : u32,
+    magnifier_center_y_0 : u32,
+    magnifier_zoom_0 : u32,
+    sdf_lod_threshold_pixels_0 : f32,
+    emissive_parallelograms_count_0 : u32,
+    next_event_estimation_0 : u32,
+    camera_layer_mask_0 : u32,
+    max_ray_bounces_0 : u32,
+    upscale_sharpness_0 : f32,
+    shadow_map_resolution_0 : u32,
+    output_dithering_0 : u32,
+    display_tone_mapping_0 : u32,
+    display_exposure_scale_0 : f32,
+    display_inverse_gamma_0 : f32,
+    display_extended_range_0 : u32,
+    bloom_intensity_0 : f32,
+    empty_slot_13_0 : f32,
+    empty_slot_14_0 : f32,
+    empty_slot_15_0 : f32,
+    lighting_aovs_enabled_0 : u32,
+    random_generator_0 : u32,
+    random_seeds_width_0 : u32,
+    random_seeds_height_0 : u32,
+    camera_near_clip_0 : f32,
+    max_ray_distance_0 : f32,
+    empty_slot_16_0 : f32,
+    empty_slot_17_0 : f32,
 };
 
 @binding(0) @group(0) var<uniform> uniforms : Uniforms_std140_0;
 @binding(0) @group(1) var<storage, read_write> pixel_color_buffer : array<vec4<f32>>;
+@binding(7) @group(1) var<storage, read_write> auto_exposure_buffer : array<f32>;
+@binding(17) @group(1) var<storage, read_write> bloom_buffer : array<vec4<f32>>;
 
 struct Parallelogram_std430_0
 {
@@ -39,30 +98,9 @@ struct Parallelogram_std430_0
 
 @binding(0) @group(2) var<storage, read> parallelograms : array<Parallelogram_std430_0>;
 
-struct BvhNode_std430_0
-{
-    aabb_min_0 : vec3<f32>,
-    primitive_index_0 : u32,
-    aabb_max_0 : vec3<f32>,
-    primitive_type_0 : u32,
-    hit_miss_skip_link_0 : i32,
-};
-
-@binding(4) @group(2) var<storage, read> bvh : array<BvhNode_std430_0>;
-
-struct Triangle_std430_0
-{
-    A_0 : vec3<f32>,
-    B_0 : vec3<f32>,
-    C_0 : vec3<f32>,
-    normalA_0 : vec3<f32>,
-    normalB_0 : vec3<f32>,
-    object_uid_1 : u32,
-    normalC_0 : vec3<f32>,
-    material_id_1 : u32,
-};
+@binding(4) @group(2) var<storage, read> bvh : array<vec4<f32>>;
 
-@binding(2) @group(2) var<storage, read> triangles : array<Triangle_std430_0>;
+@binding(2) @group(2) var<storage, read> triangles : array<vec4<f32>>;
 
 struct Sdf_std430_0
 {
@@ -76,6 +114,7 @@ struct Sdf_std430_0
     class_index_0 : i32,
     material_id_2 : u32,
     object_uid_2 : u32,
+    parameters_0 : vec4<f32>,
 };
 
 @binding(1) @group(2) var<storage, read> sdf : array<Sdf_std430_0>;
@@ -85,13 +124,16 @@ struct Sdf_std430_0
 struct Material_std430_0
 {
     albedo_0 : vec3<f32>,
+    surface_shader_uid_0 : i32,
     emission_0 : vec3<f32>,
+    normal_texture_uid_0 : i32,
     specular_0 : vec3<f32>,
     specular_strength_0 : f32,
     roughness_0 : f32,
     refractive_index_eta_0 : f32,
     albedo_texture_uid_0 : i32,
     material_class_0 : i32,
+    absorption_0 : vec3<f32>,
 };
 
 @binding(3) @group(2) var<storage, read> materials : array<Material_std430_0>;
@@ -107,6 +149,31 @@ struct AtlasMapping_std430_0
 
 @binding(7) @group(2) var<storage, read> texture_atlases_mapping : array<AtlasMapping_std430_0>;
 
+@binding(8) @group(2) var<storage, read_write> irradiance_probes : array<vec4<f32>>;
+
+@binding(9) @group(2) var<storage, read> emissive_parallelograms : array<vec2<u32>>;
+
+struct Sphere_std430_0
+{
+    center_4 : vec3<f32>,
+    radius_4 : f32,
+    object_uid_4 : u32,
+    material_id_4 : u32,
+};
+
+@binding(10) @group(2) var<storage, read> spheres : array<Sphere_std430_0>;
+
+@binding(11) @group(2) var<storage, read> object_layers : array<u32>;
+
+@binding(12) @group(2) var<storage, read> sdf_distance_grids : array<f32>;
+
+@binding(13) @group(2) var<storage, read> light_tree : array<vec4<f32>>;
+@binding(14) @group(2) var<storage, read_write> shadow_map : array<f32>;
+
+@binding(15) @group(2) var<storage, read> object_uv_transforms : array<vec4<f32>>;
+
+@binding(16) @group(2) var<storage, read> object_opacities : array<f32>;
+
 @binding(2) @group(0) var texture_atlas_page : texture_2d<f32>;
 
 @binding(1) @group(0) var atlases_sampler : sampler;
@@ -117,12 +184,23 @@ struct AtlasMapping_std430_0
 
 @binding(2) @group(1) var<storage, read_write> normal_buffer : array<vec4<f32>>;
 
-@binding(5) @group(2) var<storage, read> bvh_inflated : array<BvhNode_std430_0>;
+@binding(15) @group(1) var<storage, read_write> depth_buffer : array<f32>;
+
+@binding(18) @group(1) var<storage, read_write> motion_vector_buffer : array<vec2<f32>>;
+
+@binding(5) @group(2) var<storage, read> bvh_inflated : array<vec4<f32>>;
+
+@binding(5) @group(1) var<storage, read_write> indirect_half_resolution_guide_buffer : array<vec4<f32>>;
+
+@binding(4) @group(1) var<storage, read_write> indirect_half_resolution_buffer : array<vec4<f32>>;
+
+@binding(21) @group(1) var<storage, read_write> direct_full_resolution_buffer : array<vec4<f32>>;
 
 const full_screen_quad_positions_0 : array<vec2<f32>, i32(6)> = array<vec2<f32>, i32(6)>( vec2<f32>(-1.0f, -1.0f), vec2<f32>(1.0f, -1.0f), vec2<f32>(-1.0f, 1.0f), vec2<f32>(-1.0f, 1.0f), vec2<f32>(1.0f, -1.0f), vec2<f32>(1.0f, 1.0f) );
 struct VSOutput_0
 {
     @builtin(position) position_0 : vec4<f32>,
+    @location(0) frame_uv_0 : vec2<f32>,
 };
 
 var<private> randState : u32;
@@ -131,14 +209,49 @@ var<private> randState : u32;
 fn vs(@builtin(vertex_index) in_vertex_index_0 : u32) -> VSOutput_0
 {
     randState = u32(0);
+    var _S205 : vec2<f32> = full_screen_quad_positions_0[in_vertex_index_0];
     var output_0 : VSOutput_0;
-    output_0.position_0 = vec4<f32>(full_screen_quad_positions_0[in_vertex_index_0], 0.0f, 1.0f);
+    output_0.position_0 = vec4<f32>(_S205, 0.0f, 1.0f);
+    output_0.frame_uv_0 = vec2<f32>(0.5f, -0.5f) * _S205 + vec2<f32>(0.5f);
     return output_0;
 }
 
-fn pixel_global_index_0( pixel_position_0 : vec2<f32>,  frame_buffer_width_0 : u32) -> u32
+fn auto_exposure_scale_0() -> f32
+{
+    var _S201 : f32;
+    if(u32(0) == (uniforms.auto_exposure_enabled_0))
+    {
+        _S201 = 1.0f;
+    }
+    else
+    {
+        _S201 = auto_exposure_buffer[i32(1)];
+    }
+    return _S201;
+}
+
+fn bloom_texel_0( texel_0 : vec2<i32>,  size_0 : vec2<i32>) -> vec3<f32>
+{
+    var clamped_0 : vec2<i32> = clamp(texel_0, vec2<i32>(i32(0)), size_0 - vec2<i32>(i32(1)));
+    return bloom_buffer[clamped_0.y * size_0.x + clamped_0.x].xyz;
+}
+
+fn bloom_at_0( pixel_index_0 : u32) -> vec3<f32>
+{
+    var width_0 : u32 = uniforms.frame_buffer_size_0.x;
+    var size_1 : vec2<i32> = vec2<i32>((uniforms.frame_buffer_size_0 + vec2<u32>(u32(1))) / vec2<u32>(u32(2)));
+    var position_0 : vec2<f32> = (vec2<f32>(f32(pixel_index_0 % width_0), f32(pixel_index_0 / width_0)) + vec2<f32>(0.5f)) * vec2<f32>(0.5f) - vec2<f32>(0.5f);
+    var corner_0 : vec2<f32> = floor(position_0);
+    var weight_0 : vec2<f32> = position_0 - corner_0;
+    var texel_1 : vec2<i32> = vec2<i32>(corner_0);
+    var top_0 : vec3<f32> = mix(bloom_texel_0(texel_1, size_1), bloom_texel_0(texel_1 + vec2<i32>(i32(1), i32(0)), size_1), vec3<f32>(weight_0.x));
+    var bottom_0 : vec3<f32> = mix(bloom_texel_0(texel_1 + vec2<i32>(i32(0), i32(1)), size_1), bloom_texel_0(texel_1 + vec2<i32>(i32(1), i32(1)), size_1), vec3<f32>(weight_0.x));
+    return mix(top_0, bottom_0, vec3<f32>(weight_0.y));
+}
+
+fn downsample_side_0( footprint_0 : vec2<f32>) -> vec2<u32>
 {
-    return u32(pixel_position_0.y) * frame_buffer_width_0 + u32(pixel_position_0.x);
+    return clamp(vec2<u32>(round(footprint_0)), vec2<u32>(u32(1)), vec2<u32>(u32(4)));
 }
 
 fn aces_approx_0( v_1 : vec3<f32>) -> vec3<f32>
@@ -147,6 +260,146 @@ fn aces_approx_0( v_1 : vec3<f32>) -> vec3<f32>
     return clamp(v1_0 * (vec3<f32>(2.50999999046325684f) * v1_0 + vec3<f32>(0.02999999932944775f)) / (v1_0 * (vec3<f32>(2.43000006675720215f) * v1_0 + vec3<f32>(0.5899999737739563f)) + vec3<f32>(0.14000000059604645f)), vec3<f32>(0.0f), vec3<f32>(1.0f));
 }
 
+fn reinhard_0( v_2 : vec3<f32>) -> vec3<f32>
+{
+    return v_2 / (vec3<f32>(1.0f) + v_2);
+}
+
+fn tone_map_0( color_0 : vec3<f32>,  tone_mapping_0 : u32) -> vec3<f32>
+{
+    if(u32(0) == tone_mapping_0)
+    {
+        return aces_approx_0(color_0);
+    }
+    if(u32(1) == tone_mapping_0)
+    {
+        return reinhard_0(color_0);
+    }
+    return saturate(color_0);
+}
+
+fn grade_extended_range_0( radiance_0 : vec3<f32>,  contrast_1 : f32,  saturation_1 : f32) -> vec3<f32>
+{
+    return vec3<f32>(0.18000000715255737f) * pow(max(mix(vec3<f32>(dot(radiance_0, vec3<f32>(0.21259999275207520f, 0.71520000696182251f, 0.07220000028610229f))), radiance_0, vec3<f32>(saturation_1)), vec3<f32>(0.0f)) / vec3<f32>(0.18000000715255737f), vec3<f32>(contrast_1));
+}
+
+fn grade_contrast_and_saturation_0( tone_mapped_0 : vec3<f32>,  contrast_0 : f32,  saturation_0 : f32) -> vec3<f32>
+{
+    return saturate((mix(vec3<f32>(dot(tone_mapped_0, vec3<f32>(0.21259999275207520f, 0.71520000696182251f, 0.07220000028610229f))), tone_mapped_0, vec3<f32>(saturation_0)) - vec3<f32>(0.5f)) * vec3<f32>(contrast_0) + vec3<f32>(0.5f));
+}
+
+fn graded_pixel_color_0( pixel_index_1 : u32) -> vec3<f32>
+{
+    var color_1 : vec3<f32> = pixel_color_buffer[pixel_index_1].xyz / vec3<f32>(uniforms.frame_number_0);
+    if((uniforms.bloom_intensity_0) > 0.0f)
+    {
+        color_1 = color_1 + bloom_at_0(pixel_index_1) * vec3<f32>(uniforms.bloom_intensity_0);
+    }
+    var _S227 : vec3<f32> = color_1 * uniforms.grading_white_balance_0 * vec3<f32>(uniforms.grading_exposure_scale_0) * vec3<f32>(uniforms.display_exposure_scale_0) * vec3<f32>(auto_exposure_scale_0());
+    if(u32(0) != (uniforms.display_extended_range_0))
+    {
+        return grade_extended_range_0(_S227, uniforms.grading_contrast_0, uniforms.grading_saturation_0);
+    }
+    return grade_contrast_and_saturation_0(pow(tone_map_0(_S227.xyz, uniforms.display_tone_mapping_0).xyz, vec3<f32>(uniforms.display_inverse_gamma_0)), uniforms.grading_contrast_0, uniforms.grading_saturation_0);
+}
+
+fn magnifier_inset_offset_0( pixel_position_1 : vec2<f32>) -> vec2<i32>
+{
+    return vec2<i32>(pixel_position_1) - vec2<i32>(i32(uniforms.frame_buffer_size_0.x) - i32(256), i32(0));
+}
+
+fn inside_magnifier_inset_0( inset_offset_0 : vec2<i32>) -> bool
+{
+    var _S222 : bool;
+    if(u32(0) != (uniforms.magnifier_zoom_0))
+    {
+        _S222 = (all((inset_offset_0 >= vec2<i32>(i32(0)))));
+    }
+    else
+    {
+        _S222 = false;
+    }
+    var _S223 : bool;
+    if(_S222)
+    {
+        _S223 = (all((inset_offset_0 < vec2<i32>(i32(256)))));
+    }
+    else
+    {
+        _S223 = false;
+    }
+    return _S223;
+}
+
+fn graded_pixel_0( pixel_4 : vec2<i32>) -> vec4<f32>
+{
+    var clamped_0 : vec2<u32> = vec2<u32>(clamp(pixel_4, vec2<i32>(i32(0)), vec2<i32>(uniforms.frame_buffer_size_0) - vec2<i32>(i32(1))));
+    var i_26 : u32 = clamped_0.y * uniforms.frame_buffer_size_0.x + clamped_0.x;
+    var _S905 : f32;
+    if(u32(0) == (object_id_buffer[i_26]))
+    {
+        _S905 = uniforms.clear_color_0.w;
+    }
+    else
+    {
+        _S905 = 1.0f;
+    }
+    return vec4<f32>(graded_pixel_color_0(i_26), _S905);
+}
+
+fn upscaled_0( footprint_0 : vec2<f32>) -> bool
+{
+    return (all((footprint_0 < vec2<f32>(0.99000000953674316f))));
+}
+
+fn upscaled_pixel_0( pixel_position_3 : vec2<f32>) -> vec4<f32>
+{
+    var position_4 : vec2<f32> = pixel_position_3 - vec2<f32>(0.5f);
+    var corner_2 : vec2<f32> = floor(position_4);
+    var weight_2 : vec2<f32> = position_4 - corner_2;
+    var texel_2 : vec2<i32> = vec2<i32>(corner_2);
+    var top_left_0 : vec4<f32> = graded_pixel_0(texel_2);
+    var top_right_0 : vec4<f32> = graded_pixel_0(texel_2 + vec2<i32>(i32(1), i32(0)));
+    var bottom_left_0 : vec4<f32> = graded_pixel_0(texel_2 + vec2<i32>(i32(0), i32(1)));
+    var bottom_right_0 : vec4<f32> = graded_pixel_0(texel_2 + vec2<i32>(i32(1), i32(1)));
+    var bilinear_0 : vec4<f32> = mix(mix(top_left_0, top_right_0, vec4<f32>(weight_2.x)), mix(bottom_left_0, bottom_right_0, vec4<f32>(weight_2.x)), vec4<f32>(weight_2.y));
+    var mean_0 : vec4<f32> = (top_left_0 + top_right_0 + bottom_left_0 + bottom_right_0) * vec4<f32>(0.25f);
+    return clamp(bilinear_0 + vec4<f32>(uniforms.upscale_sharpness_0) * (bilinear_0 - mean_0), min(min(top_left_0, top_right_0), min(bottom_left_0, bottom_right_0)), max(max(top_left_0, top_right_0), max(bottom_left_0, bottom_right_0)));
+}
+
+fn magnifier_inset_color_0( inset_offset_1 : vec2<i32>) -> vec3<f32>
+{
+    var _S224 : bool;
+    if((any((inset_offset_1 == vec2<i32>(i32(0))))))
+    {
+        _S224 = true;
+    }
+    else
+    {
+        _S224 = (any((inset_offset_1 == vec2<i32>(i32(255)))));
+    }
+    if(_S224)
+    {
+        return vec3<f32>(1.0f);
+    }
+    var zoom_0 : i32 = i32(uniforms.magnifier_zoom_0);
+    var source_1 : vec2<i32> = vec2<i32>(i32(uniforms.magnifier_center_x_0), i32(uniforms.magnifier_center_y_0)) - vec2<i32>(i32(256) / zoom_0 / i32(2)) + inset_offset_1 / vec2<i32>(zoom_0);
+    var _S225 : bool;
+    if((any((source_1 < vec2<i32>(i32(0))))))
+    {
+        _S225 = true;
+    }
+    else
+    {
+        _S225 = (any((source_1 >= vec2<i32>(uniforms.frame_buffer_size_0))));
+    }
+    if(_S225)
+    {
+        return vec3<f32>(0.0f);
+    }
+    return graded_pixel_color_0(u32(source_1.y) * uniforms.frame_buffer_size_0.x + u32(source_1.x));
+}
+
 fn gradient_noise_0( uv_0 : vec2<f32>) -> f32
 {
     return fract(52.98291778564453125f * fract(dot(uv_0, vec2<f32>(0.06711056083440781f, 0.00583714991807938f))));
@@ -157,17 +410,93 @@ fn pseudo_dither_0( color_0 : vec3<f32>,  pixel_coordinate_0 : vec2<f32>) -> vec
     return color_0 + vec3<f32>((0.00392156885936856f * gradient_noise_0(pixel_coordinate_0))) - vec3<f32>(0.00196078442968428f);
 }
 
+fn bayer_threshold_0( pixel_coordinate_1 : vec2<f32>) -> f32
+{
+    var cell_0 : vec2<u32> = vec2<u32>(pixel_coordinate_1) & vec2<u32>(u32(7));
+    var _S914 : u32 = cell_0.x;
+    var mixed_0 : u32 = _S914 ^ cell_0.y;
+    return (f32((((((((mixed_0 & u32(1)) << u32(5)) | ((_S914 & u32(1)) << u32(4))) | ((mixed_0 & u32(2)) << u32(2))) | ((_S914 & u32(2)) << u32(1))) | ((mixed_0 & u32(4)) >> u32(1))) | ((_S914 & u32(4)) >> u32(2)))) + 0.5f) / 64.0f;
+}
+
+fn dither_0( color_3 : vec3<f32>,  pixel_coordinate_2 : vec2<f32>,  dithering_0 : u32) -> vec3<f32>
+{
+    if(u32(1) == dithering_0)
+    {
+        return pseudo_dither_0(color_3, pixel_coordinate_2);
+    }
+    if(u32(2) == dithering_0)
+    {
+        return color_3 + vec3<f32>((0.00392156885936856f * bayer_threshold_0(pixel_coordinate_2))) - vec3<f32>(0.00196078442968428f);
+    }
+    return color_3;
+}
+
 struct pixelOutput_0
 {
     @location(0) output_1 : vec4<f32>,
 };
 
+struct pixelInput_0
+{
+    @location(0) frame_uv_1 : vec2<f32>,
+};
+
 @fragment
-fn fs(@builtin(position) position_1 : vec4<f32>) -> pixelOutput_0
+fn fs( _S206 : pixelInput_0, @builtin(position) position_1 : vec4<f32>) -> pixelOutput_0
 {
     randState = u32(0);
     var _S1 : vec2<f32> = position_1.xy;
-    var _S2 : pixelOutput_0 = pixelOutput_0( vec4<f32>(pseudo_dither_0(pow(aces_approx_0((pixel_color_buffer[pixel_global_index_0(_S1, uniforms.frame_buffer_size_0.x)].xyz / vec3<f32>(uniforms.frame_number_0)).xyz).xyz, vec3<f32>(0.45454543828964233f)), _S1), 1.0f) );
+    var pixel_position_2 : vec2<f32> = _S206.frame_uv_1 * vec2<f32>(uniforms.frame_buffer_size_0);
+    var footprint_1 : vec2<f32> = fwidth(pixel_position_2);
+    var side_0 : vec2<u32> = downsample_side_0(footprint_1);
+    var inset_offset_2 : vec2<i32> = magnifier_inset_offset_0(pixel_position_2);
+    if(inside_magnifier_inset_0(inset_offset_2))
+    {
+        var _S226 : pixelOutput_0 = pixelOutput_0( vec4<f32>(magnifier_inset_color_0(inset_offset_2), 1.0f) );
+        return _S226;
+    }
+    var pixel_0 : vec4<f32> = vec4<f32>(0.0f);
+    if(upscaled_0(footprint_1))
+    {
+        pixel_0 = upscaled_pixel_0(pixel_position_2);
+    }
+    else
+    {
+        var start_0 : vec2<i32> = vec2<i32>(floor(pixel_position_2 - vec2<f32>(0.5f) * vec2<f32>(side_0 - vec2<u32>(u32(1)))));
+        var y_0 : u32 = u32(0);
+        for(;;)
+        {
+            if(y_0 < (side_0.y))
+            {
+            }
+            else
+            {
+                break;
+            }
+            var x_0 : u32 = u32(0);
+            for(;;)
+            {
+                if(x_0 < (side_0.x))
+                {
+                }
+                else
+                {
+                    break;
+                }
+                pixel_0 = pixel_0 + graded_pixel_0(start_0 + vec2<i32>(i32(x_0), i32(y_0)));
+                x_0 = x_0 + u32(1);
+            }
+            y_0 = y_0 + u32(1);
+        }
+        pixel_0 = pixel_0 / vec4<f32>(f32(side_0.x * side_0.y));
+    }
+    var color_2 : vec3<f32> = pixel_0.xyz;
+    var alpha_2 : f32 = pixel_0.w;
+    if(u32(0) == (uniforms.display_extended_range_0))
+    {
+        color_2 = dither_0(color_2, _S1, uniforms.output_dithering_0);
+    }
+    var _S2 : pixelOutput_0 = pixelOutput_0( vec4<f32>(color_2, alpha_2) );
     return _S2;
 }
 
@@ -207,7 +536,7 @@ fn setup_camera_0() -> Camera_0
 {
     var origin_1 : vec3<f32> = uniforms.view_matrix_col_3_0.xyz;
     var result_1 : Camera_0;
-    result_1.fov_factor_0 = 1.0f / tan(0.52359879016876221f);
+    result_1.fov_factor_0 = uniforms.camera_fov_factor_0;
     result_1.origin_0 = origin_1;
     return result_1;
 }
@@ -224,7 +553,7 @@ fn get_camera_ray_0( camera_0 : Camera_0,  s_0 : f32,  t_0 : f32) -> Ray_0
     var ray_origin_world_space_0 : vec3<f32> = (((mat4x4<f32>(uniforms.view_ray_origin_matrix_col_0_0, uniforms.view_ray_origin_matrix_col_1_0, uniforms.view_ray_origin_matrix_col_2_0, uniforms.view_ray_origin_matrix_col_3_0)) * (pixel_world_space_0))).xyz;
     var direction_1 : vec3<f32> = normalize(pixel_world_space_0.xyz - ray_origin_world_space_0);
     var result_2 : Ray_0;
-    result_2.origin_2 = ray_origin_world_space_0;
+    result_2.origin_2 = ray_origin_world_space_0 + direction_1 * vec3<f32>(uniforms.camera_near_clip_0);
     result_2.direction_0 = direction_1;
     return result_2;
 }
@@ -286,6 +615,7 @@ struct HitRecord_0
      local_0 : HitPlace_0,
      t_2 : f32,
      material_id_3 : u32,
+     object_uid_6 : u32,
      front_face_0 : bool,
 };
 
@@ -303,6 +633,34 @@ struct Parallelogram_0
     material_id_0 : u32,
 };
 
+var<private> opacitySeed : f32;
+
+fn seed_object_opacity_0( pixel_index_18 : u32)
+{
+    var state_0 : u32 = (((pixel_index_18 + u32(uniforms.frame_number_0) * u32(719393))) ^ (u32(1757159915))) * u32(747796405) + u32(2891336453);
+    var word_1 : u32 = ((((state_0 >> ((((state_0 >> (u32(28)))) + u32(4))))) ^ (state_0))) * u32(277803737);
+    opacitySeed = (f32((((((word_1 >> (u32(22)))) ^ (word_1))) >> (u32(8)))) + 1.0f) / 16777216.0f;
+    return;
+}
+
+fn object_traced_0( object_uid_5 : u32) -> bool
+{
+    if(u32(0) == ((object_layers[object_uid_5] & (uniforms.camera_layer_mask_0))))
+    {
+        return false;
+    }
+    var _S918 : bool;
+    if((opacitySeed) <= 0.0f)
+    {
+        _S918 = true;
+    }
+    else
+    {
+        _S918 = (object_opacities[object_uid_5]) > (fract(opacitySeed + f32(object_uid_5) * 0.61803400516510010f));
+    }
+    return _S918;
+}
+
 fn hit_quad_0( quad_0 : Parallelogram_0,  tmin_0 : f32,  tmax_0 : f32,  ray_1 : Ray_0) -> bool
 {
     if((dot(ray_1.direction_0, quad_0.normal_0)) > 0.0f)
@@ -310,7 +668,7 @@ fn hit_quad_0( quad_0 : Parallelogram_0,  tmin_0 : f32,  tmax_0 : f32,  ray_1 :
         return false;
     }
     var denom_0 : f32 = dot(quad_0.normal_0, ray_1.direction_0);
-    if((abs(denom_0)) < 9.99999993922529029e-09f)
+    if(false == ((abs(denom_0)) >= 9.99999993922529029e-09f))
     {
         return false;
     }
@@ -372,6 +730,7 @@ fn hit_quad_0( quad_0 : Parallelogram_0,  tmin_0 : f32,  tmax_0 : f32,  ray_1 :
     }
     hitRec.local_0.normal_1 = hitRec.global_0.normal_1;
     hitRec.material_id_3 = quad_0.material_id_0;
+    hitRec.object_uid_6 = quad_0.object_uid_0;
     return true;
 }
 
@@ -404,15 +763,143 @@ struct Triangle_0
     object_uid_1 : u32,
     normalC_0 : vec3<f32>,
     material_id_1 : u32,
+    flat_shading_0 : bool,
+};
+
+fn dequantize_0( grid_0 : vec3<u32>,  origin_5 : vec3<f32>,  step_0 : vec3<f32>) -> vec3<f32>
+{
+    return origin_5 + vec3<f32>(grid_0) * step_0;
+}
+
+fn unpack_normal_0( packed_0 : u32) -> vec3<f32>
+{
+    return normalize(vec3<f32>(vec3<u32>((packed_0 & (u32(1023))), ((packed_0 >> (u32(10))) & (u32(1023))), ((packed_0 >> (u32(20))) & (u32(1023))))) / vec3<f32>(1023.0f) * vec3<f32>(2.0f) - vec3<f32>(1.0f));
+}
+
+fn load_quantized_triangle_0( record_2 : vec4<u32>) -> Triangle_0
+{
+    var _S210 : u32 = record_2.w & (u32(2147483647));
+    var origin_6 : vec3<f32> = triangles[_S210].xyz;
+    var step_1 : vec3<f32> = triangles[_S210 + u32(1)].xyz;
+    var a_2 : vec4<u32> = (bitcast<vec4<u32>>((triangles[record_2.x])));
+    var b_1 : vec4<u32> = (bitcast<vec4<u32>>((triangles[record_2.y])));
+    var c_1 : vec4<u32> = (bitcast<vec4<u32>>((triangles[record_2.z])));
+    var result_13 : Triangle_0;
+    result_13.A_0 = dequantize_0(vec3<u32>((a_2.x & (u32(65535))), (a_2.x >> (u32(16))), (a_2.y & (u32(65535)))), origin_6, step_1);
+    result_13.B_0 = dequantize_0(vec3<u32>((b_1.x & (u32(65535))), (b_1.x >> (u32(16))), (b_1.y & (u32(65535)))), origin_6, step_1);
+    result_13.C_0 = dequantize_0(vec3<u32>((c_1.x & (u32(65535))), (c_1.x >> (u32(16))), (c_1.y & (u32(65535)))), origin_6, step_1);
+    result_13.normalA_0 = unpack_normal_0(a_2.z);
+    result_13.normalB_0 = unpack_normal_0(b_1.z);
+    result_13.normalC_0 = unpack_normal_0(c_1.z);
+    result_13.object_uid_1 = a_2.w;
+    result_13.material_id_1 = a_2.y >> (u32(16));
+    result_13.flat_shading_0 = u32(0) != (record_2.w & (u32(2147483648)));
+    return result_13;
+}
+
+fn load_triangle_0( index_1 : u32) -> Triangle_0
+{
+    var record_1 : vec4<u32> = (bitcast<vec4<u32>>((triangles[index_1])));
+    if(u32(0) != uniforms.geometry_quantized_0)
+    {
+        return load_quantized_triangle_0(record_1);
+    }
+    var _S207 : u32 = record_1.x;
+    var a_position_0 : vec4<f32> = triangles[_S207];
+    var a_normal_0 : vec4<f32> = triangles[_S207 + u32(1)];
+    var result_12 : Triangle_0;
+    result_12.A_0 = a_position_0.xyz;
+    var _S208 : u32 = record_1.y;
+    result_12.B_0 = triangles[_S208].xyz;
+    var _S209 : u32 = record_1.z;
+    result_12.C_0 = triangles[_S209].xyz;
+    result_12.normalA_0 = a_normal_0.xyz;
+    result_12.normalB_0 = triangles[_S208 + u32(1)].xyz;
+    result_12.normalC_0 = triangles[_S209 + u32(1)].xyz;
+    result_12.object_uid_1 = (bitcast<u32>((a_position_0.w)));
+    result_12.material_id_1 = (bitcast<u32>((a_normal_0.w)));
+    result_12.flat_shading_0 = u32(0) != (record_1.w & (u32(2147483648)));
+    return result_12;
+}
+
+struct BvhNode_0
+{
+    aabb_min_0 : vec3<f32>,
+    primitive_index_0 : u32,
+    aabb_max_0 : vec3<f32>,
+    primitive_type_0 : u32,
+    hit_miss_skip_link_0 : i32,
 };
 
+fn load_bvh_node_0( index_4 : u32) -> BvhNode_0
+{
+    var result_14 : BvhNode_0;
+    if(u32(0) != uniforms.geometry_quantized_0)
+    {
+        var frame_0 : u32 = uniforms.bvh_length_0 * u32(2);
+        var origin_7 : vec3<f32> = bvh[frame_0].xyz;
+        var step_2 : vec3<f32> = bvh[frame_0 + u32(1)].xyz;
+        var _S211 : u32 = index_4 * u32(2);
+        var corners_0 : vec4<u32> = (bitcast<vec4<u32>>((bvh[_S211])));
+        var links_0 : vec4<u32> = (bitcast<vec4<u32>>((bvh[_S211 + u32(1)])));
+        result_14.aabb_min_0 = dequantize_0(vec3<u32>((corners_0.x & (u32(65535))), (corners_0.x >> (u32(16))), (corners_0.y & (u32(65535)))), origin_7, step_2);
+        result_14.aabb_max_0 = dequantize_0(vec3<u32>((corners_0.y >> (u32(16))), (corners_0.z & (u32(65535))), (corners_0.z >> (u32(16)))), origin_7, step_2);
+        result_14.primitive_index_0 = corners_0.w;
+        result_14.primitive_type_0 = links_0.x;
+        result_14.hit_miss_skip_link_0 = (bitcast<i32>((links_0.y)));
+    }
+    else
+    {
+        var _S212 : u32 = index_4 * u32(3);
+        var min_record_0 : vec4<f32> = bvh[_S212];
+        var max_record_0 : vec4<f32> = bvh[_S212 + u32(1)];
+        result_14.aabb_min_0 = min_record_0.xyz;
+        result_14.primitive_index_0 = (bitcast<u32>((min_record_0.w)));
+        result_14.aabb_max_0 = max_record_0.xyz;
+        result_14.primitive_type_0 = (bitcast<u32>((max_record_0.w)));
+        result_14.hit_miss_skip_link_0 = (bitcast<i32>((bvh[_S212 + u32(2)].x)));
+    }
+    return result_14;
+}
+
+fn load_bvh_node_1( index_5 : u32) -> BvhNode_0
+{
+    var result_15 : BvhNode_0;
+    if(u32(0) != uniforms.geometry_quantized_0)
+    {
+        var frame_1 : u32 = uniforms.bvh_length_0 * u32(2);
+        var origin_8 : vec3<f32> = bvh_inflated[frame_1].xyz;
+        var step_3 : vec3<f32> = bvh_inflated[frame_1 + u32(1)].xyz;
+        var _S213 : u32 = index_5 * u32(2);
+        var corners_1 : vec4<u32> = (bitcast<vec4<u32>>((bvh_inflated[_S213])));
+        var links_1 : vec4<u32> = (bitcast<vec4<u32>>((bvh_inflated[_S213 + u32(1)])));
+        result_15.aabb_min_0 = dequantize_0(vec3<u32>((corners_1.x & (u32(65535))), (corners_1.x >> (u32(16))), (corners_1.y & (u32(65535)))), origin_8, step_3);
+        result_15.aabb_max_0 = dequantize_0(vec3<u32>((corners_1.y >> (u32(16))), (corners_1.z & (u32(65535))), (corners_1.z >> (u32(16)))), origin_8, step_3);
+        result_15.primitive_index_0 = corners_1.w;
+        result_15.primitive_type_0 = links_1.x;
+        result_15.hit_miss_skip_link_0 = (bitcast<i32>((links_1.y)));
+    }
+    else
+    {
+        var _S214 : u32 = index_5 * u32(3);
+        var min_record_1 : vec4<f32> = bvh_inflated[_S214];
+        var max_record_1 : vec4<f32> = bvh_inflated[_S214 + u32(1)];
+        result_15.aabb_min_0 = min_record_1.xyz;
+        result_15.primitive_index_0 = (bitcast<u32>((min_record_1.w)));
+        result_15.aabb_max_0 = max_record_1.xyz;
+        result_15.primitive_type_0 = (bitcast<u32>((max_record_1.w)));
+        result_15.hit_miss_skip_link_0 = (bitcast<i32>((bvh_inflated[_S214 + u32(2)].x)));
+    }
+    return result_15;
+}
+
 fn hit_triangle_0( triangle_0 : Triangle_0,  tmin_2 : f32,  tmax_2 : f32,  ray_2 : Ray_0) -> bool
 {
     var AB_0 : vec3<f32> = triangle_0.B_0 - triangle_0.A_0;
     var AC_0 : vec3<f32> = triangle_0.C_0 - triangle_0.A_0;
     var normal_2 : vec3<f32> = cross(AB_0, AC_0);
     var determinant_0 : f32 = - dot(ray_2.direction_0, normal_2);
-    if((abs(determinant_0)) < tmin_2)
+    if(false == ((abs(determinant_0)) >= tmin_2))
     {
         return false;
     }
@@ -467,7 +954,12 @@ fn hit_triangle_0( triangle_0 : Triangle_0,  tmin_2 : f32,  tmax_2 : f32,  ray_2
     var _S11 : vec3<f32> = triangle_0.A_0 * _S8 + triangle_0.B_0 * _S9 + triangle_0.C_0 * _S10;
     hitRec.global_0.position_2 = _S11;
     hitRec.local_0.position_2 = _S11;
-    var _S12 : vec3<f32> = normalize(triangle_0.normalA_0 * _S8 + triangle_0.normalB_0 * _S9 + triangle_0.normalC_0 * _S10);
+    var shading_normal_0 : vec3<f32> = select(triangle_0.normalA_0 * _S8 + triangle_0.normalB_0 * _S9 + triangle_0.normalC_0 * _S10, normal_2, triangle_0.flat_shading_0);
+    if(false == ((dot(shading_normal_0, shading_normal_0)) > 0.0f))
+    {
+        shading_normal_0 = normal_2;
+    }
+    var _S12 : vec3<f32> = normalize(shading_normal_0);
     hitRec.global_0.normal_1 = _S12;
     var _S13 : bool = (dot(ray_2.direction_0, _S12)) < 0.0f;
     hitRec.front_face_0 = _S13;
@@ -477,6 +969,80 @@ fn hit_triangle_0( triangle_0 : Triangle_0,  tmin_2 : f32,  tmax_2 : f32,  ray_2
     }
     hitRec.local_0.normal_1 = hitRec.global_0.normal_1;
     hitRec.material_id_3 = triangle_0.material_id_1;
+    hitRec.object_uid_6 = triangle_0.object_uid_1;
+    return true;
+}
+
+struct Sphere_0
+{
+    center_4 : vec3<f32>,
+    radius_4 : f32,
+    object_uid_4 : u32,
+    material_id_4 : u32,
+};
+
+fn hit_sphere_0( sphere_0 : Sphere_0,  tmin_9 : f32,  tmax_9 : f32,  ray_14 : Ray_0) -> bool
+{
+    var oc_0 : vec3<f32> = sphere_0.center_4 - ray_14.origin_2;
+    var a_6 : f32 = dot(ray_14.direction_0, ray_14.direction_0);
+    var h_0 : f32 = dot(ray_14.direction_0, oc_0);
+    var discriminant_1 : f32 = h_0 * h_0 - a_6 * (dot(oc_0, oc_0) - sphere_0.radius_4 * sphere_0.radius_4);
+    if(discriminant_1 < 0.0f)
+    {
+        return false;
+    }
+    var sqrtd_0 : f32 = sqrt(discriminant_1);
+    var root_0 : f32 = (h_0 - sqrtd_0) / a_6;
+    var _S261 : bool;
+    if(root_0 <= tmin_9)
+    {
+        _S261 = true;
+    }
+    else
+    {
+        _S261 = root_0 >= tmax_9;
+    }
+    var root_1 : f32;
+    if(_S261)
+    {
+        var _S262 : f32 = (h_0 + sqrtd_0) / a_6;
+        if(_S262 <= tmin_9)
+        {
+            _S261 = true;
+        }
+        else
+        {
+            _S261 = _S262 >= tmax_9;
+        }
+        if(_S261)
+        {
+            return false;
+        }
+        root_1 = _S262;
+    }
+    else
+    {
+        root_1 = root_0;
+    }
+    hitRec.t_2 = root_1;
+    hitRec.global_0.position_2 = Ray_at_0(ray_14, root_1);
+    hitRec.local_0.position_2 = hitRec.global_0.position_2 - sphere_0.center_4;
+    var outward_normal_0 : vec3<f32> = hitRec.local_0.position_2 / vec3<f32>(sphere_0.radius_4);
+    var _S263 : bool = (dot(ray_14.direction_0, outward_normal_0)) < 0.0f;
+    hitRec.front_face_0 = _S263;
+    var _S264 : vec3<f32>;
+    if(_S263)
+    {
+        _S264 = outward_normal_0;
+    }
+    else
+    {
+        _S264 = (vec3<f32>(0) - outward_normal_0);
+    }
+    hitRec.global_0.normal_1 = _S264;
+    hitRec.local_0.normal_1 = hitRec.global_0.normal_1;
+    hitRec.material_id_3 = sphere_0.material_id_4;
+    hitRec.object_uid_6 = sphere_0.object_uid_4;
     return true;
 }
 
@@ -520,16 +1086,27 @@ struct Sdf_0
     class_index_0 : i32,
     material_id_2 : u32,
     object_uid_2 : u32,
+    parameters_0 : vec4<f32>,
 };
 
+var<private> sdf_parameters : vec4<f32>;
+
 fn sample_sdf_0( sdf_0 : Sdf_0,  point_1 : vec3<f32>,  time_0 : f32) -> f32
 {
+    sdf_parameters = sdf_0.parameters_0;
     var _S15 : f32 = sdf_select(sdf_0.class_index_0, point_1, time_0);
     return _S15;
 }
 
 fn signed_distance_normal_0( sdf_1 : Sdf_0,  point_2 : vec3<f32>,  time_1 : f32) -> vec3<f32>
 {
+    sdf_parameters = sdf_1.parameters_0;
+    var _S293 : vec4<f32> = sdf_select_gradient(sdf_1.class_index_0, point_2, time_1);
+    var gradient_0 : vec3<f32> = _S293.xyz;
+    if((dot(gradient_0, gradient_0)) > 0.0f)
+    {
+        return normalize(gradient_0);
+    }
     var e_0 : vec2<f32> = vec2<f32>(1.0f, -1.0f) * vec2<f32>(0.57730001211166382f) * vec2<f32>(0.00050000002374873f);
     var _S16 : vec3<f32> = e_0.xyy;
     var _S17 : f32 = sample_sdf_0(sdf_1, point_2 + _S16, time_1);
@@ -552,18 +1129,72 @@ fn transform_transposed_vector_0( transformation_3 : mat3x3<f32>,  vector_1 : ve
 
 fn apply_animation_0( sdf_2 : Sdf_0,  point_3 : vec3<f32>,  time_2 : f32) -> vec3<f32>
 {
+    sdf_parameters = sdf_2.parameters_0;
     var _S27 : vec3<f32> = sdf_apply_animation(sdf_2.class_index_0, point_3, time_2);
     return _S27;
 }
 
-fn hit_sdf_0( sdf_3 : Sdf_0,  time_3 : f32,  ray_4 : Ray_0,  tmin_3 : f32,  tmax_3 : f32) -> bool
+fn sdf_distance_grid_step_0( class_index_1 : i32,  point_4 : vec3<f32>,  step_scale_0 : f32) -> f32
 {
-    var sdf_inverse_location_0 : mat3x4<f32> = mat3x4<f32>(sdf_3.inverse_location_col_0_0, sdf_3.inverse_location_col_1_0, sdf_3.inverse_location_col_2_0);
-    var sdf_location_inverse_0 : mat3x3<f32> = to_mat3x3_0(sdf_inverse_location_0);
-    var local_ray_origin_0 : vec3<f32> = transform_point_0(sdf_inverse_location_0, ray_4.origin_2);
-    var _S28 : Ray_0 = Ray_x24init_0(local_ray_origin_0, normalize(transform_vector_0(sdf_location_inverse_0, ray_4.direction_0)));
-    var _S29 : f32 = transform_ray_parameter_0(sdf_inverse_location_0, ray_4, tmin_3, local_ray_origin_0);
-    var _S30 : f32 = transform_ray_parameter_0(sdf_inverse_location_0, ray_4, tmax_3, local_ray_origin_0);
+    var _S294 : bool;
+    if(class_index_1 < i32(0))
+    {
+        _S294 = true;
+    }
+    else
+    {
+        _S294 = (u32(class_index_1)) >= (bitcast<u32>((sdf_distance_grids[i32(0)])));
+    }
+    if(_S294)
+    {
+        return 0.0f;
+    }
+    var slot_0 : u32 = u32(4) + u32(class_index_1) * u32(8);
+    var resolution_2 : u32 = (bitcast<u32>((sdf_distance_grids[slot_0 + u32(7)])));
+    if(resolution_2 == u32(0))
+    {
+        return 0.0f;
+    }
+    var grid_min_0 : vec3<f32> = vec3<f32>(sdf_distance_grids[slot_0], sdf_distance_grids[slot_0 + u32(1)], sdf_distance_grids[slot_0 + u32(2)]);
+    var cell_size_0 : vec3<f32> = vec3<f32>(sdf_distance_grids[slot_0 + u32(4)], sdf_distance_grids[slot_0 + u32(5)], sdf_distance_grids[slot_0 + u32(6)]);
+    var cell_position_0 : vec3<f32> = (point_4 - grid_min_0) / cell_size_0;
+    var _S295 : bool;
+    if((any((cell_position_0 < vec3<f32>(0.0f)))))
+    {
+        _S295 = true;
+    }
+    else
+    {
+        _S295 = (any((cell_position_0 >= vec3<f32>(f32(resolution_2)))));
+    }
+    if(_S295)
+    {
+        return 0.0f;
+    }
+    var cell_0 : vec3<u32> = vec3<u32>(cell_position_0);
+    var step_0 : f32 = sdf_distance_grids[(bitcast<u32>((sdf_distance_grids[slot_0 + u32(3)]))) + (cell_0.z * resolution_2 + cell_0.y) * resolution_2 + cell_0.x] * step_scale_0 - length(point_4 - (grid_min_0 + (vec3<f32>(cell_0) + vec3<f32>(0.5f)) * cell_size_0));
+    var _S296 : f32;
+    if(step_0 >= 0.5f * length(cell_size_0))
+    {
+        _S296 = step_0;
+    }
+    else
+    {
+        _S296 = 0.0f;
+    }
+    return _S296;
+}
+
+var<private> sdf_march_steps : u32;
+
+fn hit_sdf_0( sdf_3 : Sdf_0,  time_3 : f32,  ray_4 : Ray_0,  tmin_3 : f32,  tmax_3 : f32) -> bool
+{
+    var sdf_inverse_location_0 : mat3x4<f32> = mat3x4<f32>(sdf_3.inverse_location_col_0_0, sdf_3.inverse_location_col_1_0, sdf_3.inverse_location_col_2_0);
+    var sdf_location_inverse_0 : mat3x3<f32> = to_mat3x3_0(sdf_inverse_location_0);
+    var local_ray_origin_0 : vec3<f32> = transform_point_0(sdf_inverse_location_0, ray_4.origin_2);
+    var _S28 : Ray_0 = Ray_x24init_0(local_ray_origin_0, normalize(transform_vector_0(sdf_location_inverse_0, ray_4.direction_0)));
+    var _S29 : f32 = transform_ray_parameter_0(sdf_inverse_location_0, ray_4, tmin_3, local_ray_origin_0);
+    var _S30 : f32 = transform_ray_parameter_0(sdf_inverse_location_0, ray_4, tmax_3, local_ray_origin_0);
     var i_0 : i32 = i32(0);
     var local_t_0 : f32 = _S29;
     for(;;)
@@ -577,6 +1208,12 @@ fn hit_sdf_0( sdf_3 : Sdf_0,  time_3 : f32,  ray_4 : Ray_0,  tmin_3 : f32,  tmax
             break;
         }
         var candidate_0 : vec3<f32> = Ray_at_0(_S28, local_t_0);
+        var grid_step_0 : f32 = sdf_distance_grid_step_0(sdf_3.class_index_0, candidate_0, sdf_3.ray_marching_step_scale_0);
+        if(grid_step_0 > 0.0f)
+        {
+            local_t_0 = local_t_0 + grid_step_0;
+            continue;
+        }
         var signed_distance_0 : f32 = sample_sdf_0(sdf_3, candidate_0, time_3);
         var t_scaled_0 : f32 = 0.00009999999747379f * local_t_0;
         var _S31 : f32 = abs(signed_distance_0);
@@ -598,15 +1235,86 @@ fn hit_sdf_0( sdf_3 : Sdf_0,  time_3 : f32,  ray_4 : Ray_0,  tmin_3 : f32,  tmax
                 hitRec.local_0.normal_1 = (vec3<f32>(0) - hitRec.local_0.normal_1);
             }
             hitRec.material_id_3 = sdf_3.material_id_2;
+            hitRec.object_uid_6 = sdf_3.object_uid_2;
+            sdf_march_steps = sdf_march_steps + u32(i_0 + i32(1));
             return true;
         }
         var local_t_1 : f32 = local_t_0 + max(_S31 * sdf_3.ray_marching_step_scale_0, t_scaled_0);
         i_0 = i_0 + i32(1);
         local_t_0 = local_t_1;
     }
+    sdf_march_steps = sdf_march_steps + u32(i_0);
     return false;
 }
 
+fn sdf_replaced_by_proxy_0( aabb_min_1 : vec3<f32>,  aabb_max_1 : vec3<f32>,  ray_10 : Ray_0) -> bool
+{
+    if((uniforms.sdf_lod_threshold_pixels_0) <= 0.0f)
+    {
+        return false;
+    }
+    var _S227 : f32 = length(aabb_max_1 - aabb_min_1) * uniforms.camera_fov_factor_0 * 0.5f * f32(uniforms.frame_buffer_size_0.y) / length((aabb_min_1 + aabb_max_1) * vec3<f32>(0.5f) - ray_10.origin_2);
+    return _S227 < (uniforms.sdf_lod_threshold_pixels_0);
+}
+
+fn hit_sdf_proxy_0( sdf_9 : Sdf_0,  aabb_min_2 : vec3<f32>,  aabb_max_2 : vec3<f32>,  tmin_7 : f32,  tmax_7 : f32,  ray_11 : Ray_0) -> bool
+{
+    var center_3 : vec3<f32> = (aabb_min_2 + aabb_max_2) * vec3<f32>(0.5f);
+    var radius_3 : f32 = length(aabb_max_2 - aabb_min_2) * 0.5f;
+    var to_center_0 : vec3<f32> = center_3 - ray_11.origin_2;
+    var a_5 : f32 = dot(ray_11.direction_0, ray_11.direction_0);
+    var half_b_0 : f32 = dot(ray_11.direction_0, to_center_0);
+    var discriminant_0 : f32 = half_b_0 * half_b_0 - a_5 * (dot(to_center_0, to_center_0) - radius_3 * radius_3);
+    if(discriminant_0 < 0.0f)
+    {
+        return false;
+    }
+    var root_offset_0 : f32 = sqrt(discriminant_0);
+    var t_7 : f32 = (half_b_0 - root_offset_0) / a_5;
+    if(t_7 <= tmin_7)
+    {
+        t_7 = (half_b_0 + root_offset_0) / a_5;
+    }
+    var _S228 : bool;
+    if(t_7 <= tmin_7)
+    {
+        _S228 = true;
+    }
+    else
+    {
+        _S228 = t_7 >= tmax_7;
+    }
+    if(_S228)
+    {
+        return false;
+    }
+    hitRec.t_2 = t_7;
+    var _S229 : vec3<f32> = Ray_at_0(ray_11, t_7);
+    hitRec.global_0.position_2 = _S229;
+    var _S230 : vec3<f32> = (_S229 - center_3) / vec3<f32>(radius_3);
+    hitRec.global_0.normal_1 = _S230;
+    var _S231 : bool = (dot(ray_11.direction_0, _S230)) < 0.0f;
+    hitRec.front_face_0 = _S231;
+    if(_S231 == false)
+    {
+        hitRec.global_0.normal_1 = (vec3<f32>(0) - hitRec.global_0.normal_1);
+    }
+    hitRec.local_0.position_2 = transform_point_0(mat3x4<f32>(sdf_9.inverse_location_col_0_0, sdf_9.inverse_location_col_1_0, sdf_9.inverse_location_col_2_0), hitRec.global_0.position_2);
+    hitRec.local_0.normal_1 = hitRec.global_0.normal_1;
+    hitRec.material_id_3 = sdf_9.material_id_2;
+    hitRec.object_uid_6 = sdf_9.object_uid_2;
+    return true;
+}
+
+fn hit_sdf_within_aabb_0( sdf_10 : Sdf_0,  time_9 : f32,  aabb_min_3 : vec3<f32>,  aabb_max_3 : vec3<f32>,  ray_12 : Ray_0,  tmin_8 : f32,  tmax_8 : f32) -> bool
+{
+    if(sdf_replaced_by_proxy_0(aabb_min_3, aabb_max_3, ray_12))
+    {
+        return hit_sdf_proxy_0(sdf_10, aabb_min_3, aabb_max_3, 9.99999997475242708e-07f, tmax_8, ray_12);
+    }
+    return hit_sdf_0(sdf_10, time_9, ray_12, tmin_8, tmax_8);
+}
+
 fn snap_to_grid_0( victim_0 : vec3<f32>,  grid_step_0 : f32) -> vec3<f32>
 {
     var _S36 : vec3<f32> = vec3<f32>(grid_step_0);
@@ -637,12 +1345,12 @@ fn calculate_mip_level_0( target_texture_0 : texture_2d<f32>,  ddx_0 : vec2<f32>
     var mip_levels_0 : u32;
     {var dim = textureDimensions((target_texture_0), (u32(0)));((width_0)) = dim.x;((height_0)) = dim.y;((mip_levels_0)) = textureNumLevels((target_texture_0));};
     var texture_size_0 : vec2<f32> = vec2<f32>(f32(width_0), f32(height_0));
-    var delta_max_sqr_0 : f32 = max(length(ddx_0 * texture_size_0), length(ddy_0 * texture_size_0));
-    if(delta_max_sqr_0 <= 0.0f)
+    var delta_max_0 : f32 = max(length(ddx_0 * texture_size_0), length(ddy_0 * texture_size_0));
+    if(delta_max_0 <= 1.0f)
     {
         return u32(0);
     }
-    return clamp(u32(0.5f * log2(delta_max_sqr_0)), u32(0), mip_levels_0 - u32(1));
+    return clamp(u32(log2(delta_max_0)), u32(0), mip_levels_0 - u32(1));
 }
 
 fn pixel_half_size_0( target_texture_1 : texture_2d<f32>,  ddx_1 : vec2<f32>,  ddy_1 : vec2<f32>) -> vec2<f32>
@@ -667,8 +1375,8 @@ fn read_atlas_0( local_space_position_0 : vec3<f32>,  atlas_region_mapping_0 : A
 {
     var local_position_to_texture_0 : mat2x4<f32> = mat2x4<f32>(atlas_region_mapping_0.local_position_to_texture_x_0, atlas_region_mapping_0.local_position_to_texture_y_0);
     var texture_coordinate_0 : vec2<f32> = (((vec4<f32>(local_space_position_0, 1.0f)) * (local_position_to_texture_0)));
-    var ddx_2 : vec2<f32> = (((vec4<f32>(differentials_2.dp_dx_0, 0.0f)) * (local_position_to_texture_0)));
-    var ddy_2 : vec2<f32> = (((vec4<f32>(differentials_2.dp_dy_0, 0.0f)) * (local_position_to_texture_0)));
+    var ddx_2 : vec2<f32> = (((vec4<f32>(differentials_2.dp_dx_0, 0.0f)) * (local_position_to_texture_0))) * atlas_region_mapping_0.size_0;
+    var ddy_2 : vec2<f32> = (((vec4<f32>(differentials_2.dp_dy_0, 0.0f)) * (local_position_to_texture_0))) * atlas_region_mapping_0.size_0;
     var i_1 : i32 = i32(0);
     for(;;)
     {
@@ -719,16 +1427,32 @@ fn read_atlas_0( local_space_position_0 : vec3<f32>,  atlas_region_mapping_0 : A
 struct Material_0
 {
     albedo_0 : vec3<f32>,
+    surface_shader_uid_0 : i32,
     emission_0 : vec3<f32>,
+    normal_texture_uid_0 : i32,
     specular_0 : vec3<f32>,
     specular_strength_0 : f32,
     roughness_0 : f32,
     refractive_index_eta_0 : f32,
     albedo_texture_uid_0 : i32,
     material_class_0 : i32,
+    absorption_0 : vec3<f32>,
 };
 
-fn fetch_albedo_0( hit_1 : HitPlace_0,  ray_direction_1 : vec3<f32>,  ray_parameter_1 : f32,  material_0 : Material_0,  differentials_3 : RayDifferentials_0) -> vec3<f32>
+fn uv_transformed_0( source_mapping_0 : AtlasMapping_0,  object_uid_9 : u32) -> AtlasMapping_0
+{
+    var uv_transform_0 : vec4<f32> = object_uv_transforms[object_uid_9];
+    var uv_row_0_0 : vec2<f32> = unpack2x16float((bitcast<u32>((uv_transform_0.z))));
+    var uv_row_1_0 : vec2<f32> = unpack2x16float((bitcast<u32>((uv_transform_0.w))));
+    var mapping_x_0 : vec4<f32> = source_mapping_0.local_position_to_texture_x_0;
+    var mapping_y_0 : vec4<f32> = source_mapping_0.local_position_to_texture_y_0;
+    var transformed_0 : AtlasMapping_0 = source_mapping_0;
+    transformed_0.local_position_to_texture_x_0 = vec4<f32>(uv_row_0_0.x) * mapping_x_0 + vec4<f32>(uv_row_0_0.y) * mapping_y_0 + vec4<f32>(0.0f, 0.0f, 0.0f, uv_transform_0.x);
+    transformed_0.local_position_to_texture_y_0 = vec4<f32>(uv_row_1_0.x) * mapping_x_0 + vec4<f32>(uv_row_1_0.y) * mapping_y_0 + vec4<f32>(0.0f, 0.0f, 0.0f, uv_transform_0.y);
+    return transformed_0;
+}
+
+fn fetch_albedo_0( hit_1 : HitPlace_0,  object_uid_7 : u32,  ray_direction_1 : vec3<f32>,  ray_parameter_1 : f32,  material_0 : Material_0,  differentials_3 : RayDifferentials_0) -> vec3<f32>
 {
     var result_7 : vec3<f32> = material_0.albedo_0.xyz;
     var result_8 : vec3<f32>;
@@ -742,7 +1466,7 @@ fn fetch_albedo_0( hit_1 : HitPlace_0,  ray_direction_1 : vec3<f32>,  ray_parame
     {
         if((material_0.albedo_texture_uid_0) > i32(0))
         {
-            var _S43 : AtlasMapping_0 = AtlasMapping_0( texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].top_left_corner_uv_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].size_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].local_position_to_texture_x_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].local_position_to_texture_y_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].wrap_mode_0 );
+            var _S43 : AtlasMapping_0 = uv_transformed_0(AtlasMapping_0( texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].top_left_corner_uv_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].size_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].local_position_to_texture_x_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].local_position_to_texture_y_0, texture_atlases_mapping[material_0.albedo_texture_uid_0 - i32(1)].wrap_mode_0 ), object_uid_7);
             var texture_sample_0 : vec4<f32> = read_atlas_0(hit_1.position_2, _S43, ray_hit_position_derivatives_0(ray_direction_1, ray_parameter_1, hit_1.normal_1, differentials_3));
             var _S44 : f32 = texture_sample_0.w;
             result_8 = vec3<f32>((1.0f - _S44)) * result_7 + vec3<f32>(_S44) * texture_sample_0.xyz;
@@ -755,11 +1479,65 @@ fn fetch_albedo_0( hit_1 : HitPlace_0,  ray_direction_1 : vec3<f32>,  ray_parame
     return result_8;
 }
 
+fn linear_to_srgb_0( linear_0 : vec3<f32>) -> vec3<f32>
+{
+    return select(vec3<f32>(1.05499994754791260f) * pow(linear_0, vec3<f32>(0.41666665673255920f)) - vec3<f32>(0.05499999970197678f), linear_0 * vec3<f32>(12.92000007629394531f), (linear_0 <= vec3<f32>(0.00313080009073019f)));
+}
+
+fn rotate_between_0( from_0 : vec3<f32>,  to_0 : vec3<f32>,  victim_1 : vec3<f32>) -> vec3<f32>
+{
+    var cosine_0 : f32 = dot(from_0, to_0);
+    if(cosine_0 < -0.99989998340606689f)
+    {
+        return - victim_1;
+    }
+    var axis_0 : vec3<f32> = cross(from_0, to_0);
+    return victim_1 * vec3<f32>(cosine_0) + cross(axis_0, victim_1) + axis_0 * vec3<f32>((dot(axis_0, victim_1) / (1.0f + cosine_0)));
+}
+
+fn perturbed_normal_0( hit_2 : HitPlace_0,  object_uid_8 : u32,  global_normal_0 : vec3<f32>,  material_1 : Material_0,  ray_direction_2 : vec3<f32>,  ray_parameter_2 : f32,  differentials_4 : RayDifferentials_0) -> vec3<f32>
+{
+    if((material_1.normal_texture_uid_0) <= i32(0))
+    {
+        return global_normal_0;
+    }
+    var _S219 : i32 = material_1.normal_texture_uid_0 - i32(1);
+    var _S220 : AtlasMapping_0 = uv_transformed_0(AtlasMapping_0( texture_atlases_mapping[_S219].top_left_corner_uv_0, texture_atlases_mapping[_S219].size_0, texture_atlases_mapping[_S219].local_position_to_texture_x_0, texture_atlases_mapping[_S219].local_position_to_texture_y_0, texture_atlases_mapping[_S219].wrap_mode_0 ), object_uid_8);
+    var local_normal_0 : vec3<f32> = normalize(hit_2.normal_1);
+    var tangent_0 : vec3<f32> = _S220.local_position_to_texture_x_0.xyz;
+    var bitangent_0 : vec3<f32> = - _S220.local_position_to_texture_y_0.xyz;
+    tangent_0 = tangent_0 - local_normal_0 * vec3<f32>(dot(local_normal_0, tangent_0));
+    bitangent_0 = bitangent_0 - local_normal_0 * vec3<f32>(dot(local_normal_0, bitangent_0));
+    var _S221 : bool;
+    if((dot(tangent_0, tangent_0)) < 9.99999996004197e-13f)
+    {
+        _S221 = true;
+    }
+    else
+    {
+        _S221 = (dot(bitangent_0, bitangent_0)) < 9.99999996004197e-13f;
+    }
+    if(_S221)
+    {
+        return global_normal_0;
+    }
+    var texture_sample_1 : vec4<f32> = read_atlas_0(hit_2.position_2, _S220, ray_hit_position_derivatives_0(ray_direction_2, ray_parameter_2, hit_2.normal_1, differentials_4));
+    if((texture_sample_1.w) <= 0.0f)
+    {
+        return global_normal_0;
+    }
+    var texel_0 : vec3<f32> = linear_to_srgb_0(texture_sample_1.xyz) * vec3<f32>(2.0f) - vec3<f32>(1.0f);
+    var local_perturbed_0 : vec3<f32> = normalize(normalize(tangent_0) * vec3<f32>(texel_0.x) + normalize(bitangent_0) * vec3<f32>(texel_0.y) + local_normal_0 * vec3<f32>(texel_0.z));
+    return normalize(rotate_between_0(local_normal_0, global_normal_0, local_perturbed_0));
+}
+
 struct FirstHitSurface_0
 {
      object_uid_3 : u32,
      albedo_1 : vec3<f32>,
      normal_3 : vec3<f32>,
+     emission_1 : vec3<f32>,
+     depth_0 : f32,
 };
 
 fn trace_first_intersection_0( incident_0 : RayAndDifferentials_0) -> FirstHitSurface_0
@@ -772,7 +1550,7 @@ fn trace_first_intersection_0( incident_0 : RayAndDifferentials_0) -> FirstHitSu
     var hit_local_0 : HitPlace_0;
     hit_local_0.position_2 = _S45;
     hit_local_0.normal_1 = _S45;
-    var closest_so_far_1 : f32 = 1.0e+09f;
+    var closest_so_far_1 : f32 = uniforms.max_ray_distance_0;
     var hit_uid_1 : u32 = u32(0);
     var hit_material_id_1 : u32 = u32(0);
     var hit_global_normal_1 : vec3<f32> = _S45;
@@ -789,7 +1567,15 @@ fn trace_first_intersection_0( incident_0 : RayAndDifferentials_0) -> FirstHitSu
         var _S46 : u32 = parallelograms[i_2].object_uid_0;
         var _S47 : u32 = parallelograms[i_2].material_id_0;
         var _S48 : Parallelogram_0 = Parallelogram_0( parallelograms[i_2].Q_0, parallelograms[i_2].u_0, parallelograms[i_2].object_uid_0, parallelograms[i_2].v_0, parallelograms[i_2].D_0, parallelograms[i_2].normal_0, parallelograms[i_2].w_0, parallelograms[i_2].material_id_0 );
-        var _S49 : bool = hit_quad_0(_S48, 9.99999997475242708e-07f, closest_so_far_1, incident_0.ray_0);
+        var _S49 : bool;
+        if(object_traced_0(_S46))
+        {
+            _S49 = hit_quad_0(_S48, 9.99999997475242708e-07f, closest_so_far_1, incident_0.ray_0);
+        }
+        else
+        {
+            _S49 = false;
+        }
         if(_S49)
         {
             var _S50 : vec3<f32> = hitRec.global_0.normal_1;
@@ -834,16 +1620,25 @@ fn trace_first_intersection_0( incident_0 : RayAndDifferentials_0) -> FirstHitSu
         {
             break;
         }
-        var aabb_hit_0 : AabbHit_0 = hit_aabb_0(bvh[node_index_0].aabb_min_0, bvh[node_index_0].aabb_max_0, 9.99999997475242708e-07f, closest_so_far_1, incident_0.ray_0.origin_2, _S52);
+        var node_1 : BvhNode_0 = load_bvh_node_0(u32(node_index_0));
+        var aabb_hit_0 : AabbHit_0 = hit_aabb_0(node_1.aabb_min_0, node_1.aabb_max_0, 9.99999997475242708e-07f, closest_so_far_1, incident_0.ray_0.origin_2, _S52);
         if(aabb_hit_0.hit_0)
         {
-            var _S55 : u32 = bvh[node_index_0].primitive_type_0;
-            if(u32(2) == (bvh[node_index_0].primitive_type_0))
+            var _S55 : u32 = node_1.primitive_type_0;
+            if(u32(2) == (node_1.primitive_type_0))
             {
-                var _S56 : u32 = triangles[bvh[node_index_0].primitive_index_0].object_uid_1;
-                var _S57 : u32 = triangles[bvh[node_index_0].primitive_index_0].material_id_1;
-                var _S58 : Triangle_0 = Triangle_0( triangles[bvh[node_index_0].primitive_index_0].A_0, triangles[bvh[node_index_0].primitive_index_0].B_0, triangles[bvh[node_index_0].primitive_index_0].C_0, triangles[bvh[node_index_0].primitive_index_0].normalA_0, triangles[bvh[node_index_0].primitive_index_0].normalB_0, triangles[bvh[node_index_0].primitive_index_0].object_uid_1, triangles[bvh[node_index_0].primitive_index_0].normalC_0, triangles[bvh[node_index_0].primitive_index_0].material_id_1 );
-                var _S59 : bool = hit_triangle_0(_S58, 9.99999997475242708e-07f, closest_so_far_1, incident_0.ray_0);
+                var _S58 : Triangle_0 = load_triangle_0(node_1.primitive_index_0);
+                var _S56 : u32 = _S58.object_uid_1;
+                var _S57 : u32 = _S58.material_id_1;
+                var _S59 : bool;
+                if(object_traced_0(_S56))
+                {
+                    _S59 = hit_triangle_0(_S58, 9.99999997475242708e-07f, closest_so_far_1, incident_0.ray_0);
+                }
+                else
+                {
+                    _S59 = false;
+                }
                 if(_S59)
                 {
                     var _S60 : vec3<f32> = hitRec.global_0.normal_1;
@@ -865,10 +1660,18 @@ fn trace_first_intersection_0( incident_0 : RayAndDifferentials_0) -> FirstHitSu
             {
                 if(u32(1) == _S55)
                 {
-                    var _S61 : u32 = sdf[bvh[node_index_0].primitive_index_0].material_id_2;
-                    var _S62 : u32 = sdf[bvh[node_index_0].primitive_index_0].object_uid_2;
-                    var _S63 : Sdf_0 = Sdf_0( sdf[bvh[node_index_0].primitive_index_0].location_col_0_0, sdf[bvh[node_index_0].primitive_index_0].location_col_1_0, sdf[bvh[node_index_0].primitive_index_0].location_col_2_0, sdf[bvh[node_index_0].primitive_index_0].inverse_location_col_0_0, sdf[bvh[node_index_0].primitive_index_0].inverse_location_col_1_0, sdf[bvh[node_index_0].primitive_index_0].inverse_location_col_2_0, sdf[bvh[node_index_0].primitive_index_0].ray_marching_step_scale_0, sdf[bvh[node_index_0].primitive_index_0].class_index_0, sdf[bvh[node_index_0].primitive_index_0].material_id_2, sdf[bvh[node_index_0].primitive_index_0].object_uid_2 );
-                    var _S64 : bool = hit_sdf_0(_S63, sdf_time[bvh[node_index_0].primitive_index_0], incident_0.ray_0, aabb_hit_0.ray_parameter_0, closest_so_far_1);
+                    var _S61 : u32 = sdf[node_1.primitive_index_0].material_id_2;
+                    var _S62 : u32 = sdf[node_1.primitive_index_0].object_uid_2;
+                    var _S63 : Sdf_0 = Sdf_0( sdf[node_1.primitive_index_0].location_col_0_0, sdf[node_1.primitive_index_0].location_col_1_0, sdf[node_1.primitive_index_0].location_col_2_0, sdf[node_1.primitive_index_0].inverse_location_col_0_0, sdf[node_1.primitive_index_0].inverse_location_col_1_0, sdf[node_1.primitive_index_0].inverse_location_col_2_0, sdf[node_1.primitive_index_0].ray_marching_step_scale_0, sdf[node_1.primitive_index_0].class_index_0, sdf[node_1.primitive_index_0].material_id_2, sdf[node_1.primitive_index_0].object_uid_2, sdf[node_1.primitive_index_0].parameters_0 );
+                    var _S64 : bool;
+                    if(object_traced_0(_S62))
+                    {
+                        _S64 = hit_sdf_within_aabb_0(_S63, sdf_time[node_1.primitive_index_0], node_1.aabb_min_0, node_1.aabb_max_0, incident_0.ray_0, aabb_hit_0.ray_parameter_0, closest_so_far_1);
+                    }
+                    else
+                    {
+                        _S64 = false;
+                    }
                     if(_S64)
                     {
                         var _S65 : vec3<f32> = hitRec.global_0.normal_1;
@@ -888,17 +1691,49 @@ fn trace_first_intersection_0( incident_0 : RayAndDifferentials_0) -> FirstHitSu
                 }
                 else
                 {
-                    closest_so_far_0 = closest_so_far_1;
-                    hit_uid_0 = hit_uid_1;
-                    hit_material_id_0 = hit_material_id_1;
-                    hit_global_normal_0 = hit_global_normal_1;
+                    if(u32(3) == _S55)
+                    {
+                        var _S265 : Sphere_0 = Sphere_0( spheres[node_1.primitive_index_0].center_4, spheres[node_1.primitive_index_0].radius_4, spheres[node_1.primitive_index_0].object_uid_4, spheres[node_1.primitive_index_0].material_id_4 );
+                        var _S266 : bool;
+                        if(object_traced_0(_S265.object_uid_4))
+                        {
+                            _S266 = hit_sphere_0(_S265, 9.99999997475242708e-07f, closest_so_far_1, incident_0.ray_0);
+                        }
+                        else
+                        {
+                            _S266 = false;
+                        }
+                        if(_S266)
+                        {
+                            var _S267 : vec3<f32> = hitRec.global_0.normal_1;
+                            hit_local_0 = hitRec.local_0;
+                            closest_so_far_0 = hitRec.t_2;
+                            hit_uid_0 = _S265.object_uid_4;
+                            hit_material_id_0 = _S265.material_id_4;
+                            hit_global_normal_0 = _S267;
+                        }
+                        else
+                        {
+                            closest_so_far_0 = closest_so_far_1;
+                            hit_uid_0 = hit_uid_1;
+                            hit_material_id_0 = hit_material_id_1;
+                            hit_global_normal_0 = hit_global_normal_1;
+                        }
+                    }
+                    else
+                    {
+                        closest_so_far_0 = closest_so_far_1;
+                        hit_uid_0 = hit_uid_1;
+                        hit_material_id_0 = hit_material_id_1;
+                        hit_global_normal_0 = hit_global_normal_1;
+                    }
                 }
             }
             node_index_0 = node_index_0 + i32(1);
         }
         else
         {
-            var _S66 : i32 = bvh[node_index_0].hit_miss_skip_link_0;
+            var _S66 : i32 = node_1.hit_miss_skip_link_0;
             closest_so_far_0 = closest_so_far_1;
             hit_uid_0 = hit_uid_1;
             hit_material_id_0 = hit_material_id_1;
@@ -910,20 +1745,28 @@ fn trace_first_intersection_0( incident_0 : RayAndDifferentials_0) -> FirstHitSu
         hit_material_id_1 = hit_material_id_0;
         hit_global_normal_1 = hit_global_normal_0;
     }
+    var hit_emission_0 : vec3<f32>;
     if(u32(0) < hit_uid_1)
     {
-        var _S67 : Material_0 = Material_0( materials[hit_material_id_1].albedo_0, materials[hit_material_id_1].emission_0, materials[hit_material_id_1].specular_0, materials[hit_material_id_1].specular_strength_0, materials[hit_material_id_1].roughness_0, materials[hit_material_id_1].refractive_index_eta_0, materials[hit_material_id_1].albedo_texture_uid_0, materials[hit_material_id_1].material_class_0 );
-        var _S68 : vec3<f32> = fetch_albedo_0(hit_local_0, incident_0.ray_0.direction_0, closest_so_far_1, _S67, incident_0.differentials_0);
+        var _S67 : Material_0 = Material_0( materials[hit_material_id_1].albedo_0, materials[hit_material_id_1].surface_shader_uid_0, materials[hit_material_id_1].emission_0, materials[hit_material_id_1].normal_texture_uid_0, materials[hit_material_id_1].specular_0, materials[hit_material_id_1].specular_strength_0, materials[hit_material_id_1].roughness_0, materials[hit_material_id_1].refractive_index_eta_0, materials[hit_material_id_1].albedo_texture_uid_0, materials[hit_material_id_1].material_class_0, materials[hit_material_id_1].absorption_0 );
+        var _S68 : vec3<f32> = fetch_albedo_0(hit_local_0, hit_uid_1, incident_0.ray_0.direction_0, closest_so_far_1, _S67, incident_0.differentials_0);
+        var _S216 : Material_0 = Material_0( materials[hit_material_id_1].albedo_0, materials[hit_material_id_1].surface_shader_uid_0, materials[hit_material_id_1].emission_0, materials[hit_material_id_1].normal_texture_uid_0, materials[hit_material_id_1].specular_0, materials[hit_material_id_1].specular_strength_0, materials[hit_material_id_1].roughness_0, materials[hit_material_id_1].refractive_index_eta_0, materials[hit_material_id_1].albedo_texture_uid_0, materials[hit_material_id_1].material_class_0, materials[hit_material_id_1].absorption_0 );
+        var _S217 : vec3<f32> = perturbed_normal_0(hit_local_0, hit_uid_1, hit_global_normal_1, _S216, incident_0.ray_0.direction_0, closest_so_far_1, incident_0.differentials_0);
         hit_global_normal_0 = _S68;
+        hit_global_normal_1 = _S217;
+        hit_emission_0 = materials[hit_material_id_1].emission_0;
     }
     else
     {
         hit_global_normal_0 = _S45;
+        hit_emission_0 = _S45;
     }
     var result_9 : FirstHitSurface_0;
     result_9.object_uid_3 = hit_uid_1;
     result_9.albedo_1 = hit_global_normal_0;
     result_9.normal_3 = hit_global_normal_1;
+    result_9.emission_1 = hit_emission_0;
+    result_9.depth_0 = closest_so_far_1;
     return result_9;
 }
 
@@ -938,10 +1781,23 @@ fn compute_surface_attributes_buffer(@builtin(global_invocation_id) global_invoc
         return;
     }
     var pixel_3 : Pixel_0 = setup_pixel_coordinates_0(pixel_index_2);
-    var surface_intersection_0 : FirstHitSurface_0 = trace_first_intersection_0(ray_and_differentials_0(setup_camera_0(), pixel_3, 0.5f, 0.5f));
+    var ray_9 : RayAndDifferentials_0 = ray_and_differentials_0(setup_camera_0(), pixel_3, 0.5f, 0.5f);
+    var surface_intersection_0 : FirstHitSurface_0 = trace_first_intersection_0(ray_9);
     object_id_buffer[pixel_index_2] = surface_intersection_0.object_uid_3;
     albedo_buffer[pixel_index_2] = vec4<f32>(surface_intersection_0.albedo_1.xyz, 1.0f);
     normal_buffer[pixel_index_2] = vec4<f32>(surface_intersection_0.normal_3, 0.0f);
+    depth_buffer[pixel_index_2] = surface_intersection_0.depth_0;
+    var previous_pixel_1 : vec2<f32> = previous_frame_pixel_0(ray_9.ray_0.origin_2 + ray_9.ray_0.direction_0 * vec3<f32>(min(surface_intersection_0.depth_0, 10000.0f)));
+    var _S904 : vec2<f32>;
+    if(all(previous_pixel_1 == vec2<f32>(-1.0f)))
+    {
+        _S904 = vec2<f32>(0.0f);
+    }
+    else
+    {
+        _S904 = previous_pixel_1 - (pixel_3.coordinates_0 + vec2<f32>(0.5f));
+    }
+    motion_vector_buffer[pixel_index_2] = _S904;
     return;
 }
 
@@ -959,7 +1815,16 @@ fn get_lights_0()
         {
             break;
         }
-        if((any(((materials[parallelograms[i_3].material_id_0].emission_0.xyz) != vec3<f32>(0.0f)))))
+        var _S215 : bool;
+        if(object_traced_0(parallelograms[i_3].object_uid_0))
+        {
+            _S215 = (any(((materials[parallelograms[i_3].material_id_0].emission_0.xyz) != vec3<f32>(0.0f))));
+        }
+        else
+        {
+            _S215 = false;
+        }
+        if(_S215)
         {
             var _S69 : vec3<f32> = parallelograms[i_3].u_0;
             var _S70 : u32 = parallelograms[i_3].object_uid_0;
@@ -990,22 +1855,74 @@ fn make_common_color_evaluation_setup_0( pixel_index_3 : u32) -> Pixel_0
     return pixel_4;
 }
 
+var<private> randRotation : f32;
+
+fn xxhash32_0( value_0 : u32) -> u32
+{
+    var h32_0 : u32 = value_0 + u32(374761393);
+    var _S919 : u32 = u32(668265263) * (((((h32_0 << (u32(17)))) | ((h32_0 >> (u32(15)))))));
+    var _S920 : u32 = u32(2246822519) * ((_S919 ^ ((_S919 >> (u32(15))))));
+    var _S921 : u32 = u32(3266489917) * ((_S920 ^ ((_S920 >> (u32(13))))));
+    return _S921 ^ ((_S921 >> (u32(16))));
+}
+
 fn rand_0_1_0() -> f32
 {
-    var _S76 : u32 = randState * u32(747796405) + u32(2891336453);
-    randState = _S76;
-    var word_0 : u32 = ((((_S76 >> ((((_S76 >> (u32(28)))) + u32(4))))) ^ (_S76))) * u32(277803737);
-    return f32((((word_0 >> (u32(22)))) ^ (word_0))) / 4.294967296e+09f;
+    var word_0 : u32;
+    if(u32(1) == (uniforms.random_generator_0))
+    {
+        var _S922 : u32 = xxhash32_0(randState);
+        randState = randState + u32(1);
+        word_0 = _S922;
+    }
+    else
+    {
+        var _S76 : u32 = randState * u32(747796405) + u32(2891336453);
+        randState = _S76;
+        var permuted_0 : u32 = ((((_S76 >> ((((_S76 >> (u32(28)))) + u32(4))))) ^ (_S76))) * u32(277803737);
+        word_0 = ((permuted_0 >> (u32(22)))) ^ (permuted_0);
+    }
+    return fract(f32(word_0) / 4.294967296e+09f + randRotation);
+}
+
+fn seed_random_0( sequence_0 : u32,  frame_14 : u32)
+{
+    randRotation = 0.0f;
+    if(u32(1) == (uniforms.random_generator_0))
+    {
+        randState = xxhash32_0(sequence_0 ^ xxhash32_0(frame_14));
+    }
+    else
+    {
+        randState = sequence_0 + frame_14 * u32(719393);
+    }
+    return;
+}
+
+@binding(20) @group(1) var<storage, read> random_seeds : array<f32>;
+
+fn seed_pixel_random_0( sequence_1 : u32,  pixel_14 : vec2<u32>)
+{
+    seed_random_0(sequence_1, u32(uniforms.frame_number_0));
+    if((uniforms.random_seeds_width_0) > u32(0))
+    {
+        var texel_3 : vec2<u32> = pixel_14 % vec2<u32>(uniforms.random_seeds_width_0, uniforms.random_seeds_height_0);
+        randRotation = fract(random_seeds[texel_3.y * uniforms.random_seeds_width_0 + texel_3.x] + uniforms.frame_number_0 * 0.61803400516510010f);
+    }
+    return;
 }
 
 var<private> hitMaterial : Material_0;
 
+var<private> hitParallelogram : i32;
+
 fn hit_scene_0( ray_5 : Ray_0,  max_ray_patameter_0 : f32) -> bool
 {
     var hit_anything_0 : bool;
     var closest_so_far_2 : f32;
     var closest_so_far_3 : f32 = max_ray_patameter_0;
     var hit_anything_1 : bool = false;
+    hitParallelogram = i32(-1);
     var i_4 : u32 = u32(0);
     for(;;)
     {
@@ -1017,11 +1934,20 @@ fn hit_scene_0( ray_5 : Ray_0,  max_ray_patameter_0 : f32) -> bool
             break;
         }
         var _S77 : Parallelogram_0 = Parallelogram_0( parallelograms[i_4].Q_0, parallelograms[i_4].u_0, parallelograms[i_4].object_uid_0, parallelograms[i_4].v_0, parallelograms[i_4].D_0, parallelograms[i_4].normal_0, parallelograms[i_4].w_0, parallelograms[i_4].material_id_0 );
-        var _S78 : bool = hit_quad_0(_S77, 9.99999997475242708e-07f, closest_so_far_3, ray_5);
+        var _S78 : bool;
+        if(object_traced_0(_S77.object_uid_0))
+        {
+            _S78 = hit_quad_0(_S77, 9.99999997475242708e-07f, closest_so_far_3, ray_5);
+        }
+        else
+        {
+            _S78 = false;
+        }
         if(_S78)
         {
             closest_so_far_2 = hitRec.t_2;
             hit_anything_0 = true;
+            hitParallelogram = i32(i_4);
         }
         else
         {
@@ -1054,18 +1980,28 @@ fn hit_scene_0( ray_5 : Ray_0,  max_ray_patameter_0 : f32) -> bool
         {
             break;
         }
-        var aabb_hit_1 : AabbHit_0 = hit_aabb_0(bvh[node_index_1].aabb_min_0, bvh[node_index_1].aabb_max_0, 9.99999997475242708e-07f, closest_so_far_3, ray_5.origin_2, _S80);
+        var node_2 : BvhNode_0 = load_bvh_node_0(u32(node_index_1));
+        var aabb_hit_1 : AabbHit_0 = hit_aabb_0(node_2.aabb_min_0, node_2.aabb_max_0, 9.99999997475242708e-07f, closest_so_far_3, ray_5.origin_2, _S80);
         if(aabb_hit_1.hit_0)
         {
-            var _S83 : u32 = bvh[node_index_1].primitive_type_0;
-            if(u32(2) == (bvh[node_index_1].primitive_type_0))
+            var _S83 : u32 = node_2.primitive_type_0;
+            if(u32(2) == (node_2.primitive_type_0))
             {
-                var _S84 : Triangle_0 = Triangle_0( triangles[bvh[node_index_1].primitive_index_0].A_0, triangles[bvh[node_index_1].primitive_index_0].B_0, triangles[bvh[node_index_1].primitive_index_0].C_0, triangles[bvh[node_index_1].primitive_index_0].normalA_0, triangles[bvh[node_index_1].primitive_index_0].normalB_0, triangles[bvh[node_index_1].primitive_index_0].object_uid_1, triangles[bvh[node_index_1].primitive_index_0].normalC_0, triangles[bvh[node_index_1].primitive_index_0].material_id_1 );
-                var _S85 : bool = hit_triangle_0(_S84, 9.99999997475242708e-07f, closest_so_far_3, ray_5);
+                var _S84 : Triangle_0 = load_triangle_0(node_2.primitive_index_0);
+                var _S85 : bool;
+                if(object_traced_0(_S84.object_uid_1))
+                {
+                    _S85 = hit_triangle_0(_S84, 9.99999997475242708e-07f, closest_so_far_3, ray_5);
+                }
+                else
+                {
+                    _S85 = false;
+                }
                 if(_S85)
                 {
                     closest_so_far_2 = hitRec.t_2;
                     hit_anything_0 = true;
+                    hitParallelogram = i32(-1);
                 }
                 else
                 {
@@ -1077,12 +2013,21 @@ fn hit_scene_0( ray_5 : Ray_0,  max_ray_patameter_0 : f32) -> bool
             {
                 if(u32(1) == _S83)
                 {
-                    var _S86 : Sdf_0 = Sdf_0( sdf[bvh[node_index_1].primitive_index_0].location_col_0_0, sdf[bvh[node_index_1].primitive_index_0].location_col_1_0, sdf[bvh[node_index_1].primitive_index_0].location_col_2_0, sdf[bvh[node_index_1].primitive_index_0].inverse_location_col_0_0, sdf[bvh[node_index_1].primitive_index_0].inverse_location_col_1_0, sdf[bvh[node_index_1].primitive_index_0].inverse_location_col_2_0, sdf[bvh[node_index_1].primitive_index_0].ray_marching_step_scale_0, sdf[bvh[node_index_1].primitive_index_0].class_index_0, sdf[bvh[node_index_1].primitive_index_0].material_id_2, sdf[bvh[node_index_1].primitive_index_0].object_uid_2 );
-                    var _S87 : bool = hit_sdf_0(_S86, sdf_time[bvh[node_index_1].primitive_index_0], ray_5, aabb_hit_1.ray_parameter_0, closest_so_far_3);
+                    var _S86 : Sdf_0 = Sdf_0( sdf[node_2.primitive_index_0].location_col_0_0, sdf[node_2.primitive_index_0].location_col_1_0, sdf[node_2.primitive_index_0].location_col_2_0, sdf[node_2.primitive_index_0].inverse_location_col_0_0, sdf[node_2.primitive_index_0].inverse_location_col_1_0, sdf[node_2.primitive_index_0].inverse_location_col_2_0, sdf[node_2.primitive_index_0].ray_marching_step_scale_0, sdf[node_2.primitive_index_0].class_index_0, sdf[node_2.primitive_index_0].material_id_2, sdf[node_2.primitive_index_0].object_uid_2, sdf[node_2.primitive_index_0].parameters_0 );
+                    var _S87 : bool;
+                    if(object_traced_0(_S86.object_uid_2))
+                    {
+                        _S87 = hit_sdf_within_aabb_0(_S86, sdf_time[node_2.primitive_index_0], node_2.aabb_min_0, node_2.aabb_max_0, ray_5, aabb_hit_1.ray_parameter_0, closest_so_far_3);
+                    }
+                    else
+                    {
+                        _S87 = false;
+                    }
                     if(_S87)
                     {
                         closest_so_far_2 = hitRec.t_2;
                         hit_anything_0 = true;
+                        hitParallelogram = i32(-1);
                     }
                     else
                     {
@@ -1092,15 +2037,42 @@ fn hit_scene_0( ray_5 : Ray_0,  max_ray_patameter_0 : f32) -> bool
                 }
                 else
                 {
-                    closest_so_far_2 = closest_so_far_3;
-                    hit_anything_0 = hit_anything_1;
+                    if(u32(3) == _S83)
+                    {
+                        var _S268 : Sphere_0 = Sphere_0( spheres[node_2.primitive_index_0].center_4, spheres[node_2.primitive_index_0].radius_4, spheres[node_2.primitive_index_0].object_uid_4, spheres[node_2.primitive_index_0].material_id_4 );
+                        var _S269 : bool;
+                        if(object_traced_0(_S268.object_uid_4))
+                        {
+                            _S269 = hit_sphere_0(_S268, 9.99999997475242708e-07f, closest_so_far_3, ray_5);
+                        }
+                        else
+                        {
+                            _S269 = false;
+                        }
+                        if(_S269)
+                        {
+                            closest_so_far_2 = hitRec.t_2;
+                            hit_anything_0 = true;
+                            hitParallelogram = i32(-1);
+                        }
+                        else
+                        {
+                            closest_so_far_2 = closest_so_far_3;
+                            hit_anything_0 = hit_anything_1;
+                        }
+                    }
+                    else
+                    {
+                        closest_so_far_2 = closest_so_far_3;
+                        hit_anything_0 = hit_anything_1;
+                    }
                 }
             }
             node_index_1 = node_index_1 + i32(1);
         }
         else
         {
-            var _S88 : i32 = bvh[node_index_1].hit_miss_skip_link_0;
+            var _S88 : i32 = node_2.hit_miss_skip_link_0;
             closest_so_far_2 = closest_so_far_3;
             hit_anything_0 = hit_anything_1;
             node_index_1 = _S88;
@@ -1115,14 +2087,18 @@ fn hit_scene_0( ray_5 : Ray_0,  max_ray_patameter_0 : f32) -> bool
     var _S93 : f32 = materials[hitRec.material_id_3].refractive_index_eta_0;
     var _S94 : i32 = materials[hitRec.material_id_3].albedo_texture_uid_0;
     var _S95 : i32 = materials[hitRec.material_id_3].material_class_0;
+    var _S218 : i32 = materials[hitRec.material_id_3].normal_texture_uid_0;
     hitMaterial.albedo_0 = materials[hitRec.material_id_3].albedo_0;
+    hitMaterial.surface_shader_uid_0 = materials[hitRec.material_id_3].surface_shader_uid_0;
     hitMaterial.emission_0 = _S89;
+    hitMaterial.normal_texture_uid_0 = _S218;
     hitMaterial.specular_0 = _S90;
     hitMaterial.specular_strength_0 = _S91;
     hitMaterial.roughness_0 = _S92;
     hitMaterial.refractive_index_eta_0 = _S93;
     hitMaterial.albedo_texture_uid_0 = _S94;
     hitMaterial.material_class_0 = _S95;
+    hitMaterial.absorption_0 = materials[hitRec.material_id_3].absorption_0;
     return hit_anything_1;
 }
 
@@ -1220,7 +2196,29 @@ fn near_zero_0( v_4 : vec3<f32>) -> bool
     return _S102;
 }
 
-fn glass_scatter_0( hit_2 : HitRecord_0,  refractive_index_eta_1 : f32,  in_ray_direction_0 : vec3<f32>,  stochastic_0 : bool) -> Ray_0
+fn ggx_microfacet_normal_0( normal_2 : vec3<f32>,  roughness_1 : f32) -> vec3<f32>
+{
+    var alpha_1 : f32 = roughness_1 * roughness_1;
+    var random_0 : f32 = rand_0_1_0();
+    var tan_theta_squared_0 : f32 = alpha_1 * alpha_1 * random_0 / max(1.0f - random_0, 0.00009999999747379f);
+    var cos_theta_1 : f32 = 1.0f / sqrt(1.0f + tan_theta_squared_0);
+    var sin_theta_0 : f32 = sqrt(max(0.0f, 1.0f - cos_theta_1 * cos_theta_1));
+    var _S272 : f32 = rand_0_1_0();
+    var phi_2 : f32 = 2.0f * 3.14159274101257324f * _S272;
+    var _S273 : mat3x3<f32> = onb_build_from_w_0(normal_2);
+    return normalize(onb_get_local_0(vec3<f32>(sin_theta_0 * cos(phi_2), sin_theta_0 * sin(phi_2), cos_theta_1)));
+}
+
+fn glass_transmittance_0( hit_5 : HitRecord_0,  material_2 : Material_0) -> vec3<f32>
+{
+    if((i32(2) != (material_2.material_class_0)) || (hit_5.front_face_0))
+    {
+        return vec3<f32>(1.0f);
+    }
+    return exp(- material_2.absorption_0 * vec3<f32>(hit_5.t_2));
+}
+
+fn glass_scatter_0( hit_2 : HitRecord_0,  refractive_index_eta_1 : f32,  roughness_2 : f32,  in_ray_direction_0 : vec3<f32>,  stochastic_0 : bool) -> Ray_0
 {
     var ir_0 : f32;
     if(hit_2.front_face_0)
@@ -1231,11 +2229,21 @@ fn glass_scatter_0( hit_2 : HitRecord_0,  refractive_index_eta_1 : f32,  in_ray_
     {
         ir_0 = refractive_index_eta_1;
     }
-    var cos_theta_0 : f32 = min(- dot(in_ray_direction_0, hit_2.global_0.normal_1), 1.0f);
+    var normal_3 : vec3<f32> = hit_2.global_0.normal_1;
+    if(stochastic_0 && (roughness_2 > 0.0f))
+    {
+        var microfacet_normal_0 : vec3<f32> = ggx_microfacet_normal_0(hit_2.global_0.normal_1, roughness_2);
+        if((dot(in_ray_direction_0, microfacet_normal_0)) < 0.0f)
+        {
+            normal_3 = microfacet_normal_0;
+        }
+    }
+    var cos_theta_0 : f32 = min(- dot(in_ray_direction_0, normal_3), 1.0f);
     var direction_3 : vec3<f32>;
+    var reflected_1 : bool = true;
     if((ir_0 * sqrt(1.0f - cos_theta_0 * cos_theta_0)) > 1.0f)
     {
-        direction_3 = reflect(in_ray_direction_0, hit_2.global_0.normal_1);
+        direction_3 = reflect(in_ray_direction_0, normal_3);
     }
     else
     {
@@ -1245,18 +2253,25 @@ fn glass_scatter_0( hit_2 : HitRecord_0,  refractive_index_eta_1 : f32,  in_ray_
             var _S104 : f32 = rand_0_1_0();
             if(_S103 > _S104)
             {
-                direction_3 = reflect(in_ray_direction_0, hitRec.global_0.normal_1);
+                direction_3 = reflect(in_ray_direction_0, normal_3);
             }
             else
             {
-                direction_3 = refract(in_ray_direction_0, hitRec.global_0.normal_1, ir_0);
+                direction_3 = refract(in_ray_direction_0, normal_3, ir_0);
+                reflected_1 = false;
             }
         }
         else
         {
-            direction_3 = refract(in_ray_direction_0, hitRec.global_0.normal_1, ir_0);
+            direction_3 = refract(in_ray_direction_0, normal_3, ir_0);
+            reflected_1 = false;
         }
     }
+    var side_0 : f32 = dot(direction_3, hit_2.global_0.normal_1);
+    if((reflected_1 && (side_0 < 0.0f)) || ((!reflected_1) && (side_0 > 0.0f)))
+    {
+        direction_3 = reflect(direction_3, hit_2.global_0.normal_1);
+    }
     if(near_zero_0(direction_3))
     {
         direction_3 = hitRec.global_0.normal_1;
@@ -1318,7 +2333,7 @@ fn material_scatter_0( ray_in_0 : Ray_0) -> Ray_0
         {
             if(i32(2) == (hitMaterial.material_class_0))
             {
-                var _S111 : Ray_0 = glass_scatter_0(hitRec, hitMaterial.refractive_index_eta_0, ray_in_0.direction_0, true);
+                var _S111 : Ray_0 = glass_scatter_0(hitRec, hitMaterial.refractive_index_eta_0, hitMaterial.roughness_0, ray_in_0.direction_0, true);
                 scattered_0 = _S111;
                 scatterRec.skip_pdf_0 = true;
                 scatterRec.skip_pdf_ray_0 = scattered_0;
@@ -1437,73 +2452,450 @@ fn light_pdf_0( ray_6 : Ray_0,  quad_1 : Parallelogram_0) -> f32
     return t_4 * t_4 * _S125 * _S125 / (abs(dot(ray_6.direction_0, hitNormal_0) / _S125) * length(cross(lights.u_0, lights.v_0)));
 }
 
-fn ray_color_monte_carlo_0( incident_1 : RayAndDifferentials_0) -> vec3<f32>
+fn next_event_estimation_enabled_0() -> bool
 {
-    var current_ray_0 : Ray_0 = incident_1.ray_0;
-    var _S126 : vec3<f32> = vec3<f32>(0.0f);
-    var _S127 : vec3<f32> = vec3<f32>(1.0f);
-    var i_5 : i32 = i32(0);
-    var throughput_0 : vec3<f32> = _S127;
-    var accumulated_radiance_0 : vec3<f32> = _S126;
+    var _S239 : bool;
+    if(u32(0) != (uniforms.next_event_estimation_0))
+    {
+        _S239 = u32(0) < (uniforms.emissive_parallelograms_count_0);
+    }
+    else
+    {
+        _S239 = false;
+    }
+    return _S239;
+}
+
+fn power_heuristic_0( pdf_3 : f32,  other_pdf_0 : f32) -> f32
+{
+    var pdf_squared_0 : f32 = pdf_3 * pdf_3;
+    return pdf_squared_0 / (pdf_squared_0 + other_pdf_0 * other_pdf_0);
+}
+
+struct LightTreeNode_0
+{
+     min_2 : vec3<f32>,
+     power_0 : f32,
+     max_2 : vec3<f32>,
+     link_0 : u32,
+};
+
+fn load_light_tree_node_0( index_light_0 : u32) -> LightTreeNode_0
+{
+    var lower_0 : vec4<f32> = light_tree[u32(2) * index_light_0];
+    var upper_0 : vec4<f32> = light_tree[u32(2) * index_light_0 + u32(1)];
+    var result_light_0 : LightTreeNode_0;
+    result_light_0.min_2 = lower_0.xyz;
+    result_light_0.power_0 = lower_0.w;
+    result_light_0.max_2 = upper_0.xyz;
+    result_light_0.link_0 = (bitcast<u32>((upper_0.w)));
+    return result_light_0;
+}
+
+fn light_tree_node_importance_0( index_light_1 : u32,  position_light_0 : vec3<f32>) -> f32
+{
+    var node_light_0 : LightTreeNode_0 = load_light_tree_node_0(index_light_1);
+    var half_extent_0 : vec3<f32> = vec3<f32>(0.5f) * (node_light_0.max_2 - node_light_0.min_2);
+    var to_center_0 : vec3<f32> = vec3<f32>(0.5f) * (node_light_0.max_2 + node_light_0.min_2) - position_light_0;
+    return node_light_0.power_0 / max(dot(to_center_0, to_center_0), max(dot(half_extent_0, half_extent_0), 0.00009999999747379f));
+}
+
+fn light_tree_left_probability_0( node_light_1 : u32,  position_light_1 : vec3<f32>) -> f32
+{
+    var left_light_0 : f32 = light_tree_node_importance_0(node_light_1 + u32(1), position_light_1);
+    var _S900 : LightTreeNode_0 = load_light_tree_node_0(node_light_1);
+    var total_light_0 : f32 = left_light_0 + light_tree_node_importance_0(_S900.link_0, position_light_1);
+    if(total_light_0 > 0.0f)
+    {
+        return left_light_0 / total_light_0;
+    }
+    return 0.5f;
+}
+
+fn pick_light_0( position_light_2 : vec3<f32>,  probability_light_0 : ptr<function, f32>) -> u32
+{
+    (*probability_light_0) = 1.0f;
+    var choice_0 : f32 = rand_0_1_0();
+    var node_light_2 : u32 = u32(0);
+    var link_light_0 : u32 = load_light_tree_node_0(node_light_2).link_0;
     for(;;)
     {
-        if(i_5 < i32(50))
-        {
-        }
-        else
-        {
-            break;
-        }
-        var _S128 : bool = hit_scene_0(current_ray_0, 1.0e+09f);
-        if(_S128 == false)
+        if(u32(0) != (link_light_0 & u32(2147483648)))
         {
-            accumulated_radiance_0 = accumulated_radiance_0 + vec3<f32>(0.10000000149011612f) * throughput_0;
             break;
         }
-        var albedo_color_0 : vec3<f32> = fetch_albedo_0(hitRec.local_0, current_ray_0.direction_0, hitRec.t_2, hitMaterial, incident_1.differentials_0);
-        var _S129 : vec3<f32> = hitMaterial.emission_0.xyz;
-        var emission_color_0 : vec3<f32>;
-        if(!hitRec.front_face_0)
+        var left_light_1 : f32 = light_tree_left_probability_0(node_light_2, position_light_2);
+        if(choice_0 < left_light_1)
         {
-            emission_color_0 = _S126;
+            choice_0 = choice_0 / left_light_1;
+            (*probability_light_0) = (*probability_light_0) * left_light_1;
+            node_light_2 = node_light_2 + u32(1);
         }
         else
         {
-            emission_color_0 = _S129;
+            choice_0 = (choice_0 - left_light_1) / max(1.0f - left_light_1, 0.00009999999747379f);
+            (*probability_light_0) = (*probability_light_0) * (1.0f - left_light_1);
+            node_light_2 = link_light_0;
         }
-        var scatterred_surface_0 : Ray_0 = material_scatter_0(current_ray_0);
-        if(scatterRec.skip_pdf_0)
+        link_light_0 = load_light_tree_node_0(node_light_2).link_0;
+    }
+    return link_light_0 & (~u32(2147483648));
+}
+
+fn light_pick_probability_0( light_index_0 : u32,  position_light_3 : vec3<f32>) -> f32
+{
+    var path_light_0 : u32 = emissive_parallelograms[light_index_0].y;
+    var probability_light_1 : f32 = 1.0f;
+    var node_light_3 : u32 = u32(0);
+    var link_light_1 : u32 = load_light_tree_node_0(node_light_3).link_0;
+    var depth_light_0 : u32 = u32(0);
+    for(;;)
+    {
+        if(u32(0) != (link_light_1 & u32(2147483648)))
         {
-            var accumulated_radiance_1 : vec3<f32> = accumulated_radiance_0 + emission_color_0 * throughput_0;
-            var throughput_1 : vec3<f32> = throughput_0 * mix(albedo_color_0, hitMaterial.specular_0, vec3<f32>(doSpecular));
-            current_ray_0 = scatterRec.skip_pdf_ray_0;
-            current_ray_0.origin_2 = current_ray_0.origin_2 + current_ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
-            throughput_0 = throughput_1;
-            accumulated_radiance_0 = accumulated_radiance_1;
-            i_5 = i_5 + i32(1);
-            continue;
+            break;
         }
-        var _S130 : f32 = rand_0_1_0();
-        var scattered_2 : Ray_0;
-        if(_S130 > 0.20000000298023224f)
+        var left_light_2 : f32 = light_tree_left_probability_0(node_light_3, position_light_3);
+        if(u32(0) == ((path_light_0 >> depth_light_0) & u32(1)))
         {
-            scattered_2 = scatterred_surface_0;
+            probability_light_1 = probability_light_1 * left_light_2;
+            node_light_3 = node_light_3 + u32(1);
         }
         else
         {
-            var _S131 : Ray_0 = get_random_on_quad_0(lights, hitRec.global_0.position_2);
-            scattered_2 = _S131;
+            probability_light_1 = probability_light_1 * (1.0f - left_light_2);
+            node_light_3 = link_light_1;
         }
-        var lambertian_pdf_0 : f32 = onb_lambertian_scattering_pdf_0(scattered_2);
-        var pdf_1 : f32 = 0.20000000298023224f * light_pdf_0(scattered_2, lights) + 0.80000001192092896f * lambertian_pdf_0;
-        if(pdf_1 <= 0.00000999999974738f)
+        link_light_1 = load_light_tree_node_0(node_light_3).link_0;
+        depth_light_0 = depth_light_0 + u32(1);
+    }
+    return probability_light_1;
+}
+
+fn find_light_0( slot_light_0 : u32) -> u32
+{
+    var low_0 : u32 = u32(0);
+    var high_0 : u32 = uniforms.emissive_parallelograms_count_0;
+    for(;;)
+    {
+        if(low_0 < high_0)
+        {
+        }
+        else
+        {
+            break;
+        }
+        var middle_0 : u32 = (low_0 + high_0) / u32(2);
+        if((emissive_parallelograms[middle_0].x) < slot_light_0)
+        {
+            low_0 = middle_0 + u32(1);
+        }
+        else
+        {
+            high_0 = middle_0;
+        }
+    }
+    var _S901 : bool;
+    if(low_0 < (uniforms.emissive_parallelograms_count_0))
+    {
+        _S901 = (emissive_parallelograms[low_0].x) == slot_light_0;
+    }
+    else
+    {
+        _S901 = false;
+    }
+    if(_S901)
+    {
+        return low_0;
+    }
+    return uniforms.emissive_parallelograms_count_0;
+}
+
+fn emissive_parallelogram_pdf_0( quad_2 : Parallelogram_0,  quad_distance_0 : f32,  light_cosine_0 : f32,  pick_probability_0 : f32) -> f32
+{
+    return quad_distance_0 * quad_distance_0 / (light_cosine_0 * length(cross(quad_2.u_0, quad_2.v_0)) * pick_probability_0);
+}
+
+fn sample_direct_light_0( position_6 : vec3<f32>,  normal_4 : vec3<f32>,  albedo_2 : vec3<f32>) -> vec3<f32>
+{
+    var pick_probability_1 : f32;
+    var _S240 : u32 = pick_light_0(position_6, &(pick_probability_1));
+    var _S241 : u32 = emissive_parallelograms[_S240].x;
+    var light_0 : Parallelogram_0 = Parallelogram_0( parallelograms[_S241].Q_0, parallelograms[_S241].u_0, parallelograms[_S241].object_uid_0, parallelograms[_S241].v_0, parallelograms[_S241].D_0, parallelograms[_S241].normal_0, parallelograms[_S241].w_0, parallelograms[_S241].material_id_0 );
+    var _S902 : bool;
+    if(pick_probability_1 <= 0.0f)
+    {
+        _S902 = true;
+    }
+    else
+    {
+        _S902 = !object_traced_0(light_0.object_uid_0);
+    }
+    if(_S902)
+    {
+        return vec3<f32>(0.0f);
+    }
+    var _S242 : vec3<f32> = materials[light_0.material_id_0].emission_0;
+    var _S243 : f32 = rand_0_1_0();
+    var _S250 : vec3<f32> = light_0.Q_0 + vec3<f32>(_S243) * light_0.u_0;
+    var _S251 : f32 = rand_0_1_0();
+    var to_light_1 : vec3<f32> = _S250 + vec3<f32>(_S251) * light_0.v_0 - position_6;
+    var light_distance_0 : f32 = length(to_light_1);
+    if(light_distance_0 < 0.00009999999747379f)
+    {
+        return vec3<f32>(0.0f);
+    }
+    var direction_7 : vec3<f32> = to_light_1 / vec3<f32>(light_distance_0);
+    var surface_cosine_0 : f32 = dot(direction_7, normal_4);
+    var light_cosine_1 : f32 = - dot(direction_7, light_0.normal_0);
+    var _S244 : bool;
+    if(surface_cosine_0 <= 0.0f)
+    {
+        _S244 = true;
+    }
+    else
+    {
+        _S244 = light_cosine_1 <= 0.00009999999747379f;
+    }
+    if(_S244)
+    {
+        return vec3<f32>(0.0f);
+    }
+    var shadow_ray_0 : Ray_0;
+    shadow_ray_0.origin_2 = position_6 + direction_7 * vec3<f32>(0.00050000002374873f);
+    shadow_ray_0.direction_0 = direction_7;
+    var _S245 : bool = hit_scene_0(shadow_ray_0, (light_distance_0 - 0.00050000002374873f) * 0.99900001287460327f);
+    if(_S245)
+    {
+        return vec3<f32>(0.0f);
+    }
+    var light_sample_pdf_0 : f32 = emissive_parallelogram_pdf_0(light_0, light_distance_0, light_cosine_1, pick_probability_1);
+    var bsdf_pdf_0 : f32 = surface_cosine_0 / 3.14159274101257324f;
+    return _S242 * albedo_2 * vec3<f32>(bsdf_pdf_0) * vec3<f32>(power_heuristic_0(light_sample_pdf_0, bsdf_pdf_0)) / vec3<f32>(light_sample_pdf_0);
+}
+
+fn emission_mis_weight_0( bsdf_pdf_1 : f32,  ray_light_0 : Ray_0,  ray_parameter_2 : f32) -> f32
+{
+    var _S246 : bool;
+    if(bsdf_pdf_1 <= 0.0f)
+    {
+        _S246 = true;
+    }
+    else
+    {
+        _S246 = i32(-1) == hitParallelogram;
+    }
+    if(_S246)
+    {
+        return 1.0f;
+    }
+    var light_index_1 : u32 = find_light_0(u32(hitParallelogram));
+    if(light_index_1 >= (uniforms.emissive_parallelograms_count_0))
+    {
+        return 1.0f;
+    }
+    var light_1 : Parallelogram_0 = Parallelogram_0( parallelograms[hitParallelogram].Q_0, parallelograms[hitParallelogram].u_0, parallelograms[hitParallelogram].object_uid_0, parallelograms[hitParallelogram].v_0, parallelograms[hitParallelogram].D_0, parallelograms[hitParallelogram].normal_0, parallelograms[hitParallelogram].w_0, parallelograms[hitParallelogram].material_id_0 );
+    var light_cosine_2 : f32 = abs(dot(normalize(ray_light_0.direction_0), light_1.normal_0));
+    var pick_probability_2 : f32 = light_pick_probability_0(light_index_1, ray_light_0.origin_2);
+    var _S903 : bool;
+    if(light_cosine_2 <= 0.00009999999747379f)
+    {
+        _S903 = true;
+    }
+    else
+    {
+        _S903 = pick_probability_2 <= 0.0f;
+    }
+    if(_S903)
+    {
+        return 1.0f;
+    }
+    return power_heuristic_0(bsdf_pdf_1, emissive_parallelogram_pdf_0(light_1, ray_parameter_2 * length(ray_light_0.direction_0), light_cosine_2, pick_probability_2));
+}
+
+fn surface_shader_uv_0( local_1 : HitPlace_0) -> vec2<f32>
+{
+    var weights_0 : vec3<f32> = abs(local_1.normal_1);
+    var _S200 : bool;
+    if((weights_0.x) >= (weights_0.y))
+    {
+        _S200 = (weights_0.x) >= (weights_0.z);
+    }
+    else
+    {
+        _S200 = false;
+    }
+    if(_S200)
+    {
+        return local_1.position_2.yz;
+    }
+    if((weights_0.y) >= (weights_0.z))
+    {
+        return local_1.position_2.xz;
+    }
+    return local_1.position_2.xy;
+}
+
+fn evaluate_surface_shader_0( hit_4 : HitRecord_0,  hit_material_3 : Material_0,  hit_albedo_3 : vec3<f32>) -> vec3<f32>
+{
+    return surface_shader_select(hit_material_3.surface_shader_uid_0, hit_4.local_0.position_2, hit_4.local_0.normal_1, surface_shader_uv_0(hit_4.local_0), uniforms.global_time_seconds_0, hit_albedo_3);
+}
+
+fn background_radiance_0( direction_6 : vec3<f32>) -> vec3<f32>
+{
+    if(u32(0) == (uniforms.background_uid_0))
+    {
+        return uniforms.clear_color_0.xyz;
+    }
+    return background_select(i32(uniforms.background_uid_0), direction_6, uniforms.global_time_seconds_0);
+}
+
+struct LightingAovs_0
+{
+    direct_0 : vec3<f32>,
+    indirect_1 : vec3<f32>,
+    emission_2 : vec3<f32>,
+};
+
+var<private> sampleLighting : LightingAovs_0;
+
+fn no_lighting_0() -> LightingAovs_0
+{
+    var lighting_0 : LightingAovs_0;
+    lighting_0.direct_0 = vec3<f32>(0.0f);
+    lighting_0.indirect_1 = vec3<f32>(0.0f);
+    lighting_0.emission_2 = vec3<f32>(0.0f);
+    return lighting_0;
+}
+
+fn lighting_contribution_0( radiance_2 : vec3<f32>,  scattering_surfaces_0 : i32) -> vec3<f32>
+{
+    if(i32(0) == scattering_surfaces_0)
+    {
+        sampleLighting.emission_2 = sampleLighting.emission_2 + radiance_2;
+    }
+    else
+    {
+        if(i32(1) == scattering_surfaces_0)
+        {
+            sampleLighting.direct_0 = sampleLighting.direct_0 + radiance_2;
+        }
+        else
+        {
+            sampleLighting.indirect_1 = sampleLighting.indirect_1 + radiance_2;
+        }
+    }
+    return radiance_2;
+}
+
+var<private> pixelLighting : LightingAovs_0;
+
+fn add_sample_lighting_0()
+{
+    pixelLighting.direct_0 = pixelLighting.direct_0 + sampleLighting.direct_0;
+    pixelLighting.indirect_1 = pixelLighting.indirect_1 + sampleLighting.indirect_1;
+    pixelLighting.emission_2 = pixelLighting.emission_2 + sampleLighting.emission_2;
+    return;
+}
+
+fn ray_color_monte_carlo_0( incident_1 : RayAndDifferentials_0,  max_bounces_0 : i32) -> vec3<f32>
+{
+    var current_ray_0 : Ray_0 = incident_1.ray_0;
+    var _S126 : vec3<f32> = vec3<f32>(0.0f);
+    var _S127 : vec3<f32> = vec3<f32>(1.0f);
+    var next_event_estimation_1 : bool = next_event_estimation_enabled_0();
+    sampleLighting = no_lighting_0();
+    var i_5 : i32 = i32(0);
+    var throughput_0 : vec3<f32> = _S127;
+    var accumulated_radiance_0 : vec3<f32> = _S126;
+    var previous_bsdf_pdf_0 : f32 = 0.0f;
+    for(;;)
+    {
+        if(i_5 < max_bounces_0)
+        {
+        }
+        else
+        {
+            break;
+        }
+        var _S128 : bool = hit_scene_0(current_ray_0, uniforms.max_ray_distance_0);
+        if(_S128 == false)
+        {
+            accumulated_radiance_0 = accumulated_radiance_0 + lighting_contribution_0(background_radiance_0(current_ray_0.direction_0) * throughput_0, i_5);
+            break;
+        }
+        throughput_0 = throughput_0 * glass_transmittance_0(hitRec, hitMaterial);
+        hitRec.global_0.normal_1 = perturbed_normal_0(hitRec.local_0, hitRec.object_uid_6, hitRec.global_0.normal_1, hitMaterial, current_ray_0.direction_0, hitRec.t_2, incident_1.differentials_0);
+        var albedo_color_0 : vec3<f32> = fetch_albedo_0(hitRec.local_0, hitRec.object_uid_6, current_ray_0.direction_0, hitRec.t_2, hitMaterial, incident_1.differentials_0);
+        var _S129 : vec3<f32> = hitMaterial.emission_0.xyz;
+        var emission_color_0 : vec3<f32>;
+        if(!hitRec.front_face_0)
+        {
+            emission_color_0 = _S126;
+        }
+        else
+        {
+            emission_color_0 = _S129;
+        }
+        if(next_event_estimation_1)
+        {
+            emission_color_0 = emission_color_0 * vec3<f32>(emission_mis_weight_0(previous_bsdf_pdf_0, current_ray_0, hitRec.t_2));
+        }
+        if(i32(4) == (hitMaterial.material_class_0))
+        {
+            accumulated_radiance_0 = accumulated_radiance_0 + lighting_contribution_0(evaluate_surface_shader_0(hitRec, hitMaterial, albedo_color_0) * throughput_0, i_5);
+            break;
+        }
+        var scatterred_surface_0 : Ray_0 = material_scatter_0(current_ray_0);
+        if(scatterRec.skip_pdf_0)
+        {
+            var accumulated_radiance_1 : vec3<f32> = accumulated_radiance_0 + lighting_contribution_0(emission_color_0 * throughput_0, i_5);
+            var throughput_1 : vec3<f32> = throughput_0 * mix(albedo_color_0, hitMaterial.specular_0, vec3<f32>(doSpecular));
+            current_ray_0 = scatterRec.skip_pdf_ray_0;
+            current_ray_0.origin_2 = current_ray_0.origin_2 + current_ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
+            throughput_0 = throughput_1;
+            accumulated_radiance_0 = accumulated_radiance_1;
+            previous_bsdf_pdf_0 = 0.0f;
+            i_5 = i_5 + i32(1);
+            continue;
+        }
+        var accumulated_radiance_2 : vec3<f32>;
+        var throughput_2 : vec3<f32>;
+        if(next_event_estimation_1)
         {
-            return emission_color_0 * throughput_0;
+            previous_bsdf_pdf_0 = onb_lambertian_scattering_pdf_0(scatterred_surface_0);
+            var _S247 : vec3<f32> = accumulated_radiance_0 + lighting_contribution_0(emission_color_0 * throughput_0, i_5);
+            var _S248 : vec3<f32> = sample_direct_light_0(hitRec.global_0.position_2, hitRec.global_0.normal_1, albedo_color_0);
+            accumulated_radiance_2 = _S247 + lighting_contribution_0(_S248 * throughput_0, i_5 + i32(1));
+            throughput_2 = throughput_0 * albedo_color_0;
+            current_ray_0 = scatterred_surface_0;
+            current_ray_0.origin_2 = current_ray_0.origin_2 + current_ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
+        }
+        else
+        {
+            var _S130 : f32 = rand_0_1_0();
+            var scattered_2 : Ray_0;
+            if(_S130 > 0.20000000298023224f)
+            {
+                scattered_2 = scatterred_surface_0;
+            }
+            else
+            {
+                var _S131 : Ray_0 = get_random_on_quad_0(lights, hitRec.global_0.position_2);
+                scattered_2 = _S131;
+            }
+            var lambertian_pdf_0 : f32 = onb_lambertian_scattering_pdf_0(scattered_2);
+            var pdf_1 : f32 = 0.20000000298023224f * light_pdf_0(scattered_2, lights) + 0.80000001192092896f * lambertian_pdf_0;
+            if(pdf_1 <= 0.00000999999974738f)
+            {
+                sampleLighting = no_lighting_0();
+                return lighting_contribution_0(emission_color_0 * throughput_0, i_5);
+            }
+            accumulated_radiance_2 = accumulated_radiance_0 + lighting_contribution_0(emission_color_0 * throughput_0, i_5);
+            throughput_2 = throughput_0 * (vec3<f32>(lambertian_pdf_0) * mix(albedo_color_0, hitMaterial.specular_0, vec3<f32>(doSpecular)) / vec3<f32>(pdf_1));
+            current_ray_0 = scattered_2;
+            current_ray_0.origin_2 = current_ray_0.origin_2 + current_ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
         }
-        var accumulated_radiance_2 : vec3<f32> = accumulated_radiance_0 + emission_color_0 * throughput_0;
-        var throughput_2 : vec3<f32> = throughput_0 * (vec3<f32>(lambertian_pdf_0) * mix(albedo_color_0, hitMaterial.specular_0, vec3<f32>(doSpecular)) / vec3<f32>(pdf_1));
-        current_ray_0 = scattered_2;
-        current_ray_0.origin_2 = current_ray_0.origin_2 + current_ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
         var throughput_3 : vec3<f32>;
         if(i_5 > i32(2))
         {
@@ -1531,6 +2923,7 @@ fn path_trace_monte_carlo_0( camera_4 : Camera_0,  pixel_5 : Pixel_0) -> vec3<f3
 {
     var samples_count_0 : u32 = uniforms.pixel_side_subdivision_0 * uniforms.pixel_side_subdivision_0;
     var _S133 : vec3<f32> = vec3<f32>(0.0f);
+    pixelLighting = no_lighting_0();
     var i_6 : u32 = u32(0);
     var result_color_0 : vec3<f32> = _S133;
     for(;;)
@@ -1544,14 +2937,22 @@ fn path_trace_monte_carlo_0( camera_4 : Camera_0,  pixel_5 : Pixel_0) -> vec3<f3
         }
         var sub_pixel_x_3 : f32 = rand_0_1_0();
         var sub_pixel_y_3 : f32 = rand_0_1_0();
-        var _S134 : vec3<f32> = ray_color_monte_carlo_0(ray_and_differentials_0(camera_4, pixel_5, sub_pixel_x_3, sub_pixel_y_3));
+        var _S134 : vec3<f32> = ray_color_monte_carlo_0(ray_and_differentials_0(camera_4, pixel_5, sub_pixel_x_3, sub_pixel_y_3), i32(uniforms.max_ray_bounces_0));
         var result_color_1 : vec3<f32> = result_color_0 + _S134;
+        add_sample_lighting_0();
         i_6 = i_6 + u32(1);
         result_color_0 = result_color_1;
     }
-    return result_color_0 / vec3<f32>(f32(samples_count_0));
+    var _S915 : vec3<f32> = vec3<f32>(f32(samples_count_0));
+    pixelLighting.direct_0 = pixelLighting.direct_0 / _S915;
+    pixelLighting.indirect_1 = pixelLighting.indirect_1 / _S915;
+    pixelLighting.emission_2 = pixelLighting.emission_2 / _S915;
+    return result_color_0 / _S915;
 }
 
+@binding(19) @group(1) var<storage, read_write> lighting_aovs_buffer : array<vec4<f32>>;
+
+
 @compute
 @workgroup_size(8, 8, 1)
 fn compute_color_buffer_monte_carlo(@builtin(global_invocation_id) global_invocation_id_2 : vec3<u32>)
@@ -1564,107 +2965,630 @@ fn compute_color_buffer_monte_carlo(@builtin(global_invocation_id) global_invoca
     }
     var camera_5 : Camera_0 = setup_camera_0();
     var pixel_6 : Pixel_0 = make_common_color_evaluation_setup_0(pixel_index_4);
-    randState = pixel_index_4 + u32(uniforms.frame_number_0) * u32(719393);
+    seed_object_opacity_0(pixel_index_4);
+    seed_pixel_random_0(pixel_index_4, vec2<u32>(pixel_6.coordinates_0));
     var traced_color_0 : vec3<f32> = path_trace_monte_carlo_0(camera_5, pixel_6);
     pixel_color_buffer[pixel_index_4] = vec4<f32>(pixel_color_buffer[pixel_index_4].xyz + traced_color_0, 1.0f);
+    if(u32(0) != (uniforms.lighting_aovs_enabled_0))
+    {
+        var lighting_base_0 : u32 = pixel_index_4 * u32(3);
+        lighting_aovs_buffer[lighting_base_0] = lighting_aovs_buffer[lighting_base_0] + vec4<f32>(pixelLighting.direct_0, 1.0f);
+        var _S916 : u32 = lighting_base_0 + u32(1);
+        lighting_aovs_buffer[_S916] = lighting_aovs_buffer[_S916] + vec4<f32>(pixelLighting.indirect_1, 1.0f);
+        var _S917 : u32 = lighting_base_0 + u32(2);
+        lighting_aovs_buffer[_S917] = lighting_aovs_buffer[_S917] + vec4<f32>(pixelLighting.emission_2, 1.0f);
+    }
     return;
 }
 
-fn evaluate_hard_shadow_0( position_3 : vec3<f32>,  to_light_0 : vec3<f32>,  min_ray_offset_0 : f32,  max_ray_offset_0 : f32) -> f32
+@binding(9) @group(1) var<storage, read_write> debug_path_buffer : array<vec4<f32>>;
+
+fn record_debug_path_segment_0( segment_0 : u32,  incident_direction_0 : vec3<f32>,  event_0 : u32,  throughput_5 : vec3<f32>,  march_steps_0 : u32)
 {
-    var _S135 : bool = hit_scene_0(Ray_x24init_0(position_3 + to_light_0 * vec3<f32>(min_ray_offset_0), to_light_0), max_ray_offset_0);
-    if(_S135)
+    if(segment_0 >= u32(16))
     {
-        if((any(((hitMaterial.emission_0.xyz) > vec3<f32>(0.0f, 0.0f, 0.0f)))))
-        {
-            return 1.0f;
-        }
-        return 0.0f;
+        return;
     }
-    return 1.0f;
-}
-
-fn inside_aabb_0( box_min_1 : vec3<f32>,  box_max_1 : vec3<f32>,  probe_0 : vec3<f32>) -> bool
-{
-    var _S136 : bool;
-    if((all((probe_0 >= box_min_1))))
+    var base_1 : u32 = u32(2) + segment_0 * u32(4);
+    var face_0 : u32;
+    if(hitRec.front_face_0)
     {
-        _S136 = (all((probe_0 <= box_max_1)));
+        face_0 = u32(0);
     }
     else
     {
-        _S136 = false;
+        face_0 = u32(256);
     }
-    return _S136;
-}
-
-fn sample_signed_distance_function_0( sdf_4 : Sdf_0,  position_4 : vec3<f32>,  direction_4 : vec3<f32>,  time_4 : f32) -> f32
-{
-    var sdf_inverse_location_1 : mat3x4<f32> = mat3x4<f32>(sdf_4.inverse_location_col_0_0, sdf_4.inverse_location_col_1_0, sdf_4.inverse_location_col_2_0);
-    var local_position_1 : vec3<f32> = transform_point_0(sdf_inverse_location_1, position_4);
-    var local_direction_0 : vec3<f32> = normalize(transform_vector_0(to_mat3x3_0(sdf_inverse_location_1), direction_4));
-    var local_distance_0 : f32 = sample_sdf_0(sdf_4, local_position_1, time_4);
-    var global_offset_0 : vec3<f32> = transform_point_0(mat3x4<f32>(sdf_4.location_col_0_0, sdf_4.location_col_1_0, sdf_4.location_col_2_0), local_position_1 + local_direction_0 * vec3<f32>(local_distance_0)) - position_4;
-    return length(global_offset_0) * f32((i32(sign((dot(global_offset_0, direction_4))))));
+    debug_path_buffer[base_1] = vec4<f32>(hitRec.global_0.position_2, hitRec.t_2);
+    debug_path_buffer[base_1 + u32(1)] = vec4<f32>(hitRec.global_0.normal_1, (bitcast<f32>((hitRec.material_id_3))));
+    debug_path_buffer[base_1 + u32(2)] = vec4<f32>(incident_direction_0, (bitcast<f32>((event_0 | face_0))));
+    debug_path_buffer[base_1 + u32(3)] = vec4<f32>(throughput_5, (bitcast<f32>((march_steps_0))));
+    return;
 }
 
-fn sample_signed_distance_0( position_5 : vec3<f32>,  direction_5 : vec3<f32>) -> f32
+fn trace_debug_path_0( incident_4 : RayAndDifferentials_0)
 {
-    var _S137 : i32 = i32(uniforms.bvh_length_0);
-    var record_0 : f32 = 1.0e+09f;
-    var node_index_2 : i32 = i32(0);
+    var current_ray_2 : Ray_0 = incident_4.ray_0;
+    var _S232 : vec3<f32> = vec3<f32>(0.0f);
+    var i_13 : i32 = i32(0);
+    var throughput_6 : vec3<f32> = vec3<f32>(1.0f);
+    var accumulated_radiance_4 : vec3<f32> = _S232;
+    var termination_0 : u32 = u32(7);
+    var segments_count_1 : u32 = u32(0);
+    var path_march_steps_0 : u32 = u32(0);
+    var next_event_estimation_2 : bool = next_event_estimation_enabled_0();
+    var previous_bsdf_pdf_1 : f32 = 0.0f;
     for(;;)
     {
-        var _S138 : bool;
-        if(node_index_2 < _S137)
+        if(i_13 < (i32(uniforms.max_ray_bounces_0)))
         {
-            _S138 = i32(-1) != node_index_2;
         }
         else
         {
-            _S138 = false;
+            break;
         }
-        if(_S138)
+        sdf_march_steps = u32(0);
+        var _S233 : bool = hit_scene_0(current_ray_2, uniforms.max_ray_distance_0);
+        var march_steps_1 : u32 = sdf_march_steps;
+        path_march_steps_0 = path_march_steps_0 + march_steps_1;
+        segments_count_1 = segments_count_1 + u32(1);
+        var _S234 : u32 = segments_count_1 - u32(1);
+        if(_S233 == false)
+        {
+            accumulated_radiance_4 = accumulated_radiance_4 + background_radiance_0(current_ray_2.direction_0) * throughput_6;
+            record_debug_path_segment_0(_S234, current_ray_2.direction_0, u32(0), throughput_6, march_steps_1);
+            termination_0 = u32(0);
+            break;
+        }
+        throughput_6 = throughput_6 * glass_transmittance_0(hitRec, hitMaterial);
+        hitRec.global_0.normal_1 = perturbed_normal_0(hitRec.local_0, hitRec.object_uid_6, hitRec.global_0.normal_1, hitMaterial, current_ray_2.direction_0, hitRec.t_2, incident_4.differentials_0);
+        var albedo_color_1 : vec3<f32> = fetch_albedo_0(hitRec.local_0, hitRec.object_uid_6, current_ray_2.direction_0, hitRec.t_2, hitMaterial, incident_4.differentials_0);
+        var emission_color_1 : vec3<f32>;
+        if(!hitRec.front_face_0)
         {
+            emission_color_1 = _S232;
         }
         else
         {
+            emission_color_1 = hitMaterial.emission_0.xyz;
+        }
+        if(next_event_estimation_2)
+        {
+            emission_color_1 = emission_color_1 * vec3<f32>(emission_mis_weight_0(previous_bsdf_pdf_1, current_ray_2, hitRec.t_2));
+        }
+        if(i32(4) == (hitMaterial.material_class_0))
+        {
+            accumulated_radiance_4 = accumulated_radiance_4 + evaluate_surface_shader_0(hitRec, hitMaterial, albedo_color_1) * throughput_6;
+            record_debug_path_segment_0(_S234, current_ray_2.direction_0, u32(1), throughput_6, march_steps_1);
+            termination_0 = u32(1);
             break;
         }
-        var record_1 : f32;
-        if(inside_aabb_0(bvh_inflated[node_index_2].aabb_min_0, bvh_inflated[node_index_2].aabb_max_0, position_5))
+        var scatterred_surface_1 : Ray_0 = material_scatter_0(current_ray_2);
+        if(scatterRec.skip_pdf_0)
+        {
+            accumulated_radiance_4 = accumulated_radiance_4 + emission_color_1 * throughput_6;
+            throughput_6 = throughput_6 * mix(albedo_color_1, hitMaterial.specular_0, vec3<f32>(doSpecular));
+            record_debug_path_segment_0(_S234, current_ray_2.direction_0, u32(2), throughput_6, march_steps_1);
+            previous_bsdf_pdf_1 = 0.0f;
+            current_ray_2 = scatterRec.skip_pdf_ray_0;
+            current_ray_2.origin_2 = current_ray_2.origin_2 + current_ray_2.direction_0 * vec3<f32>(0.00050000002374873f);
+            i_13 = i_13 + i32(1);
+            continue;
+        }
+        if(next_event_estimation_2)
+        {
+            previous_bsdf_pdf_1 = onb_lambertian_scattering_pdf_0(scatterred_surface_1);
+            accumulated_radiance_4 = accumulated_radiance_4 + emission_color_1 * throughput_6;
+            var surface_throughput_0 : vec3<f32> = throughput_6;
+            throughput_6 = throughput_6 * albedo_color_1;
+            record_debug_path_segment_0(_S234, current_ray_2.direction_0, u32(3), throughput_6, march_steps_1);
+            var _S249 : vec3<f32> = sample_direct_light_0(hitRec.global_0.position_2, hitRec.global_0.normal_1, albedo_color_1);
+            accumulated_radiance_4 = accumulated_radiance_4 + _S249 * surface_throughput_0;
+            current_ray_2 = scatterred_surface_1;
+            current_ray_2.origin_2 = current_ray_2.origin_2 + current_ray_2.direction_0 * vec3<f32>(0.00050000002374873f);
+        }
+        else
         {
-            if(u32(1) == (bvh_inflated[node_index_2].primitive_type_0))
+            var _S235 : f32 = rand_0_1_0();
+            var scattered_3 : Ray_0;
+            var event_1 : u32;
+            if(_S235 > 0.20000000298023224f)
             {
-                var _S139 : Sdf_0 = Sdf_0( sdf[bvh_inflated[node_index_2].primitive_index_0].location_col_0_0, sdf[bvh_inflated[node_index_2].primitive_index_0].location_col_1_0, sdf[bvh_inflated[node_index_2].primitive_index_0].location_col_2_0, sdf[bvh_inflated[node_index_2].primitive_index_0].inverse_location_col_0_0, sdf[bvh_inflated[node_index_2].primitive_index_0].inverse_location_col_1_0, sdf[bvh_inflated[node_index_2].primitive_index_0].inverse_location_col_2_0, sdf[bvh_inflated[node_index_2].primitive_index_0].ray_marching_step_scale_0, sdf[bvh_inflated[node_index_2].primitive_index_0].class_index_0, sdf[bvh_inflated[node_index_2].primitive_index_0].material_id_2, sdf[bvh_inflated[node_index_2].primitive_index_0].object_uid_2 );
-                var candidate_distance_0 : f32 = sample_signed_distance_function_0(_S139, position_5, direction_5, sdf_time[bvh_inflated[node_index_2].primitive_index_0]);
-                if(candidate_distance_0 < record_0)
-                {
-                    record_1 = candidate_distance_0;
-                }
-                else
-                {
-                    record_1 = record_0;
-                }
+                scattered_3 = scatterred_surface_1;
+                event_1 = u32(3);
             }
             else
             {
-                record_1 = record_0;
+                var _S236 : Ray_0 = get_random_on_quad_0(lights, hitRec.global_0.position_2);
+                scattered_3 = _S236;
+                event_1 = u32(4);
             }
-            node_index_2 = node_index_2 + i32(1);
+            var lambertian_pdf_1 : f32 = onb_lambertian_scattering_pdf_0(scattered_3);
+            var pdf_2 : f32 = 0.20000000298023224f * light_pdf_0(scattered_3, lights) + 0.80000001192092896f * lambertian_pdf_1;
+            if(pdf_2 <= 0.00000999999974738f)
+            {
+                accumulated_radiance_4 = emission_color_1 * throughput_6;
+                record_debug_path_segment_0(_S234, current_ray_2.direction_0, u32(5), throughput_6, march_steps_1);
+                termination_0 = u32(5);
+                break;
+            }
+            accumulated_radiance_4 = accumulated_radiance_4 + emission_color_1 * throughput_6;
+            throughput_6 = throughput_6 * (vec3<f32>(lambertian_pdf_1) * mix(albedo_color_1, hitMaterial.specular_0, vec3<f32>(doSpecular)) / vec3<f32>(pdf_2));
+            record_debug_path_segment_0(_S234, current_ray_2.direction_0, event_1, throughput_6, march_steps_1);
+            current_ray_2 = scattered_3;
+            current_ray_2.origin_2 = current_ray_2.origin_2 + current_ray_2.direction_0 * vec3<f32>(0.00050000002374873f);
         }
-        else
+        if(i_13 > i32(2))
         {
-            var _S140 : i32 = bvh_inflated[node_index_2].hit_miss_skip_link_0;
-            record_1 = record_0;
-            node_index_2 = _S140;
+            var p_2 : f32 = max(throughput_6.x, max(throughput_6.y, throughput_6.z));
+            var _S237 : f32 = rand_0_1_0();
+            if(_S237 > p_2)
+            {
+                termination_0 = u32(6);
+                break;
+            }
+            throughput_6 = throughput_6 * vec3<f32>((1.0f / p_2));
         }
-        record_0 = record_1;
+        i_13 = i_13 + i32(1);
     }
-    return record_0;
+    debug_path_buffer[i32(0)] = vec4<f32>(debug_path_buffer[i32(0)].xy, (bitcast<f32>((segments_count_1))), (bitcast<f32>((termination_0))));
+    debug_path_buffer[i32(1)] = vec4<f32>(accumulated_radiance_4, (bitcast<f32>((path_march_steps_0))));
+    return;
 }
 
-fn approximate_ambient_occlusion_0( posision_0 : vec3<f32>,  normal_4 : vec3<f32>) -> f32
+@compute
+@workgroup_size(1, 1, 1)
+fn compute_debug_path()
+{
+    randState = u32(0);
+    var _S238 : vec2<u32> = (bitcast<vec2<u32>>((debug_path_buffer[i32(0)].xy)));
+    var pixel_index_10 : u32 = _S238.y * uniforms.frame_buffer_size_0.x + _S238.x;
+    var camera_11 : Camera_0 = setup_camera_0();
+    var pixel_12 : Pixel_0 = make_common_color_evaluation_setup_0(pixel_index_10);
+    seed_pixel_random_0(pixel_index_10, _S238);
+    var sub_pixel_x_4 : f32 = rand_0_1_0();
+    var sub_pixel_y_4 : f32 = rand_0_1_0();
+    trace_debug_path_0(ray_and_differentials_0(camera_11, pixel_12, sub_pixel_x_4, sub_pixel_y_4));
+    return;
+}
+
+@binding(10) @group(1) var<storage, read_write> wavefront_paths : array<vec4<f32>>;
+
+@binding(12) @group(1) var<storage, read_write> wavefront_queues : array<atomic<u32>>;
+
+@binding(11) @group(1) var<storage, read_write> wavefront_hits : array<vec4<f32>>;
+
+@binding(13) @group(1) var<storage, read_write> wavefront_dispatch : array<u32>;
+
+struct WavefrontPath_0
+{
+     ray_0 : Ray_0,
+     bounce_0 : u32,
+     throughput_0 : vec3<f32>,
+     previous_bsdf_pdf_0 : f32,
+     radiance_0 : vec3<f32>,
+};
+
+fn load_wavefront_path_0( pixel_index_11 : u32) -> WavefrontPath_0
+{
+    var base_2 : u32 = pixel_index_11 * u32(4);
+    var origin_and_random_0 : vec4<f32> = wavefront_paths[base_2];
+    var direction_and_bounce_0 : vec4<f32> = wavefront_paths[base_2 + u32(1)];
+    var throughput_and_pdf_0 : vec4<f32> = wavefront_paths[base_2 + u32(2)];
+    var radiance_and_rotation_0 : vec4<f32> = wavefront_paths[base_2 + u32(3)];
+    randState = (bitcast<u32>((origin_and_random_0.w)));
+    randRotation = radiance_and_rotation_0.w;
+    var result_11 : WavefrontPath_0;
+    result_11.ray_0.origin_2 = origin_and_random_0.xyz;
+    result_11.ray_0.direction_0 = direction_and_bounce_0.xyz;
+    result_11.bounce_0 = (bitcast<u32>((direction_and_bounce_0.w)));
+    result_11.throughput_0 = throughput_and_pdf_0.xyz;
+    result_11.previous_bsdf_pdf_0 = throughput_and_pdf_0.w;
+    result_11.radiance_0 = radiance_and_rotation_0.xyz;
+    return result_11;
+}
+
+fn store_wavefront_path_0( pixel_index_12 : u32,  path_0 : WavefrontPath_0)
+{
+    var base_3 : u32 = pixel_index_12 * u32(4);
+    wavefront_paths[base_3] = vec4<f32>(path_0.ray_0.origin_2, (bitcast<f32>((randState))));
+    wavefront_paths[base_3 + u32(1)] = vec4<f32>(path_0.ray_0.direction_0, (bitcast<f32>((path_0.bounce_0))));
+    wavefront_paths[base_3 + u32(2)] = vec4<f32>(path_0.throughput_0, path_0.previous_bsdf_pdf_0);
+    wavefront_paths[base_3 + u32(3)] = vec4<f32>(path_0.radiance_0, randRotation);
+    return;
+}
+
+fn enqueue_wavefront_path_0( queue_length_slot_0 : u32,  queue_start_0 : u32,  pixel_index_13 : u32)
+{
+    var position_9 : u32 = atomicAdd(&(wavefront_queues[queue_length_slot_0]), u32(1));
+    atomicStore(&(wavefront_queues[queue_start_0 + position_9]), pixel_index_13);
+    return;
+}
+
+fn wavefront_hit_queue_start_0() -> u32
+{
+    return u32(2) + uniforms.frame_buffer_area_0;
+}
+
+fn prepare_wavefront_dispatch_0( items_count_0 : u32)
+{
+    wavefront_dispatch[i32(0)] = (min(items_count_0, uniforms.frame_buffer_area_0) + u32(64) - u32(1)) / u32(64);
+    wavefront_dispatch[i32(1)] = u32(1);
+    wavefront_dispatch[i32(2)] = u32(1);
+    return;
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_wavefront_generate(@builtin(global_invocation_id) global_invocation_id_10 : vec3<u32>)
+{
+    randState = u32(0);
+    var pixel_index_14 : u32 = evaluate_pixel_index_0(global_invocation_id_10, uniforms.thread_grid_size_0);
+    if(pixel_outside_frame_buffer_0(pixel_index_14))
+    {
+        return;
+    }
+    var camera_12 : Camera_0 = setup_camera_0();
+    var pixel_13 : Pixel_0 = setup_pixel_coordinates_0(pixel_index_14);
+    seed_pixel_random_0(pixel_index_14, vec2<u32>(pixel_13.coordinates_0));
+    var sub_pixel_x_5 : f32 = rand_0_1_0();
+    var sub_pixel_y_5 : f32 = rand_0_1_0();
+    var path_1 : WavefrontPath_0;
+    path_1.ray_0 = ray_to_pixel_0(camera_12, pixel_13, sub_pixel_x_5, sub_pixel_y_5);
+    path_1.bounce_0 = u32(0);
+    path_1.throughput_0 = vec3<f32>(1.0f);
+    path_1.previous_bsdf_pdf_0 = 0.0f;
+    path_1.radiance_0 = vec3<f32>(0.0f);
+    store_wavefront_path_0(pixel_index_14, path_1);
+    enqueue_wavefront_path_0(u32(0), u32(2), pixel_index_14);
+    return;
+}
+
+@compute
+@workgroup_size(1, 1, 1)
+fn compute_wavefront_prepare_intersection()
+{
+    randState = u32(0);
+    prepare_wavefront_dispatch_0(atomicLoad(&(wavefront_queues[i32(0)])));
+    atomicStore(&(wavefront_queues[i32(1)]), u32(0));
+    return;
+}
+
+@compute
+@workgroup_size(64, 1, 1)
+fn compute_wavefront_intersect(@builtin(global_invocation_id) global_invocation_id_11 : vec3<u32>)
+{
+    randState = u32(0);
+    var item_0 : u32 = global_invocation_id_11.x;
+    if(item_0 >= (min(atomicLoad(&(wavefront_queues[i32(0)])), uniforms.frame_buffer_area_0)))
+    {
+        return;
+    }
+    var pixel_index_15 : u32 = atomicLoad(&(wavefront_queues[u32(2) + item_0]));
+    seed_object_opacity_0(pixel_index_15);
+    var path_base_0 : u32 = pixel_index_15 * u32(4);
+    var ray_13 : Ray_0;
+    ray_13.origin_2 = wavefront_paths[path_base_0].xyz;
+    ray_13.direction_0 = wavefront_paths[path_base_0 + u32(1)].xyz;
+    var hit_base_0 : u32 = pixel_index_15 * u32(4);
+    var _S252 : bool = hit_scene_0(ray_13, uniforms.max_ray_distance_0);
+    if(_S252)
+    {
+        var _S253 : u32;
+        if(hitRec.front_face_0)
+        {
+            _S253 = u32(1);
+        }
+        else
+        {
+            _S253 = u32(0);
+        }
+        var flags_0 : u32 = _S253 | ((u32(hitParallelogram + i32(1)) << (u32(1))));
+        wavefront_hits[hit_base_0] = vec4<f32>(hitRec.global_0.position_2, hitRec.t_2);
+        wavefront_hits[hit_base_0 + u32(1)] = vec4<f32>(hitRec.global_0.normal_1, (bitcast<f32>((hitRec.material_id_3))));
+        wavefront_hits[hit_base_0 + u32(2)] = vec4<f32>(hitRec.local_0.position_2, (bitcast<f32>((flags_0))));
+        wavefront_hits[hit_base_0 + u32(3)] = vec4<f32>(hitRec.local_0.normal_1, (bitcast<f32>((hitRec.object_uid_6))));
+    }
+    else
+    {
+        wavefront_hits[hit_base_0] = vec4<f32>(0.0f, 0.0f, 0.0f, -1.0f);
+    }
+    enqueue_wavefront_path_0(u32(1), wavefront_hit_queue_start_0(), pixel_index_15);
+    return;
+}
+
+@compute
+@workgroup_size(1, 1, 1)
+fn compute_wavefront_prepare_shading()
+{
+    randState = u32(0);
+    prepare_wavefront_dispatch_0(atomicLoad(&(wavefront_queues[i32(1)])));
+    atomicStore(&(wavefront_queues[i32(0)]), u32(0));
+    return;
+}
+
+fn wavefront_scatter_0( path_2 : ptr<function, WavefrontPath_0>,  differentials_5 : RayDifferentials_0) -> bool
+{
+    var next_event_estimation_3 : bool = next_event_estimation_enabled_0();
+    (*path_2).throughput_0 = (*path_2).throughput_0 * glass_transmittance_0(hitRec, hitMaterial);
+    hitRec.global_0.normal_1 = perturbed_normal_0(hitRec.local_0, hitRec.object_uid_6, hitRec.global_0.normal_1, hitMaterial, (*path_2).ray_0.direction_0, hitRec.t_2, differentials_5);
+    var albedo_color_2 : vec3<f32> = fetch_albedo_0(hitRec.local_0, hitRec.object_uid_6, (*path_2).ray_0.direction_0, hitRec.t_2, hitMaterial, differentials_5);
+    var _S254 : vec3<f32> = vec3<f32>(0.0f);
+    var emission_color_2 : vec3<f32>;
+    if(!hitRec.front_face_0)
+    {
+        emission_color_2 = _S254;
+    }
+    else
+    {
+        emission_color_2 = hitMaterial.emission_0.xyz;
+    }
+    if(next_event_estimation_3)
+    {
+        emission_color_2 = emission_color_2 * vec3<f32>(emission_mis_weight_0((*path_2).previous_bsdf_pdf_0, (*path_2).ray_0, hitRec.t_2));
+    }
+    if(i32(4) == (hitMaterial.material_class_0))
+    {
+        (*path_2).radiance_0 = (*path_2).radiance_0 + evaluate_surface_shader_0(hitRec, hitMaterial, albedo_color_2) * (*path_2).throughput_0;
+        return false;
+    }
+    var scatterred_surface_2 : Ray_0 = material_scatter_0((*path_2).ray_0);
+    if(scatterRec.skip_pdf_0)
+    {
+        (*path_2).radiance_0 = (*path_2).radiance_0 + emission_color_2 * (*path_2).throughput_0;
+        (*path_2).throughput_0 = (*path_2).throughput_0 * mix(albedo_color_2, hitMaterial.specular_0, vec3<f32>(doSpecular));
+        (*path_2).previous_bsdf_pdf_0 = 0.0f;
+        (*path_2).ray_0 = scatterRec.skip_pdf_ray_0;
+        (*path_2).ray_0.origin_2 = (*path_2).ray_0.origin_2 + (*path_2).ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
+        return true;
+    }
+    if(next_event_estimation_3)
+    {
+        (*path_2).previous_bsdf_pdf_0 = onb_lambertian_scattering_pdf_0(scatterred_surface_2);
+        (*path_2).radiance_0 = (*path_2).radiance_0 + emission_color_2 * (*path_2).throughput_0;
+        var _S255 : vec3<f32> = sample_direct_light_0(hitRec.global_0.position_2, hitRec.global_0.normal_1, albedo_color_2);
+        (*path_2).radiance_0 = (*path_2).radiance_0 + _S255 * (*path_2).throughput_0;
+        (*path_2).throughput_0 = (*path_2).throughput_0 * albedo_color_2;
+        (*path_2).ray_0 = scatterred_surface_2;
+        (*path_2).ray_0.origin_2 = (*path_2).ray_0.origin_2 + (*path_2).ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
+    }
+    else
+    {
+        var _S256 : f32 = rand_0_1_0();
+        var scattered_4 : Ray_0;
+        if(_S256 > 0.20000000298023224f)
+        {
+            scattered_4 = scatterred_surface_2;
+        }
+        else
+        {
+            scattered_4 = get_random_on_quad_0(lights, hitRec.global_0.position_2);
+        }
+        var lambertian_pdf_2 : f32 = onb_lambertian_scattering_pdf_0(scattered_4);
+        var pdf_3 : f32 = 0.20000000298023224f * light_pdf_0(scattered_4, lights) + 0.80000001192092896f * lambertian_pdf_2;
+        if(pdf_3 <= 0.00000999999974738f)
+        {
+            (*path_2).radiance_0 = emission_color_2 * (*path_2).throughput_0;
+            return false;
+        }
+        (*path_2).radiance_0 = (*path_2).radiance_0 + emission_color_2 * (*path_2).throughput_0;
+        (*path_2).throughput_0 = (*path_2).throughput_0 * (vec3<f32>(lambertian_pdf_2) * mix(albedo_color_2, hitMaterial.specular_0, vec3<f32>(doSpecular)) / vec3<f32>(pdf_3));
+        (*path_2).ray_0 = scattered_4;
+        (*path_2).ray_0.origin_2 = (*path_2).ray_0.origin_2 + (*path_2).ray_0.direction_0 * vec3<f32>(0.00050000002374873f);
+    }
+    if(((*path_2).bounce_0) > u32(2))
+    {
+        var p_3 : f32 = max((*path_2).throughput_0.x, max((*path_2).throughput_0.y, (*path_2).throughput_0.z));
+        var _S257 : f32 = rand_0_1_0();
+        if(_S257 > p_3)
+        {
+            return false;
+        }
+        (*path_2).throughput_0 = (*path_2).throughput_0 * vec3<f32>((1.0f / p_3));
+    }
+    return true;
+}
+
+@compute
+@workgroup_size(64, 1, 1)
+fn compute_wavefront_shade(@builtin(global_invocation_id) global_invocation_id_12 : vec3<u32>)
+{
+    randState = u32(0);
+    var item_1 : u32 = global_invocation_id_12.x;
+    if(item_1 >= (min(atomicLoad(&(wavefront_queues[i32(1)])), uniforms.frame_buffer_area_0)))
+    {
+        return;
+    }
+    var pixel_index_16 : u32 = atomicLoad(&(wavefront_queues[wavefront_hit_queue_start_0() + item_1]));
+    seed_object_opacity_0(pixel_index_16);
+    var path_3 : WavefrontPath_0 = load_wavefront_path_0(pixel_index_16);
+    var hit_base_1 : u32 = pixel_index_16 * u32(4);
+    var position_and_parameter_0 : vec4<f32> = wavefront_hits[hit_base_1];
+    if((position_and_parameter_0.w) < 0.0f)
+    {
+        path_3.radiance_0 = path_3.radiance_0 + background_radiance_0(path_3.ray_0.direction_0) * path_3.throughput_0;
+        store_wavefront_path_0(pixel_index_16, path_3);
+        return;
+    }
+    var normal_and_material_0 : vec4<f32> = wavefront_hits[hit_base_1 + u32(1)];
+    var local_position_and_flags_0 : vec4<f32> = wavefront_hits[hit_base_1 + u32(2)];
+    var flags_1 : u32 = (bitcast<u32>((local_position_and_flags_0.w)));
+    hitRec.global_0.position_2 = position_and_parameter_0.xyz;
+    hitRec.t_2 = position_and_parameter_0.w;
+    hitRec.global_0.normal_1 = normal_and_material_0.xyz;
+    hitRec.material_id_3 = (bitcast<u32>((normal_and_material_0.w)));
+    hitRec.local_0.position_2 = local_position_and_flags_0.xyz;
+    var local_normal_and_object_0 : vec4<f32> = wavefront_hits[hit_base_1 + u32(3)];
+    hitRec.local_0.normal_1 = local_normal_and_object_0.xyz;
+    hitRec.object_uid_6 = (bitcast<u32>((local_normal_and_object_0.w)));
+    hitRec.front_face_0 = u32(0) != (flags_1 & u32(1));
+    hitParallelogram = i32((flags_1 >> (u32(1)))) - i32(1);
+    hitMaterial.albedo_0 = materials[hitRec.material_id_3].albedo_0;
+    hitMaterial.surface_shader_uid_0 = materials[hitRec.material_id_3].surface_shader_uid_0;
+    hitMaterial.emission_0 = materials[hitRec.material_id_3].emission_0;
+    hitMaterial.normal_texture_uid_0 = materials[hitRec.material_id_3].normal_texture_uid_0;
+    hitMaterial.specular_0 = materials[hitRec.material_id_3].specular_0;
+    hitMaterial.specular_strength_0 = materials[hitRec.material_id_3].specular_strength_0;
+    hitMaterial.roughness_0 = materials[hitRec.material_id_3].roughness_0;
+    hitMaterial.refractive_index_eta_0 = materials[hitRec.material_id_3].refractive_index_eta_0;
+    hitMaterial.albedo_texture_uid_0 = materials[hitRec.material_id_3].albedo_texture_uid_0;
+    hitMaterial.material_class_0 = materials[hitRec.material_id_3].material_class_0;
+    hitMaterial.absorption_0 = materials[hitRec.material_id_3].absorption_0;
+    get_lights_0();
+    var _S258 : RayDifferentials_0 = ray_differentials_0(setup_camera_0(), setup_pixel_coordinates_0(pixel_index_16), 0.5f, 0.5f);
+    var alive_0 : bool = wavefront_scatter_0(&(path_3), _S258);
+    path_3.bounce_0 = path_3.bounce_0 + u32(1);
+    store_wavefront_path_0(pixel_index_16, path_3);
+    if(alive_0 && (path_3.bounce_0 < uniforms.max_ray_bounces_0))
+    {
+        enqueue_wavefront_path_0(u32(0), u32(2), pixel_index_16);
+    }
+    return;
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_wavefront_resolve(@builtin(global_invocation_id) global_invocation_id_13 : vec3<u32>)
+{
+    randState = u32(0);
+    var pixel_index_17 : u32 = evaluate_pixel_index_0(global_invocation_id_13, uniforms.thread_grid_size_0);
+    if(pixel_outside_frame_buffer_0(pixel_index_17))
+    {
+        return;
+    }
+    pixel_color_buffer[pixel_index_17] = vec4<f32>(pixel_color_buffer[pixel_index_17].xyz + wavefront_paths[pixel_index_17 * u32(4) + u32(3)].xyz, 1.0f);
+    return;
+}
+
+fn evaluate_hard_shadow_0( position_3 : vec3<f32>,  to_light_0 : vec3<f32>,  min_ray_offset_0 : f32,  max_ray_offset_0 : f32) -> f32
+{
+    var _S135 : bool = hit_scene_0(Ray_x24init_0(position_3 + to_light_0 * vec3<f32>(min_ray_offset_0), to_light_0), max_ray_offset_0);
+    if(_S135)
+    {
+        if((any(((hitMaterial.emission_0.xyz) > vec3<f32>(0.0f, 0.0f, 0.0f)))))
+        {
+            return 1.0f;
+        }
+        return 0.0f;
+    }
+    return 1.0f;
+}
+
+fn inside_aabb_0( box_min_1 : vec3<f32>,  box_max_1 : vec3<f32>,  probe_0 : vec3<f32>) -> bool
+{
+    var _S136 : bool;
+    if((all((probe_0 >= box_min_1))))
+    {
+        _S136 = (all((probe_0 <= box_max_1)));
+    }
+    else
+    {
+        _S136 = false;
+    }
+    return _S136;
+}
+
+fn sample_signed_distance_function_0( sdf_4 : Sdf_0,  position_4 : vec3<f32>,  direction_4 : vec3<f32>,  time_4 : f32) -> f32
+{
+    var sdf_inverse_location_1 : mat3x4<f32> = mat3x4<f32>(sdf_4.inverse_location_col_0_0, sdf_4.inverse_location_col_1_0, sdf_4.inverse_location_col_2_0);
+    var local_position_1 : vec3<f32> = transform_point_0(sdf_inverse_location_1, position_4);
+    var local_direction_0 : vec3<f32> = normalize(transform_vector_0(to_mat3x3_0(sdf_inverse_location_1), direction_4));
+    var local_distance_0 : f32 = sample_sdf_0(sdf_4, local_position_1, time_4);
+    var global_offset_0 : vec3<f32> = transform_point_0(mat3x4<f32>(sdf_4.location_col_0_0, sdf_4.location_col_1_0, sdf_4.location_col_2_0), local_position_1 + local_direction_0 * vec3<f32>(local_distance_0)) - position_4;
+    return length(global_offset_0) * f32((i32(sign((dot(global_offset_0, direction_4))))));
+}
+
+fn sample_signed_distance_0( position_5 : vec3<f32>,  direction_5 : vec3<f32>) -> f32
+{
+    var _S137 : i32 = i32(uniforms.bvh_length_0);
+    var record_0 : f32 = 1.0e+09f;
+    var node_index_2 : i32 = i32(0);
+    for(;;)
+    {
+        var _S138 : bool;
+        if(node_index_2 < _S137)
+        {
+            _S138 = i32(-1) != node_index_2;
+        }
+        else
+        {
+            _S138 = false;
+        }
+        if(_S138)
+        {
+        }
+        else
+        {
+            break;
+        }
+        var node_3 : BvhNode_0 = load_bvh_node_1(u32(node_index_2));
+        var record_1 : f32;
+        if(inside_aabb_0(node_3.aabb_min_0, node_3.aabb_max_0, position_5))
+        {
+            if(u32(1) == (node_3.primitive_type_0))
+            {
+                var _S139 : Sdf_0 = Sdf_0( sdf[node_3.primitive_index_0].location_col_0_0, sdf[node_3.primitive_index_0].location_col_1_0, sdf[node_3.primitive_index_0].location_col_2_0, sdf[node_3.primitive_index_0].inverse_location_col_0_0, sdf[node_3.primitive_index_0].inverse_location_col_1_0, sdf[node_3.primitive_index_0].inverse_location_col_2_0, sdf[node_3.primitive_index_0].ray_marching_step_scale_0, sdf[node_3.primitive_index_0].class_index_0, sdf[node_3.primitive_index_0].material_id_2, sdf[node_3.primitive_index_0].object_uid_2, sdf[node_3.primitive_index_0].parameters_0 );
+                var candidate_distance_0 : f32 = sample_signed_distance_function_0(_S139, position_5, direction_5, sdf_time[node_3.primitive_index_0]);
+                var _S270 : bool;
+                if(candidate_distance_0 < record_0)
+                {
+                    _S270 = object_traced_0(_S139.object_uid_2);
+                }
+                else
+                {
+                    _S270 = false;
+                }
+                if(_S270)
+                {
+                    record_1 = candidate_distance_0;
+                }
+                else
+                {
+                    record_1 = record_0;
+                }
+            }
+            else
+            {
+                if(u32(3) == (node_3.primitive_type_0))
+                {
+                    var candidate_distance_1 : f32 = length(position_5 - spheres[node_3.primitive_index_0].center_4) - spheres[node_3.primitive_index_0].radius_4;
+                    var _S271 : bool;
+                    if(candidate_distance_1 < record_0)
+                    {
+                        _S271 = object_traced_0(spheres[node_3.primitive_index_0].object_uid_4);
+                    }
+                    else
+                    {
+                        _S271 = false;
+                    }
+                    if(_S271)
+                    {
+                        record_1 = candidate_distance_1;
+                    }
+                    else
+                    {
+                        record_1 = record_0;
+                    }
+                }
+                else
+                {
+                    record_1 = record_0;
+                }
+            }
+            node_index_2 = node_index_2 + i32(1);
+        }
+        else
+        {
+            var _S140 : i32 = node_3.hit_miss_skip_link_0;
+            record_1 = record_0;
+            node_index_2 = _S140;
+        }
+        record_0 = record_1;
+    }
+    return record_0;
+}
+
+fn approximate_ambient_occlusion_0( posision_0 : vec3<f32>,  normal_4 : vec3<f32>) -> f32
 {
     var i_7 : i32 = i32(0);
     var fall_off_0 : f32 = 1.0f;
@@ -1678,186 +3602,965 @@ fn approximate_ambient_occlusion_0( posision_0 : vec3<f32>,  normal_4 : vec3<f32
         {
             break;
         }
-        var height_2 : f32 = 0.00999999977648258f + 0.11999999731779099f * f32(i_7) / 4.0f;
-        var signed_distance_1 : f32 = sample_signed_distance_0(posision_0 + vec3<f32>(height_2) * normal_4, normal_4);
-        var occlusion_1 : f32 = occlusion_0 + max(0.0f, (height_2 - signed_distance_1) * fall_off_0);
-        var fall_off_1 : f32 = fall_off_0 * 0.94999998807907104f;
-        if(occlusion_1 > 0.34999999403953552f)
+        var height_2 : f32 = 0.00999999977648258f + 0.11999999731779099f * f32(i_7) / 4.0f;
+        var signed_distance_1 : f32 = sample_signed_distance_0(posision_0 + vec3<f32>(height_2) * normal_4, normal_4);
+        var occlusion_1 : f32 = occlusion_0 + max(0.0f, (height_2 - signed_distance_1) * fall_off_0);
+        var fall_off_1 : f32 = fall_off_0 * 0.94999998807907104f;
+        if(occlusion_1 > 0.34999999403953552f)
+        {
+            occlusion_0 = occlusion_1;
+            break;
+        }
+        i_7 = i_7 + i32(1);
+        fall_off_0 = fall_off_1;
+        occlusion_0 = occlusion_1;
+    }
+    return clamp(2.5f - 7.0f * occlusion_0, 0.0f, 1.0f);
+}
+
+struct ShadowMapFrame_0
+{
+     origin_9 : vec3<f32>,
+     direction_9 : vec3<f32>,
+     axis_u_0 : vec3<f32>,
+     axis_v_0 : vec3<f32>,
+     radius_9 : f32,
+};
+
+fn shadow_map_frame_around_0( origin_10 : vec3<f32>,  direction_10 : vec3<f32>,  radius_10 : f32) -> ShadowMapFrame_0
+{
+    var result_80 : ShadowMapFrame_0;
+    result_80.origin_9 = origin_10;
+    result_80.direction_9 = direction_10;
+    var helper_0 : vec3<f32>;
+    if((abs(direction_10.x)) > 0.89999997615814209f)
+    {
+        helper_0 = vec3<f32>(0.0f, 1.0f, 0.0f);
+    }
+    else
+    {
+        helper_0 = vec3<f32>(1.0f, 0.0f, 0.0f);
+    }
+    var _S906 : vec3<f32> = normalize(cross(direction_10, helper_0));
+    result_80.axis_v_0 = _S906;
+    result_80.axis_u_0 = cross(_S906, direction_10);
+    result_80.radius_9 = radius_10;
+    return result_80;
+}
+
+fn fit_shadow_map_frame_0() -> ShadowMapFrame_0
+{
+    var bounds_min_0 : vec3<f32> = vec3<f32>(1.0e+09f);
+    var bounds_max_0 : vec3<f32> = vec3<f32>(-1.0e+09f);
+    if((uniforms.bvh_length_0) > u32(0))
+    {
+        var root_0 : BvhNode_0 = load_bvh_node_0(u32(0));
+        bounds_min_0 = root_0.aabb_min_0;
+        bounds_max_0 = root_0.aabb_max_0;
+    }
+    var i_40 : u32 = u32(0);
+    for(;;)
+    {
+        if(i_40 < (uniforms.parallelograms_count_0))
+        {
+        }
+        else
+        {
+            break;
+        }
+        var parallelogram_9 : Parallelogram_0 = Parallelogram_0( parallelograms[i_40].Q_0, parallelograms[i_40].u_0, parallelograms[i_40].object_uid_0, parallelograms[i_40].v_0, parallelograms[i_40].D_0, parallelograms[i_40].normal_0, parallelograms[i_40].w_0, parallelograms[i_40].material_id_0 );
+        var _S907 : bool;
+        if(object_traced_0(parallelogram_9.object_uid_0))
+        {
+            _S907 = (all(((materials[parallelogram_9.material_id_0].emission_0.xyz) <= vec3<f32>(0.0f))));
+        }
+        else
+        {
+            _S907 = false;
+        }
+        if(_S907)
+        {
+            var corner_u_0 : vec3<f32> = parallelogram_9.Q_0 + parallelogram_9.u_0;
+            var corner_v_0 : vec3<f32> = parallelogram_9.Q_0 + parallelogram_9.v_0;
+            var corner_far_0 : vec3<f32> = corner_u_0 + parallelogram_9.v_0;
+            bounds_min_0 = min(bounds_min_0, min(min(parallelogram_9.Q_0, corner_u_0), min(corner_v_0, corner_far_0)));
+            bounds_max_0 = max(bounds_max_0, max(max(parallelogram_9.Q_0, corner_u_0), max(corner_v_0, corner_far_0)));
+        }
+        i_40 = i_40 + u32(1);
+    }
+    var light_center_1 : vec3<f32> = lights.Q_0 + (lights.u_0 + lights.v_0) * vec3<f32>(0.5f);
+    if((any((bounds_min_0 > bounds_max_0))))
+    {
+        return shadow_map_frame_around_0(light_center_1, vec3<f32>(0.0f, -1.0f, 0.0f), 0.0f);
+    }
+    var to_bounds_0 : vec3<f32> = (bounds_min_0 + bounds_max_0) * vec3<f32>(0.5f) - light_center_1;
+    var distance_30 : f32 = length(to_bounds_0);
+    var direction_11 : vec3<f32>;
+    if(distance_30 > 0.00009999999747379f)
+    {
+        direction_11 = to_bounds_0 / vec3<f32>(distance_30);
+    }
+    else
+    {
+        direction_11 = vec3<f32>(0.0f, -1.0f, 0.0f);
+    }
+    return shadow_map_frame_around_0(light_center_1, direction_11, max(length(bounds_max_0 - bounds_min_0) * 0.5f, 0.00009999999747379f));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_shadow_map(@builtin(global_invocation_id) global_invocation_id_20 : vec3<u32>)
+{
+    randState = u32(0);
+    var resolution_12 : u32 = uniforms.shadow_map_resolution_0;
+    var _S908 : bool;
+    if((global_invocation_id_20.x) >= resolution_12)
+    {
+        _S908 = true;
+    }
+    else
+    {
+        _S908 = (global_invocation_id_20.y) >= resolution_12;
+    }
+    if(_S908)
+    {
+        return;
+    }
+    get_lights_0();
+    var frame_12 : ShadowMapFrame_0 = fit_shadow_map_frame_0();
+    var _S909 : bool;
+    if(u32(0) == (global_invocation_id_20.x))
+    {
+        _S909 = u32(0) == (global_invocation_id_20.y);
+    }
+    else
+    {
+        _S909 = false;
+    }
+    if(_S909)
+    {
+        shadow_map[u32(0)] = frame_12.origin_9.x;
+        shadow_map[u32(1)] = frame_12.origin_9.y;
+        shadow_map[u32(2)] = frame_12.origin_9.z;
+        shadow_map[u32(3)] = frame_12.radius_9;
+        shadow_map[u32(4)] = frame_12.direction_9.x;
+        shadow_map[u32(5)] = frame_12.direction_9.y;
+        shadow_map[u32(6)] = frame_12.direction_9.z;
+        shadow_map[u32(7)] = 0.0f;
+    }
+    var depth_12 : f32 = 1.0e+09f;
+    if((frame_12.radius_9) > 0.0f)
+    {
+        var offset_20 : vec2<f32> = ((vec2<f32>(global_invocation_id_20.xy) + vec2<f32>(0.5f)) / vec2<f32>(f32(resolution_12)) * vec2<f32>(2.0f) - vec2<f32>(1.0f)) * vec2<f32>(frame_12.radius_9);
+        var origin_11 : vec3<f32> = frame_12.origin_9 + frame_12.axis_u_0 * vec3<f32>(offset_20.x) + frame_12.axis_v_0 * vec3<f32>(offset_20.y);
+        var travelled_0 : f32 = 0.0f;
+        var i_41 : i32 = i32(0);
+        for(;;)
+        {
+            if(i_41 <= i32(4))
+            {
+            }
+            else
+            {
+                break;
+            }
+            var _S910 : bool = hit_scene_0(Ray_x24init_0(origin_11 + frame_12.direction_9 * vec3<f32>(travelled_0), frame_12.direction_9), 1.0e+09f);
+            if(false == _S910)
+            {
+                break;
+            }
+            travelled_0 = travelled_0 + hitRec.t_2;
+            if((all(((hitMaterial.emission_0.xyz) <= vec3<f32>(0.0f)))))
+            {
+                depth_12 = travelled_0;
+                break;
+            }
+            i_41 = i_41 + i32(1);
+        }
+    }
+    shadow_map[u32(8) + global_invocation_id_20.y * resolution_12 + global_invocation_id_20.x] = depth_12;
+    return;
+}
+
+fn sample_shadow_map_0( position_30 : vec3<f32>,  normal_30 : vec3<f32>) -> f32
+{
+    var radius_11 : f32 = shadow_map[u32(3)];
+    if(radius_11 <= 0.0f)
+    {
+        return -1.0f;
+    }
+    var frame_13 : ShadowMapFrame_0 = shadow_map_frame_around_0(vec3<f32>(shadow_map[u32(0)], shadow_map[u32(1)], shadow_map[u32(2)]), vec3<f32>(shadow_map[u32(4)], shadow_map[u32(5)], shadow_map[u32(6)]), radius_11);
+    var relative_0 : vec3<f32> = position_30 - frame_13.origin_9;
+    var depth_13 : f32 = dot(relative_0, frame_13.direction_9);
+    var resolution_13 : f32 = f32(uniforms.shadow_map_resolution_0);
+    var texel_0 : vec2<f32> = (vec2<f32>(dot(relative_0, frame_13.axis_u_0), dot(relative_0, frame_13.axis_v_0)) / vec2<f32>(frame_13.radius_9) * vec2<f32>(0.5f) + vec2<f32>(0.5f)) * vec2<f32>(resolution_13) - vec2<f32>(0.5f);
+    var _S911 : bool;
+    if(depth_13 < 0.0f)
+    {
+        _S911 = true;
+    }
+    else
+    {
+        _S911 = (any((texel_0 < vec2<f32>(0.0f))));
+    }
+    var _S912 : bool;
+    if(_S911)
+    {
+        _S912 = true;
+    }
+    else
+    {
+        _S912 = (any((texel_0 > vec2<f32>(resolution_13 - 1.0f))));
+    }
+    if(_S912)
+    {
+        return -1.0f;
+    }
+    var biased_depth_0 : f32 = depth_13 - 0.00499999988824129f - 1.5f * (2.0f * frame_13.radius_9 / resolution_13) / max(abs(dot(normal_30, frame_13.direction_9)), 0.25f);
+    var last_0 : i32 = i32(uniforms.shadow_map_resolution_0) - i32(1);
+    var center_7 : vec2<i32> = vec2<i32>(round(texel_0));
+    var lit_0 : f32 = 0.0f;
+    var y_12 : i32 = i32(-1);
+    for(;;)
+    {
+        if(y_12 <= i32(1))
+        {
+        }
+        else
+        {
+            break;
+        }
+        var x_12 : i32 = i32(-1);
+        for(;;)
+        {
+            if(x_12 <= i32(1))
+            {
+            }
+            else
+            {
+                break;
+            }
+            var tap_0 : vec2<i32> = clamp(center_7 + vec2<i32>(x_12, y_12), vec2<i32>(i32(0)), vec2<i32>(last_0));
+            var _S913 : f32;
+            if(biased_depth_0 <= (shadow_map[u32(8) + u32(tap_0.y * (last_0 + i32(1)) + tap_0.x)]))
+            {
+                _S913 = 1.0f;
+            }
+            else
+            {
+                _S913 = 0.0f;
+            }
+            lit_0 = lit_0 + _S913;
+            x_12 = x_12 + i32(1);
+        }
+        y_12 = y_12 + i32(1);
+    }
+    return lit_0 / 9.0f;
+}
+
+fn evaluate_dielectric_surface_color_0( camera_origin_0 : vec3<f32>,  hit_3 : HitRecord_0,  hit_material_0 : Material_0,  hit_albedo_0 : vec3<f32>,  ambient_radiance_0 : vec3<f32>) -> vec3<f32>
+{
+    var to_light_1 : vec3<f32> = lights.Q_0 + (lights.u_0 + lights.v_0) * vec3<f32>(0.5f) - hit_3.global_0.position_2;
+    var to_light_distance_0 : f32 = length(to_light_1);
+    var to_light_direction_0 : vec3<f32>;
+    if(to_light_distance_0 > 0.00009999999747379f)
+    {
+        to_light_direction_0 = to_light_1 / vec3<f32>(to_light_distance_0);
+    }
+    else
+    {
+        to_light_direction_0 = vec3<f32>(0.0f, 0.0f, 0.0f);
+    }
+    var diffuse_fall_off_0 : f32 = max(0.0f, dot(hit_3.global_0.normal_1, to_light_direction_0));
+    var specular_fall_off_0 : f32 = pow(max(0.0f, dot(reflect((vec3<f32>(0) - to_light_direction_0), hit_3.global_0.normal_1), normalize(camera_origin_0 - hit_3.global_0.position_2))), 4.0f) * diffuse_fall_off_0;
+    var shadow_0 : f32;
+    if(u32(0) == (uniforms.shadow_map_resolution_0))
+    {
+        shadow_0 = -1.0f;
+    }
+    else
+    {
+        shadow_0 = sample_shadow_map_0(hit_3.global_0.position_2, hit_3.global_0.normal_1);
+    }
+    if(shadow_0 < 0.0f)
+    {
+        shadow_0 = evaluate_hard_shadow_0(hit_3.global_0.position_2, to_light_direction_0, 0.00499999988824129f, to_light_distance_0);
+    }
+    var shadow_lightened_0 : f32 = shadow_0 * 0.39999997615814209f + 0.60000002384185791f;
+    var occlusion_2 : f32 = approximate_ambient_occlusion_0(hit_3.global_0.position_2, hit_3.global_0.normal_1);
+    var _S141 : vec3<f32> = vec3<f32>(occlusion_2);
+    return mix(vec3<f32>(diffuse_fall_off_0) * hit_albedo_0 * _S141, vec3<f32>(specular_fall_off_0) * hit_material_0.specular_0, vec3<f32>(hit_material_0.specular_strength_0)) * materials[lights.material_id_0].emission_0.xyz * vec3<f32>(shadow_lightened_0) + ambient_radiance_0 * hit_albedo_0 * _S141 + hit_material_0.emission_0.xyz;
+}
+
+fn rand_from_seed_0( seed_0 : f32) -> f32
+{
+    return fract(sin(seed_0) * 43758.546875f);
+}
+
+fn reflection_roughness_addition_0( position_6 : vec3<f32>,  extra_seed_0 : f32) -> vec3<f32>
+{
+    var _S142 : f32 = rand_0_1_0();
+    var phi_3 : f32 = rand_from_seed_0((_S142 + position_6.x + 0.35699999332427979f) * extra_seed_0) * 2.0f * 3.14159274101257324f;
+    var _S143 : f32 = rand_0_1_0();
+    var theta_1 : f32 = acos(2.0f * rand_from_seed_0((_S143 + position_6.y + 16.35647010803222656f) * extra_seed_0) - 1.0f);
+    var _S144 : f32 = sin(theta_1);
+    return vec3<f32>(_S144 * cos(phi_3), _S144 * sin(phi_3), cos(theta_1));
+}
+
+fn evaluate_reflection_0( incident_2 : vec3<f32>,  normal_5 : vec3<f32>,  hit_position_0 : vec3<f32>,  roughness_1 : f32) -> vec3<f32>
+{
+    var perfect_0 : vec3<f32> = reflect(incident_2, normal_5);
+    if(near_zero_scalar_0(roughness_1))
+    {
+        return perfect_0;
+    }
+    var _S145 : vec3<f32> = reflection_roughness_addition_0(hit_position_0, incident_2.z);
+    return normalize(perfect_0 + _S145 * vec3<f32>(roughness_1));
+}
+
+fn ray_color_deterministic_0( camera_origin_1 : vec3<f32>,  incident_3 : RayAndDifferentials_0) -> vec3<f32>
+{
+    var accumulated_radiance_3 : vec3<f32>;
+    var _S146 : vec3<f32> = vec3<f32>(0.0f);
+    var current_ray_1 : Ray_0 = incident_3.ray_0;
+    var _S147 : vec3<f32> = vec3<f32>(1.0f);
+    var i_8 : i32 = i32(0);
+    var throughput_4 : vec3<f32> = _S147;
+    for(;;)
+    {
+        if(i_8 < (i32(uniforms.max_ray_bounces_0)))
+        {
+        }
+        else
+        {
+            accumulated_radiance_3 = _S146;
+            break;
+        }
+        var _S148 : bool = hit_scene_0(current_ray_1, uniforms.max_ray_distance_0);
+        if(false == _S148)
+        {
+            accumulated_radiance_3 = background_radiance_0(current_ray_1.direction_0) * throughput_4;
+            break;
+        }
+        var hit_material_1 : Material_0 = hitMaterial;
+        throughput_4 = throughput_4 * glass_transmittance_0(hitRec, hit_material_1);
+        hitRec.global_0.normal_1 = perturbed_normal_0(hitRec.local_0, hitRec.object_uid_6, hitRec.global_0.normal_1, hitMaterial, current_ray_1.direction_0, hitRec.t_2, incident_3.differentials_0);
+        var hit_albedo_1 : vec3<f32> = fetch_albedo_0(hitRec.local_0, hitRec.object_uid_6, current_ray_1.direction_0, hitRec.t_2, hitMaterial, incident_3.differentials_0);
+        if(i32(0) == (hit_material_1.material_class_0))
+        {
+            var ambient_radiance_1 : vec3<f32> = sample_ambient_radiance_0(hitRec.global_0.position_2, hitRec.global_0.normal_1);
+            var _S149 : vec3<f32> = evaluate_dielectric_surface_color_0(camera_origin_1, hitRec, hit_material_1, hit_albedo_1, ambient_radiance_1);
+            accumulated_radiance_3 = throughput_4 * _S149;
+            break;
+        }
+        if(i32(4) == (hit_material_1.material_class_0))
+        {
+            accumulated_radiance_3 = throughput_4 * evaluate_surface_shader_0(hitRec, hit_material_1, hit_albedo_1);
+            break;
+        }
+        if(i32(1) == (hit_material_1.material_class_0))
+        {
+            var reflected_1 : vec3<f32> = evaluate_reflection_0(current_ray_1.direction_0, hitRec.global_0.normal_1, hitRec.global_0.position_2, hit_material_1.roughness_0);
+            current_ray_1.origin_2 = hitRec.global_0.position_2 + reflected_1 * vec3<f32>(0.00050000002374873f);
+            current_ray_1.direction_0 = reflected_1;
+            throughput_4 = throughput_4 * hit_albedo_1;
+        }
+        else
+        {
+            var _S150 : vec3<f32>;
+            if(i32(2) == (hit_material_1.material_class_0))
+            {
+                var _S151 : Ray_0 = glass_scatter_0(hitRec, hit_material_1.refractive_index_eta_0, hit_material_1.roughness_0, current_ray_1.direction_0, false);
+                current_ray_1 = _S151;
+                current_ray_1.origin_2 = current_ray_1.origin_2 + current_ray_1.direction_0 * vec3<f32>(0.00050000002374873f);
+                _S150 = throughput_4 * hit_albedo_1;
+            }
+            else
+            {
+                accumulated_radiance_3 = hit_albedo_1;
+                break;
+            }
+            throughput_4 = _S150;
+        }
+        i_8 = i_8 + i32(1);
+    }
+    return accumulated_radiance_3;
+}
+
+fn path_trace_deterministic_0( camera_6 : Camera_0,  pixel_7 : Pixel_0) -> vec3<f32>
+{
+    var _S152 : u32 = uniforms.pixel_side_subdivision_0;
+    if((uniforms.pixel_side_subdivision_0) == u32(1))
+    {
+        var _S153 : vec3<f32> = ray_color_deterministic_0(camera_6.origin_0, ray_and_differentials_0(camera_6, pixel_7, 0.5f, 0.5f));
+        return _S153;
+    }
+    var _S154 : vec3<f32> = vec3<f32>(0.0f);
+    var _S155 : f32 = 1.0f / f32(_S152 - u32(1));
+    var i_9 : u32 = u32(0);
+    var result_color_2 : vec3<f32> = _S154;
+    for(;;)
+    {
+        if(i_9 < _S152)
+        {
+        }
+        else
+        {
+            break;
+        }
+        var j_0 : u32 = u32(0);
+        for(;;)
+        {
+            if(j_0 < _S152)
+            {
+            }
+            else
+            {
+                break;
+            }
+            var _S156 : vec3<f32> = ray_color_deterministic_0(camera_6.origin_0, ray_and_differentials_0(camera_6, pixel_7, _S155 * f32(i_9), _S155 * f32(j_0)));
+            var result_color_3 : vec3<f32> = result_color_2 + _S156;
+            j_0 = j_0 + u32(1);
+            result_color_2 = result_color_3;
+        }
+        i_9 = i_9 + u32(1);
+    }
+    return result_color_2 / vec3<f32>(f32(_S152 * _S152));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_color_buffer_deterministic(@builtin(global_invocation_id) global_invocation_id_3 : vec3<u32>)
+{
+    randState = u32(0);
+    var pixel_index_5 : u32 = evaluate_pixel_index_0(global_invocation_id_3, uniforms.thread_grid_size_0);
+    if(pixel_outside_frame_buffer_0(pixel_index_5))
+    {
+        return;
+    }
+    var camera_7 : Camera_0 = setup_camera_0();
+    var pixel_8 : Pixel_0 = make_common_color_evaluation_setup_0(pixel_index_5);
+    seed_object_opacity_0(pixel_index_5);
+    var traced_color_1 : vec3<f32> = path_trace_deterministic_0(camera_7, pixel_8);
+    pixel_color_buffer[pixel_index_5] = vec4<f32>(traced_color_1, 1.0f);
+    return;
+}
+
+
+fn half_resolution_size_0() -> vec2<u32>
+{
+    return (uniforms.frame_buffer_size_0 + vec2<u32>(u32(1))) / vec2<u32>(u32(2));
+}
+
+fn demodulate_0( radiance_0 : vec3<f32>,  albedo_2 : vec3<f32>) -> vec3<f32>
+{
+    return select(vec3<f32>(0.0f), radiance_0 / max(albedo_2, vec3<f32>(0.00100000004749745f)), albedo_2 > vec3<f32>(0.00100000004749745f));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_indirect_half_resolution(@builtin(global_invocation_id) global_invocation_id_4 : vec3<u32>)
+{
+    randState = u32(0);
+    var half_size_0 : vec2<u32> = half_resolution_size_0();
+    if(global_invocation_id_4.x >= (half_size_0.x) || global_invocation_id_4.y >= (half_size_0.y))
+    {
+        return;
+    }
+    var half_pixel_index_0 : u32 = global_invocation_id_4.y * half_size_0.x + global_invocation_id_4.x;
+    var camera_8 : Camera_0 = setup_camera_0();
+    get_lights_0();
+    var block_corner_0 : Pixel_0;
+    block_corner_0.coordinates_0 = vec2<f32>(global_invocation_id_4.xy * vec2<u32>(u32(2)));
+    var block_center_0 : FirstHitSurface_0 = trace_first_intersection_0(ray_and_differentials_0(camera_8, block_corner_0, 1.0f, 1.0f));
+    indirect_half_resolution_guide_buffer[half_pixel_index_0] = vec4<f32>(block_center_0.normal_3, block_center_0.depth_0);
+    seed_pixel_random_0(half_pixel_index_0, global_invocation_id_4.xy);
+    var _S157 : f32 = 2.0f * rand_0_1_0();
+    var ray_7 : RayAndDifferentials_0 = ray_and_differentials_0(camera_8, block_corner_0, _S157, 2.0f * rand_0_1_0());
+    var first_hit_0 : FirstHitSurface_0 = trace_first_intersection_0(ray_7);
+    var demodulated_0 : vec3<f32>;
+    if(u32(0) < (first_hit_0.object_uid_3))
+    {
+        var _S158 : vec3<f32> = ray_color_monte_carlo_0(ray_7, i32(uniforms.max_ray_bounces_0));
+        demodulated_0 = demodulate_0(sampleLighting.indirect_1, first_hit_0.albedo_1);
+    }
+    else
+    {
+        demodulated_0 = vec3<f32>(0.0f);
+    }
+    indirect_half_resolution_buffer[half_pixel_index_0] = vec4<f32>(indirect_half_resolution_buffer[half_pixel_index_0].xyz + demodulated_0, 1.0f);
+    return;
+}
+
+fn upsample_indirect_0( pixel_9 : Pixel_0,  normal_6 : vec3<f32>,  depth_1 : f32) -> vec3<f32>
+{
+    var half_size_1 : vec2<u32> = half_resolution_size_0();
+    var position_7 : vec2<f32> = (pixel_9.coordinates_0 + vec2<f32>(0.5f)) * vec2<f32>(0.5f) - vec2<f32>(0.5f);
+    var base_0 : vec2<i32> = vec2<i32>(floor(position_7));
+    var fraction_0 : vec2<f32> = position_7 - vec2<f32>(base_0);
+    var _S159 : vec2<i32> = vec2<i32>(half_size_1) - vec2<i32>(i32(1));
+    var guided_sum_0 : vec3<f32> = vec3<f32>(0.0f);
+    var guided_weight_0 : f32 = 0.0f;
+    var bilinear_sum_0 : vec3<f32> = vec3<f32>(0.0f);
+    var j_1 : i32 = i32(0);
+    for(;;)
+    {
+        if(j_1 < i32(2))
+        {
+        }
+        else
+        {
+            break;
+        }
+        var i_10 : i32 = i32(0);
+        for(;;)
         {
-            occlusion_0 = occlusion_1;
-            break;
+            if(i_10 < i32(2))
+            {
+            }
+            else
+            {
+                break;
+            }
+            var texel_0 : vec2<i32> = clamp(base_0 + vec2<i32>(i_10, j_1), vec2<i32>(i32(0)), _S159);
+            var index_0 : u32 = u32(texel_0.y) * half_size_1.x + u32(texel_0.x);
+            var guide_0 : vec4<f32> = indirect_half_resolution_guide_buffer[index_0];
+            var indirect_0 : vec3<f32> = indirect_half_resolution_buffer[index_0].xyz;
+            var _S160 : f32;
+            if(i_10 == i32(0))
+            {
+                _S160 = 1.0f - fraction_0.x;
+            }
+            else
+            {
+                _S160 = fraction_0.x;
+            }
+            var _S161 : f32;
+            if(j_1 == i32(0))
+            {
+                _S161 = 1.0f - fraction_0.y;
+            }
+            else
+            {
+                _S161 = fraction_0.y;
+            }
+            var bilinear_weight_0 : f32 = _S160 * _S161;
+            var weight_0 : f32 = bilinear_weight_0 * pow(max(0.0f, dot(normal_6, guide_0.xyz)), 32.0f) * exp(- abs(depth_1 - guide_0.w) / (0.05000000074505806f * depth_1 + 0.00009999999747379f));
+            guided_sum_0 = guided_sum_0 + indirect_0 * vec3<f32>(weight_0);
+            guided_weight_0 = guided_weight_0 + weight_0;
+            bilinear_sum_0 = bilinear_sum_0 + indirect_0 * vec3<f32>(bilinear_weight_0);
+            i_10 = i_10 + i32(1);
         }
-        i_7 = i_7 + i32(1);
-        fall_off_0 = fall_off_1;
-        occlusion_0 = occlusion_1;
+        j_1 = j_1 + i32(1);
     }
-    return clamp(2.5f - 7.0f * occlusion_0, 0.0f, 1.0f);
+    if(guided_weight_0 < 0.00009999999747379f)
+    {
+        return bilinear_sum_0;
+    }
+    return guided_sum_0 / vec3<f32>(guided_weight_0);
 }
 
-fn evaluate_dielectric_surface_color_0( camera_origin_0 : vec3<f32>,  hit_3 : HitRecord_0,  hit_material_0 : Material_0,  hit_albedo_0 : vec3<f32>) -> vec3<f32>
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_color_buffer_half_resolution_indirect(@builtin(global_invocation_id) global_invocation_id_5 : vec3<u32>)
 {
-    var to_light_1 : vec3<f32> = lights.Q_0 + (lights.u_0 + lights.v_0) * vec3<f32>(0.5f) - hit_3.global_0.position_2;
-    var to_light_distance_0 : f32 = length(to_light_1);
-    var to_light_direction_0 : vec3<f32>;
-    if(to_light_distance_0 > 0.00009999999747379f)
+    randState = u32(0);
+    var pixel_index_6 : u32 = evaluate_pixel_index_0(global_invocation_id_5, uniforms.thread_grid_size_0);
+    if(pixel_outside_frame_buffer_0(pixel_index_6))
     {
-        to_light_direction_0 = to_light_1 / vec3<f32>(to_light_distance_0);
+        return;
+    }
+    var camera_9 : Camera_0 = setup_camera_0();
+    get_lights_0();
+    var pixel_10 : Pixel_0 = setup_pixel_coordinates_0(pixel_index_6);
+    seed_pixel_random_0(pixel_index_6, vec2<u32>(pixel_10.coordinates_0));
+    var _S923 : f32 = rand_0_1_0();
+    var _S924 : vec3<f32> = ray_color_monte_carlo_0(ray_and_differentials_0(camera_9, pixel_10, _S923, rand_0_1_0()), min(i32(2), i32(uniforms.max_ray_bounces_0)));
+    var direct_1 : vec3<f32> = direct_full_resolution_buffer[pixel_index_6].xyz + sampleLighting.direct_0;
+    direct_full_resolution_buffer[pixel_index_6] = vec4<f32>(direct_1, 1.0f);
+    var primary_0 : RayAndDifferentials_0 = ray_and_differentials_0(camera_9, pixel_10, 0.5f, 0.5f);
+    var surface_0 : FirstHitSurface_0 = trace_first_intersection_0(primary_0);
+    var color_1 : vec3<f32>;
+    if(u32(0) < (surface_0.object_uid_3))
+    {
+        color_1 = surface_0.emission_1 + surface_0.albedo_1 * (upsample_indirect_0(pixel_10, surface_0.normal_3, surface_0.depth_0) / vec3<f32>(uniforms.frame_number_0)) + direct_1 / vec3<f32>(uniforms.frame_number_0);
     }
     else
     {
-        to_light_direction_0 = vec3<f32>(0.0f, 0.0f, 0.0f);
+        color_1 = background_radiance_0(primary_0.ray_0.direction_0);
     }
-    var diffuse_fall_off_0 : f32 = max(0.0f, dot(hit_3.global_0.normal_1, to_light_direction_0));
-    var specular_fall_off_0 : f32 = pow(max(0.0f, dot(reflect((vec3<f32>(0) - to_light_direction_0), hit_3.global_0.normal_1), normalize(camera_origin_0 - hit_3.global_0.position_2))), 4.0f) * diffuse_fall_off_0;
-    var shadow_0 : f32 = evaluate_hard_shadow_0(hit_3.global_0.position_2, to_light_direction_0, 0.00499999988824129f, to_light_distance_0);
-    var shadow_lightened_0 : f32 = shadow_0 * 0.39999997615814209f + 0.60000002384185791f;
-    var occlusion_2 : f32 = approximate_ambient_occlusion_0(hit_3.global_0.position_2, hit_3.global_0.normal_1);
-    var _S141 : vec3<f32> = vec3<f32>(occlusion_2);
-    return mix(vec3<f32>(diffuse_fall_off_0) * hit_albedo_0 * _S141, vec3<f32>(specular_fall_off_0) * hit_material_0.specular_0, vec3<f32>(hit_material_0.specular_strength_0)) * materials[lights.material_id_0].emission_0.xyz * vec3<f32>(shadow_lightened_0) + vec3<f32>(0.10000000149011612f) * hit_albedo_0 * _S141 + hit_material_0.emission_0.xyz;
+    pixel_color_buffer[pixel_index_6] = vec4<f32>(color_1 * vec3<f32>(uniforms.frame_number_0), 1.0f);
+    return;
 }
 
-fn rand_from_seed_0( seed_0 : f32) -> f32
+fn irradiance_probe_face_direction_0( face_0 : u32) -> vec3<f32>
 {
-    return fract(sin(seed_0) * 43758.546875f);
+    var axis_0 : vec3<f32> = vec3<f32>(0.0f);
+    var _S162 : f32;
+    if(u32(0) == face_0 % u32(2))
+    {
+        _S162 = 1.0f;
+    }
+    else
+    {
+        _S162 = -1.0f;
+    }
+    axis_0[face_0 / u32(2)] = _S162;
+    return axis_0;
 }
 
-fn reflection_roughness_addition_0( position_6 : vec3<f32>,  extra_seed_0 : f32) -> vec3<f32>
+fn irradiance_probe_position_0( probe_index_0 : u32) -> vec3<f32>
 {
-    var _S142 : f32 = rand_0_1_0();
-    var phi_3 : f32 = rand_from_seed_0((_S142 + position_6.x + 0.35699999332427979f) * extra_seed_0) * 2.0f * 3.14159274101257324f;
-    var _S143 : f32 = rand_0_1_0();
-    var theta_1 : f32 = acos(2.0f * rand_from_seed_0((_S143 + position_6.y + 16.35647010803222656f) * extra_seed_0) - 1.0f);
-    var _S144 : f32 = sin(theta_1);
-    return vec3<f32>(_S144 * cos(phi_3), _S144 * sin(phi_3), cos(theta_1));
+    var resolution_0 : vec3<u32> = uniforms.probe_grid_resolution_0;
+    var cell_0 : vec3<u32> = vec3<u32>(probe_index_0 % resolution_0.x, probe_index_0 / resolution_0.x % resolution_0.y, probe_index_0 / (resolution_0.x * resolution_0.y));
+    return uniforms.probe_grid_min_0 + vec3<f32>(cell_0) * uniforms.probe_grid_cell_size_0;
 }
 
-fn evaluate_reflection_0( incident_2 : vec3<f32>,  normal_5 : vec3<f32>,  hit_position_0 : vec3<f32>,  roughness_1 : f32) -> vec3<f32>
+fn irradiance_probe_face_radiance_0( origin_3 : vec3<f32>,  direction_3 : vec3<f32>) -> vec3<f32>
 {
-    var perfect_0 : vec3<f32> = reflect(incident_2, normal_5);
-    if(near_zero_scalar_0(roughness_1))
+    var _S163 : Ray_0 = Ray_0( origin_3, direction_3 );
+    var _S164 : bool = hit_scene_0(_S163, 1.0e+09f);
+    if(false == _S164)
     {
-        return perfect_0;
+        return background_radiance_0(direction_3);
     }
-    var _S145 : vec3<f32> = reflection_roughness_addition_0(hit_position_0, incident_2.z);
-    return normalize(perfect_0 + _S145 * vec3<f32>(roughness_1));
+    var no_footprint_0 : RayDifferentials_0;
+    no_footprint_0.dx_0 = direction_3;
+    no_footprint_0.dy_0 = direction_3;
+    var hit_material_2 : Material_0 = hitMaterial;
+    hitRec.global_0.normal_1 = perturbed_normal_0(hitRec.local_0, hitRec.object_uid_6, hitRec.global_0.normal_1, hitMaterial, direction_3, hitRec.t_2, no_footprint_0);
+    var hit_albedo_2 : vec3<f32> = fetch_albedo_0(hitRec.local_0, hitRec.object_uid_6, direction_3, hitRec.t_2, hitMaterial, no_footprint_0);
+    return evaluate_dielectric_surface_color_0(origin_3, hitRec, hit_material_2, hit_albedo_2, uniforms.clear_color_0.xyz);
 }
 
-fn ray_color_deterministic_0( camera_origin_1 : vec3<f32>,  incident_3 : RayAndDifferentials_0) -> vec3<f32>
+@compute
+@workgroup_size(64, 1, 1)
+fn compute_irradiance_probes(@builtin(global_invocation_id) global_invocation_id_6 : vec3<u32>)
 {
-    var accumulated_radiance_3 : vec3<f32>;
-    var _S146 : vec3<f32> = vec3<f32>(0.0f);
-    var current_ray_1 : Ray_0 = incident_3.ray_0;
-    var _S147 : vec3<f32> = vec3<f32>(1.0f);
-    var i_8 : i32 = i32(0);
-    var throughput_4 : vec3<f32> = _S147;
+    randState = u32(0);
+    var probe_index_1 : u32 = global_invocation_id_6.x;
+    if(probe_index_1 >= (uniforms.probe_grid_probes_count_0))
+    {
+        return;
+    }
+    get_lights_0();
+    var probe_position_0 : vec3<f32> = irradiance_probe_position_0(probe_index_1);
+    var first_face_slot_0 : u32 = probe_index_1 * u32(6);
+    var accumulated_samples_0 : f32 = irradiance_probes[first_face_slot_0].w;
+    var blend_0 : f32 = 1.0f / (min(accumulated_samples_0, 64.0f) + 1.0f);
+    seed_random_0(probe_index_1, u32(accumulated_samples_0));
+    var face_1 : u32 = u32(0);
     for(;;)
     {
-        if(i_8 < i32(8))
+        if(face_1 < u32(6))
         {
         }
         else
         {
-            accumulated_radiance_3 = _S146;
             break;
         }
-        var _S148 : bool = hit_scene_0(current_ray_1, 1.0e+09f);
-        if(false == _S148)
+        var uvw_1 : mat3x3<f32> = onb_build_from_w_0(irradiance_probe_face_direction_0(face_1));
+        var radiance_1 : vec3<f32> = vec3<f32>(0.0f);
+        var i_11 : i32 = i32(0);
+        for(;;)
+        {
+            if(i_11 < i32(8))
+            {
+            }
+            else
+            {
+                break;
+            }
+            var _S165 : vec3<f32> = cosine_sampling_wrt_Z_0();
+            var direction_4 : vec3<f32> = normalize(onb_get_local_0(_S165));
+            var _S166 : vec3<f32> = irradiance_probe_face_radiance_0(probe_position_0, direction_4);
+            radiance_1 = radiance_1 + _S166;
+            i_11 = i_11 + i32(1);
+        }
+        radiance_1 = radiance_1 / vec3<f32>(8.0f);
+        var slot_0 : u32 = first_face_slot_0 + face_1;
+        irradiance_probes[slot_0] = vec4<f32>(mix(irradiance_probes[slot_0].xyz, radiance_1, vec3<f32>(blend_0)), accumulated_samples_0 + 1.0f);
+        face_1 = face_1 + u32(1);
+    }
+    return;
+}
+
+fn irradiance_probe_radiance_0( probe_index_2 : u32,  normal_7 : vec3<f32>) -> vec3<f32>
+{
+    var first_face_slot_1 : u32 = probe_index_2 * u32(6);
+    var faces_0 : vec3<u32> = select(vec3<u32>(u32(1), u32(3), u32(5)), vec3<u32>(u32(0), u32(2), u32(4)), normal_7 >= vec3<f32>(0.0f));
+    var squared_0 : vec3<f32> = normal_7 * normal_7;
+    return vec3<f32>(squared_0.x) * irradiance_probes[first_face_slot_1 + faces_0.x].xyz + vec3<f32>(squared_0.y) * irradiance_probes[first_face_slot_1 + faces_0.y].xyz + vec3<f32>(squared_0.z) * irradiance_probes[first_face_slot_1 + faces_0.z].xyz;
+}
+
+fn sample_ambient_radiance_0( position_8 : vec3<f32>,  normal_8 : vec3<f32>) -> vec3<f32>
+{
+    if(u32(0) == (uniforms.probe_grid_probes_count_0))
+    {
+        return uniforms.clear_color_0.xyz;
+    }
+    var last_node_0 : vec3<i32> = vec3<i32>(uniforms.probe_grid_resolution_0) - vec3<i32>(i32(1));
+    var grid_position_0 : vec3<f32> = clamp((position_8 - uniforms.probe_grid_min_0) / uniforms.probe_grid_cell_size_0, vec3<f32>(0.0f), vec3<f32>(last_node_0));
+    var base_1 : vec3<i32> = min(vec3<i32>(floor(grid_position_0)), last_node_0 - vec3<i32>(i32(1)));
+    var fraction_1 : vec3<f32> = grid_position_0 - vec3<f32>(base_1);
+    var result_10 : vec3<f32> = vec3<f32>(0.0f);
+    var corner_0 : i32 = i32(0);
+    for(;;)
+    {
+        if(corner_0 < i32(8))
+        {
+        }
+        else
         {
-            accumulated_radiance_3 = vec3<f32>(0.10000000149011612f) * throughput_4;
             break;
         }
-        var hit_material_1 : Material_0 = hitMaterial;
-        var hit_albedo_1 : vec3<f32> = fetch_albedo_0(hitRec.local_0, current_ray_1.direction_0, hitRec.t_2, hitMaterial, incident_3.differentials_0);
-        if(i32(0) == (hit_material_1.material_class_0))
+        var offset_0 : vec3<i32> = vec3<i32>(corner_0 & i32(1), (corner_0 >> u32(1)) & i32(1), (corner_0 >> u32(2)) & i32(1));
+        var axis_weights_0 : vec3<f32> = mix(vec3<f32>(1.0f) - fraction_1, fraction_1, vec3<f32>(offset_0));
+        var node_0 : vec3<u32> = vec3<u32>(base_1 + offset_0);
+        var probe_index_3 : u32 = (node_0.z * uniforms.probe_grid_resolution_0.y + node_0.y) * uniforms.probe_grid_resolution_0.x + node_0.x;
+        var _S167 : vec3<f32> = irradiance_probe_radiance_0(probe_index_3, normal_8);
+        result_10 = result_10 + vec3<f32>(axis_weights_0.x * axis_weights_0.y * axis_weights_0.z) * _S167;
+        corner_0 = corner_0 + i32(1);
+    }
+    return result_10;
+}
+
+@binding(6) @group(1) var<storage, read_write> luminance_histogram_buffer : array<atomic<u32>>;
+
+fn luminance_histogram_bin_0( color_2 : vec3<f32>) -> u32
+{
+    var luminance_0 : f32 = dot(color_2, vec3<f32>(0.21259999275207520f, 0.71520000696182251f, 0.07220000028610229f));
+    if(luminance_0 < 0.00100000004749745f)
+    {
+        return u32(0);
+    }
+    return u32(saturate((log2(luminance_0) - -10.0f) / 22.0f) * 254.0f + 1.0f);
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_luminance_histogram(@builtin(global_invocation_id) global_invocation_id_7 : vec3<u32>)
+{
+    randState = u32(0);
+    var pixel_index_7 : u32 = evaluate_pixel_index_0(global_invocation_id_7, uniforms.thread_grid_size_0);
+    if(pixel_outside_frame_buffer_0(pixel_index_7))
+    {
+        return;
+    }
+    var _S202 : u32 = atomicAdd(&(luminance_histogram_buffer[luminance_histogram_bin_0(pixel_color_buffer[pixel_index_7].xyz / vec3<f32>(uniforms.frame_number_0))]), u32(1));
+    return;
+}
+
+var<workgroup> luminance_histogram_shared : array<u32, i32(256)>;
+
+@compute
+@workgroup_size(256, 1, 1)
+fn compute_auto_exposure(@builtin(local_invocation_id) local_invocation_id_0 : vec3<u32>)
+{
+    randState = u32(0);
+    var bin_0 : u32 = local_invocation_id_0.x;
+    var bin_count_0 : u32 = atomicExchange(&(luminance_histogram_buffer[bin_0]), u32(0));
+    luminance_histogram_shared[bin_0] = bin_count_0 * bin_0;
+    workgroupBarrier();
+    var cutoff_0 : u32 = u32(128);
+    for(;;)
+    {
+        if(cutoff_0 > u32(0))
+        {
+        }
+        else
         {
-            var _S149 : vec3<f32> = evaluate_dielectric_surface_color_0(camera_origin_1, hitRec, hit_material_1, hit_albedo_1);
-            accumulated_radiance_3 = throughput_4 * _S149;
             break;
         }
-        if(i32(1) == (hit_material_1.material_class_0))
+        if(bin_0 < cutoff_0)
         {
-            var reflected_1 : vec3<f32> = evaluate_reflection_0(current_ray_1.direction_0, hitRec.global_0.normal_1, hitRec.global_0.position_2, hit_material_1.roughness_0);
-            current_ray_1.origin_2 = hitRec.global_0.position_2 + reflected_1 * vec3<f32>(0.00050000002374873f);
-            current_ray_1.direction_0 = reflected_1;
-            throughput_4 = throughput_4 * hit_albedo_1;
+            luminance_histogram_shared[bin_0] = luminance_histogram_shared[bin_0] + luminance_histogram_shared[bin_0 + cutoff_0];
+        }
+        workgroupBarrier();
+        cutoff_0 = cutoff_0 >> u32(1);
+    }
+    if(u32(0) == bin_0)
+    {
+        var lit_pixels_0 : f32 = max(f32(uniforms.frame_buffer_area_0) - f32(bin_count_0), 1.0f);
+        var measured_0 : f32 = exp2((f32(luminance_histogram_shared[i32(0)]) / lit_pixels_0 - 1.0f) / 254.0f * 22.0f + -10.0f);
+        var previous_0 : f32 = auto_exposure_buffer[i32(0)];
+        var blend_1 : f32 = 1.0f - exp(- max(uniforms.global_time_seconds_0 - auto_exposure_buffer[i32(2)], 0.0f) * uniforms.auto_exposure_adaptation_speed_0);
+        var adapted_0 : f32;
+        if(previous_0 > 0.0f)
+        {
+            adapted_0 = mix(previous_0, measured_0, blend_1);
         }
         else
         {
-            var _S150 : vec3<f32>;
-            if(i32(2) == (hit_material_1.material_class_0))
-            {
-                var _S151 : Ray_0 = glass_scatter_0(hitRec, hit_material_1.refractive_index_eta_0, current_ray_1.direction_0, false);
-                current_ray_1 = _S151;
-                current_ray_1.origin_2 = current_ray_1.origin_2 + current_ray_1.direction_0 * vec3<f32>(0.00050000002374873f);
-                _S150 = throughput_4 * hit_albedo_1;
-            }
-            else
-            {
-                accumulated_radiance_3 = hit_albedo_1;
-                break;
-            }
-            throughput_4 = _S150;
+            adapted_0 = measured_0;
         }
-        i_8 = i_8 + i32(1);
+        auto_exposure_buffer[i32(0)] = adapted_0;
+        auto_exposure_buffer[i32(1)] = clamp(0.18000000715255737f / adapted_0, uniforms.auto_exposure_min_scale_0, uniforms.auto_exposure_max_scale_0);
+        auto_exposure_buffer[i32(2)] = uniforms.global_time_seconds_0;
     }
-    return accumulated_radiance_3;
+    return;
 }
 
-fn path_trace_deterministic_0( camera_6 : Camera_0,  pixel_7 : Pixel_0) -> vec3<f32>
+@binding(8) @group(1) var<storage, read_write> reprojection_history_buffer : array<vec4<f32>>;
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_reprojection_history(@builtin(global_invocation_id) global_invocation_id_8 : vec3<u32>)
 {
-    var _S152 : u32 = uniforms.pixel_side_subdivision_0;
-    if((uniforms.pixel_side_subdivision_0) == u32(1))
+    randState = u32(0);
+    var pixel_index_8 : u32 = evaluate_pixel_index_0(global_invocation_id_8, uniforms.thread_grid_size_0);
+    if(pixel_outside_frame_buffer_0(pixel_index_8))
     {
-        var _S153 : vec3<f32> = ray_color_deterministic_0(camera_6.origin_0, ray_and_differentials_0(camera_6, pixel_7, 0.5f, 0.5f));
-        return _S153;
+        return;
     }
-    var _S154 : vec3<f32> = vec3<f32>(0.0f);
-    var _S155 : f32 = 1.0f / f32(_S152 - u32(1));
-    var i_9 : u32 = u32(0);
-    var result_color_2 : vec3<f32> = _S154;
+    reprojection_history_buffer[pixel_index_8] = vec4<f32>(pixel_color_buffer[pixel_index_8].xyz, (bitcast<f32>((object_id_buffer[pixel_index_8]))));
+    return;
+}
+
+fn previous_frame_pixel_0( world_position_0 : vec3<f32>) -> vec2<f32>
+{
+    var ray_origin_0 : vec3<f32> = (((mat4x4<f32>(uniforms.previous_view_ray_origin_matrix_col_0_0, uniforms.previous_view_ray_origin_matrix_col_1_0, uniforms.previous_view_ray_origin_matrix_col_2_0, uniforms.previous_view_ray_origin_matrix_col_3_0)) * (vec4<f32>(world_position_0, 1.0f)))).xyz;
+    var world_to_camera_0 : mat4x4<f32> = mat4x4<f32>(uniforms.previous_world_to_camera_col_0_0, uniforms.previous_world_to_camera_col_1_0, uniforms.previous_world_to_camera_col_2_0, uniforms.previous_world_to_camera_col_3_0);
+    var origin_camera_space_0 : vec3<f32> = (((world_to_camera_0) * (vec4<f32>(ray_origin_0, 1.0f)))).xyz;
+    var direction_camera_space_0 : vec3<f32> = (((world_to_camera_0) * (vec4<f32>(world_position_0 - ray_origin_0, 0.0f)))).xyz;
+    if((direction_camera_space_0.z) >= 0.0f)
+    {
+        return vec2<f32>(-1.0f);
+    }
+    var view_plane_hit_0 : vec2<f32> = origin_camera_space_0.xy + direction_camera_space_0.xy * vec2<f32>(((- uniforms.previous_camera_fov_factor_0 - origin_camera_space_0.z) / direction_camera_space_0.z));
+    return vec2<f32>((view_plane_hit_0.x / uniforms.frame_buffer_aspect_0 + 1.0f) * 0.5f * f32(uniforms.frame_buffer_size_0.x), (1.0f - view_plane_hit_0.y) * 0.5f * f32(uniforms.frame_buffer_size_0.y));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_temporal_reprojection(@builtin(global_invocation_id) global_invocation_id_9 : vec3<u32>)
+{
+    randState = u32(0);
+    var pixel_index_9 : u32 = evaluate_pixel_index_0(global_invocation_id_9, uniforms.thread_grid_size_0);
+    if(pixel_outside_frame_buffer_0(pixel_index_9))
+    {
+        return;
+    }
+    var pixel_11 : Pixel_0 = setup_pixel_coordinates_0(pixel_index_9);
+    var camera_10 : Camera_0 = setup_camera_0();
+    var ray_8 : RayAndDifferentials_0 = ray_and_differentials_0(camera_10, pixel_11, 0.5f, 0.5f);
+    var surface_1 : FirstHitSurface_0 = trace_first_intersection_0(ray_8);
+    var _S203 : vec3<f32> = pixel_color_buffer[pixel_index_9].xyz;
+    var color_3 : vec3<f32> = _S203 * vec3<f32>((uniforms.reprojection_history_frames_0 + 1.0f));
+    var previous_pixel_0 : vec2<f32> = previous_frame_pixel_0(ray_8.ray_0.origin_2 + ray_8.ray_0.direction_0 * vec3<f32>(min(surface_1.depth_0, 10000.0f)));
+    var _S204 : bool;
+    if((all((previous_pixel_0 >= vec2<f32>(0.0f)))))
+    {
+        _S204 = (all((previous_pixel_0 < vec2<f32>(uniforms.frame_buffer_size_0))));
+    }
+    else
+    {
+        _S204 = false;
+    }
+    if(_S204)
+    {
+        var history_0 : vec4<f32> = reprojection_history_buffer[u32(previous_pixel_0.y) * uniforms.frame_buffer_size_0.x + u32(previous_pixel_0.x)];
+        if((bitcast<u32>((history_0.w))) == (surface_1.object_uid_3))
+        {
+            color_3 = history_0.xyz * vec3<f32>(uniforms.reprojection_history_scale_0) + _S203;
+        }
+    }
+    pixel_color_buffer[pixel_index_9] = vec4<f32>(color_3, 1.0f);
+    return;
+}
+
+@binding(16) @group(1) var<storage, read_write> sdf_distance_grids_bake_buffer : array<f32>;
+
+@compute
+@workgroup_size(8, 8, 1)
+fn compute_sdf_distance_grids(@builtin(global_invocation_id) global_invocation_id_15 : vec3<u32>)
+{
+    randState = u32(0);
+    var index_3 : u32 = global_invocation_id_15.y * (bitcast<u32>((sdf_distance_grids_bake_buffer[i32(2)]))) + global_invocation_id_15.x;
+    if(index_3 >= (bitcast<u32>((sdf_distance_grids_bake_buffer[i32(1)]))))
+    {
+        return;
+    }
+    var values_start_0 : u32 = u32(4) + (bitcast<u32>((sdf_distance_grids_bake_buffer[i32(0)]))) * u32(8);
+    var class_index_2 : u32 = u32(0);
     for(;;)
     {
-        if(i_9 < _S152)
+        if(class_index_2 < (bitcast<u32>((sdf_distance_grids_bake_buffer[i32(0)]))))
         {
         }
         else
         {
             break;
         }
-        var j_0 : u32 = u32(0);
-        for(;;)
+        var slot_1 : u32 = u32(4) + class_index_2 * u32(8);
+        var resolution_3 : u32 = (bitcast<u32>((sdf_distance_grids_bake_buffer[slot_1 + u32(7)])));
+        var offset_0 : u32 = (bitcast<u32>((sdf_distance_grids_bake_buffer[slot_1 + u32(3)]))) - values_start_0;
+        var _S297 : bool;
+        if(resolution_3 == u32(0))
         {
-            if(j_0 < _S152)
-            {
-            }
-            else
-            {
-                break;
-            }
-            var _S156 : vec3<f32> = ray_color_deterministic_0(camera_6.origin_0, ray_and_differentials_0(camera_6, pixel_7, _S155 * f32(i_9), _S155 * f32(j_0)));
-            var result_color_3 : vec3<f32> = result_color_2 + _S156;
-            j_0 = j_0 + u32(1);
-            result_color_2 = result_color_3;
+            _S297 = true;
         }
-        i_9 = i_9 + u32(1);
+        else
+        {
+            _S297 = index_3 < offset_0;
+        }
+        if(_S297)
+        {
+            _S297 = true;
+        }
+        else
+        {
+            _S297 = index_3 >= (offset_0 + resolution_3 * resolution_3 * resolution_3);
+        }
+        if(_S297)
+        {
+            class_index_2 = class_index_2 + u32(1);
+            continue;
+        }
+        var local_index_0 : u32 = index_3 - offset_0;
+        var cell_1 : vec3<u32> = vec3<u32>(local_index_0 % resolution_3, (local_index_0 / resolution_3) % resolution_3, local_index_0 / (resolution_3 * resolution_3));
+        var grid_min_1 : vec3<f32> = vec3<f32>(sdf_distance_grids_bake_buffer[slot_1], sdf_distance_grids_bake_buffer[slot_1 + u32(1)], sdf_distance_grids_bake_buffer[slot_1 + u32(2)]);
+        var cell_size_1 : vec3<f32> = vec3<f32>(sdf_distance_grids_bake_buffer[slot_1 + u32(4)], sdf_distance_grids_bake_buffer[slot_1 + u32(5)], sdf_distance_grids_bake_buffer[slot_1 + u32(6)]);
+        sdf_parameters = vec4<f32>(0.0f);
+        var _S298 : f32 = sdf_select(i32(class_index_2), grid_min_1 + (vec3<f32>(cell_1) + vec3<f32>(0.5f)) * cell_size_1, 0.0f);
+        sdf_distance_grids_bake_buffer[values_start_0 + index_3] = abs(_S298);
+        return;
     }
-    return result_color_2 / vec3<f32>(f32(_S152 * _S152));
+    return;
+}
+
+@binding(14) @group(1) var<storage, read_write> procedural_texture_bake_buffer : array<vec4<f32>>;
+
+fn unmap_texture_delta_0( to_u_0 : vec3<f32>,  to_v_0 : vec3<f32>,  texture_delta_0 : vec2<f32>) -> vec3<f32>
+{
+    var uu_0 : f32 = dot(to_u_0, to_u_0);
+    var uv_1 : f32 = dot(to_u_0, to_v_0);
+    var vv_0 : f32 = dot(to_v_0, to_v_0);
+    var determinant_1 : f32 = uu_0 * vv_0 - uv_1 * uv_1;
+    return vec3<f32>(((texture_delta_0.x * vv_0 - texture_delta_0.y * uv_1) / determinant_1)) * to_u_0 + vec3<f32>(((texture_delta_0.y * uu_0 - texture_delta_0.x * uv_1) / determinant_1)) * to_v_0;
 }
 
 @compute
 @workgroup_size(8, 8, 1)
-fn compute_color_buffer_deterministic(@builtin(global_invocation_id) global_invocation_id_3 : vec3<u32>)
+fn compute_procedural_texture_bake(@builtin(global_invocation_id) global_invocation_id_14 : vec3<u32>)
 {
     randState = u32(0);
-    var pixel_index_5 : u32 = evaluate_pixel_index_0(global_invocation_id_3, uniforms.thread_grid_size_0);
-    if(pixel_outside_frame_buffer_0(pixel_index_5))
+    var header_0 : vec4<f32> = procedural_texture_bake_buffer[i32(0)];
+    var resolution_1 : vec2<u32> = (bitcast<vec2<u32>>((header_0.yz)));
+    if((any((global_invocation_id_14.xy >= resolution_1))))
     {
         return;
     }
-    var camera_7 : Camera_0 = setup_camera_0();
-    var pixel_8 : Pixel_0 = make_common_color_evaluation_setup_0(pixel_index_5);
-    var traced_color_1 : vec3<f32> = path_trace_deterministic_0(camera_7, pixel_8);
-    pixel_color_buffer[pixel_index_5] = vec4<f32>(traced_color_1, 1.0f);
+    var to_u_1 : vec4<f32> = procedural_texture_bake_buffer[i32(1)];
+    var to_v_1 : vec4<f32> = procedural_texture_bake_buffer[i32(2)];
+    var texel_size_0 : vec2<f32> = vec2<f32>(1.0f) / vec2<f32>(resolution_1);
+    var _S259 : vec3<f32> = to_u_1.xyz;
+    var _S260 : vec3<f32> = to_v_1.xyz;
+    var position_10 : vec3<f32> = unmap_texture_delta_0(_S259, _S260, (vec2<f32>(global_invocation_id_14.xy) + vec2<f32>(0.5f)) * texel_size_0 - vec2<f32>(to_u_1.w, to_v_1.w));
+    var normal_9 : vec3<f32> = normalize(cross(_S259, _S260));
+    var dp_dx_1 : vec3<f32> = unmap_texture_delta_0(_S259, _S260, vec2<f32>(texel_size_0.x, 0.0f));
+    var dp_dy_2 : vec3<f32> = unmap_texture_delta_0(_S259, _S260, vec2<f32>(0.0f, texel_size_0.y));
+    var color_4 : vec3<f32> = procedural_texture_select((bitcast<i32>((header_0.x))), position_10, normal_9, uniforms.global_time_seconds_0, dp_dx_1, dp_dy_2);
+    procedural_texture_bake_buffer[u32(3) + global_invocation_id_14.y * resolution_1.x + global_invocation_id_14.x] = vec4<f32>(color_4, 1.0f);
     return;
 }
-
@@ -6,6 +6,7 @@ use std::time::Instant;
 
 pub(crate) struct Animator {
     animations: HashMap<ObjectUid, Clock>,
+    camera: Option<Clock>,
     current_time: Instant,
 }
 
@@ -14,16 +15,31 @@ impl Animator {
     pub(crate) fn new() -> Self {
         Self {
             animations: HashMap::new(),
+            camera: None,
             current_time: Instant::now(),
         }
     }
 
-    pub(crate) fn remove_finished(&mut self) {
-        self.animations.retain(|_, clock| {
-            clock.ticking(self.current_time)
+    pub(crate) fn remove_finished(&mut self, finished: &mut Vec<ObjectUid>) {
+        self.animations.retain(|uid, clock| {
+            let ticking = clock.ticking(self.current_time);
+            if false == ticking {
+                finished.push(*uid);
+            }
+            ticking
         })
     }
 
+    // returns true if the camera animation has just finished
+    #[must_use]
+    pub(crate) fn remove_finished_camera(&mut self) -> bool {
+        let finished = self.camera.as_ref().is_some_and(|clock| false == clock.ticking(self.current_time));
+        if finished {
+            self.camera = None;
+        }
+        finished
+    }
+
     pub(crate) fn take_time(&mut self) {
         self.current_time = Instant::now();
     }
@@ -36,6 +52,19 @@ impl Animator {
         self.animations.remove(&target);
     }
 
+    pub(crate) fn animate_camera_time(&mut self, parameters: ClockAnimationAct<PhaseAlive>) {
+        self.camera = Some(Clock::new(self.current_time, parameters));
+    }
+
+    pub(crate) fn stop_camera(&mut self) {
+        self.camera = None;
+    }
+
+    #[must_use]
+    pub(crate) fn camera_local_time(&self) -> Option<f64> {
+        self.camera.as_ref().map(|animation| animation.local_time(self.current_time))
+    }
+
     pub(crate) fn clear(&mut self) {
         self.animations.clear();
     }
@@ -59,10 +88,13 @@ mod tests {
     fn test_empty_animator() {
         let mut system_under_test = Animator::new();
 
+        let mut finished = Vec::new();
         system_under_test.take_time();
-        system_under_test.remove_finished();
+        system_under_test.remove_finished(&mut finished);
         system_under_test.clear();
         
+        assert!(finished.is_empty());
+        
         assert_eq!(system_under_test.local_time_of(ObjectUid(0)), None);
     }
 
@@ -158,12 +190,37 @@ mod tests {
         system_under_test.animate_time(to_be_finished, animation_act);
         system_under_test.animate_time(to_be_continued, infinite_animation());
         thread::sleep(animation_duration + Duration::from_millis(1));
+        let mut finished = Vec::new();
         system_under_test.take_time();
-        system_under_test.remove_finished();
+        system_under_test.remove_finished(&mut finished);
 
         let time_after_clean = system_under_test.local_time_of(to_be_finished);
         assert!(time_after_clean.is_none(), "animation did not finished as expected");
         assert!(system_under_test.local_time_of(to_be_continued).is_some(), "infinite animation has been removed");
+        assert_eq!(finished, vec![to_be_finished]);
+    }
+
+    #[test]
+    fn test_camera_animation() {
+        let mut system_under_test = Animator::new();
+        let animation_duration = Duration::from_nanos(1);
+        let animation_act = ClockAnimationAct::new()
+            .with_global_finite_time_to_live(animation_duration, TimeDirection::Forward)
+            .make();
+
+        system_under_test.animate_camera_time(animation_act);
+        assert_eq!(system_under_test.camera_local_time(), Some(0.0));
+        assert_eq!(system_under_test.remove_finished_camera(), false);
+
+        thread::sleep(animation_duration + Duration::from_millis(1));
+        system_under_test.take_time();
+
+        assert!(system_under_test.remove_finished_camera());
+        assert_eq!(system_under_test.camera_local_time(), None);
+
+        system_under_test.animate_camera_time(infinite_animation());
+        system_under_test.stop_camera();
+        assert_eq!(system_under_test.camera_local_time(), None);
     }
 
     #[must_use]
@@ -0,0 +1,218 @@
+use crate::geometry::alias::Point;
+use cgmath::{Deg, EuclideanSpace};
+use std::ops::{Add, Mul, Sub};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    eye: Point,
+    look_at: Point,
+    fov: Deg<f64>,
+}
+
+impl CameraPose {
+    #[must_use]
+    pub fn new(eye: Point, look_at: Point, fov: Deg<f64>) -> Self {
+        Self { eye, look_at, fov }
+    }
+
+    #[must_use]
+    pub fn eye(&self) -> Point {
+        self.eye
+    }
+
+    #[must_use]
+    pub fn look_at(&self) -> Point {
+        self.look_at
+    }
+
+    #[must_use]
+    pub fn fov(&self) -> Deg<f64> {
+        self.fov
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraKeyframe {
+    time: Duration,
+    pose: CameraPose,
+}
+
+impl CameraKeyframe {
+    #[must_use]
+    pub fn new(time: Duration, pose: CameraPose) -> Self {
+        Self { time, pose }
+    }
+
+    #[must_use]
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    #[must_use]
+    pub fn pose(&self) -> CameraPose {
+        self.pose
+    }
+}
+
+// fly-through path: the eye, the look at point and the fov follow cubic Hermite splines
+// through the keyframes (Catmull-Rom tangents), so the motion has no velocity jumps at the keys;
+// the pose is a pure function of the track time, hence replays are frame-exact
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraTrack {
+    keyframes: Vec<CameraKeyframe>,
+}
+
+impl CameraTrack {
+    #[must_use]
+    pub fn new(keyframes: Vec<CameraKeyframe>) -> Self {
+        assert!(false == keyframes.is_empty(), "camera track without keyframes");
+        assert!(keyframes.windows(2).all(|pair| pair[0].time < pair[1].time), "keyframe times are not strictly increasing");
+        Self { keyframes }
+    }
+
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.keyframes.last().unwrap().time
+    }
+
+    #[must_use]
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    // the time is clamped into the keyframes span
+    #[must_use]
+    pub fn sample(&self, seconds: f64) -> CameraPose {
+        let first = self.keyframes.first().unwrap();
+        let last = self.keyframes.last().unwrap();
+        if seconds <= first.time.as_secs_f64() {
+            return first.pose;
+        }
+        if seconds >= last.time.as_secs_f64() {
+            return last.pose;
+        }
+
+        let segment = self.keyframes.partition_point(|key| key.time.as_secs_f64() <= seconds) - 1;
+        let start_time = self.keyframes[segment].time.as_secs_f64();
+        let span = self.keyframes[segment + 1].time.as_secs_f64() - start_time;
+        let local = (seconds - start_time) / span;
+
+        let eye = self.interpolate(segment, local, span, |pose| pose.eye.to_vec());
+        let look_at = self.interpolate(segment, local, span, |pose| pose.look_at.to_vec());
+        let fov = self.interpolate(segment, local, span, |pose| pose.fov.0);
+        CameraPose::new(Point::from_vec(eye), Point::from_vec(look_at), Deg(fov))
+    }
+
+    #[must_use]
+    fn interpolate<T, Value>(&self, segment: usize, local: f64, span: f64, value: Value) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f64, Output = T>,
+        Value: Fn(&CameraPose) -> T,
+    {
+        let start = value(&self.keyframes[segment].pose);
+        let end = value(&self.keyframes[segment + 1].pose);
+        let start_tangent = self.tangent(segment, &value);
+        let end_tangent = self.tangent(segment + 1, &value);
+
+        let local_squared = local * local;
+        let local_cubed = local_squared * local;
+        start * (2.0 * local_cubed - 3.0 * local_squared + 1.0)
+            + start_tangent * ((local_cubed - 2.0 * local_squared + local) * span)
+            + end * (-2.0 * local_cubed + 3.0 * local_squared)
+            + end_tangent * ((local_cubed - local_squared) * span)
+    }
+
+    // finite difference over the neighbours: one-sided at the track ends
+    #[must_use]
+    fn tangent<T, Value>(&self, key: usize, value: &Value) -> T
+    where
+        T: Copy + Sub<Output = T> + Mul<f64, Output = T>,
+        Value: Fn(&CameraPose) -> T,
+    {
+        let previous = key.saturating_sub(1);
+        let next = (key + 1).min(self.keyframes.len() - 1);
+        let delta = self.keyframes[next].time.as_secs_f64() - self.keyframes[previous].time.as_secs_f64();
+        if 0.0 == delta {
+            return value(&self.keyframes[key].pose) * 0.0;
+        }
+        (value(&self.keyframes[next].pose) - value(&self.keyframes[previous].pose)) * (1.0 / delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::assert_abs_diff_eq;
+
+    #[must_use]
+    fn make_pose(x: f64, fov: f64) -> CameraPose {
+        CameraPose::new(Point::new(x, 1.0, 5.0), Point::new(x, 0.0, 0.0), Deg(fov))
+    }
+
+    #[must_use]
+    fn make_track() -> CameraTrack {
+        CameraTrack::new(vec![
+            CameraKeyframe::new(Duration::from_secs(0), make_pose(0.0, 60.0)),
+            CameraKeyframe::new(Duration::from_secs(1), make_pose(1.0, 50.0)),
+            CameraKeyframe::new(Duration::from_secs(3), make_pose(3.0, 40.0)),
+        ])
+    }
+
+    #[test]
+    fn test_sample_at_keyframes() {
+        let system_under_test = make_track();
+
+        for key in system_under_test.keyframes() {
+            let pose = system_under_test.sample(key.time().as_secs_f64());
+            assert_abs_diff_eq!(pose.eye(), key.pose().eye(), epsilon = 1e-12);
+            assert_abs_diff_eq!(pose.look_at(), key.pose().look_at(), epsilon = 1e-12);
+            assert_abs_diff_eq!(pose.fov().0, key.pose().fov().0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_sample_outside_of_track() {
+        let system_under_test = make_track();
+
+        assert_eq!(system_under_test.sample(-1.0), make_pose(0.0, 60.0));
+        assert_eq!(system_under_test.sample(7.0), make_pose(3.0, 40.0));
+        assert_eq!(system_under_test.duration(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_linear_motion_is_reproduced() {
+        let system_under_test = make_track();
+
+        for seconds in [0.25, 0.5, 1.5, 2.0, 2.75] {
+            let pose = system_under_test.sample(seconds);
+            assert_abs_diff_eq!(pose.eye().x, seconds, epsilon = 1e-12);
+            assert_abs_diff_eq!(pose.look_at().x, seconds, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_smooth_at_keyframe() {
+        let system_under_test = CameraTrack::new(vec![
+            CameraKeyframe::new(Duration::from_secs(0), make_pose(0.0, 60.0)),
+            CameraKeyframe::new(Duration::from_secs(1), make_pose(2.0, 60.0)),
+            CameraKeyframe::new(Duration::from_secs(2), make_pose(0.0, 60.0)),
+        ]);
+        let epsilon = 1e-6;
+
+        let before = system_under_test.sample(1.0 - epsilon).eye().x;
+        let at = system_under_test.sample(1.0).eye().x;
+        let after = system_under_test.sample(1.0 + epsilon).eye().x;
+
+        assert_abs_diff_eq!((at - before) / epsilon, (after - at) / epsilon, epsilon = 1e-4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unordered_keyframes() {
+        let _ = CameraTrack::new(vec![
+            CameraKeyframe::new(Duration::from_secs(1), make_pose(0.0, 60.0)),
+            CameraKeyframe::new(Duration::from_secs(1), make_pose(1.0, 60.0)),
+        ]);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::animation::clock_animation_act::{ClockAnimationAct, EndActionKind, PhaseAlive, WrapKind};
+use crate::animation::easing::Easing;
 use std::time::{Duration, Instant};
 
 pub(super) struct Clock {
@@ -19,7 +20,7 @@ impl Clock {
     pub(super) fn ticking(&self, global_time: Instant) -> bool {
         self.parameters.get_time_to_live()
             .is_none_or( 
-                |time_to_live| global_time.duration_since(self.global_clock_start) < time_to_live.span())
+                |time_to_live| global_time.duration_since(self.global_clock_start) < self.parameters.get_start_delay() + time_to_live.span())
     }
     
     #[must_use]
@@ -36,7 +37,7 @@ impl Clock {
 
     #[must_use]
     fn local_forward_time(&self, global_time: Instant) -> f64 {
-        let global_elapsed = global_time.duration_since(self.global_clock_start);
+        let global_elapsed = global_time.duration_since(self.global_clock_start).saturating_sub(self.parameters.get_start_delay());
 
         if let Some(time_to_live) = self.parameters.get_time_to_live()
             && global_elapsed > time_to_live.span() {
@@ -54,12 +55,12 @@ impl Clock {
     fn evaluate_time_point(&self, global_elapsed: Duration) -> f64 {
         let local_tile_offset = self.parameters.get_birth_time_offset().as_secs_f64();
         let local_time_multiplier = self.parameters.get_playback_speed_multiplier();
-        let local_elapsed = local_tile_offset + global_elapsed.as_secs_f64() * local_time_multiplier;
 
         if let Some(periodization) = self.parameters.get_periodization() {
+            let local_elapsed = local_tile_offset + global_elapsed.as_secs_f64() * local_time_multiplier;
             let period = periodization.period().as_secs_f64();
             let period_count: i64 = (local_elapsed / period) as i64;
-            let local_period_rest = local_elapsed - (period_count as f64) * period;
+            let local_period_rest = self.ease(local_elapsed - (period_count as f64) * period, period);
             match periodization.wrap_kind() {
                 WrapKind::Restart => local_period_rest,
                 WrapKind::Reverse => {
@@ -70,10 +71,22 @@ impl Clock {
                     }
                 }
             }
+        } else if let Some(time_to_live) = self.parameters.get_time_to_live() {
+            let span = time_to_live.span().as_secs_f64();
+            local_tile_offset + self.ease(global_elapsed.as_secs_f64(), span) * local_time_multiplier
         } else {
-            local_elapsed
+            local_tile_offset + global_elapsed.as_secs_f64() * local_time_multiplier
         }
     }
+
+    #[must_use]
+    fn ease(&self, elapsed: f64, span: f64) -> f64 {
+        let easing = self.parameters.get_easing();
+        if Easing::Linear == easing || span <= 0.0 {
+            return elapsed;
+        }
+        span * easing.apply(elapsed / span)
+    }
 }
 
 #[cfg(test)]
@@ -359,4 +372,54 @@ mod tests {
         
         assert!((result - elapsed.as_secs_f64()).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_local_time_with_start_delay() {
+        let start = Instant::now();
+        let delay = Duration::from_secs(2);
+        let life_span = Duration::from_secs(3);
+
+        let animation = ClockAnimationAct::new()
+            .start_delay(delay)
+            .with_global_finite_time_to_live(life_span, TimeDirection::Forward)
+            .make();
+        let system_under_test = Clock::new(start, animation);
+
+        assert_eq!(system_under_test.local_time(start + Duration::from_secs(1)), 0.0);
+        assert_eq!(system_under_test.local_time(start + Duration::from_secs(3)), 1.0);
+        assert!(system_under_test.ticking(start + Duration::from_secs(4)));
+        assert_eq!(system_under_test.ticking(start + delay + life_span), false);
+    }
+
+    #[test]
+    fn test_local_time_eased_over_life_span() {
+        let start = Instant::now();
+
+        let animation = ClockAnimationAct::new()
+            .playback_speed_multiplier(2.0)
+            .with_global_finite_time_to_live(Duration::from_secs(4), TimeDirection::Forward)
+            .easing(Easing::QuadraticIn)
+            .make();
+        let system_under_test = Clock::new(start, animation);
+
+        assert_eq!(system_under_test.local_time(start + Duration::from_secs(2)), 2.0);
+        assert_eq!(system_under_test.local_time(start + Duration::from_secs(4)), 8.0);
+    }
+
+    #[test]
+    fn test_local_time_eased_per_period() {
+        let start = Instant::now();
+        let periodization = Periodization::new(WrapKind::Reverse, Duration::from_secs(4));
+
+        let animation = ClockAnimationAct::new()
+            .periodization(Some(periodization))
+            .easing(Easing::QuadraticOut)
+            .make();
+        let system_under_test = Clock::new(start, animation);
+
+        // first period: 4 * ease(0.5)
+        assert_eq!(system_under_test.local_time(start + Duration::from_secs(2)), 3.0);
+        // second period runs back: 4 - 4 * ease(0.25)
+        assert_eq!(system_under_test.local_time(start + Duration::from_secs(5)), 2.25);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::animation::easing::Easing;
 use more_asserts::assert_gt;
 use std::marker::PhantomData;
 use std::time::Duration;
@@ -10,10 +11,12 @@ pub struct PhaseAlive;
 #[derive(Clone, PartialEq, Debug)]
 pub struct ClockAnimationAct<Phase = PhaseConstruction> {
     birth_time_offset: Duration,
+    start_delay: Duration,
     time_to_live: Option<LifeSpan>,
     local_playback_speed_multiplier: f64,
     periodization: Option<Periodization>,
     end_action: EndActionKind,
+    easing: Easing,
 
     phase: PhantomData<Phase>,
 }
@@ -93,10 +96,12 @@ impl ClockAnimationAct<PhaseConstruction> {
         
         ClockAnimationAct::<PhaseAlive> {
             birth_time_offset: self.birth_time_offset,
+            start_delay: self.start_delay,
             time_to_live: self.time_to_live,
             local_playback_speed_multiplier: self.local_playback_speed_multiplier,
             periodization: self.periodization,
             end_action: self.end_action,
+            easing: self.easing,
 
             phase: PhantomData,
         }
@@ -106,10 +111,12 @@ impl ClockAnimationAct<PhaseConstruction> {
     pub fn new() -> Self {
         Self {
             birth_time_offset: Duration::ZERO,
+            start_delay: Duration::ZERO,
             time_to_live: Self::INFINITY,
             local_playback_speed_multiplier: 1.0,
             periodization: None,
             end_action: EndActionKind::LeaveAsIs,
+            easing: Easing::Linear,
 
             phase: PhantomData,
         }
@@ -121,6 +128,13 @@ impl ClockAnimationAct<PhaseConstruction> {
         self
     }
 
+    // global time the local time stays at the birth offset; the time to live is counted after the delay
+    #[must_use]
+    pub fn start_delay(mut self, value: Duration) -> Self {
+        self.start_delay = value;
+        self
+    }
+
     #[must_use]
     pub fn with_global_finite_time_to_live(mut self, span: Duration, direction: TimeDirection) -> Self {
         self.time_to_live = Some(LifeSpan{span, direction});
@@ -151,6 +165,13 @@ impl ClockAnimationAct<PhaseConstruction> {
         self.end_action = action;
         self
     }
+
+    // applied per period for the periodic animations and over the whole life span for the finite ones
+    #[must_use]
+    pub fn easing(mut self, value: Easing) -> Self {
+        self.easing = value;
+        self
+    }
 }
 
 impl<Phase> ClockAnimationAct<Phase> {
@@ -159,6 +180,11 @@ impl<Phase> ClockAnimationAct<Phase> {
         self.birth_time_offset
     }
 
+    #[must_use]
+    pub(crate) fn get_start_delay(&self) -> Duration {
+        self.start_delay
+    }
+
     #[must_use]
     pub(crate) fn get_time_to_live(&self) -> Option<LifeSpan> {
         self.time_to_live
@@ -178,6 +204,11 @@ impl<Phase> ClockAnimationAct<Phase> {
     pub(crate) fn get_end_action(&self) -> EndActionKind {
         self.end_action
     }
+
+    #[must_use]
+    pub(crate) fn get_easing(&self) -> Easing {
+        self.easing
+    }
 }
 
 impl Default for ClockAnimationAct<PhaseConstruction> {
@@ -208,12 +239,16 @@ mod tests {
         assert_eq!(system_under_test.get_playback_speed_multiplier(), 1.0);
         assert_eq!(system_under_test.get_periodization()            , None);
         assert_eq!(system_under_test.get_end_action()               , EndActionKind::LeaveAsIs);
+        assert_eq!(system_under_test.get_start_delay()              , Duration::ZERO);
+        assert_eq!(system_under_test.get_easing()                   , Easing::Linear);
         
         assert_eq!(default_alive.get_birth_time_offset()            , Duration::ZERO);
         assert_eq!(default_alive.get_time_to_live()                 , None);
         assert_eq!(default_alive.get_playback_speed_multiplier()    , 1.0);
         assert_eq!(default_alive.get_periodization()                , None);
         assert_eq!(default_alive.get_end_action()                   , EndActionKind::LeaveAsIs);
+        assert_eq!(default_alive.get_start_delay()                  , Duration::ZERO);
+        assert_eq!(default_alive.get_easing()                       , Easing::Linear);
     }
 
     #[test]
@@ -224,6 +259,8 @@ mod tests {
         let expected_ttl_direction = TimeDirection::Backward;
         let expected_periodization = Some(Periodization::new(WrapKind::Reverse, Duration::from_secs(7)));
         let expected_end_action = EndActionKind::LeaveAsIs;
+        let expected_start_delay = Duration::from_secs(2);
+        let expected_easing = Easing::SmoothStep;
 
         let system_under_test = ClockAnimationAct::new()
             .birth_time_offset(expected_birth_time_offset)
@@ -231,6 +268,8 @@ mod tests {
             .playback_speed_multiplier(expected_speed_multiplier)
             .periodization(expected_periodization)
             .end_action(expected_end_action)
+            .start_delay(expected_start_delay)
+            .easing(expected_easing)
             ;
         
         assert_eq!(system_under_test.get_birth_time_offset(), expected_birth_time_offset);
@@ -238,6 +277,8 @@ mod tests {
         assert_eq!(system_under_test.get_playback_speed_multiplier(), expected_speed_multiplier);
         assert_eq!(system_under_test.get_periodization(), expected_periodization);
         assert_eq!(system_under_test.get_end_action(), expected_end_action);
+        assert_eq!(system_under_test.get_start_delay(), expected_start_delay);
+        assert_eq!(system_under_test.get_easing(), expected_easing);
     }
 
     #[test]
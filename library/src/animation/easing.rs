@@ -0,0 +1,59 @@
+// maps the progress of an animation cycle (from zero to one) onto the eased progress
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadraticIn,
+    QuadraticOut,
+    QuadraticInOut,
+    SmoothStep,
+}
+
+impl Easing {
+    #[must_use]
+    pub fn apply(&self, progress: f64) -> f64 {
+        let t = progress.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadraticIn => t * t,
+            Easing::QuadraticOut => t * (2.0 - t),
+            Easing::QuadraticInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - 2.0 * (1.0 - t) * (1.0 - t)
+                }
+            },
+            Easing::SmoothStep => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Easing::Linear)]
+    #[case(Easing::QuadraticIn)]
+    #[case(Easing::QuadraticOut)]
+    #[case(Easing::QuadraticInOut)]
+    #[case(Easing::SmoothStep)]
+    fn test_end_points(#[case] system_under_test: Easing) {
+        assert_eq!(system_under_test.apply(0.0), 0.0);
+        assert_eq!(system_under_test.apply(1.0), 1.0);
+        assert_eq!(system_under_test.apply(-1.0), 0.0);
+        assert_eq!(system_under_test.apply(2.0), 1.0);
+    }
+
+    #[rstest]
+    #[case(Easing::Linear        , 0.25  )]
+    #[case(Easing::QuadraticIn   , 0.0625)]
+    #[case(Easing::QuadraticOut  , 0.4375)]
+    #[case(Easing::QuadraticInOut, 0.125 )]
+    #[case(Easing::SmoothStep    , 0.15625)]
+    fn test_quarter_progress(#[case] system_under_test: Easing, #[case] expected: f64) {
+        assert_eq!(system_under_test.apply(0.25), expected);
+    }
+}
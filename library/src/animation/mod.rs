@@ -1,4 +1,7 @@
 pub mod clock_animation_act;
 pub mod time_tracker;
+pub mod easing;
+pub mod play_mode;
+pub mod camera_track;
 pub(crate) mod clock;
 pub(crate) mod animator;
\ No newline at end of file
@@ -0,0 +1,139 @@
+use crate::animation::clock_animation_act::{ClockAnimationAct, EndActionKind, Periodization, PhaseAlive, TimeDirection, WrapKind};
+use crate::animation::easing::Easing;
+use more_asserts::assert_gt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayMode {
+    Infinite,
+    // plays for the given global duration, then the end action decides where the time stays
+    Single { duration: Duration, end_action: EndActionKind },
+    // the local time runs forth and back within the period; none duration - forever
+    BackAndForth { period: Duration, duration: Option<Duration> },
+}
+
+// the playback settings of a tracked object: the time tracker keeps them, so an animation
+// can be (re)started by the object uid alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObjectAnimation {
+    play_mode: PlayMode,
+    speed: f64,
+    delay: Duration,
+    easing: Easing,
+}
+
+impl ObjectAnimation {
+    #[must_use]
+    pub fn new(play_mode: PlayMode) -> Self {
+        Self { play_mode, speed: 1.0, delay: Duration::ZERO, easing: Easing::Linear }
+    }
+
+    #[must_use]
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        assert_gt!(speed, 0.0);
+        self.speed = speed;
+        self
+    }
+
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    #[must_use]
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    #[must_use]
+    pub fn play_mode(&self) -> PlayMode {
+        self.play_mode
+    }
+
+    #[must_use]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    #[must_use]
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    #[must_use]
+    pub fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    #[must_use]
+    pub(crate) fn make_act(&self) -> ClockAnimationAct<PhaseAlive> {
+        let act = ClockAnimationAct::new()
+            .playback_speed_multiplier(self.speed)
+            .start_delay(self.delay)
+            .easing(self.easing);
+
+        match self.play_mode {
+            PlayMode::Infinite => act,
+            PlayMode::Single { duration, end_action } => act
+                .with_global_finite_time_to_live(duration, TimeDirection::Forward)
+                .end_action(end_action),
+            PlayMode::BackAndForth { period, duration } => {
+                let act = act.periodization(Some(Periodization::new(WrapKind::Reverse, period)));
+                match duration {
+                    Some(duration) => act.with_global_finite_time_to_live(duration, TimeDirection::Forward),
+                    None => act,
+                }
+            },
+        }.make()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_settings() {
+        let system_under_test = ObjectAnimation::new(PlayMode::Infinite);
+
+        let act = system_under_test.make_act();
+
+        assert_eq!(act, ClockAnimationAct::<PhaseAlive>::default());
+    }
+
+    #[test]
+    fn test_single_play_mode() {
+        let duration = Duration::from_secs(2);
+        let system_under_test = ObjectAnimation::new(PlayMode::Single { duration, end_action: EndActionKind::TeleportToZero })
+            .with_speed(3.0)
+            .with_delay(Duration::from_millis(500))
+            .with_easing(Easing::SmoothStep);
+
+        let act = system_under_test.make_act();
+
+        assert_eq!(act.get_time_to_live().map(|ttl| ttl.span()), Some(duration));
+        assert_eq!(act.get_end_action(), EndActionKind::TeleportToZero);
+        assert_eq!(act.get_playback_speed_multiplier(), 3.0);
+        assert_eq!(act.get_start_delay(), Duration::from_millis(500));
+        assert_eq!(act.get_easing(), Easing::SmoothStep);
+    }
+
+    #[test]
+    fn test_back_and_forth_play_mode() {
+        let period = Duration::from_secs(3);
+        let system_under_test = ObjectAnimation::new(PlayMode::BackAndForth { period, duration: None });
+
+        let act = system_under_test.make_act();
+
+        assert_eq!(act.get_periodization(), Some(Periodization::new(WrapKind::Reverse, period)));
+        assert_eq!(act.get_time_to_live(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_speed() {
+        let _ = ObjectAnimation::new(PlayMode::Infinite).with_speed(0.0);
+    }
+}
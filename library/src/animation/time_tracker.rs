@@ -1,20 +1,27 @@
 use crate::animation::animator::Animator;
+use crate::animation::camera_track::{CameraPose, CameraTrack};
 use crate::animation::clock_animation_act::{ClockAnimationAct, PhaseAlive};
+use crate::animation::play_mode::ObjectAnimation;
 use crate::utils::object_uid::ObjectUid;
 use crate::utils::version::Version;
 use more_asserts::assert_ge;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 pub struct TimeTracker {
     animator: Animator,
     tracked: HashMap<ObjectUid, Animatable>,
+    // ordered, so the last one tells the slots count
+    taken_slots: BTreeSet<usize>,
+    finished: Vec<ObjectUid>,
     version: Version,
+    camera_track: Option<CameraTrack>,
+    camera_pose: Option<CameraPose>,
 }
 
 impl TimeTracker {
     #[must_use]
     pub(crate) fn new() -> Self {
-        Self { animator: Animator::new(), tracked: HashMap::new(), version:Version(0) }
+        Self { animator: Animator::new(), tracked: HashMap::new(), taken_slots: BTreeSet::new(), finished: Vec::new(), version:Version(0), camera_track: None, camera_pose: None }
     }
     
     pub(crate) fn update_time(&mut self) {
@@ -27,42 +34,101 @@ impl TimeTracker {
         if any_updated {
             self.version += 1;
         }
-        self.animator.remove_finished();
+        if let (Some(track), Some(time)) = (&self.camera_track, self.animator.camera_local_time()) {
+            self.camera_pose = Some(track.sample(time));
+        }
+        self.animator.remove_finished(&mut self.finished);
+        if self.animator.remove_finished_camera() {
+            self.camera_track = None;
+        }
+    }
+
+    // objects whose finite animations have run to the end since the previous call;
+    // stopped or relaunched animations are not reported
+    #[must_use]
+    pub fn take_finished(&mut self) -> Vec<ObjectUid> {
+        std::mem::take(&mut self.finished)
     }
 
     pub fn launch(&mut self, target: ObjectUid, parameters: ClockAnimationAct<PhaseAlive>) {
         assert!(self.tracked.contains_key(&target));
+        self.finished.retain(|uid| *uid != target);
         self.animator.animate_time(target, parameters);
     }
     
+    // the settings are kept until the object is forgotten; they are used by 'play'
+    pub fn configure(&mut self, target: ObjectUid, animation: ObjectAnimation) {
+        self.tracked.get_mut(&target).unwrap_or_else(|| panic!("unknown object uid {target}")).set_animation(animation);
+    }
+
+    #[must_use]
+    pub fn configured_animation(&self, target: ObjectUid) -> Option<ObjectAnimation> {
+        self.tracked.get(&target).and_then(|animatable| animatable.animation())
+    }
+
+    // returns false if the target has no configured animation
+    pub fn play(&mut self, target: ObjectUid) -> bool {
+        match self.configured_animation(target) {
+            Some(animation) => {
+                self.launch(target, animation.make_act());
+                true
+            },
+            None => false,
+        }
+    }
+
     pub fn stop(&mut self, target: ObjectUid) {
         assert!(self.tracked.contains_key(&target));
+        self.finished.retain(|uid| *uid != target);
         self.animator.stop(target);
     }
     
+    // the track time is produced by the animation clock, so the speed, the delay, the easing
+    // and the play mode apply; the camera takes the sampled pose at each frame start
+    pub fn play_camera_track(&mut self, track: CameraTrack, animation: ObjectAnimation) {
+        self.camera_track = Some(track);
+        self.camera_pose = None;
+        self.animator.animate_camera_time(animation.make_act());
+    }
+
+    // the camera stays where the track has left it
+    pub fn stop_camera_track(&mut self) {
+        self.camera_track = None;
+        self.camera_pose = None;
+        self.animator.stop_camera();
+    }
+
+    #[must_use]
+    pub fn camera_track_playing(&self) -> bool {
+        self.camera_track.is_some()
+    }
+
+    // the pose sampled by the last 'update_time' call, if not taken yet
+    #[must_use]
+    pub(crate) fn take_camera_pose(&mut self) -> Option<CameraPose> {
+        self.camera_pose.take()
+    }
+
     #[must_use]
     pub fn animating(&self, target: ObjectUid) -> bool {
         self.animator.local_time_of(target).is_some()
     }
     
-    pub(crate) fn track(&mut self, target: ObjectUid, new_order: &[ObjectUid]) {
-        assert_eq!(new_order.len(), self.tracked.len()+1);
-        
-        self.tracked.insert(target, Animatable::new(0));
-        self.update_indices(new_order);
-        
+    // the slot is the object position in the gpu buffers; it does not change while the object lives
+    pub(crate) fn track(&mut self, target: ObjectUid, slot: usize) {
+        assert!(self.taken_slots.insert(slot), "slot {slot} is already taken");
+        if let Some(replaced) = self.tracked.insert(target, Animatable::new(slot)) {
+            self.taken_slots.remove(&replaced.index());
+        }
         self.version += 1;
     }
     
-    pub(crate) fn forget(&mut self, target: ObjectUid, new_order: &[ObjectUid]) {
-        if self.tracked.remove(&target).is_some() {
-            assert_eq!(new_order.len(), self.tracked.len());
+    pub(crate) fn forget(&mut self, target: ObjectUid) {
+        if let Some(forgotten) = self.tracked.remove(&target) {
+            self.taken_slots.remove(&forgotten.index());
+            self.finished.retain(|uid| *uid != target);
             self.animator.stop(target);
-            self.update_indices(new_order);
             self.version += 1;
-        } else {
-            assert_eq!(new_order.len(), self.tracked.len());
-            self.update_indices(new_order);
         }
     }
     
@@ -71,6 +137,8 @@ impl TimeTracker {
             return;
         }
         self.tracked.clear();
+        self.taken_slots.clear();
+        self.finished.clear();
         self.animator.clear();
         self.version += 1;
     }
@@ -82,36 +150,58 @@ impl TimeTracker {
         }
     }
 
-    fn update_indices(&mut self, new_order: &[ObjectUid]) {
-        for (index, uid) in new_order.iter().enumerate() {
-            if let Some(animatable)  = self.tracked.get_mut(uid) {
-                animatable.set_index(index);
-            } else {
-                panic!("unknown object uid {uid}");
-            } 
+    // the fraction goes from the previous 'update_time' call (zero) to the last one (one)
+    pub(crate) fn write_sub_frame_times(&self, target: &mut [f32], fraction: f64) {
+        assert_ge!(target.len(), self.tracked.len());
+        for animatable in self.tracked.values() {
+            target[animatable.index()] = animatable.sub_frame_time(fraction);
         }
     }
 
+    // true if any time was changed by the last 'update_time' call
+    #[must_use]
+    pub(crate) fn in_motion(&self) -> bool {
+        self.tracked.values().any(|animatable| animatable.in_motion())
+    }
+
     #[must_use]
     pub(crate) fn version(&self) -> Version {
         self.version
     }
     
+    #[cfg(test)]
     #[must_use]
     pub(crate) fn tracked_count(&self) -> usize {
         self.tracked.len()
     }
+
+    // the length of the times array: the slots of the deleted objects are kept as holes
+    #[must_use]
+    pub(crate) fn slots_count(&self) -> usize {
+        self.taken_slots.last().map_or(0, |slot| slot + 1)
+    }
 }
 
 struct Animatable {
+    previous_time: f64,
     time: f64,
     index: usize,
+    animation: Option<ObjectAnimation>,
 }
 
 impl Animatable {
     #[must_use]
     fn new(index: usize) -> Self {
-        Self { time: 0.0, index }
+        Self { previous_time: 0.0, time: 0.0, index, animation: None }
+    }
+
+    fn set_animation(&mut self, animation: ObjectAnimation) {
+        self.animation = Some(animation);
+    }
+
+    #[must_use]
+    fn animation(&self) -> Option<ObjectAnimation> {
+        self.animation
     }
     
     #[must_use]
@@ -119,15 +209,22 @@ impl Animatable {
         self.time as f32
     }
     
+    #[must_use]
+    fn sub_frame_time(&self, fraction: f64) -> f32 {
+        (self.previous_time + (self.time - self.previous_time) * fraction) as f32
+    }
+
+    #[must_use]
+    fn in_motion(&self) -> bool {
+        self.previous_time != self.time
+    }
+
     #[must_use]
     fn update_time(&mut self, new_time: Option<f64>) -> bool {
+        self.previous_time = self.time;
         new_time.is_some_and(|new| { self.time = new; true })
     }
     
-    fn set_index(&mut self, index: usize) {
-        self.index = index;
-    }
-
     #[must_use]
     fn index(&self) -> usize {
         self.index
@@ -138,12 +235,81 @@ impl Animatable {
 mod tests {
     use super::*;
     use crate::animation::clock_animation_act::{ClockAnimationAct, EndActionKind, TimeDirection};
+    use crate::animation::camera_track::CameraKeyframe;
+    use crate::animation::play_mode::PlayMode;
+    use crate::geometry::alias::Point;
     use crate::utils::object_uid::ObjectUid;
+    use cgmath::{Deg, EuclideanSpace};
     use crate::utils::tests::assert_utils::tests::assert_all_unique;
     use more_asserts::assert_gt;
     use std::thread;
     use std::time::Duration;
 
+    #[test]
+    fn test_play_configured_animation() {
+        let mut system_under_test = TimeTracker::new();
+        let configured = ObjectUid(0);
+        let plain = ObjectUid(1);
+        system_under_test.track(configured, 0);
+        system_under_test.track(plain, 1);
+        let animation = ObjectAnimation::new(PlayMode::Infinite).with_speed(2.0);
+
+        system_under_test.configure(configured, animation);
+
+        assert_eq!(system_under_test.configured_animation(configured), Some(animation));
+        assert!(system_under_test.play(configured));
+        assert!(system_under_test.animating(configured));
+        assert_eq!(system_under_test.play(plain), false);
+        assert_eq!(system_under_test.animating(plain), false);
+        assert_eq!(system_under_test.play(ObjectUid(17)), false);
+    }
+
+    #[test]
+    fn test_play_camera_track() {
+        let mut system_under_test = TimeTracker::new();
+        let start = CameraPose::new(Point::new(0.0, 0.0, 5.0), Point::origin(), Deg(60.0));
+        let end = CameraPose::new(Point::new(5.0, 0.0, 5.0), Point::origin(), Deg(30.0));
+        let track = CameraTrack::new(vec![CameraKeyframe::new(Duration::ZERO, start), CameraKeyframe::new(Duration::from_millis(1), end)]);
+        let animation = ObjectAnimation::new(PlayMode::Single { duration: track.duration(), end_action: EndActionKind::TeleportToEnd });
+
+        system_under_test.play_camera_track(track, animation);
+        assert!(system_under_test.camera_track_playing());
+        assert_eq!(system_under_test.take_camera_pose(), None);
+
+        thread::sleep(Duration::from_millis(3));
+        system_under_test.update_time();
+
+        assert_eq!(system_under_test.take_camera_pose(), Some(end));
+        assert_eq!(system_under_test.take_camera_pose(), None);
+        assert_eq!(system_under_test.camera_track_playing(), false);
+    }
+
+    #[test]
+    fn test_stop_camera_track() {
+        let mut system_under_test = TimeTracker::new();
+        let pose = CameraPose::new(Point::new(0.0, 0.0, 5.0), Point::origin(), Deg(60.0));
+        let track = CameraTrack::new(vec![CameraKeyframe::new(Duration::ZERO, pose)]);
+        system_under_test.play_camera_track(track, ObjectAnimation::new(PlayMode::Infinite));
+
+        system_under_test.stop_camera_track();
+        system_under_test.update_time();
+
+        assert_eq!(system_under_test.camera_track_playing(), false);
+        assert_eq!(system_under_test.take_camera_pose(), None);
+    }
+
+    #[test]
+    fn test_forget_configured_animation() {
+        let mut system_under_test = TimeTracker::new();
+        let uid = ObjectUid(0);
+        system_under_test.track(uid, 0);
+        system_under_test.configure(uid, ObjectAnimation::new(PlayMode::Infinite));
+
+        system_under_test.forget(uid);
+
+        assert_eq!(system_under_test.configured_animation(uid), None);
+    }
+
     #[test]
     fn test_empty_time_tracker() {
         let mut system_under_test = TimeTracker::new();
@@ -165,8 +331,7 @@ mod tests {
         let version_before = system_under_test.version();
 
         let uid = ObjectUid(0);
-        let order = vec![uid];
-        system_under_test.track(uid, &order);
+        system_under_test.track(uid, 0);
 
         let version_after_track = system_under_test.version();
 
@@ -191,7 +356,7 @@ mod tests {
 
         for i in 0..uids.len() {
             let count_after_add = i + 1;
-            system_under_test.track(uids[i], &uids[..count_after_add]);
+            system_under_test.track(uids[i], i);
             versions.push(system_under_test.version());
             assert_eq!(system_under_test.tracked_count(), count_after_add);
         }
@@ -209,8 +374,8 @@ mod tests {
         let to_continue = ObjectUid(7);
         let to_stop = ObjectUid(5);
 
-        system_under_test.track(to_continue, &[to_continue]);
-        system_under_test.track(to_stop, &[to_continue, to_stop]);
+        system_under_test.track(to_continue, 0);
+        system_under_test.track(to_stop, 1);
 
         system_under_test.stop(to_continue);
         system_under_test.stop(to_stop);
@@ -236,19 +401,44 @@ mod tests {
         let to_keep = ObjectUid(7);
         let to_forget = ObjectUid(5);
 
-        system_under_test.track(to_keep, &[to_keep]);
-        system_under_test.track(to_forget, &[to_keep, to_forget]);
+        system_under_test.track(to_keep, 0);
+        system_under_test.track(to_forget, 1);
 
         let version_before = system_under_test.version();
-        system_under_test.forget(to_keep, &[to_forget]);
+        system_under_test.forget(to_keep);
         let version_after = system_under_test.version();
 
         assert_ne!(version_before, version_after);
         assert_eq!(system_under_test.tracked_count(), 1);
+        assert_eq!(system_under_test.slots_count(), 2);
 
-        let mut times = vec![-5.0f32; 1];
+        let mut times = vec![-5.0f32; 2];
         system_under_test.write_times(&mut times);
-        assert_eq!(times, [0.0_f32]);
+        assert_eq!(times, [-5.0_f32, 0.0_f32]);
+    }
+
+    #[test]
+    fn test_forget_object_of_last_slot() {
+        let mut system_under_test = TimeTracker::new();
+        let to_keep = ObjectUid(7);
+        let to_forget = ObjectUid(5);
+        system_under_test.track(to_keep, 0);
+        system_under_test.track(to_forget, 1);
+
+        system_under_test.forget(to_forget);
+        assert_eq!(system_under_test.slots_count(), 1);
+
+        system_under_test.track(ObjectUid(9), 1);
+        assert_eq!(system_under_test.slots_count(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_track_into_taken_slot() {
+        let mut system_under_test = TimeTracker::new();
+
+        system_under_test.track(ObjectUid(1), 0);
+        system_under_test.track(ObjectUid(2), 0);
     }
 
     #[test]
@@ -257,8 +447,8 @@ mod tests {
         let first = ObjectUid(0);
         let second = ObjectUid(1);
 
-        system_under_test.track(first, &[first]);
-        system_under_test.track(second, &[first, second]);
+        system_under_test.track(first, 0);
+        system_under_test.track(second, 1);
 
         let version_before = system_under_test.version();
         system_under_test.clear();
@@ -276,12 +466,12 @@ mod tests {
         let animated = ObjectUid(13);
         let animation = ClockAnimationAct::default();
 
-        system_under_test.track(animated, &[animated]);
+        system_under_test.track(animated, 0);
         let version_before_launch = system_under_test.version();
         system_under_test.launch(animated, animation);
         let still = ObjectUid(17);
         let version_after_launch = system_under_test.version();
-        system_under_test.track(still, &[animated, still]);
+        system_under_test.track(still, 1);
         
         let mut times = vec![-5.0f32; system_under_test.tracked_count()];
         system_under_test.write_times(&mut times);
@@ -305,7 +495,7 @@ mod tests {
         let expected_duration = Duration::from_millis(1);
         let one_ms_animation = finite_animation(expected_duration);
 
-        system_under_test.track(uid, &[uid]);
+        system_under_test.track(uid, 0);
         system_under_test.launch(uid, infinite_animation);
         system_under_test.launch(uid, one_ms_animation);
         thread::sleep(expected_duration + Duration::from_millis(3));
@@ -322,8 +512,8 @@ mod tests {
         let tiny_uid = ObjectUid(13);
         let huge_uid = ObjectUid(31);
         
-        system_under_test.track(tiny_uid, &[tiny_uid]);
-        system_under_test.track(huge_uid, &[huge_uid, tiny_uid]);
+        system_under_test.track(tiny_uid, 1);
+        system_under_test.track(huge_uid, 0);
 
         let tiny_uid_time = Duration::from_micros(3);
         system_under_test.launch(tiny_uid, finite_animation(tiny_uid_time));
@@ -340,6 +530,61 @@ mod tests {
         assert_eq!(buffer, [huge_uid_time.as_secs_f32(), tiny_uid_time.as_secs_f32(), excess_slot_marker]);
     }
     
+    #[test]
+    fn test_write_sub_frame_times() {
+        let mut system_under_test = TimeTracker::new();
+        let uid = ObjectUid(13);
+        system_under_test.track(uid, 0);
+        system_under_test.launch(uid, ClockAnimationAct::default());
+        assert_eq!(system_under_test.in_motion(), false);
+
+        system_under_test.update_time();
+        assert!(system_under_test.in_motion());
+
+        let mut frame_end = vec![-5.0f32; 1];
+        system_under_test.write_times(&mut frame_end);
+        let mut frame_middle = vec![-5.0f32; 1];
+        system_under_test.write_sub_frame_times(&mut frame_middle, 0.5);
+        let mut frame_start = vec![-5.0f32; 1];
+        system_under_test.write_sub_frame_times(&mut frame_start, 0.0);
+
+        assert_eq!(frame_start, [0.0_f32]);
+        assert_eq!(frame_middle, [frame_end[0] * 0.5]);
+    }
+
+    #[test]
+    fn test_take_finished() {
+        let mut system_under_test = TimeTracker::new();
+        let finite = ObjectUid(13);
+        let infinite = ObjectUid(17);
+        system_under_test.track(finite, 0);
+        system_under_test.track(infinite, 1);
+
+        let animation_duration = Duration::from_millis(1);
+        system_under_test.launch(finite, finite_animation(animation_duration));
+        system_under_test.launch(infinite, ClockAnimationAct::default());
+        thread::sleep(animation_duration + Duration::from_millis(3));
+        system_under_test.update_time();
+
+        assert_eq!(system_under_test.take_finished(), vec![finite]);
+        assert!(system_under_test.take_finished().is_empty());
+    }
+
+    #[test]
+    fn test_relaunched_animation_is_not_reported() {
+        let mut system_under_test = TimeTracker::new();
+        let uid = ObjectUid(13);
+        system_under_test.track(uid, 0);
+
+        let animation_duration = Duration::from_millis(1);
+        system_under_test.launch(uid, finite_animation(animation_duration));
+        thread::sleep(animation_duration + Duration::from_millis(3));
+        system_under_test.update_time();
+        system_under_test.launch(uid, ClockAnimationAct::default());
+
+        assert!(system_under_test.take_finished().is_empty());
+    }
+
     #[must_use]
     fn finite_animation(expected_duration: Duration) -> ClockAnimationAct<PhaseAlive> {
         ClockAnimationAct::new()
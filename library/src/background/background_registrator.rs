@@ -0,0 +1,88 @@
+use crate::background::background_uid::BackgroundUid;
+use crate::shader::conventions;
+use crate::shader::user_function_registrator::{UserFunctionConventions, UserFunctionRegistrator, UserFunctionUid};
+
+pub type BackgroundRegistrator = UserFunctionRegistrator<BackgroundUid>;
+
+// the body of a background sees 'direction: vec3f' and 'time: f32' and returns the 'vec3f' radiance
+impl UserFunctionUid for BackgroundUid {
+    const CONVENTIONS: UserFunctionConventions = UserFunctionConventions {
+        selection_function_name: "background_select",
+        function_name_prefix: "background",
+        default_name: "function",
+        parameter_name_index: "background_index",
+        parameters: &[(conventions::PARAMETER_NAME_THE_DIRECTION, "vec3f"), (conventions::PARAMETER_NAME_THE_TIME, "f32")],
+        return_type: "vec3f",
+        // unregistered uids only: the tracer returns the clear color of the uniforms for the uid zero
+        default_value: "vec3f(0.1)",
+    };
+
+    fn from_ordinal(ordinal: usize) -> Self {
+        BackgroundUid(ordinal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::code::{FunctionBody, Generic, ShaderCode};
+
+    #[must_use]
+    fn background(body: &str) -> ShaderCode<FunctionBody> {
+        ShaderCode::<FunctionBody>::new(body.to_string())
+    }
+
+    #[test]
+    fn test_new_without_shared_code() {
+        assert_eq!(
+            BackgroundRegistrator::default().generate_gpu_code().to_string(),
+            "fn background_select(background_index: i32, direction: vec3f, time: f32) -> vec3f {\nreturn vec3f(0.1);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_new_with_shared_code() {
+        let system_under_test = BackgroundRegistrator::new(Some(ShaderCode::<Generic>::new("shared background code".to_string())));
+
+        let generated_code = system_under_test.generate_gpu_code();
+
+        assert_eq!(
+            generated_code.to_string(),
+            "shared background code\nfn background_select(background_index: i32, direction: vec3f, time: f32) -> vec3f {\nreturn vec3f(0.1);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_gpu_code_multiple_backgrounds() {
+        let mut system_under_test = BackgroundRegistrator::default();
+
+        let sky = system_under_test.add(background("return vec3f(0.0, 0.0, direction.y);"), Some("sky"));
+        let dusk = system_under_test.add(background("return vec3f(1.0, 0.5, 0.0);"), Some("dusk"));
+
+        assert_ne!(sky, dusk);
+        let expected_code = "fn background_dusk(direction: vec3f, time: f32)->vec3f{\nreturn vec3f(1.0, 0.5, 0.0);\n}\nfn background_sky(direction: vec3f, time: f32)->vec3f{\nreturn vec3f(0.0, 0.0, direction.y);\n}\nfn background_select(background_index: i32, direction: vec3f, time: f32) -> vec3f {\nif (background_index == 2) { return background_dusk(direction,time); }\nif (background_index == 1) { return background_sky(direction,time); }\nreturn vec3f(0.1);\n}\n";
+        assert_eq!(system_under_test.generate_gpu_code().to_string(), expected_code);
+    }
+
+    #[test]
+    fn test_add_with_same_name() {
+        let mut system_under_test = BackgroundRegistrator::default();
+
+        let _ = system_under_test.add(background("return vec3f(1.0);"), Some("stars"));
+        let _ = system_under_test.add(background("return vec3f(0.0);"), Some("stars"));
+
+        let generated_code = system_under_test.generate_gpu_code();
+        assert_eq!(generated_code.as_str().matches("fn background_stars(").count(), 1);
+    }
+
+    #[test]
+    fn test_animated() {
+        let mut system_under_test = BackgroundRegistrator::default();
+        let still = system_under_test.add(background("return vec3f(0.5);"), None);
+        let animated = system_under_test.add(background("return vec3f(sin(time));"), None);
+
+        assert_eq!(system_under_test.animated(still), false);
+        assert!(system_under_test.animated(animated));
+        assert_eq!(system_under_test.animated(BackgroundUid(42)), false);
+    }
+}
\ No newline at end of file
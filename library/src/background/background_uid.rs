@@ -0,0 +1,23 @@
+use derive_more::Display;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Display, Hash)]
+pub struct BackgroundUid(pub usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_uid_display() {
+        let expected_value = 7;
+        let system_under_test = BackgroundUid(expected_value);
+        assert_eq!(format!("{}", system_under_test), expected_value.to_string());
+    }
+
+    #[test]
+    fn test_background_uid_equality() {
+        let system_under_test = BackgroundUid(3);
+        assert_eq!(system_under_test, BackgroundUid(3));
+        assert_ne!(system_under_test, BackgroundUid(4));
+    }
+}
\ No newline at end of file
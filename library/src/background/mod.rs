@@ -0,0 +1,2 @@
+pub mod background_registrator;
+pub mod background_uid;
\ No newline at end of file
@@ -1,10 +1,13 @@
 use crate::bvh::node::{get_bvh_node_children, BvhNode};
-use crate::bvh::proxy::SceneObjectProxy;
+use crate::bvh::proxy::{PrimitiveType, SceneObjectProxy};
+use crate::geometry::aabb::Aabb;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use crate::serialization::quantization::QuantizationFrame;
 use crate::serialization::serializable_for_gpu::GpuSerializationSize;
 use std::cell::RefCell;
 use std::rc::Rc;
 use crate::bvh::dfs::depth_first_search;
+use strum::EnumCount;
 
 pub(crate) struct Bvh {
     root: Rc<RefCell<BvhNode>>,
@@ -16,6 +19,82 @@ impl Bvh {
     pub(crate) fn root(&self) -> &Rc<RefCell<BvhNode>> {
         &self.root
     }
+
+    // the support must describe the same primitives the tree was built for, only their boxes may differ
+    pub(crate) fn refit(&mut self, support: &[SceneObjectProxy]) {
+        let mut boxes: Vec<Vec<Aabb>> = vec![Vec::new(); PrimitiveType::COUNT];
+        for proxy in support {
+            let of_type = &mut boxes[proxy.primitive_type() as usize];
+            if of_type.len() <= proxy.host_container_index() {
+                of_type.resize(proxy.host_container_index() + 1, Aabb::make_null());
+            }
+            of_type[proxy.host_container_index()] = proxy.aabb();
+        }
+
+        let _ = BvhNode::refit(&self.root, &|primitive_type: PrimitiveType, index: usize| {
+            *boxes[primitive_type as usize].get(index).unwrap_or_else(|| panic!("{primitive_type:?} {index} is missing in the support: topology has changed"))
+        });
+    }
+
+    // the leaves (the primitive type and the index) under the nodes the 'enter' accepts; the left
+    // subtrees go first, the 'enter' may reject more nodes as the leaves get visited
+    pub(crate) fn visit_leaves<Enter, Leaf>(&self, enter: Enter, mut leaf: Leaf)
+    where Enter: Fn(&Aabb) -> bool, Leaf: FnMut(PrimitiveType, usize)
+    {
+        let mut stack = vec![self.root.clone()];
+        while let Some(candidate) = stack.pop() {
+            let node = candidate.borrow();
+            if false == enter(node.aabb()) {
+                continue;
+            }
+            match (node.content_type(), node.content_index()) {
+                (Some(primitive_type), Some(index)) => leaf(primitive_type, index),
+                _ => {
+                    let (left, right) = get_bvh_node_children(&node);
+                    stack.extend(right);
+                    stack.extend(left);
+                },
+            }
+        }
+    }
+
+    // the nodes on the longest path from the root to a leaf, the root and the leaf included
+    #[must_use]
+    pub(crate) fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut stack = vec![(self.root.clone(), 1_usize)];
+        while let Some((candidate, depth)) = stack.pop() {
+            deepest = deepest.max(depth);
+            let (left, right) = get_bvh_node_children(&candidate.borrow());
+            stack.extend([left, right].into_iter().flatten().map(|child| (child, depth + 1)));
+        }
+        deepest
+    }
+
+    #[must_use]
+    pub(crate) fn serialize(&self) -> GpuReadySerializationBuffer {
+        let quartet_count = <BvhNode as GpuSerializationSize>::SERIALIZED_QUARTET_COUNT;
+        let filler = 0.0;
+        let mut serialized = GpuReadySerializationBuffer::make_filled(self.nodes_count, quartet_count, filler);
+        serialize(Some(self.root.clone()), &mut serialized);
+        serialized
+    }
+
+    // the nodes are followed by the frame (the root box) their boxes are quantized in
+    #[must_use]
+    pub(crate) fn serialize_quantized(&self) -> GpuReadySerializationBuffer {
+        let frame = QuantizationFrame::new(self.root.borrow().aabb());
+        let mut serialized = GpuReadySerializationBuffer::make_filled(self.nodes_count + 1, BvhNode::SERIALIZED_QUANTIZED_QUARTET_COUNT, 0.0);
+        depth_first_search(
+            self.root.clone(),
+            get_bvh_node_children,
+            |node: &mut BvhNode, _next_right: Option<Rc<RefCell<BvhNode>>>| {
+                node.serialize_quantized_by_index_into(&mut serialized, &frame);
+            }
+        );
+        frame.serialize_as_object_into(&mut serialized, self.nodes_count);
+        serialized
+    }
 }
 
 #[must_use]
@@ -30,16 +109,10 @@ pub(crate) fn build_bvh(support: &mut[SceneObjectProxy]) -> Bvh {
     Bvh { root, nodes_count }
 }
 
+#[cfg(test)]
 #[must_use]
 pub(crate) fn build_serialized_bvh(support: &mut[SceneObjectProxy]) -> GpuReadySerializationBuffer {
-    let bvh = build_bvh(support);
-
-    let quartet_count = <BvhNode as GpuSerializationSize>::SERIALIZED_QUARTET_COUNT;
-    let filler = 0.0;
-    let mut serialized = GpuReadySerializationBuffer::make_filled(bvh.nodes_count, quartet_count, filler);
-    serialize(Some(bvh.root.clone()), &mut serialized);
-
-    serialized
+    build_bvh(support).serialize()
 }
 
 fn serialize(candidate: Option<Rc<RefCell<BvhNode>>>, buffer: &mut GpuReadySerializationBuffer) {
@@ -72,10 +145,135 @@ fn evaluate_serial_indices(candidate: Option<Rc<RefCell<BvhNode>>>, index: &mut
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geometry::alias::Point;
     use crate::bvh::node::tests::make_triangle;
     use crate::bvh::proxy::PrimitiveType;
     use crate::container::bvh_proxies::{proxy_of_triangle, SceneObjects};
     use crate::serialization::gpu_ready_serialization_buffer::DEFAULT_PAD_VALUE;
+    use crate::objects::triangle::Triangle;
+    use more_asserts::{assert_ge, assert_le};
+    use std::time::{Duration, Instant};
+
+    #[must_use]
+    fn make_triangles_grid(count: usize, shift: f64) -> Vec<Triangle> {
+        let side = (count as f64).sqrt().ceil() as usize;
+        (0..count).map(|index| {
+            let x = (index % side) as f64 + shift;
+            let y = (index / side) as f64;
+            make_triangle([x, y, 0.0, x + 0.5, y, 0.0, x, y + 0.5, shift])
+        }).collect()
+    }
+
+    #[must_use]
+    fn make_support(triangles: &Vec<Triangle>) -> Vec<SceneObjectProxy> {
+        let mut support = Vec::with_capacity(triangles.len());
+        triangles.make_proxies(&mut support, 0.0);
+        support
+    }
+
+    #[test]
+    fn test_visit_leaves() {
+        let triangles = make_triangles_grid(16, 0.0);
+        let system_under_test = build_bvh(&mut make_support(&triangles));
+        let probe = Aabb::from_points(Point::new(0.1, 0.1, -1.0), Point::new(1.2, 0.2, 1.0));
+
+        let mut visited = Vec::new();
+        system_under_test.visit_leaves(|aabb| Aabb::make_intersection(*aabb, probe).is_some(), |primitive_type, index| {
+            assert_eq!(primitive_type, PrimitiveType::Triangle);
+            visited.push(index);
+        });
+        visited.sort();
+
+        assert_eq!(visited, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_depth() {
+        assert_eq!(build_bvh(&mut make_support(&make_triangles_grid(1, 0.0))).depth(), 1);
+        assert_eq!(build_bvh(&mut make_support(&make_triangles_grid(2, 0.0))).depth(), 2);
+
+        let system_under_test = build_bvh(&mut make_support(&make_triangles_grid(16, 0.0)));
+
+        assert_ge!(system_under_test.depth(), 5);
+    }
+
+    #[test]
+    fn test_refit_of_moved_triangles() {
+        let mut system_under_test = build_bvh(&mut make_support(&make_triangles_grid(37, 0.0)));
+        let moved = make_triangles_grid(37, 0.25);
+
+        system_under_test.refit(&make_support(&moved));
+
+        let expected_root_box = moved.iter().fold(Aabb::make_null(), |union, triangle| Aabb::make_union(union, triangle.bounding_box()));
+        assert_eq!(system_under_test.root().borrow().aabb(), &expected_root_box);
+        assert_eq!(system_under_test.serialize().backend(), build_serialized_bvh(&mut make_support(&moved)).backend());
+    }
+
+    #[test]
+    fn test_quantized_boxes_contain_originals() {
+        let system_under_test = build_bvh(&mut make_support(&make_triangles_grid(37, 0.0)));
+
+        let full = system_under_test.serialize();
+        let quantized = system_under_test.serialize_quantized();
+
+        let full_words: &[u32] = bytemuck::cast_slice(full.backend());
+        let quantized_words: &[u32] = bytemuck::cast_slice(quantized.backend());
+        let nodes_count = full.total_slots_count();
+        assert_eq!(quantized.total_slots_count(), nodes_count + 1);
+
+        let frame = &quantized_words[8 * nodes_count..];
+        let restore = |grid: u32, axis: usize| f32::from_bits(frame[axis]) as f64 + grid as f64 * f32::from_bits(frame[4 + axis]) as f64;
+        for node in 0..nodes_count {
+            let original = &full_words[12 * node..12 * (node + 1)];
+            let packed = &quantized_words[8 * node..8 * (node + 1)];
+            let grid_min = [packed[0] & 0xFFFF, packed[0] >> 16, packed[1] & 0xFFFF];
+            let grid_max = [packed[1] >> 16, packed[2] & 0xFFFF, packed[2] >> 16];
+            for axis in 0..3 {
+                assert_le!(restore(grid_min[axis], axis), f32::from_bits(original[axis]) as f64);
+                assert_ge!(restore(grid_max[axis], axis), f32::from_bits(original[4 + axis]) as f64);
+            }
+            assert_eq!(packed[3], original[3], "primitive index");
+            assert_eq!(packed[4], original[7], "primitive type");
+            assert_eq!(packed[5], original[8], "miss link");
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_refit_with_changed_topology() {
+        let mut system_under_test = build_bvh(&mut make_support(&make_triangles_grid(4, 0.0)));
+        system_under_test.refit(&make_support(&make_triangles_grid(3, 0.0)));
+    }
+
+    // cargo test --release -p library -- --ignored --nocapture benchmark_refit
+    #[test]
+    #[ignore]
+    fn benchmark_refit_against_rebuild() {
+        const TRIANGLES_COUNT: usize = 100_000;
+        const ITERATIONS: u32 = 8;
+
+        let mut bvh = build_bvh(&mut make_support(&make_triangles_grid(TRIANGLES_COUNT, 0.0)));
+        let mut rebuild_time = Duration::ZERO;
+        let mut refit_time = Duration::ZERO;
+
+        for iteration in 1..=ITERATIONS {
+            let moved = make_support(&make_triangles_grid(TRIANGLES_COUNT, iteration as f64 * 0.01));
+
+            let start = Instant::now();
+            let rebuilt = build_serialized_bvh(&mut moved.clone());
+            rebuild_time += start.elapsed();
+
+            let start = Instant::now();
+            bvh.refit(&moved);
+            let refitted = bvh.serialize();
+            refit_time += start.elapsed();
+
+            assert_eq!(rebuilt.total_slots_count(), refitted.total_slots_count());
+        }
+
+        println!("{TRIANGLES_COUNT} triangles: rebuild {:?}, refit {:?} per update", rebuild_time / ITERATIONS, refit_time / ITERATIONS);
+        assert!(refit_time < rebuild_time);
+    }
 
     #[test]
     fn test_evaluate_serial_indices_none() {
@@ -0,0 +1,175 @@
+use crate::bvh::proxy::SceneObjectProxy;
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// the tree depends on the proxies only, so their hash identifies the serialized tree
+#[must_use]
+pub(crate) fn support_hash(support: &[SceneObjectProxy]) -> u64 {
+    let mut bytes: Vec<u8> = Vec::with_capacity(size_of_val(support));
+    for proxy in support {
+        bytes.extend_from_slice(&(proxy.host_container_index() as u64).to_ne_bytes());
+        bytes.extend_from_slice(&(proxy.primitive_type() as u32).to_ne_bytes());
+        let aabb = proxy.aabb();
+        for coordinate in [aabb.min().x, aabb.min().y, aabb.min().z, aabb.max().x, aabb.max().y, aabb.max().z] {
+            bytes.extend_from_slice(&coordinate.to_ne_bytes());
+        }
+    }
+    seahash::hash(&bytes)
+}
+
+#[must_use]
+pub(crate) fn save_serialized_bvh(path: &Path, source_hash: u64, quartets_per_node: usize, serialized: &GpuReadySerializationBuffer) -> Option<()> {
+    let file = File::create(path).ok()?;
+    let mut writer = BufWriter::new(file);
+
+    let header = BvhDumpHeader::new(source_hash, quartets_per_node as u32, serialized.backend().len() as u64);
+    writer.write_all(header.as_bytes()).ok()?;
+    writer.write_all(serialized.backend()).ok()?;
+
+    writer.flush().ok()
+}
+
+// none if there is no dump, it is of another layout (version or node size) or it was made for another scene
+#[must_use]
+pub(crate) fn load_serialized_bvh(path: &Path, expected_source_hash: u64, quartets_per_node: usize) -> Option<GpuReadySerializationBuffer> {
+    let file = File::open(path).ok()?;
+    let mut reader = BufReader::new(file);
+
+    let mut header_bytes = vec![0u8; size_of::<BvhDumpHeader>()];
+    reader.read_exact(&mut header_bytes).ok()?;
+    let header = BvhDumpHeader::from_bytes(&header_bytes)?;
+
+    if header.source_hash != expected_source_hash || header.quartets_per_node != quartets_per_node as u32 {
+        return None;
+    }
+
+    let mut data = vec![0u8; header.data_size as usize];
+    reader.read_exact(&mut data).ok()?;
+
+    GpuReadySerializationBuffer::from_backend(data, quartets_per_node)
+}
+
+#[repr(C, packed)]
+struct BvhDumpHeader {
+    magic: [u8; 4], // Magic bytes "BVHD" (BVH Dump)
+    layout_version: u32,
+    quartets_per_node: u32,
+    source_hash: u64,
+    data_size: u64,
+}
+
+impl BvhDumpHeader {
+    const MAGIC: [u8; 4] = *b"BVHD";
+    // bump on any change of the node serialization
+    const LAYOUT_VERSION: u32 = 1;
+
+    #[must_use]
+    fn new(source_hash: u64, quartets_per_node: u32, data_size: u64) -> Self {
+        Self {
+            magic: Self::MAGIC,
+            layout_version: Self::LAYOUT_VERSION,
+            quartets_per_node,
+            source_hash,
+            data_size,
+        }
+    }
+
+    #[must_use]
+    fn is_valid(&self) -> bool {
+        self.magic == Self::MAGIC && self.layout_version == Self::LAYOUT_VERSION
+    }
+
+    #[must_use]
+    fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(
+                self as *const Self as *const u8,
+                size_of::<Self>()
+            )
+        }
+    }
+
+    #[must_use]
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < size_of::<Self>() {
+            return None;
+        }
+
+        let header = unsafe {
+            std::ptr::read_unaligned(bytes.as_ptr() as *const Self)
+        };
+
+        if header.is_valid() {
+            Some(header)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bvh::builder::build_serialized_bvh;
+    use crate::bvh::node::BvhNode;
+    use crate::serialization::serializable_for_gpu::GpuSerializationSize;
+    use crate::bvh::node::tests::make_triangle;
+    use crate::container::bvh_proxies::proxy_of_triangle;
+    use tempfile::tempdir;
+
+    #[must_use]
+    fn make_support() -> Vec<SceneObjectProxy> {
+        let triangle_one = make_triangle([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        let triangle_two = make_triangle([2.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0]);
+        vec![proxy_of_triangle(0, &triangle_one, 0.0), proxy_of_triangle(1, &triangle_two, 0.0)]
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("bvh");
+        let mut support = make_support();
+        let hash = support_hash(&support);
+        let serialized = build_serialized_bvh(&mut support);
+
+        save_serialized_bvh(&path, hash, BvhNode::SERIALIZED_QUARTET_COUNT, &serialized).unwrap();
+        let loaded = load_serialized_bvh(&path, hash, BvhNode::SERIALIZED_QUARTET_COUNT).unwrap();
+
+        assert_eq!(loaded.backend(), serialized.backend());
+        assert_eq!(loaded.total_slots_count(), serialized.total_slots_count());
+    }
+
+    #[test]
+    fn test_load_for_another_scene() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("bvh");
+        let mut support = make_support();
+        let hash = support_hash(&support);
+        save_serialized_bvh(&path, hash, BvhNode::SERIALIZED_QUARTET_COUNT, &build_serialized_bvh(&mut support)).unwrap();
+
+        assert!(load_serialized_bvh(&path, hash + 1, BvhNode::SERIALIZED_QUARTET_COUNT).is_none());
+        assert!(load_serialized_bvh(&path, hash, BvhNode::SERIALIZED_QUANTIZED_QUARTET_COUNT).is_none());
+    }
+
+    #[test]
+    fn test_load_corrupted() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("bvh");
+        std::fs::write(&path, b"BVHD but not a dump").unwrap();
+
+        assert!(load_serialized_bvh(&path, 0, BvhNode::SERIALIZED_QUARTET_COUNT).is_none());
+        assert!(load_serialized_bvh(&directory.path().join("missing"), 0, BvhNode::SERIALIZED_QUARTET_COUNT).is_none());
+    }
+
+    #[test]
+    fn test_support_hash_depends_on_boxes() {
+        let support = make_support();
+        let mut moved = make_support();
+        moved[1] = proxy_of_triangle(1, &make_triangle([3.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0]), 0.0);
+
+        assert_eq!(support_hash(&support), support_hash(&make_support()));
+        assert_ne!(support_hash(&support), support_hash(&moved));
+    }
+}
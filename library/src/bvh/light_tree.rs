@@ -0,0 +1,167 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::Point;
+use crate::serialization::pod_vector::PodVector;
+use cgmath::EuclideanSpace;
+
+// an emitter the next event estimation samples: the slot of the parallelogram, its bounds and the
+// emitted power, the luminance of the emission over the area
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct LightSource {
+    pub(crate) slot: u32,
+    pub(crate) bounds: Aabb,
+    pub(crate) power: f64,
+}
+
+// the bounds and the power of the lights beneath; the right child of an inner node is referenced,
+// the left one follows the node, a leaf references the light
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct LightTreeNode {
+    bounds: Aabb,
+    power: f64,
+    link: u32,
+}
+
+// the binary tree over the lights the shader descends to pick one in O(log n), each child with
+// the probability of its importance for the shading point; a light path holds the turns to the
+// light's leaf: bit i is set when the path goes right at the depth i
+pub(crate) struct LightTree {
+    nodes: Vec<LightTreeNode>,
+    paths: Vec<u32>,
+}
+
+impl LightTree {
+    // must match 'LIGHT_TREE_LEAF' of the shader
+    const LEAF_FLAG: u32 = 1 << 31;
+    const MAX_DEPTH: u32 = u32::BITS;
+
+    #[must_use]
+    pub(crate) fn new(lights: &[LightSource]) -> Self {
+        let mut result = Self { nodes: Vec::with_capacity(2 * lights.len()), paths: vec![0; lights.len()] };
+        if false == lights.is_empty() {
+            let mut order: Vec<usize> = (0..lights.len()).collect();
+            result.build(lights, &mut order, 0, 0);
+        }
+        result
+    }
+
+    fn build(&mut self, lights: &[LightSource], order: &mut [usize], depth: u32, path: u32) {
+        assert!(depth < Self::MAX_DEPTH, "the light tree is too deep");
+        let bounds = order.iter().fold(Aabb::make_null(), |bounds, light| Aabb::make_union(bounds, lights[*light].bounds));
+        let power = order.iter().map(|light| lights[*light].power).sum();
+
+        if let [light] = order {
+            self.paths[*light] = path;
+            self.nodes.push(LightTreeNode { bounds, power, link: Self::LEAF_FLAG | *light as u32 });
+            return;
+        }
+
+        let centers: Vec<Point> = order.iter().map(|light| lights[*light].bounds.center()).collect();
+        let centers_bounds = centers.iter().fold(Aabb::make_null(), |bounds, center| Aabb::make_union(bounds, Aabb::from_points(*center, *center)));
+        let extent = centers_bounds.extent();
+        let axis = if extent.x >= extent.y && extent.x >= extent.z { 0 } else if extent.y >= extent.z { 1 } else { 2 };
+        order.sort_by(|left, right| lights[*left].bounds.center()[axis].total_cmp(&lights[*right].bounds.center()[axis]));
+
+        let node = self.nodes.len();
+        self.nodes.push(LightTreeNode { bounds, power, link: 0 });
+        let (left, right) = order.split_at_mut(order.len() / 2);
+        self.build(lights, left, depth + 1, path);
+        self.nodes[node].link = self.nodes.len() as u32;
+        self.build(lights, right, depth + 1, path | (1 << depth));
+    }
+
+    // two quartets a node: the minimum and the power, the maximum and the link bits
+    #[must_use]
+    pub(crate) fn serialize(&self) -> Vec<PodVector> {
+        if self.nodes.is_empty() {
+            // the gpu can't accept empty buffer, the lights count in the uniforms tells there are none
+            return vec![PodVector::default(); 2];
+        }
+        self.nodes.iter()
+            .flat_map(|node| {
+                let (min, max) = (node.bounds.min().to_vec().cast::<f32>().unwrap(), node.bounds.max().to_vec().cast::<f32>().unwrap());
+                [
+                    PodVector { x: min.x, y: min.y, z: min.z, w: node.power as f32 },
+                    PodVector { x: max.x, y: max.y, z: max.z, w: f32::from_bits(node.link) },
+                ]
+            })
+            .collect()
+    }
+
+    // in the order of the lights the tree is built of
+    #[must_use]
+    pub(crate) fn paths(&self) -> &[u32] {
+        &self.paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[must_use]
+    fn make_light(slot: u32, x: f64, power: f64) -> LightSource {
+        LightSource { slot, bounds: Aabb::from_points(Point::new(x, 0.0, 0.0), Point::new(x + 1.0, 1.0, 0.0)), power }
+    }
+
+    #[test]
+    fn test_single_light() {
+        let system_under_test = LightTree::new(&[make_light(3, 0.0, 2.0)]);
+
+        assert_eq!(system_under_test.nodes.len(), 1);
+        assert_eq!(system_under_test.nodes[0].link, LightTree::LEAF_FLAG);
+        assert_eq!(system_under_test.paths(), &[0]);
+    }
+
+    #[test]
+    fn test_no_lights() {
+        let system_under_test = LightTree::new(&[]);
+
+        assert!(system_under_test.paths().is_empty());
+        assert_eq!(system_under_test.serialize().len(), 2);
+    }
+
+    #[test]
+    fn test_paths_lead_to_lights() {
+        let lights: Vec<LightSource> = [5.0, -3.0, 11.0, 0.0, 7.0].iter().enumerate()
+            .map(|(index, x)| make_light(index as u32, *x, 1.0 + index as f64))
+            .collect();
+
+        let system_under_test = LightTree::new(&lights);
+
+        assert_eq!(system_under_test.nodes.len(), 2 * lights.len() - 1);
+        assert_eq!(system_under_test.nodes[0].power, 15.0);
+        for (light, path) in system_under_test.paths().iter().enumerate() {
+            let mut node = 0;
+            let mut depth = 0;
+            while 0 == system_under_test.nodes[node].link & LightTree::LEAF_FLAG {
+                node = if 0 == (path >> depth) & 1 { node + 1 } else { system_under_test.nodes[node].link as usize };
+                depth += 1;
+            }
+            assert_eq!(system_under_test.nodes[node].link & !LightTree::LEAF_FLAG, light as u32);
+        }
+    }
+
+    #[test]
+    fn test_lights_are_split_along_longest_axis() {
+        let lights: Vec<LightSource> = [0.0, 10.0, 1.0, 11.0].iter().enumerate()
+            .map(|(index, x)| make_light(index as u32, *x, 1.0))
+            .collect();
+
+        let system_under_test = LightTree::new(&lights);
+
+        let left = system_under_test.nodes[1];
+        assert_eq!(left.bounds, Aabb::from_points(Point::new(0.0, 0.0, 0.0), Point::new(2.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let system_under_test = LightTree::new(&[make_light(0, 0.0, 2.0), make_light(1, 4.0, 1.0)]);
+
+        let serialized = system_under_test.serialize();
+
+        assert_eq!(serialized.len(), 6);
+        assert_eq!(serialized[0], PodVector { x: 0.0, y: 0.0, z: 0.0, w: 3.0 });
+        assert_eq!(serialized[1].w.to_bits(), 2);
+        assert_eq!(serialized[5].w.to_bits(), LightTree::LEAF_FLAG | 1);
+    }
+}
@@ -2,4 +2,6 @@ pub(crate) mod builder;
 pub(crate) mod node;
 pub(crate) mod proxy;
 pub(crate) mod bvh_to_dot;
+pub(crate) mod dump;
+pub(crate) mod light_tree;
 mod dfs;
@@ -8,6 +8,7 @@ use crate::bvh::dfs::depth_first_search;
 use crate::bvh::proxy::{PrimitiveType, SceneObjectProxy};
 use crate::geometry::utils::Max;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use crate::serialization::quantization::{pack_unorm16_pair, QuantizationFrame};
 use crate::serialization::serializable_for_gpu::GpuSerializationSize;
 
 struct BvhNodeContent {
@@ -59,6 +60,8 @@ impl GpuSerializationSize for BvhNode {
 }
 
 impl BvhNode {
+    pub(crate) const SERIALIZED_QUANTIZED_QUARTET_COUNT: usize = 2;
+
     #[must_use]
     fn new() -> Self {
         Self {
@@ -213,6 +216,26 @@ impl BvhNode {
         root.unwrap()
     }
 
+    // the hierarchy is kept, only the boxes are recomputed bottom-up: the tree quality
+    // degrades with the motion, so it has to be rebuilt once the topology changes anyway
+    pub(super) fn refit<Lookup>(node: &Rc<RefCell<BvhNode>>, leaf_box: &Lookup) -> Aabb
+    where Lookup: Fn(PrimitiveType, usize) -> Aabb
+    {
+        let (left, right) = get_bvh_node_children(&node.borrow());
+        let bounding_box = match node.borrow().content.as_ref() {
+            Some(content) => leaf_box(content.primitive_type(), content.primitive_index()),
+            None => {
+                let mut union = Aabb::make_null();
+                for child in [left, right].iter().flatten() {
+                    union = Aabb::make_union(union, BvhNode::refit(child, leaf_box));
+                }
+                union
+            },
+        };
+        node.borrow_mut().bounding_box = bounding_box;
+        bounding_box
+    }
+
     // "Implementing a practical rendering system using GLSL" by Toshiya Hachisuka
     pub(crate) fn make_tree_threaded(bvh: Rc<RefCell<BvhNode>>) {
         depth_first_search(
@@ -260,24 +283,20 @@ impl BvhNode {
         BvhNode::box_z_compare,
     ];
 
+    #[must_use]
+    fn serialized_primitive(&self) -> (u32, u32) {
+        match self.content.as_ref() {
+            Some(content) => (content.primitive_index() as u32, content.primitive_type() as u32),
+            None => (0, PrimitiveType::Null as u32),
+        }
+    }
+
     pub(super) fn serialize_by_index_into(&self, container: &mut GpuReadySerializationBuffer) {
         assert!(self.serial_index.is_some(), "index was not set");
         debug_assert!(container.fully_written(), "buffer underflow");
 
         let index = self.serial_index().unwrap();
-        
-        let primitive_index: u32;
-        let primitive_type: u32;
-        match self.content.as_ref() {
-            Some(content) => {
-                primitive_index = content.primitive_index() as u32;
-                primitive_type = content.primitive_type() as u32;
-            }
-            None => {
-                primitive_index = 0;
-                primitive_type = PrimitiveType::Null as u32;
-            },
-        }
+        let (primitive_index, primitive_type) = self.serialized_primitive();
 
         container.write_object(index, |writer|{
             
@@ -301,6 +320,33 @@ impl BvhNode {
             
         });
     }
+
+    // the box corners become 16 bit fractions of the 'frame', rounded outwards
+    pub(super) fn serialize_quantized_by_index_into(&self, container: &mut GpuReadySerializationBuffer, frame: &QuantizationFrame) {
+        assert!(self.serial_index.is_some(), "index was not set");
+        debug_assert!(container.fully_written(), "buffer underflow");
+
+        let index = self.serial_index().unwrap();
+        let (primitive_index, primitive_type) = self.serialized_primitive();
+        let min = frame.quantize_down(self.bounding_box.min());
+        let max = frame.quantize_up(self.bounding_box.max());
+
+        container.write_object(index, |writer|{
+
+            writer.write_quartet(|writer| {
+                writer.write_unsigned(pack_unorm16_pair(min[0], min[1]));
+                writer.write_unsigned(pack_unorm16_pair(min[2], max[0]));
+                writer.write_unsigned(pack_unorm16_pair(max[1], max[2]));
+                writer.write_unsigned(primitive_index);
+            });
+
+            writer.write_quartet(|writer| {
+                writer.write_unsigned(primitive_type);
+                writer.write_signed(self.miss_node_index_or_null());
+            });
+
+        });
+    }
 }
 
 #[cfg(test)]
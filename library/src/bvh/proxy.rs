@@ -1,10 +1,12 @@
 use crate::geometry::aabb::Aabb;
+use strum_macros::EnumCount;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(EnumCount, Copy, Clone, PartialEq, Debug)]
 pub(crate) enum PrimitiveType {
     Null = 0,
     Sdf = 1,
     Triangle = 2,
+    Sphere = 3,
 }
 
 #[derive(Copy, Clone)]
@@ -15,6 +15,11 @@ pub(crate) fn proxy_of_sdf(index: usize, aabb: Aabb) -> SceneObjectProxy {
     SceneObjectProxy::new(index, PrimitiveType::Sdf, aabb)
 }
 
+#[must_use]
+pub(crate) fn proxy_of_sphere(index: usize, aabb: Aabb) -> SceneObjectProxy {
+    SceneObjectProxy::new(index, PrimitiveType::Sphere, aabb)
+}
+
 pub(crate) trait SceneObjects {
     fn make_proxies(&self, destination: &mut Vec<SceneObjectProxy>, aabb_inflation: f64);
 }
@@ -62,6 +67,14 @@ mod tests {
         assert_eq!(actual_object.host_container_index(), expected_container_index); 
     }
 
+    #[test]
+    fn test_proxy_of_sphere() {
+        let expected_container_index = 5;
+        let actual_object = proxy_of_sphere(expected_container_index, Aabb::make_minimal());
+        assert_eq!(actual_object.primitive_type(), PrimitiveType::Sphere);
+        assert_eq!(actual_object.host_container_index(), expected_container_index);
+    }
+
     #[must_use]
     fn make_dummy_triangle() -> Triangle {
         let dummy_vertex = Vertex::new(Point::origin(), Vector::unit_z());
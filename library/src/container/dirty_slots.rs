@@ -0,0 +1,73 @@
+use crate::utils::version::Version;
+use std::ops::Range;
+
+// the data versions at which the slots of a kind were written last: the holder of the serialized data
+// of an older version re-serializes and uploads just the range of the slots written since
+#[derive(Default)]
+pub(super) struct DirtySlots {
+    written_at: Vec<Version>,
+    // the buffer length changes along with the slots count: the holders of the older versions rebuild it all
+    resized_at: Version,
+}
+
+impl DirtySlots {
+    pub(super) fn write(&mut self, slot: usize, slots_count: usize, version: Version) {
+        self.resize(slots_count, version);
+        // the slot of the last object is dropped along with the object
+        if let Some(written_at) = self.written_at.get_mut(slot) {
+            *written_at = version;
+        }
+    }
+
+    pub(super) fn resize(&mut self, slots_count: usize, version: Version) {
+        if self.written_at.len() != slots_count {
+            self.written_at.resize(slots_count, version);
+            self.resized_at = version;
+        }
+    }
+
+    // none if the whole buffer has to be rebuilt; the range is empty if no slot has been written since
+    #[must_use]
+    pub(super) fn written_since(&self, version: Version) -> Option<Range<usize>> {
+        if self.resized_at > version {
+            return None;
+        }
+        let mut written = self.written_at.iter().enumerate().filter(|(_, written_at)| **written_at > version).map(|(slot, _)| slot);
+        let Some(first) = written.next() else {
+            return Some(0..0);
+        };
+        let last = written.next_back().unwrap_or(first);
+        Some(first..last + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_written_range_spans_written_slots() {
+        let mut system_under_test = DirtySlots::default();
+        system_under_test.resize(5, Version(1));
+
+        system_under_test.write(3, 5, Version(2));
+        system_under_test.write(1, 5, Version(3));
+
+        assert_eq!(system_under_test.written_since(Version(1)), Some(1..4));
+        assert_eq!(system_under_test.written_since(Version(2)), Some(1..2));
+        assert_eq!(system_under_test.written_since(Version(3)), Some(0..0));
+    }
+
+    #[test]
+    fn test_resize_invalidates_older_versions() {
+        let mut system_under_test = DirtySlots::default();
+        system_under_test.resize(2, Version(1));
+
+        system_under_test.write(2, 3, Version(2));
+        system_under_test.write(2, 2, Version(3));
+
+        assert_eq!(system_under_test.written_since(Version(1)), None);
+        assert_eq!(system_under_test.written_since(Version(2)), None);
+        assert_eq!(system_under_test.written_since(Version(3)), Some(0..0));
+    }
+}
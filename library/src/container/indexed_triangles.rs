@@ -0,0 +1,220 @@
+use crate::container::mesh_warehouse::MeshShading;
+use crate::geometry::aabb::Aabb;
+use crate::geometry::vertex::Vertex;
+use crate::material::material_index::MaterialIndex;
+use crate::objects::triangle::Triangle;
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use crate::serialization::quantization::{pack_normal, pack_unorm16_pair, QuantizationFrame};
+use crate::utils::object_uid::ObjectUid;
+use std::collections::HashMap;
+
+pub(super) struct MeshVertices<'a> {
+    pub(super) uid: ObjectUid,
+    pub(super) material: MaterialIndex,
+    pub(super) vertices: &'a [Vertex],
+    pub(super) shading: MeshShading,
+}
+
+const QUARTETS_PER_VERTEX: usize = 2;
+// the last word of a triangle record: the tracer ignores the vertex normals of such triangles
+const FLAT_SHADING_FLAG: u32 = 1 << 31;
+
+#[must_use]
+fn shading_flag(shading: MeshShading) -> u32 {
+    match shading {
+        MeshShading::Flat => FLAT_SHADING_FLAG,
+        MeshShading::Smooth => 0,
+    }
+}
+
+// the triangles keep the order (bvh leaves refer to them by index) and come first, each one
+// as a quartet of its vertices offsets and the shading flag; the vertices of all the meshes follow, two quartets each:
+// the position with the uid and the normal with the material, so the mesh links are stored per vertex
+#[must_use]
+pub(super) fn serialize_indexed_triangles(triangles: &[Triangle], meshes: &[MeshVertices]) -> GpuReadySerializationBuffer {
+    let mut first_vertex_offset: HashMap<ObjectUid, (usize, u32)> = HashMap::with_capacity(meshes.len());
+    let mut vertices_count = 0;
+    for mesh in meshes {
+        first_vertex_offset.insert(mesh.uid, (triangles.len() + QUARTETS_PER_VERTEX * vertices_count, shading_flag(mesh.shading)));
+        vertices_count += mesh.vertices.len();
+    }
+
+    let mut result = GpuReadySerializationBuffer::new(triangles.len() + QUARTETS_PER_VERTEX * vertices_count, 1);
+
+    for triangle in triangles {
+        let (first_vertex, flags) = *first_vertex_offset.get(&triangle.host()).unwrap_or_else(|| panic!("vertices of mesh {} are missing", triangle.host()));
+        let [a, b, c] = triangle.vertex_indices().map(|index| (first_vertex + QUARTETS_PER_VERTEX * index as usize) as u32);
+        result.write_quartet(|writer| {
+            writer.write_unsigned(a).write_unsigned(b).write_unsigned(c).write_unsigned(flags);
+        });
+    }
+
+    for mesh in meshes {
+        for vertex in mesh.vertices {
+            let position = vertex.position();
+            let normal = vertex.normal();
+            result.write_quartet(|writer| {
+                writer.write_float_64(position.x).write_float_64(position.y).write_float_64(position.z).write_unsigned(mesh.uid.0);
+            });
+            result.write_quartet(|writer| {
+                writer.write_float_64(normal.x).write_float_64(normal.y).write_float_64(normal.z).write_unsigned(mesh.material.0 as u32);
+            });
+        }
+    }
+
+    debug_assert!(result.fully_written());
+    result
+}
+
+// the same order of the parts, but the records also keep the offset of the mesh quantization frame (the mesh box),
+// which precedes the mesh vertices, combined with the shading flag; each vertex is one quartet: the position as 16 bit fractions of the frame,
+// the material in the upper half of the third position word, the normal packed into 10 bits per axis, the uid
+#[must_use]
+pub(super) fn serialize_quantized_indexed_triangles(triangles: &[Triangle], meshes: &[MeshVertices]) -> GpuReadySerializationBuffer {
+    let mut first_vertex_offset: HashMap<ObjectUid, (usize, u32)> = HashMap::with_capacity(meshes.len());
+    let mut quartets_count = triangles.len();
+    for mesh in meshes {
+        first_vertex_offset.insert(mesh.uid, (quartets_count + QuantizationFrame::SERIALIZED_QUARTET_COUNT, shading_flag(mesh.shading)));
+        quartets_count += QuantizationFrame::SERIALIZED_QUARTET_COUNT + mesh.vertices.len();
+    }
+
+    assert!(quartets_count < FLAT_SHADING_FLAG as usize, "too many triangles to quantize: {quartets_count} quartets");
+    let mut result = GpuReadySerializationBuffer::new(quartets_count, 1);
+
+    for triangle in triangles {
+        let (first_vertex, flags) = *first_vertex_offset.get(&triangle.host()).unwrap_or_else(|| panic!("vertices of mesh {} are missing", triangle.host()));
+        let [a, b, c] = triangle.vertex_indices().map(|index| (first_vertex + index as usize) as u32);
+        let frame = (first_vertex - QuantizationFrame::SERIALIZED_QUARTET_COUNT) as u32;
+        result.write_quartet(|writer| {
+            writer.write_unsigned(a).write_unsigned(b).write_unsigned(c).write_unsigned(frame | flags);
+        });
+    }
+
+    for mesh in meshes {
+        assert!(mesh.material.0 <= u16::MAX as usize, "material {} does not fit quantized vertex", mesh.material.0);
+        let bounds = mesh.vertices.iter()
+            .fold(Aabb::make_null(), |union, vertex| Aabb::make_union(union, Aabb::from_points(vertex.position(), vertex.position())));
+        let frame = QuantizationFrame::new(&bounds);
+        frame.serialize_into(&mut result);
+
+        for vertex in mesh.vertices {
+            let position = frame.quantize_nearest(vertex.position());
+            result.write_quartet(|writer| {
+                writer.write_unsigned(pack_unorm16_pair(position[0], position[1]));
+                writer.write_unsigned(pack_unorm16_pair(position[2], mesh.material.0 as u16));
+                writer.write_unsigned(pack_normal(vertex.normal()));
+                writer.write_unsigned(mesh.uid.0);
+            });
+        }
+    }
+
+    debug_assert!(result.fully_written());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::alias::{Point, Vector};
+    use crate::objects::common_properties::Linkage;
+    use bytemuck::cast_slice;
+    use cgmath::assert_abs_diff_eq;
+
+    #[must_use]
+    fn make_vertex(x: f64) -> Vertex {
+        Vertex::new(Point::new(x, 0.0, 0.0), Vector::new(0.0, 0.0, x))
+    }
+
+    #[test]
+    fn test_shared_vertices() {
+        let links = Linkage::new(ObjectUid(7), MaterialIndex(3));
+        let vertices = [make_vertex(1.0), make_vertex(2.0), make_vertex(3.0), make_vertex(4.0)];
+        let triangles = [
+            Triangle::new(vertices[0], vertices[1], vertices[2], links).with_vertex_indices([0, 1, 2]),
+            Triangle::new(vertices[2], vertices[1], vertices[3], links).with_vertex_indices([2, 1, 3]),
+        ];
+        let meshes = [MeshVertices { uid: links.uid(), material: links.material_index(), vertices: &vertices, shading: MeshShading::Smooth }];
+
+        let serialized = serialize_indexed_triangles(&triangles, &meshes);
+
+        assert_eq!(serialized.total_slots_count(), triangles.len() + 2 * vertices.len());
+        let words: &[u32] = cast_slice(serialized.backend());
+        assert_eq!(&words[0..3], &[2, 4, 6]);
+        assert_eq!(&words[4..7], &[6, 4, 8]);
+        let last_vertex = &words[4 * 8..4 * 10];
+        assert_eq!(f32::from_bits(last_vertex[0]), 4.0);
+        assert_eq!(last_vertex[3], 7);
+        assert_eq!(f32::from_bits(last_vertex[6]), 4.0);
+        assert_eq!(last_vertex[7], 3);
+    }
+
+    #[test]
+    fn test_several_meshes() {
+        let first = Linkage::new(ObjectUid(1), MaterialIndex(0));
+        let second = Linkage::new(ObjectUid(2), MaterialIndex(0));
+        let first_vertices = [make_vertex(1.0), make_vertex(2.0), make_vertex(3.0)];
+        let second_vertices = [make_vertex(5.0), make_vertex(6.0), make_vertex(7.0)];
+        let triangles = [
+            Triangle::new(second_vertices[0], second_vertices[1], second_vertices[2], second),
+            Triangle::new(first_vertices[0], first_vertices[1], first_vertices[2], first),
+        ];
+        let meshes = [
+            MeshVertices { uid: first.uid(), material: first.material_index(), vertices: &first_vertices, shading: MeshShading::Smooth },
+            MeshVertices { uid: second.uid(), material: second.material_index(), vertices: &second_vertices, shading: MeshShading::Flat },
+        ];
+
+        let serialized = serialize_indexed_triangles(&triangles, &meshes);
+
+        let words: &[u32] = cast_slice(serialized.backend());
+        assert_eq!(&words[0..4], &[8, 10, 12, FLAT_SHADING_FLAG]);
+        assert_eq!(&words[4..8], &[2, 4, 6, 0]);
+        assert_eq!(f32::from_bits(words[4 * 8]), 5.0);
+    }
+
+    #[test]
+    fn test_quantized_vertices() {
+        let links = Linkage::new(ObjectUid(7), MaterialIndex(3));
+        let vertices = [make_vertex(1.0), make_vertex(2.0), make_vertex(5.0)];
+        let triangles = [
+            Triangle::new(vertices[0], vertices[1], vertices[2], links),
+            Triangle::new(vertices[2], vertices[1], vertices[0], links).with_vertex_indices([2, 1, 0]),
+        ];
+        let meshes = [MeshVertices { uid: links.uid(), material: links.material_index(), vertices: &vertices, shading: MeshShading::Smooth }];
+
+        let serialized = serialize_quantized_indexed_triangles(&triangles, &meshes);
+
+        assert_eq!(serialized.total_slots_count(), triangles.len() + 2 + vertices.len());
+        let words: &[u32] = cast_slice(serialized.backend());
+        assert_eq!(&words[0..4], &[4, 5, 6, 2]);
+        assert_eq!(&words[4..8], &[6, 5, 4, 2]);
+        assert_abs_diff_eq!(f32::from_bits(words[4 * 2]), 1.0, epsilon = 1e-4);
+        assert_abs_diff_eq!(f32::from_bits(words[4 * 3]), 4.0 / u16::MAX as f32, epsilon = 1e-8);
+        let last_vertex = &words[4 * 6..4 * 7];
+        assert_eq!(last_vertex[0] & 0xFFFF, u16::MAX as u32);
+        assert_eq!(last_vertex[1] >> 16, 3);
+        assert_eq!(last_vertex[2] >> 20, 1023);
+        assert_eq!(last_vertex[3], 7);
+    }
+
+    #[test]
+    fn test_quantized_flat_shading() {
+        let links = Linkage::new(ObjectUid(7), MaterialIndex(3));
+        let vertices = [make_vertex(1.0), make_vertex(2.0), make_vertex(5.0)];
+        let triangles = [Triangle::new(vertices[0], vertices[1], vertices[2], links)];
+        let meshes = [MeshVertices { uid: links.uid(), material: links.material_index(), vertices: &vertices, shading: MeshShading::Flat }];
+
+        let serialized = serialize_quantized_indexed_triangles(&triangles, &meshes);
+
+        let words: &[u32] = cast_slice(serialized.backend());
+        assert_eq!(&words[0..4], &[3, 4, 5, 1 | FLAT_SHADING_FLAG]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_missing_mesh_vertices() {
+        let vertex = make_vertex(1.0);
+        let triangles = [Triangle::new(vertex, vertex, vertex, Linkage::new(ObjectUid(1), MaterialIndex(0)))];
+
+        let _ = serialize_indexed_triangles(&triangles, &[]);
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::{BufRead, Read};
+use std::ops::ControlFlow;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct MeshLoadProgress {
+    bytes_parsed: u64,
+    bytes_total: u64,
+    triangles_parsed: usize,
+}
+
+impl MeshLoadProgress {
+    #[must_use]
+    pub fn bytes_parsed(&self) -> u64 {
+        self.bytes_parsed
+    }
+
+    #[must_use]
+    pub fn bytes_total(&self) -> u64 {
+        self.bytes_total
+    }
+
+    #[must_use]
+    pub fn triangles_parsed(&self) -> usize {
+        self.triangles_parsed
+    }
+
+    #[must_use]
+    pub fn fraction(&self) -> f64 {
+        if 0 == self.bytes_total {
+            return 1.0;
+        }
+        self.bytes_parsed as f64 / self.bytes_total as f64
+    }
+}
+
+// feeds the (memory mapped) file content to the obj parser, reporting the progress
+// each 'report_step' bytes; the parser consumes the input line by line
+pub(super) struct ProgressReader<'a, Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>> {
+    data: &'a [u8],
+    progress: MeshLoadProgress,
+    observer: Observer,
+    report_step: u64,
+    next_report_at: u64,
+    cancelled: bool,
+}
+
+impl<'a, Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>> ProgressReader<'a, Observer> {
+    pub(super) const DEFAULT_REPORT_STEP: u64 = 1024 * 1024;
+
+    #[must_use]
+    pub(super) fn new(data: &'a [u8], observer: Observer, report_step: u64) -> Self {
+        assert!(report_step > 0, "report step is zero");
+        let progress = MeshLoadProgress { bytes_parsed: 0, bytes_total: data.len() as u64, triangles_parsed: 0 };
+        Self { data, progress, observer, report_step, next_report_at: report_step, cancelled: false }
+    }
+
+    #[must_use]
+    pub(super) fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    #[cfg(test)]
+    #[must_use]
+    pub(super) fn progress(&self) -> MeshLoadProgress {
+        self.progress
+    }
+
+    pub(super) fn report(&mut self) {
+        if (self.observer)(self.progress).is_break() {
+            self.cancelled = true;
+        }
+    }
+
+    #[must_use]
+    fn is_face_statement(line: &[u8]) -> bool {
+        let line = line.trim_ascii_start();
+        line.len() > 1 && b'f' == line[0] && line[1].is_ascii_whitespace()
+    }
+}
+
+impl<Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>> Read for ProgressReader<'_, Observer> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buffer.len());
+        buffer[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl<Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>> BufRead for ProgressReader<'_, Observer> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.cancelled {
+            // not 'Interrupted': line readers retry on that kind
+            return Err(std::io::Error::other("mesh loading has been cancelled"));
+        }
+        Ok(&self.data[self.progress.bytes_parsed as usize..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        let start = self.progress.bytes_parsed as usize;
+        let consumed = &self.data[start..start + amount];
+        if Self::is_face_statement(consumed) {
+            self.progress.triangles_parsed += 1;
+        }
+        self.progress.bytes_parsed += amount as u64;
+
+        if self.progress.bytes_parsed >= self.next_report_at {
+            self.next_report_at = self.progress.bytes_parsed + self.report_step;
+            self.report();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &[u8] = b"v 0 1 0\nv 1 0 0\nv 0 0 1\n  f 1 2 3\nf\t1 3 2\nfoo\n";
+
+    #[test]
+    fn test_counting() {
+        let mut reports: Vec<MeshLoadProgress> = Vec::new();
+        let mut system_under_test = ProgressReader::new(CONTENT, |progress| { reports.push(progress); ControlFlow::Continue(()) }, 20);
+
+        let lines = (&mut system_under_test).lines().count();
+        let last = system_under_test.progress();
+
+        assert_eq!(lines, 6);
+        assert_eq!(last.bytes_parsed(), CONTENT.len() as u64);
+        assert_eq!(last.triangles_parsed(), 2);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].bytes_parsed(), 24);
+        assert_eq!(reports[0].triangles_parsed(), 0);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let mut system_under_test = ProgressReader::new(CONTENT, |_| ControlFlow::Break(()), 1);
+
+        let mut line = String::new();
+        let first = system_under_test.read_line(&mut line);
+        let second = system_under_test.read_line(&mut line);
+
+        assert!(system_under_test.cancelled());
+        assert_eq!(first.unwrap(), 8);
+        assert!(second.is_err());
+    }
+
+    #[test]
+    fn test_fraction_of_empty() {
+        assert_eq!(MeshLoadProgress::default().fraction(), 1.0);
+    }
+}
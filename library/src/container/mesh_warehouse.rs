@@ -1,13 +1,20 @@
 use crate::geometry::alias::{Point, Vector};
 use crate::geometry::axis::Axis;
+use crate::geometry::fundamental_constants::VERTICES_IN_TRIANGLE;
 use crate::geometry::transform::{TransformableCoordinate, Transformation};
 use crate::geometry::vertex::Vertex;
 use crate::objects::common_properties::Linkage;
 use crate::objects::triangle_mesh::{TriangleMesh, VertexData};
-use obj::{Obj, ObjError};
+use crate::container::mesh_load_progress::{MeshLoadProgress, ProgressReader};
+use cgmath::InnerSpace;
+use memmap2::Mmap;
+use obj::raw::object::Polygon;
+use obj::raw::RawObj;
+use obj::ObjError;
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
 use strum::EnumCount;
 use thiserror::Error;
 
@@ -18,43 +25,217 @@ pub enum MeshLoadError {
     #[error("format problem while loading mesh: {what:?}")]
     FormatError { what: String },
     #[error("invalid mesh content: {what:?}")]
-    ContentError {what: String}
+    ContentError {what: String},
+    #[error("mesh loading has been cancelled")]
+    Cancelled,
 }
 
 struct RawMesh {
     vertices: Vec<VertexData>,
     indices: Vec<u32>,
+    dropped_triangles: usize,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct WarehouseSlot(pub(crate) usize);
 
+// how the tracer gets the normal inside a triangle of a mesh instance
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum MeshShading {
+    // the normal of the triangle plane
+    Flat,
+    // the vertex normals interpolated across the triangle
+    #[default]
+    Smooth,
+}
+
 pub struct MeshWarehouse {
     prototypes: Vec<RawMesh>,
+    sources: Vec<PathBuf>,
 }
 
 impl MeshWarehouse {
     #[must_use]
     pub fn new() -> Self {
-        Self { prototypes: Vec::new() }
+        Self { prototypes: Vec::new(), sources: Vec::new() }
+    }
+
+    // the file the slot was loaded from
+    #[must_use]
+    pub fn source_of(&self, slot: WarehouseSlot) -> &Path {
+        &self.sources[slot.0]
+    }
+
+    // the triangles of the source file without area or with non-finite positions are not loaded: they have
+    // no plane to shade by; a warning is logged as well
+    #[must_use]
+    pub fn dropped_triangles(&self, slot: WarehouseSlot) -> usize {
+        self.prototypes[slot.0].dropped_triangles
+    }
+
+    // parses the source file of the slot once more; the slot keeps the old mesh if that fails;
+    // the instances already in a scene are not touched, see 'Hub::reshape_mesh'
+    pub fn reload(&mut self, slot: WarehouseSlot) -> Result<(), MeshLoadError> {
+        self.reload_with_progress(slot, |_| ControlFlow::Continue(()))
+    }
+
+    pub fn reload_with_progress<Observer>(&mut self, slot: WarehouseSlot, observer: Observer) -> Result<(), MeshLoadError>
+    where Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>
+    {
+        let mesh = MeshWarehouse::parse(&self.sources[slot.0], observer, ProgressReader::<Observer>::DEFAULT_REPORT_STEP)?;
+        self.prototypes[slot.0] = mesh;
+        Ok(())
     }
 
     pub fn load(&mut self, source_file: impl AsRef<Path>) -> Result<WarehouseSlot, MeshLoadError> {
+        self.load_with_progress(source_file, |_| ControlFlow::Continue(()))
+    }
+
+    // the observer is called each megabyte of the parsed file and once the mesh is built;
+    // breaking from it cancels the loading; the warehouse stays unchanged in that case
+    pub fn load_with_progress<Observer>(&mut self, source_file: impl AsRef<Path>, observer: Observer) -> Result<WarehouseSlot, MeshLoadError>
+    where Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>
+    {
+        self.load_reporting_each(source_file, observer, ProgressReader::<Observer>::DEFAULT_REPORT_STEP)
+    }
+
+    fn load_reporting_each<Observer>(&mut self, source_file: impl AsRef<Path>, observer: Observer, report_step: u64) -> Result<WarehouseSlot, MeshLoadError>
+    where Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>
+    {
+        let mesh = MeshWarehouse::parse(source_file.as_ref(), observer, report_step)?;
+
+        self.prototypes.push(mesh);
+        self.sources.push(source_file.as_ref().to_path_buf());
+
+        Ok(WarehouseSlot(self.prototypes.len() - 1))
+    }
+
+    fn parse<Observer>(source_file: &Path, observer: Observer, report_step: u64) -> Result<RawMesh, MeshLoadError>
+    where Observer: FnMut(MeshLoadProgress) -> ControlFlow<()>
+    {
         let file = File::open(source_file).map_err(|e| MeshLoadError::IoError { what: e.to_string() })?;
-        let reader = BufReader::new(file);
-        let obj: Obj<obj::Vertex, u32> = obj::load_obj::<obj::Vertex, BufReader<File>, u32>(reader).map_err(MeshWarehouse::translate_error)?;
+        // safety: the mapping is read only and dropped before return; truncation of the file
+        // by another process meanwhile is the caller's responsibility
+        let content = unsafe { Mmap::map(&file) }.map_err(|e| MeshLoadError::IoError { what: e.to_string() })?;
 
-        if obj.indices.is_empty() || obj.vertices.is_empty() {
+        let mut reader = ProgressReader::new(&content, observer, report_step);
+        let parsed = obj::raw::parse_obj(&mut reader);
+        if reader.cancelled() {
+            return Err(MeshLoadError::Cancelled);
+        }
+        let raw = parsed.map_err(MeshWarehouse::translate_error)?;
+
+        if raw.polygons.is_empty() || raw.positions.is_empty() {
             return Err(MeshLoadError::ContentError { what: "empty mesh".to_string() });
         }
 
-        let vertices: Vec<VertexData> = {
-            let vertices_bytes = bytemuck::cast_slice(&obj.vertices);
-            vertices_bytes.to_vec()
-        };
-        self.prototypes.push(RawMesh { vertices, indices: obj.indices });
+        let mesh = MeshWarehouse::build_mesh(&raw)?;
+        if mesh.dropped_triangles > 0 {
+            log::warn!("{} degenerate triangles of {} are dropped", mesh.dropped_triangles, source_file.display());
+        }
 
-        Ok(WarehouseSlot(self.prototypes.len() - 1))
+        reader.report();
+        if reader.cancelled() {
+            return Err(MeshLoadError::Cancelled);
+        }
+
+        Ok(mesh)
+    }
+
+    // the corners with the same position and normal share a vertex; the corners without
+    // a normal share the position only and get the smooth one: the sum of the normals
+    // of the triangles around, weighted by the triangle areas; so do the corners with
+    // a zero or non-finite normal; the degenerate triangles are dropped
+    fn build_mesh(raw: &RawObj) -> Result<RawMesh, MeshLoadError> {
+        let mut vertices: Vec<VertexData> = Vec::new();
+        let mut indices: Vec<u32> = Vec::with_capacity(raw.polygons.len() * VERTICES_IN_TRIANGLE);
+        let mut vertex_of_corner: HashMap<(usize, Option<usize>), u32> = HashMap::new();
+        let mut smoothed: Vec<bool> = Vec::new();
+        let mut dropped_triangles = 0;
+
+        for polygon in &raw.polygons {
+            let corners: Vec<(usize, Option<usize>)> = match polygon {
+                Polygon::P(corners) => corners.iter().map(|&position| (position, None)).collect(),
+                Polygon::PT(corners) => corners.iter().map(|&(position, _)| (position, None)).collect(),
+                Polygon::PN(corners) => corners.iter().map(|&(position, normal)| (position, Some(normal))).collect(),
+                Polygon::PTN(corners) => corners.iter().map(|&(position, _, normal)| (position, Some(normal))).collect(),
+            };
+            if corners.len() != VERTICES_IN_TRIANGLE {
+                return Err(MeshLoadError::ContentError { what: format!("polygon of {} vertices: mesh must be triangulated", corners.len()) });
+            }
+            if MeshWarehouse::degenerate(raw, &corners)? {
+                dropped_triangles += 1;
+                continue;
+            }
+            for corner in corners {
+                if let Some(&index) = vertex_of_corner.get(&corner) {
+                    indices.push(index);
+                    continue;
+                }
+                let (position, normal) = corner;
+                let position = raw.positions.get(position)
+                    .ok_or_else(|| MeshLoadError::ContentError { what: format!("position index {position} is out of range") })?;
+                let normal = match normal {
+                    Some(normal) => {
+                        let normal = raw.normals.get(normal)
+                            .ok_or_else(|| MeshLoadError::ContentError { what: format!("normal index {normal} is out of range") })?;
+                        [normal.0, normal.1, normal.2]
+                    }
+                    None => [0.0; 3],
+                };
+                let usable_normal = normal.iter().all(|component| component.is_finite()) && normal.iter().any(|component| *component != 0.0);
+                let index = vertices.len() as u32;
+                vertices.push(VertexData { position: [position.0, position.1, position.2], normal });
+                smoothed.push(false == usable_normal);
+                vertex_of_corner.insert(corner, index);
+                indices.push(index);
+            }
+        }
+
+        if indices.is_empty() {
+            return Err(MeshLoadError::ContentError { what: "all triangles are degenerate".to_string() });
+        }
+
+        if smoothed.iter().any(|&smooth| smooth) {
+            MeshWarehouse::smooth_normals(&mut vertices, &indices, &smoothed);
+        }
+
+        Ok(RawMesh { vertices, indices, dropped_triangles })
+    }
+
+    // no area to hit (e.g. repeated or collinear corners) or non-finite positions
+    fn degenerate(raw: &RawObj, corners: &[(usize, Option<usize>)]) -> Result<bool, MeshLoadError> {
+        let mut positions = [Vector::new(0.0, 0.0, 0.0); VERTICES_IN_TRIANGLE];
+        for (position, &(index, _)) in positions.iter_mut().zip(corners) {
+            let raw_position = raw.positions.get(index)
+                .ok_or_else(|| MeshLoadError::ContentError { what: format!("position index {index} is out of range") })?;
+            *position = Vector::new(raw_position.0 as f64, raw_position.1 as f64, raw_position.2 as f64);
+        }
+        let [a, b, c] = positions;
+        let doubled_area = (b - a).cross(c - a).magnitude();
+        Ok(false == (doubled_area > 0.0 && doubled_area.is_finite()))
+    }
+
+    fn smooth_normals(vertices: &mut [VertexData], indices: &[u32], smoothed: &[bool]) {
+        let vector = |components: [f32; Axis::COUNT]| Vector::new(components[0] as f64, components[1] as f64, components[2] as f64);
+        let mut sums = vec![Vector::new(0.0, 0.0, 0.0); vertices.len()];
+        for triangle in indices.chunks(VERTICES_IN_TRIANGLE) {
+            let [a, b, c] = [0, 1, 2].map(|corner| vector(vertices[triangle[corner] as usize].position));
+            // the length of the cross product is the doubled area
+            let face_normal = (b - a).cross(c - a);
+            for &index in triangle {
+                sums[index as usize] += face_normal;
+            }
+        }
+        for (index, vertex) in vertices.iter_mut().enumerate() {
+            if false == smoothed[index] {
+                continue;
+            }
+            let sum = sums[index];
+            // the normals of the triangles around may cancel each other out: any direction will do
+            let normal = if sum.magnitude2() > 0.0 { sum.normalize() } else { Vector::unit_z() };
+            vertex.normal = [normal.x as f32, normal.y as f32, normal.z as f32];
+        }
     }
 
     #[must_use]
@@ -98,12 +279,10 @@ mod tests {
     use crate::geometry::transform::Affine;
     use crate::objects::common_properties::ObjectUid;
     use crate::objects::triangle::Triangle;
-    use std::io::Write;
+    use std::io::{Seek, Write};
     use tempfile::NamedTempFile;
     use crate::material::material_index::MaterialIndex;
 
-    const _: () = assert!(size_of::<VertexData>() == size_of::<obj::Vertex>());
-
     const TEST_LINKS: Linkage = Linkage::new(ObjectUid(0), MaterialIndex(1));
 
     const SINGLE_TRIANGLE_OBJ_FILE: &str = r#"
@@ -134,4 +313,172 @@ mod tests {
 
         assert_eq!(triangles.len(), 1);
     }
+
+    #[must_use]
+    fn make_obj_file() -> NamedTempFile {
+        make_obj_file_of(SINGLE_TRIANGLE_OBJ_FILE)
+    }
+
+    #[test]
+    fn test_load_with_progress() {
+        let temp_file = make_obj_file();
+        let mut reports: Vec<MeshLoadProgress> = Vec::new();
+
+        let mut system_under_test = MeshWarehouse::new();
+        let slot = system_under_test.load_reporting_each(temp_file.path(), |progress| { reports.push(progress); ControlFlow::Continue(()) }, 64);
+
+        assert!(slot.is_ok());
+        assert_eq!(reports.len(), 2);
+        let last = reports.last().unwrap();
+        assert_eq!(last.bytes_parsed(), SINGLE_TRIANGLE_OBJ_FILE.len() as u64);
+        assert_eq!(last.bytes_total(), SINGLE_TRIANGLE_OBJ_FILE.len() as u64);
+        assert_eq!(last.triangles_parsed(), 1);
+    }
+
+    #[test]
+    fn test_cancelled_load() {
+        let temp_file = make_obj_file();
+
+        let mut system_under_test = MeshWarehouse::new();
+        let cancelled_while_parsing = system_under_test.load_reporting_each(temp_file.path(), |_| ControlFlow::Break(()), 1);
+        let cancelled_after_parsing = system_under_test.load_with_progress(temp_file.path(), |_| ControlFlow::Break(()));
+        let loaded = system_under_test.load(temp_file.path());
+
+        assert!(matches!(cancelled_while_parsing, Err(MeshLoadError::Cancelled)));
+        assert!(matches!(cancelled_after_parsing, Err(MeshLoadError::Cancelled)));
+        assert_eq!(loaded.unwrap(), WarehouseSlot(0));
+    }
+
+    #[must_use]
+    fn make_obj_file_of(content: &str) -> NamedTempFile {
+        let mut temp_file = NamedTempFile::new_in("./").expect("failed to create temp file");
+        temp_file.write_all(content.as_bytes()).expect("failed to write dummy data into the temp file");
+        temp_file
+    }
+
+    #[test]
+    fn test_smooth_normals_of_mesh_without_normals() {
+        let temp_file = make_obj_file_of(r#"
+            v 0.0 0.0 0.0
+            v 1.0 0.0 0.0
+            v 0.0 1.0 0.0
+            v 0.0 0.0 1.0
+
+            f 1 2 3
+            f 1 3 4
+            "#);
+
+        let mut system_under_test = MeshWarehouse::new();
+        let slot = system_under_test.load(temp_file.path()).unwrap();
+
+        let mesh = &system_under_test.prototypes[slot.0];
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices, [0, 1, 2, 0, 2, 3]);
+        let shared = mesh.vertices[0].normal;
+        assert!((shared[0] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6 && shared[1].abs() < 1e-6 && (shared[2] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(mesh.vertices[1].normal, [0.0, 0.0, 1.0]);
+        assert_eq!(mesh.vertices[3].normal, [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_explicit_normals_are_kept() {
+        let temp_file = make_obj_file();
+
+        let mut system_under_test = MeshWarehouse::new();
+        let slot = system_under_test.load(temp_file.path()).unwrap();
+
+        assert!(system_under_test.prototypes[slot.0].vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_reload_replaces_prototype_in_place() {
+        let mut temp_file = make_obj_file();
+        let mut system_under_test = MeshWarehouse::new();
+        let slot = system_under_test.load(temp_file.path()).unwrap();
+
+        temp_file.as_file_mut().set_len(0).unwrap();
+        temp_file.as_file_mut().rewind().unwrap();
+        temp_file.write_all(r#"
+            v 0.0 0.0 0.0
+            v 1.0 0.0 0.0
+            v 0.0 1.0 0.0
+            v 0.0 0.0 1.0
+
+            f 1 2 3
+            f 1 3 4
+            "#.as_bytes()).unwrap();
+        system_under_test.reload(slot).unwrap();
+
+        assert_eq!(system_under_test.source_of(slot), temp_file.path());
+        assert_eq!(system_under_test.prototypes.len(), 1);
+        assert_eq!(system_under_test.prototypes[slot.0].indices.len(), 6);
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_prototype() {
+        let mut temp_file = make_obj_file();
+        let mut system_under_test = MeshWarehouse::new();
+        let slot = system_under_test.load(temp_file.path()).unwrap();
+
+        temp_file.as_file_mut().set_len(0).unwrap();
+
+        assert!(matches!(system_under_test.reload(slot), Err(MeshLoadError::ContentError { .. })));
+        assert_eq!(system_under_test.prototypes[slot.0].indices.len(), 3);
+    }
+
+    #[test]
+    fn test_degenerate_triangles_are_dropped() {
+        let temp_file = make_obj_file_of(r#"
+            v 0.0 0.0 0.0
+            v 1.0 0.0 0.0
+            v 0.0 1.0 0.0
+            v 2.0 0.0 0.0
+
+            vn 0.0 0.0 0.0
+
+            f 1//1 2//1 3//1
+            f 1 2 4
+            f 1 1 3
+            "#);
+
+        let mut system_under_test = MeshWarehouse::new();
+        let slot = system_under_test.load(temp_file.path()).unwrap();
+
+        assert_eq!(system_under_test.dropped_triangles(slot), 2);
+        let mesh = &system_under_test.prototypes[slot.0];
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+        // the zero normal is replaced by the smooth one
+        assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_mesh_of_degenerate_triangles_is_rejected() {
+        let temp_file = make_obj_file_of(r#"
+            v 0.0 0.0 0.0
+            v 1.0 0.0 0.0
+            v 2.0 0.0 0.0
+
+            f 1 2 3
+            "#);
+
+        let mut system_under_test = MeshWarehouse::new();
+
+        assert!(matches!(system_under_test.load(temp_file.path()), Err(MeshLoadError::ContentError { .. })));
+    }
+
+    #[test]
+    fn test_polygons_are_rejected() {
+        let temp_file = make_obj_file_of(r#"
+            v 0.0 0.0 0.0
+            v 1.0 0.0 0.0
+            v 1.0 1.0 0.0
+            v 0.0 1.0 0.0
+
+            f 1 2 3 4
+            "#);
+
+        let mut system_under_test = MeshWarehouse::new();
+
+        assert!(matches!(system_under_test.load(temp_file.path()), Err(MeshLoadError::ContentError { .. })));
+    }
 }
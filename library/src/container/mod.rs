@@ -1,10 +1,19 @@
 pub mod visual_objects;
 pub mod mesh_warehouse;
+pub mod mesh_load_progress;
+pub mod scene_builder;
 pub(crate) mod sdf_warehouse;
+pub(crate) mod sdf_distance_grids;
 mod monolithic;
+mod object_layers;
+mod object_uv_transforms;
+mod object_opacities;
+mod object_slots;
+mod dirty_slots;
 mod scene_object;
 mod statistics;
 mod triangulated;
+mod indexed_triangles;
 pub(crate) mod bvh_proxies;
 pub mod texture_atlas_page_composer;
 pub mod texture_helpers;
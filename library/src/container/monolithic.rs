@@ -1,7 +1,11 @@
 use crate::container::scene_object::{SceneEnvironment, SceneObject};
+use crate::geometry::alias::Vector;
+use crate::geometry::ray::{Ray, RayIntersection};
 use crate::geometry::transform::Affine;
 use crate::material::material_index::MaterialIndex;
+use crate::objects::parallelogram::Parallelogram;
 use crate::objects::ray_traceable::RayTraceable;
+use crate::sdf::framework::sdf_parameters::SdfParameterValues;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
 
 pub(super) struct Monolithic {
@@ -30,6 +34,10 @@ impl SceneObject for Monolithic {
     fn set_material(&mut self, new_material: MaterialIndex, _environment: &mut SceneEnvironment) {
         self.geometry.set_material(new_material)
     }
+    fn translate(&mut self, offset: Vector, _environment: &mut SceneEnvironment) {
+        self.geometry.translate(offset);
+        self.transformation = Affine::from_translation(offset) * self.transformation;
+    }
 
     fn data_kind_uid(&self) -> usize {
         self.geometry_kind
@@ -41,6 +49,21 @@ impl SceneObject for Monolithic {
         &self.transformation
     }
 
+    fn intersect(&self, ray: &Ray) -> Option<RayIntersection> {
+        self.geometry.intersect(ray)
+    }
+
+    fn parallelogram(&self) -> Option<&Parallelogram> {
+        self.geometry.parallelogram()
+    }
+
+    fn sdf_parameters(&self) -> Option<SdfParameterValues> {
+        self.geometry.sdf_parameters()
+    }
+    fn set_sdf_parameters(&mut self, parameters: SdfParameterValues) {
+        self.geometry.set_sdf_parameters(parameters)
+    }
+
     fn serialized_quartet_count(&self) -> usize {
         self.geometry.serialized_quartet_count()
     }
@@ -68,9 +91,15 @@ mod tests {
 
         fn set_material(&mut self, _material_index: MaterialIndex) {}
 
+        fn translate(&mut self, _offset: Vector) {}
+
         fn serialized_quartet_count(&self) -> usize {
             0
         }
+
+        fn intersect(&self, _ray: &Ray) -> Option<RayIntersection> {
+            None
+        }
     }
 
     #[test]
@@ -0,0 +1,149 @@
+use crate::scene::layer_mask::LayerMask;
+use crate::utils::object_uid::ObjectUid;
+use crate::utils::version::Version;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct ObjectLayer {
+    visible: bool,
+    mask: LayerMask,
+}
+
+impl Default for ObjectLayer {
+    fn default() -> Self {
+        Self { visible: true, mask: LayerMask::DEFAULT_OBJECT_LAYER }
+    }
+}
+
+// the visibility and the layers of the objects, indexed by the uid: the gpu gets the layers of each
+// object (zero for the hidden ones) and skips the objects whose layers the camera does not see;
+// a hidden object keeps its uid and its slot, so showing it again rebuilds nothing but this array
+pub(super) struct ObjectLayers {
+    per_uid: Vec<ObjectLayer>,
+    version: Version,
+}
+
+impl ObjectLayers {
+    #[must_use]
+    pub(super) fn new() -> Self {
+        // the zero uid is never assigned: the gpu never traces it
+        Self { per_uid: vec![ObjectLayer { visible: false, mask: LayerMask::NONE }], version: Version(0) }
+    }
+
+    // the uid of a new or a deleted object starts over as visible on the default layer
+    pub(super) fn reset(&mut self, uid: ObjectUid) {
+        let index = uid.0 as usize;
+        if index >= self.per_uid.len() {
+            self.per_uid.resize(index + 1, ObjectLayer::default());
+            self.version += 1;
+        } else if self.per_uid[index] != ObjectLayer::default() {
+            self.per_uid[index] = ObjectLayer::default();
+            self.version += 1;
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        let reset = self.per_uid.iter().skip(1).any(|layer| *layer != ObjectLayer::default());
+        self.per_uid.iter_mut().skip(1).for_each(|layer| *layer = ObjectLayer::default());
+        if reset {
+            self.version += 1;
+        }
+    }
+
+    pub(super) fn set_visible(&mut self, uid: ObjectUid, visible: bool) {
+        let layer = &mut self.per_uid[uid.0 as usize];
+        if layer.visible != visible {
+            layer.visible = visible;
+            self.version += 1;
+        }
+    }
+
+    #[must_use]
+    pub(super) fn visible(&self, uid: ObjectUid) -> bool {
+        self.per_uid[uid.0 as usize].visible
+    }
+
+    pub(super) fn set_mask(&mut self, uid: ObjectUid, mask: LayerMask) {
+        let layer = &mut self.per_uid[uid.0 as usize];
+        if layer.mask != mask {
+            layer.mask = mask;
+            self.version += 1;
+        }
+    }
+
+    #[must_use]
+    pub(super) fn mask(&self, uid: ObjectUid) -> LayerMask {
+        self.per_uid[uid.0 as usize].mask
+    }
+
+    // whether a camera seeing the layers sees the object
+    #[must_use]
+    pub(super) fn traced(&self, uid: ObjectUid, layers: LayerMask) -> bool {
+        let layer = self.per_uid[uid.0 as usize];
+        layer.visible && layer.mask.overlaps(layers)
+    }
+
+    #[must_use]
+    pub(super) fn version(&self) -> Version {
+        self.version
+    }
+
+    #[must_use]
+    pub(super) fn serialize(&self) -> Vec<u32> {
+        self.per_uid.iter().map(|layer| if layer.visible { layer.mask.0 } else { LayerMask::NONE.0 }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_grows_the_array() {
+        let mut system_under_test = ObjectLayers::new();
+        let initial_version = system_under_test.version();
+
+        system_under_test.reset(ObjectUid(3));
+
+        assert_eq!(system_under_test.serialize(), vec![0, 1, 1, 1]);
+        assert!(system_under_test.version() > initial_version);
+    }
+
+    #[test]
+    fn test_hidden_object_has_no_layers() {
+        let mut system_under_test = ObjectLayers::new();
+        system_under_test.reset(ObjectUid(1));
+        system_under_test.reset(ObjectUid(2));
+
+        system_under_test.set_mask(ObjectUid(1), LayerMask(0b110));
+        system_under_test.set_visible(ObjectUid(2), false);
+
+        assert_eq!(system_under_test.serialize(), vec![0, 0b110, 0]);
+        assert_eq!(system_under_test.mask(ObjectUid(2)), LayerMask::DEFAULT_OBJECT_LAYER);
+        assert!(false == system_under_test.visible(ObjectUid(2)));
+    }
+
+    #[test]
+    fn test_unchanged_state_keeps_version() {
+        let mut system_under_test = ObjectLayers::new();
+        system_under_test.reset(ObjectUid(1));
+        let version = system_under_test.version();
+
+        system_under_test.set_visible(ObjectUid(1), true);
+        system_under_test.set_mask(ObjectUid(1), LayerMask::DEFAULT_OBJECT_LAYER);
+        system_under_test.reset(ObjectUid(1));
+
+        assert_eq!(system_under_test.version(), version);
+    }
+
+    #[test]
+    fn test_reset_of_reused_uid() {
+        let mut system_under_test = ObjectLayers::new();
+        system_under_test.reset(ObjectUid(1));
+        system_under_test.set_visible(ObjectUid(1), false);
+
+        system_under_test.reset(ObjectUid(1));
+
+        assert!(system_under_test.visible(ObjectUid(1)));
+        assert_eq!(system_under_test.serialize(), vec![0, 1]);
+    }
+}
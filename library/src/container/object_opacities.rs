@@ -0,0 +1,94 @@
+use crate::utils::object_uid::ObjectUid;
+use crate::utils::version::Version;
+
+const OPAQUE: f32 = 1.0;
+
+// the opacities of the objects, indexed by the uid: a ghosted object (below one) is traced by that part
+// of the rays only, so the accumulated image blends it with what is behind it
+pub(super) struct ObjectOpacities {
+    per_uid: Vec<f32>,
+    version: Version,
+}
+
+impl ObjectOpacities {
+    #[must_use]
+    pub(super) fn new() -> Self {
+        Self { per_uid: vec![OPAQUE], version: Version(0) }
+    }
+
+    // the uid of a new or a deleted object starts over as opaque
+    pub(super) fn reset(&mut self, uid: ObjectUid) {
+        let index = uid.0 as usize;
+        if index >= self.per_uid.len() {
+            self.per_uid.resize(index + 1, OPAQUE);
+            self.version += 1;
+        } else if self.per_uid[index] != OPAQUE {
+            self.per_uid[index] = OPAQUE;
+            self.version += 1;
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        let reset = self.per_uid.iter().any(|opacity| *opacity != OPAQUE);
+        self.per_uid.fill(OPAQUE);
+        if reset {
+            self.version += 1;
+        }
+    }
+
+    pub(super) fn set(&mut self, uid: ObjectUid, opacity: f32) {
+        let current = &mut self.per_uid[uid.0 as usize];
+        if *current != opacity {
+            *current = opacity;
+            self.version += 1;
+        }
+    }
+
+    #[must_use]
+    pub(super) fn get(&self, uid: ObjectUid) -> f32 {
+        self.per_uid[uid.0 as usize]
+    }
+
+    #[must_use]
+    pub(super) fn version(&self) -> Version {
+        self.version
+    }
+
+    #[must_use]
+    pub(super) fn serialize(&self) -> &[f32] {
+        &self.per_uid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_grows_the_array() {
+        let mut system_under_test = ObjectOpacities::new();
+
+        system_under_test.reset(ObjectUid(2));
+
+        assert_eq!(system_under_test.serialize(), &[OPAQUE; 3]);
+    }
+
+    #[test]
+    fn test_version() {
+        let mut system_under_test = ObjectOpacities::new();
+        system_under_test.reset(ObjectUid(1));
+        let version = system_under_test.version();
+
+        system_under_test.set(ObjectUid(1), OPAQUE);
+        assert_eq!(system_under_test.version(), version);
+
+        system_under_test.set(ObjectUid(1), 0.25);
+        assert_ne!(system_under_test.version(), version);
+        assert_eq!(system_under_test.serialize(), &[OPAQUE, 0.25]);
+
+        let version = system_under_test.version();
+        system_under_test.clear();
+        assert_ne!(system_under_test.version(), version);
+        assert_eq!(system_under_test.get(ObjectUid(1)), OPAQUE);
+    }
+}
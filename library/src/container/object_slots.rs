@@ -0,0 +1,106 @@
+use crate::utils::object_uid::ObjectUid;
+use std::collections::{BTreeSet, HashMap};
+
+// persistent positions of the objects of a kind in the gpu buffer: a deleted object leaves a hole,
+// which the next addition takes (the lowest hole first), so the positions of the others never shift;
+// the trailing holes are dropped, so the buffer shrinks when the last objects go
+#[derive(Default)]
+pub(super) struct ObjectSlots {
+    occupants: Vec<Option<ObjectUid>>,
+    slot_of: HashMap<ObjectUid, usize>,
+    vacant: BTreeSet<usize>,
+}
+
+impl ObjectSlots {
+    #[must_use]
+    pub(super) fn occupy(&mut self, uid: ObjectUid) -> usize {
+        assert!(false == self.slot_of.contains_key(&uid), "object {uid} already has a slot");
+        let slot = if let Some(hole) = self.vacant.pop_first() {
+            self.occupants[hole] = Some(uid);
+            hole
+        } else {
+            self.occupants.push(Some(uid));
+            self.occupants.len() - 1
+        };
+        self.slot_of.insert(uid, slot);
+        slot
+    }
+
+    pub(super) fn release(&mut self, uid: ObjectUid) -> Option<usize> {
+        let slot = self.slot_of.remove(&uid)?;
+        self.occupants[slot] = None;
+        self.vacant.insert(slot);
+
+        while let Some(None) = self.occupants.last() {
+            self.occupants.pop();
+            self.vacant.remove(&self.occupants.len());
+        }
+        Some(slot)
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.occupants.clear();
+        self.slot_of.clear();
+        self.vacant.clear();
+    }
+
+    #[must_use]
+    pub(super) fn slot(&self, uid: ObjectUid) -> Option<usize> {
+        self.slot_of.get(&uid).copied()
+    }
+
+    #[must_use]
+    pub(super) fn occupants(&self) -> &[Option<ObjectUid>] {
+        &self.occupants
+    }
+
+    #[must_use]
+    pub(super) fn len(&self) -> usize {
+        self.occupants.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots_survive_deletion() {
+        let mut system_under_test = ObjectSlots::default();
+        let slots = [1, 2, 3].map(|uid| system_under_test.occupy(ObjectUid(uid)));
+
+        assert_eq!(system_under_test.release(ObjectUid(2)), Some(1));
+
+        assert_eq!(slots, [0, 1, 2]);
+        assert_eq!(system_under_test.slot(ObjectUid(3)), Some(2));
+        assert_eq!(system_under_test.occupants(), [Some(ObjectUid(1)), None, Some(ObjectUid(3))]);
+    }
+
+    #[test]
+    fn test_lowest_hole_is_reused() {
+        let mut system_under_test = ObjectSlots::default();
+        for uid in 1..=4 {
+            let _ = system_under_test.occupy(ObjectUid(uid));
+        }
+        let _ = system_under_test.release(ObjectUid(3));
+        let _ = system_under_test.release(ObjectUid(2));
+
+        assert_eq!(system_under_test.occupy(ObjectUid(7)), 1);
+        assert_eq!(system_under_test.occupy(ObjectUid(8)), 2);
+        assert_eq!(system_under_test.occupy(ObjectUid(9)), 4);
+    }
+
+    #[test]
+    fn test_trailing_holes_are_dropped() {
+        let mut system_under_test = ObjectSlots::default();
+        for uid in 1..=3 {
+            let _ = system_under_test.occupy(ObjectUid(uid));
+        }
+        let _ = system_under_test.release(ObjectUid(2));
+        let _ = system_under_test.release(ObjectUid(3));
+
+        assert_eq!(system_under_test.len(), 1);
+        assert_eq!(system_under_test.occupy(ObjectUid(5)), 1);
+        assert_eq!(system_under_test.release(ObjectUid(17)), None);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::scene::uv_transform::UvTransform;
+use crate::utils::object_uid::ObjectUid;
+use crate::utils::version::Version;
+use exr::prelude::f16;
+
+// the texture coordinate transforms of the objects, indexed by the uid: a quartet per object, the offset
+// in the first two floats, the rows of the linear part in the last two, each row as two packed halves
+pub(super) struct ObjectUvTransforms {
+    per_uid: Vec<UvTransform>,
+    version: Version,
+}
+
+impl ObjectUvTransforms {
+    #[must_use]
+    pub(super) fn new() -> Self {
+        Self { per_uid: vec![UvTransform::IDENTITY], version: Version(0) }
+    }
+
+    // the uid of a new or a deleted object starts over with the identity
+    pub(super) fn reset(&mut self, uid: ObjectUid) {
+        let index = uid.0 as usize;
+        if index >= self.per_uid.len() {
+            self.per_uid.resize(index + 1, UvTransform::IDENTITY);
+            self.version += 1;
+        } else if self.per_uid[index] != UvTransform::IDENTITY {
+            self.per_uid[index] = UvTransform::IDENTITY;
+            self.version += 1;
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        let reset = self.per_uid.iter().any(|transform| *transform != UvTransform::IDENTITY);
+        self.per_uid.fill(UvTransform::IDENTITY);
+        if reset {
+            self.version += 1;
+        }
+    }
+
+    pub(super) fn set(&mut self, uid: ObjectUid, transform: UvTransform) {
+        let current = &mut self.per_uid[uid.0 as usize];
+        if *current != transform {
+            *current = transform;
+            self.version += 1;
+        }
+    }
+
+    #[must_use]
+    pub(super) fn get(&self, uid: ObjectUid) -> UvTransform {
+        self.per_uid[uid.0 as usize]
+    }
+
+    #[must_use]
+    pub(super) fn version(&self) -> Version {
+        self.version
+    }
+
+    #[must_use]
+    pub(super) fn serialize(&self) -> Vec<[f32; 4]> {
+        let pack = |low: f32, high: f32| f32::from_bits(u32::from(f16::from_f32(low).to_bits()) | (u32::from(f16::from_f32(high).to_bits()) << 16));
+        self.per_uid.iter().map(|transform| {
+            let linear = transform.linear();
+            let offset = transform.offset();
+            [offset.x, offset.y, pack(linear.x.x, linear.y.x), pack(linear.x.y, linear.y.y)]
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[must_use]
+    fn unpack(packed: f32) -> (f32, f32) {
+        (f16::from_bits(packed.to_bits() as u16).to_f32(), f16::from_bits((packed.to_bits() >> 16) as u16).to_f32())
+    }
+
+    #[test]
+    fn test_identity_serialization() {
+        let mut system_under_test = ObjectUvTransforms::new();
+        system_under_test.reset(ObjectUid(1));
+
+        let serialized = system_under_test.serialize();
+
+        assert_eq!(serialized.len(), 2);
+        assert_eq!(serialized[1][0..2], [0.0, 0.0]);
+        assert_eq!(unpack(serialized[1][2]), (1.0, 0.0));
+        assert_eq!(unpack(serialized[1][3]), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_rows_are_packed() {
+        let mut system_under_test = ObjectUvTransforms::new();
+        system_under_test.reset(ObjectUid(1));
+        system_under_test.set(ObjectUid(1), UvTransform::new().with_scale(2.0, 0.5).with_offset(0.25, -1.0));
+
+        let serialized = system_under_test.serialize()[1];
+
+        assert_eq!(serialized[0..2], [0.25, -1.0]);
+        assert_eq!(unpack(serialized[2]), (2.0, 0.0));
+        assert_eq!(unpack(serialized[3]), (0.0, 0.5));
+    }
+
+    #[test]
+    fn test_version() {
+        let mut system_under_test = ObjectUvTransforms::new();
+        system_under_test.reset(ObjectUid(1));
+        let version = system_under_test.version();
+
+        system_under_test.set(ObjectUid(1), UvTransform::IDENTITY);
+        assert_eq!(system_under_test.version(), version);
+
+        system_under_test.set(ObjectUid(1), UvTransform::new().with_scale(4.0, 4.0));
+        assert_ne!(system_under_test.version(), version);
+
+        let version = system_under_test.version();
+        system_under_test.reset(ObjectUid(1));
+        assert_ne!(system_under_test.version(), version);
+        assert_eq!(system_under_test.get(ObjectUid(1)), UvTransform::IDENTITY);
+    }
+}
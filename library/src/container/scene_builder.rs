@@ -0,0 +1,133 @@
+use crate::container::visual_objects::VisualObjects;
+use crate::geometry::alias::{Point, Vector};
+use crate::material::material_builder::MaterialBuilder;
+use crate::material::material_index::MaterialIndex;
+use crate::material::material_properties::MaterialProperties;
+
+// chained construction of the common setups; the objects take the material chosen last
+pub struct SceneBuilder {
+    objects: VisualObjects,
+    material: Option<MaterialIndex>,
+}
+
+impl SceneBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_objects(VisualObjects::new(None, None, None, None, None))
+    }
+
+    // the objects with the sdf classes, the textures or the backgrounds already there
+    #[must_use]
+    pub fn from_objects(objects: VisualObjects) -> Self {
+        Self { objects, material: None }
+    }
+
+    #[must_use]
+    pub fn material(mut self, material: impl Into<MaterialProperties>) -> Self {
+        self.material = Some(self.objects.materials_mutable().add(&material.into()));
+        self
+    }
+
+    #[must_use]
+    pub fn reuse_material(mut self, material: MaterialIndex) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    #[must_use]
+    pub fn wall(mut self, origin: Point, local_x: Vector, local_y: Vector) -> Self {
+        let material = self.current_material();
+        self.objects.add_parallelogram(origin, local_x, local_y, material);
+        self
+    }
+
+    // the floor, the ceiling and the walls along the box, open at the max z side
+    #[must_use]
+    pub fn room(self, min: Point, max: Point) -> Self {
+        let size = max - min;
+        let width = Vector::new(size.x, 0.0, 0.0);
+        let height = Vector::new(0.0, size.y, 0.0);
+        let depth = Vector::new(0.0, 0.0, size.z);
+        self.wall(min, width, depth)
+            .wall(min + height, width, depth)
+            .wall(min, width, height)
+            .wall(min, depth, height)
+            .wall(min + width, depth, height)
+    }
+
+    // an emissive panel of its own material: the chosen material stays the same
+    #[must_use]
+    pub fn light(mut self, origin: Point, local_x: Vector, local_y: Vector, r: f32, g: f32, b: f32) -> Self {
+        let material = self.objects.materials_mutable().add(&MaterialBuilder::light(r, g, b).build());
+        self.objects.add_parallelogram(origin, local_x, local_y, material);
+        self
+    }
+
+    #[must_use]
+    pub fn sphere(mut self, center: Point, radius: f64) -> Self {
+        let material = self.current_material();
+        self.objects.add_sphere(center, radius, material);
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> VisualObjects {
+        self.objects
+    }
+
+    #[must_use]
+    fn current_material(&self) -> MaterialIndex {
+        self.material.expect("material is to be chosen before the objects")
+    }
+}
+
+impl Default for SceneBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::visual_objects::DataKind;
+    use crate::utils::object_uid::ObjectUid;
+
+    #[test]
+    fn test_objects_take_the_last_material() {
+        let system_under_test = SceneBuilder::new()
+            .material(MaterialBuilder::matte(1.0, 0.0, 0.0))
+            .wall(Point::new(0.0, 0.0, 0.0), Vector::unit_x(), Vector::unit_y())
+            .material(MaterialBuilder::mirror(1.0, 1.0, 1.0))
+            .sphere(Point::new(0.0, 0.0, 1.0), 0.5);
+
+        let objects = system_under_test.build();
+
+        assert_eq!(objects.count_of_a_kind(DataKind::Parallelogram), 1);
+        assert_eq!(objects.count_of_a_kind(DataKind::Sphere), 1);
+        assert_eq!(objects.material_of(ObjectUid(1)), MaterialIndex(0));
+        assert_eq!(objects.material_of(ObjectUid(2)), MaterialIndex(1));
+    }
+
+    #[test]
+    fn test_room_and_light() {
+        let system_under_test = SceneBuilder::new()
+            .material(MaterialBuilder::matte(0.8, 0.8, 0.8))
+            .room(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+            .light(Point::new(-0.25, 0.99, -0.25), Vector::new(0.5, 0.0, 0.0), Vector::new(0.0, 0.0, 0.5), 2.0, 2.0, 2.0)
+            .wall(Point::new(-1.0, -1.0, 1.0), Vector::new(2.0, 0.0, 0.0), Vector::new(0.0, 2.0, 0.0));
+
+        let objects = system_under_test.build();
+
+        assert_eq!(objects.count_of_a_kind(DataKind::Parallelogram), 7);
+        assert_eq!(objects.materials().count(), 2);
+        assert_eq!(objects.emissive_parallelogram_slots().len(), 1);
+        assert_eq!(objects.material_of(ObjectUid(7)), MaterialIndex(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_object_without_material() {
+        let _ = SceneBuilder::new().sphere(Point::new(0.0, 0.0, 0.0), 1.0);
+    }
+}
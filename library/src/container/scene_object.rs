@@ -1,14 +1,21 @@
+use crate::geometry::alias::Vector;
+use crate::geometry::ray::{Ray, RayIntersection};
 use crate::geometry::transform::Affine;
 use crate::material::material_index::MaterialIndex;
+use crate::objects::parallelogram::Parallelogram;
 use crate::objects::triangle::Triangle;
+use crate::sdf::framework::sdf_parameters::SdfParameterValues;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
 
 pub(super) type SceneEnvironment = Vec<Triangle>;
 
-pub(super) trait SceneObject {
+// sync: the objects of a kind get serialized by the batches in parallel
+pub(super) trait SceneObject: Sync {
     #[must_use]
     fn material(&self) -> MaterialIndex;
     fn set_material(&mut self, new_material: MaterialIndex, environment: &mut SceneEnvironment);
+    // the geometry along with the placement
+    fn translate(&mut self, offset: Vector, environment: &mut SceneEnvironment);
 
     #[must_use]
     fn data_kind_uid(&self) -> usize;
@@ -17,6 +24,22 @@ pub(super) trait SceneObject {
     #[must_use]
     fn transformation(&self) -> &Affine;
     
+    #[must_use]
+    fn intersect(&self, ray: &Ray) -> Option<RayIntersection>;
+
+    #[must_use]
+    fn parallelogram(&self) -> Option<&Parallelogram> {
+        None
+    }
+
+    #[must_use]
+    fn sdf_parameters(&self) -> Option<SdfParameterValues> {
+        None
+    }
+    fn set_sdf_parameters(&mut self, _parameters: SdfParameterValues) {
+        panic!("the object has no sdf parameters");
+    }
+
     #[must_use]
     fn serialized_quartet_count(&self) -> usize;
     fn serialize_into(&self, buffer: &mut GpuReadySerializationBuffer);
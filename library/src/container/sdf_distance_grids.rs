@@ -0,0 +1,116 @@
+use crate::geometry::aabb::Aabb;
+use crate::gpu::frame_buffer_size::FrameBufferSize;
+
+// the values are baked by the gpu row by row, see 'compute_sdf_distance_grids'
+const BAKE_ROW_WIDTH: usize = 256;
+
+const HEADER_FLOATS: usize = 4;
+const SLOT_FLOATS: usize = 8;
+
+// the gpu buffer of the distance grids of the sdf classes, see 'sdf_distance_grid_step' in the tracer:
+// a header quartet (the count of the class slots, the count of the values, the width of the baking rows),
+// two quartets per class slot (the grid corner and the offset of its values; the cell size and the resolution,
+// zero for the classes without a grid), then the distances at the cell centers, x changes first;
+// the integers are stored as bits, the values are zeroes until baked (the tracer does not skip by those)
+pub(crate) struct SdfDistanceGrids {
+    content: Vec<f32>,
+    values_count: usize,
+}
+
+impl SdfDistanceGrids {
+    // indexed by the class index: the box and the resolution of the classes having a grid
+    #[must_use]
+    pub(crate) fn new(class_grids: impl ExactSizeIterator<Item = Option<(Aabb, u32)>>) -> Self {
+        let slots_count = class_grids.len();
+        let mut content = vec![0.0_f32; HEADER_FLOATS + slots_count * SLOT_FLOATS];
+        let mut values_count: usize = 0;
+
+        for (slot, grid) in class_grids.enumerate() {
+            let Some((aabb, resolution)) = grid else {
+                continue;
+            };
+            let aabb = aabb.pad();
+            let cell_size = aabb.extent() / f64::from(resolution);
+            let offset = content.len() + values_count;
+            let start = HEADER_FLOATS + slot * SLOT_FLOATS;
+            content[start..start + SLOT_FLOATS].copy_from_slice(&[
+                aabb.min().x as f32, aabb.min().y as f32, aabb.min().z as f32, f32::from_bits(u32::try_from(offset).expect("distance grids are too big")),
+                cell_size.x as f32, cell_size.y as f32, cell_size.z as f32, f32::from_bits(resolution),
+            ]);
+            values_count += (resolution as usize).pow(3);
+        }
+
+        content[0] = f32::from_bits(slots_count as u32);
+        content[1] = f32::from_bits(u32::try_from(values_count).expect("distance grids are too big"));
+        content[2] = f32::from_bits(BAKE_ROW_WIDTH as u32);
+        content.resize(content.len() + values_count, 0.0);
+
+        Self { content, values_count }
+    }
+
+    #[must_use]
+    pub(crate) fn content(&self) -> &[f32] {
+        &self.content
+    }
+
+    #[must_use]
+    pub(crate) fn values_count(&self) -> usize {
+        self.values_count
+    }
+
+    // the invocations of the baking: a value per invocation
+    #[must_use]
+    pub(crate) fn bake_grid(&self) -> FrameBufferSize {
+        FrameBufferSize::new(BAKE_ROW_WIDTH as u32, self.values_count.div_ceil(BAKE_ROW_WIDTH).max(1) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::alias::Point;
+
+    #[test]
+    fn test_no_grids() {
+        let system_under_test = SdfDistanceGrids::new([None, None].into_iter());
+
+        assert_eq!(system_under_test.values_count(), 0);
+        assert_eq!(system_under_test.content().len(), HEADER_FLOATS + 2 * SLOT_FLOATS);
+        assert_eq!(system_under_test.content()[0].to_bits(), 2);
+        assert_eq!(system_under_test.content()[HEADER_FLOATS + SLOT_FLOATS + 7].to_bits(), 0);
+    }
+
+    #[test]
+    fn test_grids_layout() {
+        let aabb = Aabb::from_points(Point::new(-1.0, -2.0, -4.0), Point::new(1.0, 2.0, 4.0));
+
+        let system_under_test = SdfDistanceGrids::new([Some((aabb, 2)), None, Some((aabb, 4))].into_iter());
+
+        let content = system_under_test.content();
+        let values_start = HEADER_FLOATS + 3 * SLOT_FLOATS;
+        assert_eq!(system_under_test.values_count(), 8 + 64);
+        assert_eq!(content.len(), values_start + 8 + 64);
+        assert_eq!(content[1].to_bits(), 8 + 64);
+        assert_eq!(content[2].to_bits(), BAKE_ROW_WIDTH as u32);
+
+        let first = &content[HEADER_FLOATS..HEADER_FLOATS + SLOT_FLOATS];
+        assert_eq!(&first[0..3], &[-1.0, -2.0, -4.0]);
+        assert_eq!(first[3].to_bits(), values_start as u32);
+        assert_eq!(&first[4..7], &[1.0, 2.0, 4.0]);
+        assert_eq!(first[7].to_bits(), 2);
+
+        let third = &content[HEADER_FLOATS + 2 * SLOT_FLOATS..values_start];
+        assert_eq!(third[3].to_bits(), (values_start + 8) as u32);
+        assert_eq!(&third[4..7], &[0.5, 1.0, 2.0]);
+        assert_eq!(third[7].to_bits(), 4);
+    }
+
+    #[test]
+    fn test_bake_grid() {
+        let aabb = Aabb::from_points(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0));
+
+        let system_under_test = SdfDistanceGrids::new([Some((aabb, 8))].into_iter());
+
+        assert_eq!(system_under_test.bake_grid(), FrameBufferSize::new(BAKE_ROW_WIDTH as u32, 2));
+    }
+}
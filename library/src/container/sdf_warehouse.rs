@@ -1,60 +1,132 @@
+use crate::container::sdf_distance_grids::SdfDistanceGrids;
 use crate::geometry::aabb::Aabb;
 use crate::objects::sdf_class_index::SdfClassIndex;
 use crate::sdf::framework::animation_undo_generator::AnimationUndoGenerator;
 use crate::sdf::framework::sdf_registrator::SdfRegistrator;
-use crate::sdf::framework::named_sdf::UniqueSdfClassName;
+use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+use crate::sdf::framework::sdf_parameters::SdfParameters;
 use crate::sdf::framework::selection_generator::SelectionGenerator;
+use crate::utils::version::Version;
 use std::collections::HashMap;
 use crate::sdf::framework::sdf_code_generator::SdfCodeGenerator;
 
 pub(crate) struct SdfWarehouse {
     properties_from_name: HashMap<UniqueSdfClassName, SdfClassIndex>,
-    bounding_boxes: Vec<Aabb>,
+    // indexed by the class index; scene objects keep the index, so removed classes leave a vacancy
+    classes: Vec<Option<SdfClass>>,
     sdf_classes_code: String,
+    version: Version,
+}
+
+struct SdfClass {
+    named: NamedSdf,
+    aabb: Aabb,
+    ray_marching_step_scale: f64,
+}
+
+impl SdfClass {
+    #[must_use]
+    fn new(named: NamedSdf) -> Self {
+        let aabb = named.sdf().aabb();
+        let ray_marching_step_scale = 1.0 / named.sdf().lipschitz_bound();
+        Self { named, aabb, ray_marching_step_scale }
+    }
 }
 
 impl SdfWarehouse {
     #[must_use]
     pub(crate) fn new(sdf_classes: SdfRegistrator) -> Self {
-        let mut properties_from_name: HashMap<UniqueSdfClassName, SdfClassIndex> = HashMap::new();
-        let mut bounding_boxes: Vec<Aabb> = Vec::new();
-        let mut overall_accumulated_code = String::new();
-        let mut sdf_selection_uber_function = SelectionGenerator::new();
-        let mut sdf_animation_undo_uber_function = AnimationUndoGenerator::new();
-        
         let code_generator = SdfCodeGenerator::new(sdf_classes);
-        
+
         let registrations = code_generator.registrations();
         let names_ordered = {
             let mut names: Vec<_> = registrations.keys().collect();
             names.sort();
             names
         };
-        
+
+        let mut properties_from_name: HashMap<UniqueSdfClassName, SdfClassIndex> = HashMap::new();
+        let mut classes: Vec<Option<SdfClass>> = Vec::with_capacity(names_ordered.len());
         for (name_index, name) in names_ordered.iter().enumerate() {
-            let index = SdfClassIndex(name_index);
-            let sdf_class = registrations.get(name).unwrap();
-            
-            properties_from_name.insert((*name).clone(), index);
-            bounding_boxes.push(sdf_class.sdf().aabb());
-            
-            let function_to_call = code_generator.generate_unique_code_for(sdf_class, &mut overall_accumulated_code);
-            sdf_selection_uber_function.add_selection(&function_to_call, index);
-            sdf_animation_undo_uber_function.add_handler(sdf_class.sdf(), index);
+            properties_from_name.insert((*name).clone(), SdfClassIndex(name_index));
+            classes.push(Some(SdfClass::new(registrations.get(name).unwrap().clone())));
+        }
+
+        let sdf_classes_code = Self::generate_code(code_generator, &classes);
+
+        Self { properties_from_name, classes, sdf_classes_code, version: Version(0) }
+    }
+
+    // the shader has to be recomposed after any of the class mutations
+    pub(crate) fn add_class(&mut self, target: &NamedSdf) -> SdfClassIndex {
+        assert!(false == self.properties_from_name.contains_key(target.name()), "name {} of given sdf is not unique", target.name());
+
+        let index = SdfClassIndex(self.classes.len());
+        self.properties_from_name.insert(target.name().clone(), index);
+        self.classes.push(Some(SdfClass::new(target.clone())));
+        self.regenerate_code();
+
+        index
+    }
+
+    pub(crate) fn replace_class(&mut self, target: &NamedSdf) -> SdfClassIndex {
+        let index = *self.properties_for_name(target.name()).unwrap_or_else(|| panic!("registration for the '{}' sdf has not been found", target.name()));
+
+        self.classes[index.0] = Some(SdfClass::new(target.clone()));
+        self.regenerate_code();
+
+        index
+    }
+
+    pub(crate) fn remove_class(&mut self, name: &UniqueSdfClassName) -> SdfClassIndex {
+        let index = self.properties_from_name.remove(name).unwrap_or_else(|| panic!("registration for the '{name}' sdf has not been found"));
+
+        self.classes[index.0] = None;
+        self.regenerate_code();
+
+        index
+    }
+
+    fn regenerate_code(&mut self) {
+        let mut registrator = SdfRegistrator::new();
+        for class in self.classes.iter().flatten() {
+            registrator.add(&class.named);
+        }
+        self.sdf_classes_code = Self::generate_code(SdfCodeGenerator::new(registrator), &self.classes);
+        self.version += 1;
+    }
+
+    #[must_use]
+    fn generate_code(code_generator: SdfCodeGenerator, classes: &[Option<SdfClass>]) -> String {
+        let mut overall_accumulated_code = String::new();
+        let mut sdf_selection_uber_function = SelectionGenerator::new();
+        let mut sdf_gradient_selection_uber_function = SelectionGenerator::new_for_gradients();
+        let mut sdf_animation_undo_uber_function = AnimationUndoGenerator::new();
+
+        for (class_index, class) in classes.iter().enumerate() {
+            if let Some(class) = class {
+                let index = SdfClassIndex(class_index);
+                let function_to_call = code_generator.generate_unique_code_for(&class.named, &mut overall_accumulated_code);
+                sdf_selection_uber_function.add_selection(&function_to_call, index);
+                if let Some(gradient_to_call) = code_generator.generate_gradient_code_for(&class.named, &mut overall_accumulated_code) {
+                    sdf_gradient_selection_uber_function.add_selection(&gradient_to_call, index);
+                }
+                sdf_animation_undo_uber_function.add_handler(class.named.sdf(), index);
+            }
         }
         code_generator.generate_shared_code(&mut overall_accumulated_code);
-        
+
         overall_accumulated_code.push_str(sdf_selection_uber_function.make().as_str());
+        overall_accumulated_code.push_str(sdf_gradient_selection_uber_function.make().as_str());
         overall_accumulated_code.push_str(sdf_animation_undo_uber_function.make().as_str());
-
-        Self { properties_from_name, bounding_boxes, sdf_classes_code: overall_accumulated_code }
+        overall_accumulated_code
     }
 
     #[must_use]
     pub(crate) fn properties_for_name(&self, name: &UniqueSdfClassName) -> Option<&SdfClassIndex> {
         self.properties_from_name.get(name)
     }
-    
+
     #[must_use]
     pub(crate) fn name_from_index(&self, needle: SdfClassIndex) -> Option<&UniqueSdfClassName> {
         for (name, index) in self.properties_from_name.iter() {
@@ -67,12 +139,128 @@ impl SdfWarehouse {
 
     #[must_use]
     pub(crate) fn aabb_from_index(&self, index: SdfClassIndex) -> &Aabb {
-        assert!(index.0 < self.bounding_boxes.len());
-        &self.bounding_boxes[index.0]
+        assert!(index.0 < self.classes.len());
+        &self.classes[index.0].as_ref().unwrap_or_else(|| panic!("sdf class {index} has been removed")).aabb
+    }
+
+    #[must_use]
+    pub(crate) fn parameters_from_index(&self, index: SdfClassIndex) -> &SdfParameters {
+        assert!(index.0 < self.classes.len());
+        self.classes[index.0].as_ref().unwrap_or_else(|| panic!("sdf class {index} has been removed")).named.parameters()
+    }
+
+    // the conservative one: the distance field of the class may change faster than the distance itself
+    #[must_use]
+    pub(crate) fn ray_marching_step_scale_from_index(&self, index: SdfClassIndex) -> f64 {
+        assert!(index.0 < self.classes.len());
+        self.classes[index.0].as_ref().unwrap_or_else(|| panic!("sdf class {index} has been removed")).ray_marching_step_scale
+    }
+
+    // the slots follow the class indices, the removed classes included
+    #[must_use]
+    pub(crate) fn distance_grids(&self) -> SdfDistanceGrids {
+        SdfDistanceGrids::new(self.classes.iter().map(|class| class.as_ref()
+            .and_then(|class| class.named.distance_grid_resolution().map(|resolution| (class.aabb, resolution)))))
     }
 
     #[must_use]
     pub(crate) fn sdf_classes_code(&self) -> &str {
         &self.sdf_classes_code
     }
-}
\ No newline at end of file
+
+    #[must_use]
+    pub(crate) fn version(&self) -> Version {
+        self.version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdf::morphing::sdf_displacement::{DisplacementFunction, SdfDisplacement};
+    use crate::sdf::object::sdf_box::SdfBox;
+    use crate::sdf::object::sdf_sphere::SdfSphere;
+    use crate::geometry::alias::Vector;
+    use crate::shader::code::{FunctionBody, ShaderCode};
+
+    #[must_use]
+    fn make_named_sphere(name: &str, radius: f64) -> NamedSdf {
+        NamedSdf::new(SdfSphere::new(radius), UniqueSdfClassName::new(name.to_string()))
+    }
+
+    #[must_use]
+    fn make_system_under_test() -> SdfWarehouse {
+        let mut registrator = SdfRegistrator::new();
+        registrator.add(&make_named_sphere("b_sphere", 1.0));
+        registrator.add(&make_named_sphere("c_sphere", 2.0));
+        SdfWarehouse::new(registrator)
+    }
+
+    #[test]
+    fn test_added_class_keeps_existing_indices() {
+        let mut system_under_test = make_system_under_test();
+        let initial_version = system_under_test.version();
+
+        let added = system_under_test.add_class(&make_named_sphere("a_sphere", 3.0));
+
+        assert_eq!(added, SdfClassIndex(2));
+        assert_eq!(system_under_test.properties_for_name(&UniqueSdfClassName::new("b_sphere".to_string())), Some(&SdfClassIndex(0)));
+        assert!(system_under_test.sdf_classes_code().contains("a_sphere"));
+        assert!(system_under_test.version() > initial_version);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_duplicate_class() {
+        let mut system_under_test = make_system_under_test();
+        let _ = system_under_test.add_class(&make_named_sphere("b_sphere", 3.0));
+    }
+
+    #[test]
+    fn test_replace_class() {
+        let mut system_under_test = make_system_under_test();
+        let replacement = NamedSdf::new(SdfBox::new(Vector::new(1.0, 2.0, 3.0)), UniqueSdfClassName::new("c_sphere".to_string()));
+
+        let replaced = system_under_test.replace_class(&replacement);
+
+        assert_eq!(replaced, SdfClassIndex(1));
+        assert_eq!(system_under_test.aabb_from_index(replaced), &replacement.sdf().aabb());
+    }
+
+    #[test]
+    fn test_remove_class() {
+        let mut system_under_test = make_system_under_test();
+        let name = UniqueSdfClassName::new("b_sphere".to_string());
+
+        let removed = system_under_test.remove_class(&name);
+
+        assert_eq!(removed, SdfClassIndex(0));
+        assert_eq!(system_under_test.properties_for_name(&name), None);
+        assert!(false == system_under_test.sdf_classes_code().contains("b_sphere"));
+        assert_eq!(system_under_test.aabb_from_index(SdfClassIndex(1)), &make_named_sphere("c_sphere", 2.0).sdf().aabb());
+    }
+
+    #[test]
+    fn test_ray_marching_step_scale() {
+        let mut system_under_test = make_system_under_test();
+        let wave = DisplacementFunction::new(ShaderCode::<FunctionBody>::new("return sin(point.x);".to_string()), 1.0, 1.0);
+        let displaced = NamedSdf::new(SdfDisplacement::new(SdfSphere::new(1.0), wave, 0.5, 2.0), UniqueSdfClassName::new("rough_sphere".to_string()));
+
+        let added = system_under_test.add_class(&displaced);
+
+        assert_eq!(system_under_test.ray_marching_step_scale_from_index(SdfClassIndex(0)), 1.0);
+        assert_eq!(system_under_test.ray_marching_step_scale_from_index(added), 0.5);
+    }
+
+    #[test]
+    fn test_distance_grids() {
+        let mut system_under_test = make_system_under_test();
+        let _ = system_under_test.add_class(&make_named_sphere("a_sphere", 3.0).with_distance_grid(4));
+        let _ = system_under_test.remove_class(&UniqueSdfClassName::new("b_sphere".to_string()));
+
+        let grids = system_under_test.distance_grids();
+
+        assert_eq!(grids.values_count(), 4 * 4 * 4);
+        assert_eq!(grids.content()[0].to_bits(), 3);
+    }
+}
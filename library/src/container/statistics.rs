@@ -3,23 +3,65 @@ use crate::utils::version::Version;
 #[derive(Default, Copy, Clone)]
 pub(super) struct Statistics {
     data_version: Version,
+    // bumped when the objects are added or removed, but not when they are mutated
+    topology_version: Version,
     object_count: usize,
+
+    // while deferred, the changes are only noted: each version is bumped once on 'resume_version_bumps'
+    deferred: bool,
+    data_changed: bool,
+    topology_changed: bool,
 }
 
 impl Statistics {
     pub(super) fn register_new_object(&mut self) {
         self.object_count += 1;
-        self.data_version += 1;
+        self.bump(true);
     }
 
     pub(super) fn delete_object(&mut self) {
         assert!(self.object_count > 0);
         self.object_count -= 1;
-        self.data_version += 1;
+        self.bump(true);
     }
 
     pub(super) fn register_object_mutation(&mut self) {
+        self.bump(false);
+    }
+
+    // the object is kept, but its primitives are replaced (a reloaded mesh): the bvh has to be rebuilt
+    pub(super) fn register_object_reshape(&mut self) {
+        self.bump(true);
+    }
+
+    pub(super) fn defer_version_bumps(&mut self) {
+        assert!(false == self.deferred, "version bumps are already deferred");
+        self.deferred = true;
+    }
+
+    pub(super) fn resume_version_bumps(&mut self) {
+        assert!(self.deferred, "version bumps are not deferred");
+        self.deferred = false;
+        if self.data_changed {
+            self.data_version += 1;
+        }
+        if self.topology_changed {
+            self.topology_version += 1;
+        }
+        self.data_changed = false;
+        self.topology_changed = false;
+    }
+
+    fn bump(&mut self, topology_changed: bool) {
+        if self.deferred {
+            self.data_changed = true;
+            self.topology_changed |= topology_changed;
+            return;
+        }
         self.data_version += 1;
+        if topology_changed {
+            self.topology_version += 1;
+        }
     }
 
     #[must_use]
@@ -27,6 +69,17 @@ impl Statistics {
         self.data_version
     }
 
+    // the version the pending change gets published under: right away or on 'resume_version_bumps'
+    #[must_use]
+    pub(super) fn upcoming_data_version(&self) -> Version {
+        self.data_version + 1
+    }
+
+    #[must_use]
+    pub(super) fn topology_version(&self) -> Version {
+        self.topology_version
+    }
+
     #[must_use]
     pub(super) fn object_count(&self) -> usize {
         self.object_count
@@ -34,7 +87,7 @@ impl Statistics {
 
     pub(super) fn clear_objects(&mut self) {
         self.object_count = 0;
-        self.data_version += 1;
+        self.bump(true);
     }
 }
 
@@ -68,8 +121,10 @@ mod tests {
     fn test_delete_object(fixture: &mut Context) {
         fixture.system_under_test.register_new_object();
         let version_before_deletion = fixture.system_under_test.data_version();
+        let topology_version_before_deletion = fixture.system_under_test.topology_version();
         fixture.system_under_test.delete_object();
         assert_eq!(fixture.system_under_test.object_count(), 0);
+        assert_ne!(fixture.system_under_test.topology_version(), topology_version_before_deletion);
         assert_ne!(fixture.system_under_test.data_version(), version_before_deletion);
     }
 
@@ -92,9 +147,11 @@ mod tests {
         assert_eq!(fixture.system_under_test.object_count(), 1);
         assert_eq!(fixture.system_under_test.data_version(), Version(1));
 
+        let topology_version_before_mutation = fixture.system_under_test.topology_version();
         fixture.system_under_test.register_object_mutation();
         assert_eq!(fixture.system_under_test.object_count(), 1);
         assert_eq!(fixture.system_under_test.data_version(), Version(2));
+        assert_eq!(fixture.system_under_test.topology_version(), topology_version_before_mutation);
 
         fixture.system_under_test.register_new_object();
         assert_eq!(fixture.system_under_test.object_count(), 2);
@@ -113,4 +170,31 @@ mod tests {
         assert_eq!(fixture.system_under_test.object_count(), 1);
         assert_eq!(fixture.system_under_test.data_version(), Version(3));
     }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_deferred_version_bumps(fixture: &mut Context) {
+        fixture.system_under_test.defer_version_bumps();
+        fixture.system_under_test.register_new_object();
+        fixture.system_under_test.register_new_object();
+        fixture.system_under_test.register_object_mutation();
+        assert_eq!(fixture.system_under_test.upcoming_data_version(), Version(1));
+        assert_eq!(fixture.system_under_test.object_count(), 2);
+        assert_eq!(fixture.system_under_test.data_version(), Version(0));
+        assert_eq!(fixture.system_under_test.topology_version(), Version(0));
+
+        fixture.system_under_test.resume_version_bumps();
+        assert_eq!(fixture.system_under_test.data_version(), Version(1));
+        assert_eq!(fixture.system_under_test.topology_version(), Version(1));
+
+        fixture.system_under_test.defer_version_bumps();
+        fixture.system_under_test.register_object_mutation();
+        fixture.system_under_test.resume_version_bumps();
+        assert_eq!(fixture.system_under_test.data_version(), Version(2));
+        assert_eq!(fixture.system_under_test.topology_version(), Version(1));
+
+        fixture.system_under_test.defer_version_bumps();
+        fixture.system_under_test.resume_version_bumps();
+        assert_eq!(fixture.system_under_test.data_version(), Version(2));
+    }
 }
@@ -3,11 +3,11 @@ use crate::material::material_properties::MaterialProperties;
 use crate::material::texture_atlas_regions_warehouse::TextureAtlasRegionsWarehouse;
 use crate::material::texture_reference::TextureReference;
 use crate::material::texture_region::TextureRegion;
-use crate::utils::bitmap_utils::{save_bitmap_to_png, set_texel, write_sub_bitmap, write_sub_bitmap_column, write_sub_bitmap_row, BitmapSize, ImmutableBitmapReference, MutableBitmapReference};
+use crate::utils::bitmap_utils::{save_bitmap_to_png, set_texel, write_sub_bitmap, write_sub_bitmap_column, write_sub_bitmap_row, write_wrapped_sub_bitmap, BitmapSize, ImmutableBitmapReference, MutableBitmapReference};
 use crate::utils::version::Version;
 use cast::i32;
 use cgmath::Vector2;
-use etagere::{AllocId, AtlasAllocator, Size};
+use etagere::{size2, AllocId, AllocatorOptions, AtlasAllocator, Size};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
@@ -16,10 +16,15 @@ use crate::utils::file_system::ensure_folders_exist;
 
 pub type AtlasRegionUid = AllocId;
 
+struct Allocation {
+    region: TextureRegion,
+    bitmap_size: BitmapSize,
+}
+
 pub struct TextureAtlasPageComposer {
     atlas_page_buffer: Vec<u8>,
     allocator: AtlasAllocator,
-    allocations: HashMap<AllocId, TextureRegion>,
+    allocations: HashMap<AllocId, Allocation>,
     warehouse: Rc<RefCell<TextureAtlasRegionsWarehouse>>,
     page_size: BitmapSize,
     atlas_page_data_version: Version,
@@ -27,12 +32,17 @@ pub struct TextureAtlasPageComposer {
 
 impl TextureAtlasPageComposer {
     const DEFENSIVE_BORDER_SIZE: usize = 1;
+    // the regions are aligned to the texels of the coarsest mip, so the 2x2 downsampling never mixes two regions
+    pub(crate) const MIP_LEVELS: u32 = 5;
+    const REGION_ALIGNMENT: i32 = 1 << (Self::MIP_LEVELS - 1);
 
     #[must_use]
     pub(crate) fn new(page_size: BitmapSize, warehouse: Rc<RefCell<TextureAtlasRegionsWarehouse>>) -> Self {
         Self {
             atlas_page_buffer: vec![0; page_size.bytes_in_bitmap()],
-            allocator: AtlasAllocator::new(Size::new(page_size.width() as i32, page_size.height() as i32)),
+            allocator: AtlasAllocator::with_options(
+                Size::new(page_size.width() as i32, page_size.height() as i32),
+                &AllocatorOptions { alignment: Self::region_alignment(page_size), ..AllocatorOptions::default() }),
             allocations: HashMap::new(),
             warehouse,
             page_size,
@@ -40,6 +50,13 @@ impl TextureAtlasPageComposer {
         }
     }
 
+    // tiny pages can't hold the aligned shelves; they have a short mip chain anyway
+    #[must_use]
+    fn region_alignment(page_size: BitmapSize) -> Size {
+        let alignment = |extent: usize| Self::REGION_ALIGNMENT.min(1 << extent.ilog2());
+        size2(alignment(page_size.width()), alignment(page_size.height()))
+    }
+
     #[must_use]
     pub fn allocate(&mut self, bitmap: ImmutableBitmapReference) -> Option<AtlasRegionUid> {
         const BORDER: usize = TextureAtlasPageComposer::DEFENSIVE_BORDER_SIZE;
@@ -57,7 +74,30 @@ impl TextureAtlasPageComposer {
         let width = bitmap.size().width() as f32 / page_width;
         let height = bitmap.size().height() as f32 / page_height;
         let region = TextureRegion::new(Vector2::new(u, v), Vector2::new(width, height));
-        self.allocations.insert(allocation.id, region);
+        self.allocations.insert(allocation.id, Allocation { region, bitmap_size: bitmap.size() });
+
+        self.write_region(allocation.id, bitmap);
+
+        Some(allocation.id)
+    }
+
+    // replaces the texels of the region (a reloaded bitmap); the region can't be resized in place
+    pub fn rewrite(&mut self, region: AtlasRegionUid, bitmap: ImmutableBitmapReference) -> anyhow::Result<()> {
+        let allocation = self.allocations.get(&region)
+            .ok_or_else(|| anyhow::anyhow!(format!("atlas region allocation not found for uid: {:?}", region)))?;
+        let size = bitmap.size();
+        if allocation.bitmap_size.width() != size.width() || allocation.bitmap_size.height() != size.height() {
+            return Err(anyhow::anyhow!(format!("atlas region {:?} holds {} bitmap, can't put {} one in place", region, allocation.bitmap_size, size)));
+        }
+        self.write_region(region, bitmap);
+        Ok(())
+    }
+
+    fn write_region(&mut self, region: AtlasRegionUid, bitmap: ImmutableBitmapReference) {
+        const BORDER: usize = TextureAtlasPageComposer::DEFENSIVE_BORDER_SIZE;
+        let allocated_rectangle = self.allocator.get(region);
+        let pixel_x = allocated_rectangle.min.x as usize + BORDER;
+        let pixel_y = allocated_rectangle.min.y as usize + BORDER;
 
         let mut atlas = MutableBitmapReference::new(&mut self.atlas_page_buffer, self.page_size);
         write_sub_bitmap(&mut atlas, &bitmap, pixel_x, pixel_y);
@@ -75,23 +115,37 @@ impl TextureAtlasPageComposer {
         set_texel(&mut atlas, pixel_x-1, pixel_y+bitmap.size().height(), &bitmap, bitmap.size().width()-1, 0);
         set_texel(&mut atlas, pixel_x+bitmap.size().width(), pixel_y+bitmap.size().height(), &bitmap, 0, 0);
 
-        self.atlas_page_data_version += 1;
+        // the rest of the aligned allocation continues the tiling: the coarser mips of the region blend its own texels only
+        let allocated_size = BitmapSize::new(allocated_rectangle.width() as usize, allocated_rectangle.height() as usize);
+        write_wrapped_sub_bitmap(&mut atlas, allocated_rectangle.min.x as usize, allocated_rectangle.min.y as usize, allocated_size, &bitmap, pixel_x, pixel_y);
 
-        Some(allocation.id)
+        self.atlas_page_data_version += 1;
     }
 
     pub fn map_into(&mut self, region: AtlasRegionUid, mapping: AtlasRegionMappingBuilder, target: &mut MaterialProperties) -> anyhow::Result<()> {
+        let reference = self.map_region(region, mapping)?;
+        target.set_albedo_texture(reference);
+        Ok(())
+    }
+
+    // the region bitmap holds tangent space normals; the mapping also defines the tangent frame
+    pub fn map_normals_into(&mut self, region: AtlasRegionUid, mapping: AtlasRegionMappingBuilder, target: &mut MaterialProperties) -> anyhow::Result<()> {
+        let reference = self.map_region(region, mapping)?;
+        target.set_normal_texture(reference);
+        Ok(())
+    }
+
+    pub(crate) fn map_region(&mut self, region: AtlasRegionUid, mapping: AtlasRegionMappingBuilder) -> anyhow::Result<TextureReference> {
         let allocation = self.allocations.get(&region)
             .ok_or_else(|| anyhow::anyhow!(format!("atlas region allocation not found for uid: {:?}", region)))?;
 
-        let atlas_region_mapping = mapping.build(allocation.clone());
+        let atlas_region_mapping = mapping.build(allocation.region.clone());
         let mapped_region_uid = self.warehouse.borrow_mut().add_region(atlas_region_mapping);
 
         let bitmap_index = self.warehouse.borrow_mut().get_region_index(mapped_region_uid)
             .ok_or_else(|| anyhow::anyhow!(format!("region index not found for uid {:?}", mapped_region_uid)))?;
 
-        target.set_albedo_texture(TextureReference::Bitmap(bitmap_index));
-        Ok(())
+        Ok(TextureReference::Bitmap(bitmap_index))
     }
     
     pub(crate) fn try_commit<ConsumerDelegate: FnOnce(Version, &[u8])>(&self, consumer_data_version_or_none: Option<Version>, consume: ConsumerDelegate) {
@@ -109,4 +163,48 @@ impl TextureAtlasPageComposer {
         ensure_folders_exist(&file_path)?;
         save_bitmap_to_png(&self.atlas_page_buffer, self.page_size, file_path.as_ref())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocations_are_padded_by_wrapping() {
+        let page_size = BitmapSize::new(64, 64);
+        let mut system_under_test = TextureAtlasPageComposer::new(page_size, Rc::new(RefCell::new(TextureAtlasRegionsWarehouse::new())));
+        let first = [255, 0, 0, 255, 0, 255, 0, 255];
+        let second = [0, 0, 255, 255];
+
+        let first_uid = system_under_test.allocate(ImmutableBitmapReference::new(&first, BitmapSize::new(2, 1))).unwrap();
+        let second_uid = system_under_test.allocate(ImmutableBitmapReference::new(&second, BitmapSize::new(1, 1))).unwrap();
+
+        for uid in [first_uid, second_uid] {
+            let corner = system_under_test.allocator.get(uid).min;
+            assert_eq!(corner.x % TextureAtlasPageComposer::REGION_ALIGNMENT, 0);
+            assert_eq!(corner.y % TextureAtlasPageComposer::REGION_ALIGNMENT, 0);
+        }
+        let page = &system_under_test.atlas_page_buffer;
+        let texel = |u: usize, v: usize| &page[(v * page_size.width() + u) * 4..][..4];
+        assert_eq!(texel(0, 0), [0, 255, 0, 255]);
+        assert_eq!(texel(15, 15), [255, 0, 0, 255]);
+        assert_eq!(texel(16, 0), [0, 0, 255, 255]);
+        assert_eq!(texel(31, 15), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_rewrite_region() {
+        let page_size = BitmapSize::new(32, 32);
+        let mut system_under_test = TextureAtlasPageComposer::new(page_size, Rc::new(RefCell::new(TextureAtlasRegionsWarehouse::new())));
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let uid = system_under_test.allocate(ImmutableBitmapReference::new(&red, BitmapSize::new(1, 1))).unwrap();
+        let version_before = system_under_test.atlas_page_data_version;
+
+        system_under_test.rewrite(uid, ImmutableBitmapReference::new(&blue, BitmapSize::new(1, 1))).unwrap();
+
+        assert_ne!(system_under_test.atlas_page_data_version, version_before);
+        assert!(system_under_test.atlas_page_buffer[..16 * 4].chunks(4).all(|texel| texel == blue));
+        let resized = system_under_test.rewrite(uid, ImmutableBitmapReference::new(&[blue, blue].concat(), BitmapSize::new(2, 1)));
+        assert!(resized.is_err());
+    }
+}
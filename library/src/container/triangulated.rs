@@ -1,4 +1,6 @@
 use crate::container::scene_object::{SceneEnvironment, SceneObject};
+use crate::geometry::alias::Vector;
+use crate::geometry::ray::{Ray, RayIntersection};
 use crate::geometry::transform::Affine;
 use crate::material::material_index::MaterialIndex;
 use crate::objects::common_properties::Linkage;
@@ -37,6 +39,15 @@ impl SceneObject for Triangulated {
         self.links.set_material_index(new_material);
     }
 
+    fn translate(&mut self, offset: Vector, environment: &mut SceneEnvironment) {
+        for triangle in environment {
+            if triangle.host() == self.links.uid() {
+                triangle.translate(offset)
+            }
+        }
+        self.transformation = Affine::from_translation(offset) * self.transformation;
+    }
+
     fn data_kind_uid(&self) -> usize {
         self.geometry_kind
     }
@@ -47,6 +58,11 @@ impl SceneObject for Triangulated {
         &self.transformation
     }
 
+    // the triangles are in the bvh on their own, the container intersects them one by one
+    fn intersect(&self, _ray: &Ray) -> Option<RayIntersection> {
+        None
+    }
+
     fn serialized_quartet_count(&self) -> usize {
         0
     }
@@ -1,53 +1,97 @@
-use crate::bvh::builder::{build_bvh, build_serialized_bvh, };
+use crate::background::background_registrator::BackgroundRegistrator;
+use crate::background::background_uid::BackgroundUid;
+use crate::bvh::builder::{build_bvh, Bvh};
 use crate::bvh::bvh_to_dot::save_bvh_as_dot_detailed;
+use crate::bvh::dump::support_hash;
+use crate::bvh::light_tree::LightSource;
 use crate::bvh::proxy::{PrimitiveType, SceneObjectProxy};
-use crate::container::bvh_proxies::{proxy_of_sdf, SceneObjects};
-use crate::container::mesh_warehouse::{MeshWarehouse, WarehouseSlot};
+use crate::container::bvh_proxies::{proxy_of_sdf, proxy_of_sphere, SceneObjects};
+use crate::container::dirty_slots::DirtySlots;
+use crate::container::indexed_triangles::{serialize_indexed_triangles, serialize_quantized_indexed_triangles, MeshVertices};
+use crate::container::mesh_warehouse::{MeshShading, MeshWarehouse, WarehouseSlot};
 use crate::container::monolithic::Monolithic;
+use crate::container::object_layers::ObjectLayers;
+use crate::container::object_uv_transforms::ObjectUvTransforms;
+use crate::container::object_opacities::ObjectOpacities;
+use crate::container::object_slots::ObjectSlots;
 use crate::container::scene_object::SceneObject;
+use crate::container::sdf_distance_grids::SdfDistanceGrids;
 use crate::container::sdf_warehouse::SdfWarehouse;
 use crate::container::statistics::Statistics;
 use crate::container::texture_atlas_page_composer::TextureAtlasPageComposer;
 use crate::container::triangulated::Triangulated;
+use crate::geometry::aabb::Aabb;
 use crate::geometry::alias::{Point, Vector};
+use crate::geometry::ray::{intersect_aabb, ray_meets_aabb, Ray, RayIntersection};
 use crate::geometry::transform::{Affine, Transformation};
 use crate::geometry::utils::is_affine;
+use crate::geometry::vertex::Vertex;
+use crate::material::atlas_region_mapping::AtlasRegionMappingBuilder;
 use crate::material::material_index::MaterialIndex;
 use crate::material::materials_warehouse::MaterialsWarehouse;
 use crate::material::procedural_textures::ProceduralTextures;
+use crate::material::surface_shaders::SurfaceShaders;
+use crate::material::texture_reference::TextureReference;
 use crate::objects::common_properties::Linkage;
 use crate::objects::parallelogram::Parallelogram;
 use crate::objects::sdf_class_index::SdfClassIndex;
 use crate::objects::sdf_instance::SdfInstance;
+use crate::objects::sphere::Sphere;
 use crate::objects::triangle::Triangle;
-use crate::sdf::framework::named_sdf::UniqueSdfClassName;
+use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
 use crate::sdf::framework::sdf_registrator::SdfRegistrator;
+use crate::scene::layer_mask::LayerMask;
+use crate::scene::ray_hit::RayHit;
+use crate::scene::uv_transform::UvTransform;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
-use crate::serialization::serializable_for_gpu::serialize_batch;
-use crate::utils::bitmap_utils::BitmapSize;
+use crate::utils::bitmap_utils::{BitmapSize, ImmutableBitmapReference};
 use crate::utils::object_uid::ObjectUid;
 use crate::utils::remove_with_reorder::remove_with_reorder;
 use crate::utils::uid_generator::UidGenerator;
 use crate::utils::version::Version;
-use cgmath::SquareMatrix;
+use anyhow::anyhow;
+use cgmath::{EuclideanSpace, SquareMatrix};
 use more_asserts::assert_gt;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::io::Error;
+use std::ops::Range;
 use std::path::Path;
 use strum::EnumCount;
 use strum_macros::{AsRefStr, Display, EnumCount, EnumIter};
 
+// objects per parallel serialization task: small batches do not pay off the scheduling
+const SERIALIZATION_BATCH_SIZE: usize = 256;
+
 pub struct VisualObjects {
     per_object_kind_statistics: Vec<Statistics>,
     objects: HashMap<ObjectUid, Box<dyn SceneObject>>,
+    per_object_kind_slots: Vec<ObjectSlots>,
+    per_object_kind_dirty_slots: Vec<DirtySlots>,
     triangles: Vec<Triangle>,
+    mesh_vertices: HashMap<ObjectUid, (MeshShading, Vec<Vertex>)>,
     
     materials: MaterialsWarehouse,
     texture_atlas_page_composer: TextureAtlasPageComposer,
 
     sdf_prototypes: SdfWarehouse,
+
+    backgrounds: BackgroundRegistrator,
     
     uid_generator: UidGenerator<ObjectUid>,
+    object_layers: ObjectLayers,
+    object_uv_transforms: ObjectUvTransforms,
+    object_opacities: ObjectOpacities,
+
+    ray_query_bvh: RefCell<Option<RayQueryBvh>>,
+}
+
+// the tree for the ray casts on the cpu, built at the first cast: like the gpu one, it is refitted
+// while the objects are only mutated and rebuilt once they are added or removed
+struct RayQueryBvh {
+    tree: Bvh,
+    topology_version: (Version, Version, Version),
+    data_version: (Version, Version, Version),
 }
 
 #[derive(EnumIter, EnumCount, Display, AsRefStr, Copy, Clone, PartialEq, Debug)]
@@ -55,22 +99,31 @@ pub(crate) enum DataKind {
     Parallelogram,
     Sdf,
     TriangleMesh,
+    Sphere,
 }
 
 impl VisualObjects {
     #[must_use]
-    pub fn new(texture_atlas_page_size: Option<BitmapSize>, sdf_classes: Option<SdfRegistrator>, procedural_textures: Option<ProceduralTextures>) -> Self {
-        let materials = MaterialsWarehouse::new(procedural_textures);
+    pub fn new(texture_atlas_page_size: Option<BitmapSize>, sdf_classes: Option<SdfRegistrator>, procedural_textures: Option<ProceduralTextures>, surface_shaders: Option<SurfaceShaders>, backgrounds: Option<BackgroundRegistrator>) -> Self {
+        let materials = MaterialsWarehouse::new(procedural_textures, surface_shaders);
         let texture_atlas_regions = materials.texture_atlas_regions();
         let atlas_page_composer = TextureAtlasPageComposer::new(texture_atlas_page_size.unwrap_or(BitmapSize::new(1, 1)), texture_atlas_regions);
         Self {
             per_object_kind_statistics: vec![Statistics::default(); DataKind::COUNT],
             objects: HashMap::new(),
+            per_object_kind_slots: (0..DataKind::COUNT).map(|_| ObjectSlots::default()).collect(),
+            per_object_kind_dirty_slots: (0..DataKind::COUNT).map(|_| DirtySlots::default()).collect(),
             triangles: Vec::new(),
+            mesh_vertices: HashMap::new(),
             materials,
             texture_atlas_page_composer: atlas_page_composer,
             sdf_prototypes: SdfWarehouse::new(sdf_classes.unwrap_or_default()),
+            backgrounds: backgrounds.unwrap_or_default(),
             uid_generator: UidGenerator::new(),
+            object_layers: ObjectLayers::new(),
+            object_uv_transforms: ObjectUvTransforms::new(),
+            object_opacities: ObjectOpacities::new(),
+            ray_query_bvh: RefCell::new(None),
         }
     }
 
@@ -81,7 +134,7 @@ impl VisualObjects {
 
     pub(crate) fn dump_scene_bvh(&self, destination: impl AsRef<Path>) -> Result<(), Error> {
         let mut objects_to_tree = self.make_bvh_support(0.0);
-        let sdf_list = self.sorted_of_a_kind(DataKind::Sdf as usize, self.count_of_a_kind(DataKind::Sdf));
+        let sdf_list = self.slotted_of_a_kind(DataKind::Sdf);
         
         let bvh = build_bvh(&mut objects_to_tree);
         save_bvh_as_dot_detailed(bvh.root(), |index| {
@@ -89,7 +142,8 @@ impl VisualObjects {
                 let proxy = objects_to_tree[index];
                 match proxy.primitive_type() {
                     PrimitiveType::Sdf => {
-                        let class_index = SdfClassIndex(sdf_list[proxy.host_container_index()].entity.payload());
+                        let sdf = sdf_list[proxy.host_container_index()].as_ref().expect("bvh refers to an empty slot");
+                        let class_index = SdfClassIndex(sdf.payload());
                         let name = self.sdf_prototypes.name_from_index(class_index);
                         if let Some(name) = name {
                             name.to_string()
@@ -111,7 +165,19 @@ impl VisualObjects {
     pub(crate) fn compose_shader(&self, base_code: &str) -> String {
         let sdf_classes_code = self.sdf_prototypes.sdf_classes_code();
         let procedural_textures_code = self.materials.procedural_textures_code();
-        format!("{base_code}\n{sdf_classes_code}\n{procedural_textures_code}")
+        let surface_shaders_code = self.materials.surface_shaders_code();
+        let backgrounds_code = self.backgrounds.generate_gpu_code();
+        format!("{base_code}\n{sdf_classes_code}\n{procedural_textures_code}\n{surface_shaders_code}\n{backgrounds_code}")
+    }
+
+    #[must_use]
+    pub(crate) fn has_background(&self, uid: BackgroundUid) -> bool {
+        self.backgrounds.contains(uid)
+    }
+
+    #[must_use]
+    pub(crate) fn background_animated(&self, uid: BackgroundUid) -> bool {
+        self.backgrounds.animated(uid)
     }
 
     #[must_use]
@@ -146,13 +212,113 @@ impl VisualObjects {
             Some(object) => {
                 if object.material() != material {
                     object.set_material(material, &mut self.triangles);
-                    self.per_object_kind_statistics[object.data_kind_uid()].register_object_mutation();   
+                    let kind = object.data_kind_uid();
+                    let slot = self.per_object_kind_slots[kind].slot(victim).expect("object has no slot");
+                    Self::mark_written(&self.per_object_kind_statistics[kind], &self.per_object_kind_slots[kind], &mut self.per_object_kind_dirty_slots[kind], slot);
+                    self.per_object_kind_statistics[kind].register_object_mutation();
                 }
             },
             None => panic!("object {victim} not found"),
         }
     }
 
+    // the texels go into a new atlas region, which stands in for the procedural albedo of the material; on
+    // failure (e.g. the atlas is full) the baking is turned off for the material, so it is not retried every frame
+    pub(crate) fn put_baked_albedo(&mut self, material: MaterialIndex, texels: ImmutableBitmapReference, mapping: AtlasRegionMappingBuilder) -> anyhow::Result<()> {
+        let baked = self.texture_atlas_page_composer.allocate(texels)
+            .ok_or_else(|| anyhow!("no room in the texture atlas for the baked albedo of material {}", material.0))
+            .and_then(|region| self.texture_atlas_page_composer.map_region(region, mapping));
+        match baked {
+            Ok(TextureReference::Bitmap(region)) => {
+                self.materials.set_baked_albedo_texture(material, region);
+                Ok(())
+            }
+            Ok(_) => unreachable!("atlas regions are mapped as bitmaps"),
+            Err(error) => {
+                self.materials.set_albedo_baking(material, None);
+                Err(error)
+            }
+        }
+    }
+
+    // a hidden object keeps its uid, slot and layers; it is just not traced
+    pub(crate) fn set_visible(&mut self, target: ObjectUid, visible: bool) {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_layers.set_visible(target, visible);
+    }
+
+    #[must_use]
+    pub(crate) fn visible(&self, target: ObjectUid) -> bool {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_layers.visible(target)
+    }
+
+    pub(crate) fn set_layer_mask(&mut self, target: ObjectUid, mask: LayerMask) {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_layers.set_mask(target, mask);
+    }
+
+    #[must_use]
+    pub(crate) fn layer_mask(&self, target: ObjectUid) -> LayerMask {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_layers.mask(target)
+    }
+
+    #[must_use]
+    pub(crate) fn object_layers_version(&self) -> Version {
+        self.object_layers.version()
+    }
+
+    // indexed by the object uid, zero for the hidden objects
+    #[must_use]
+    pub(crate) fn serialize_object_layers(&self) -> Vec<u32> {
+        self.object_layers.serialize()
+    }
+
+    pub(crate) fn set_uv_transform(&mut self, target: ObjectUid, transform: UvTransform) {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_uv_transforms.set(target, transform);
+    }
+
+    #[must_use]
+    pub(crate) fn uv_transform(&self, target: ObjectUid) -> UvTransform {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_uv_transforms.get(target)
+    }
+
+    #[must_use]
+    pub(crate) fn object_uv_transforms_version(&self) -> Version {
+        self.object_uv_transforms.version()
+    }
+
+    // indexed by the object uid, a quartet per object
+    #[must_use]
+    pub(crate) fn serialize_object_uv_transforms(&self) -> Vec<[f32; 4]> {
+        self.object_uv_transforms.serialize()
+    }
+
+    pub(crate) fn set_opacity(&mut self, target: ObjectUid, opacity: f32) {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_opacities.set(target, opacity);
+    }
+
+    #[must_use]
+    pub(crate) fn opacity(&self, target: ObjectUid) -> f32 {
+        assert!(self.objects.contains_key(&target), "object {target} not found");
+        self.object_opacities.get(target)
+    }
+
+    #[must_use]
+    pub(crate) fn object_opacities_version(&self) -> Version {
+        self.object_opacities.version()
+    }
+
+    // indexed by the object uid, one for the opaque objects
+    #[must_use]
+    pub(crate) fn serialize_object_opacities(&self) -> &[f32] {
+        self.object_opacities.serialize()
+    }
+
     #[must_use]
     pub(crate) fn material_of(&self, victim: ObjectUid) -> MaterialIndex {
         match self.objects.get(&victim) {
@@ -163,56 +329,276 @@ impl VisualObjects {
         }
     }
 
+    // a degenerate parallelogram (see 'Parallelogram::is_degenerate') is kept with a warning, to be reshaped
+    // later, but is neither hit nor sampled as a light; 'degenerate_parallelogram' tells it
     pub fn add_parallelogram(&mut self, origin: Point, local_x: Vector, local_y: Vector, material: MaterialIndex) -> ObjectUid {
-        Self::add_object(&mut self.objects, &mut self.uid_generator, &mut self.per_object_kind_statistics, |uid| {
+        let uid = Self::add_object(&mut self.objects, &mut self.uid_generator, &mut self.object_layers, &mut self.per_object_kind_statistics, &mut self.per_object_kind_slots, &mut self.per_object_kind_dirty_slots, |uid| {
+            let parallelogram = Parallelogram::new(origin, local_x, local_y, Linkage::new(uid, material));
+            Self::warn_if_degenerate(&parallelogram, uid);
             Box::new(Monolithic::new(
                 DataKind::Parallelogram as usize,
-                Box::new(Parallelogram::new(origin, local_x, local_y, Linkage::new(uid, material))),
+                Box::new(parallelogram),
                 0,
                 Affine::identity(),
             ))
-        })
+        });
+        self.object_uv_transforms.reset(uid);
+        self.object_opacities.reset(uid);
+        uid
+    }
+
+    // the parallelogram keeps its uid, slot, material and layers: only its slot is uploaded again,
+    // the bvh stays as it is; returns false if the target is not a parallelogram
+    pub(crate) fn reshape_parallelogram(&mut self, target: ObjectUid, origin: Point, local_x: Vector, local_y: Vector) -> bool {
+        let kind = DataKind::Parallelogram as usize;
+        let Some(object) = self.objects.get_mut(&target).filter(|object| object.data_kind_uid() == kind) else {
+            return false;
+        };
+        let parallelogram = Parallelogram::new(origin, local_x, local_y, Linkage::new(target, object.material()));
+        Self::warn_if_degenerate(&parallelogram, target);
+        *object = Box::new(Monolithic::new(
+            kind,
+            Box::new(parallelogram),
+            0,
+            Affine::identity(),
+        ));
+
+        let slot = self.per_object_kind_slots[kind].slot(target).expect("object has no slot");
+        Self::mark_written(&self.per_object_kind_statistics[kind], &self.per_object_kind_slots[kind], &mut self.per_object_kind_dirty_slots[kind], slot);
+        self.per_object_kind_statistics[kind].register_object_mutation();
+        true
+    }
+
+    fn warn_if_degenerate(parallelogram: &Parallelogram, uid: ObjectUid) {
+        if parallelogram.is_degenerate() {
+            log::warn!("parallelogram {uid} is degenerate: it will not be traced");
+        }
+    }
+
+    // false for the other kinds of objects and for the unknown uids
+    #[must_use]
+    pub fn degenerate_parallelogram(&self, target: ObjectUid) -> bool {
+        self.objects.get(&target).and_then(|object| object.parallelogram()).is_some_and(Parallelogram::is_degenerate)
+    }
+
+    pub fn add_sphere(&mut self, center: Point, radius: f64, material: MaterialIndex) -> ObjectUid {
+        // the unit cube placed by the transformation bounds the sphere, see 'make_bvh_support'
+        let placement = Affine::from_translation(center.to_vec()) * Affine::from_scale(radius);
+        let uid = Self::add_object(&mut self.objects, &mut self.uid_generator, &mut self.object_layers, &mut self.per_object_kind_statistics, &mut self.per_object_kind_slots, &mut self.per_object_kind_dirty_slots, |uid| {
+            Box::new(Monolithic::new(
+                DataKind::Sphere as usize,
+                Box::new(Sphere::new(center, radius, Linkage::new(uid, material))),
+                0,
+                placement,
+            ))
+        });
+        self.object_uv_transforms.reset(uid);
+        self.object_opacities.reset(uid);
+        uid
     }
 
     pub fn add_sdf(&mut self, location: &Affine, ray_marching_step_scale: f64, class_uid: &UniqueSdfClassName, material: MaterialIndex) -> ObjectUid {
         assert!(is_affine(location), "projection matrices are not supported");
         assert_gt!(ray_marching_step_scale, 0.0);
         let index = self.sdf_prototypes.properties_for_name(class_uid).unwrap_or_else(|| panic!("registration for the '{class_uid}' sdf has not been found"));
-        Self::add_object(&mut self.objects, &mut self.uid_generator, &mut self.per_object_kind_statistics, |uid| {
+        let parameters = self.sdf_prototypes.parameters_from_index(*index).defaults();
+        let uid = Self::add_object(&mut self.objects, &mut self.uid_generator, &mut self.object_layers, &mut self.per_object_kind_statistics, &mut self.per_object_kind_slots, &mut self.per_object_kind_dirty_slots, |uid| {
             Box::new(Monolithic::new(
                 DataKind::Sdf as usize,
-                Box::new(SdfInstance::new(*location, ray_marching_step_scale, *index, Linkage::new(uid, material))),
+                Box::new(SdfInstance::new(*location, ray_marching_step_scale, *index, Linkage::new(uid, material), parameters)),
                 index.0,
                 *location,
             ))
-        })
+        });
+        self.object_uv_transforms.reset(uid);
+        self.object_opacities.reset(uid);
+        uid
+    }
+
+    // one of the parameters declared by the class (see 'NamedSdf::parametric'): only the slot of the instance
+    // is uploaded again, the shader and the bvh stay as they are
+    pub(crate) fn set_sdf_parameter(&mut self, target: ObjectUid, name: &str, value: f64) {
+        let object = self.objects.get_mut(&target).unwrap_or_else(|| panic!("object {target} not found"));
+        assert_eq!(object.data_kind_uid(), DataKind::Sdf as usize, "object {target} is not an sdf");
+        let parameters = self.sdf_prototypes.parameters_from_index(SdfClassIndex(object.payload()));
+        let parameter = parameters.find(name).unwrap_or_else(|| panic!("the sdf class of {target} has no parameter '{name}'"));
+        let range = parameters.range(parameter);
+        assert!(range.contains(&value), "value {value} of the parameter '{name}' is out of the range {range:?}");
+
+        let mut values = object.sdf_parameters().expect("sdf instance has no parameters");
+        values[parameter.index()] = value as f32;
+        object.set_sdf_parameters(values);
+
+        let kind = DataKind::Sdf as usize;
+        let slot = self.per_object_kind_slots[kind].slot(target).expect("object has no slot");
+        Self::mark_written(&self.per_object_kind_statistics[kind], &self.per_object_kind_slots[kind], &mut self.per_object_kind_dirty_slots[kind], slot);
+        self.per_object_kind_statistics[kind].register_object_mutation();
+    }
+
+    #[must_use]
+    pub(crate) fn sdf_parameter(&self, target: ObjectUid, name: &str) -> f64 {
+        let object = self.objects.get(&target).unwrap_or_else(|| panic!("object {target} not found"));
+        assert_eq!(object.data_kind_uid(), DataKind::Sdf as usize, "object {target} is not an sdf");
+        let parameters = self.sdf_prototypes.parameters_from_index(SdfClassIndex(object.payload()));
+        let parameter = parameters.find(name).unwrap_or_else(|| panic!("the sdf class of {target} has no parameter '{name}'"));
+        object.sdf_parameters().expect("sdf instance has no parameters")[parameter.index()] as f64
+    }
+
+    // the steps the class can be ray marched with safely, see 'Sdf::lipschitz_bound'
+    #[must_use]
+    pub(crate) fn sdf_class_ray_marching_step_scale(&self, class_uid: &UniqueSdfClassName) -> f64 {
+        let index = self.sdf_prototypes.properties_for_name(class_uid).unwrap_or_else(|| panic!("registration for the '{class_uid}' sdf has not been found"));
+        self.sdf_prototypes.ray_marching_step_scale_from_index(*index)
     }
 
-    pub fn add_mesh(&mut self, source: &MeshWarehouse, slot: WarehouseSlot, transformation: &Transformation, material: MaterialIndex) -> ObjectUid {
+    // the classes can be changed while rendering: the shader gets recomposed before the next frame
+    pub fn add_sdf_class(&mut self, class: &NamedSdf) {
+        let _ = self.sdf_prototypes.add_class(class);
+    }
+
+    // instances of the class take the new shape; they keep their parameter values unless
+    // the new class declares other parameters, then they take the new defaults
+    pub fn replace_sdf_class(&mut self, class: &NamedSdf) {
+        let previous_parameters = self.sdf_prototypes.properties_for_name(class.name())
+            .map(|index| self.sdf_prototypes.parameters_from_index(*index).clone());
+        let index = self.sdf_prototypes.replace_class(class);
+        if self.sdf_class_instantiated(index) {
+            let kind = DataKind::Sdf as usize;
+            if previous_parameters.as_ref() != Some(class.parameters()) {
+                let defaults = class.parameters().defaults();
+                self.objects.values_mut()
+                    .filter(|object| object.data_kind_uid() == kind && object.payload() == index.0)
+                    .for_each(|object| object.set_sdf_parameters(defaults));
+            }
+            let instance_slots: Vec<usize> = self.slotted_of_a_kind(DataKind::Sdf).iter().enumerate()
+                .filter(|(_, sdf)| sdf.is_some_and(|sdf| sdf.payload() == index.0))
+                .map(|(slot, _)| slot)
+                .collect();
+            for slot in instance_slots {
+                Self::mark_written(&self.per_object_kind_statistics[kind], &self.per_object_kind_slots[kind], &mut self.per_object_kind_dirty_slots[kind], slot);
+            }
+            self.per_object_kind_statistics[kind].register_object_mutation();
+        }
+    }
+
+    pub fn remove_sdf_class(&mut self, name: &UniqueSdfClassName) {
+        let index = *self.sdf_prototypes.properties_for_name(name).unwrap_or_else(|| panic!("registration for the '{name}' sdf has not been found"));
+        assert!(false == self.sdf_class_instantiated(index), "sdf class '{name}' is used by scene objects");
+        let _ = self.sdf_prototypes.remove_class(name);
+    }
+
+    #[must_use]
+    fn sdf_class_instantiated(&self, index: SdfClassIndex) -> bool {
+        self.objects.values().any(|object| object.data_kind_uid() == DataKind::Sdf as usize && object.payload() == index.0)
+    }
+
+    #[must_use]
+    pub(crate) fn sdf_classes_version(&self) -> Version {
+        self.sdf_prototypes.version()
+    }
+
+    #[must_use]
+    pub(crate) fn sdf_distance_grids(&self) -> SdfDistanceGrids {
+        self.sdf_prototypes.distance_grids()
+    }
+
+    pub fn add_mesh(&mut self, source: &MeshWarehouse, slot: WarehouseSlot, transformation: &Transformation, material: MaterialIndex, shading: MeshShading) -> ObjectUid {
         let links = Linkage::new(self.uid_generator.next(), material);
+        self.object_layers.reset(links.uid());
+        self.object_uv_transforms.reset(links.uid());
+        self.object_opacities.reset(links.uid());
 
         let instance = source.instantiate(slot, transformation, links,);
         instance.put_triangles_into(&mut self.triangles);
+        self.mesh_vertices.insert(links.uid(), (shading, instance.into_vertices()));
 
         let geometry_kind = DataKind::TriangleMesh as usize;
         self.objects.insert(links.uid(), Box::new(Triangulated::new(links, geometry_kind, 0, *transformation.forward())));
+        let slot = self.per_object_kind_slots[geometry_kind].occupy(links.uid());
+        Self::mark_written(&self.per_object_kind_statistics[geometry_kind], &self.per_object_kind_slots[geometry_kind], &mut self.per_object_kind_dirty_slots[geometry_kind], slot);
         self.per_object_kind_statistics[geometry_kind].register_new_object();
 
         links.uid()
     }
 
+    // the mesh keeps its uid, material, shading and placement, the triangles are taken from the prototype
+    // once more (e.g. after 'MeshWarehouse::reload'); returns false if the target is not a mesh
+    pub(crate) fn reshape_mesh(&mut self, target: ObjectUid, source: &MeshWarehouse, slot: WarehouseSlot) -> bool {
+        let geometry_kind = DataKind::TriangleMesh as usize;
+        let Some(object) = self.objects.get(&target).filter(|object| object.data_kind_uid() == geometry_kind) else {
+            return false;
+        };
+        let links = Linkage::new(target, object.material());
+        let transformation = Transformation::new(*object.transformation());
+
+        remove_with_reorder(&mut self.triangles, |triangle| triangle.host() == target);
+        let instance = source.instantiate(slot, &transformation, links);
+        instance.put_triangles_into(&mut self.triangles);
+        let shading = self.mesh_vertices[&target].0;
+        self.mesh_vertices.insert(target, (shading, instance.into_vertices()));
+
+        let slot = self.per_object_kind_slots[geometry_kind].slot(target).expect("object has no slot");
+        Self::mark_written(&self.per_object_kind_statistics[geometry_kind], &self.per_object_kind_slots[geometry_kind], &mut self.per_object_kind_dirty_slots[geometry_kind], slot);
+        self.per_object_kind_statistics[geometry_kind].register_object_reshape();
+        true
+    }
+
+    // moves the object of any kind in place, keeping its uid, slot, material and layers; the meshes and the
+    // bounded objects rebuild the bvh, the parallelograms are not in it; returns false if there is no target
+    pub(crate) fn translate(&mut self, target: ObjectUid, offset: Vector) -> bool {
+        let Some(object) = self.objects.get_mut(&target) else {
+            return false;
+        };
+        object.translate(offset, &mut self.triangles);
+        let kind = object.data_kind_uid();
+        if kind == DataKind::TriangleMesh as usize {
+            let (_, vertices) = self.mesh_vertices.get_mut(&target).expect("mesh has no vertices");
+            vertices.iter_mut().for_each(|vertex| *vertex = vertex.translated(offset));
+        }
+
+        let slot = self.per_object_kind_slots[kind].slot(target).expect("object has no slot");
+        Self::mark_written(&self.per_object_kind_statistics[kind], &self.per_object_kind_slots[kind], &mut self.per_object_kind_dirty_slots[kind], slot);
+        if kind == DataKind::Parallelogram as usize {
+            self.per_object_kind_statistics[kind].register_object_mutation();
+        } else {
+            self.per_object_kind_statistics[kind].register_object_reshape();
+        }
+        true
+    }
+
     pub(crate) fn delete(&mut self, target: ObjectUid) {
         let removed_or_none = self.objects.remove(&target);
         if let Some(removed) = removed_or_none {
-            self.per_object_kind_statistics[removed.data_kind_uid()].delete_object();
+            let kind = removed.data_kind_uid();
+            let slot = self.per_object_kind_slots[kind].release(target).expect("object has no slot");
+            Self::mark_written(&self.per_object_kind_statistics[kind], &self.per_object_kind_slots[kind], &mut self.per_object_kind_dirty_slots[kind], slot);
+            self.per_object_kind_statistics[kind].delete_object();
             self.uid_generator.put_back(target);
+            self.object_layers.reset(target);
+            self.object_uv_transforms.reset(target);
+            self.object_opacities.reset(target);
             
             if removed.data_kind_uid() == DataKind::TriangleMesh as usize {
                 remove_with_reorder(&mut self.triangles, |triangle| triangle.host() == target);
+                self.mesh_vertices.remove(&target);
             }
         }
     }
     
+    // the changes made in between are published by a single version bump per object kind,
+    // so the bvh and the gpu buffers are rebuilt once
+    pub(crate) fn defer_version_bumps(&mut self) {
+        for statistics in self.per_object_kind_statistics.iter_mut() {
+            statistics.defer_version_bumps();
+        }
+    }
+
+    pub(crate) fn resume_version_bumps(&mut self) {
+        for statistics in self.per_object_kind_statistics.iter_mut() {
+            statistics.resume_version_bumps();
+        }
+    }
+
     pub(crate) fn clear_objects(&mut self) {
         if self.objects.is_empty() {
             return;
@@ -221,50 +607,182 @@ impl VisualObjects {
         for object in self.objects.keys() {
             self.uid_generator.put_back(*object);
         }
-        for statistics in self.per_object_kind_statistics.iter_mut() {
+        for (statistics, dirty_slots) in self.per_object_kind_statistics.iter_mut().zip(self.per_object_kind_dirty_slots.iter_mut()) {
+            dirty_slots.resize(0, statistics.upcoming_data_version());
             statistics.clear_objects();
         }
+        for slots in self.per_object_kind_slots.iter_mut() {
+            slots.clear();
+        }
         self.objects.clear();
+        self.object_layers.clear();
+        self.object_uv_transforms.clear();
+        self.object_opacities.clear();
         self.triangles.clear();
+        self.mesh_vertices.clear();
     }
     
+    // the position of the object in the gpu buffer of its kind; it stays the same until the object is deleted
     #[must_use]
-    pub(crate) fn morphable(&self) -> Vec<ObjectUid> {
-        let sdf_count = self.count_of_a_kind(DataKind::Sdf);
-        let identified = self.sorted_of_a_kind(DataKind::Sdf as usize, sdf_count);
-        identified.iter().map(|x| x.id).collect()
+    pub(crate) fn slot_of(&self, target: ObjectUid) -> Option<usize> {
+        let object = self.objects.get(&target)?;
+        self.per_object_kind_slots[object.data_kind_uid()].slot(target)
+    }
+
+    // the length of the gpu buffer of the kind: the objects and the holes left by the deleted ones
+    #[must_use]
+    pub(crate) fn slots_count_of_a_kind(&self, kind: DataKind) -> usize {
+        self.per_object_kind_slots[kind as usize].len()
     }
     
     #[must_use]
-    pub(crate) fn evaluate_serialized_triangles(&self) -> GpuReadySerializationBuffer {
+    pub(crate) fn evaluate_serialized_triangles(&self, quantized: bool) -> GpuReadySerializationBuffer {
         assert!(!self.triangles.is_empty(), "gpu can't accept empty buffer");
-        serialize_batch(&self.triangles)
+        let mut meshes: Vec<MeshVertices> = self.mesh_vertices.iter()
+            .map(|(uid, (shading, vertices))| MeshVertices { uid: *uid, material: self.material_of(*uid), vertices, shading: *shading })
+            .collect();
+        meshes.sort_by_key(|mesh| mesh.uid.0);
+        if quantized {
+            serialize_quantized_indexed_triangles(&self.triangles, &meshes)
+        } else {
+            serialize_indexed_triangles(&self.triangles, &meshes)
+        }
     }
 
     #[must_use]
-    pub(crate) fn evaluate_serialized_bvh(&self, aabb_inflation_rate: f64) -> GpuReadySerializationBuffer {
+    pub(crate) fn evaluate_bvh(&self, aabb_inflation_rate: f64) -> Bvh {
         assert!(self.bvh_object_count() > 0, "gpu can't accept empty buffer");
         assert!(aabb_inflation_rate >= 0.0, "aabb_inflation is negative");
         
         let mut objects_to_tree = self.make_bvh_support(aabb_inflation_rate);
-        build_serialized_bvh(&mut objects_to_tree)
+        build_bvh(&mut objects_to_tree)
     }
-    
+
+    // valid while the bvh topology version stays the same as it was for the build
+    pub(crate) fn refit_bvh(&self, target: &mut Bvh, aabb_inflation_rate: f64) {
+        assert!(aabb_inflation_rate >= 0.0, "aabb_inflation is negative");
+        let objects_to_tree = self.make_bvh_support(aabb_inflation_rate);
+        target.refit(&objects_to_tree);
+    }
+
+    #[must_use]
+    pub(crate) fn bvh_support_hash(&self, aabb_inflation_rate: f64) -> u64 {
+        support_hash(&self.make_bvh_support(aabb_inflation_rate))
+    }
+
+    #[must_use]
+    pub(crate) fn bvh_topology_version(&self) -> (Version, Version, Version) {
+        (
+            self.per_object_kind_statistics[DataKind::TriangleMesh as usize].topology_version(),
+            self.per_object_kind_statistics[DataKind::Sdf as usize].topology_version(),
+            self.per_object_kind_statistics[DataKind::Sphere as usize].topology_version(),
+        )
+    }
+
+    // the closest hit among the objects a camera seeing the 'layers' sees: the triangles, the spheres and the
+    // parallelograms are hit exactly, an sdf - by the box of its class (the distance field exists as the
+    // shader code only), such a hit is marked as not exact
+    #[must_use]
+    pub(crate) fn cast_ray(&self, ray: &Ray, layers: LayerMask) -> Option<RayHit> {
+        let mut closest: Option<(ObjectUid, RayIntersection, bool)> = None;
+        // the bvh nodes farther than the closest hit are skipped
+        let closest_distance = Cell::new(f64::MAX);
+        let mut consider = |uid: ObjectUid, intersection: Option<RayIntersection>, exact: bool| {
+            let Some(intersection) = intersection else {
+                return;
+            };
+            if intersection.distance() < closest_distance.get() && self.object_layers.traced(uid, layers) {
+                closest = Some((uid, intersection, exact));
+                closest_distance.set(intersection.distance());
+            }
+        };
+
+        // the gpu does not put the parallelograms into the bvh either
+        let parallelograms = &self.per_object_kind_slots[DataKind::Parallelogram as usize];
+        for uid in parallelograms.occupants().iter().flatten() {
+            consider(*uid, self.objects[uid].intersect(ray), true);
+        }
+
+        if self.bvh_inhabited() {
+            self.with_ray_query_bvh(|tree| {
+                tree.visit_leaves(|aabb| ray_meets_aabb(ray, aabb, closest_distance.get()), |primitive_type, index| {
+                    let (uid, intersection, exact) = match primitive_type {
+                        PrimitiveType::Triangle => {
+                            let triangle = &self.triangles[index];
+                            (triangle.host(), triangle.intersect(ray), true)
+                        },
+                        PrimitiveType::Sdf => {
+                            let uid = self.occupant_of(DataKind::Sdf, index);
+                            (uid, self.intersect_sdf_box(uid, ray), false)
+                        },
+                        PrimitiveType::Sphere => {
+                            let uid = self.occupant_of(DataKind::Sphere, index);
+                            (uid, self.objects[&uid].intersect(ray), true)
+                        },
+                        PrimitiveType::Null => unreachable!("bvh leaves refer to primitives"),
+                    };
+                    consider(uid, intersection, exact);
+                });
+            });
+        }
+
+        closest.map(|(uid, intersection, exact)| RayHit::new(uid, ray.at(intersection.distance()), intersection.normal(), intersection.distance(), exact))
+    }
+
+    fn with_ray_query_bvh(&self, action: impl FnOnce(&Bvh)) {
+        let topology_version = self.bvh_topology_version();
+        let data_version = (self.data_version(DataKind::TriangleMesh), self.data_version(DataKind::Sdf), self.data_version(DataKind::Sphere));
+        let mut cached = self.ray_query_bvh.borrow_mut();
+        match cached.as_mut() {
+            Some(cached) if cached.topology_version == topology_version => {
+                if cached.data_version != data_version {
+                    self.refit_bvh(&mut cached.tree, 0.0);
+                    cached.data_version = data_version;
+                }
+            },
+            _ => {
+                *cached = Some(RayQueryBvh { tree: self.evaluate_bvh(0.0), topology_version, data_version });
+            },
+        }
+        action(&cached.as_ref().unwrap().tree);
+    }
+
+    // the ray goes into the space of the class, where the box is
+    #[must_use]
+    fn intersect_sdf_box(&self, uid: ObjectUid, ray: &Ray) -> Option<RayIntersection> {
+        let sdf = &self.objects[&uid];
+        let class_aabb = self.sdf_prototypes.aabb_from_index(SdfClassIndex(sdf.payload()));
+        let location = Transformation::new(*sdf.transformation());
+        let local_ray = ray.transform(&sdf.transformation().invert().expect("sdf location is not invertible"));
+        intersect_aabb(&local_ray, class_aabb)
+            .map(|intersection| RayIntersection::facing(ray, intersection.distance(), location.of_surface_vector(intersection.normal())))
+    }
+
+    #[must_use]
+    fn occupant_of(&self, kind: DataKind, slot: usize) -> ObjectUid {
+        self.per_object_kind_slots[kind as usize].occupants()[slot].expect("bvh refers to an empty slot")
+    }
+
     #[must_use]
     fn make_bvh_support(&self, aabb_inflation_rate: f64) -> Vec<SceneObjectProxy> {
         let mut objects_to_tree: Vec<SceneObjectProxy> = Vec::with_capacity(self.bvh_object_count());
 
         self.triangles.make_proxies(&mut objects_to_tree, aabb_inflation_rate);
         
-        let sdf_count = self.count_of_a_kind(DataKind::Sdf);
-        if sdf_count > 0 {
-            let sorted_of_a_kind = self.sorted_of_a_kind(DataKind::Sdf as usize, sdf_count);
-            for (index, sdf) in sorted_of_a_kind.iter().enumerate() {
-                let class_index = sdf.entity.payload();
+        for (slot, sdf) in self.slotted_of_a_kind(DataKind::Sdf).iter().enumerate() {
+            if let Some(sdf) = sdf {
+                let class_index = sdf.payload();
                 let class_aabb = self.sdf_prototypes.aabb_from_index(SdfClassIndex(class_index));
                 let class_aabb = class_aabb.extent_relative_inflate(aabb_inflation_rate);
-                let instance_aabb = class_aabb.transform(sdf.entity.transformation());
-                objects_to_tree.push(proxy_of_sdf(index, instance_aabb));
+                let instance_aabb = class_aabb.transform(sdf.transformation());
+                objects_to_tree.push(proxy_of_sdf(slot, instance_aabb));
+            }
+        }
+
+        for (slot, sphere) in self.slotted_of_a_kind(DataKind::Sphere).iter().enumerate() {
+            if let Some(sphere) = sphere {
+                let aabb = Aabb::from_points(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0)).transform(sphere.transformation());
+                objects_to_tree.push(proxy_of_sphere(slot, aabb.extent_relative_inflate(aabb_inflation_rate)));
             }
         }
 
@@ -274,7 +792,22 @@ impl VisualObjects {
     #[must_use]
     pub(crate) fn evaluate_serialized(&self, kind: DataKind) -> GpuReadySerializationBuffer {
         assert_ne!(kind, DataKind::TriangleMesh, "call 'evaluate_serialized_triangles' instead");
-        self.serialize(kind)
+        self.serialize(kind, 0..self.slots_count_of_a_kind(kind))
+    }
+
+    // the part of the 'evaluate_serialized' result: to be written over the buffer of the same slots count
+    #[must_use]
+    pub(crate) fn evaluate_serialized_slots(&self, kind: DataKind, slots: Range<usize>) -> GpuReadySerializationBuffer {
+        assert_ne!(kind, DataKind::TriangleMesh, "call 'evaluate_serialized_triangles' instead");
+        assert!(false == slots.is_empty(), "gpu can't accept empty buffer");
+        self.serialize(kind, slots)
+    }
+
+    // the slots of the kind serialized differently than at the version; none if the slots count
+    // has changed since, so the whole buffer has to be serialized once more
+    #[must_use]
+    pub(crate) fn slots_written_since(&self, kind: DataKind, version: Version) -> Option<Range<usize>> {
+        self.per_object_kind_dirty_slots[kind as usize].written_since(version)
     }
 
     #[must_use]
@@ -297,66 +830,111 @@ impl VisualObjects {
         self.per_object_kind_statistics[kind as usize].data_version()
     }
 
+    #[must_use] #[cfg(test)]
+    pub(crate) fn emissive_parallelogram_slots(&self) -> Vec<u32> {
+        self.light_sources().iter().map(|light| light.slot).collect()
+    }
+
+    // the parallelograms with emissive materials (but the degenerate ones), in the order of the slots: the lights
+    // sampled by the next event estimation
+    #[must_use]
+    pub(crate) fn light_sources(&self) -> Vec<LightSource> {
+        self.slotted_of_a_kind(DataKind::Parallelogram).iter().enumerate()
+            .filter_map(|(slot, object)| {
+                let object = object.filter(|object| self.materials.emissive(object.material()))?;
+                let parallelogram = object.parallelogram().expect("parallelogram kind object is a parallelogram");
+                if parallelogram.is_degenerate() {
+                    return None;
+                }
+                let power = self.materials.emitted_luminance(object.material()) * parallelogram.area();
+                Some(LightSource { slot: slot as u32, bounds: parallelogram.bounding_box(), power })
+            })
+            .collect()
+    }
+
+    // both versions only grow, so their sum changes whenever the parallelograms or the materials do
+    #[must_use]
+    pub(crate) fn emissive_parallelograms_version(&self) -> Version {
+        self.data_version(DataKind::Parallelogram) + self.materials.data_version().0
+    }
+
+    // the box around all the objects but the degenerate parallelograms, none for an empty scene
+    #[must_use]
+    pub(crate) fn bounds(&self) -> Option<Aabb> {
+        let parallelograms = self.slotted_of_a_kind(DataKind::Parallelogram).into_iter().flatten()
+            .filter_map(|object| object.parallelogram())
+            .filter(|parallelogram| false == parallelogram.is_degenerate())
+            .map(|parallelogram| parallelogram.bounding_box());
+        let others = self.make_bvh_support(0.0).into_iter().map(|proxy| proxy.aabb());
+        parallelograms.chain(others).reduce(Aabb::make_union)
+    }
+
     #[must_use]
     fn bvh_object_count(&self) -> usize {
-        self.triangles.len() + self.count_of_a_kind(DataKind::Sdf)
+        self.triangles.len() + self.count_of_a_kind(DataKind::Sdf) + self.count_of_a_kind(DataKind::Sphere)
     }
 
     #[must_use]
     fn add_object<Constructor: FnOnce(ObjectUid) -> Box<dyn SceneObject>>(
         container: &mut HashMap<ObjectUid, Box<dyn SceneObject>>,
         uid_generator: &mut UidGenerator<ObjectUid>,
+        layers: &mut ObjectLayers,
         statistics: &mut [Statistics],
+        slots: &mut [ObjectSlots],
+        dirty_slots: &mut [DirtySlots],
         create_object: Constructor,
     ) -> ObjectUid {
         let uid = uid_generator.next();
+        layers.reset(uid);
         let object = create_object(uid);
 
-        statistics[object.data_kind_uid()].register_new_object();
+        let kind = object.data_kind_uid();
+        let slot = slots[kind].occupy(uid);
+        Self::mark_written(&statistics[kind], &slots[kind], &mut dirty_slots[kind], slot);
+        statistics[kind].register_new_object();
         container.insert(uid, object);
 
         uid
     }
 
+    // goes before the statistics bump: the slot gets the version the change is published under
+    fn mark_written(statistics: &Statistics, slots: &ObjectSlots, dirty_slots: &mut DirtySlots, slot: usize) {
+        dirty_slots.write(slot, slots.len(), statistics.upcoming_data_version());
+    }
+
+    // the objects go in the order of their slots; the holes are zeroed (the gpu never refers to them, a zeroed
+    // parallelogram has no area and no object uid)
     #[must_use]
-    fn serialize(&self, desired_kind: DataKind) -> GpuReadySerializationBuffer { // TODO: we can reuse the buffer in case object count is the same
-        let desired_kind = desired_kind as usize;
-        let count = self.per_object_kind_statistics[desired_kind].object_count();
-        assert!(count > 0, "gpu can't accept empty buffer");
-        let sorted_of_a_kind = self.sorted_of_a_kind(desired_kind, count);
-        
-        let quartets_per_object = sorted_of_a_kind[0].entity.serialized_quartet_count();
-        let mut result = GpuReadySerializationBuffer::new(count, quartets_per_object);
-        for object in sorted_of_a_kind {
-            object.entity.serialize_into(&mut result);
-        }
+    fn serialize(&self, desired_kind: DataKind, slots: Range<usize>) -> GpuReadySerializationBuffer {
+        assert!(self.count_of_a_kind(desired_kind) > 0, "gpu can't accept empty buffer");
+        let slotted_of_a_kind = self.slotted_of_a_kind(desired_kind);
+
+        let quartets_per_object = slotted_of_a_kind.iter().flatten().next().unwrap().serialized_quartet_count();
+        let mut result = GpuReadySerializationBuffer::new(slots.len(), quartets_per_object);
+        result.write_batches_in_parallel(&slotted_of_a_kind[slots], SERIALIZATION_BATCH_SIZE, |object, batch| {
+            match object {
+                Some(object) => object.serialize_into(batch),
+                None => (0..quartets_per_object).for_each(|_| batch.write_quartet_f32(0.0, 0.0, 0.0, 0.0)),
+            }
+        });
 
         result
     }
     
-    fn sorted_of_a_kind(&self, desired_kind: usize, expected_count: usize) -> Vec<IdentifiedObject<'_>> {
-        let mut sorted = Vec::with_capacity(expected_count);
-        for (key, object) in self.objects.iter() {
-            if object.data_kind_uid() == desired_kind {
-                sorted.push(IdentifiedObject{ id: *key, entity: object.as_ref() });
-            }
-        }
-        debug_assert_eq!(sorted.len(), expected_count);
-
-        sorted.sort_by_key(|x| x.id.0);
-        sorted
+    #[must_use]
+    fn slotted_of_a_kind(&self, desired_kind: DataKind) -> Vec<Option<&dyn SceneObject>> {
+        self.per_object_kind_slots[desired_kind as usize].occupants().iter()
+            .map(|occupant| occupant.map(|uid| self.objects[&uid].as_ref()))
+            .collect()
     }
 }
 
-struct IdentifiedObject<'a> {
-    id: ObjectUid,
-    entity: &'a dyn SceneObject,
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::container::mesh_warehouse::{MeshWarehouse, WarehouseSlot};
+    use crate::container::mesh_warehouse::{MeshShading, MeshWarehouse, WarehouseSlot};
+    use crate::bvh::proxy::PrimitiveType;
     use crate::container::visual_objects::{DataKind, VisualObjects};
+    use crate::geometry::aabb::Aabb;
     use crate::geometry::alias::{Point, Vector};
     use crate::geometry::transform::{Affine, Transformation};
     use crate::material::material_index::MaterialIndex;
@@ -369,7 +947,13 @@ mod tests {
     use crate::objects::parallelogram::Parallelogram;
     use crate::objects::sdf_class_index::SdfClassIndex;
     use crate::objects::sdf_instance::SdfInstance;
+    use crate::objects::sphere::Sphere;
+    use crate::geometry::epsilon::DEFAULT_EPSILON_F64;
+    use crate::geometry::ray::Ray;
+    use crate::scene::layer_mask::LayerMask;
+    use crate::scene::uv_transform::UvTransform;
     use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+    use crate::sdf::framework::sdf_parameters::SDF_PARAMETERS_COUNT;
     use crate::sdf::framework::sdf_registrator::SdfRegistrator;
     use crate::sdf::object::sdf_sphere::SdfSphere;
     use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
@@ -379,9 +963,9 @@ mod tests {
     use crate::utils::object_uid::ObjectUid;
     use crate::utils::tests::assert_utils::tests::assert_all_not_equal;
     use crate::utils::version::Version;
-    use cgmath::{EuclideanSpace, SquareMatrix, Zero};
+    use cgmath::{assert_abs_diff_eq, EuclideanSpace, SquareMatrix, Zero};
     use std::cell::RefCell;
-    use std::io::Write;
+    use std::io::{Seek, Write};
     use std::path::Path;
     use std::rc::Rc;
     use strum::{EnumCount, IntoEnumIterator};
@@ -406,7 +990,7 @@ mod tests {
 
     #[must_use]
     fn make_empty_container() -> VisualObjects {
-        VisualObjects::new(None, None, None)
+        VisualObjects::new(None, None, None, None, None)
     }
 
     #[must_use]
@@ -420,7 +1004,7 @@ mod tests {
         let sdf_class_name = UniqueSdfClassName::new("i".to_string());
         sdf_registrator.add(&NamedSdf::new(SdfSphere::new(1.0), sdf_class_name.clone()));
 
-        (VisualObjects::new(None, Some(sdf_registrator), Some(textures)), texture_uid, sdf_class_name)
+        (VisualObjects::new(None, Some(sdf_registrator), Some(textures), None, None), texture_uid, sdf_class_name)
     }
 
     #[must_use]
@@ -463,7 +1047,7 @@ mod tests {
     #[test]
     fn test_set_material() {
         let (sphere_sdf_name, sdf_classes) = make_single_sdf_sphere();
-        let system_under_test = Rc::new(RefCell::new(VisualObjects::new(None, Some(sdf_classes), None)));
+        let system_under_test = Rc::new(RefCell::new(VisualObjects::new(None, Some(sdf_classes), None, None, None)));
         
         let material_one = system_under_test.borrow_mut().materials_mutable().add(&MaterialProperties::default());
         let material_two = system_under_test.borrow_mut().materials_mutable().add(&MaterialProperties::default());
@@ -489,7 +1073,7 @@ mod tests {
         assert_material_changed(material_one, material_two, parallelogram);
 
         let (mesh_warehouse, mesh_slot) = make_test_mesh();
-        let mesh = system_under_test.borrow_mut().add_mesh(&mesh_warehouse, mesh_slot, &Transformation::identity(), material_one);
+        let mesh = system_under_test.borrow_mut().add_mesh(&mesh_warehouse, mesh_slot, &Transformation::identity(), material_one, MeshShading::Smooth);
         let version_before = system_under_test.borrow().data_version(DataKind::TriangleMesh);
         assert_material_changed(material_one, material_two, mesh);
         assert_ne!(system_under_test.borrow().data_version(DataKind::TriangleMesh), version_before);
@@ -501,7 +1085,7 @@ mod tests {
     #[test]
     fn test_add_sdf() {
         let (sphere_sdf_name, sdf_classes) = make_single_sdf_sphere();
-        let mut system_under_test = VisualObjects::new(None, Some(sdf_classes), None);
+        let mut system_under_test = VisualObjects::new(None, Some(sdf_classes), None, None, None);
 
         const SDF_TO_ADD: u32 = 5;
 
@@ -520,7 +1104,7 @@ mod tests {
         {
             {
                 let linkage = Linkage::new(ObjectUid(i+1), expected_material);
-                let expected_sdf = SdfInstance::new(Affine::identity(), 1.0, SdfClassIndex(0), linkage);
+                let expected_sdf = SdfInstance::new(Affine::identity(), 1.0, SdfClassIndex(0), linkage, [0.0; SDF_PARAMETERS_COUNT]);
                 expected_sdf.serialize_into(&mut expected_serialized);
             }
             assert_eq!(system_under_test.count_of_a_kind(DataKind::Sdf), i as usize);
@@ -570,6 +1154,48 @@ mod tests {
         assert_eq!(actual_serialized.backend(), expected_serialized.backend());
     }
 
+    #[test]
+    fn test_reshape_parallelogram() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default().with_emission(1.0, 1.0, 1.0));
+        let _ = system_under_test.add_parallelogram(Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), material);
+        let target = system_under_test.add_parallelogram(Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), material);
+        let data_version_before = system_under_test.data_version(DataKind::Parallelogram);
+
+        let expected_origin = Point::new(1.0, 2.0, 3.0);
+        let expected_x = Vector::new(0.0, 0.0, 2.0);
+        let expected_y = Vector::new(0.0, 3.0, 0.0);
+        assert!(system_under_test.reshape_parallelogram(target, expected_origin, expected_x, expected_y));
+
+        let mut expected_serialized = GpuReadySerializationBuffer::new(1, Parallelogram::SERIALIZED_QUARTET_COUNT);
+        Parallelogram::new(expected_origin, expected_x, expected_y, Linkage::new(target, material)).serialize_into(&mut expected_serialized);
+        assert_eq!(system_under_test.slots_written_since(DataKind::Parallelogram, data_version_before), Some(1..2));
+        assert_eq!(system_under_test.evaluate_serialized_slots(DataKind::Parallelogram, 1..2).backend(), expected_serialized.backend());
+        assert_eq!(system_under_test.material_of(target), material);
+        assert_ne!(system_under_test.data_version(DataKind::Parallelogram), data_version_before);
+    }
+
+    #[test]
+    fn test_reshape_parallelogram_of_another_kind() {
+        let mut system_under_test = make_empty_container();
+        let sphere = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, MaterialIndex(0));
+
+        assert!(false == system_under_test.reshape_parallelogram(sphere, Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_degenerate_parallelogram() {
+        let mut system_under_test = make_empty_container();
+        let flat = system_under_test.add_parallelogram(Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(2.0, 0.0, 0.0), MaterialIndex(0));
+        let sphere = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, MaterialIndex(0));
+        assert!(system_under_test.degenerate_parallelogram(flat));
+        assert_eq!(system_under_test.degenerate_parallelogram(sphere), false);
+
+        assert!(system_under_test.reshape_parallelogram(flat, Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)));
+
+        assert_eq!(system_under_test.degenerate_parallelogram(flat), false);
+    }
+
     const CUBE_OBJ_FILE: &str = r#"
         v 0.270893 0.270893 -0.270893
         v 0.270893 -0.270893 -0.270893
@@ -632,10 +1258,10 @@ mod tests {
         let (mesh, meshes) = prepare_test_mesh();
         let dummy_material = system_under_test.materials_mutable().add(&MaterialProperties::default());
         
-        let to_be_kept_one = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material);
-        let to_be_deleted = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material);
-        let to_be_kept_two = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material);
-        let to_be_kept_three = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material);
+        let to_be_kept_one = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material, MeshShading::Smooth);
+        let to_be_deleted = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material, MeshShading::Smooth);
+        let to_be_kept_two = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material, MeshShading::Smooth);
+        let to_be_kept_three = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), dummy_material, MeshShading::Smooth);
 
         system_under_test.delete(to_be_deleted);
 
@@ -646,8 +1272,78 @@ mod tests {
         assert_eq!(system_under_test.material_of(to_be_kept_three), dummy_material);
 
         let triangles_in_a_cube = 12;
-        let serialized_triangles = system_under_test.evaluate_serialized_triangles();
-        assert_eq!(serialized_triangles.total_slots_count(), expected_mesh_count * triangles_in_a_cube);
+        let vertices_in_a_cube = 24; // the corners are split by the face normals
+        let serialized_triangles = system_under_test.evaluate_serialized_triangles(false);
+        assert_eq!(serialized_triangles.total_slots_count(), expected_mesh_count * (triangles_in_a_cube + 2 * vertices_in_a_cube));
+        let quantized_triangles = system_under_test.evaluate_serialized_triangles(true);
+        assert_eq!(quantized_triangles.total_slots_count(), expected_mesh_count * (triangles_in_a_cube + 2 + vertices_in_a_cube));
+    }
+
+    #[test]
+    fn test_reshape_mesh() {
+        let mut system_under_test = make_empty_container();
+        let mut temp_file = NamedTempFile::new_in("./").expect("failed to create temp file");
+        temp_file.write_all(CUBE_OBJ_FILE.as_bytes()).expect("failed to write cube data into the temp file");
+        let mut meshes = MeshWarehouse::new();
+        let mesh = meshes.load(temp_file.path()).unwrap();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let placement = Transformation::new(Affine::from_translation(Vector::new(1.0, 2.0, 3.0)));
+        let cube = system_under_test.add_mesh(&meshes, mesh, &placement, material, MeshShading::Flat);
+        let parallelogram = system_under_test.add_parallelogram(Point::origin(), Vector::unit_x(), Vector::unit_y(), material);
+
+        temp_file.as_file_mut().set_len(0).unwrap();
+        temp_file.as_file_mut().rewind().unwrap();
+        temp_file.write_all(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+        meshes.reload(mesh).unwrap();
+        let data_version_before = system_under_test.data_version(DataKind::TriangleMesh);
+        let topology_version_before = system_under_test.bvh_topology_version();
+
+        assert!(system_under_test.reshape_mesh(cube, &meshes, mesh));
+        assert_eq!(system_under_test.reshape_mesh(parallelogram, &meshes, mesh), false);
+
+        assert_eq!(system_under_test.triangles_count(), 1);
+        assert_eq!(system_under_test.material_of(cube), material);
+        assert_eq!(system_under_test.count_of_a_kind(DataKind::TriangleMesh), 1);
+        assert_ne!(system_under_test.data_version(DataKind::TriangleMesh), data_version_before);
+        assert_ne!(system_under_test.bvh_topology_version(), topology_version_before);
+    }
+
+    #[test]
+    fn test_translate_parallelogram() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let target = system_under_test.add_parallelogram(Point::origin(), Vector::unit_x(), Vector::unit_y(), material);
+        let data_version_before = system_under_test.data_version(DataKind::Parallelogram);
+        let topology_version_before = system_under_test.bvh_topology_version();
+
+        assert!(system_under_test.translate(target, Vector::new(1.0, 2.0, 3.0)));
+
+        let mut expected_serialized = GpuReadySerializationBuffer::new(1, Parallelogram::SERIALIZED_QUARTET_COUNT);
+        Parallelogram::new(Point::new(1.0, 2.0, 3.0), Vector::unit_x(), Vector::unit_y(), Linkage::new(target, material)).serialize_into(&mut expected_serialized);
+        assert_eq!(system_under_test.evaluate_serialized(DataKind::Parallelogram).backend(), expected_serialized.backend());
+        assert_ne!(system_under_test.data_version(DataKind::Parallelogram), data_version_before);
+        assert_eq!(system_under_test.bvh_topology_version(), topology_version_before);
+        assert_eq!(system_under_test.translate(ObjectUid(target.0 + 1), Vector::unit_x()), false);
+    }
+
+    #[test]
+    fn test_translate_mesh() {
+        let mut system_under_test = make_empty_container();
+        let (mesh, meshes) = prepare_test_mesh();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let cube = system_under_test.add_mesh(&meshes, mesh, &Transformation::identity(), material, MeshShading::Flat);
+        let topology_version_before = system_under_test.bvh_topology_version();
+        let offset = Vector::new(0.0, 5.0, 0.0);
+        let mut expected_triangles = system_under_test.triangles.clone();
+        expected_triangles.iter_mut().for_each(|triangle| triangle.translate(offset));
+        let expected_vertices: Vec<_> = system_under_test.mesh_vertices[&cube].1.iter().map(|vertex| vertex.translated(offset)).collect();
+
+        assert!(system_under_test.translate(cube, offset));
+
+        assert_eq!(system_under_test.triangles, expected_triangles);
+        assert_eq!(system_under_test.mesh_vertices[&cube].1, expected_vertices);
+        assert_eq!(*system_under_test.objects[&cube].transformation(), Affine::from_translation(offset));
+        assert_ne!(system_under_test.bvh_topology_version(), topology_version_before);
     }
 
     #[test]
@@ -669,6 +1365,174 @@ mod tests {
         assert_eq!(fixture.container.material_of(fixture.mesh), fixture.dummy_material);
     }
 
+    #[test]
+    fn test_slots_are_kept_after_deletion() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let origin = Point::new(1.0, 2.0, 3.0);
+        let [first, second, third] = [(); 3].map(|_| system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), material));
+
+        system_under_test.delete(second);
+
+        assert_eq!(system_under_test.slot_of(first), Some(0));
+        assert_eq!(system_under_test.slot_of(third), Some(2));
+        assert_eq!(system_under_test.slot_of(second), None);
+        assert_eq!(system_under_test.slots_count_of_a_kind(DataKind::Parallelogram), 3);
+        let serialized = system_under_test.evaluate_serialized(DataKind::Parallelogram);
+        let object_size = Parallelogram::SERIALIZED_QUARTET_COUNT * 16;
+        assert_eq!(serialized.total_slots_count(), 3);
+        assert!(serialized.backend()[object_size..2 * object_size].iter().all(|byte| 0 == *byte));
+
+        let reused = system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), material);
+        assert_eq!(system_under_test.slot_of(reused), Some(1));
+    }
+
+    #[test]
+    fn test_slots_written_since() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let another_material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let origin = Point::new(1.0, 2.0, 3.0);
+        let [_, second, third, _] = [(); 4].map(|_| system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), material));
+        let populated = system_under_test.data_version(DataKind::Parallelogram);
+
+        system_under_test.set_material(third, another_material);
+        system_under_test.delete(second);
+
+        assert_eq!(system_under_test.slots_written_since(DataKind::Parallelogram, populated), Some(1..3));
+        let written = system_under_test.evaluate_serialized_slots(DataKind::Parallelogram, 1..3);
+        let whole = system_under_test.evaluate_serialized(DataKind::Parallelogram);
+        let object_size = Parallelogram::SERIALIZED_QUARTET_COUNT * 16;
+        assert_eq!(written.backend().as_slice(), &whole.backend()[object_size..3 * object_size]);
+
+        let _ = system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), material);
+        assert_eq!(system_under_test.slots_written_since(DataKind::Parallelogram, populated), Some(1..3));
+        let _ = system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), material);
+        assert_eq!(system_under_test.slots_written_since(DataKind::Parallelogram, populated), None);
+    }
+
+    #[test]
+    fn test_emissive_parallelogram_slots() {
+        let mut system_under_test = make_empty_container();
+        let dull = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let glowing = system_under_test.materials_mutable().add(&MaterialProperties::default().with_emission(1.0, 1.0, 1.0));
+        let origin = Point::origin();
+        let lamp_to_delete = system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), glowing);
+        let _ = system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), dull);
+        let _ = system_under_test.add_parallelogram(origin, Vector::unit_x(), Vector::unit_y(), glowing);
+        assert_eq!(system_under_test.emissive_parallelogram_slots(), vec![0, 2]);
+
+        let version_before = system_under_test.emissive_parallelograms_version();
+        system_under_test.delete(lamp_to_delete);
+
+        assert_eq!(system_under_test.emissive_parallelogram_slots(), vec![2]);
+        assert_ne!(system_under_test.emissive_parallelograms_version(), version_before);
+    }
+
+    #[test]
+    fn test_light_sources() {
+        let mut system_under_test = make_empty_container();
+        let dull = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let glowing = system_under_test.materials_mutable().add(&MaterialProperties::default().with_emission(2.0, 2.0, 2.0));
+        let _ = system_under_test.add_parallelogram(Point::origin(), Vector::unit_x(), Vector::unit_y(), dull);
+        let _ = system_under_test.add_parallelogram(Point::new(0.0, 0.0, 1.0), Vector::unit_x() * 3.0, Vector::unit_y(), glowing);
+        let _ = system_under_test.add_parallelogram(Point::origin(), Vector::unit_x(), Vector::unit_x(), glowing);
+
+        let lights = system_under_test.light_sources();
+
+        assert_eq!(lights.len(), 1);
+        assert_eq!(lights[0].slot, 1);
+        assert_abs_diff_eq!(lights[0].power, 6.0, epsilon = 1e-5);
+        assert_eq!(lights[0].bounds, Aabb::from_points(Point::new(0.0, 0.0, 1.0), Point::new(3.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut system_under_test = make_empty_container();
+        let dummy_material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        assert_eq!(system_under_test.bounds(), None);
+
+        let _ = system_under_test.add_parallelogram(Point::new(0.0, 0.0, -1.0), Vector::unit_x(), Vector::unit_y(), dummy_material);
+        let _ = system_under_test.add_parallelogram(Point::new(9.0, 9.0, 9.0), Vector::unit_x(), Vector::unit_x(), dummy_material);
+        let _ = system_under_test.add_sphere(Point::new(3.0, 0.0, 0.0), 0.5, dummy_material);
+
+        let actual_bounds = system_under_test.bounds().expect("scene is not empty");
+        assert_abs_diff_eq!(actual_bounds.min(), Point::new(0.0, -0.5, -1.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(actual_bounds.max(), Point::new(3.5, 1.0, 0.5), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_add_sdf_class() {
+        let mut fixture = make_filled_container();
+        let version_before = fixture.container.sdf_classes_version();
+        let new_class_name = UniqueSdfClassName::new("late_sphere".to_string());
+
+        fixture.container.add_sdf_class(&NamedSdf::new(SdfSphere::new(2.0), new_class_name.clone()));
+        let _ = fixture.container.add_sdf(&Affine::identity(), 1.0, &new_class_name, fixture.dummy_material);
+
+        assert_ne!(fixture.container.sdf_classes_version(), version_before);
+        assert_eq!(fixture.container.count_of_a_kind(DataKind::Sdf), 2);
+        assert!(fixture.container.compose_shader("").contains("late_sphere"));
+    }
+
+    #[test]
+    fn test_replace_instantiated_sdf_class() {
+        let mut fixture = make_filled_container();
+        let sdf_version_before = fixture.container.data_version(DataKind::Sdf);
+
+        fixture.container.replace_sdf_class(&NamedSdf::new(SdfSphere::new(7.0), fixture.sdf_name.clone()));
+
+        assert_ne!(fixture.container.data_version(DataKind::Sdf), sdf_version_before);
+    }
+
+    #[must_use]
+    fn make_container_with_parametric_sphere() -> (VisualObjects, ObjectUid) {
+        let mut system_under_test = make_empty_container();
+        let class_name = UniqueSdfClassName::new("parametric_sphere".to_string());
+        system_under_test.add_sdf_class(&NamedSdf::parametric(class_name.clone(), |parameters| {
+            SdfSphere::new_parametric(parameters.declare("radius", 1.0, 0.5..=2.0))
+        }));
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let sphere = system_under_test.add_sdf(&Affine::identity(), 1.0, &class_name, material);
+        (system_under_test, sphere)
+    }
+
+    #[test]
+    fn test_set_sdf_parameter() {
+        let (mut system_under_test, sphere) = make_container_with_parametric_sphere();
+        assert_eq!(system_under_test.sdf_parameter(sphere, "radius"), 1.0);
+        let sdf_version_before = system_under_test.data_version(DataKind::Sdf);
+
+        system_under_test.set_sdf_parameter(sphere, "radius", 1.5);
+
+        assert_eq!(system_under_test.sdf_parameter(sphere, "radius"), 1.5);
+        assert_ne!(system_under_test.data_version(DataKind::Sdf), sdf_version_before);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_sdf_parameter_out_of_range() {
+        let (mut system_under_test, sphere) = make_container_with_parametric_sphere();
+        system_under_test.set_sdf_parameter(sphere, "radius", 3.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_remove_instantiated_sdf_class() {
+        let mut fixture = make_filled_container();
+        fixture.container.remove_sdf_class(&fixture.sdf_name);
+    }
+
+    #[test]
+    fn test_remove_sdf_class() {
+        let mut fixture = make_filled_container();
+        fixture.container.delete(fixture.sdf);
+
+        fixture.container.remove_sdf_class(&fixture.sdf_name);
+
+        assert!(false == fixture.container.compose_shader("").contains(&fixture.sdf_name.to_string()));
+    }
+
     #[test]
     fn test_clean() {
         let mut fixture = make_filled_container();
@@ -693,6 +1557,156 @@ mod tests {
         assert_eq!(version_before, version_after);
     }
 
+    #[test]
+    fn test_hidden_object_keeps_its_slot() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let hidden = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, material);
+        let layered = system_under_test.add_sphere(Point::new(3.0, 0.0, 0.0), 1.0, material);
+        let spheres_version = system_under_test.data_version(DataKind::Sphere);
+        let layers_version = system_under_test.object_layers_version();
+
+        system_under_test.set_visible(hidden, false);
+        system_under_test.set_layer_mask(layered, LayerMask(0b100));
+
+        assert!(false == system_under_test.visible(hidden));
+        assert_eq!(system_under_test.layer_mask(layered), LayerMask(0b100));
+        assert_eq!(system_under_test.slot_of(hidden), Some(0));
+        assert_eq!(system_under_test.data_version(DataKind::Sphere), spheres_version);
+        assert_ne!(system_under_test.object_layers_version(), layers_version);
+        assert_eq!(system_under_test.serialize_object_layers(), vec![0, 0, 0b100]);
+    }
+
+    #[test]
+    fn test_deleted_object_layers_are_reset() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let victim = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, material);
+        system_under_test.set_visible(victim, false);
+
+        system_under_test.delete(victim);
+        let reused = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, material);
+
+        assert_eq!(reused, victim);
+        assert!(system_under_test.visible(reused));
+        assert_eq!(system_under_test.layer_mask(reused), LayerMask::DEFAULT_OBJECT_LAYER);
+    }
+
+    #[test]
+    fn test_deleted_object_uv_transform_is_reset() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let victim = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, material);
+        let version = system_under_test.object_uv_transforms_version();
+        system_under_test.set_uv_transform(victim, UvTransform::new().with_offset(0.5, 0.0));
+        assert_ne!(system_under_test.object_uv_transforms_version(), version);
+
+        system_under_test.delete(victim);
+        let reused = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, material);
+
+        assert_eq!(reused, victim);
+        assert_eq!(system_under_test.uv_transform(reused), UvTransform::IDENTITY);
+    }
+
+    #[test]
+    fn test_deleted_object_opacity_is_reset() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let victim = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, material);
+        system_under_test.set_opacity(victim, 0.3);
+        assert_eq!(system_under_test.serialize_object_opacities()[victim.0 as usize], 0.3);
+
+        system_under_test.delete(victim);
+        let reused = system_under_test.add_sphere(Point::new(0.0, 0.0, 0.0), 1.0, material);
+
+        assert_eq!(reused, victim);
+        assert_eq!(system_under_test.opacity(reused), 1.0);
+    }
+
+    #[test]
+    fn test_cast_ray_closest_traced() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let sphere = system_under_test.add_sphere(Point::new(0.0, 0.0, 5.0), 1.0, material);
+        let parallelogram = system_under_test.add_parallelogram(Point::new(-1.0, -1.0, 2.0), Vector::unit_x() * 2.0, Vector::unit_y() * 2.0, material);
+        let ray = Ray::new(Point::origin(), Vector::unit_z());
+
+        let closest = system_under_test.cast_ray(&ray, LayerMask::ALL).unwrap();
+        assert_eq!(closest.object(), parallelogram);
+        assert_eq!(closest.distance(), 2.0);
+        assert_eq!(closest.position(), Point::new(0.0, 0.0, 2.0));
+        assert_eq!(closest.normal(), -Vector::unit_z());
+        assert!(closest.exact());
+
+        system_under_test.set_visible(parallelogram, false);
+        let behind = system_under_test.cast_ray(&ray, LayerMask::ALL).unwrap();
+        assert_eq!(behind.object(), sphere);
+        assert_eq!(behind.distance(), 4.0);
+
+        system_under_test.set_layer_mask(sphere, LayerMask::layer(1));
+        assert_eq!(system_under_test.cast_ray(&ray, LayerMask::DEFAULT_OBJECT_LAYER), None);
+        assert_eq!(system_under_test.cast_ray(&Ray::new(Point::origin(), -Vector::unit_z()), LayerMask::ALL), None);
+    }
+
+    #[test]
+    fn test_cast_ray_at_sdf_box() {
+        let (class_name, sdf_classes) = make_single_sdf_sphere();
+        let mut system_under_test = VisualObjects::new(None, Some(sdf_classes), None, None, None);
+        let location = Affine::from_translation(Vector::new(0.0, 0.0, 5.0)) * Affine::from_scale(2.0);
+        let sdf = system_under_test.add_sdf(&location, 1.0, &class_name, MaterialIndex(0));
+        let ray = Ray::new(Point::new(0.5, 0.5, 0.0), Vector::unit_z());
+
+        let hit = system_under_test.cast_ray(&ray, LayerMask::ALL).unwrap();
+        assert_eq!(hit.object(), sdf);
+        assert_eq!(hit.distance(), 3.0);
+        assert_eq!(hit.normal(), -Vector::unit_z());
+        assert!(false == hit.exact());
+
+        system_under_test.replace_sdf_class(&NamedSdf::new(SdfSphere::new(2.0), class_name));
+        let refitted = system_under_test.cast_ray(&ray, LayerMask::ALL).unwrap();
+        assert_eq!(refitted.distance(), 1.0);
+    }
+
+    #[test]
+    fn test_cast_ray_at_mesh() {
+        let (meshes, slot) = make_test_mesh();
+        let mut system_under_test = make_empty_container();
+        let placement = Transformation::new(Affine::from_translation(Vector::new(0.0, 0.0, 10.0)));
+        let mesh = system_under_test.add_mesh(&meshes, slot, &placement, MaterialIndex(0), MeshShading::Smooth);
+
+        let hit = system_under_test.cast_ray(&Ray::new(Point::new(0.1, 0.1, 0.0), Vector::unit_z()), LayerMask::ALL).unwrap();
+        assert_eq!(hit.object(), mesh);
+        assert!(hit.distance() < 10.0);
+        assert_abs_diff_eq!(hit.normal(), -Vector::unit_z(), epsilon = DEFAULT_EPSILON_F64);
+        assert!(hit.exact());
+
+        system_under_test.delete(mesh);
+        assert_eq!(system_under_test.cast_ray(&Ray::new(Point::new(0.1, 0.1, 0.0), Vector::unit_z()), LayerMask::ALL), None);
+    }
+
+    #[test]
+    fn test_add_sphere() {
+        let mut system_under_test = make_empty_container();
+        let material = system_under_test.materials_mutable().add(&MaterialProperties::default());
+        let center = Point::new(1.0, 2.0, 3.0);
+        let topology_version_before = system_under_test.bvh_topology_version();
+
+        let uid = system_under_test.add_sphere(center, 0.5, material);
+
+        assert_eq!(system_under_test.count_of_a_kind(DataKind::Sphere), 1);
+        assert_ne!(system_under_test.bvh_topology_version(), topology_version_before);
+        assert!(system_under_test.bvh_inhabited());
+
+        let mut expected_serialized = GpuReadySerializationBuffer::new(1, Sphere::SERIALIZED_QUARTET_COUNT);
+        Sphere::new(center, 0.5, Linkage::new(uid, material)).serialize_into(&mut expected_serialized);
+        assert_eq!(system_under_test.evaluate_serialized(DataKind::Sphere).backend(), expected_serialized.backend());
+
+        let support = system_under_test.make_bvh_support(0.0);
+        assert_eq!(support.len(), 1);
+        assert_eq!(support[0].primitive_type(), PrimitiveType::Sphere);
+        assert_eq!(support[0].aabb(), Aabb::from_points(Point::new(0.5, 1.5, 2.5), Point::new(1.5, 2.5, 3.5)));
+    }
+
     #[test]
     fn test_bvh_inhabited() {
         let mut fixture = make_filled_container();
@@ -727,14 +1741,14 @@ mod tests {
     #[must_use]
     fn make_filled_container() -> FilledContainerFixture {
         let (sdf_name, sdf_classes) = make_single_sdf_sphere();
-        let mut container = VisualObjects::new(None, Some(sdf_classes), None);
+        let mut container = VisualObjects::new(None, Some(sdf_classes), None, None, None);
 
         let dummy_material = container.materials_mutable().add(&MaterialProperties::default());
         let (mesh_id, meshes) = prepare_test_mesh();
 
         let sdf = container.add_sdf(&Affine::identity(), 1.0, &sdf_name, dummy_material);
         let parallelogram = container.add_parallelogram(Point::origin(), Vector::unit_x(), Vector::unit_y(), dummy_material);
-        let mesh = container.add_mesh(&meshes, mesh_id, &Transformation::identity(), dummy_material);
+        let mesh = container.add_mesh(&meshes, mesh_id, &Transformation::identity(), dummy_material, MeshShading::Smooth);
 
         FilledContainerFixture { container, dummy_material, sdf, sdf_name, parallelogram, mesh, }
     }
@@ -0,0 +1,36 @@
+use crate::denoiser::filter::Quality;
+
+// the denoising of the Monte Carlo renders, for the machines of different speed served by the same build
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub enum DenoiseMode {
+    // the accumulated image is shown as is, the denoiser does not run
+    Off,
+    // lower precision filter, for the real time use on slower machines
+    Fast,
+    #[default]
+    High,
+}
+
+impl DenoiseMode {
+    // none when the denoiser is bypassed
+    #[must_use]
+    pub(super) fn quality(&self) -> Option<Quality> {
+        match self {
+            DenoiseMode::Off => None,
+            DenoiseMode::Fast => Some(Quality::Fast),
+            DenoiseMode::High => Some(Quality::High),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality() {
+        assert_eq!(DenoiseMode::default().quality(), Some(Quality::High));
+        assert_eq!(DenoiseMode::Fast.quality(), Some(Quality::Fast));
+        assert_eq!(DenoiseMode::Off.quality(), None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::denoiser::device_type::DenoiserDeviceType;
 use crate::denoiser::error::Error;
 use crate::denoiser::sys::OIDNDevice;
 use crate::denoiser::sys::*;
@@ -36,6 +37,22 @@ impl Device {
         Self::create(OIDNDeviceType_OIDN_DEVICE_TYPE_DEFAULT)
     }
 
+    /// Create a device of the requested type, returns None if the type is not
+    /// supported by the current hardware or the installed runtime
+    #[must_use]
+    pub(super) fn new_of_type(device_type: DenoiserDeviceType) -> Option<Self> {
+        let handle = get_handle(device_type.as_raw_oidn_device_type());
+        if handle.is_null() {
+            return None;
+        }
+        unsafe {
+            oidnCommitDevice(handle);
+        }
+        let result = Self(handle, Arc::new(0));
+        result.get_error().ok()?;
+        Some(result)
+    }
+
     #[must_use]
     fn create(device_type: OIDNDeviceType) -> Self {
         let handle = get_handle(device_type);
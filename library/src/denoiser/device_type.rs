@@ -0,0 +1,46 @@
+use crate::denoiser::sys::*;
+
+// GPU backends run the filter in the video memory of the respective vendor API
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub enum DenoiserDeviceType {
+    #[default]
+    Fastest,
+    Cpu,
+    Sycl,
+    Cuda,
+    Hip,
+    Metal,
+}
+
+impl DenoiserDeviceType {
+    #[must_use]
+    pub(super) fn as_raw_oidn_device_type(&self) -> OIDNDeviceType {
+        match self {
+            DenoiserDeviceType::Fastest => OIDNDeviceType_OIDN_DEVICE_TYPE_DEFAULT,
+            DenoiserDeviceType::Cpu => OIDNDeviceType_OIDN_DEVICE_TYPE_CPU,
+            DenoiserDeviceType::Sycl => OIDNDeviceType_OIDN_DEVICE_TYPE_SYCL,
+            DenoiserDeviceType::Cuda => OIDNDeviceType_OIDN_DEVICE_TYPE_CUDA,
+            DenoiserDeviceType::Hip => OIDNDeviceType_OIDN_DEVICE_TYPE_HIP,
+            DenoiserDeviceType::Metal => OIDNDeviceType_OIDN_DEVICE_TYPE_METAL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_fastest() {
+        assert_eq!(DenoiserDeviceType::default(), DenoiserDeviceType::Fastest);
+        assert_eq!(DenoiserDeviceType::default().as_raw_oidn_device_type(), OIDNDeviceType_OIDN_DEVICE_TYPE_DEFAULT);
+    }
+
+    #[test]
+    fn test_gpu_types_mapping() {
+        assert_eq!(DenoiserDeviceType::Sycl.as_raw_oidn_device_type(), OIDNDeviceType_OIDN_DEVICE_TYPE_SYCL);
+        assert_eq!(DenoiserDeviceType::Cuda.as_raw_oidn_device_type(), OIDNDeviceType_OIDN_DEVICE_TYPE_CUDA);
+        assert_eq!(DenoiserDeviceType::Hip.as_raw_oidn_device_type(), OIDNDeviceType_OIDN_DEVICE_TYPE_HIP);
+        assert_eq!(DenoiserDeviceType::Metal.as_raw_oidn_device_type(), OIDNDeviceType_OIDN_DEVICE_TYPE_METAL);
+    }
+}
\ No newline at end of file
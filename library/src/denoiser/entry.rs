@@ -7,8 +7,10 @@
 use crate::denoiser::filter::Quality;
 use crate::denoiser::buffer::Buffer;
 use crate::denoiser::device::Device;
+use crate::denoiser::denoise_mode::DenoiseMode;
+use crate::denoiser::device_type::DenoiserDeviceType;
 use crate::denoiser::filter::RayTracing;
-use log::error;
+use log::{error, warn};
 use std::rc::Rc;
 
 const CHANNELS_PER_PIXEL: usize = 4;
@@ -114,8 +116,12 @@ pub(crate) struct Denoiser {
 impl Denoiser {
     
     #[must_use]
-    pub(crate) fn new() -> Self {
-        let device = Rc::new(Device::new());
+    pub(crate) fn new(device_type: DenoiserDeviceType, mode: DenoiseMode) -> Self {
+        let device = Device::new_of_type(device_type).unwrap_or_else(|| {
+            warn!("denoiser device {device_type:?} is not available, falling back to the fastest one");
+            Device::new()
+        });
+        let device = Rc::new(device);
         let filter = RayTracing::new(device.clone(), CHANNELS_PER_PIXEL);
                 
         let mut result = Self { 
@@ -127,7 +133,8 @@ impl Denoiser {
         result.filter
             .clean_aux(true)
             .hdr(true)
-            .filter_quality(Quality::High);
+            // a bypassed denoiser is not run, the quality does not matter then
+            .filter_quality(mode.quality().unwrap_or(Quality::High));
 
         result
     }
@@ -247,6 +254,11 @@ mod tests {
 
     #[test]
     fn test_denoiser_construction() {
-        let _ = Denoiser::new();
+        let _ = Denoiser::new(DenoiserDeviceType::default(), DenoiseMode::default());
+    }
+
+    #[test]
+    fn test_denoiser_construction_with_cpu_device() {
+        let _ = Denoiser::new(DenoiserDeviceType::Cpu, DenoiseMode::Fast);
     }
 }
\ No newline at end of file
@@ -1,4 +1,8 @@
 pub(crate) mod entry;
+pub mod device_type;
+pub mod denoise_mode;
+pub mod worker;
+pub(crate) mod schedule;
 mod buffer;
 mod device;
 mod error;
@@ -0,0 +1,88 @@
+// the denoiser runs every 'interval' presented frames and whenever the accumulation restarts; the
+// frames in between show the last denoised image blended with the samples accumulated since
+pub(crate) struct DenoisingSchedule {
+    frames_since_denoising: u32,
+    // the accumulation restarts count the last denoising was of
+    denoised_accumulation: Option<u64>,
+    // of the image in the denoised buffer
+    denoised_frame_number: Option<u32>,
+}
+
+impl DenoisingSchedule {
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self { frames_since_denoising: 0, denoised_accumulation: None, denoised_frame_number: None }
+    }
+
+    // to be called once a presented frame; true when the frame is to be denoised
+    #[must_use]
+    pub(crate) fn begin_frame(&mut self, interval: u32, accumulation_restarts: u64) -> bool {
+        assert!(interval > 0, "denoising interval is expected to be at least one frame");
+        let due = self.denoised_accumulation != Some(accumulation_restarts) || self.frames_since_denoising + 1 >= interval;
+        if due {
+            self.frames_since_denoising = 0;
+            self.denoised_accumulation = Some(accumulation_restarts);
+        } else {
+            self.frames_since_denoising += 1;
+        }
+        due
+    }
+
+    // the denoised image of the frame has reached the denoised buffer
+    pub(crate) fn denoised(&mut self, frame_number: u32) {
+        self.denoised_frame_number = Some(frame_number);
+    }
+
+    #[must_use]
+    pub(crate) fn denoised_frame_number(&self) -> Option<u32> {
+        self.denoised_frame_number
+    }
+
+    // the denoised buffer has been replaced
+    pub(crate) fn forget(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_frame_with_unit_interval() {
+        let mut system_under_test = DenoisingSchedule::new();
+
+        assert!((0..5).all(|_| system_under_test.begin_frame(1, 0)));
+    }
+
+    #[test]
+    fn test_every_nth_frame() {
+        let mut system_under_test = DenoisingSchedule::new();
+
+        let denoised: Vec<bool> = (0..7).map(|_| system_under_test.begin_frame(3, 0)).collect();
+
+        assert_eq!(denoised, vec![true, false, false, true, false, false, true]);
+    }
+
+    #[test]
+    fn test_accumulation_restart_is_denoised_at_once() {
+        let mut system_under_test = DenoisingSchedule::new();
+        assert!(system_under_test.begin_frame(4, 0));
+        assert!(false == system_under_test.begin_frame(4, 0));
+
+        assert!(system_under_test.begin_frame(4, 1));
+        assert!(false == system_under_test.begin_frame(4, 1));
+    }
+
+    #[test]
+    fn test_forget() {
+        let mut system_under_test = DenoisingSchedule::new();
+        assert!(system_under_test.begin_frame(4, 0));
+        system_under_test.denoised(8);
+
+        system_under_test.forget();
+
+        assert_eq!(system_under_test.denoised_frame_number(), None);
+        assert!(system_under_test.begin_frame(4, 0));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::denoiser::denoise_mode::DenoiseMode;
+use crate::denoiser::device_type::DenoiserDeviceType;
+use crate::denoiser::entry::Denoiser;
+use crate::serialization::pod_vector::PodVector;
+use crate::utils::bounded_queue::{BoundedQueue, OverflowPolicy};
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// the noisy frames waiting for the denoiser thread: when the cpu is slower than the gpu, the
+// accumulation goes on and the frames above the capacity are dropped according to the policy
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct DenoiserQueue {
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl DenoiserQueue {
+    #[must_use]
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        assert!(capacity > 0);
+        Self { capacity, policy }
+    }
+}
+
+impl Default for DenoiserQueue {
+    // the denoiser always takes the latest complete frame
+    fn default() -> Self {
+        Self::new(1, OverflowPolicy::DropOldest)
+    }
+}
+
+pub(crate) struct NoisyFrame {
+    width: usize,
+    height: usize,
+    frame_number: u32,
+    beauty: Vec<PodVector>,
+    albedo: Vec<PodVector>,
+    normal: Vec<PodVector>,
+}
+
+impl NoisyFrame {
+    #[must_use]
+    pub(crate) fn new(width: usize, height: usize, frame_number: u32, beauty: Vec<PodVector>, albedo: Vec<PodVector>, normal: Vec<PodVector>) -> Self {
+        assert!(width > 0);
+        assert!(height > 0);
+        Self { width, height, frame_number, beauty, albedo, normal }
+    }
+}
+
+pub(crate) struct DenoisedFrame {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    // the accumulated samples the beauty is of
+    pub(crate) frame_number: u32,
+    pub(crate) beauty: Vec<PodVector>,
+    pub(crate) denoising_time: Duration,
+}
+
+// owns the denoiser on a thread of its own: the oidn device is not to be shared between threads
+pub(crate) struct DenoiserWorker {
+    settings: DenoiserQueue,
+    noisy_frames: Arc<BoundedQueue<NoisyFrame>>,
+    denoised_frames: Receiver<DenoisedFrame>,
+    thread: Option<JoinHandle<()>>,
+    dropped_frames_count: u64,
+}
+
+impl DenoiserWorker {
+    #[must_use]
+    pub(crate) fn new(device_type: DenoiserDeviceType, mode: DenoiseMode, settings: DenoiserQueue) -> Self {
+        let noisy_frames = Arc::new(BoundedQueue::new(settings.capacity, settings.policy));
+        let (sender, denoised_frames) = channel();
+
+        let worker_queue = noisy_frames.clone();
+        let thread = std::thread::Builder::new()
+            .name("denoiser".to_string())
+            .spawn(move || Self::run(device_type, mode, &worker_queue, &sender))
+            .expect("failed to spawn the denoiser thread");
+
+        Self { settings, noisy_frames, denoised_frames, thread: Some(thread), dropped_frames_count: 0 }
+    }
+
+    fn run(device_type: DenoiserDeviceType, mode: DenoiseMode, noisy_frames: &BoundedQueue<NoisyFrame>, denoised_frames: &Sender<DenoisedFrame>) {
+        let mut denoiser = Denoiser::new(device_type, mode);
+        while let Some(mut frame) = noisy_frames.pop() {
+            let start = Instant::now();
+            {
+                let beauty_floats: &mut [f32] = bytemuck::cast_slice_mut(&mut frame.beauty);
+                let mut executor = denoiser.begin_denoise(frame.width, frame.height);
+                executor.issue_albedo_write(bytemuck::cast_slice(&frame.albedo));
+                executor.issue_normal_write(bytemuck::cast_slice(&frame.normal));
+                executor.issue_noisy_beauty_write(beauty_floats);
+                executor.filter(beauty_floats);
+            }
+            let denoised = DenoisedFrame { width: frame.width, height: frame.height, frame_number: frame.frame_number, beauty: frame.beauty, denoising_time: start.elapsed() };
+            if denoised_frames.send(denoised).is_err() {
+                return;
+            }
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn settings(&self) -> DenoiserQueue {
+        self.settings
+    }
+
+    // never blocks: a busy denoiser thread makes the queue drop a frame
+    pub(crate) fn submit(&mut self, frame: NoisyFrame) {
+        if self.noisy_frames.push(frame).is_some() {
+            self.dropped_frames_count += 1;
+        }
+    }
+
+    // the frames denoised since the previous call, but the latest one, are skipped
+    #[must_use]
+    pub(crate) fn take_latest_denoised(&self) -> Option<DenoisedFrame> {
+        self.denoised_frames.try_iter().last()
+    }
+
+    #[must_use]
+    pub(crate) fn dropped_frames_count(&self) -> u64 {
+        self.dropped_frames_count
+    }
+}
+
+impl Drop for DenoiserWorker {
+    fn drop(&mut self) {
+        self.noisy_frames.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,172 @@
+// the 'mint' feature makes the aliases convertible with 'From' and 'Into' (cgmath implements them);
+// glam and nalgebra know nothing about cgmath and the orphan rule forbids 'From' between two foreign
+// crates, so their types get the 'GeometryConversion' trait instead
+
+#[cfg(any(feature = "glam", feature = "nalgebra"))]
+pub trait GeometryConversion<Geometry>: Sized {
+    #[must_use]
+    fn from_geometry(source: Geometry) -> Self;
+    #[must_use]
+    fn into_geometry(self) -> Geometry;
+}
+
+#[cfg(feature = "glam")]
+mod glam_conversion {
+    use super::GeometryConversion;
+    use crate::geometry::alias::{Point, Vector};
+    use crate::geometry::transform::Affine;
+
+    impl GeometryConversion<Point> for glam::DVec3 {
+        fn from_geometry(source: Point) -> Self {
+            glam::DVec3::new(source.x, source.y, source.z)
+        }
+        fn into_geometry(self) -> Point {
+            Point::new(self.x, self.y, self.z)
+        }
+    }
+
+    impl GeometryConversion<Vector> for glam::DVec3 {
+        fn from_geometry(source: Vector) -> Self {
+            glam::DVec3::new(source.x, source.y, source.z)
+        }
+        fn into_geometry(self) -> Vector {
+            Vector::new(self.x, self.y, self.z)
+        }
+    }
+
+    // the single precision types lose the digits beyond f32
+    impl GeometryConversion<Point> for glam::Vec3 {
+        fn from_geometry(source: Point) -> Self {
+            glam::DVec3::from_geometry(source).as_vec3()
+        }
+        fn into_geometry(self) -> Point {
+            self.as_dvec3().into_geometry()
+        }
+    }
+
+    impl GeometryConversion<Vector> for glam::Vec3 {
+        fn from_geometry(source: Vector) -> Self {
+            glam::DVec3::from_geometry(source).as_vec3()
+        }
+        fn into_geometry(self) -> Vector {
+            self.as_dvec3().into_geometry()
+        }
+    }
+
+    // both keep the columns
+    impl GeometryConversion<Affine> for glam::DMat4 {
+        fn from_geometry(source: Affine) -> Self {
+            glam::DMat4::from_cols_array_2d(source.as_ref())
+        }
+        fn into_geometry(self) -> Affine {
+            Affine::from(self.to_cols_array_2d())
+        }
+    }
+
+    impl GeometryConversion<Affine> for glam::Mat4 {
+        fn from_geometry(source: Affine) -> Self {
+            glam::DMat4::from_geometry(source).as_mat4()
+        }
+        fn into_geometry(self) -> Affine {
+            self.as_dmat4().into_geometry()
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_conversion {
+    use super::GeometryConversion;
+    use crate::geometry::alias::{Point, Vector};
+    use crate::geometry::transform::Affine;
+
+    impl GeometryConversion<Point> for nalgebra::Point3<f64> {
+        fn from_geometry(source: Point) -> Self {
+            nalgebra::Point3::new(source.x, source.y, source.z)
+        }
+        fn into_geometry(self) -> Point {
+            Point::new(self.x, self.y, self.z)
+        }
+    }
+
+    impl GeometryConversion<Vector> for nalgebra::Vector3<f64> {
+        fn from_geometry(source: Vector) -> Self {
+            nalgebra::Vector3::new(source.x, source.y, source.z)
+        }
+        fn into_geometry(self) -> Vector {
+            Vector::new(self.x, self.y, self.z)
+        }
+    }
+
+    // both keep the columns
+    impl GeometryConversion<Affine> for nalgebra::Matrix4<f64> {
+        fn from_geometry(source: Affine) -> Self {
+            let columns: [[f64; 4]; 4] = source.into();
+            nalgebra::Matrix4::from(columns)
+        }
+        fn into_geometry(self) -> Affine {
+            Affine::from(<[[f64; 4]; 4]>::from(self))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "glam", feature = "nalgebra"))]
+    use super::*;
+    use crate::geometry::alias::{Point, Vector};
+    use crate::geometry::transform::Affine;
+    #[cfg(any(feature = "glam", feature = "nalgebra"))]
+    use cgmath::Transform;
+
+    #[must_use]
+    fn make_transform() -> Affine {
+        Affine::from_translation(Vector::new(1.0, 2.0, 3.0)) * Affine::from_nonuniform_scale(4.0, 5.0, 6.0)
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_glam_round_trip() {
+        let point = Point::new(1.0, -2.0, 3.5);
+        let vector = Vector::new(-4.0, 5.0, 0.25);
+        let transform = make_transform();
+
+        let glam_point = glam::DVec3::from_geometry(point);
+        let glam_transform = glam::DMat4::from_geometry(transform);
+
+        let transformed_point: Point = glam_transform.transform_point3(glam_point).into_geometry();
+        let round_trip_vector: Vector = glam::Vec3::from_geometry(vector).into_geometry();
+        assert_eq!(glam_point, glam::DVec3::new(1.0, -2.0, 3.5));
+        assert_eq!(transformed_point, transform.transform_point(point));
+        assert_eq!(round_trip_vector, vector);
+        assert_eq!(glam::Mat4::from_geometry(transform).into_geometry(), transform);
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_nalgebra_round_trip() {
+        let point = Point::new(1.0, -2.0, 3.5);
+        let vector = Vector::new(-4.0, 5.0, 0.25);
+        let transform = make_transform();
+
+        let nalgebra_point = nalgebra::Point3::from_geometry(point);
+        let nalgebra_transform = nalgebra::Matrix4::from_geometry(transform);
+
+        assert_eq!(nalgebra_transform.transform_point(&nalgebra_point).into_geometry(), transform.transform_point(point));
+        assert_eq!(nalgebra::Vector3::from_geometry(vector).into_geometry(), vector);
+        assert_eq!(nalgebra_transform.into_geometry(), transform);
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn test_mint_round_trip() {
+        let point = Point::new(1.0, -2.0, 3.5);
+        let transform = make_transform();
+
+        let mint_point: mint::Point3<f64> = point.into();
+        let mint_transform: mint::ColumnMatrix4<f64> = transform.into();
+
+        assert_eq!(mint_point, mint::Point3 { x: 1.0, y: -2.0, z: 3.5 });
+        assert_eq!(Point::from(mint_point), point);
+        assert_eq!(Affine::from(mint_transform), transform);
+    }
+}
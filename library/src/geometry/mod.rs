@@ -2,8 +2,11 @@ pub mod alias;
 pub mod axis;
 pub mod transform;
 pub mod aabb;
+#[cfg(any(feature = "glam", feature = "nalgebra", feature = "mint"))]
+pub mod conversion;
 pub(crate) mod epsilon;
 pub(crate) mod fundamental_constants;
 pub(crate) mod utils;
 pub(crate) mod vertex;
-pub(crate) mod cylinder;
\ No newline at end of file
+pub(crate) mod cylinder;
+pub(crate) mod ray;
\ No newline at end of file
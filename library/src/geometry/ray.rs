@@ -0,0 +1,171 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::{Point, Vector};
+use crate::geometry::axis::Axis;
+use crate::geometry::transform::Affine;
+use cgmath::{InnerSpace, Transform};
+use strum::EnumCount;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct Ray {
+    origin: Point,
+    direction: Vector,
+}
+
+impl Ray {
+    // the direction gets normalized: the parameter of a point along the ray is the distance to it
+    #[must_use]
+    pub(crate) fn new(origin: Point, direction: Vector) -> Self {
+        assert!(direction.magnitude2() > 0.0, "ray direction is zero");
+        Self { origin, direction: direction.normalize() }
+    }
+
+    #[must_use]
+    pub(crate) fn origin(&self) -> Point {
+        self.origin
+    }
+
+    #[must_use]
+    pub(crate) fn direction(&self) -> Vector {
+        self.direction
+    }
+
+    #[must_use]
+    pub(crate) fn at(&self, parameter: f64) -> Point {
+        self.origin + self.direction * parameter
+    }
+
+    // the direction is not normalized afterward, so a point has the same parameter along both rays
+    #[must_use]
+    pub(crate) fn transform(&self, transformation: &Affine) -> Self {
+        Self { origin: transformation.transform_point(self.origin), direction: transformation.transform_vector(self.direction) }
+    }
+}
+
+// the normal is the geometric one, it faces the origin of the ray
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct RayIntersection {
+    distance: f64,
+    normal: Vector,
+}
+
+impl RayIntersection {
+    #[must_use]
+    pub(crate) fn new(distance: f64, normal: Vector) -> Self {
+        Self { distance, normal }
+    }
+
+    // the normal gets normalized and turned against the ray
+    #[must_use]
+    pub(crate) fn facing(ray: &Ray, distance: f64, normal: Vector) -> Self {
+        let normal = normal.normalize();
+        Self { distance, normal: if normal.dot(ray.direction()) > 0.0 { -normal } else { normal } }
+    }
+
+    #[must_use]
+    pub(crate) fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    #[must_use]
+    pub(crate) fn normal(&self) -> Vector {
+        self.normal
+    }
+}
+
+// the slab test; none for a box behind the ray or around its origin: the ray has to enter the box
+#[must_use]
+pub(crate) fn intersect_aabb(ray: &Ray, target: &Aabb) -> Option<RayIntersection> {
+    let mut entry = f64::MIN;
+    let mut exit = f64::MAX;
+    let mut entry_axis = 0;
+    for axis in 0..Axis::COUNT {
+        let inverse_direction = 1.0 / ray.direction()[axis];
+        let near = (target.min()[axis] - ray.origin()[axis]) * inverse_direction;
+        let far = (target.max()[axis] - ray.origin()[axis]) * inverse_direction;
+        let (near, far) = if inverse_direction < 0.0 { (far, near) } else { (near, far) };
+        if near > entry {
+            entry = near;
+            entry_axis = axis;
+        }
+        exit = exit.min(far);
+    }
+    if entry > exit || entry < 0.0 {
+        return None;
+    }
+    let mut normal = Vector::new(0.0, 0.0, 0.0);
+    normal[entry_axis] = -ray.direction()[entry_axis].signum();
+    Some(RayIntersection::new(entry, normal))
+}
+
+// whether the ray goes through the box closer than the 'distance'; the origin may be inside
+#[must_use]
+pub(crate) fn ray_meets_aabb(ray: &Ray, target: &Aabb, distance: f64) -> bool {
+    let mut entry = 0.0_f64;
+    let mut exit = distance;
+    for axis in 0..Axis::COUNT {
+        let inverse_direction = 1.0 / ray.direction()[axis];
+        let near = (target.min()[axis] - ray.origin()[axis]) * inverse_direction;
+        let far = (target.max()[axis] - ray.origin()[axis]) * inverse_direction;
+        // not a min/max of the two: a null box (min above max) has to stay empty
+        let (near, far) = if inverse_direction < 0.0 { (far, near) } else { (near, far) };
+        entry = entry.max(near);
+        exit = exit.min(far);
+    }
+    entry <= exit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::epsilon::DEFAULT_EPSILON_F64;
+    use cgmath::assert_abs_diff_eq;
+
+    #[test]
+    fn test_direction_is_normalized() {
+        let system_under_test = Ray::new(Point::new(1.0, 0.0, 0.0), Vector::new(0.0, 3.0, 4.0));
+
+        assert_abs_diff_eq!(system_under_test.direction(), Vector::new(0.0, 0.6, 0.8), epsilon = DEFAULT_EPSILON_F64);
+        assert_abs_diff_eq!(system_under_test.at(5.0), Point::new(1.0, 3.0, 4.0), epsilon = DEFAULT_EPSILON_F64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_direction() {
+        let _ = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_transform_keeps_parameter() {
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::unit_z());
+        let transformation = Affine::from_translation(Vector::new(1.0, 0.0, 0.0)) * Affine::from_scale(2.0);
+
+        let system_under_test = ray.transform(&transformation);
+
+        assert_abs_diff_eq!(system_under_test.at(3.0), transformation.transform_point(ray.at(3.0)), epsilon = DEFAULT_EPSILON_F64);
+    }
+
+    #[test]
+    fn test_intersect_aabb() {
+        let target = Aabb::from_points(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+
+        let hit = intersect_aabb(&Ray::new(Point::new(0.5, 0.0, -3.0), Vector::unit_z()), &target).unwrap();
+        assert_abs_diff_eq!(hit.distance(), 2.0);
+        assert_abs_diff_eq!(hit.normal(), -Vector::unit_z());
+
+        assert_eq!(intersect_aabb(&Ray::new(Point::new(0.0, 0.0, -3.0), -Vector::unit_z()), &target), None);
+        assert_eq!(intersect_aabb(&Ray::new(Point::new(0.0, 0.0, 0.0), Vector::unit_x()), &target), None);
+        assert_eq!(intersect_aabb(&Ray::new(Point::new(0.0, 3.0, -3.0), Vector::unit_z()), &target), None);
+        assert_eq!(intersect_aabb(&Ray::new(Point::new(0.0, 0.0, -3.0), Vector::unit_z()), &Aabb::make_null()), None);
+    }
+
+    #[test]
+    fn test_ray_meets_aabb() {
+        let target = Aabb::from_points(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+        let ray = Ray::new(Point::new(0.0, 0.0, -3.0), Vector::unit_z());
+
+        assert!(ray_meets_aabb(&ray, &target, 2.5));
+        assert!(false == ray_meets_aabb(&ray, &target, 1.5));
+        assert!(ray_meets_aabb(&Ray::new(Point::new(0.0, 0.0, 0.0), Vector::unit_x()), &target, 0.1));
+        assert!(false == ray_meets_aabb(&ray, &Aabb::make_null(), f64::MAX));
+    }
+}
@@ -35,6 +35,11 @@ impl Vertex {
     pub(crate) fn normal(&self) -> Vector {
         self.normal
     }
+
+    #[must_use]
+    pub(crate) fn translated(&self, offset: Vector) -> Vertex {
+        Vertex { position: self.position + offset, normal: self.normal }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,195 @@
+use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::serialization::pod_vector::PodVector;
+use anyhow::bail;
+use more_asserts::assert_gt;
+use bytemuck::{Pod, Zeroable};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// the sums of the samples accumulated by the pixels and the count of the passes that have made them:
+// a long offline render saves it to stop and resumes from it in a renderer of the same scene,
+// camera and frame buffer size
+pub(crate) struct AccumulationCheckpoint {
+    frame_buffer_size: FrameBufferSize,
+    frame_number: u32,
+    pixel_color: Vec<PodVector>,
+}
+
+impl AccumulationCheckpoint {
+    #[must_use]
+    pub(crate) fn new(frame_buffer_size: FrameBufferSize, frame_number: u32, pixel_color: Vec<PodVector>) -> Self {
+        assert_eq!(pixel_color.len(), frame_buffer_size.area() as usize, "pixels count does not match frame buffer size");
+        Self { frame_buffer_size, frame_number, pixel_color }
+    }
+
+    #[must_use]
+    pub(crate) fn frame_buffer_size(&self) -> FrameBufferSize {
+        self.frame_buffer_size
+    }
+
+    #[must_use]
+    pub(crate) fn frame_number(&self) -> u32 {
+        self.frame_number
+    }
+
+    #[must_use]
+    pub(crate) fn pixel_color(&self) -> &[PodVector] {
+        &self.pixel_color
+    }
+
+    // nearest neighbour resampling to another frame buffer size; the sums are scaled down to make the
+    // checkpoint weigh no more than 'weight_limit' passes, so the samples of the new size soon outweigh it
+    #[must_use]
+    pub(crate) fn resampled(&self, frame_buffer_size: FrameBufferSize, weight_limit: u32) -> Self {
+        assert_gt!(weight_limit, 0);
+        let frame_number = self.frame_number.min(weight_limit);
+        let weight = if 0 == self.frame_number { 1.0 } else { frame_number as f32 / self.frame_number as f32 };
+
+        let source_index = |target: u32, target_extent: u32, source_extent: u32| -> u32 {
+            // the source pixel under the center of the target one
+            ((2 * target as u64 + 1) * source_extent as u64 / (2 * target_extent as u64)) as u32
+        };
+        let mut pixel_color = Vec::with_capacity(frame_buffer_size.area() as usize);
+        for y in 0..frame_buffer_size.height() {
+            let source_y = source_index(y, frame_buffer_size.height(), self.frame_buffer_size.height());
+            for x in 0..frame_buffer_size.width() {
+                let source_x = source_index(x, frame_buffer_size.width(), self.frame_buffer_size.width());
+                let source = self.pixel_color[(source_y * self.frame_buffer_size.width() + source_x) as usize];
+                pixel_color.push(PodVector { x: source.x * weight, y: source.y * weight, z: source.z * weight, w: source.w * weight });
+            }
+        }
+
+        Self { frame_buffer_size, frame_number, pixel_color }
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header = CheckpointHeader::new(self.frame_buffer_size, self.frame_number);
+        writer.write_all(bytemuck::bytes_of(&header))?;
+        writer.write_all(bytemuck::cast_slice(&self.pixel_color))?;
+        Ok(writer.flush()?)
+    }
+
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut header = CheckpointHeader::zeroed();
+        reader.read_exact(bytemuck::bytes_of_mut(&mut header))?;
+        if header.magic != CheckpointHeader::MAGIC || header.layout_version != CheckpointHeader::LAYOUT_VERSION {
+            bail!("{path:?} is not an accumulation checkpoint of the supported layout");
+        }
+        if 0 == header.width || 0 == header.height {
+            bail!("{path:?} has an empty frame buffer");
+        }
+
+        let frame_buffer_size = FrameBufferSize::new(header.width, header.height);
+        let mut pixel_color = vec![PodVector::default(); frame_buffer_size.area() as usize];
+        reader.read_exact(bytemuck::cast_slice_mut(&mut pixel_color))?;
+
+        Ok(Self { frame_buffer_size, frame_number: header.frame_number, pixel_color })
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct CheckpointHeader {
+    magic: [u8; 4], // "ACCP" (accumulation checkpoint)
+    layout_version: u32,
+    width: u32,
+    height: u32,
+    frame_number: u32,
+}
+
+impl CheckpointHeader {
+    const MAGIC: [u8; 4] = *b"ACCP";
+    // bump on any change of the file layout
+    const LAYOUT_VERSION: u32 = 1;
+
+    #[must_use]
+    fn new(frame_buffer_size: FrameBufferSize, frame_number: u32) -> Self {
+        Self {
+            magic: Self::MAGIC,
+            layout_version: Self::LAYOUT_VERSION,
+            width: frame_buffer_size.width(),
+            height: frame_buffer_size.height(),
+            frame_number,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_save_and_load() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("checkpoint");
+        let pixel_color = (0..6).map(|i| PodVector::new_full(i as f32, 0.5, 0.25, 1.0)).collect();
+        let expected = AccumulationCheckpoint::new(FrameBufferSize::new(3, 2), 17, pixel_color);
+
+        expected.save(&path).unwrap();
+        let actual = AccumulationCheckpoint::load(&path).unwrap();
+
+        assert_eq!(actual.frame_buffer_size(), expected.frame_buffer_size());
+        assert_eq!(actual.frame_number(), 17);
+        assert_eq!(actual.pixel_color(), expected.pixel_color());
+    }
+
+    #[test]
+    fn test_load_of_foreign_file() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("checkpoint");
+        std::fs::write(&path, [0u8; 64]).unwrap();
+
+        assert!(AccumulationCheckpoint::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_load_of_truncated_file() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("checkpoint");
+        AccumulationCheckpoint::new(FrameBufferSize::new(2, 2), 3, vec![PodVector::default(); 4]).save(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+
+        assert!(AccumulationCheckpoint::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_resampled_up() {
+        let pixel_color = (0..4).map(|i| PodVector::new_full(i as f32, 0.0, 0.0, 0.0)).collect();
+        let system_under_test = AccumulationCheckpoint::new(FrameBufferSize::new(2, 2), 4, pixel_color);
+
+        let actual = system_under_test.resampled(FrameBufferSize::new(4, 2), 4);
+
+        assert_eq!(actual.frame_buffer_size(), FrameBufferSize::new(4, 2));
+        assert_eq!(actual.frame_number(), 4);
+        let reds: Vec<f32> = actual.pixel_color().iter().map(|pixel| pixel.x).collect();
+        assert_eq!(reds, vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_resampled_down_with_weight_limit() {
+        let pixel_color = (0..16).map(|i| PodVector::new_full(i as f32 * 10.0, 10.0, 20.0, 30.0)).collect();
+        let system_under_test = AccumulationCheckpoint::new(FrameBufferSize::new(4, 4), 10, pixel_color);
+
+        let actual = system_under_test.resampled(FrameBufferSize::new(2, 2), 2);
+
+        assert_eq!(actual.frame_number(), 2);
+        assert_eq!(actual.pixel_color(), &[
+            PodVector::new_full(10.0, 2.0, 4.0, 6.0),
+            PodVector::new_full(14.0, 2.0, 4.0, 6.0),
+            PodVector::new_full(26.0, 2.0, 4.0, 6.0),
+            PodVector::new_full(30.0, 2.0, 4.0, 6.0),
+        ]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pixels_count_mismatch() {
+        let _ = AccumulationCheckpoint::new(FrameBufferSize::new(2, 2), 1, vec![PodVector::default(); 3]);
+    }
+}
@@ -4,24 +4,25 @@ use wgpu::{Adapter, AdapterInfo};
 pub(crate) struct AdapterFeatures {
     desired_features: wgpu::Features,
     pipeline_caching_supported: bool,
+    timestamp_queries_supported: bool,
 }
 
 impl AdapterFeatures {
     #[must_use]
     pub(crate) fn new(adapter: &Adapter) -> Self {
         let adapter_features = adapter.features();
-        
-        if adapter_features.contains(wgpu::Features::PIPELINE_CACHE) {
-            AdapterFeatures {
-                desired_features: wgpu::Features::PIPELINE_CACHE | wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER,
-                pipeline_caching_supported: true,
-            }
-        } else {
-            AdapterFeatures {
-                desired_features: wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER,
-                pipeline_caching_supported: false,
-            }
+        let pipeline_caching_supported = adapter_features.contains(wgpu::Features::PIPELINE_CACHE);
+        // the passes are timed only where the adapter can, see 'PassTimer'
+        let timestamp_queries_supported = adapter_features.contains(wgpu::Features::TIMESTAMP_QUERY);
+
+        let mut desired_features = wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER;
+        if pipeline_caching_supported {
+            desired_features |= wgpu::Features::PIPELINE_CACHE;
+        }
+        if timestamp_queries_supported {
+            desired_features |= wgpu::Features::TIMESTAMP_QUERY;
         }
+        AdapterFeatures { desired_features, pipeline_caching_supported, timestamp_queries_supported }
     }
 
     #[must_use]
@@ -33,6 +34,11 @@ impl AdapterFeatures {
     pub(crate) fn pipeline_caching_supported(&self) -> bool {
         self.pipeline_caching_supported
     }
+
+    #[must_use]
+    pub(crate) fn timestamp_queries_supported(&self) -> bool {
+        self.timestamp_queries_supported
+    }
 }
 
 pub(crate) fn log_adapter_info(adapter_info: &AdapterInfo) {
@@ -0,0 +1,96 @@
+use crate::gpu::composed_shader::{pipeline_routines, ComposedShader, PipelineFingerprints};
+use crate::gpu::pipeline_code::ShaderHash;
+use crate::gpu::pipelines_factory::ComputeRoutineEntryPoint;
+use crate::utils::version::Version;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+// a compute pipeline built off the render thread, taken by the factory instead of compiling the same code again
+pub(crate) struct PrebuiltComputePipeline {
+    pub(crate) hash: ShaderHash,
+    pub(crate) routine: ComputeRoutineEntryPoint,
+    pub(crate) pipeline: wgpu::ComputePipeline,
+}
+
+// the tracer composed with the new sdf classes, along with the compute pipelines of the codes the classes outdated
+pub(super) struct CompiledShader {
+    module: wgpu::ShaderModule,
+    fingerprints: PipelineFingerprints,
+    compute_pipelines: Vec<PrebuiltComputePipeline>,
+}
+
+impl CompiledShader {
+    #[must_use]
+    pub(super) fn into_parts(self) -> (ComposedShader, Vec<PrebuiltComputePipeline>) {
+        (ComposedShader::from_parts(Rc::new(self.module), self.fingerprints), self.compute_pipelines)
+    }
+}
+
+pub(super) enum CompilationPoll {
+    Pending,
+    Compiled(CompiledShader),
+    // the thread is gone without the outcome
+    Failed,
+}
+
+// the shader module and the outdated compute pipelines are made by a thread of their own, so that the
+// frames keep coming (with the last traced image) meanwhile; the pipelines are built without the driver
+// caches, those belong to the factory of the render thread. The rasterization pipeline does not reach
+// the sdf code, so if outdated at all it is left to the factory.
+pub(super) struct BackgroundShaderCompilation {
+    sdf_classes_version: Version,
+    outcome: mpsc::Receiver<CompiledShader>,
+}
+
+impl BackgroundShaderCompilation {
+    #[must_use]
+    pub(super) fn start(device: &wgpu::Device, source: String, built: PipelineFingerprints, sdf_classes_version: Version) -> Self {
+        let (sender, outcome) = mpsc::channel();
+        let device = device.clone();
+        thread::spawn(move || {
+            let fingerprints = ComposedShader::fingerprint(source.as_str());
+            let outdated = ComposedShader::outdated(&fingerprints, &built);
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("ray tracer shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+
+            let mut compute_pipelines = Vec::new();
+            for (uid, routines) in pipeline_routines() {
+                let Some(routines) = routines.filter(|_| outdated.contains(uid)) else {
+                    continue;
+                };
+                let hash = fingerprints[uid];
+                for routine in routines {
+                    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                        label: routine.name(),
+                        compilation_options: Default::default(),
+                        layout: None,
+                        module: &module,
+                        entry_point: routine.name(),
+                        cache: None,
+                    });
+                    compute_pipelines.push(PrebuiltComputePipeline { hash, routine, pipeline });
+                }
+            }
+            // the receiver is gone when a compilation of newer classes superseded this one
+            let _ = sender.send(CompiledShader { module, fingerprints, compute_pipelines });
+        });
+        Self { sdf_classes_version, outcome }
+    }
+
+    #[must_use]
+    pub(super) fn sdf_classes_version(&self) -> Version {
+        self.sdf_classes_version
+    }
+
+    #[must_use]
+    pub(super) fn poll(&self) -> CompilationPoll {
+        match self.outcome.try_recv() {
+            Ok(compiled) => CompilationPoll::Compiled(compiled),
+            Err(mpsc::TryRecvError::Empty) => CompilationPoll::Pending,
+            Err(mpsc::TryRecvError::Disconnected) => CompilationPoll::Failed,
+        }
+    }
+}
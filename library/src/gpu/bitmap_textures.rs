@@ -1,13 +1,17 @@
 use wgpu::{Sampler, Texture};
 use crate::gpu::bind_group_builder::BindGroupBuilder;
+use crate::container::texture_atlas_page_composer::TextureAtlasPageComposer;
 use crate::gpu::resources::Resources;
-use crate::utils::bitmap_utils::BitmapSize;
+use crate::utils::bitmap_utils::{downsample_srgba_bitmap, BitmapSize, ImmutableBitmapReference};
 use crate::utils::version::Version;
 
 pub(super) struct BitmapTextures {
     sampler: Sampler,
     atlas_page: Texture,
+    atlas_page_size: BitmapSize,
     last_seen_data_version: Option<Version>,
+    // the texels last written, for a new device to get the same page
+    atlas_page_data: Option<Vec<u8>>,
 }
 
 impl BitmapTextures {
@@ -21,19 +25,49 @@ impl BitmapTextures {
     pub(super) fn new(resources: &Resources, atlas_page_size: BitmapSize) -> Self {
         Self {
             sampler: resources.create_sampler(BitmapTextures::ATLAS_SAMPLER_LABEL),
-            atlas_page: resources.create_texture(BitmapTextures::ATLAS_PAGE_LABEL, 1, atlas_page_size),
+            atlas_page: resources.create_texture(BitmapTextures::ATLAS_PAGE_LABEL, Self::mip_levels(atlas_page_size), atlas_page_size),
+            atlas_page_size,
             last_seen_data_version: None,
+            atlas_page_data: None,
         }
     }
 
+    // the page last uploaded, by the scene or by the host, goes to the new device as is
+    #[must_use]
+    pub(super) fn recreate(&self, resources: &Resources) -> Self {
+        let mut textures = Self::new(resources, self.atlas_page_size);
+        if let Some(data) = self.atlas_page_data.as_deref() {
+            textures.set_atlas_page(resources, data, self.last_seen_data_version);
+        }
+        textures
+    }
+
     pub(super) fn bind(&self, bind_group: &mut BindGroupBuilder) {
         bind_group.set_sampler_entry(BitmapTextures::BIND_GROUP_SAMPLER_SLOT, self.sampler.clone());
         bind_group.set_texture_entry(BitmapTextures::BIND_GROUP_ATLAS_PAGE_SLOT, self.atlas_page.create_view(&wgpu::TextureViewDescriptor::default()));
     }
     
+    #[must_use]
+    fn mip_levels(atlas_page_size: BitmapSize) -> u32 {
+        TextureAtlasPageComposer::MIP_LEVELS.min(Resources::max_mip_levels(atlas_page_size))
+    }
+
+    // the composer aligns the regions, so downsampling the whole page keeps them apart
     pub(super) fn set_atlas_page(&mut self, resources: &Resources, data: &[u8], data_version: Option<Version>) {
         resources.write_whole_srgba_texture_data(&self.atlas_page, data);
+
+        let mut level_data: Option<Vec<u8>> = None;
+        let mut level_size = self.atlas_page_size;
+        for mip_level in 1..self.atlas_page.mip_level_count() {
+            let source = ImmutableBitmapReference::new(level_data.as_deref().unwrap_or(data), level_size);
+            let (next_data, next_size) = downsample_srgba_bitmap(&source);
+            resources.write_whole_srgba_texture_level(&self.atlas_page, mip_level, &next_data);
+            level_data = Some(next_data);
+            level_size = next_size;
+        }
+
         self.last_seen_data_version = data_version;
+        self.atlas_page_data = Some(data.to_vec());
     }
 
     #[must_use]
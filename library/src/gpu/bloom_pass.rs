@@ -0,0 +1,262 @@
+use crate::gpu::bind_group_builder::BindGroupBuilder;
+use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::gpu::pipeline_code::PipelineCode;
+use crate::gpu::pipelines_factory::{ComputeRoutineEntryPoint, PipelinesFactory};
+use crate::gpu::resources::Resources;
+use crate::gpu::uniforms::Uniforms;
+use bytemuck::{Pod, Zeroable};
+use std::rc::Rc;
+use wgpu::BufferUsages;
+
+pub(crate) const BLOOM_GPU_CODE: &str = include_str!("../../shader/_bloom.wgsl");
+
+// must match 'BloomStep' of the shader
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable, PartialEq, Debug)]
+struct BloomStepUniforms {
+    source_offset: u32,
+    source_width: u32,
+    source_height: u32,
+    destination_offset: u32,
+    destination_width: u32,
+    destination_height: u32,
+    horizontal: u32,
+    threshold: f32,
+    inverse_frame_number: f32,
+    padding: [f32; 3],
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum BloomKernel {
+    BrightPass,
+    Downsample,
+    Blur,
+    Upsample,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct BloomImage {
+    offset: u32,
+    size: FrameBufferSize,
+}
+
+// the layout of the chain buffer: the images, each half of the previous one, starting from the half
+// resolution one; then the scratch image the separable blur goes through, of the size of the first one
+#[derive(Clone, PartialEq, Debug)]
+struct BloomChain {
+    frame_buffer_size: FrameBufferSize,
+    images: Vec<BloomImage>,
+    scratch_offset: u32,
+    pixels_count: u32,
+}
+
+impl BloomChain {
+    const MAX_IMAGES: usize = 6;
+
+    #[must_use]
+    fn new(frame_buffer_size: FrameBufferSize) -> Self {
+        let mut images = Vec::with_capacity(Self::MAX_IMAGES);
+        let mut size = frame_buffer_size.half_resolution();
+        let mut offset = 0;
+        loop {
+            images.push(BloomImage { offset, size });
+            offset += size.area();
+            if images.len() == Self::MAX_IMAGES || size.width() == 1 || size.height() == 1 {
+                break;
+            }
+            size = size.half_resolution();
+        }
+        let scratch_offset = offset;
+        Self { frame_buffer_size, pixels_count: scratch_offset + images[0].size.area(), images, scratch_offset }
+    }
+
+    #[must_use]
+    fn step(kernel: BloomKernel, source: BloomImage, destination: BloomImage) -> (BloomKernel, BloomStepUniforms) {
+        (kernel, BloomStepUniforms {
+            source_offset: source.offset,
+            source_width: source.size.width(),
+            source_height: source.size.height(),
+            destination_offset: destination.offset,
+            destination_width: destination.size.width(),
+            destination_height: destination.size.height(),
+            ..BloomStepUniforms::zeroed()
+        })
+    }
+
+    // the dispatches in their order: the bright pass, the downsamples, the blurs, the upsamples
+    #[must_use]
+    fn steps(&self, threshold: f32, inverse_frame_number: f32) -> Vec<(BloomKernel, BloomStepUniforms)> {
+        let frame = BloomImage { offset: 0, size: self.frame_buffer_size };
+        let mut result = Vec::with_capacity(4 * self.images.len());
+
+        let (kernel, mut bright_pass) = Self::step(BloomKernel::BrightPass, frame, self.images[0]);
+        bright_pass.threshold = threshold;
+        bright_pass.inverse_frame_number = inverse_frame_number;
+        result.push((kernel, bright_pass));
+
+        for pair in self.images.windows(2) {
+            result.push(Self::step(BloomKernel::Downsample, pair[0], pair[1]));
+        }
+
+        for image in &self.images {
+            let scratch = BloomImage { offset: self.scratch_offset, size: image.size };
+            let (kernel, mut horizontal) = Self::step(BloomKernel::Blur, *image, scratch);
+            horizontal.horizontal = 1;
+            result.push((kernel, horizontal));
+            result.push(Self::step(BloomKernel::Blur, scratch, *image));
+        }
+
+        for pair in self.images.windows(2).rev() {
+            result.push(Self::step(BloomKernel::Upsample, pair[1], pair[0]));
+        }
+
+        result
+    }
+}
+
+// the bright pixels of the displayed image, blurred over a mip chain; the output pass adds the result
+pub(super) struct BloomPass {
+    bright_pass: wgpu::ComputePipeline,
+    downsample: wgpu::ComputePipeline,
+    blur: wgpu::ComputePipeline,
+    upsample: wgpu::ComputePipeline,
+    chain_buffer: Rc<wgpu::Buffer>,
+    chain: Option<BloomChain>,
+    step_uniforms: Vec<Rc<wgpu::Buffer>>,
+}
+
+impl BloomPass {
+    const BIND_GROUP_INDEX: u32 = 0;
+    const BIND_GROUP_STEP_SLOT: u32 = 0;
+    const BIND_GROUP_PIXEL_COLOR_SLOT: u32 = 1;
+    const BIND_GROUP_CHAIN_SLOT: u32 = 2;
+
+    #[must_use]
+    pub(super) fn new(resources: &Resources, pipelines_factory: &mut PipelinesFactory) -> Self {
+        let module = resources.create_shader_module("bloom shader", BLOOM_GPU_CODE);
+        let code = PipelineCode::new(module, seahash::hash(BLOOM_GPU_CODE.as_bytes()), "bloom_code".to_string());
+        Self {
+            bright_pass: pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::BloomBrightPass, &code),
+            downsample: pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::BloomDownsample, &code),
+            blur: pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::BloomBlur, &code),
+            upsample: pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::BloomUpsample, &code),
+            // an empty storage buffer can not be bound
+            chain_buffer: resources.create_buffer("bloom chain", BufferUsages::STORAGE, bytemuck::cast_slice(&[0.0_f32; 4])),
+            chain: None,
+            step_uniforms: Vec::new(),
+        }
+    }
+
+    // read by the output pass, so it is to be bound again when replaced
+    #[must_use]
+    pub(super) fn chain_buffer(&self) -> Rc<wgpu::Buffer> {
+        self.chain_buffer.clone()
+    }
+
+    // lays the chain out for the frame buffer; true when the chain buffer has been replaced
+    #[must_use]
+    pub(super) fn fit(&mut self, frame_buffer_size: FrameBufferSize, device: &wgpu::Device, resources: &Resources) -> bool {
+        if self.chain.as_ref().is_some_and(|chain| chain.frame_buffer_size == frame_buffer_size) {
+            return false;
+        }
+        let chain = BloomChain::new(frame_buffer_size);
+        let steps_count = chain.steps(0.0, 0.0).len();
+        self.step_uniforms = (0..steps_count)
+            .map(|_| resources.create_uniform_buffer("bloom step", bytemuck::bytes_of(&BloomStepUniforms::zeroed())))
+            .collect();
+
+        let required_size = u64::from(chain.pixels_count) * size_of::<[f32; 4]>() as u64;
+        self.chain = Some(chain);
+        if self.chain_buffer.size() >= required_size {
+            return false;
+        }
+        self.chain_buffer = Rc::new(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("bloom chain"),
+            size: required_size,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        }));
+        true
+    }
+
+    // 'pixel_color' is the image the output pass shows: the noisy or the denoised one
+    pub(super) fn run(&self, encoder: &mut wgpu::CommandEncoder, device: &wgpu::Device, queue: &wgpu::Queue, pixel_color: Rc<wgpu::Buffer>, threshold: f32, frame_number: u32) {
+        let chain = self.chain.as_ref().expect("the bloom chain is to be fitted first");
+        let steps = chain.steps(threshold, 1.0 / frame_number.max(1) as f32);
+
+        for ((kernel, step), step_uniforms) in steps.iter().zip(&self.step_uniforms) {
+            queue.write_buffer(step_uniforms, 0, bytemuck::bytes_of(step));
+
+            let pipeline = match kernel {
+                BloomKernel::BrightPass => &self.bright_pass,
+                BloomKernel::Downsample => &self.downsample,
+                BloomKernel::Blur => &self.blur,
+                BloomKernel::Upsample => &self.upsample,
+            };
+            let mut bind_group_builder = BindGroupBuilder::new(Self::BIND_GROUP_INDEX, Some("bloom pipeline group"), pipeline.get_bind_group_layout(Self::BIND_GROUP_INDEX));
+            bind_group_builder
+                .set_storage_entry(Self::BIND_GROUP_STEP_SLOT, step_uniforms.clone())
+                .set_storage_entry(Self::BIND_GROUP_CHAIN_SLOT, self.chain_buffer.clone())
+            ;
+            if BloomKernel::BrightPass == *kernel {
+                bind_group_builder.set_storage_entry(Self::BIND_GROUP_PIXEL_COLOR_SLOT, pixel_color.clone());
+            }
+            let bind_group = bind_group_builder.make_bind_group(device);
+
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("bloom compute pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(Self::BIND_GROUP_INDEX, &bind_group, &[]);
+            let work_groups_needed = Uniforms::work_groups_count_of(FrameBufferSize::new(step.destination_width, step.destination_height));
+            pass.dispatch_workgroups(work_groups_needed.x, work_groups_needed.y, work_groups_needed.z);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_layout() {
+        let system_under_test = BloomChain::new(FrameBufferSize::new(100, 30));
+
+        let sizes: Vec<(u32, u32)> = system_under_test.images.iter().map(|image| (image.size.width(), image.size.height())).collect();
+        assert_eq!(sizes, vec![(50, 15), (25, 8), (13, 4), (7, 2), (4, 1)]);
+        assert_eq!(system_under_test.images[1].offset, 50 * 15);
+        assert_eq!(system_under_test.scratch_offset, 750 + 200 + 52 + 14 + 4);
+        assert_eq!(system_under_test.pixels_count, system_under_test.scratch_offset + 750);
+    }
+
+    #[test]
+    fn test_chain_is_limited() {
+        let system_under_test = BloomChain::new(FrameBufferSize::new(4096, 4096));
+
+        assert_eq!(system_under_test.images.len(), BloomChain::MAX_IMAGES);
+    }
+
+    #[test]
+    fn test_steps_order() {
+        let system_under_test = BloomChain::new(FrameBufferSize::new(16, 16));
+
+        let steps = system_under_test.steps(1.5, 0.25);
+
+        let kernels: Vec<BloomKernel> = steps.iter().map(|(kernel, _)| *kernel).collect();
+        let images = system_under_test.images.len();
+        assert_eq!(images, 4);
+        assert_eq!(kernels[0], BloomKernel::BrightPass);
+        assert!(kernels[1..images].iter().all(|kernel| *kernel == BloomKernel::Downsample));
+        assert!(kernels[images..3 * images].iter().all(|kernel| *kernel == BloomKernel::Blur));
+        assert!(kernels[3 * images..].iter().all(|kernel| *kernel == BloomKernel::Upsample));
+        assert_eq!(steps.len(), 4 * images - 1);
+
+        let (_, bright_pass) = steps[0];
+        assert_eq!((bright_pass.source_width, bright_pass.destination_width), (16, 8));
+        assert_eq!((bright_pass.threshold, bright_pass.inverse_frame_number), (1.5, 0.25));
+
+        let (_, last_upsample) = steps[steps.len() - 1];
+        assert_eq!((last_upsample.source_offset, last_upsample.destination_offset), (system_under_test.images[1].offset, 0));
+    }
+}
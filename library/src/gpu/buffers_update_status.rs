@@ -1,9 +1,12 @@
 use crate::gpu::resizable_buffer::ResizeStatus;
 use crate::gpu::versioned_buffer::BufferUpdateStatus;
+use crate::scene::frame_statistics::FrameStatistics;
+use std::time::Duration;
 
 pub(super) struct BuffersUpdateStatus {
     geometry_status: BufferUpdateStatus,
     materials_status: BufferUpdateStatus,
+    bvh_build_time: Duration,
 }
 
 impl BuffersUpdateStatus {
@@ -12,6 +15,7 @@ impl BuffersUpdateStatus {
         Self {
             geometry_status: BufferUpdateStatus::new_updated(false),
             materials_status: BufferUpdateStatus::new_updated(false),
+            bvh_build_time: Duration::ZERO,
         }
     }
 
@@ -30,10 +34,19 @@ impl BuffersUpdateStatus {
         self.geometry_status.updated()
     }
 
-    pub(super) fn merge_bvh(&mut self, child_status: ResizeStatus) {
+    #[must_use]
+    pub(super) fn frame_statistics(&self) -> FrameStatistics {
+        self.geometry_status.uploads().merge(self.materials_status.uploads()).with_bvh_build_time(self.bvh_build_time)
+    }
+
+    pub(super) fn record_bvh_build(&mut self, bvh_build_time: Duration) {
+        self.bvh_build_time += bvh_build_time;
+    }
+
+    pub(super) fn merge_bvh(&mut self, child_status: ResizeStatus, uploaded_bytes: u64) {
         let updated = true;
         let resized = child_status == ResizeStatus::Resized;
-        let status = BufferUpdateStatus::new(resized, updated);
+        let status = BufferUpdateStatus::new(resized, updated).with_upload(uploaded_bytes, Duration::ZERO);
         self.geometry_status = self.geometry_status.merge(status);
     }
     
@@ -150,12 +163,29 @@ mod tests {
     #[test_context(Context)]
     #[test]
     fn test_merge_bvh(fixture: &mut Context) {
-        fixture.system_under_test.merge_bvh(ResizeStatus::SizeKept);
+        fixture.system_under_test.merge_bvh(ResizeStatus::SizeKept, 0);
         assert_eq!(fixture.system_under_test.any_resized(), false);
         assert_eq!(fixture.system_under_test.any_updated(), true);
 
-        fixture.system_under_test.merge_bvh(ResizeStatus::Resized);
+        fixture.system_under_test.merge_bvh(ResizeStatus::Resized, 0);
         assert_eq!(fixture.system_under_test.any_resized(), true);
         assert_eq!(fixture.system_under_test.any_updated(), true);
     }
-}
\ No newline at end of file
+
+    #[test_context(Context)]
+    #[test]
+    fn test_frame_statistics(fixture: &mut Context) {
+        fixture.system_under_test.merge_geometry(BufferUpdateStatus::new_updated(true).with_upload(40, Duration::from_millis(1)));
+        fixture.system_under_test.merge_materials(BufferUpdateStatus::new_updated(true).with_upload(8, Duration::from_millis(2)));
+        fixture.system_under_test.merge_bvh(ResizeStatus::SizeKept, 64);
+        fixture.system_under_test.record_bvh_build(Duration::from_millis(3));
+
+        let statistics = fixture.system_under_test.frame_statistics();
+
+        assert_eq!(statistics.uploaded_buffers(), 3);
+        assert_eq!(statistics.uploaded_bytes(), 112);
+        assert_eq!(statistics.peak_staging_bytes(), 64);
+        assert_eq!(statistics.serialization_time(), Duration::from_millis(3));
+        assert_eq!(statistics.bvh_build_time(), Duration::from_millis(3));
+    }
+}
@@ -1,28 +1,36 @@
 use std::cell::{Ref, RefCell};
 use std::rc::Rc;
 use crate::gpu::compute_pipeline::ComputePipeline;
-
-#[derive(PartialEq, Copy, Clone)]
-pub(crate) enum RenderStrategyId {
-    MonteCarlo,
-    Deterministic,
-}
+use crate::scene::render_strategy::RenderStrategyId;
 
 pub(super) struct ColorBufferEvaluationStrategy {
     ray_tracing_pipeline: Rc<RefCell<ComputePipeline>>,
+    indirect_lighting_pipeline: Option<Rc<RefCell<ComputePipeline>>>,
     frame_counter_increment: u32,
     frame_counter_default: u32,
     id: RenderStrategyId,
 }
 
 impl ColorBufferEvaluationStrategy {
+    #[cfg(feature = "monte_carlo")]
     #[must_use]
     pub(super) fn new_monte_carlo(pipeline: Rc<RefCell<ComputePipeline>>) -> Self {
-        Self { ray_tracing_pipeline: pipeline, frame_counter_increment: 1, frame_counter_default: 0, id: RenderStrategyId::MonteCarlo, }
+        Self { ray_tracing_pipeline: pipeline, indirect_lighting_pipeline: None, frame_counter_increment: 1, frame_counter_default: 0, id: RenderStrategyId::MonteCarlo, }
+    }
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(super) fn new_monte_carlo_half_resolution_indirect(composition_pipeline: Rc<RefCell<ComputePipeline>>, indirect_lighting_pipeline: Rc<RefCell<ComputePipeline>>) -> Self {
+        Self { ray_tracing_pipeline: composition_pipeline, indirect_lighting_pipeline: Some(indirect_lighting_pipeline), frame_counter_increment: 1, frame_counter_default: 0, id: RenderStrategyId::MonteCarloHalfResolutionIndirect, }
+    }
+    // the wavefront kernels are recorded by the renderer, the pipeline resolves the traced paths into the pixels
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(super) fn new_wavefront(resolve_pipeline: Rc<RefCell<ComputePipeline>>) -> Self {
+        Self { ray_tracing_pipeline: resolve_pipeline, indirect_lighting_pipeline: None, frame_counter_increment: 1, frame_counter_default: 0, id: RenderStrategyId::Wavefront, }
     }
     #[must_use]
     pub(super) fn new_deterministic(pipeline: Rc<RefCell<ComputePipeline>>) -> Self {
-        Self { ray_tracing_pipeline: pipeline, frame_counter_increment: 0, frame_counter_default: 1, id: RenderStrategyId::Deterministic, }
+        Self { ray_tracing_pipeline: pipeline, indirect_lighting_pipeline: None, frame_counter_increment: 0, frame_counter_default: 1, id: RenderStrategyId::Deterministic, }
     }
     
     #[must_use]
@@ -30,6 +38,10 @@ impl ColorBufferEvaluationStrategy {
         self.ray_tracing_pipeline.borrow()
     }
     #[must_use]
+    pub(super) fn indirect_lighting_pipeline(&self) -> Option<Ref<'_, ComputePipeline>> {
+        self.indirect_lighting_pipeline.as_ref().map(|pipeline| pipeline.borrow())
+    }
+    #[must_use]
     pub(super) fn frame_counter_increment(&self) -> u32 {
         self.frame_counter_increment
     }
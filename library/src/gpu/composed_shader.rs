@@ -0,0 +1,146 @@
+use crate::container::visual_objects::VisualObjects;
+use crate::gpu::pipeline_code::{PipelineCode, ShaderHash};
+use crate::gpu::pipelines_factory::ComputeRoutineEntryPoint;
+use crate::gpu::resources::Resources;
+use crate::shader::call_graph::CallGraph;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+// the rasterization pipeline takes the only vertex and fragment routines of the module
+const RASTERIZATION_ENTRY_POINTS: [&str; 2] = ["vs", "fs"];
+
+#[must_use]
+fn compute_entry_points(routines: &[ComputeRoutineEntryPoint]) -> Vec<&'static str> {
+    routines.iter().map(|routine| routine.name().expect("tracer routines are named")).collect()
+}
+
+// the pipeline code uids along with the compute routines the pipelines of the code run, none for the rasterization code
+#[must_use]
+pub(super) fn pipeline_routines() -> Vec<(&'static str, Option<Vec<ComputeRoutineEntryPoint>>)> {
+    vec![
+        #[cfg(feature = "monte_carlo")]
+        ("monte_carlo_code", Some(vec![ComputeRoutineEntryPoint::RayTracingMonteCarlo])),
+        ("deterministic_code", Some(vec![ComputeRoutineEntryPoint::RayTracingDeterministic])),
+        ("irradiance_probes_code", Some(vec![ComputeRoutineEntryPoint::IrradianceProbes])),
+        ("shadow_map_code", Some(vec![ComputeRoutineEntryPoint::ShadowMap])),
+        ("auto_exposure_code", Some(vec![ComputeRoutineEntryPoint::LuminanceHistogram, ComputeRoutineEntryPoint::AutoExposure])),
+        #[cfg(feature = "monte_carlo")]
+        ("half_resolution_indirect_code", Some(vec![ComputeRoutineEntryPoint::RayTracingMonteCarloHalfResolutionIndirect, ComputeRoutineEntryPoint::IndirectLightingHalfResolution])),
+        #[cfg(feature = "monte_carlo")]
+        ("wavefront_code", Some(vec![
+            ComputeRoutineEntryPoint::WavefrontResolve,
+            ComputeRoutineEntryPoint::WavefrontGenerate,
+            ComputeRoutineEntryPoint::WavefrontPrepareIntersection,
+            ComputeRoutineEntryPoint::WavefrontIntersect,
+            ComputeRoutineEntryPoint::WavefrontPrepareShading,
+            ComputeRoutineEntryPoint::WavefrontShade,
+        ])),
+        #[cfg(feature = "monte_carlo")]
+        ("temporal_reprojection_code", Some(vec![ComputeRoutineEntryPoint::ReprojectionHistory, ComputeRoutineEntryPoint::TemporalReprojection])),
+        #[cfg(feature = "monte_carlo")]
+        ("debug_path_code", Some(vec![ComputeRoutineEntryPoint::DebugPath])),
+        ("surface_attributes_pipeline_code", Some(vec![ComputeRoutineEntryPoint::SurfaceAttributes])),
+        ("procedural_texture_bake_code", Some(vec![ComputeRoutineEntryPoint::ProceduralTextureBake])),
+        ("sdf_distance_grids_bake_code", Some(vec![ComputeRoutineEntryPoint::SdfDistanceGridsBake])),
+        ("final_image_rasterization_code", None),
+    ]
+}
+
+// the pipeline code uids along with the entry points the pipelines of the code run
+#[must_use]
+fn pipeline_codes() -> Vec<(&'static str, Vec<&'static str>)> {
+    pipeline_routines().into_iter()
+        .map(|(uid, routines)| (uid, routines.map_or_else(|| RASTERIZATION_ENTRY_POINTS.to_vec(), |routines| compute_entry_points(&routines))))
+        .collect()
+}
+
+pub(super) type PipelineFingerprints = HashMap<&'static str, ShaderHash>;
+
+// the tracer with the scene generated code: one module for all the pipelines, yet each pipeline code
+// is hashed by the functions its entry points reach, so that the changed generated code rebuilds
+// only the pipelines calling it
+pub(super) struct ComposedShader {
+    module: Rc<wgpu::ShaderModule>,
+    fingerprints: PipelineFingerprints,
+}
+
+impl ComposedShader {
+    #[must_use]
+    pub(super) fn new(base_code: &str, scene: &VisualObjects, resources: &Resources) -> Self {
+        let source = scene.compose_shader(base_code);
+        let fingerprints = Self::fingerprint(source.as_str());
+        let module = resources.create_shader_module("ray tracer shader", source.as_str());
+        Self { module, fingerprints }
+    }
+
+    // of the module made and the source fingerprinted elsewhere, see 'BackgroundShaderCompilation'
+    #[must_use]
+    pub(super) fn from_parts(module: Rc<wgpu::ShaderModule>, fingerprints: PipelineFingerprints) -> Self {
+        Self { module, fingerprints }
+    }
+
+    #[must_use]
+    pub(super) fn fingerprint(source: &str) -> PipelineFingerprints {
+        let call_graph = CallGraph::new(source);
+        pipeline_codes().into_iter()
+            .map(|(uid, entry_points)| (uid, call_graph.fingerprint(entry_points.as_slice())))
+            .collect()
+    }
+
+    #[must_use]
+    pub(super) fn code(&self, uid: &str) -> PipelineCode {
+        let hash = *self.fingerprints.get(uid).unwrap_or_else(|| panic!("'{uid}' is not a tracer pipeline code"));
+        PipelineCode::new(self.module.clone(), hash, uid.to_string())
+    }
+
+    #[must_use]
+    pub(super) fn fingerprints(&self) -> &PipelineFingerprints {
+        &self.fingerprints
+    }
+
+    // the uids of the codes which differ from the ones the pipelines were built of
+    #[must_use]
+    pub(super) fn outdated_since(&self, built: &PipelineFingerprints) -> HashSet<&'static str> {
+        Self::outdated(&self.fingerprints, built)
+    }
+
+    #[must_use]
+    pub(super) fn outdated(actual: &PipelineFingerprints, built: &PipelineFingerprints) -> HashSet<&'static str> {
+        actual.iter()
+            .filter(|(uid, hash)| built.get(*uid) != Some(*hash))
+            .map(|(uid, _)| *uid)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::render::WHOLE_TRACER_GPU_CODE;
+    use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+    use crate::sdf::object::sdf_sphere::SdfSphere;
+
+    #[test]
+    fn test_every_pipeline_code_is_fingerprinted() {
+        let scene = VisualObjects::new(None, None, None, None, None);
+
+        let system_under_test = ComposedShader::fingerprint(scene.compose_shader(WHOLE_TRACER_GPU_CODE).as_str());
+
+        assert_eq!(system_under_test.len(), pipeline_codes().len());
+    }
+
+    #[test]
+    fn test_sdf_class_change_outdates_only_pipelines_reaching_sdf_code() {
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let before = ComposedShader::fingerprint(scene.compose_shader(WHOLE_TRACER_GPU_CODE).as_str());
+
+        scene.add_sdf_class(&NamedSdf::new(SdfSphere::new(1.0), UniqueSdfClassName::new("late_sphere".to_string())));
+        let after = ComposedShader::fingerprint(scene.compose_shader(WHOLE_TRACER_GPU_CODE).as_str());
+
+        let changed = ComposedShader::outdated(&after, &before);
+        assert!(changed.contains("sdf_distance_grids_bake_code"));
+        assert!(changed.contains("deterministic_code"));
+        assert!(false == changed.contains("final_image_rasterization_code"));
+        assert!(false == changed.contains("auto_exposure_code"));
+    }
+}
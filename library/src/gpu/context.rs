@@ -5,13 +5,14 @@ pub(crate) struct Context {
     device: wgpu::Device,
     queue: wgpu::Queue,
     pipeline_caching_supported: bool,
+    timestamp_queries_supported: bool,
     adapter_info: AdapterInfo,
 }
 
 impl Context {
     #[must_use]
-    pub(crate) fn new(device: wgpu::Device, queue: wgpu::Queue, pipeline_caching_supported: bool, adapter_info: AdapterInfo,) -> Self {
-        Self { device, queue, pipeline_caching_supported, adapter_info, }
+    pub(crate) fn new(device: wgpu::Device, queue: wgpu::Queue, pipeline_caching_supported: bool, timestamp_queries_supported: bool, adapter_info: AdapterInfo,) -> Self {
+        Self { device, queue, pipeline_caching_supported, timestamp_queries_supported, adapter_info, }
     }
 
     #[must_use]
@@ -30,10 +31,34 @@ impl Context {
     }
 
     #[must_use]
-    pub(super) fn adapter_info(&self) -> &AdapterInfo {
+    pub(crate) fn timestamp_queries_supported(&self) -> bool {
+        self.timestamp_queries_supported
+    }
+
+    #[must_use]
+    pub(crate) fn adapter_info(&self) -> &AdapterInfo {
         &self.adapter_info
     }
 
+    // runs the callbacks of the completed work without waiting for the rest
+    pub(crate) fn poll(&self) -> PollStatus {
+        self.device.poll(PollType::Poll).expect("failed to poll the device")
+    }
+
+    // RenderDoc (Vulkan, DX12, GL) or Xcode (Metal) records the gpu work between the calls when the
+    // application runs under it; a no-op otherwise
+    pub(crate) fn start_graphics_debugger_capture(&self) {
+        // SAFETY: the capture is started and stopped on the thread owning the device, as wgpu requires
+        unsafe { self.device.start_graphics_debugger_capture() }
+    }
+
+    pub(crate) fn stop_graphics_debugger_capture(&self) {
+        // the debuggers are known to cut the frame short unless the submitted work is complete
+        self.wait(None);
+        // SAFETY: see 'start_graphics_debugger_capture'
+        unsafe { self.device.stop_graphics_debugger_capture() }
+    }
+
     pub(crate) fn wait(&self, target: Option<SubmissionIndex>) -> PollStatus {
         let poll_type = if let Some(index) = target {
             PollType::WaitForSubmissionIndex(index)
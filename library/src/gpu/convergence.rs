@@ -0,0 +1,136 @@
+use crate::serialization::pod_vector::PodVector;
+
+// estimates the error of the accumulated image from its snapshots taken as the sample count doubles:
+// for the means of 'n' and 'm > n' samples, the variance of their difference is 'var * (1/n - 1/m)',
+// while the one of the 'm' samples mean is 'var / m', so the scaled difference measures the latter
+pub(crate) struct Convergence {
+    snapshot: Option<Snapshot>,
+    relative_error: Option<f64>,
+}
+
+struct Snapshot {
+    samples: u32,
+    luminance: Vec<f32>,
+}
+
+impl Convergence {
+    // fewer samples give too noisy an estimate of the error itself
+    pub(crate) const FIRST_SNAPSHOT_SAMPLES: u32 = 8;
+
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self { snapshot: None, relative_error: None }
+    }
+
+    // the accumulation has restarted
+    pub(crate) fn forget(&mut self) {
+        self.snapshot = None;
+        self.relative_error = None;
+    }
+
+    // the samples count drops when the accumulation restarts
+    pub(crate) fn forget_if_restarted(&mut self, samples: u32) {
+        if self.snapshot.as_ref().is_some_and(|snapshot| samples < snapshot.samples) {
+            self.forget();
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn relative_error(&self) -> Option<f64> {
+        self.relative_error
+    }
+
+    #[must_use]
+    pub(crate) fn snapshot_due(&self, samples: u32) -> bool {
+        match &self.snapshot {
+            Some(snapshot) => samples >= snapshot.samples * 2,
+            None => samples >= Self::FIRST_SNAPSHOT_SAMPLES,
+        }
+    }
+
+    // the pixels hold the sums of the 'samples' samples
+    pub(crate) fn take_snapshot(&mut self, samples: u32, accumulated: &[PodVector]) {
+        assert!(samples > 0, "no samples to take the snapshot of");
+        let luminance: Vec<f32> = accumulated.iter()
+            .map(|sum| (0.2126 * sum.x + 0.7152 * sum.y + 0.0722 * sum.z) / samples as f32)
+            .collect();
+        if let Some(previous) = self.snapshot.as_ref().filter(|previous| previous.samples < samples && previous.luminance.len() == luminance.len()) {
+            let scale = (f64::from(previous.samples) / f64::from(samples - previous.samples)).sqrt();
+            self.relative_error = Some(scale * Self::relative_difference(&previous.luminance, &luminance));
+        }
+        self.snapshot = Some(Snapshot { samples, luminance });
+    }
+
+    // zero for a black image: there is nothing to converge
+    #[must_use]
+    fn relative_difference(previous: &[f32], current: &[f32]) -> f64 {
+        let (difference, magnitude) = previous.iter().zip(current)
+            .filter(|(previous, current)| previous.is_finite() && current.is_finite())
+            .fold((0.0, 0.0), |(difference, magnitude), (&previous, &current)| {
+                let delta = f64::from(current - previous);
+                (difference + delta * delta, magnitude + f64::from(current) * f64::from(current))
+            });
+        if magnitude > 0.0 { (difference / magnitude).sqrt() } else { 0.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::assert_abs_diff_eq;
+
+    #[must_use]
+    fn make_gray(sums: &[f32]) -> Vec<PodVector> {
+        sums.iter().map(|&sum| PodVector::new(sum, sum, sum)).collect()
+    }
+
+    #[test]
+    fn test_snapshots_follow_doubling_samples() {
+        let mut system_under_test = Convergence::new();
+
+        assert!(false == system_under_test.snapshot_due(Convergence::FIRST_SNAPSHOT_SAMPLES - 1));
+        assert!(system_under_test.snapshot_due(Convergence::FIRST_SNAPSHOT_SAMPLES));
+
+        system_under_test.take_snapshot(8, &make_gray(&[8.0, 8.0]));
+
+        assert_eq!(system_under_test.relative_error(), None);
+        assert!(false == system_under_test.snapshot_due(15));
+        assert!(system_under_test.snapshot_due(16));
+    }
+
+    #[test]
+    fn test_relative_error() {
+        let mut system_under_test = Convergence::new();
+        system_under_test.take_snapshot(8, &make_gray(&[8.0, 8.0]));
+
+        // the means went from (1, 1) to (1.1, 0.9)
+        system_under_test.take_snapshot(16, &make_gray(&[17.6, 14.4]));
+
+        let expected_error = (0.02_f64 / 2.02).sqrt();
+        assert_abs_diff_eq!(system_under_test.relative_error().unwrap(), expected_error, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_forget() {
+        let mut system_under_test = Convergence::new();
+        system_under_test.take_snapshot(8, &make_gray(&[8.0]));
+        system_under_test.take_snapshot(16, &make_gray(&[20.0]));
+
+        system_under_test.forget_if_restarted(16);
+        assert!(system_under_test.relative_error().is_some());
+
+        system_under_test.forget_if_restarted(1);
+
+        assert_eq!(system_under_test.relative_error(), None);
+        assert!(system_under_test.snapshot_due(Convergence::FIRST_SNAPSHOT_SAMPLES));
+    }
+
+    #[test]
+    fn test_black_image_is_converged() {
+        let mut system_under_test = Convergence::new();
+        system_under_test.take_snapshot(8, &make_gray(&[0.0, 0.0]));
+        system_under_test.take_snapshot(16, &make_gray(&[0.0, 0.0]));
+
+        assert_eq!(system_under_test.relative_error(), Some(0.0));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::gpu::bind_group_builder::BindGroupBuilder;
+use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::gpu::pipeline_code::PipelineCode;
+use crate::gpu::pipelines_factory::{ComputeRoutineEntryPoint, PipelinesFactory};
+use crate::gpu::resources::Resources;
+use crate::gpu::uniforms::Uniforms;
+use bytemuck::{Pod, Zeroable};
+use std::rc::Rc;
+use wgpu::BufferUsages;
+
+pub(crate) const DENOISED_BLEND_GPU_CODE: &str = include_str!("../../shader/_denoised_blend.wgsl");
+
+// must match 'DenoisedBlend' of the shader
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable, PartialEq, Debug)]
+struct DenoisedBlendUniforms {
+    frame_width: u32,
+    frame_height: u32,
+    noisy_weight: f32,
+    padding: f32,
+}
+
+// the image the output pass shows with the denoiser: the last denoised one along with the samples
+// accumulated since it was made
+pub(super) struct DenoisedBlendPass {
+    pipeline: wgpu::ComputePipeline,
+    uniforms: Rc<wgpu::Buffer>,
+    blended_buffer: Rc<wgpu::Buffer>,
+    frame_buffer_size: Option<FrameBufferSize>,
+    // the denoising is off: the output pass reads the accumulated image instead
+    bypassed: bool,
+}
+
+impl DenoisedBlendPass {
+    const BIND_GROUP_INDEX: u32 = 0;
+    const BIND_GROUP_PARAMETERS_SLOT: u32 = 0;
+    const BIND_GROUP_NOISY_SLOT: u32 = 1;
+    const BIND_GROUP_DENOISED_SLOT: u32 = 2;
+    const BIND_GROUP_BLENDED_SLOT: u32 = 3;
+
+    #[must_use]
+    pub(super) fn new(resources: &Resources, pipelines_factory: &mut PipelinesFactory) -> Self {
+        let module = resources.create_shader_module("denoised blend shader", DENOISED_BLEND_GPU_CODE);
+        let code = PipelineCode::new(module, seahash::hash(DENOISED_BLEND_GPU_CODE.as_bytes()), "denoised_blend_code".to_string());
+        Self {
+            pipeline: pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::DenoisedBlend, &code),
+            uniforms: resources.create_uniform_buffer("denoised blend", bytemuck::bytes_of(&DenoisedBlendUniforms::zeroed())),
+            // an empty storage buffer can not be bound
+            blended_buffer: resources.create_buffer("denoised blend", BufferUsages::STORAGE, bytemuck::cast_slice(&[0.0_f32; 4])),
+            frame_buffer_size: None,
+            bypassed: false,
+        }
+    }
+
+    // read by the output pass, so it is to be bound again when replaced
+    #[must_use]
+    pub(super) fn blended_buffer(&self) -> Rc<wgpu::Buffer> {
+        self.blended_buffer.clone()
+    }
+
+    #[must_use]
+    pub(super) fn bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    pub(super) fn set_bypassed(&mut self, bypassed: bool) {
+        self.bypassed = bypassed;
+    }
+
+    // true when the blended buffer has been replaced
+    #[must_use]
+    pub(super) fn fit(&mut self, frame_buffer_size: FrameBufferSize, device: &wgpu::Device) -> bool {
+        if self.frame_buffer_size == Some(frame_buffer_size) {
+            return false;
+        }
+        self.frame_buffer_size = Some(frame_buffer_size);
+        let required_size = u64::from(frame_buffer_size.area()) * size_of::<[f32; 4]>() as u64;
+        if self.blended_buffer.size() >= required_size {
+            return false;
+        }
+        self.blended_buffer = Rc::new(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("denoised blend"),
+            size: required_size,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        }));
+        true
+    }
+
+    // the denoised image made of 'denoised_frame_number' samples, the noisy one of 'frame_number'
+    #[must_use]
+    pub(super) fn noisy_weight(denoised_frame_number: u32, frame_number: u32) -> f32 {
+        if frame_number <= denoised_frame_number {
+            return 0.0;
+        }
+        1.0 - denoised_frame_number as f32 / frame_number as f32
+    }
+
+    pub(super) fn run(&self, encoder: &mut wgpu::CommandEncoder, device: &wgpu::Device, queue: &wgpu::Queue, noisy: Rc<wgpu::Buffer>, denoised: Rc<wgpu::Buffer>, noisy_weight: f32) {
+        let frame_buffer_size = self.frame_buffer_size.expect("the blended buffer is to be fitted first");
+        let uniforms = DenoisedBlendUniforms {
+            frame_width: frame_buffer_size.width(),
+            frame_height: frame_buffer_size.height(),
+            noisy_weight,
+            padding: 0.0,
+        };
+        queue.write_buffer(&self.uniforms, 0, bytemuck::bytes_of(&uniforms));
+
+        let mut bind_group_builder = BindGroupBuilder::new(Self::BIND_GROUP_INDEX, Some("denoised blend pipeline group"), self.pipeline.get_bind_group_layout(Self::BIND_GROUP_INDEX));
+        bind_group_builder
+            .set_storage_entry(Self::BIND_GROUP_PARAMETERS_SLOT, self.uniforms.clone())
+            .set_storage_entry(Self::BIND_GROUP_NOISY_SLOT, noisy)
+            .set_storage_entry(Self::BIND_GROUP_DENOISED_SLOT, denoised)
+            .set_storage_entry(Self::BIND_GROUP_BLENDED_SLOT, self.blended_buffer.clone())
+        ;
+        let bind_group = bind_group_builder.make_bind_group(device);
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("denoised blend compute pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(Self::BIND_GROUP_INDEX, &bind_group, &[]);
+        let work_groups_needed = Uniforms::work_groups_count_of(frame_buffer_size);
+        pass.dispatch_workgroups(work_groups_needed.x, work_groups_needed.y, work_groups_needed.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noisy_weight() {
+        assert_eq!(DenoisedBlendPass::noisy_weight(8, 8), 0.0);
+        assert_eq!(DenoisedBlendPass::noisy_weight(8, 32), 0.75);
+        assert_eq!(DenoisedBlendPass::noisy_weight(0, 4), 1.0);
+    }
+
+    #[test]
+    fn test_noisy_weight_after_accumulation_restart() {
+        assert_eq!(DenoisedBlendPass::noisy_weight(40, 4), 0.0);
+    }
+}
@@ -34,6 +34,11 @@ impl FrameBufferSize {
         self.width as f32 / self.height as f32
     }
     
+    #[must_use]
+    pub(crate) fn half_resolution(&self) -> Self {
+        Self::new(self.width.div_ceil(2), self.height.div_ceil(2))
+    }
+    
     #[must_use]
     pub(crate) fn work_groups_count(&self, work_group_size: Vector2<u32>) -> Vector3<u32> {
         Vector3::<u32>::new(
@@ -76,6 +81,22 @@ mod tests {
         assert_eq!(actual_count, expected_count);
     }
 
+    #[test]
+    fn test_half_resolution() {
+        let system_under_test = FrameBufferSize::new(801, 600);
+
+        let actual_size = system_under_test.half_resolution();
+
+        assert_eq!(actual_size, FrameBufferSize::new(401, 300));
+    }
+
+    #[test]
+    fn test_half_resolution_of_single_pixel() {
+        let system_under_test = FrameBufferSize::new(1, 1);
+
+        assert_eq!(system_under_test.half_resolution(), system_under_test);
+    }
+
     #[test]
     fn test_aspect() {
         let width: u32 = 1000;
@@ -1,38 +1,127 @@
-#[cfg(test)]
-pub(crate) mod tests {
-    use crate::backend_vulkan_or_primary;
-    use crate::gpu::adapter_features::AdapterFeatures;
-    use crate::gpu::context::Context;
-    use std::rc::Rc;
-    use std::sync::OnceLock;
-    use wgpu::{Instance, Trace};
+use crate::gpu::adapter_features::{log_adapter_info, AdapterFeatures};
+use crate::gpu::context::Context;
+use crate::EngineInstantiationError;
+use wgpu::{Instance, Trace};
+#[cfg(any(test, feature = "gpu_testing"))]
+use crate::gpu::scaffolding::backend_vulkan_or_primary;
+#[cfg(any(test, feature = "gpu_testing"))]
+use std::sync::OnceLock;
+#[cfg(feature = "gpu_testing")]
+use std::rc::Rc;
 
-    const HEADLESS_DEVICE_LABEL: &str = "Rust Tracer Library Headless Device";
+pub(crate) async fn create_headless_wgpu_device(instance: &Instance, label: &str) -> Result<Context, EngineInstantiationError> {
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            ..Default::default()
+        })
+        .await
+        .map_err(|error| EngineInstantiationError::AdapterRequisitionError{what: error.to_string()})?;
 
-    /*
-    Why a singleton, given that it’s one of the worst anti-patterns?
+    let adapter_info = adapter.get_info();
+    log_adapter_info(&adapter_info);
 
-    In theory, each test could create its own instance. In practice,
-    it turned out that on Windows, when tests are run massively in
-    parallel, they can’t always get a separate VULKAN instance. If the
-    implementation type is specified explicitly, some tests fail when
-    trying to obtain it. But if we request a PRIMARY one, then some
-    tests end up running under DirectX, where we have stability issues.
+    let features = AdapterFeatures::new(&adapter);
 
-    That’s why we use exactly one explicitly chosen instance for all tests.
-    */
-    static THE_INSTANCE_FOR_TESTS: OnceLock<Instance> = OnceLock::new();
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some(label),
+                required_features: features.desired_features(),
+                required_limits: wgpu::Limits::default(),
+                memory_hints: wgpu::MemoryHints::default(),
+                trace: Trace::Off,
+            },
+        )
+        .await
+        .map_err(|error| EngineInstantiationError::DeviceSelectionError {what: error.to_string()})?;
 
-    #[must_use]
-    pub(crate) fn get_wgpu_single_instance() -> &'static Instance {
-        THE_INSTANCE_FOR_TESTS.get_or_init(|| {
-            Instance::new(&wgpu::InstanceDescriptor {
-                backends: backend_vulkan_or_primary(),
-                ..Default::default()
-            })
+    Ok(Context::new(device, queue, features.pipeline_caching_supported(), features.timestamp_queries_supported(), adapter_info,))
+}
+
+#[cfg(any(test, feature = "gpu_testing"))]
+const HEADLESS_DEVICE_LABEL: &str = "Rust Tracer Library Headless Device";
+
+/*
+Why a singleton, given that it’s one of the worst anti-patterns?
+
+In theory, each test could create its own instance. In practice,
+it turned out that on Windows, when tests are run massively in
+parallel, they can’t always get a separate VULKAN instance. If the
+implementation type is specified explicitly, some tests fail when
+trying to obtain it. But if we request a PRIMARY one, then some
+tests end up running under DirectX, where we have stability issues.
+
+That’s why we use exactly one explicitly chosen instance for all tests.
+*/
+#[cfg(any(test, feature = "gpu_testing"))]
+static THE_INSTANCE_FOR_TESTS: OnceLock<Instance> = OnceLock::new();
+
+#[cfg(any(test, feature = "gpu_testing"))]
+#[must_use]
+pub(crate) fn get_wgpu_single_instance() -> &'static Instance {
+    THE_INSTANCE_FOR_TESTS.get_or_init(|| {
+        Instance::new(&wgpu::InstanceDescriptor {
+            backends: backend_vulkan_or_primary(),
+            ..Default::default()
         })
+    })
+}
+
+// a device for the gpu tests of the crates embedding the library; all of them share one wgpu instance
+#[cfg(feature = "gpu_testing")]
+pub struct HeadlessContext {
+    context: Rc<Context>,
+}
+
+#[cfg(feature = "gpu_testing")]
+impl HeadlessContext {
+    pub fn new() -> Result<Self, EngineInstantiationError> {
+        let context = pollster::block_on(create_headless_wgpu_device(get_wgpu_single_instance(), HEADLESS_DEVICE_LABEL))?;
+        Ok(Self { context: Rc::new(context) })
+    }
+
+    // none when the machine has no suitable adapter, so a test can return early instead of failing;
+    // the other failures (the adapter is there, but refuses the device) are bugs and panic
+    #[must_use]
+    pub fn new_or_skip(test_name: &str) -> Option<Self> {
+        match Self::new() {
+            Ok(context) => Some(context),
+            Err(EngineInstantiationError::AdapterRequisitionError { what }) => {
+                eprintln!("skipping '{test_name}': no gpu adapter ({what})");
+                None
+            }
+            Err(error) => panic!("failed to create headless context: {error}"),
+        }
+    }
+
+    #[must_use]
+    pub fn device(&self) -> &wgpu::Device {
+        self.context.device()
     }
 
+    #[must_use]
+    pub fn queue(&self) -> &wgpu::Queue {
+        self.context.queue()
+    }
+
+    #[must_use]
+    pub fn adapter_info(&self) -> &wgpu::AdapterInfo {
+        self.context.adapter_info()
+    }
+
+    #[must_use]
+    pub(crate) fn context(&self) -> Rc<Context> {
+        self.context.clone()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::rc::Rc;
+
     #[must_use]
     pub(crate) fn create_headless_wgpu_vulkan_context() -> Rc<Context> {
         Rc::new(pollster::block_on(create_headless_wgpu_device_async(get_wgpu_single_instance())))
@@ -40,16 +129,9 @@ pub(crate) mod tests {
 
     #[must_use]
     pub(crate) async fn create_headless_wgpu_device_async(instance: &Instance) -> Context {
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: None,
-                ..Default::default()
-            })
-            .await
-            .expect("failed to find an adapter");
-
-        let adapter_info = adapter.get_info();
+        let context = create_headless_wgpu_device(instance, HEADLESS_DEVICE_LABEL).await.expect("failed to create headless device");
+
+        let adapter_info = context.adapter_info();
         println!(
             "Adapter Info:\n\
             Name: {}\n\
@@ -59,22 +141,18 @@ pub(crate) mod tests {
             adapter_info.backend,
             adapter_info.device_type,
         );
-        
-        let features = AdapterFeatures::new(&adapter);
-        
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: Some(HEADLESS_DEVICE_LABEL),
-                    required_features: features.desired_features(),
-                    required_limits: wgpu::Limits::default(),
-                    memory_hints: wgpu::MemoryHints::default(),
-                    trace: Trace::Off,
-                },
-            )
-            .await
-            .expect("failed to create device");
-
-        Context::new(device, queue, features.pipeline_caching_supported(), adapter_info,)
+
+        context
+    }
+
+    #[cfg(feature = "gpu_testing")]
+    #[test]
+    fn test_headless_context_or_skip() {
+        let Some(system_under_test) = HeadlessContext::new_or_skip("test_headless_context_or_skip") else {
+            return;
+        };
+
+        assert_eq!(system_under_test.device().limits(), system_under_test.context().device().limits());
+        assert!(false == system_under_test.adapter_info().name.is_empty());
     }
-}
\ No newline at end of file
+}
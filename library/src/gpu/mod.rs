@@ -1,22 +1,41 @@
 pub(super) mod color_buffer_evaluation;
 
+pub(crate) mod accumulation_checkpoint;
+
 pub(crate) mod resources;
 pub(crate) mod headless_device;
 pub(crate) mod render;
 pub(crate) mod frame_buffer_size;
 pub(crate) mod context;
+pub(crate) mod convergence;
 pub(crate) mod output;
 pub(crate) mod compute_pipeline;
 pub(crate) mod bind_group_builder;
 
 mod binding_groups;
 mod rasterization_pipeline;
+mod overlay_pass;
+mod selection_outline_pass;
+mod bloom_pass;
+#[cfg(feature = "denoiser")]
+mod denoised_blend_pass;
+mod composed_shader;
+mod background_shader_compilation;
+mod picking_pass;
+mod pass_profiler;
+mod pass_timer;
 mod versioned_buffer;
 mod buffers_update_status;
 pub(crate) mod pipelines_factory;
+pub(crate) mod pipelines_warm_up;
 pub(crate) mod pipeline_code;
 pub(crate) mod adapter_features;
 mod resizable_buffer;
 pub(crate) mod scaffolding;
 pub(crate) mod uniforms;
-mod bitmap_textures;
\ No newline at end of file
+pub(crate) mod temporal_reprojection;
+pub(crate) mod viewport;
+mod scene_bvh;
+mod bitmap_textures;
+#[cfg(feature = "monte_carlo")]
+mod wavefront;
\ No newline at end of file
@@ -18,15 +18,32 @@ impl<T: Sized + AnyBitPattern + Pod> DuplexLayer<T> {
     }
 
     pub(crate) fn prepare_cpu_read(&self, encoder: &mut wgpu::CommandEncoder) {
-        self.gpu_located_part.issue_copy_to_cpu_mediator(encoder)
+        let issued = self.gpu_located_part.issue_copy_to_cpu_mediator(encoder);
+        assert!(issued, "no vacant cpu mediator: the pending reads have to be collected first");
     }
-    
-    pub(crate) fn read_cpu_copy(&mut self) -> impl Future<Output = ()> {
-        self.gpu_located_part.read_cpu_mediator(|data| {
-            self.last_read.clear();
-            self.last_read.extend(data);
+
+    pub(crate) fn request_cpu_read(&self) {
+        self.gpu_located_part.request_cpu_mediator_mapping();
+    }
+
+    // the cpu copy gets the latest of the completed reads; false if none has completed yet
+    pub(crate) fn collect_cpu_read(&mut self) -> bool {
+        let last_read = &mut self.last_read;
+        self.gpu_located_part.consume_mapped_cpu_mediators(|data| {
+            last_read.clear();
+            last_read.extend(data);
         })
     }
+
+    #[must_use]
+    pub(crate) fn cpu_read_pending(&self) -> bool {
+        self.gpu_located_part.cpu_read_pending()
+    }
+
+    #[must_use]
+    pub(crate) fn can_prepare_cpu_read(&self) -> bool {
+        self.gpu_located_part.has_vacant_cpu_mediator()
+    }
     
     #[must_use]
     pub(crate) fn cpu_copy(&self) -> &Vec<T> {
@@ -43,6 +60,11 @@ impl<T: Sized + AnyBitPattern + Pod> DuplexLayer<T> {
         self.gpu_located_part.gpu_render_target()
     }
 
+    // the cpu copy is kept: it is the last read of the gpu one
+    pub(crate) fn fill_gpu_copy(&self, queue: &wgpu::Queue, data: &[T]) {
+        self.gpu_located_part.fill_render_target(queue, data);
+    }
+
     pub(super) fn invalidate_cpu_copy(&mut self) {
         self.last_read.clear();
     }
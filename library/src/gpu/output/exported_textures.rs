@@ -0,0 +1,153 @@
+use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::gpu::output::frame_buffer::FrameBuffer;
+use wgpu::{BufferAddress, CommandEncoder};
+
+// storage buffers can not be sampled, so the layers are copied into textures on request
+pub(crate) struct ExportedTextures {
+    frame_buffer_size: FrameBufferSize,
+
+    final_image: ExportedTexture,
+    albedo: ExportedTexture,
+    normal: ExportedTexture,
+    depth: ExportedTexture,
+    object_id: ExportedTexture,
+}
+
+impl ExportedTextures {
+    const SURFACE_ATTRIBUTE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba32Float;
+    const OBJECT_ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+    const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+
+    #[must_use]
+    pub(crate) fn new(device: &wgpu::Device, frame_buffer_size: FrameBufferSize, final_image_format: wgpu::TextureFormat) -> Self {
+        let copy_target = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC;
+        let render_target = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC;
+
+        Self {
+            frame_buffer_size,
+
+            final_image: ExportedTexture::new(device, frame_buffer_size, final_image_format, render_target, "exported final image"),
+            albedo: ExportedTexture::new(device, frame_buffer_size, Self::SURFACE_ATTRIBUTE_FORMAT, copy_target, "exported albedo"),
+            normal: ExportedTexture::new(device, frame_buffer_size, Self::SURFACE_ATTRIBUTE_FORMAT, copy_target, "exported normal"),
+            depth: ExportedTexture::new(device, frame_buffer_size, Self::DEPTH_FORMAT, copy_target, "exported depth"),
+            object_id: ExportedTexture::new(device, frame_buffer_size, Self::OBJECT_ID_FORMAT, copy_target, "exported object id"),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn frame_buffer_size(&self) -> FrameBufferSize {
+        self.frame_buffer_size
+    }
+
+    pub(crate) fn issue_copy_from(&self, encoder: &mut CommandEncoder, frame_buffer: &FrameBuffer) {
+        self.albedo.issue_copy_from(encoder, &frame_buffer.albedo_gpu());
+        self.normal.issue_copy_from(encoder, &frame_buffer.normal_at_gpu());
+        self.depth.issue_copy_from(encoder, &frame_buffer.depth_at_gpu());
+        self.object_id.issue_copy_from(encoder, &frame_buffer.object_id_at_gpu());
+    }
+
+    #[must_use]
+    pub(crate) fn final_image(&self) -> &wgpu::TextureView {
+        &self.final_image.view
+    }
+
+    #[must_use]
+    pub(crate) fn albedo(&self) -> &wgpu::TextureView {
+        &self.albedo.view
+    }
+
+    #[must_use]
+    pub(crate) fn normal(&self) -> &wgpu::TextureView {
+        &self.normal.view
+    }
+
+    #[must_use]
+    pub(crate) fn depth(&self) -> &wgpu::TextureView {
+        &self.depth.view
+    }
+
+    #[must_use]
+    pub(crate) fn object_id(&self) -> &wgpu::TextureView {
+        &self.object_id.view
+    }
+}
+
+struct ExportedTexture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl ExportedTexture {
+    #[must_use]
+    fn new(device: &wgpu::Device, frame_buffer_size: FrameBufferSize, format: wgpu::TextureFormat, usage: wgpu::TextureUsages, label: &str) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d { width: frame_buffer_size.width(), height: frame_buffer_size.height(), depth_or_array_layers: 1, },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self { texture, view }
+    }
+
+    fn issue_copy_from(&self, encoder: &mut CommandEncoder, source: &wgpu::Buffer) {
+        let size = self.texture.size();
+        let bytes_per_texel = self.texture.format().block_copy_size(None).expect("exported texture format must be copyable");
+        let bytes_per_row = size.width * bytes_per_texel;
+
+        if bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0 {
+            Self::issue_copy_of_rows(encoder, source, 0, &self.texture, 0, size.height, Some(bytes_per_row));
+            return;
+        }
+
+        // frame buffer rows are tightly packed: the unaligned pitch is allowed for single row copies only
+        for row in 0..size.height {
+            let offset = (row * bytes_per_row) as BufferAddress;
+            Self::issue_copy_of_rows(encoder, source, offset, &self.texture, row, 1, None);
+        }
+    }
+
+    fn issue_copy_of_rows(encoder: &mut CommandEncoder, source: &wgpu::Buffer, offset: BufferAddress, destination: &wgpu::Texture, first_row: u32, rows_count: u32, bytes_per_row: Option<u32>) {
+        encoder.copy_buffer_to_texture(
+            wgpu::TexelCopyBufferInfo {
+                buffer: source,
+                layout: wgpu::TexelCopyBufferLayout { offset, bytes_per_row, rows_per_image: None, },
+            },
+            wgpu::TexelCopyTextureInfo {
+                texture: destination,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x: 0, y: first_row, z: 0, },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d { width: destination.width(), height: rows_count, depth_or_array_layers: 1, },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
+    use wgpu::CommandEncoderDescriptor;
+
+    #[test]
+    fn test_copy_with_unaligned_rows() {
+        let context = create_headless_wgpu_vulkan_context();
+        let frame_buffer_size = FrameBufferSize::new(37, 11);
+        let frame_buffer = FrameBuffer::new(context.device(), frame_buffer_size, false);
+        let system_under_test = ExportedTextures::new(context.device(), frame_buffer_size, wgpu::TextureFormat::Rgba8Unorm);
+
+        let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
+        system_under_test.issue_copy_from(&mut encoder, &frame_buffer);
+        context.queue().submit(Some(encoder.finish()));
+        context.wait(None);
+
+        assert_eq!(system_under_test.albedo.texture.width(), frame_buffer_size.width());
+        assert_eq!(system_under_test.object_id.texture.format(), wgpu::TextureFormat::R32Uint);
+        assert_eq!(system_under_test.depth.texture.format(), wgpu::TextureFormat::R32Float);
+    }
+}
@@ -3,27 +3,64 @@ use crate::gpu::output::duplex_layer::DuplexLayer;
 use crate::serialization::pod_vector::PodVector;
 use std::rc::Rc;
 use wgpu::Buffer;
-use crate::gpu::output::frame_buffer_layer::SupportUpdateFromCpu;
+use crate::gpu::output::frame_buffer_layer::{FrameBufferLayer, SupportUpdateFromCpu};
 
 pub(crate) struct FrameBuffer {
     object_id: DuplexLayer<u32>,
     
     albedo: DuplexLayer<PodVector>,
     normal: DuplexLayer<PodVector>,
+    depth: DuplexLayer<f32>,
+    // in pixels, from the pixel to where its surface point was seen the frame before
+    motion_vector: FrameBufferLayer<[f32; 2]>,
 
     noisy_pixel_color: DuplexLayer<PodVector>,
+
+    // the summed direct light, indirect light and emission of the Monte Carlo samples, the samples count in w;
+    // a single texel stands in while the lighting aovs are off
+    lighting: DuplexLayer<[PodVector; LIGHTING_AOVS_PER_PIXEL]>,
+    lighting_allocated: bool,
+
+    #[cfg(feature = "monte_carlo")]
+    indirect_half_resolution: FrameBufferLayer<PodVector>,
+    #[cfg(feature = "monte_carlo")]
+    indirect_half_resolution_guide: FrameBufferLayer<PodVector>,
+    // the direct light of the half resolution indirect strategy, summed at full resolution
+    #[cfg(feature = "monte_carlo")]
+    direct_full_resolution: FrameBufferLayer<PodVector>,
+
+    #[cfg(feature = "monte_carlo")]
+    reprojection_history: FrameBufferLayer<PodVector>,
 }
 
+pub(crate) const LIGHTING_AOVS_PER_PIXEL: usize = 3;
+
 impl FrameBuffer {
     #[must_use]
-    pub(crate) fn new(device: &wgpu::Device, frame_buffer_size: FrameBufferSize) -> Self {
+    pub(crate) fn new(device: &wgpu::Device, frame_buffer_size: FrameBufferSize, lighting_aovs: bool) -> Self {
+        let lighting_size = if lighting_aovs { frame_buffer_size } else { FrameBufferSize::new(1, 1) };
         Self {
             object_id: DuplexLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::No, "object id"),
             
             albedo: DuplexLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::No, "albedo"),
             normal: DuplexLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::No, "normal"),
+            depth: DuplexLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::No, "depth"),
+            motion_vector: FrameBufferLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::Yes, "motion vector"),
 
             noisy_pixel_color: DuplexLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::Yes, "noisy pixel color"),
+
+            lighting: DuplexLayer::new(device, lighting_size, SupportUpdateFromCpu::No, "lighting aovs"),
+            lighting_allocated: lighting_aovs,
+
+            #[cfg(feature = "monte_carlo")]
+            indirect_half_resolution: FrameBufferLayer::new(device, frame_buffer_size.half_resolution(), SupportUpdateFromCpu::Yes, "half resolution indirect"),
+            #[cfg(feature = "monte_carlo")]
+            indirect_half_resolution_guide: FrameBufferLayer::new(device, frame_buffer_size.half_resolution(), SupportUpdateFromCpu::No, "half resolution indirect guide"),
+            #[cfg(feature = "monte_carlo")]
+            direct_full_resolution: FrameBufferLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::Yes, "full resolution direct"),
+
+            #[cfg(feature = "monte_carlo")]
+            reprojection_history: FrameBufferLayer::new(device, frame_buffer_size, SupportUpdateFromCpu::No, "reprojection history"),
         }
     }
 
@@ -34,6 +71,7 @@ impl FrameBuffer {
     pub(crate) fn prepare_all_aux_buffers_copy_from_gpu(&self, encoder: &mut wgpu::CommandEncoder) {
         self.object_id.prepare_cpu_read(encoder);
         self.normal.prepare_cpu_read(encoder);
+        self.depth.prepare_cpu_read(encoder);
         self.albedo.prepare_cpu_read(encoder);
     }
     
@@ -44,28 +82,50 @@ impl FrameBuffer {
     pub(crate) fn prepare_object_id_copy_from_gpu(&self, encoder: &mut wgpu::CommandEncoder) {
         self.object_id.prepare_cpu_read(encoder);
     }
+
+    pub(crate) fn prepare_lighting_copy_from_gpu(&self, encoder: &mut wgpu::CommandEncoder) {
+        self.lighting.prepare_cpu_read(encoder);
+    }
     
-    pub(crate) fn copy_all_aux_buffers_from_gpu(&mut self) -> impl Future<Output = ()> {
-        let object_id_read = self.object_id.read_cpu_copy();
-        let normals_read = self.normal.read_cpu_copy();
-        let albedo_read = self.albedo.read_cpu_copy();
-        
-        async move {
-            futures::join!(object_id_read, normals_read, albedo_read);
-        }
+    // each layer keeps two copies in flight at most: the pending ones have to be collected before preparing more
+    #[must_use]
+    pub(crate) fn can_prepare_copies_from_gpu(&self) -> bool {
+        self.object_id.can_prepare_cpu_read()
+            && self.albedo.can_prepare_cpu_read()
+            && self.normal.can_prepare_cpu_read()
+            && self.depth.can_prepare_cpu_read()
+            && self.noisy_pixel_color.can_prepare_cpu_read()
+            && self.lighting.can_prepare_cpu_read()
     }
 
-    #[cfg(any(test, feature = "denoiser"))]
-    pub(crate) fn copy_pixel_colors_from_gpu(&mut self) -> impl Future<Output = ()> {
-        self.noisy_pixel_color.read_cpu_copy()
+    // must follow the submission of the encoders the copies are prepared in
+    pub(crate) fn request_copies_from_gpu(&self) {
+        self.object_id.request_cpu_read();
+        self.albedo.request_cpu_read();
+        self.normal.request_cpu_read();
+        self.depth.request_cpu_read();
+        self.noisy_pixel_color.request_cpu_read();
+        self.lighting.request_cpu_read();
     }
-    
-    pub(crate) fn copy_albedo_from_gpu(&mut self) -> impl Future<Output = ()> {
-        self.albedo.read_cpu_copy()
+
+    // takes the copies the last device poll has completed
+    pub(crate) fn collect_copies_from_gpu(&mut self) {
+        let _ = self.object_id.collect_cpu_read();
+        let _ = self.albedo.collect_cpu_read();
+        let _ = self.normal.collect_cpu_read();
+        let _ = self.depth.collect_cpu_read();
+        let _ = self.noisy_pixel_color.collect_cpu_read();
+        let _ = self.lighting.collect_cpu_read();
     }
 
-    pub(crate) fn copy_object_id_from_gpu(&mut self) -> impl Future<Output = ()> {
-        self.object_id.read_cpu_copy()
+    #[must_use]
+    pub(crate) fn copies_from_gpu_pending(&self) -> bool {
+        self.object_id.cpu_read_pending()
+            || self.albedo.cpu_read_pending()
+            || self.normal.cpu_read_pending()
+            || self.depth.cpu_read_pending()
+            || self.noisy_pixel_color.cpu_read_pending()
+            || self.lighting.cpu_read_pending()
     }
 
     #[must_use]
@@ -73,6 +133,45 @@ impl FrameBuffer {
         self.noisy_pixel_color.gpu_copy()
     }
 
+    #[must_use]
+    pub(crate) fn lighting_at_gpu(&self) -> Rc<Buffer> {
+        self.lighting.gpu_copy()
+    }
+
+    #[must_use]
+    pub(crate) fn lighting_at_cpu(&self) -> &Vec<[PodVector; LIGHTING_AOVS_PER_PIXEL]> {
+        self.lighting.cpu_copy()
+    }
+
+    #[must_use]
+    pub(crate) fn lighting_allocated(&self) -> bool {
+        self.lighting_allocated
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(crate) fn indirect_half_resolution_at_gpu(&self) -> Rc<Buffer> {
+        self.indirect_half_resolution.gpu_render_target()
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(crate) fn indirect_half_resolution_guide_at_gpu(&self) -> Rc<Buffer> {
+        self.indirect_half_resolution_guide.gpu_render_target()
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(crate) fn direct_full_resolution_at_gpu(&self) -> Rc<Buffer> {
+        self.direct_full_resolution.gpu_render_target()
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(crate) fn reprojection_history_at_gpu(&self) -> Rc<Buffer> {
+        self.reprojection_history.gpu_render_target()
+    }
+
     #[must_use]
     pub(crate) fn object_id_at_gpu(&self) -> Rc<Buffer> {
         self.object_id.gpu_copy()
@@ -83,6 +182,16 @@ impl FrameBuffer {
         self.normal.gpu_copy()
     }
 
+    #[must_use]
+    pub(crate) fn depth_at_gpu(&self) -> Rc<Buffer> {
+        self.depth.gpu_copy()
+    }
+
+    #[must_use]
+    pub(crate) fn motion_vector_at_gpu(&self) -> Rc<Buffer> {
+        self.motion_vector.gpu_render_target()
+    }
+
     #[must_use]
     pub(crate) fn albedo_gpu(&self) -> Rc<Buffer> {
         self.albedo.gpu_copy()
@@ -92,20 +201,46 @@ impl FrameBuffer {
     pub(crate) fn object_id_at_cpu(&self) -> &Vec<u32> {
         self.object_id.cpu_copy()
     }
+
+    #[must_use]
+    pub(crate) fn normal_at_cpu(&self) -> &Vec<PodVector> {
+        self.normal.cpu_copy()
+    }
+
+    #[must_use]
+    pub(crate) fn depth_at_cpu(&self) -> &Vec<f32> {
+        self.depth.cpu_copy()
+    }
+
+    #[must_use]
+    pub(crate) fn albedo_at_cpu(&self) -> &Vec<PodVector> {
+        self.albedo.cpu_copy()
+    }
     
     #[must_use] #[cfg(feature = "denoiser")]
     pub(crate) fn denoiser_input(&mut self) -> (&mut Vec<PodVector>, &Vec<PodVector>, &Vec<PodVector>) {
         (self.noisy_pixel_color.mutable_cpu_copy(), self.albedo.cpu_copy(), self.normal.cpu_copy())
     }
 
-    #[cfg(test)]
+    // lands before the commands of the next submission
+    pub(crate) fn fill_noisy_pixel_color(&self, queue: &wgpu::Queue, data: &[PodVector]) {
+        self.noisy_pixel_color.fill_gpu_copy(queue, data);
+    }
+
+    #[must_use]
     pub(crate) fn noisy_pixel_color_at_cpu(&self) -> &Vec<PodVector> {
         self.noisy_pixel_color.cpu_copy()
     }
     
+    // neither read back nor on the way
     #[must_use]
     pub(crate) fn albedo_at_cpu_is_absent(&self) -> bool {
-        self.albedo.cpu_copy().is_empty()
+        self.albedo.cpu_copy().is_empty() && false == self.albedo.cpu_read_pending()
+    }
+
+    #[must_use]
+    pub(crate) fn object_id_at_cpu_is_absent(&self) -> bool {
+        self.object_id.cpu_copy().is_empty() && false == self.object_id.cpu_read_pending()
     }
     
     pub(crate) fn invalidate_cpu_copies(&mut self) {
@@ -113,6 +248,8 @@ impl FrameBuffer {
         self.noisy_pixel_color.invalidate_cpu_copy();
         self.albedo.invalidate_cpu_copy();
         self.normal.invalidate_cpu_copy();
+        self.depth.invalidate_cpu_copy();
+        self.lighting.invalidate_cpu_copy();
     }
 }
 
@@ -131,16 +268,33 @@ mod tests {
     fn test_construction() {
         let context = create_headless_wgpu_vulkan_context();
         
-        let system_under_test = FrameBuffer::new(context.device(), test_buffer_size());
+        let system_under_test = FrameBuffer::new(context.device(), test_buffer_size(), false);
         
         assert!(system_under_test.object_id_at_cpu().is_empty());
     }
 
+    #[test]
+    fn test_lighting_acquiring() {
+        let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = FrameBuffer::new(context.device(), test_buffer_size(), true);
+
+        let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
+        system_under_test.prepare_lighting_copy_from_gpu(&mut encoder);
+        context.queue().submit(Some(encoder.finish()));
+        system_under_test.request_copies_from_gpu();
+        context.wait(None);
+        system_under_test.collect_copies_from_gpu();
+
+        assert!(system_under_test.lighting_allocated());
+        assert_eq!(system_under_test.lighting_at_cpu().len(), test_buffer_size().area() as usize);
+    }
+
     #[test]
     fn test_object_id_acquiring() {
         let system_under_test = test_aux_buffers_reading();
         
         assert_eq!(system_under_test.object_id_at_cpu().len(), test_buffer_size().area() as usize);
+        assert_eq!(system_under_test.depth_at_cpu().len(), test_buffer_size().area() as usize);
     }
 
     #[test] #[cfg(feature = "denoiser")]
@@ -156,15 +310,17 @@ mod tests {
     fn test_aux_buffers_reading() -> FrameBuffer {
         let context = create_headless_wgpu_vulkan_context();
 
-        let mut system_under_test = FrameBuffer::new(context.device(), test_buffer_size());
+        let mut system_under_test = FrameBuffer::new(context.device(), test_buffer_size(), false);
 
         let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
         system_under_test.prepare_all_aux_buffers_copy_from_gpu(&mut encoder);
         context.queue().submit(Some(encoder.finish()));
 
-        let gpu_to_cpu_copy = system_under_test.copy_all_aux_buffers_from_gpu();
+        system_under_test.request_copies_from_gpu();
+        assert!(system_under_test.copies_from_gpu_pending());
         context.wait(None);
-        pollster::block_on(gpu_to_cpu_copy);
+        system_under_test.collect_copies_from_gpu();
+        assert!(false == system_under_test.copies_from_gpu_pending());
 
         system_under_test
     }
@@ -1,10 +1,11 @@
 use crate::gpu::frame_buffer_size::FrameBufferSize;
 use crate::gpu::output::utils::{create_frame_buffer_layer, frame_buffer_layer_size_bytes, FrameBufferLayerParameters, FrameBufferLayerParametersBuilder};
 use bytemuck::{AnyBitPattern, Pod};
-use futures_intrusive::channel::shared::oneshot_channel;
+use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
-use wgpu::{BufferAddress, BufferUsages, CommandEncoder};
+use std::sync::{Arc, OnceLock};
+use wgpu::{BufferAddress, BufferAsyncError, BufferUsages, CommandEncoder};
 
 #[derive(PartialEq)]
 pub(crate) enum SupportUpdateFromCpu {
@@ -12,9 +13,25 @@ pub(crate) enum SupportUpdateFromCpu {
     No,
 }
 
+enum MediatorState {
+    Vacant,
+    // the copy is recorded, the mapping is requested once the encoder is submitted
+    CopyRecorded { order: u64 },
+    // the device poll that finds the copy done fills the outcome
+    Mapping { order: u64, outcome: Arc<OnceLock<Result<(), BufferAsyncError>>> },
+}
+
+struct CpuMediator {
+    buffer: wgpu::Buffer,
+    state: RefCell<MediatorState>,
+}
+
+// the render target is read back through a pair of cpu mappable mediators: while one of them
+// waits for the gpu, the next frame copies into the other, so nobody blocks on the mapping
 pub(crate) struct FrameBufferLayer<T: Sized + AnyBitPattern + Pod> {
     gpu_located_render_target: Rc<wgpu::Buffer>,
-    cpu_mappable_mediator: wgpu::Buffer,
+    cpu_mappable_mediators: [CpuMediator; 2],
+    copies_recorded: RefCell<u64>,
     buffer_size_bytes: BufferAddress,
     
     _marker: PhantomData<T>,
@@ -35,14 +52,18 @@ impl<T: Sized + AnyBitPattern + Pod> FrameBufferLayer<T> {
         let mediator_usage = BufferUsages::MAP_READ | BufferUsages::COPY_DST;
         let mediator_label = format!("{} {}", marker, Self::LABEL_CPU_MAPPABLE_MEDIATOR);
         let parameters_cpu_mappable_mediator = Self::parameters(frame_buffer_size, mediator_usage, mediator_label.as_str());
-        let cpu_mappable_mediator = create_frame_buffer_layer(device, &parameters_cpu_mappable_mediator);
+        let cpu_mappable_mediators = [(); 2].map(|_| CpuMediator {
+            buffer: create_frame_buffer_layer(device, &parameters_cpu_mappable_mediator),
+            state: RefCell::new(MediatorState::Vacant),
+        });
 
         let buffer_size_bytes: BufferAddress = frame_buffer_layer_size_bytes(&parameters_cpu_mappable_mediator);
         debug_assert_eq!(buffer_size_bytes, frame_buffer_layer_size_bytes(&parameters_gpu_located_render_target));
 
         Self {
             gpu_located_render_target: Rc::new(gpu_located_copy),
-            cpu_mappable_mediator,
+            cpu_mappable_mediators,
+            copies_recorded: RefCell::new(0),
             buffer_size_bytes,
             
             _marker: PhantomData,
@@ -57,41 +78,90 @@ impl<T: Sized + AnyBitPattern + Pod> FrameBufferLayer<T> {
     const ZERO_SOURCE_OFFSET: BufferAddress = 0;
     const ZERO_DESTINATION_OFFSET: BufferAddress = 0;
 
-    pub(crate) fn issue_copy_to_cpu_mediator(&self, encoder: &mut CommandEncoder) {
+    // a mediator with the copy not yet submitted takes the newer one; false when both mediators are being mapped
+    #[must_use]
+    pub(crate) fn issue_copy_to_cpu_mediator(&self, encoder: &mut CommandEncoder) -> bool {
+        let target = self.cpu_mappable_mediators.iter().find(|mediator| matches!(*mediator.state.borrow(), MediatorState::CopyRecorded { .. }))
+            .or_else(|| self.cpu_mappable_mediators.iter().find(|mediator| matches!(*mediator.state.borrow(), MediatorState::Vacant)));
+        let Some(target) = target else {
+            return false;
+        };
+
         encoder.copy_buffer_to_buffer(
             &self.gpu_located_render_target,
             Self::ZERO_SOURCE_OFFSET,
-            &self.cpu_mappable_mediator,
+            &target.buffer,
             Self::ZERO_DESTINATION_OFFSET,
             self.buffer_size_bytes as BufferAddress,
         );
+        let order = self.copies_recorded.replace_with(|count| *count + 1);
+        target.state.replace(MediatorState::CopyRecorded { order });
+        true
     }
 
-    #[cfg(feature = "denoiser")]
-    pub(crate) fn fill_render_target(&self, queue: &wgpu::Queue, data: &[T]) {
-        assert!(size_of_val(data) <= self.buffer_size_bytes as usize);
-        queue.write_buffer(&self.gpu_located_render_target, Self::ZERO_DESTINATION_OFFSET, bytemuck::cast_slice(data));
+    #[must_use]
+    pub(crate) fn has_vacant_cpu_mediator(&self) -> bool {
+        self.cpu_mappable_mediators.iter().any(|mediator| false == matches!(*mediator.state.borrow(), MediatorState::Mapping { .. }))
+    }
+
+    #[must_use]
+    pub(crate) fn cpu_read_pending(&self) -> bool {
+        self.cpu_mappable_mediators.iter().any(|mediator| false == matches!(*mediator.state.borrow(), MediatorState::Vacant))
     }
 
-    pub(crate) fn read_cpu_mediator<ConsumeData: FnOnce(&[T])>(&self, consume: ConsumeData) -> impl Future<Output = ()> {
-        let cpu_mediator_slice = self.cpu_mappable_mediator.slice(..);
+    // must follow the submission of the encoder with the copy
+    pub(crate) fn request_cpu_mediator_mapping(&self) {
+        for mediator in &self.cpu_mappable_mediators {
+            let MediatorState::CopyRecorded { order } = *mediator.state.borrow() else {
+                continue;
+            };
+            let outcome = Arc::new(OnceLock::new());
+            let outcome_of_callback = outcome.clone();
+            mediator.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                outcome_of_callback.set(result).expect("cpu mediator mapping outcome is set twice");
+            });
+            mediator.state.replace(MediatorState::Mapping { order, outcome });
+        }
+    }
 
-        let (sender, receiver) = oneshot_channel();
-        cpu_mediator_slice.map_async(wgpu::MapMode::Read, move |result| {
-            sender.send(result).expect("cpu_mediator_slice.map_async executed the callback, but result send failed");
-        });
+    // hands the mapped mediators to the consumer, the older copy first; the mediators get vacant;
+    // a device poll has to run before, the mapping callbacks are called from it; the failed mappings
+    // are dropped, true if any mediator has been consumed
+    pub(crate) fn consume_mapped_cpu_mediators<ConsumeData: FnMut(&[T])>(&self, mut consume: ConsumeData) -> bool {
+        let mut mapped: Vec<(u64, &CpuMediator)> = self.cpu_mappable_mediators.iter()
+            .filter_map(|mediator| match &*mediator.state.borrow() {
+                MediatorState::Mapping { order, outcome } if outcome.get().is_some() => Some((*order, mediator)),
+                _ => None,
+            })
+            .collect();
+        mapped.sort_by_key(|(order, _)| *order);
 
-        async move {
-            let map_result = receiver.receive().await;
-            map_result.expect("the result of 'map' operation is unknown").expect("'map' operation has failed");
+        let mut consumed = false;
+        for (_, mediator) in &mapped {
+            let failure = match &*mediator.state.borrow() {
+                MediatorState::Mapping { outcome, .. } => outcome.get().unwrap().as_ref().err().cloned(),
+                _ => None,
+            };
+            if let Some(error) = failure {
+                // a failed 'map' (e.g. after a device loss) leaves the buffer unmapped: the readback is dropped
+                log::warn!("frame buffer readback is dropped: {error}");
+                mediator.state.replace(MediatorState::Vacant);
+                continue;
+            }
             {
-                let raw_data = cpu_mediator_slice.get_mapped_range();
-                let object_ids: &[T] = bytemuck::cast_slice(&raw_data);
-                consume(object_ids);
+                let raw_data = mediator.buffer.slice(..).get_mapped_range();
+                consume(bytemuck::cast_slice(&raw_data));
             }
-            
-            self.cpu_mappable_mediator.unmap();
+            mediator.buffer.unmap();
+            mediator.state.replace(MediatorState::Vacant);
+            consumed = true;
         }
+        consumed
+    }
+
+    pub(crate) fn fill_render_target(&self, queue: &wgpu::Queue, data: &[T]) {
+        assert!(size_of_val(data) <= self.buffer_size_bytes as usize);
+        queue.write_buffer(&self.gpu_located_render_target, Self::ZERO_DESTINATION_OFFSET, bytemuck::cast_slice(data));
     }
 
     #[must_use]
@@ -110,7 +180,6 @@ mod tests {
     use super::*;
     use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
     use futures_intrusive::channel::shared::oneshot_channel;
-    use std::cell::RefCell;
     use wgpu::wgt::PollType;
     use wgpu::{CommandEncoderDescriptor, PollStatus};
 
@@ -135,22 +204,63 @@ mod tests {
         let context = create_headless_wgpu_vulkan_context();
         let buffer_size = test_buffer_size();
         let system_under_test = FrameBufferLayer::<u32>::new(context.device(), buffer_size, SupportUpdateFromCpu::No, "test layer");
-        let callback_spy_call_counter = Rc::new(RefCell::new(0_u32));
+        let mut callback_spy_call_counter = 0_u32;
 
-        let read_callback = system_under_test.read_cpu_mediator(|data| {
-            assert_eq!(data.len(), buffer_size.area() as usize);
-            *callback_spy_call_counter.borrow_mut() += 1;
-        });
+        let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
+        assert!(system_under_test.issue_copy_to_cpu_mediator(&mut encoder));
+        context.queue().submit(Some(encoder.finish()));
+        system_under_test.request_cpu_mediator_mapping();
         let poll_status = context.wait(None);
         assert_eq!(poll_status, PollStatus::QueueEmpty);
-        pollster::block_on(read_callback);
+        let consumed = system_under_test.consume_mapped_cpu_mediators(|data| {
+            assert_eq!(data.len(), buffer_size.area() as usize);
+            callback_spy_call_counter += 1;
+        });
 
-        assert_eq!(
-            *callback_spy_call_counter.borrow(),
-            1,
-            "callback is expected to be called once, but called {} times",
-            *callback_spy_call_counter.borrow()
-        );
+        assert!(consumed);
+        assert_eq!(callback_spy_call_counter, 1, "callback is expected to be called once, but called {callback_spy_call_counter} times");
+        assert!(false == system_under_test.cpu_read_pending());
+    }
+
+    #[test]
+    fn test_mediators_ping_pong() {
+        let context = create_headless_wgpu_vulkan_context();
+        let system_under_test = FrameBufferLayer::<u32>::new(context.device(), test_buffer_size(), SupportUpdateFromCpu::No, "test layer");
+
+        let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
+        assert!(system_under_test.issue_copy_to_cpu_mediator(&mut encoder));
+        assert!(system_under_test.issue_copy_to_cpu_mediator(&mut encoder));
+        context.queue().submit(Some(encoder.finish()));
+        system_under_test.request_cpu_mediator_mapping();
+
+        let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
+        assert!(system_under_test.issue_copy_to_cpu_mediator(&mut encoder));
+        context.queue().submit(Some(encoder.finish()));
+        system_under_test.request_cpu_mediator_mapping();
+
+        let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
+        assert!(false == system_under_test.has_vacant_cpu_mediator());
+        assert!(false == system_under_test.issue_copy_to_cpu_mediator(&mut encoder));
+
+        let _ = context.wait(None);
+        let mut reads = 0;
+        assert!(system_under_test.consume_mapped_cpu_mediators(|_| reads += 1));
+        assert_eq!(reads, 2);
+        assert!(system_under_test.has_vacant_cpu_mediator());
+    }
+
+    #[test]
+    fn test_failed_mapping_is_dropped() {
+        let context = create_headless_wgpu_vulkan_context();
+        let system_under_test = FrameBufferLayer::<u32>::new(context.device(), test_buffer_size(), SupportUpdateFromCpu::No, "test layer");
+        system_under_test.cpu_mappable_mediators[0].state.replace(MediatorState::Mapping { order: 0, outcome: Arc::new(OnceLock::from(Err(BufferAsyncError))) });
+
+        let mut reads = 0;
+        let consumed = system_under_test.consume_mapped_cpu_mediators(|_| reads += 1);
+
+        assert!(false == consumed);
+        assert_eq!(reads, 0);
+        assert!(false == system_under_test.cpu_read_pending());
     }
 
     #[test]
@@ -160,7 +270,7 @@ mod tests {
         let system_under_test = FrameBufferLayer::<u32>::new(context.device(), buffer_size, SupportUpdateFromCpu::No, "test layer");
 
         let mut encoder = context.device().create_command_encoder(&CommandEncoderDescriptor { label: None });
-        system_under_test.issue_copy_to_cpu_mediator(&mut encoder);
+        assert!(system_under_test.issue_copy_to_cpu_mediator(&mut encoder));
         context.queue().submit(Some(encoder.finish()));
 
         let (copy_request_finished_cast, copy_request_finished_signal) = oneshot_channel();
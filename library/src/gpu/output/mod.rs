@@ -1,4 +1,5 @@
 pub(crate) mod frame_buffer;
 pub(crate) mod frame_buffer_layer;
 pub(crate) mod duplex_layer;
+pub(crate) mod exported_textures;
 mod utils;
\ No newline at end of file
@@ -0,0 +1,115 @@
+use crate::gpu::bind_group_builder::BindGroupBuilder;
+use crate::gpu::pipeline_code::PipelineCode;
+use crate::gpu::pipelines_factory::PipelinesFactory;
+use crate::gpu::rasterization_pipeline::RasterizationPipeline;
+use crate::gpu::resources::Resources;
+use crate::scene::overlay::{Overlay, OverlayVertex};
+use crate::utils::bitmap_utils::BitmapSize;
+use crate::utils::version::Version;
+use std::rc::Rc;
+use wgpu::{BufferUsages, Sampler, StoreOp, Texture};
+
+pub(crate) const OVERLAY_GPU_CODE: &str = include_str!("../../shader/_overlay.wgsl");
+
+// draws the overlay primitives over the final image, in a render pass of its own
+pub(super) struct OverlayPass {
+    pipeline: RasterizationPipeline,
+    uniforms: Rc<wgpu::Buffer>,
+    sampler: Sampler,
+    vertices: Option<Rc<wgpu::Buffer>>,
+    vertices_count: u32,
+    vertices_version: Option<Version>,
+    glyph_atlas_version: Option<Version>,
+}
+
+impl OverlayPass {
+    const BIND_GROUP_INDEX: u32 = 0;
+    const BIND_GROUP_UNIFORMS_SLOT: u32 = 0;
+    const BIND_GROUP_SAMPLER_SLOT: u32 = 1;
+    const BIND_GROUP_GLYPH_ATLAS_SLOT: u32 = 2;
+
+    const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4];
+
+    #[must_use]
+    pub(super) fn new(device: &wgpu::Device, resources: &Resources, pipelines_factory: &mut PipelinesFactory) -> Self {
+        let module = resources.create_shader_module("overlay shader", OVERLAY_GPU_CODE);
+        let code = PipelineCode::new(module, seahash::hash(OVERLAY_GPU_CODE.as_bytes()), "overlay_code".to_string());
+        let vertices_layout = wgpu::VertexBufferLayout {
+            array_stride: size_of::<OverlayVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::VERTEX_ATTRIBUTES,
+        };
+
+        let mut overlay_pass = Self {
+            pipeline: RasterizationPipeline::new(pipelines_factory.create_overlay_pipeline(&code, vertices_layout)),
+            uniforms: resources.create_uniform_buffer("overlay uniforms", bytemuck::cast_slice(&[0.0_f32; 4])),
+            sampler: resources.create_sampler("overlay glyph atlas sampler"),
+            vertices: None,
+            vertices_count: 0,
+            vertices_version: None,
+            glyph_atlas_version: None,
+        };
+        // the solid primitives do not sample the atlas, but the binding is to be there
+        let placeholder = resources.create_texture("overlay glyph atlas placeholder", 1, BitmapSize::new(1, 1));
+        overlay_pass.bind_glyph_atlas(device, &placeholder);
+        overlay_pass
+    }
+
+    fn bind_glyph_atlas(&mut self, device: &wgpu::Device, glyph_atlas: &Texture) {
+        let layout = self.pipeline.bind_group_layout(Self::BIND_GROUP_INDEX);
+        let mut bind_group_builder = BindGroupBuilder::new(Self::BIND_GROUP_INDEX, Some("overlay pipeline group"), layout);
+        bind_group_builder
+            .set_storage_entry(Self::BIND_GROUP_UNIFORMS_SLOT, self.uniforms.clone())
+            .set_sampler_entry(Self::BIND_GROUP_SAMPLER_SLOT, self.sampler.clone())
+            .set_texture_entry(Self::BIND_GROUP_GLYPH_ATLAS_SLOT, glyph_atlas.create_view(&wgpu::TextureViewDescriptor::default()))
+        ;
+        self.pipeline.commit_bind_group(device, bind_group_builder);
+    }
+
+    // uploads the vertices and the atlas changed since the previous frame
+    pub(super) fn update(&mut self, overlay: &Overlay, device: &wgpu::Device, queue: &wgpu::Queue, resources: &Resources) {
+        if self.glyph_atlas_version != Some(overlay.glyph_atlas_version()) {
+            if let Some(atlas) = overlay.glyph_atlas() {
+                let texture = resources.create_texture("overlay glyph atlas", 1, atlas.size());
+                resources.write_whole_srgba_texture_data(&texture, atlas.texels());
+                self.bind_glyph_atlas(device, &texture);
+            }
+            self.glyph_atlas_version = Some(overlay.glyph_atlas_version());
+        }
+
+        if self.vertices_version != Some(overlay.version()) {
+            let data: &[u8] = bytemuck::cast_slice(overlay.vertices());
+            match self.vertices.as_ref() {
+                Some(buffer) if buffer.size() >= data.len() as wgpu::BufferAddress => queue.write_buffer(buffer, 0, data),
+                _ if data.is_empty() => {},
+                _ => self.vertices = Some(resources.create_buffer("overlay vertices", BufferUsages::VERTEX | BufferUsages::COPY_DST, data)),
+            }
+            self.vertices_count = overlay.vertices().len() as u32;
+            self.vertices_version = Some(overlay.version());
+        }
+    }
+
+    // the target keeps its content: the primitives are blended over it
+    pub(super) fn draw(&self, encoder: &mut wgpu::CommandEncoder, queue: &wgpu::Queue, view: &wgpu::TextureView, target_width: u32, target_height: u32) {
+        let Some(vertices) = self.vertices.as_ref().filter(|_| self.vertices_count > 0) else {
+            return;
+        };
+        queue.write_buffer(&self.uniforms, 0, bytemuck::cast_slice(&[target_width as f32, target_height as f32, 0.0, 0.0]));
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("overlay pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        self.pipeline.set_into_pass(&mut pass);
+        pass.set_vertex_buffer(0, vertices.slice(..));
+        pass.draw(0..self.vertices_count, 0..1);
+    }
+}
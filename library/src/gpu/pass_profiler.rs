@@ -0,0 +1,77 @@
+use crate::scene::engine_statistics::PassTiming;
+use crate::scene::gpu_passes_report::{GpuPassTimes, GpuPassesReport};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// keeps the pass timings of the last read back frames, for the rolling report
+pub(super) struct PassProfiler {
+    frames: VecDeque<Vec<PassTiming>>,
+    capacity: usize,
+}
+
+impl PassProfiler {
+    #[must_use]
+    pub(super) fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self { frames: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    pub(super) fn record(&mut self, frame: &[PassTiming]) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame.to_vec());
+    }
+
+    #[must_use]
+    pub(super) fn report(&self) -> GpuPassesReport {
+        let mut labels: Vec<&'static str> = Vec::new();
+        for timing in self.frames.iter().flatten() {
+            if false == labels.contains(&timing.label()) {
+                labels.push(timing.label());
+            }
+        }
+        let passes = labels.into_iter().map(|label| {
+            let durations: Vec<Duration> = self.frames.iter().flatten().filter(|timing| timing.label() == label).map(PassTiming::duration).collect();
+            let total: Duration = durations.iter().sum();
+            let min = durations.iter().min().copied().unwrap_or_default();
+            let max = durations.iter().max().copied().unwrap_or_default();
+            GpuPassTimes::new(label, durations.len(), total / durations.len() as u32, min, max)
+        }).collect();
+        GpuPassesReport::new(self.frames.len(), passes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut system_under_test = PassProfiler::new(3);
+        system_under_test.record(&[PassTiming::new("surface", Duration::from_millis(1)), PassTiming::new("trace", Duration::from_millis(4))]);
+        system_under_test.record(&[PassTiming::new("trace", Duration::from_millis(2))]);
+
+        let report = system_under_test.report();
+
+        assert_eq!(report.frames(), 2);
+        assert_eq!(report.passes(), &[
+            GpuPassTimes::new("surface", 1, Duration::from_millis(1), Duration::from_millis(1), Duration::from_millis(1)),
+            GpuPassTimes::new("trace", 2, Duration::from_millis(3), Duration::from_millis(2), Duration::from_millis(4)),
+        ]);
+    }
+
+    #[test]
+    fn test_oldest_frames_roll_out() {
+        let mut system_under_test = PassProfiler::new(2);
+        system_under_test.record(&[PassTiming::new("trace", Duration::from_millis(9))]);
+        system_under_test.record(&[PassTiming::new("trace", Duration::from_millis(2))]);
+        system_under_test.record(&[PassTiming::new("trace", Duration::from_millis(4))]);
+
+        let report = system_under_test.report();
+
+        assert_eq!(report.frames(), 2);
+        assert_eq!(report.of_label("trace").unwrap().max(), Duration::from_millis(4));
+        assert_eq!(report.of_label("trace").unwrap().average(), Duration::from_millis(3));
+    }
+}
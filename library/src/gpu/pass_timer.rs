@@ -0,0 +1,192 @@
+use crate::gpu::context::Context;
+use crate::gpu::pass_profiler::PassProfiler;
+use crate::scene::engine_statistics::PassTiming;
+use crate::scene::gpu_passes_report::GpuPassesReport;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use wgpu::BufferAsyncError;
+
+const TIMESTAMP_SIZE: u64 = size_of::<u64>() as u64;
+
+// the query pair written at the beginning and at the end of a pass
+pub(super) struct PassTimestamps {
+    query_set: wgpu::QuerySet,
+    beginning: u32,
+}
+
+impl PassTimestamps {
+    #[must_use]
+    pub(super) fn compute_pass_writes(&self) -> wgpu::ComputePassTimestampWrites<'_> {
+        wgpu::ComputePassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(self.beginning),
+            end_of_pass_write_index: Some(self.beginning + 1),
+        }
+    }
+
+    #[must_use]
+    pub(super) fn render_pass_writes(&self) -> wgpu::RenderPassTimestampWrites<'_> {
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(self.beginning),
+            end_of_pass_write_index: Some(self.beginning + 1),
+        }
+    }
+}
+
+struct PendingTimings {
+    labels: Vec<&'static str>,
+    readback: wgpu::Buffer,
+    outcome: Arc<OnceLock<Result<(), BufferAsyncError>>>,
+}
+
+// times the passes of a frame with the timestamp queries, if the adapter has them: each pass is given
+// a query pair, the pairs are resolved at the end of the frame and read back a few frames later; the timings
+// of the frames ended while a read back is still pending are dropped
+pub(super) struct PassTimer {
+    queries: Option<wgpu::QuerySet>,
+    resolved: Option<wgpu::Buffer>,
+    // the passes of the current frame, in the order of their query pairs
+    labels: Vec<&'static str>,
+    pending: Option<PendingTimings>,
+    recent: Vec<PassTiming>,
+    profiler: PassProfiler,
+}
+
+impl PassTimer {
+    const MAX_TIMED_PASSES: u32 = 32;
+    const PROFILED_FRAMES: usize = 60;
+
+    #[must_use]
+    pub(super) fn new(context: &Context) -> Self {
+        if false == context.timestamp_queries_supported() {
+            return Self { queries: None, resolved: None, labels: Vec::new(), pending: None, recent: Vec::new(), profiler: PassProfiler::new(Self::PROFILED_FRAMES) };
+        }
+        let queries = context.device().create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("pass timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: Self::MAX_TIMED_PASSES * 2,
+        });
+        let resolved = context.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("resolved pass timestamps"),
+            size: u64::from(Self::MAX_TIMED_PASSES * 2) * TIMESTAMP_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        Self { queries: Some(queries), resolved: Some(resolved), labels: Vec::new(), pending: None, recent: Vec::new(), profiler: PassProfiler::new(Self::PROFILED_FRAMES) }
+    }
+
+    #[must_use]
+    pub(super) fn supported(&self) -> bool {
+        self.queries.is_some()
+    }
+
+    // none without the timestamp queries or when the passes of the frame have taken all the pairs
+    #[must_use]
+    pub(super) fn begin_pass(&mut self, label: &'static str) -> Option<PassTimestamps> {
+        let query_set = self.queries.as_ref()?;
+        if self.labels.len() as u32 >= Self::MAX_TIMED_PASSES {
+            return None;
+        }
+        let beginning = self.labels.len() as u32 * 2;
+        self.labels.push(label);
+        Some(PassTimestamps { query_set: query_set.clone(), beginning })
+    }
+
+    // resolves the pairs the passes of the frame have written
+    pub(super) fn end_frame(&mut self, context: &Context) {
+        let labels = std::mem::take(&mut self.labels);
+        let (Some(queries), Some(resolved)) = (self.queries.as_ref(), self.resolved.as_ref()) else {
+            return;
+        };
+        if labels.is_empty() || self.pending.is_some() {
+            return;
+        }
+        let size = labels.len() as u64 * 2 * TIMESTAMP_SIZE;
+        let readback = context.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("pass timestamps readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = context.device().create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("pass timestamps resolve encoder") });
+        encoder.resolve_query_set(queries, 0..labels.len() as u32 * 2, resolved, 0);
+        encoder.copy_buffer_to_buffer(resolved, 0, &readback, 0, size);
+        context.queue().submit(Some(encoder.finish()));
+
+        let outcome = Arc::new(OnceLock::new());
+        let outcome_of_callback = outcome.clone();
+        readback.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            outcome_of_callback.set(result).expect("pass timestamps mapping outcome is set twice");
+        });
+        self.pending = Some(PendingTimings { labels, readback, outcome });
+    }
+
+    // takes the timings the last device poll has read back
+    pub(super) fn collect(&mut self, context: &Context) {
+        if false == self.pending.as_ref().is_some_and(|pending| pending.outcome.get().is_some()) {
+            return;
+        }
+        let pending = self.pending.take().unwrap();
+        if let Err(error) = pending.outcome.get().unwrap() {
+            // a failed 'map' (e.g. after a device loss) leaves the buffer unmapped: the timings are dropped
+            log::warn!("pass timings readback is dropped: {error}");
+            return;
+        }
+        let timestamps: Vec<u64> = bytemuck::cast_slice(&pending.readback.slice(..).get_mapped_range()).to_vec();
+        pending.readback.unmap();
+        self.recent = Self::timings(&pending.labels, &timestamps, context.queue().get_timestamp_period());
+        self.profiler.record(&self.recent);
+    }
+
+    // of the last frame read back; the passes of the same label are summed up
+    #[must_use]
+    pub(super) fn recent(&self) -> &[PassTiming] {
+        &self.recent
+    }
+
+    // none without the timestamp queries
+    #[must_use]
+    pub(super) fn report(&self) -> Option<GpuPassesReport> {
+        self.supported().then(|| self.profiler.report())
+    }
+
+    #[must_use]
+    fn timings(labels: &[&'static str], timestamps: &[u64], nanoseconds_per_tick: f32) -> Vec<PassTiming> {
+        let mut result: Vec<PassTiming> = Vec::new();
+        for (label, pair) in labels.iter().zip(timestamps.chunks_exact(2)) {
+            let ticks = pair[1].saturating_sub(pair[0]);
+            let duration = Duration::from_nanos((ticks as f64 * f64::from(nanoseconds_per_tick)) as u64);
+            match result.iter_mut().find(|timing| timing.label() == *label) {
+                Some(timing) => timing.add(duration),
+                None => result.push(PassTiming::new(label, duration)),
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings() {
+        let labels = ["bake", "trace", "bake"];
+        let timestamps = [100, 150, 200, 400, 1000, 1010];
+
+        let system_under_test = PassTimer::timings(&labels, &timestamps, 2.0);
+
+        assert_eq!(system_under_test, vec![
+            PassTiming::new("bake", Duration::from_nanos(120)),
+            PassTiming::new("trace", Duration::from_nanos(400)),
+        ]);
+    }
+
+    #[test]
+    fn test_clock_wrap_is_not_negative() {
+        let system_under_test = PassTimer::timings(&["trace"], &[400, 100], 1.0);
+
+        assert_eq!(system_under_test, vec![PassTiming::new("trace", Duration::ZERO)]);
+    }
+}
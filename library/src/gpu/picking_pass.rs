@@ -0,0 +1,249 @@
+use crate::gpu::bind_group_builder::BindGroupBuilder;
+use crate::gpu::context::Context;
+use crate::gpu::pipeline_code::PipelineCode;
+use crate::gpu::pipelines_factory::{ComputeRoutineEntryPoint, PipelinesFactory};
+use crate::gpu::resources::Resources;
+use crate::utils::object_uid::ObjectUid;
+use bytemuck::{Pod, Zeroable};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
+use wgpu::{BufferAsyncError, BufferUsages};
+
+pub(crate) const PICKING_GPU_CODE: &str = include_str!("../../shader/_picking.wgsl");
+
+// must match 'PickRequest' of the shader
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct PickRequestUniforms {
+    pixel_index: u32,
+    frame_buffer_area: u32,
+    padding: [u32; 2],
+}
+
+// the frame buffer pixel with the object under it, none for the background
+type Pick = ((u32, u32), Option<ObjectUid>);
+
+// the answers to the latest queries, the most recent first; all of them are of the current object ids
+struct RecentPicks {
+    entries: VecDeque<Pick>,
+}
+
+impl RecentPicks {
+    const CAPACITY: usize = 16;
+
+    #[must_use]
+    fn new() -> Self {
+        Self { entries: VecDeque::with_capacity(Self::CAPACITY) }
+    }
+
+    // the outer none: the pixel has not been asked about recently
+    #[must_use]
+    fn get(&mut self, pixel: (u32, u32)) -> Option<Option<ObjectUid>> {
+        let position = self.entries.iter().position(|(picked, _)| *picked == pixel)?;
+        let entry = self.entries.remove(position)?;
+        self.entries.push_front(entry);
+        Some(entry.1)
+    }
+
+    fn insert(&mut self, pixel: (u32, u32), object: Option<ObjectUid>) {
+        self.entries.retain(|(picked, _)| *picked != pixel);
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop_back();
+        }
+        self.entries.push_front((pixel, object));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+struct PendingPick {
+    pixel: (u32, u32),
+    readback: wgpu::Buffer,
+    // the device poll that finds the copy done fills the outcome
+    outcome: Arc<OnceLock<Result<(), BufferAsyncError>>>,
+    submission: wgpu::SubmissionIndex,
+}
+
+// the object under a pixel without the cpu copy of the object id buffer: a single thread kernel copies
+// the id into four bytes the host maps; the answers stay valid until the object ids are rewritten
+pub(super) struct PickingPass {
+    pipeline: wgpu::ComputePipeline,
+    picked: Rc<wgpu::Buffer>,
+    pending: Vec<PendingPick>,
+    recent: RecentPicks,
+}
+
+impl PickingPass {
+    const BIND_GROUP_INDEX: u32 = 0;
+    const BIND_GROUP_REQUEST_SLOT: u32 = 0;
+    const BIND_GROUP_OBJECT_ID_SLOT: u32 = 1;
+    const BIND_GROUP_PICKED_SLOT: u32 = 2;
+
+    const PICKED_SIZE: wgpu::BufferAddress = size_of::<u32>() as wgpu::BufferAddress;
+
+    #[must_use]
+    pub(super) fn new(resources: &Resources, pipelines_factory: &mut PipelinesFactory) -> Self {
+        let module = resources.create_shader_module("picking shader", PICKING_GPU_CODE);
+        let code = PipelineCode::new(module, seahash::hash(PICKING_GPU_CODE.as_bytes()), "picking_code".to_string());
+        Self {
+            pipeline: pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::PickObjectId, &code),
+            picked: resources.create_buffer("picked object id", BufferUsages::STORAGE | BufferUsages::COPY_SRC, bytemuck::bytes_of(&0_u32)),
+            pending: Vec::new(),
+            recent: RecentPicks::new(),
+        }
+    }
+
+    // the outer none: the pixel is not answered yet
+    #[must_use]
+    pub(super) fn answer(&mut self, pixel: (u32, u32)) -> Option<Option<ObjectUid>> {
+        self.recent.get(pixel)
+    }
+
+    #[must_use]
+    pub(super) fn requested(&self, pixel: (u32, u32)) -> bool {
+        self.pending.iter().any(|pending| pending.pixel == pixel)
+    }
+
+    #[must_use]
+    pub(super) fn answers_pending(&self) -> bool {
+        false == self.pending.is_empty()
+    }
+
+    // 'pixel_index' is of the frame buffer; the answer is collected after a device poll
+    pub(super) fn request(&mut self, pixel: (u32, u32), pixel_index: u32, frame_buffer_area: u32, object_ids: Rc<wgpu::Buffer>, context: &Context, resources: &Resources) {
+        let device = context.device();
+        let request = PickRequestUniforms { pixel_index, frame_buffer_area, ..PickRequestUniforms::zeroed() };
+        let mut bind_group_builder = BindGroupBuilder::new(Self::BIND_GROUP_INDEX, Some("picking pipeline group"), self.pipeline.get_bind_group_layout(Self::BIND_GROUP_INDEX));
+        bind_group_builder
+            .set_storage_entry(Self::BIND_GROUP_REQUEST_SLOT, resources.create_uniform_buffer("pick request", bytemuck::bytes_of(&request)))
+            .set_storage_entry(Self::BIND_GROUP_OBJECT_ID_SLOT, object_ids)
+            .set_storage_entry(Self::BIND_GROUP_PICKED_SLOT, self.picked.clone())
+        ;
+        let bind_group = bind_group_builder.make_bind_group(device);
+
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("picked object id cpu mappable mediator"),
+            size: Self::PICKED_SIZE,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("picking encoder") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("picking compute pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(Self::BIND_GROUP_INDEX, &bind_group, &[]);
+            pass.dispatch_workgroups(1, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&self.picked, 0, &readback, 0, Self::PICKED_SIZE);
+        let submission = context.queue().submit(Some(encoder.finish()));
+
+        let outcome = Arc::new(OnceLock::new());
+        let outcome_of_callback = outcome.clone();
+        readback.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            outcome_of_callback.set(result).expect("picked object id mapping outcome is set twice");
+        });
+        self.pending.push(PendingPick { pixel, readback, outcome, submission });
+    }
+
+    // takes the answers the last device poll has completed; the picks of the failed mappings are dropped
+    pub(super) fn collect(&mut self) {
+        let (done, pending): (Vec<PendingPick>, Vec<PendingPick>) = self.pending.drain(..).partition(|pending| pending.outcome.get().is_some());
+        self.pending = pending;
+        for pick in done {
+            if let Err(error) = pick.outcome.get().unwrap() {
+                // a failed 'map' (e.g. after a device loss) leaves the buffer unmapped
+                log::warn!("pick of pixel {:?} is dropped: {error}", pick.pixel);
+                continue;
+            }
+            let uid: u32 = *bytemuck::from_bytes(&pick.readback.slice(..).get_mapped_range());
+            pick.readback.unmap();
+            self.recent.insert(pick.pixel, (0 != uid).then_some(ObjectUid(uid)));
+        }
+    }
+
+    // blocks till all the requested answers are at the cpu
+    pub(super) fn wait(&mut self, context: &Context) {
+        if let Some(latest) = self.pending.last() {
+            context.wait(Some(latest.submission.clone()));
+        }
+        self.collect();
+    }
+
+    // the object ids got rewritten: the answers, the pending ones as well, describe the previous ones
+    pub(super) fn invalidate(&mut self) {
+        self.pending.clear();
+        self.recent.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
+    use crate::utils::tests::common_values::tests::COMMON_PRESENTATION_FORMAT;
+
+    #[test]
+    fn test_recent_picks_answer() {
+        let mut system_under_test = RecentPicks::new();
+
+        system_under_test.insert((1, 2), Some(ObjectUid(7)));
+        system_under_test.insert((3, 4), None);
+
+        assert_eq!(system_under_test.get((1, 2)), Some(Some(ObjectUid(7))));
+        assert_eq!(system_under_test.get((3, 4)), Some(None));
+        assert_eq!(system_under_test.get((5, 6)), None);
+    }
+
+    #[test]
+    fn test_recent_picks_forget_least_recently_used() {
+        let mut system_under_test = RecentPicks::new();
+        for column in 0..RecentPicks::CAPACITY as u32 {
+            system_under_test.insert((column, 0), Some(ObjectUid(column + 1)));
+        }
+
+        let _ = system_under_test.get((0, 0));
+        system_under_test.insert((100, 0), None);
+
+        assert_eq!(system_under_test.entries.len(), RecentPicks::CAPACITY);
+        assert_eq!(system_under_test.get((0, 0)), Some(Some(ObjectUid(1))));
+        assert_eq!(system_under_test.get((1, 0)), None);
+    }
+
+    #[test]
+    fn test_recent_picks_clear() {
+        let mut system_under_test = RecentPicks::new();
+        system_under_test.insert((1, 2), Some(ObjectUid(7)));
+
+        system_under_test.clear();
+
+        assert_eq!(system_under_test.get((1, 2)), None);
+    }
+
+    #[test]
+    fn test_failed_mapping_drops_pick() {
+        let context = create_headless_wgpu_vulkan_context();
+        let resources = Resources::new(context.clone());
+        let mut pipelines_factory = PipelinesFactory::new(context.clone(), COMMON_PRESENTATION_FORMAT, None);
+        let mut system_under_test = PickingPass::new(&resources, &mut pipelines_factory);
+        let readback = context.device().create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: PickingPass::PICKED_SIZE,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let submission = context.queue().submit([]);
+        system_under_test.pending.push(PendingPick { pixel: (1, 2), readback, outcome: Arc::new(OnceLock::from(Err(BufferAsyncError))), submission });
+
+        system_under_test.collect();
+
+        assert!(false == system_under_test.answers_pending());
+        assert_eq!(system_under_test.answer((1, 2)), None);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::gpu::background_shader_compilation::PrebuiltComputePipeline;
 use crate::gpu::context::Context;
 use crate::gpu::pipeline_code::{PipelineCode, ShaderHash};
+use crate::scene::pipeline_cache_statistics::{PipelineCacheLookup, PipelineCacheStatistics};
 use bitflags::bitflags;
 use derive_more::Display;
 use log::info;
@@ -9,6 +11,7 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use wgpu::{AdapterInfo, PipelineCache, PipelineCacheDescriptor};
 
 pub (crate) struct PipelinesFactory {
@@ -17,6 +20,9 @@ pub (crate) struct PipelinesFactory {
     caches_path: Option<PathBuf>,
     caches: HashMap<String, CacheAndHash>,
     io: Rc<dyn Io>,
+    statistics: PipelineCacheStatistics,
+    // built off the render thread for the codes of these hashes, see 'BackgroundShaderCompilation'
+    prebuilt: HashMap<(ShaderHash, ComputeRoutineEntryPoint), wgpu::ComputePipeline>,
 }
 
 struct CacheAndHash {
@@ -26,6 +32,8 @@ struct CacheAndHash {
 
 impl PipelinesFactory {
     const RASTERIZATION_PIPELINE_LABEL: &'static str = "rasterization pipeline";
+    const OVERLAY_PIPELINE_LABEL: &'static str = "overlay pipeline";
+    const SELECTION_OUTLINE_PIPELINE_LABEL: &'static str = "selection outline pipeline";
     const DISK_CACHE_VERSION_CODE: usize = 0;
 
     #[must_use]
@@ -39,12 +47,39 @@ impl PipelinesFactory {
             && let Err(e) = fs::create_dir_all(&path) {
                 info!("failed to create directories in path {path:?}: {e}");
             }
-        Self { context, presentation_format, caches_path, caches: HashMap::new(), io, }
+        Self { context, presentation_format, caches_path, caches: HashMap::new(), io, statistics: PipelineCacheStatistics::default(), prebuilt: HashMap::new(), }
+    }
+
+    // the pipeline caches belong to a device, so the factory of a new device starts over with the disk caches
+    #[must_use]
+    pub(crate) fn for_context(&self, context: Rc<Context>) -> Self {
+        Self::new_with_custom_io(context, self.presentation_format, self.caches_path.clone(), self.io.clone())
+    }
+
+    #[must_use]
+    pub(super) fn presentation_format(&self) -> wgpu::TextureFormat {
+        self.presentation_format
+    }
+
+    // the next creations of these are instant; the ones not asked for are dropped by 'forget_prebuilt'
+    pub(crate) fn add_prebuilt(&mut self, pipelines: Vec<PrebuiltComputePipeline>) {
+        self.prebuilt.extend(pipelines.into_iter().map(|prebuilt| ((prebuilt.hash, prebuilt.routine), prebuilt.pipeline)));
+    }
+
+    pub(crate) fn forget_prebuilt(&mut self) {
+        self.prebuilt.clear();
+    }
+
+    // of the pipelines this factory has created
+    #[must_use]
+    pub(crate) fn statistics(&self) -> PipelineCacheStatistics {
+        self.statistics
     }
 
     #[must_use]
     pub(super) fn create_rasterization_pipeline(&mut self, code: &PipelineCode) -> wgpu::RenderPipeline {
-        let (cache, status) = self.find_or_create_cache(code.human_readable_uid(), code.content_hash());
+        let started = Instant::now();
+        let (cache, status, lookup) = self.find_or_create_cache(code.human_readable_uid(), code.content_hash());
         let pipeline = self.context.device().create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some(Self::RASTERIZATION_PIPELINE_LABEL),
             layout: None,
@@ -72,12 +107,66 @@ impl PipelinesFactory {
         });
         
         self.handle_actions(code, cache, status);
+        self.statistics.record(lookup, started.elapsed());
+        pipeline
+    }
+
+    // unlike the final image, the primitives come in a vertex buffer and are blended over the target
+    #[must_use]
+    pub(super) fn create_overlay_pipeline(&mut self, code: &PipelineCode, vertices: wgpu::VertexBufferLayout) -> wgpu::RenderPipeline {
+        self.create_blending_pipeline(Self::OVERLAY_PIPELINE_LABEL, code, &[vertices])
+    }
+
+    // a full screen quad, as the final image, but blended over it
+    #[must_use]
+    pub(super) fn create_selection_outline_pipeline(&mut self, code: &PipelineCode) -> wgpu::RenderPipeline {
+        self.create_blending_pipeline(Self::SELECTION_OUTLINE_PIPELINE_LABEL, code, &[])
+    }
+
+    #[must_use]
+    fn create_blending_pipeline(&mut self, label: &str, code: &PipelineCode, vertices: &[wgpu::VertexBufferLayout]) -> wgpu::RenderPipeline {
+        let started = Instant::now();
+        let (cache, status, lookup) = self.find_or_create_cache(code.human_readable_uid(), code.content_hash());
+        let pipeline = self.context.device().create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: code.module().as_ref(),
+                entry_point: None,
+                compilation_options: Default::default(),
+                buffers: vertices,
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: code.module().as_ref(),
+                entry_point: None,
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: self.presentation_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: cache.as_deref(),
+        });
+
+        self.handle_actions(code, cache, status);
+        self.statistics.record(lookup, started.elapsed());
         pipeline
     }
 
     #[must_use]
     pub(crate) fn create_compute_pipeline(&mut self, routine: ComputeRoutineEntryPoint, code: &PipelineCode) -> wgpu::ComputePipeline {
-        let (cache, actions) = self.find_or_create_cache(code.human_readable_uid(), code.content_hash());
+        if let Some(pipeline) = self.prebuilt.remove(&(code.content_hash(), routine)) {
+            // compiled without the caches, the time was not spent here though
+            self.statistics.record(PipelineCacheLookup::Miss, Duration::ZERO);
+            return pipeline;
+        }
+        let started = Instant::now();
+        let (cache, actions, lookup) = self.find_or_create_cache(code.human_readable_uid(), code.content_hash());
         let pipeline = self.context.device().create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
             label: routine.name(),
             compilation_options: Default::default(),
@@ -88,6 +177,7 @@ impl PipelinesFactory {
         });
         
         self.handle_actions(code, cache, actions);
+        self.statistics.record(lookup, started.elapsed());
         pipeline
     }
 
@@ -114,18 +204,22 @@ impl PipelinesFactory {
     }
 
     #[must_use]
-    fn find_or_create_cache(&self, uid: &str, desired_hash: ShaderHash) -> (Option<Rc<PipelineCache>>, CacheAction) {
+    fn find_or_create_cache(&self, uid: &str, desired_hash: ShaderHash) -> (Option<Rc<PipelineCache>>, CacheAction, PipelineCacheLookup) {
+        if false == self.context.pipeline_caching_supported() {
+            return (None, CacheAction::empty(), PipelineCacheLookup::Miss);
+        }
+
         if let Some(cache) = self.caches.get(uid) {
             if cache.hash == desired_hash {
-                return (Some(cache.this.clone()), CacheAction::empty());    
+                return (Some(cache.this.clone()), CacheAction::empty(), PipelineCacheLookup::MemoryHit);
             }
-            return (self.create_pipeline_cache(uid, None), CacheAction::StoreInMemory | CacheAction::SaveOnDisk);
+            return (self.create_pipeline_cache(uid, None), CacheAction::StoreInMemory | CacheAction::SaveOnDisk, PipelineCacheLookup::Miss);
         }
         
         if let Some(cache_data) = self.try_load_cache_data_from_disk(uid, desired_hash) {
-            (self.create_pipeline_cache(uid, Some(cache_data.as_slice())), CacheAction::StoreInMemory)
+            (self.create_pipeline_cache(uid, Some(cache_data.as_slice())), CacheAction::StoreInMemory, PipelineCacheLookup::DiskHit)
         } else {
-            (self.create_pipeline_cache(uid, None), CacheAction::StoreInMemory | CacheAction::SaveOnDisk)
+            (self.create_pipeline_cache(uid, None), CacheAction::StoreInMemory | CacheAction::SaveOnDisk, PipelineCacheLookup::Miss)
         }
     }
     
@@ -351,12 +445,36 @@ impl CacheFileHeader {
     }
 }
 
-#[derive(Display)]
+#[derive(Display, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum ComputeRoutineEntryPoint {
     SurfaceAttributes,
 
-    RayTracingMonteCarlo,
+    #[cfg(feature = "monte_carlo")] RayTracingMonteCarlo,
     RayTracingDeterministic,
+    #[cfg(feature = "monte_carlo")] RayTracingMonteCarloHalfResolutionIndirect,
+    #[cfg(feature = "monte_carlo")] IndirectLightingHalfResolution,
+    #[cfg(feature = "monte_carlo")] ReprojectionHistory,
+    #[cfg(feature = "monte_carlo")] TemporalReprojection,
+    #[cfg(feature = "monte_carlo")] DebugPath,
+    #[cfg(feature = "monte_carlo")] WavefrontGenerate,
+    #[cfg(feature = "monte_carlo")] WavefrontPrepareIntersection,
+    #[cfg(feature = "monte_carlo")] WavefrontIntersect,
+    #[cfg(feature = "monte_carlo")] WavefrontPrepareShading,
+    #[cfg(feature = "monte_carlo")] WavefrontShade,
+    #[cfg(feature = "monte_carlo")] WavefrontResolve,
+    IrradianceProbes,
+    ShadowMap,
+    LuminanceHistogram,
+    AutoExposure,
+    SdfSamples,
+    SdfDistanceGridsBake,
+    ProceduralTextureBake,
+    BloomBrightPass,
+    BloomDownsample,
+    BloomBlur,
+    BloomUpsample,
+    #[cfg(feature = "denoiser")] DenoisedBlend,
+    PickObjectId,
 
     #[cfg(test)] Default,
     #[cfg(test)] TestDefault,
@@ -367,8 +485,32 @@ impl ComputeRoutineEntryPoint {
     pub(crate) fn name(&self) -> Option<&'static str> {
         match self {
             ComputeRoutineEntryPoint::SurfaceAttributes => Some("compute_surface_attributes_buffer"),
-            ComputeRoutineEntryPoint::RayTracingMonteCarlo => Some("compute_color_buffer_monte_carlo"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::RayTracingMonteCarlo => Some("compute_color_buffer_monte_carlo"),
             ComputeRoutineEntryPoint::RayTracingDeterministic => Some("compute_color_buffer_deterministic"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::RayTracingMonteCarloHalfResolutionIndirect => Some("compute_color_buffer_half_resolution_indirect"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::IndirectLightingHalfResolution => Some("compute_indirect_half_resolution"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::ReprojectionHistory => Some("compute_reprojection_history"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::TemporalReprojection => Some("compute_temporal_reprojection"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::DebugPath => Some("compute_debug_path"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::WavefrontGenerate => Some("compute_wavefront_generate"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::WavefrontPrepareIntersection => Some("compute_wavefront_prepare_intersection"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::WavefrontIntersect => Some("compute_wavefront_intersect"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::WavefrontPrepareShading => Some("compute_wavefront_prepare_shading"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::WavefrontShade => Some("compute_wavefront_shade"),
+            #[cfg(feature = "monte_carlo")] ComputeRoutineEntryPoint::WavefrontResolve => Some("compute_wavefront_resolve"),
+            ComputeRoutineEntryPoint::IrradianceProbes => Some("compute_irradiance_probes"),
+            ComputeRoutineEntryPoint::ShadowMap => Some("compute_shadow_map"),
+            ComputeRoutineEntryPoint::LuminanceHistogram => Some("compute_luminance_histogram"),
+            ComputeRoutineEntryPoint::AutoExposure => Some("compute_auto_exposure"),
+            ComputeRoutineEntryPoint::SdfSamples => Some("compute_sdf_samples"),
+            ComputeRoutineEntryPoint::SdfDistanceGridsBake => Some("compute_sdf_distance_grids"),
+            ComputeRoutineEntryPoint::ProceduralTextureBake => Some("compute_procedural_texture_bake"),
+            ComputeRoutineEntryPoint::BloomBrightPass => Some("bloom_bright_pass"),
+            ComputeRoutineEntryPoint::BloomDownsample => Some("bloom_downsample"),
+            ComputeRoutineEntryPoint::BloomBlur => Some("bloom_blur"),
+            ComputeRoutineEntryPoint::BloomUpsample => Some("bloom_upsample"),
+            #[cfg(feature = "denoiser")] ComputeRoutineEntryPoint::DenoisedBlend => Some("denoised_blend"),
+            ComputeRoutineEntryPoint::PickObjectId => Some("pick_object_id"),
             
             #[cfg(test)] ComputeRoutineEntryPoint::TestDefault => Some("main"),
             #[cfg(test)] ComputeRoutineEntryPoint::Default => None,
@@ -483,6 +625,8 @@ mod tests {
         
         let _ = system_under_test.create_compute_pipeline(ComputeRoutineEntryPoint::Default, &pipeline_code);
         assert_saved_once_loaded_none(&expected_cache_file, &io_spy, hash);
+        assert_eq!(system_under_test.statistics().misses(), 1);
+        assert_eq!(system_under_test.statistics().memory_hits(), 1);
     }
 
     #[test]
@@ -524,6 +668,7 @@ mod tests {
             let mut system_under_test = PipelinesFactory::new_with_custom_io(context, COMMON_PRESENTATION_FORMAT, cache_directory, io_spy.clone());
             let _ = system_under_test.create_compute_pipeline(ComputeRoutineEntryPoint::Default, &pipeline_code);
             assert_saved_once_loaded_once(&expected_cache_file, &io_spy, hash);
+            assert_eq!(system_under_test.statistics().disk_hits(), 1);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,202 @@
+use crate::container::visual_objects::VisualObjects;
+use crate::gpu::bloom_pass::BloomPass;
+use crate::gpu::composed_shader::{pipeline_routines, ComposedShader};
+use crate::gpu::context::Context;
+#[cfg(feature = "denoiser")]
+use crate::gpu::denoised_blend_pass::DenoisedBlendPass;
+#[cfg(feature = "gpu_testing")]
+use crate::gpu::headless_device::HeadlessContext;
+use crate::gpu::headless_device::create_headless_wgpu_device;
+use crate::gpu::overlay_pass::OverlayPass;
+use crate::gpu::picking_pass::PickingPass;
+use crate::gpu::pipelines_factory::{ComputeRoutineEntryPoint, PipelinesFactory};
+use crate::gpu::render::WHOLE_TRACER_GPU_CODE;
+use crate::gpu::resources::Resources;
+use crate::gpu::scaffolding::backend_vulkan_or_primary;
+use crate::gpu::selection_outline_pass::SelectionOutlinePass;
+use crate::scene::pipeline_cache_statistics::PipelineCacheStatistics;
+use crate::utils::caches_directory::{CacheKind, CachesDirectory};
+use crate::{EngineInstantiationError, OFFSCREEN_OUTPUT_FORMAT};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+const WARM_UP_DEVICE_LABEL: &str = "Rust Tracer Library Pipelines Warm Up";
+
+#[derive(Copy, Clone, PartialEq)]
+enum WarmUpStep {
+    TracerCompute { code_uid: &'static str, routine: ComputeRoutineEntryPoint },
+    TracerRasterization { code_uid: &'static str },
+    Overlay,
+    SelectionOutline,
+    Bloom,
+    #[cfg(feature = "denoiser")]
+    DenoisedBlend,
+    Picking,
+}
+
+// compiles the pipelines an engine creates into the pipeline caches of the caches directory ahead of it, a step at a
+// time, so that an application shows the progress of the first run; the engine made afterwards with the same caches
+// directory on the same adapter loads them from the disk. The tracer is composed with the sdf classes of the scene,
+// so the warm up is to get the scene (or at least the classes) of the engine. The rasterization pipelines are made
+// for the offscreen output format: a window of another one compiles them anew, they are cheap though.
+pub struct PipelinesWarmUp {
+    context: Rc<Context>,
+    resources: Resources,
+    pipelines_factory: PipelinesFactory,
+    shader: ComposedShader,
+    steps: Vec<WarmUpStep>,
+    steps_done: usize,
+}
+
+impl PipelinesWarmUp {
+    pub async fn new(scene: &VisualObjects, caches_path: impl Into<PathBuf>) -> Result<PipelinesWarmUp, EngineInstantiationError> {
+        let wgpu_instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: backend_vulkan_or_primary(),
+            ..Default::default()
+        });
+        let context = create_headless_wgpu_device(&wgpu_instance, WARM_UP_DEVICE_LABEL).await?;
+        Ok(Self::with_context(Rc::new(context), scene, caches_path.into()))
+    }
+
+    #[cfg(feature = "gpu_testing")]
+    #[must_use]
+    pub fn with_headless_context(context: &HeadlessContext, scene: &VisualObjects, caches_path: impl Into<PathBuf>) -> PipelinesWarmUp {
+        Self::with_context(context.context(), scene, caches_path.into())
+    }
+
+    #[must_use]
+    fn with_context(context: Rc<Context>, scene: &VisualObjects, caches_path: PathBuf) -> PipelinesWarmUp {
+        let caches = CachesDirectory::open(caches_path);
+        let resources = Resources::new(context.clone());
+        let pipelines_factory = PipelinesFactory::new(context.clone(), OFFSCREEN_OUTPUT_FORMAT, Some(caches.path_of(CacheKind::Pipelines)));
+        let shader = ComposedShader::new(WHOLE_TRACER_GPU_CODE, scene, &resources);
+        Self { context, resources, pipelines_factory, shader, steps: Self::plan(), steps_done: 0 }
+    }
+
+    // the tracer pipelines first: they take the most of the time
+    #[must_use]
+    fn plan() -> Vec<WarmUpStep> {
+        let mut result = Vec::new();
+        for (code_uid, routines) in pipeline_routines() {
+            match routines {
+                Some(routines) => result.extend(routines.into_iter().map(|routine| WarmUpStep::TracerCompute { code_uid, routine })),
+                None => result.push(WarmUpStep::TracerRasterization { code_uid }),
+            }
+        }
+        result.extend([
+            WarmUpStep::Overlay,
+            WarmUpStep::SelectionOutline,
+            WarmUpStep::Bloom,
+            #[cfg(feature = "denoiser")]
+            WarmUpStep::DenoisedBlend,
+            WarmUpStep::Picking,
+        ]);
+        result
+    }
+
+    #[must_use]
+    pub fn steps_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    #[must_use]
+    pub fn steps_done(&self) -> usize {
+        self.steps_done
+    }
+
+    #[must_use]
+    pub fn finished(&self) -> bool {
+        self.steps_done == self.steps.len()
+    }
+
+    // compiles the next pipeline (or the few of a pass); false once all are done
+    pub fn step(&mut self) -> bool {
+        let Some(step) = self.steps.get(self.steps_done).copied() else {
+            return false;
+        };
+        let factory = &mut self.pipelines_factory;
+        match step {
+            WarmUpStep::TracerCompute { code_uid, routine } => {
+                let _ = factory.create_compute_pipeline(routine, &self.shader.code(code_uid));
+            },
+            WarmUpStep::TracerRasterization { code_uid } => {
+                let _ = factory.create_rasterization_pipeline(&self.shader.code(code_uid));
+            },
+            WarmUpStep::Overlay => {
+                let _ = OverlayPass::new(self.context.device(), &self.resources, factory);
+            },
+            WarmUpStep::SelectionOutline => {
+                let _ = SelectionOutlinePass::new(&self.resources, factory);
+            },
+            WarmUpStep::Bloom => {
+                let _ = BloomPass::new(&self.resources, factory);
+            },
+            #[cfg(feature = "denoiser")]
+            WarmUpStep::DenoisedBlend => {
+                let _ = DenoisedBlendPass::new(&self.resources, factory);
+            },
+            WarmUpStep::Picking => {
+                let _ = PickingPass::new(&self.resources, factory);
+            },
+        }
+        self.steps_done += 1;
+        true
+    }
+
+    // blocks till all the steps are done; the progress gets the steps done and the steps count after each
+    pub fn run(&mut self, mut progress: impl FnMut(usize, usize)) {
+        while self.step() {
+            progress(self.steps_done, self.steps.len());
+        }
+    }
+
+    // the misses are the pipelines the engine would have compiled on its creation
+    #[must_use]
+    pub fn statistics(&self) -> PipelineCacheStatistics {
+        self.pipelines_factory.statistics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_plan_covers_every_tracer_routine() {
+        let system_under_test = PipelinesWarmUp::plan();
+
+        for (code_uid, routines) in pipeline_routines() {
+            match routines {
+                Some(routines) => assert!(routines.into_iter().all(|routine| system_under_test.contains(&WarmUpStep::TracerCompute { code_uid, routine }))),
+                None => assert!(system_under_test.contains(&WarmUpStep::TracerRasterization { code_uid })),
+            }
+        }
+        assert!(system_under_test.contains(&WarmUpStep::Picking));
+    }
+
+    #[test]
+    fn test_second_warm_up_loads_from_disk() {
+        let context = create_headless_wgpu_vulkan_context();
+        let caches = tempdir().unwrap();
+        let scene = VisualObjects::new(None, None, None, None, None);
+
+        let mut first = PipelinesWarmUp::with_context(context.clone(), &scene, caches.path().to_path_buf());
+        let mut reported = Vec::new();
+        first.run(|done, count| reported.push((done, count)));
+
+        assert!(first.finished());
+        assert_eq!(reported.len(), first.steps_count());
+        assert_eq!(reported.last(), Some(&(first.steps_count(), first.steps_count())));
+        assert!(first.statistics().pipelines_created() as usize >= first.steps_count());
+        if false == context.pipeline_caching_supported() {
+            return;
+        }
+
+        let mut second = PipelinesWarmUp::with_context(context, &scene, caches.path().to_path_buf());
+        second.run(|_, _| {});
+
+        assert!(second.statistics().disk_hits() > 0);
+    }
+}
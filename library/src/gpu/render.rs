@@ -1,45 +1,99 @@
 use crate::animation::time_tracker::TimeTracker;
-use crate::bvh::node::BvhNode;
+use crate::background::background_uid::BackgroundUid;
+use crate::bvh::light_tree::LightTree;
 use crate::container::visual_objects::{DataKind, VisualObjects};
+use crate::gpu::accumulation_checkpoint::AccumulationCheckpoint;
+use crate::gpu::background_shader_compilation::{BackgroundShaderCompilation, CompilationPoll};
 use crate::gpu::bind_group_builder::BindGroupBuilder;
 use crate::gpu::bitmap_textures::BitmapTextures;
 use crate::gpu::buffers_update_status::BuffersUpdateStatus;
-use crate::gpu::color_buffer_evaluation::{ColorBufferEvaluationStrategy, RenderStrategyId};
+use crate::gpu::color_buffer_evaluation::ColorBufferEvaluationStrategy;
+use crate::gpu::composed_shader::{ComposedShader, PipelineFingerprints};
 use crate::gpu::compute_pipeline::ComputePipeline;
 use crate::gpu::context::Context;
+use crate::gpu::convergence::Convergence;
 use crate::gpu::frame_buffer_size::FrameBufferSize;
-use crate::gpu::output::frame_buffer::FrameBuffer;
+use crate::gpu::output::exported_textures::ExportedTextures;
+use crate::gpu::output::frame_buffer::{FrameBuffer, LIGHTING_AOVS_PER_PIXEL};
+use crate::gpu::pass_timer::{PassTimer, PassTimestamps};
 use crate::gpu::output::frame_buffer_layer::{FrameBufferLayer, SupportUpdateFromCpu};
 use crate::gpu::pipeline_code::PipelineCode;
 use crate::gpu::pipelines_factory::{ComputeRoutineEntryPoint, PipelinesFactory};
+use crate::gpu::bloom_pass::BloomPass;
+use crate::gpu::overlay_pass::OverlayPass;
+use crate::gpu::picking_pass::PickingPass;
+use crate::gpu::selection_outline_pass::SelectionOutlinePass;
 use crate::gpu::rasterization_pipeline::RasterizationPipeline;
-use crate::gpu::resizable_buffer::ResizableBuffer;
+use crate::gpu::resizable_buffer::{ResizableBuffer, ResizeStatus};
 use crate::gpu::resources::Resources;
 use crate::gpu::uniforms::Uniforms;
+use crate::gpu::viewport::Viewport;
+use crate::gpu::scene_bvh::SceneBvh;
 use crate::gpu::versioned_buffer::{BufferUpdateStatus, VersionedBuffer};
+#[cfg(feature = "monte_carlo")]
+use crate::gpu::wavefront::{WavefrontBuffers, WavefrontPipelines};
+use crate::geometry::ray::Ray;
 use crate::material::atlas_region_mapping::AtlasRegionMapping;
 use crate::material::material_properties::MaterialProperties;
+use crate::material::procedural_texture_baking::{ProceduralTextureBaking, BAKE_REQUEST_QUARTETS};
+use crate::material::procedural_texture_index::ProceduralTextureUid;
 use crate::objects::parallelogram::Parallelogram;
 use crate::objects::sdf_instance::SdfInstance;
-use crate::objects::triangle::Triangle;
+use crate::objects::sphere::Sphere;
+use crate::scene::auto_exposure::AutoExposure;
+use crate::scene::bloom::Bloom;
+use crate::scene::buffers_statistics::{SceneBufferKind, SceneBufferStatistics, SceneBuffersStatistics};
 use crate::scene::camera::Camera;
+use crate::scene::color_grading::ColorGrading;
+use crate::scene::engine_statistics::{EngineStatistics, ObjectCounts};
+use crate::scene::display_mapping::DisplayMapping;
+use crate::scene::frame_buffer_size_policy::FrameBufferSizePolicy;
+use crate::scene::frame_capture::{CapturedAovs, CapturedFrame};
+use crate::scene::frame_statistics::FrameStatistics;
+use crate::scene::g_buffer::GBuffer;
+use crate::scene::gpu_passes_report::GpuPassesReport;
 use crate::scene::hub::Hub;
+use crate::scene::key_light_shadows::KeyLightShadows;
+use crate::scene::layer_mask::LayerMask;
+use crate::scene::magnifier::{Magnifier, MagnifierReadout};
+use crate::scene::output_dithering::OutputDithering;
+use crate::scene::overlay::Overlay;
+use crate::scene::pipeline_cache_statistics::PipelineCacheStatistics;
+#[cfg(feature = "monte_carlo")]
+use crate::scene::pixel_path::PixelPath;
+use crate::scene::probe_grid::ProbeGrid;
+use crate::scene::random_generator::RandomGenerator;
+use crate::scene::random_seed_texture::RandomSeedTexture;
+use crate::scene::render_strategy::{RenderStrategyConfig, RenderStrategyId};
+use crate::scene::sdf_level_of_detail::SdfLevelOfDetail;
+use crate::scene::stop_criterion::StopCriterion;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
 use crate::serialization::pod_vector::PodVector;
 use crate::serialization::serializable_for_gpu::GpuSerializationSize;
+use crate::utils::bitmap_utils::ImmutableBitmapReference;
+use crate::utils::caches_directory::{CacheKind, CachesDirectory};
 use crate::utils::object_uid::ObjectUid;
 use crate::utils::version::Version;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::Instant;
-use wgpu::{BufferAddress, CommandEncoder, StoreOp, SubmissionIndex};
+use std::time::{Duration, Instant};
+use anyhow::bail;
+use cgmath::Vector3;
+use more_asserts::{assert_gt, assert_le};
+use wgpu::{BufferAddress, BufferUsages, CommandEncoder, StoreOp, SubmissionIndex};
 use winit::dpi::PhysicalSize;
 
 #[cfg(feature = "denoiser")]
 mod denoiser {
     pub(super) use crate::denoiser::entry::Denoiser;
+    pub(super) use crate::denoiser::device_type::DenoiserDeviceType;
+    pub(super) use crate::denoiser::denoise_mode::DenoiseMode;
+    pub(super) use crate::denoiser::worker::{DenoiserQueue, DenoiserWorker, NoisyFrame};
+    pub(super) use crate::denoiser::schedule::DenoisingSchedule;
+    pub(super) use crate::gpu::denoised_blend_pass::DenoisedBlendPass;
     pub(super) use crate::utils::min_max_time_measurer::MinMaxTimeMeasurer;
     pub(super) use exr::prelude::write_rgba_file;
     pub(super) use pxm::PFMBuilder;
@@ -50,17 +104,88 @@ mod denoiser {
 pub(crate) struct Renderer {
     gpu: Gpu,
     uniforms: Uniforms,
+    #[cfg(feature = "monte_carlo")]
     pipeline_ray_tracing_monte_carlo: Rc<RefCell<ComputePipeline>>,
     pipeline_ray_tracing_deterministic: Rc<RefCell<ComputePipeline>>,
+    #[cfg(feature = "monte_carlo")]
+    pipeline_ray_tracing_monte_carlo_half_resolution_indirect: Rc<RefCell<ComputePipeline>>,
+    #[cfg(feature = "monte_carlo")]
+    pipeline_indirect_lighting_half_resolution: Rc<RefCell<ComputePipeline>>,
+    #[cfg(feature = "monte_carlo")]
+    pipeline_wavefront_resolve: Rc<RefCell<ComputePipeline>>,
+    #[cfg(feature = "monte_carlo")]
+    pipelines_wavefront: WavefrontPipelines,
+    #[cfg(feature = "monte_carlo")]
+    pipeline_reprojection_history: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    pipeline_temporal_reprojection: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    temporal_reprojection_enabled: bool,
+    #[cfg(feature = "monte_carlo")]
+    pipeline_debug_path: ComputePipeline,
     color_buffer_evaluation: ColorBufferEvaluationStrategy,
+    // the strategies the user has configured; the others run with their defaults
+    strategy_configs: HashMap<RenderStrategyId, RenderStrategyConfig>,
     pipeline_surface_attributes: ComputePipeline,
+    pipeline_procedural_texture_bake: ComputePipeline,
+    pipeline_sdf_distance_grids_bake: ComputePipeline,
+    pipeline_irradiance_probes: ComputePipeline,
+    pipeline_shadow_map: ComputePipeline,
+    // the key light's shadow map is to be cast again before the deterministic shading reads it
+    shadow_map_outdated: bool,
+    pipeline_luminance_histogram: ComputePipeline,
+    pipeline_auto_exposure: ComputePipeline,
     pipeline_final_image_rasterization: RasterizationPipeline,
+    // of the code each pipeline is built of, see 'recompose_shader_if_sdf_classes_changed'
+    pipeline_fingerprints: PipelineFingerprints,
+    sdf_classes_version: Version,
+    last_frame_statistics: FrameStatistics,
+    // the shader of newer sdf classes being compiled; the tracing waits for it, the last image is presented meanwhile
+    shader_compilation: Option<BackgroundShaderCompilation>,
+    background_shader_compilation: bool,
+    // the grids of the buffer are zeroes till baked, see 'bake_sdf_distance_grids'
+    sdf_distance_grids_baked: bool,
+    scene_bvh: SceneBvh,
+    scene_bvh_inflated: SceneBvh,
+    // the triangles and the bvh are to be re-serialized even if the scene is the same
+    geometry_layout_changed: bool,
+    // the object ids (and the other surface attributes) are not of the current frame buffer yet
+    surface_attributes_outdated: bool,
+    // the surface attributes pass has written the motion of a camera that stands still since
+    motion_vectors_stale: bool,
+    // the object id buffer is copied to the cpu whenever rewritten; without, the picking reads single ids
+    object_id_readback: bool,
+    camera_layer_mask_changed: bool,
+    // the accumulation the next pass continues instead of starting over
+    resumed_accumulation: Option<AccumulationCheckpoint>,
     objects: Hub,
+    overlay: Overlay,
+    highlighted_objects: Vec<ObjectUid>,
+    highlight_color: [f32; 4],
+    output_size: PhysicalSize<u32>,
+    fixed_aspect_ratio: Option<f64>,
+    frame_buffer_size_policy: FrameBufferSizePolicy,
+    viewport: Viewport,
+    sub_frame_animation: bool,
+    sub_frame: f64,
+    stop_criterion: Option<StopCriterion>,
+    convergence: Convergence,
+    exported_textures: Option<ExportedTextures>,
+    // kept at the cpu to be uploaded again on a new device
+    random_seed_texture: Option<RandomSeedTexture>,
 
     start_time: Instant,
 
     #[cfg(feature = "denoiser")]
     denoiser: denoiser::Denoiser,
+    #[cfg(feature = "denoiser")]
+    denoiser_device_type: denoiser::DenoiserDeviceType,
+    #[cfg(feature = "denoiser")]
+    denoise_mode: denoiser::DenoiseMode,
+    #[cfg(feature = "denoiser")]
+    denoiser_worker: Option<denoiser::DenoiserWorker>,
+    #[cfg(feature = "denoiser")]
+    denoising_schedule: denoiser::DenoisingSchedule,
 }
 
 struct Gpu {
@@ -71,6 +196,41 @@ struct Gpu {
     textures: BitmapTextures,
 
     pipelines_factory: PipelinesFactory,
+    overlay: OverlayPass,
+    selection_outline: SelectionOutlinePass,
+    bloom: BloomPass,
+    #[cfg(feature = "denoiser")]
+    denoised_blend: denoiser::DenoisedBlendPass,
+    picking: PickingPass,
+    pass_timer: RefCell<PassTimer>,
+}
+
+struct Pipelines {
+    #[cfg(feature = "monte_carlo")]
+    ray_tracing_monte_carlo: ComputePipeline,
+    ray_tracing_deterministic: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    ray_tracing_monte_carlo_half_resolution_indirect: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    indirect_lighting_half_resolution: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    wavefront_resolve: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    wavefront: WavefrontPipelines,
+    #[cfg(feature = "monte_carlo")]
+    reprojection_history: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    temporal_reprojection: ComputePipeline,
+    #[cfg(feature = "monte_carlo")]
+    debug_path: ComputePipeline,
+    surface_attributes: ComputePipeline,
+    procedural_texture_bake: ComputePipeline,
+    sdf_distance_grids_bake: ComputePipeline,
+    irradiance_probes: ComputePipeline,
+    shadow_map: ComputePipeline,
+    luminance_histogram: ComputePipeline,
+    auto_exposure: ComputePipeline,
+    final_image_rasterization: RasterizationPipeline,
 }
 
 pub(crate) struct FrameBufferSettings {
@@ -80,10 +240,29 @@ pub(crate) struct FrameBufferSettings {
 }
 
 impl FrameBufferSettings {
+    // the linear extended sRGB (scRGB) surface of the HDR displays: one is the SDR white, the brighter values pass through
+    const EXTENDED_RANGE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
     #[must_use]
     pub(crate) fn new(presentation_format: wgpu::TextureFormat, frame_buffer_size: FrameBufferSize, antialiasing_level: u32) -> Self {
         Self { presentation_format, frame_buffer_size, antialiasing_level }
     }
+
+    // the extended range one, if the surface supports it, otherwise the surface preferred one;
+    // 'formats' is not empty and comes in the order of preference, as the surface capabilities list them
+    #[must_use]
+    pub(crate) fn pick_presentation_format(formats: &[wgpu::TextureFormat]) -> wgpu::TextureFormat {
+        if formats.contains(&Self::EXTENDED_RANGE_FORMAT) {
+            Self::EXTENDED_RANGE_FORMAT
+        } else {
+            formats[0]
+        }
+    }
+
+    #[must_use]
+    fn extended_range(&self) -> bool {
+        Self::EXTENDED_RANGE_FORMAT == self.presentation_format
+    }
 }
 
 impl Renderer {
@@ -100,84 +279,702 @@ impl Renderer {
         -> anyhow::Result<Self>
     {
         let start_time = Instant::now();
+        let output_size = PhysicalSize::new(frame_buffer_settings.frame_buffer_size.width(), frame_buffer_settings.frame_buffer_size.height());
         let pixel_side_subdivision: u32 = 1;
         let mut uniforms = Uniforms::new(frame_buffer_settings.frame_buffer_size, camera, pixel_side_subdivision, start_time.elapsed());
+        uniforms.set_extended_range_output(frame_buffer_settings.extended_range());
 
         let scene = Hub::new(objects_container);
 
         let resources = Resources::new(context.clone());
-        let buffers = Self::init_buffers(&scene, &context, &mut uniforms, &resources);
+        let caches = caches_path.map(CachesDirectory::open);
+        let (mut scene_bvh, mut scene_bvh_inflated) = Self::make_scene_bvhs(caches.as_ref());
+        let mut pipelines_factory = PipelinesFactory::new(context.clone(), frame_buffer_settings.presentation_format, caches.map(|caches| caches.path_of(CacheKind::Pipelines)));
+        let buffers = Self::init_buffers(&scene, &mut scene_bvh, &mut scene_bvh_inflated, &context, &mut uniforms, &resources);
         let textures = BitmapTextures::new(&resources, scene.container().texture_atlas_page_size());
-        let pipelines_factory = PipelinesFactory::new(context.clone(), frame_buffer_settings.presentation_format, caches_path);
-
-        let mut gpu = Gpu { context, resources, buffers, textures, pipelines_factory };
-
-        let shader_source_text = scene.container().compose_shader(WHOLE_TRACER_GPU_CODE);
-        let shader_source_hash = seahash::hash(shader_source_text.as_bytes());
-
-        let shader_module = gpu.resources.create_shader_module("ray tracer shader", shader_source_text.as_str());
-
-        let monte_carlo_code = PipelineCode::new(shader_module.clone(), shader_source_hash, "monte_carlo_code".to_string());
-        let ray_tracing_monte_carlo = Rc::new(RefCell::new(
-            Self::create_ray_tracing_pipeline(&mut gpu, &monte_carlo_code, ComputeRoutineEntryPoint::RayTracingMonteCarlo, false)));
-
-        let deterministic_code = PipelineCode::new(shader_module.clone(), shader_source_hash, "deterministic_code".to_string());
-        let ray_tracing_deterministic = Rc::new(RefCell::new(
-            Self::create_ray_tracing_pipeline(&mut gpu, &deterministic_code, ComputeRoutineEntryPoint::RayTracingDeterministic, true)));
-
-        let surface_attributes_code = PipelineCode::new(shader_module.clone(), shader_source_hash, "surface_attributes_pipeline_code".to_string());
-        let surface_attributes = Self::create_surface_attributes_pipeline(&mut gpu, &surface_attributes_code);
-
+        let overlay = OverlayPass::new(context.device(), &resources, &mut pipelines_factory);
+        let selection_outline = SelectionOutlinePass::new(&resources, &mut pipelines_factory);
+        let bloom = BloomPass::new(&resources, &mut pipelines_factory);
+        #[cfg(feature = "denoiser")]
+        let denoised_blend = denoiser::DenoisedBlendPass::new(&resources, &mut pipelines_factory);
+        let picking = PickingPass::new(&resources, &mut pipelines_factory);
+
+        let pass_timer = RefCell::new(PassTimer::new(&context));
+        let mut gpu = Gpu { context, resources, buffers, textures, pipelines_factory, overlay, selection_outline, bloom, #[cfg(feature = "denoiser")] denoised_blend, picking, pass_timer };
+
+        #[cfg(feature = "monte_carlo")]
+        let default_strategy_id = RenderStrategyId::MonteCarlo;
+        #[cfg(not(feature = "monte_carlo"))]
+        let default_strategy_id = RenderStrategyId::Deterministic;
+        let shader = ComposedShader::new(WHOLE_TRACER_GPU_CODE, scene.container(), &gpu.resources);
+        let pipelines = Self::create_pipelines(&mut gpu, &shader, default_strategy_id);
+
+        #[cfg(feature = "monte_carlo")]
+        let ray_tracing_monte_carlo = Rc::new(RefCell::new(pipelines.ray_tracing_monte_carlo));
+        let ray_tracing_deterministic = Rc::new(RefCell::new(pipelines.ray_tracing_deterministic));
+        #[cfg(feature = "monte_carlo")]
+        let ray_tracing_monte_carlo_half_resolution_indirect = Rc::new(RefCell::new(pipelines.ray_tracing_monte_carlo_half_resolution_indirect));
+        #[cfg(feature = "monte_carlo")]
+        let indirect_lighting_half_resolution = Rc::new(RefCell::new(pipelines.indirect_lighting_half_resolution));
+        #[cfg(feature = "monte_carlo")]
+        let wavefront_resolve = Rc::new(RefCell::new(pipelines.wavefront_resolve));
+
+        #[cfg(feature = "monte_carlo")]
         let default_strategy = ColorBufferEvaluationStrategy::new_monte_carlo(ray_tracing_monte_carlo.clone());
-        let final_image_rasterization_code = PipelineCode::new(shader_module.clone(), shader_source_hash, "final_image_rasterization_code".to_string());
-        let final_image_rasterization = Self::create_rasterization_pipeline(&mut gpu, &final_image_rasterization_code, default_strategy.id());
+        #[cfg(not(feature = "monte_carlo"))]
+        let default_strategy = ColorBufferEvaluationStrategy::new_deterministic(ray_tracing_deterministic.clone());
 
         let mut renderer = Self {
             gpu,
             uniforms,
+            #[cfg(feature = "monte_carlo")]
             pipeline_ray_tracing_monte_carlo: ray_tracing_monte_carlo.clone(),
             pipeline_ray_tracing_deterministic: ray_tracing_deterministic.clone(),
+            #[cfg(feature = "monte_carlo")]
+            pipeline_ray_tracing_monte_carlo_half_resolution_indirect: ray_tracing_monte_carlo_half_resolution_indirect.clone(),
+            #[cfg(feature = "monte_carlo")]
+            pipeline_indirect_lighting_half_resolution: indirect_lighting_half_resolution.clone(),
+            #[cfg(feature = "monte_carlo")]
+            pipeline_wavefront_resolve: wavefront_resolve.clone(),
+            #[cfg(feature = "monte_carlo")]
+            pipelines_wavefront: pipelines.wavefront,
+            #[cfg(feature = "monte_carlo")]
+            pipeline_reprojection_history: pipelines.reprojection_history,
+            #[cfg(feature = "monte_carlo")]
+            pipeline_temporal_reprojection: pipelines.temporal_reprojection,
+            #[cfg(feature = "monte_carlo")]
+            temporal_reprojection_enabled: false,
+            #[cfg(feature = "monte_carlo")]
+            pipeline_debug_path: pipelines.debug_path,
             color_buffer_evaluation: default_strategy,
-            pipeline_surface_attributes: surface_attributes,
-            pipeline_final_image_rasterization: final_image_rasterization,
+            strategy_configs: HashMap::from([(strategy, RenderStrategyConfig::default_for(strategy).with_pixel_subdivision(frame_buffer_settings.antialiasing_level))]),
+            pipeline_surface_attributes: pipelines.surface_attributes,
+            pipeline_procedural_texture_bake: pipelines.procedural_texture_bake,
+            pipeline_sdf_distance_grids_bake: pipelines.sdf_distance_grids_bake,
+            pipeline_irradiance_probes: pipelines.irradiance_probes,
+            pipeline_shadow_map: pipelines.shadow_map,
+            shadow_map_outdated: true,
+            pipeline_luminance_histogram: pipelines.luminance_histogram,
+            pipeline_auto_exposure: pipelines.auto_exposure,
+            pipeline_final_image_rasterization: pipelines.final_image_rasterization,
+            pipeline_fingerprints: shader.fingerprints().clone(),
+            sdf_classes_version: scene.container().sdf_classes_version(),
+            last_frame_statistics: FrameStatistics::default(),
+            shader_compilation: None,
+            background_shader_compilation: false,
+            sdf_distance_grids_baked: false,
+            scene_bvh,
+            scene_bvh_inflated,
+            geometry_layout_changed: false,
+            surface_attributes_outdated: true,
+            motion_vectors_stale: false,
+            object_id_readback: true,
+            camera_layer_mask_changed: false,
+            resumed_accumulation: None,
             objects: scene,
+            overlay: Overlay::default(),
+            highlighted_objects: Vec::new(),
+            highlight_color: [1.0, 1.0, 1.0, 1.0],
+            output_size,
+            fixed_aspect_ratio: None,
+            frame_buffer_size_policy: FrameBufferSizePolicy::default(),
+            viewport: Viewport::fit(output_size, None),
+            sub_frame_animation: false,
+            sub_frame: 1.0,
+            stop_criterion: None,
+            convergence: Convergence::new(),
+            exported_textures: None,
+            random_seed_texture: None,
 
             start_time,
 
             #[cfg(feature = "denoiser")]
-            denoiser: denoiser::Denoiser::new(),
+            denoiser: denoiser::Denoiser::new(denoiser::DenoiserDeviceType::default(), denoiser::DenoiseMode::default()),
+            #[cfg(feature = "denoiser")]
+            denoiser_device_type: denoiser::DenoiserDeviceType::default(),
+            #[cfg(feature = "denoiser")]
+            denoise_mode: denoiser::DenoiseMode::default(),
+            #[cfg(feature = "denoiser")]
+            denoiser_worker: None,
+            #[cfg(feature = "denoiser")]
+            denoising_schedule: denoiser::DenoisingSchedule::new(),
         };
-        renderer.set_render_strategy(strategy, frame_buffer_settings.antialiasing_level);
-        
+        renderer.set_render_strategy(strategy);
+
         Ok(renderer)
     }
 
+    // with the caches directory, the trees of a static scene are loaded instead of being built
+    #[must_use]
+    fn make_scene_bvhs(caches: Option<&CachesDirectory>) -> (SceneBvh, SceneBvh) {
+        let scene_bvh = SceneBvh::new(0.0);
+        let scene_bvh_inflated = SceneBvh::new(Self::BVH_INFLATION_RATE);
+        match caches.map(|caches| caches.path_of(CacheKind::Bvh)) {
+            Some(directory) => (scene_bvh.with_dump(directory.join("bvh_dump")), scene_bvh_inflated.with_dump(directory.join("bvh_inflated_dump"))),
+            None => (scene_bvh, scene_bvh_inflated),
+        }
+    }
+
+    #[must_use]
+    fn create_pipelines(gpu: &mut Gpu, shader: &ComposedShader, render_strategy: RenderStrategyId) -> Pipelines {
+        #[cfg(feature = "monte_carlo")]
+        let ray_tracing_monte_carlo = {
+            let monte_carlo_code = shader.code("monte_carlo_code");
+            Self::create_ray_tracing_pipeline(gpu, &monte_carlo_code, ComputeRoutineEntryPoint::RayTracingMonteCarlo, false, false, true)
+        };
+
+        let deterministic_code = shader.code("deterministic_code");
+        let ray_tracing_deterministic = Self::create_ray_tracing_pipeline(gpu, &deterministic_code, ComputeRoutineEntryPoint::RayTracingDeterministic, true, true, false);
+
+        let irradiance_probes_code = shader.code("irradiance_probes_code");
+        let irradiance_probes = Self::create_irradiance_probes_pipeline(gpu, &irradiance_probes_code);
+
+        let shadow_map_code = shader.code("shadow_map_code");
+        let shadow_map = Self::create_shadow_map_pipeline(gpu, &shadow_map_code);
+
+        let auto_exposure_code = shader.code("auto_exposure_code");
+        let luminance_histogram = Self::create_auto_exposure_pipeline(gpu, &auto_exposure_code, ComputeRoutineEntryPoint::LuminanceHistogram);
+        let auto_exposure = Self::create_auto_exposure_pipeline(gpu, &auto_exposure_code, ComputeRoutineEntryPoint::AutoExposure);
+
+        #[cfg(feature = "monte_carlo")]
+        let (ray_tracing_monte_carlo_half_resolution_indirect, indirect_lighting_half_resolution) = {
+            let half_resolution_indirect_code = shader.code("half_resolution_indirect_code");
+            (
+                Self::create_half_resolution_indirect_composition_pipeline(gpu, &half_resolution_indirect_code),
+                Self::create_indirect_lighting_half_resolution_pipeline(gpu, &half_resolution_indirect_code),
+            )
+        };
+
+        #[cfg(feature = "monte_carlo")]
+        let (wavefront_resolve, wavefront) = {
+            let wavefront_code = shader.code("wavefront_code");
+            (
+                Self::create_wavefront_resolve_pipeline(gpu, &wavefront_code),
+                Self::create_wavefront_pipelines(gpu, &wavefront_code),
+            )
+        };
+
+        #[cfg(feature = "monte_carlo")]
+        let (reprojection_history, temporal_reprojection) = {
+            let temporal_reprojection_code = shader.code("temporal_reprojection_code");
+            (
+                Self::create_reprojection_history_pipeline(gpu, &temporal_reprojection_code),
+                Self::create_temporal_reprojection_pipeline(gpu, &temporal_reprojection_code),
+            )
+        };
+
+        #[cfg(feature = "monte_carlo")]
+        let debug_path = {
+            let debug_path_code = shader.code("debug_path_code");
+            Self::create_debug_path_pipeline(gpu, &debug_path_code)
+        };
+
+        let surface_attributes_code = shader.code("surface_attributes_pipeline_code");
+        let surface_attributes = Self::create_surface_attributes_pipeline(gpu, &surface_attributes_code);
+
+        let procedural_texture_bake_code = shader.code("procedural_texture_bake_code");
+        let procedural_texture_bake = Self::create_procedural_texture_bake_pipeline(gpu, &procedural_texture_bake_code);
+
+        let sdf_distance_grids_bake_code = shader.code("sdf_distance_grids_bake_code");
+        let sdf_distance_grids_bake = Self::create_sdf_distance_grids_bake_pipeline(gpu, &sdf_distance_grids_bake_code);
+
+        let final_image_rasterization_code = shader.code("final_image_rasterization_code");
+        let final_image_rasterization = Self::create_rasterization_pipeline(gpu, &final_image_rasterization_code, render_strategy);
+
+        Pipelines {
+            #[cfg(feature = "monte_carlo")]
+            ray_tracing_monte_carlo,
+            ray_tracing_deterministic,
+            #[cfg(feature = "monte_carlo")]
+            ray_tracing_monte_carlo_half_resolution_indirect,
+            #[cfg(feature = "monte_carlo")]
+            indirect_lighting_half_resolution,
+            #[cfg(feature = "monte_carlo")]
+            wavefront_resolve,
+            #[cfg(feature = "monte_carlo")]
+            wavefront,
+            #[cfg(feature = "monte_carlo")]
+            reprojection_history,
+            #[cfg(feature = "monte_carlo")]
+            temporal_reprojection,
+            #[cfg(feature = "monte_carlo")]
+            debug_path,
+            surface_attributes,
+            procedural_texture_bake,
+            sdf_distance_grids_bake,
+            irradiance_probes,
+            shadow_map,
+            luminance_histogram,
+            auto_exposure,
+            final_image_rasterization,
+        }
+    }
+
+    // sdf classes were changed at runtime: the scene buffers are kept, only the code is replaced
+    fn recompose_shader_if_sdf_classes_changed(&mut self) {
+        self.install_shader_if_compiled();
+        let actual_version = self.objects.container().sdf_classes_version();
+        if self.sdf_classes_version == actual_version {
+            return;
+        }
+        if self.background_shader_compilation {
+            if self.shader_compilation.as_ref().is_none_or(|compilation| compilation.sdf_classes_version() != actual_version) {
+                // the compilation of older classes is superseded, its outcome dropped
+                let source = self.objects.container().compose_shader(WHOLE_TRACER_GPU_CODE);
+                self.shader_compilation = Some(BackgroundShaderCompilation::start(self.gpu.context.device(), source, self.pipeline_fingerprints.clone(), actual_version));
+            }
+            return;
+        }
+        self.sdf_classes_version = actual_version;
+
+        let shader = ComposedShader::new(WHOLE_TRACER_GPU_CODE, self.objects.container(), &self.gpu.resources);
+        self.install_composed_shader(&shader);
+    }
+
+    fn install_shader_if_compiled(&mut self) {
+        let Some(compilation) = self.shader_compilation.take() else {
+            return;
+        };
+        let shader = match compilation.poll() {
+            CompilationPoll::Pending => {
+                self.shader_compilation = Some(compilation);
+                return;
+            },
+            CompilationPoll::Compiled(compiled) => {
+                let (shader, pipelines) = compiled.into_parts();
+                self.gpu.pipelines_factory.add_prebuilt(pipelines);
+                shader
+            },
+            // composed again here, so that the error (if any) is reported the usual way
+            CompilationPoll::Failed => ComposedShader::new(WHOLE_TRACER_GPU_CODE, self.objects.container(), &self.gpu.resources),
+        };
+        self.sdf_classes_version = compilation.sdf_classes_version();
+        self.install_composed_shader(&shader);
+        self.gpu.pipelines_factory.forget_prebuilt();
+    }
+
+    #[must_use]
+    pub(crate) fn shader_compilation_pending(&self) -> bool {
+        self.shader_compilation.is_some()
+    }
+
+    // with it on, the change of the sdf classes leaves the tracing paused till the new shader is compiled
+    pub(crate) fn set_background_shader_compilation(&mut self, enabled: bool) {
+        self.background_shader_compilation = enabled;
+    }
+
+    fn install_composed_shader(&mut self, shader: &ComposedShader) {
+        self.gpu.buffers.sdf_distance_grids = Self::make_sdf_distance_grids_buffer(self.objects.container(), &self.gpu.resources);
+        self.sdf_distance_grids_baked = false;
+
+        let outdated = shader.outdated_since(&self.pipeline_fingerprints);
+        self.recreate_pipelines(shader, &outdated);
+        self.pipeline_fingerprints = shader.fingerprints().clone();
+
+        // the kept pipelines still read the replaced grids buffer
+        self.bind_scene_buffers();
+        Self::bind_sdf_distance_grids_for_bake(&self.gpu, &mut self.pipeline_sdf_distance_grids_bake);
+
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    // only the pipelines of the 'outdated' codes, the other ones are kept as they are
+    fn recreate_pipelines(&mut self, shader: &ComposedShader, outdated: &HashSet<&'static str>) {
+        let gpu = &mut self.gpu;
+        #[cfg(feature = "monte_carlo")]
+        {
+            if outdated.contains("monte_carlo_code") {
+                *self.pipeline_ray_tracing_monte_carlo.borrow_mut() = Self::create_ray_tracing_pipeline(gpu, &shader.code("monte_carlo_code"), ComputeRoutineEntryPoint::RayTracingMonteCarlo, false, false, true);
+            }
+            if outdated.contains("half_resolution_indirect_code") {
+                let code = shader.code("half_resolution_indirect_code");
+                *self.pipeline_ray_tracing_monte_carlo_half_resolution_indirect.borrow_mut() = Self::create_half_resolution_indirect_composition_pipeline(gpu, &code);
+                *self.pipeline_indirect_lighting_half_resolution.borrow_mut() = Self::create_indirect_lighting_half_resolution_pipeline(gpu, &code);
+            }
+            if outdated.contains("wavefront_code") {
+                let code = shader.code("wavefront_code");
+                *self.pipeline_wavefront_resolve.borrow_mut() = Self::create_wavefront_resolve_pipeline(gpu, &code);
+                self.pipelines_wavefront = Self::create_wavefront_pipelines(gpu, &code);
+            }
+            if outdated.contains("temporal_reprojection_code") {
+                let code = shader.code("temporal_reprojection_code");
+                self.pipeline_reprojection_history = Self::create_reprojection_history_pipeline(gpu, &code);
+                self.pipeline_temporal_reprojection = Self::create_temporal_reprojection_pipeline(gpu, &code);
+            }
+            if outdated.contains("debug_path_code") {
+                self.pipeline_debug_path = Self::create_debug_path_pipeline(gpu, &shader.code("debug_path_code"));
+            }
+        }
+        if outdated.contains("deterministic_code") {
+            *self.pipeline_ray_tracing_deterministic.borrow_mut() = Self::create_ray_tracing_pipeline(gpu, &shader.code("deterministic_code"), ComputeRoutineEntryPoint::RayTracingDeterministic, true, true, false);
+        }
+        if outdated.contains("irradiance_probes_code") {
+            self.pipeline_irradiance_probes = Self::create_irradiance_probes_pipeline(gpu, &shader.code("irradiance_probes_code"));
+        }
+        if outdated.contains("shadow_map_code") {
+            self.pipeline_shadow_map = Self::create_shadow_map_pipeline(gpu, &shader.code("shadow_map_code"));
+        }
+        if outdated.contains("auto_exposure_code") {
+            let code = shader.code("auto_exposure_code");
+            self.pipeline_luminance_histogram = Self::create_auto_exposure_pipeline(gpu, &code, ComputeRoutineEntryPoint::LuminanceHistogram);
+            self.pipeline_auto_exposure = Self::create_auto_exposure_pipeline(gpu, &code, ComputeRoutineEntryPoint::AutoExposure);
+        }
+        if outdated.contains("surface_attributes_pipeline_code") {
+            self.pipeline_surface_attributes = Self::create_surface_attributes_pipeline(gpu, &shader.code("surface_attributes_pipeline_code"));
+        }
+        if outdated.contains("procedural_texture_bake_code") {
+            self.pipeline_procedural_texture_bake = Self::create_procedural_texture_bake_pipeline(gpu, &shader.code("procedural_texture_bake_code"));
+        }
+        if outdated.contains("sdf_distance_grids_bake_code") {
+            self.pipeline_sdf_distance_grids_bake = Self::create_sdf_distance_grids_bake_pipeline(gpu, &shader.code("sdf_distance_grids_bake_code"));
+        }
+        if outdated.contains("final_image_rasterization_code") {
+            self.pipeline_final_image_rasterization = Self::create_rasterization_pipeline(gpu, &shader.code("final_image_rasterization_code"), self.color_buffer_evaluation.id());
+        }
+    }
+
+    // the versions of the replaced scene say nothing about the buffers made from the previous one,
+    // so everything the scene is made into is built anew, once
+    fn rebuild_if_scene_replaced(&mut self) {
+        if false == self.objects.take_scene_replacement() {
+            return;
+        }
+        let probe_grid = self.uniforms.probe_grid();
+        self.uniforms.set_probe_grid(None);
+
+        self.scene_bvh.forget_tree();
+        self.scene_bvh_inflated.forget_tree();
+        self.gpu.buffers = Self::init_buffers(&self.objects, &mut self.scene_bvh, &mut self.scene_bvh_inflated, &self.gpu.context, &mut self.uniforms, &self.gpu.resources);
+        self.gpu.textures = BitmapTextures::new(&self.gpu.resources, self.objects.container().texture_atlas_page_size());
+
+        let shader = ComposedShader::new(WHOLE_TRACER_GPU_CODE, self.objects.container(), &self.gpu.resources);
+        let pipelines = Self::create_pipelines(&mut self.gpu, &shader, self.color_buffer_evaluation.id());
+        self.install_pipelines(pipelines);
+        self.pipeline_fingerprints = shader.fingerprints().clone();
+
+        self.sdf_classes_version = self.objects.container().sdf_classes_version();
+        self.shader_compilation = None;
+        self.sdf_distance_grids_baked = false;
+        self.geometry_layout_changed = false;
+        self.resumed_accumulation = None;
+        if let Some(uid) = self.uniforms.background() && false == self.objects.container().has_background(uid) {
+            self.uniforms.set_background(None);
+        }
+        self.set_probe_grid(probe_grid);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    // the strategies share the ray tracing pipelines, so those are replaced in place
+    fn install_pipelines(&mut self, pipelines: Pipelines) {
+        #[cfg(feature = "monte_carlo")]
+        {
+            *self.pipeline_ray_tracing_monte_carlo.borrow_mut() = pipelines.ray_tracing_monte_carlo;
+            *self.pipeline_ray_tracing_monte_carlo_half_resolution_indirect.borrow_mut() = pipelines.ray_tracing_monte_carlo_half_resolution_indirect;
+            *self.pipeline_indirect_lighting_half_resolution.borrow_mut() = pipelines.indirect_lighting_half_resolution;
+            *self.pipeline_wavefront_resolve.borrow_mut() = pipelines.wavefront_resolve;
+            self.pipelines_wavefront = pipelines.wavefront;
+            self.pipeline_reprojection_history = pipelines.reprojection_history;
+            self.pipeline_temporal_reprojection = pipelines.temporal_reprojection;
+            self.pipeline_debug_path = pipelines.debug_path;
+        }
+        *self.pipeline_ray_tracing_deterministic.borrow_mut() = pipelines.ray_tracing_deterministic;
+        self.pipeline_surface_attributes = pipelines.surface_attributes;
+        self.pipeline_procedural_texture_bake = pipelines.procedural_texture_bake;
+        self.pipeline_sdf_distance_grids_bake = pipelines.sdf_distance_grids_bake;
+        self.pipeline_irradiance_probes = pipelines.irradiance_probes;
+        self.pipeline_shadow_map = pipelines.shadow_map;
+        self.pipeline_luminance_histogram = pipelines.luminance_histogram;
+        self.pipeline_auto_exposure = pipelines.auto_exposure;
+        self.pipeline_final_image_rasterization = pipelines.final_image_rasterization;
+    }
+
+    // the previous device is lost along with everything on it: the scene, the camera and the settings
+    // are kept at the cpu, so the buffers and the pipelines are made anew from them; so is the atlas
+    // page last uploaded
+    pub(crate) fn recreate_on_device(&mut self, context: Rc<Context>) {
+        let probe_grid = self.uniforms.probe_grid();
+        self.uniforms.set_probe_grid(None);
+
+        let resources = Resources::new(context.clone());
+        let mut pipelines_factory = self.gpu.pipelines_factory.for_context(context.clone());
+        let buffers = Self::init_buffers(&self.objects, &mut self.scene_bvh, &mut self.scene_bvh_inflated, &context, &mut self.uniforms, &resources);
+        let textures = self.gpu.textures.recreate(&resources);
+        let overlay = OverlayPass::new(context.device(), &resources, &mut pipelines_factory);
+        let selection_outline = SelectionOutlinePass::new(&resources, &mut pipelines_factory);
+        let bloom = BloomPass::new(&resources, &mut pipelines_factory);
+        #[cfg(feature = "denoiser")]
+        let mut denoised_blend = denoiser::DenoisedBlendPass::new(&resources, &mut pipelines_factory);
+        #[cfg(feature = "denoiser")]
+        denoised_blend.set_bypassed(self.gpu.denoised_blend.bypassed());
+        let picking = PickingPass::new(&resources, &mut pipelines_factory);
+        let pass_timer = RefCell::new(PassTimer::new(&context));
+        self.gpu = Gpu { context, resources, buffers, textures, pipelines_factory, overlay, selection_outline, bloom, #[cfg(feature = "denoiser")] denoised_blend, picking, pass_timer };
+        self.gpu.buffers.random_seeds = Self::make_random_seeds_buffer(&self.gpu.resources, self.random_seed_texture.as_ref());
+
+        let shader = ComposedShader::new(WHOLE_TRACER_GPU_CODE, self.objects.container(), &self.gpu.resources);
+        let pipelines = Self::create_pipelines(&mut self.gpu, &shader, self.color_buffer_evaluation.id());
+        self.install_pipelines(pipelines);
+        self.pipeline_fingerprints = shader.fingerprints().clone();
+
+        self.sdf_classes_version = self.objects.container().sdf_classes_version();
+        self.shader_compilation = None;
+        self.sdf_distance_grids_baked = false;
+        self.geometry_layout_changed = false;
+        self.surface_attributes_outdated = true;
+        self.exported_textures = None;
+        self.set_probe_grid(probe_grid);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
     #[must_use]
     pub(crate) fn objects(&mut self) -> &mut Hub {
         &mut self.objects
     }
+
+    #[must_use]
+    pub(crate) fn overlay(&mut self) -> &mut Overlay {
+        &mut self.overlay
+    }
     
     pub(crate) fn upload_texture_atlas_page(&mut self, data: &[u8], data_version: Option<Version>) {
         self.gpu.textures.set_atlas_page(&self.gpu.resources, data, data_version);
     }
     
-    pub(crate) fn set_render_strategy(&mut self, flavour: RenderStrategyId, antialiasing_level: u32) {
-        if self.color_buffer_evaluation.id() == flavour {
+    pub(crate) fn set_probe_grid(&mut self, probe_grid: Option<ProbeGrid>) {
+        if self.uniforms.probe_grid() == probe_grid {
             return;
         }
-        
-        self.color_buffer_evaluation = match flavour {
-            RenderStrategyId::MonteCarlo => {
-                ColorBufferEvaluationStrategy::new_monte_carlo(self.pipeline_ray_tracing_monte_carlo.clone())
-            }
-            RenderStrategyId::Deterministic => {
-                ColorBufferEvaluationStrategy::new_deterministic(self.pipeline_ray_tracing_deterministic.clone())
+        self.uniforms.set_probe_grid(probe_grid);
+
+        let probes_count = probe_grid.map_or(1, |grid| grid.probes_count()) as usize;
+        let cleared_probes = vec![PodVector::default(); probes_count * Self::IRRADIANCE_PROBE_FACES];
+        let status = self.gpu.buffers.irradiance_probes.update_with_slice(&self.gpu.resources, self.gpu.context.queue(), &cleared_probes);
+        if status == ResizeStatus::Resized {
+            Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_ray_tracing_deterministic.borrow_mut().deref_mut(), true, true, false);
+            Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_irradiance_probes, true, true, false);
+            Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_shadow_map, true, true, false);
+        }
+
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    pub(crate) fn set_key_light_shadows(&mut self, shadows: KeyLightShadows) {
+        let resolution = shadows.shadow_map_resolution();
+        if self.uniforms.shadow_map_resolution() == resolution {
+            return;
+        }
+        self.uniforms.set_shadow_map_resolution(resolution);
+
+        if resolution > 0 {
+            // the zeroed header keeps the shadow rays until the map is cast
+            let texels = vec![0.0_f32; Self::SHADOW_MAP_HEADER_SIZE + (resolution as usize).pow(2)];
+            let status = self.gpu.buffers.shadow_map.update_with_slice(&self.gpu.resources, self.gpu.context.queue(), &texels);
+            if status == ResizeStatus::Resized {
+                Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_ray_tracing_deterministic.borrow_mut().deref_mut(), true, true, false);
+                Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_irradiance_probes, true, true, false);
+                Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_shadow_map, true, true, false);
             }
-        };
-        
+            self.shadow_map_outdated = true;
+        }
+
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    pub(crate) fn set_background(&mut self, background: Option<BackgroundUid>) {
+        if let Some(uid) = background {
+            assert!(self.objects.container().has_background(uid), "background {} is not registered", uid);
+        }
+        if self.uniforms.background() == background {
+            return;
+        }
+        self.uniforms.set_background(background);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    pub(crate) fn set_clear_color(&mut self, clear_color: [f32; 4]) {
+        assert!(clear_color.iter().all(|component| *component >= 0.0), "clear color components must not be negative");
+        assert_le!(clear_color[3], 1.0);
+        if self.uniforms.clear_color() == clear_color {
+            return;
+        }
+        self.uniforms.set_clear_color(clear_color);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    // grading is applied by the output pass only, so the accumulated samples stay valid
+    pub(crate) fn set_color_grading(&mut self, color_grading: ColorGrading) {
+        self.uniforms.set_color_grading(color_grading);
+    }
+
+    // the same as the grading: the tone mapping is a part of the output pass
+    pub(crate) fn set_display_mapping(&mut self, display_mapping: DisplayMapping) {
+        self.uniforms.set_display_mapping(display_mapping);
+    }
+
+    #[must_use]
+    pub(crate) fn display_mapping(&self) -> DisplayMapping {
+        self.uniforms.display_mapping()
+    }
+
+    // no HDR metadata reaches the display: wgpu does not expose it, so the display applies its defaults
+    #[must_use]
+    pub(crate) fn extended_range_output(&self) -> bool {
+        self.uniforms.extended_range_output()
+    }
+
+    // spread in the output pass, so the accumulated samples stay valid
+    pub(crate) fn set_bloom(&mut self, bloom: Option<Bloom>) {
+        self.uniforms.set_bloom(bloom);
+    }
+
+    // the exposure adapts in the output pass, so the accumulated samples stay valid
+    pub(crate) fn set_auto_exposure(&mut self, auto_exposure: Option<AutoExposure>) {
+        if self.uniforms.auto_exposure() == auto_exposure {
+            return;
+        }
+        if self.uniforms.auto_exposure().is_none() {
+            self.gpu.context.queue().write_buffer(&self.gpu.buffers.auto_exposure, 0, bytemuck::cast_slice(&Self::AUTO_EXPOSURE_INITIAL_STATE));
+        }
+        self.uniforms.set_auto_exposure(auto_exposure);
+    }
+
+    #[must_use]
+    pub(crate) fn auto_exposure(&self) -> Option<AutoExposure> {
+        self.uniforms.auto_exposure()
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    pub(crate) fn set_temporal_reprojection(&mut self, enabled: bool) {
+        self.temporal_reprojection_enabled = enabled;
+    }
+
+    // the inset is drawn by the output pass only, so the accumulated samples stay valid
+    pub(crate) fn set_magnifier(&mut self, magnifier: Option<Magnifier>) {
+        self.uniforms.set_magnifier(magnifier);
+    }
+
+    pub(crate) fn set_highlighted_objects(&mut self, objects: &[ObjectUid], color: [f32; 4]) {
+        self.highlighted_objects = objects.to_vec();
+        self.highlight_color = color;
+    }
+
+    // the estimator changes, so the accumulated samples are dropped
+    #[cfg(feature = "monte_carlo")]
+    pub(crate) fn set_next_event_estimation(&mut self, enabled: bool) {
+        if self.uniforms.next_event_estimation() == enabled {
+            return;
+        }
+        self.uniforms.set_next_event_estimation(enabled);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    // the traced objects change, so the accumulated samples and the surface attributes are dropped
+    pub(crate) fn set_camera_layer_mask(&mut self, mask: LayerMask) {
+        if self.uniforms.camera_layer_mask() == mask {
+            return;
+        }
+        self.uniforms.set_camera_layer_mask(mask);
+        self.camera_layer_mask_changed = true;
+    }
+
+    // the proxies change the traced geometry, so the accumulated samples are dropped
+    pub(crate) fn set_sdf_level_of_detail(&mut self, sdf_level_of_detail: Option<SdfLevelOfDetail>) {
+        if self.uniforms.sdf_level_of_detail() == sdf_level_of_detail {
+            return;
+        }
+        self.uniforms.set_sdf_level_of_detail(sdf_level_of_detail);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    // reads the whole accumulated image back, so it is for debugging only
+    #[must_use]
+    pub(crate) fn magnifier_readout(&mut self) -> Option<MagnifierReadout> {
+        let magnifier = self.uniforms.magnifier()?;
+
+        self.copy_noisy_pixels_to_cpu();
+
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        let divider = self.uniforms.frame_number().max(1) as f32;
+        let pixels = self.gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color_at_cpu();
+        Some(MagnifierReadout::new(&magnifier, frame_buffer_size.width(), frame_buffer_size.height(), |x, y| {
+            let pixel = pixels[(y * frame_buffer_size.width() + x) as usize];
+            [pixel.x / divider, pixel.y / divider, pixel.z / divider]
+        }))
+    }
+
+    // the scene buffers get smaller: vertices are stored as 16 bit fractions of their mesh boxes
+    // and the bvh boxes as 16 bit fractions of the scene box (rounded outwards, so nothing is missed)
+    pub(crate) fn set_quantized_geometry(&mut self, enabled: bool) {
+        if self.uniforms.geometry_quantized() == enabled {
+            return;
+        }
+        self.uniforms.set_geometry_quantized(enabled);
+        self.scene_bvh.set_quantized(enabled);
+        self.scene_bvh_inflated.set_quantized(enabled);
+        self.geometry_layout_changed = true;
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+    }
+
+    pub(crate) fn set_antialiasing_level(&mut self, level: u32) {
+        let strategy = self.color_buffer_evaluation.id();
+        let config = self.render_strategy_config(strategy).with_pixel_subdivision(level);
+        self.set_render_strategy_config(strategy, config);
+    }
+
+    #[must_use]
+    pub(crate) fn render_strategy(&self) -> RenderStrategyId {
+        self.color_buffer_evaluation.id()
+    }
+
+    #[must_use]
+    pub(crate) fn render_strategy_config(&self, strategy: RenderStrategyId) -> RenderStrategyConfig {
+        self.strategy_configs.get(&strategy).copied().unwrap_or_else(|| RenderStrategyConfig::default_for(strategy))
+    }
+
+    // the config of the strategy in use is applied at once, the others when switched to
+    pub(crate) fn set_render_strategy_config(&mut self, strategy: RenderStrategyId, config: RenderStrategyConfig) {
+        self.strategy_configs.insert(strategy, config);
+        if self.color_buffer_evaluation.id() == strategy {
+            self.apply_render_strategy_config(config);
+        }
+    }
+
+    fn apply_render_strategy_config(&mut self, config: RenderStrategyConfig) {
+        if self.uniforms.pixel_side_subdivision() == config.pixel_subdivision() && self.uniforms.max_ray_bounces() == config.max_bounces() {
+            return;
+        }
+        self.uniforms.set_pixel_side_subdivision(config.pixel_subdivision());
+        self.uniforms.set_max_ray_bounces(config.max_bounces());
         self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
-        self.uniforms.set_pixel_side_subdivision(antialiasing_level);
-        Self::setup_frame_buffers_bindings_for_rasterization(&self.gpu, &mut self.pipeline_final_image_rasterization, flavour);
+    }
+
+    pub(crate) fn set_render_strategy(&mut self, flavour: RenderStrategyId) {
+        if self.color_buffer_evaluation.id() != flavour {
+            self.color_buffer_evaluation = match flavour {
+                #[cfg(feature = "monte_carlo")]
+                RenderStrategyId::MonteCarlo => {
+                    ColorBufferEvaluationStrategy::new_monte_carlo(self.pipeline_ray_tracing_monte_carlo.clone())
+                }
+                #[cfg(feature = "monte_carlo")]
+                RenderStrategyId::MonteCarloHalfResolutionIndirect => {
+                    ColorBufferEvaluationStrategy::new_monte_carlo_half_resolution_indirect(
+                        self.pipeline_ray_tracing_monte_carlo_half_resolution_indirect.clone(),
+                        self.pipeline_indirect_lighting_half_resolution.clone(),
+                    )
+                }
+                #[cfg(feature = "monte_carlo")]
+                RenderStrategyId::Wavefront => {
+                    ColorBufferEvaluationStrategy::new_wavefront(self.pipeline_wavefront_resolve.clone())
+                }
+                RenderStrategyId::Deterministic => {
+                    ColorBufferEvaluationStrategy::new_deterministic(self.pipeline_ray_tracing_deterministic.clone())
+                }
+            };
+
+            self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+            Self::setup_frame_buffers_bindings_for_rasterization(&self.gpu, &mut self.pipeline_final_image_rasterization, flavour);
+        }
+        self.apply_render_strategy_config(self.render_strategy_config(flavour));
     }
     
     #[must_use]
@@ -185,31 +982,31 @@ impl Renderer {
         1 == self.color_buffer_evaluation.frame_counter_increment()
     }
 
+    // while the slots count stays the same, only the range of the slots written since the buffer version is uploaded
     #[must_use]
     fn update_buffer<T: GpuSerializationSize>(geometry_kind: &'static DataKind, buffer: &mut VersionedBuffer, resources: &Resources, scene: &VisualObjects, queue: &wgpu::Queue,) -> BufferUpdateStatus {
         let actual_data_version = scene.data_version(*geometry_kind);
-        let serializer = || Self::serialize_scene_data::<T>(scene, geometry_kind);
-        buffer.try_update_with_generator(actual_data_version, resources, queue, serializer)
-    }
-    
-    #[must_use]
-    fn serialize_triangles(scene: &VisualObjects) -> GpuReadySerializationBuffer {
-        if scene.triangles_count() > 0 {
-            scene.evaluate_serialized_triangles()
-        } else {
-            Self::make_empty_buffer_marker::<Triangle>()
+        if false == buffer.version_diverges(actual_data_version) {
+            return BufferUpdateStatus::new_updated(false);
+        }
+        match scene.slots_written_since(*geometry_kind, buffer.content_version()) {
+            Some(written) if false == written.is_empty() && scene.count_of_a_kind(*geometry_kind) > 0 => {
+                buffer.update_objects_with_generator(actual_data_version, queue, written.start, || scene.evaluate_serialized_slots(*geometry_kind, written))
+            }
+            _ => {
+                let serializer = || Self::serialize_scene_data::<T>(scene, geometry_kind);
+                buffer.update_with_generator(actual_data_version, resources, queue, serializer)
+            }
         }
     }
     
     #[must_use]
-    fn serialize_bvh(scene: &VisualObjects, aabb_inflation_rate: f64) -> (GpuReadySerializationBuffer, u32) {
-        assert!(aabb_inflation_rate >= 0.0, "aabb_inflation is negative");
-        if scene.bvh_inhabited() {
-            let bvh = scene.evaluate_serialized_bvh(aabb_inflation_rate);
-            let count = bvh.total_slots_count() as u32;
-            (bvh, count)
+    fn serialize_triangles(scene: &VisualObjects, quantized: bool) -> GpuReadySerializationBuffer {
+        if scene.triangles_count() > 0 {
+            scene.evaluate_serialized_triangles(quantized)
         } else {
-            (Self::make_empty_buffer_marker::<BvhNode>(), 0)
+            // the buffer consists of single quartets
+            GpuReadySerializationBuffer::make_filled(1, 1, 0.0_f32)
         }
     }
     
@@ -225,20 +1022,28 @@ impl Renderer {
         composite_status.merge_materials(self.gpu.buffers.texture_atlases_mapping.try_update_with_generator(texture_atlas_regions_version, &self.gpu.resources, self.gpu.context.queue(), || container.materials().texture_atlas_regions().borrow().serialize()));
 
         let current_gpu_texture_atlas_data_version = self.gpu.textures.last_seen_data_version();
+        let mut atlas_page_bytes = None;
         container.texture_atlas_page_composer().try_commit(current_gpu_texture_atlas_data_version, |new_version, data: &[u8]| {
             self.gpu.textures.set_atlas_page(&self.gpu.resources, data, Some(new_version));
+            atlas_page_bytes = Some(data.len() as u64);
         });
+        // rewritten atlas texels (a reloaded bitmap) invalidate the accumulated image
+        if let Some(bytes) = atlas_page_bytes {
+            composite_status.merge_materials(BufferUpdateStatus::new_updated(true).with_upload(bytes, Duration::ZERO));
+        }
 
+        let parallelograms_changed = self.gpu.buffers.parallelograms.version_diverges(container.data_version(DataKind::Parallelogram));
         composite_status.merge_geometry(Self::update_buffer::<Parallelogram>(&DataKind::Parallelogram, &mut self.gpu.buffers.parallelograms, &self.gpu.resources, container, self.gpu.context.queue()));
-        self.uniforms.set_parallelograms_count(container.count_of_a_kind(DataKind::Parallelogram) as u32);
+        self.uniforms.set_parallelograms_count(container.slots_count_of_a_kind(DataKind::Parallelogram) as u32);
         
         let mut update_bvh = false;
         
         let triangles_set_version = container.data_version(DataKind::TriangleMesh);
-        if self.gpu.buffers.triangles.version_diverges(triangles_set_version) {
-            let serialized_triangles = Self::serialize_triangles(container);
-            composite_status.merge_geometry(self.gpu.buffers.triangles.try_update_with_generator(triangles_set_version, &self.gpu.resources, self.gpu.context.queue(), || serialized_triangles));
+        if self.geometry_layout_changed || self.gpu.buffers.triangles.version_diverges(triangles_set_version) {
+            let quantized = self.uniforms.geometry_quantized();
+            composite_status.merge_geometry(self.gpu.buffers.triangles.update_with_generator(triangles_set_version, &self.gpu.resources, self.gpu.context.queue(), || Self::serialize_triangles(container, quantized)));
             update_bvh = true;
+            self.geometry_layout_changed = false;
         }
 
         let sdf_set_version = container.data_version(DataKind::Sdf);
@@ -247,17 +1052,64 @@ impl Renderer {
             update_bvh = true;
         }
 
+        let spheres_set_version = container.data_version(DataKind::Sphere);
+        if self.gpu.buffers.spheres.version_diverges(spheres_set_version) {
+            composite_status.merge_geometry(Self::update_buffer::<Sphere>(&DataKind::Sphere, &mut self.gpu.buffers.spheres, &self.gpu.resources, container, self.gpu.context.queue()));
+            update_bvh = true;
+        }
+
         if update_bvh {
-            let (bvh, bvh_length) = Self::serialize_bvh(container, 0.0);
-            composite_status.merge_bvh(self.gpu.buffers.bvh.update_with_generator(&self.gpu.resources, self.gpu.context.queue(), || bvh));
+            let started = Instant::now();
+            let (bvh, bvh_length) = self.scene_bvh.serialize(container);
+            let (bvh_inflated, bvh_inflated_length) = self.scene_bvh_inflated.serialize(container);
+            composite_status.record_bvh_build(started.elapsed());
+
+            let bvh_bytes = bvh.backend().len() as u64;
+            composite_status.merge_bvh(self.gpu.buffers.bvh.update_with_generator(&self.gpu.resources, self.gpu.context.queue(), || bvh), bvh_bytes);
 
-            let (bvh_inflated, bvh_inflated_length) = Self::serialize_bvh(container, Self::BVH_INFLATION_RATE);
-            composite_status.merge_bvh(self.gpu.buffers.bvh_inflated.update_with_generator(&self.gpu.resources, self.gpu.context.queue(), || bvh_inflated));
+            let bvh_inflated_bytes = bvh_inflated.backend().len() as u64;
+            composite_status.merge_bvh(self.gpu.buffers.bvh_inflated.update_with_generator(&self.gpu.resources, self.gpu.context.queue(), || bvh_inflated), bvh_inflated_bytes);
 
             self.uniforms.set_bvh_length(bvh_length);
             assert_eq!(bvh_length, bvh_inflated_length);
         }
+
+        if update_bvh || parallelograms_changed {
+            self.uniforms.set_scene_bounds(container.bounds());
+        }
         
+        let object_layers_version = container.object_layers_version();
+        if self.gpu.buffers.object_layers.version_diverges(object_layers_version) {
+            composite_status.merge_geometry(
+                self.gpu.buffers.object_layers.try_update_with_slice(object_layers_version, &self.gpu.resources, self.gpu.context.queue(), &container.serialize_object_layers())
+            );
+        }
+
+        let object_uv_transforms_version = container.object_uv_transforms_version();
+        if self.gpu.buffers.object_uv_transforms.version_diverges(object_uv_transforms_version) {
+            composite_status.merge_materials(
+                self.gpu.buffers.object_uv_transforms.try_update_with_slice(object_uv_transforms_version, &self.gpu.resources, self.gpu.context.queue(), &container.serialize_object_uv_transforms())
+            );
+        }
+
+        let object_opacities_version = container.object_opacities_version();
+        if self.gpu.buffers.object_opacities.version_diverges(object_opacities_version) {
+            composite_status.merge_materials(
+                self.gpu.buffers.object_opacities.try_update_with_slice(object_opacities_version, &self.gpu.resources, self.gpu.context.queue(), container.serialize_object_opacities())
+            );
+        }
+
+        let emissive_parallelograms_version = container.emissive_parallelograms_version();
+        if self.gpu.buffers.emissive_parallelograms.version_diverges(emissive_parallelograms_version) {
+            let (emissive_parallelograms, light_tree) = Self::make_gpu_ready_lights(container, &mut self.uniforms);
+            composite_status.merge_geometry(
+                self.gpu.buffers.emissive_parallelograms.try_update_with_slice(emissive_parallelograms_version, &self.gpu.resources, self.gpu.context.queue(), &emissive_parallelograms)
+            );
+            composite_status.merge_geometry(
+                self.gpu.buffers.light_tree.try_update_with_slice(emissive_parallelograms_version, &self.gpu.resources, self.gpu.context.queue(), &light_tree)
+            );
+        }
+
         let animator = self.objects.animator();
         if self.gpu.buffers.sdf_time.version_diverges(animator.version()) {
             let per_sdf_time = Self::make_gpu_ready_animation_times_array(animator);
@@ -267,21 +1119,49 @@ impl Renderer {
         }
         
         if composite_status.any_resized() {
-            Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_ray_tracing_monte_carlo.borrow_mut().deref_mut(), false);
-            Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_ray_tracing_deterministic.borrow_mut().deref_mut(), true);
-            Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_surface_attributes, false);
+            self.bind_scene_buffers();
         }
         
         composite_status
     }
+
+    fn bind_scene_buffers(&mut self) {
+        #[cfg(feature = "monte_carlo")]
+        {
+            Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_ray_tracing_monte_carlo.borrow_mut().deref_mut(), false, false, true);
+            Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_ray_tracing_monte_carlo_half_resolution_indirect.borrow_mut().deref_mut(), false, false, false);
+            Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_indirect_lighting_half_resolution.borrow_mut().deref_mut(), false, false, true);
+            Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_temporal_reprojection, false, false, false);
+            Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_debug_path, false, false, true);
+            Self::create_hit_scene_buffers_bindings(&self.gpu, self.pipelines_wavefront.intersect_mut());
+            Self::create_geometry_buffers_bindings(&self.gpu, self.pipelines_wavefront.shade_mut(), false, false, true);
+        }
+        Self::create_geometry_buffers_bindings(&self.gpu, self.pipeline_ray_tracing_deterministic.borrow_mut().deref_mut(), true, true, false);
+        Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_surface_attributes, false, false, false);
+        Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_irradiance_probes, true, true, false);
+        Self::create_geometry_buffers_bindings(&self.gpu, &mut self.pipeline_shadow_map, true, true, false);
+    }
     
     #[must_use]
     fn make_gpu_ready_animation_times_array(animator: &TimeTracker) -> Vec<f32> {
-        let mut per_sdf_time = vec![0.0_f32; std::cmp::max(1, animator.tracked_count())];
+        let mut per_sdf_time = vec![0.0_f32; std::cmp::max(1, animator.slots_count())];
         animator.write_times(&mut per_sdf_time);
         per_sdf_time
     }
     
+    // the lights the next event estimation samples, each is the slot of the parallelogram and the path
+    // to the light in the tree, see 'LightTree'; the slots grow, so the shader finds a light by binary search
+    #[must_use]
+    fn make_gpu_ready_lights(scene: &VisualObjects, uniforms: &mut Uniforms) -> (Vec<[u32; 2]>, Vec<PodVector>) {
+        let lights = scene.light_sources();
+        uniforms.set_emissive_parallelograms_count(lights.len() as u32);
+        let tree = LightTree::new(&lights);
+        let emissive_parallelograms: Vec<[u32; 2]> = lights.iter().zip(tree.paths()).map(|(light, path)| [light.slot, *path]).collect();
+        // the gpu can't accept empty buffer, the count in the uniforms tells the meaningful length
+        let emissive_parallelograms = if emissive_parallelograms.is_empty() { vec![[0, 0]] } else { emissive_parallelograms };
+        (emissive_parallelograms, tree.serialize())
+    }
+
     #[must_use]
     fn make_empty_buffer_marker<T: GpuSerializationSize>() -> GpuReadySerializationBuffer {
         GpuReadySerializationBuffer::make_filled(1, T::SERIALIZED_QUARTET_COUNT, 0.0_f32)
@@ -302,14 +1182,14 @@ impl Renderer {
         VersionedBuffer::from_generator(scene.data_version(*geometry_kind), resources, geometry_kind.as_ref(), || serialized)
     }
     
-    fn init_buffers(scene: &Hub, context: &Context, uniforms: &mut Uniforms, resources: &Resources) -> Buffers {
+    fn init_buffers(scene: &Hub, scene_bvh: &mut SceneBvh, scene_bvh_inflated: &mut SceneBvh, context: &Context, uniforms: &mut Uniforms, resources: &Resources) -> Buffers {
         let container = scene.container();
         let animator = scene.animator();
         
-        let serialized_triangles = Self::serialize_triangles(container);
+        let serialized_triangles = Self::serialize_triangles(container, uniforms.geometry_quantized());
 
-        let (bvh, bvh_length) = Self::serialize_bvh(container, 0.0);
-        let (bvh_inflated, bvh_inflated_length) = Self::serialize_bvh(container, Self::BVH_INFLATION_RATE);
+        let (bvh, bvh_length) = scene_bvh.serialize(container);
+        let (bvh_inflated, bvh_inflated_length) = scene_bvh_inflated.serialize(container);
         assert_eq!(bvh_length, bvh_inflated_length);
         uniforms.set_bvh_length(bvh_length);
 
@@ -319,18 +1199,25 @@ impl Renderer {
         let texture_atlas_regions = if container.materials().texture_atlas_regions().borrow().count() > 0
             { container.materials().texture_atlas_regions().borrow().serialize() } else { Self::make_empty_buffer_marker::<AtlasRegionMapping>() };
         
-        uniforms.set_parallelograms_count(container.count_of_a_kind(DataKind::Parallelogram) as u32);
+        uniforms.set_parallelograms_count(container.slots_count_of_a_kind(DataKind::Parallelogram) as u32);
+        uniforms.set_scene_bounds(container.bounds());
+
+        let (emissive_parallelograms, light_tree) = Self::make_gpu_ready_lights(container, uniforms);
         
         let per_sdf_time = Self::make_gpu_ready_animation_times_array(animator);
         
         Buffers {
             uniforms: resources.create_uniform_buffer("uniforms", uniforms.serialize().backend()),
 
-            ray_tracing_frame_buffer: FrameBuffer::new(context.device(), uniforms.frame_buffer_size()),
+            ray_tracing_frame_buffer: FrameBuffer::new(context.device(), uniforms.frame_buffer_size(), uniforms.lighting_aovs()),
             denoised_beauty_image: FrameBufferLayer::new(context.device(), uniforms.frame_buffer_size(), SupportUpdateFromCpu::Yes, "denoised pixels"),
             
             parallelograms: Self::make_buffer::<Parallelogram>(container, resources, &DataKind::Parallelogram),
             sdf: Self::make_buffer::<SdfInstance>(container, resources, &DataKind::Sdf),
+            spheres: Self::make_buffer::<Sphere>(container, resources, &DataKind::Sphere),
+            object_layers: VersionedBuffer::from_slice(container.object_layers_version(), resources, "object layers", &container.serialize_object_layers()),
+            object_uv_transforms: VersionedBuffer::from_slice(container.object_uv_transforms_version(), resources, "object uv transforms", &container.serialize_object_uv_transforms()),
+            object_opacities: VersionedBuffer::from_slice(container.object_opacities_version(), resources, "object opacities", container.serialize_object_opacities()),
             materials: VersionedBuffer::from_generator(container.materials().data_version(), resources, "materials", || materials),
             triangles: VersionedBuffer::from_generator(container.data_version(DataKind::TriangleMesh), resources, "triangles from all meshes", || serialized_triangles),
             texture_atlases_mapping: VersionedBuffer::from_generator(container.materials().texture_atlas_regions().borrow().version(), resources, "texture atlases mapping", || texture_atlas_regions),
@@ -339,32 +1226,244 @@ impl Renderer {
             bvh_inflated: ResizableBuffer::from_generator(resources, "bvh inflated", || bvh_inflated),
             
             sdf_time: VersionedBuffer::from_slice(animator.version(), resources, "sdf time", &per_sdf_time),
+            sdf_distance_grids: Self::make_sdf_distance_grids_buffer(container, resources),
+
+            irradiance_probes: ResizableBuffer::from_slice(resources, "irradiance probes", &[PodVector::default(); Self::IRRADIANCE_PROBE_FACES]),
+            // the zero radius of the header tells the map is not cast yet
+            shadow_map: ResizableBuffer::from_slice(resources, "shadow map", &[0.0_f32; Self::SHADOW_MAP_HEADER_SIZE + 1]),
+            emissive_parallelograms: VersionedBuffer::from_slice(container.emissive_parallelograms_version(), resources, "emissive parallelograms", &emissive_parallelograms),
+            light_tree: VersionedBuffer::from_slice(container.emissive_parallelograms_version(), resources, "light tree", &light_tree),
+
+            luminance_histogram: resources.create_buffer("luminance histogram", BufferUsages::STORAGE, bytemuck::cast_slice(&[0_u32; Self::LUMINANCE_HISTOGRAM_BINS])),
+            auto_exposure: resources.create_buffer("auto exposure", BufferUsages::STORAGE | BufferUsages::COPY_DST, bytemuck::cast_slice(&Self::AUTO_EXPOSURE_INITIAL_STATE)),
+            random_seeds: Self::make_random_seeds_buffer(resources, None),
+
+            #[cfg(feature = "monte_carlo")]
+            wavefront: WavefrontBuffers::new(context.device(), uniforms.frame_buffer_area()),
+            #[cfg(feature = "monte_carlo")]
+            debug_path: resources.create_buffer("debug path", BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST, bytemuck::cast_slice(&[0_u32; PixelPath::SERIALIZED_QUARTET_COUNT * 4])),
         }
     }
 
-    const UNIFORMS_GROUP_INDEX: u32 = 0;
-    const FRAME_BUFFERS_GROUP_INDEX: u32 = 1;
-    const SCENE_GROUP_INDEX: u32 = 2;
-
+    // made anew (and baked) after the classes change, the instances do not affect the grids
+    #[must_use]
+    fn make_sdf_distance_grids_buffer(container: &VisualObjects, resources: &Resources) -> Rc<wgpu::Buffer> {
+        resources.create_buffer("sdf distance grids", BufferUsages::STORAGE, bytemuck::cast_slice(container.sdf_distance_grids().content()))
+    }
+
+    // without the texture, a single value keeps the binding valid: the zero width tells the shader to skip it
+    #[must_use]
+    fn make_random_seeds_buffer(resources: &Resources, texture: Option<&RandomSeedTexture>) -> Rc<wgpu::Buffer> {
+        let values = texture.map_or(&[0.0_f32][..], |texture| texture.values());
+        resources.create_buffer("random seeds", BufferUsages::STORAGE, bytemuck::cast_slice(values))
+    }
+
+    const UNIFORMS_GROUP_INDEX: u32 = 0;
+    const FRAME_BUFFERS_GROUP_INDEX: u32 = 1;
+    const SCENE_GROUP_INDEX: u32 = 2;
+
+    const IRRADIANCE_PROBE_FACES: usize = 6;
+    // must match 'SHADOW_MAP_HEADER_SIZE' of the shader
+    const SHADOW_MAP_HEADER_SIZE: usize = 8;
+
+    const LUMINANCE_HISTOGRAM_BINS: usize = 256;
+    // adapted average luminance (zero - not measured yet), exposure scale, measurement time
+    const AUTO_EXPOSURE_INITIAL_STATE: [f32; 3] = [0.0, 1.0, 0.0];
+
     #[must_use]
     fn create_surface_attributes_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
         let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::SurfaceAttributes, code);
         let uses_inflated_bvh = false;
+        let uses_irradiance_probes = false;
+        let uses_emissive_parallelograms = false;
         Self::create_compute_pipeline(gpu, pipeline, |device, buffers, pipeline| {
             Self::setup_frame_buffers_bindings_for_surface_attributes_compute(device, buffers, pipeline);
-        }, uses_inflated_bvh)
+        }, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+    }
+
+    #[must_use]
+    fn create_irradiance_probes_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::IrradianceProbes, code);
+        let uses_inflated_bvh = true;
+        let uses_irradiance_probes = true;
+        let uses_emissive_parallelograms = false;
+        Self::create_compute_pipeline(gpu, pipeline, |_device, _buffers, _pipeline| {}, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+    }
+
+    // the shadow map is bound along with the irradiance probes: both are written for the deterministic shading
+    #[must_use]
+    fn create_shadow_map_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::ShadowMap, code);
+        let uses_inflated_bvh = true;
+        let uses_irradiance_probes = true;
+        let uses_emissive_parallelograms = false;
+        Self::create_compute_pipeline(gpu, pipeline, |_device, _buffers, _pipeline| {}, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
     }
     
     #[must_use]
-    fn create_ray_tracing_pipeline(gpu: &mut Gpu, code: &PipelineCode, routine: ComputeRoutineEntryPoint, uses_inflated_bvh: bool) -> ComputePipeline {
+    fn create_auto_exposure_pipeline(gpu: &mut Gpu, code: &PipelineCode, routine: ComputeRoutineEntryPoint) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(routine, code);
+        let device = gpu.context.device();
+        let mut pipeline = ComputePipeline::new(pipeline);
+
+        pipeline.setup_bind_group(Self::UNIFORMS_GROUP_INDEX, Some("auto exposure compute pipeline uniform group"), device, |bind_group| {
+            bind_group.set_storage_entry(0, gpu.buffers.uniforms.clone());
+        });
+        Self::setup_frame_buffers_bindings_for_auto_exposure_compute(device, &gpu.buffers, &mut pipeline, routine);
+
+        pipeline
+    }
+
+    #[must_use]
+    fn create_ray_tracing_pipeline(gpu: &mut Gpu, code: &PipelineCode, routine: ComputeRoutineEntryPoint, uses_inflated_bvh: bool, uses_irradiance_probes: bool, uses_emissive_parallelograms: bool) -> ComputePipeline {
         let pipeline = gpu.pipelines_factory.create_compute_pipeline(routine, code);
         Self::create_compute_pipeline(gpu, pipeline, |device, buffers, pipeline| {
-            Self::setup_frame_buffers_bindings_for_ray_tracing_compute(device, buffers, pipeline);
-        }, uses_inflated_bvh)
+            Self::setup_frame_buffers_bindings_for_ray_tracing_compute(device, buffers, pipeline, routine);
+        }, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_half_resolution_indirect_composition_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::RayTracingMonteCarloHalfResolutionIndirect, code);
+        let uses_inflated_bvh = false;
+        let uses_irradiance_probes = false;
+        let uses_emissive_parallelograms = false;
+        Self::create_compute_pipeline(gpu, pipeline, |device, buffers, pipeline| {
+            Self::setup_frame_buffers_bindings_for_half_resolution_indirect_composition(device, buffers, pipeline);
+        }, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_indirect_lighting_half_resolution_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::IndirectLightingHalfResolution, code);
+        let uses_inflated_bvh = false;
+        let uses_irradiance_probes = false;
+        let uses_emissive_parallelograms = true;
+        Self::create_compute_pipeline(gpu, pipeline, |device, buffers, pipeline| {
+            Self::setup_frame_buffers_bindings_for_indirect_lighting_half_resolution(device, buffers, pipeline);
+        }, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_reprojection_history_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::ReprojectionHistory, code);
+        let device = gpu.context.device();
+        let mut pipeline = ComputePipeline::new(pipeline);
+
+        pipeline.setup_bind_group(Self::UNIFORMS_GROUP_INDEX, Some("reprojection history compute pipeline uniform group"), device, |bind_group| {
+            bind_group.set_storage_entry(0, gpu.buffers.uniforms.clone());
+        });
+        Self::setup_frame_buffers_bindings_for_reprojection_history(device, &gpu.buffers, &mut pipeline);
+
+        pipeline
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_temporal_reprojection_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::TemporalReprojection, code);
+        let uses_inflated_bvh = false;
+        let uses_irradiance_probes = false;
+        let uses_emissive_parallelograms = false;
+        Self::create_compute_pipeline(gpu, pipeline, |device, buffers, pipeline| {
+            Self::setup_frame_buffers_bindings_for_temporal_reprojection(device, buffers, pipeline);
+        }, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_debug_path_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::DebugPath, code);
+        let uses_inflated_bvh = false;
+        let uses_irradiance_probes = false;
+        let uses_emissive_parallelograms = true;
+        Self::create_compute_pipeline(gpu, pipeline, |device, buffers, pipeline| {
+            Self::setup_frame_buffers_bindings_for_debug_path(device, buffers, pipeline);
+        }, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_wavefront_resolve_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let mut pipeline = Self::create_wavefront_queue_pipeline(gpu, code, ComputeRoutineEntryPoint::WavefrontResolve);
+        Self::setup_frame_buffers_bindings_for_wavefront_resolve(gpu.context.device(), &gpu.buffers, &mut pipeline);
+        pipeline
+    }
+
+    // only the shading samples the textures and the lights, the rest read the uniforms and the queues
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_wavefront_pipelines(gpu: &mut Gpu, code: &PipelineCode) -> WavefrontPipelines {
+        let generate = Self::create_wavefront_queue_pipeline(gpu, code, ComputeRoutineEntryPoint::WavefrontGenerate);
+        let prepare_intersection = Self::create_wavefront_queue_pipeline(gpu, code, ComputeRoutineEntryPoint::WavefrontPrepareIntersection);
+        let prepare_shading = Self::create_wavefront_queue_pipeline(gpu, code, ComputeRoutineEntryPoint::WavefrontPrepareShading);
+
+        let mut intersect = Self::create_wavefront_queue_pipeline(gpu, code, ComputeRoutineEntryPoint::WavefrontIntersect);
+        Self::create_hit_scene_buffers_bindings(gpu, &mut intersect);
+
+        let shade = {
+            let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::WavefrontShade, code);
+            let uses_inflated_bvh = false;
+            let uses_irradiance_probes = false;
+            let uses_emissive_parallelograms = true;
+            Self::create_compute_pipeline(gpu, pipeline, |_device, _buffers, _pipeline| {}, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms)
+        };
+
+        let mut pipelines = WavefrontPipelines::new(generate, prepare_intersection, intersect, prepare_shading, shade);
+        Self::setup_frame_buffers_bindings_for_wavefront(gpu.context.device(), &gpu.buffers, &mut pipelines);
+        pipelines
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn create_wavefront_queue_pipeline(gpu: &mut Gpu, code: &PipelineCode, routine: ComputeRoutineEntryPoint) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(routine, code);
+        let mut pipeline = ComputePipeline::new(pipeline);
+
+        pipeline.setup_bind_group(Self::UNIFORMS_GROUP_INDEX, Some("wavefront compute pipeline uniform group"), gpu.context.device(), |bind_group| {
+            bind_group.set_storage_entry(0, gpu.buffers.uniforms.clone());
+        });
+
+        pipeline
+    }
+
+    // the buffer of a bake is made per request, so the frame buffers group is bound at the dispatch
+    #[must_use]
+    fn create_procedural_texture_bake_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::ProceduralTextureBake, code);
+        let mut pipeline = ComputePipeline::new(pipeline);
+
+        pipeline.setup_bind_group(Self::UNIFORMS_GROUP_INDEX, Some("procedural texture bake compute pipeline uniform group"), gpu.context.device(), |bind_group| {
+            bind_group.set_storage_entry(0, gpu.buffers.uniforms.clone());
+        });
+
+        pipeline
+    }
+
+    // the baking reads no uniforms, but the group precedes the used one and has to be bound
+    #[must_use]
+    fn create_sdf_distance_grids_bake_pipeline(gpu: &mut Gpu, code: &PipelineCode) -> ComputePipeline {
+        let pipeline = gpu.pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::SdfDistanceGridsBake, code);
+        let mut pipeline = ComputePipeline::new(pipeline);
+
+        let device = gpu.context.device();
+        pipeline.setup_bind_group(Self::UNIFORMS_GROUP_INDEX, Some("sdf distance grids bake compute pipeline uniform group"), device, |_| {});
+        Self::bind_sdf_distance_grids_for_bake(gpu, &mut pipeline);
+
+        pipeline
+    }
+
+    fn bind_sdf_distance_grids_for_bake(gpu: &Gpu, pipeline: &mut ComputePipeline) {
+        pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, Some("sdf distance grids bake compute pipeline frame buffers group"), gpu.context.device(), |bind_group| {
+            bind_group.set_storage_entry(16, gpu.buffers.sdf_distance_grids.clone());
+        });
     }
 
     #[must_use]
-    fn create_compute_pipeline<Code>(gpu: &Gpu, pipeline: wgpu::ComputePipeline, customization: Code, uses_inflated_bvh: bool) -> ComputePipeline
+    fn create_compute_pipeline<Code>(gpu: &Gpu, pipeline: wgpu::ComputePipeline, customization: Code, uses_inflated_bvh: bool, uses_irradiance_probes: bool, uses_emissive_parallelograms: bool) -> ComputePipeline
         where Code: FnOnce(&wgpu::Device, &Buffers, &mut ComputePipeline), 
     {
         let device = gpu.context.device();
@@ -379,30 +1478,59 @@ impl Renderer {
 
         customization(device, &gpu.buffers, &mut pipeline);
 
-        Self::create_geometry_buffers_bindings(gpu, &mut pipeline, uses_inflated_bvh);
+        Self::create_geometry_buffers_bindings(gpu, &mut pipeline, uses_inflated_bvh, uses_irradiance_probes, uses_emissive_parallelograms);
         
         pipeline
     }
     
-    fn create_geometry_buffers_bindings(gpu: &Gpu, pipeline: &mut ComputePipeline, uses_inflated_bvh: bool) {
+    fn create_geometry_buffers_bindings(gpu: &Gpu, pipeline: &mut ComputePipeline, uses_inflated_bvh: bool, uses_irradiance_probes: bool, uses_emissive_parallelograms: bool) {
         let label = Some("compute pipeline scene group");
         pipeline.setup_bind_group(Self::SCENE_GROUP_INDEX, label, gpu.context.device(), |bind_group| {
-            bind_group
-                .set_storage_entry(0, gpu.buffers.parallelograms.backend().clone())
-                .set_storage_entry(1, gpu.buffers.sdf.backend().clone())
-                .set_storage_entry(2, gpu.buffers.triangles.backend().clone())
-                .set_storage_entry(3, gpu.buffers.materials.backend().clone())
-                .set_storage_entry(4, gpu.buffers.bvh.backend().clone());
+            Self::set_hit_scene_entries(gpu, bind_group);
                 
             if uses_inflated_bvh {
                 bind_group.set_storage_entry(5, gpu.buffers.bvh_inflated.backend().clone());
             }
 
-            bind_group.set_storage_entry(6, gpu.buffers.sdf_time.backend().clone());
             bind_group.set_storage_entry(7, gpu.buffers.texture_atlases_mapping.backend().clone());
+            bind_group.set_storage_entry(15, gpu.buffers.object_uv_transforms.backend().clone());
+
+            if uses_irradiance_probes {
+                bind_group.set_storage_entry(8, gpu.buffers.irradiance_probes.backend().clone());
+                bind_group.set_storage_entry(14, gpu.buffers.shadow_map.backend().clone());
+            }
+
+            if uses_emissive_parallelograms {
+                bind_group.set_storage_entry(9, gpu.buffers.emissive_parallelograms.backend().clone());
+                bind_group.set_storage_entry(13, gpu.buffers.light_tree.backend().clone());
+            }
+        });
+    }
+
+    // the closest hit search alone reads neither the texture atlases mapping nor the lights
+    #[cfg(feature = "monte_carlo")]
+    fn create_hit_scene_buffers_bindings(gpu: &Gpu, pipeline: &mut ComputePipeline) {
+        let label = Some("compute pipeline hit scene group");
+        pipeline.setup_bind_group(Self::SCENE_GROUP_INDEX, label, gpu.context.device(), |bind_group| {
+            Self::set_hit_scene_entries(gpu, bind_group);
         });
     }
 
+    fn set_hit_scene_entries(gpu: &Gpu, bind_group: &mut BindGroupBuilder) {
+        bind_group
+            .set_storage_entry(0, gpu.buffers.parallelograms.backend().clone())
+            .set_storage_entry(1, gpu.buffers.sdf.backend().clone())
+            .set_storage_entry(2, gpu.buffers.triangles.backend().clone())
+            .set_storage_entry(3, gpu.buffers.materials.backend().clone())
+            .set_storage_entry(4, gpu.buffers.bvh.backend().clone())
+            .set_storage_entry(6, gpu.buffers.sdf_time.backend().clone())
+            .set_storage_entry(10, gpu.buffers.spheres.backend().clone())
+            .set_storage_entry(11, gpu.buffers.object_layers.backend().clone())
+            .set_storage_entry(16, gpu.buffers.object_opacities.backend().clone())
+            .set_storage_entry(12, gpu.buffers.sdf_distance_grids.clone())
+        ;
+    }
+
     fn setup_frame_buffers_bindings_for_surface_attributes_compute(device: &wgpu::Device, buffers: &Buffers, surface_attributes_pipeline: &mut ComputePipeline) {
         let label = Some("'surface attributes' compute pipeline frame buffers group");
 
@@ -411,17 +1539,124 @@ impl Renderer {
                 .set_storage_entry(1, buffers.ray_tracing_frame_buffer.object_id_at_gpu())
                 .set_storage_entry(2, buffers.ray_tracing_frame_buffer.normal_at_gpu())
                 .set_storage_entry(3, buffers.ray_tracing_frame_buffer.albedo_gpu())
+                .set_storage_entry(15, buffers.ray_tracing_frame_buffer.depth_at_gpu())
+                .set_storage_entry(18, buffers.ray_tracing_frame_buffer.motion_vector_at_gpu())
             ;
         });
     }
     
-    fn setup_frame_buffers_bindings_for_ray_tracing_compute(device: &wgpu::Device, buffers: &Buffers, ray_tracing_pipeline: &mut ComputePipeline) {
+    fn setup_frame_buffers_bindings_for_ray_tracing_compute(device: &wgpu::Device, buffers: &Buffers, ray_tracing_pipeline: &mut ComputePipeline, routine: ComputeRoutineEntryPoint) {
         let label = Some("ray tracing compute pipeline frame buffers group");
 
         ray_tracing_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
             bind_group_builder
                 .set_storage_entry(0, buffers.ray_tracing_frame_buffer.noisy_pixel_color())
             ;
+            // only the megakernel splits the lighting and seeds the random numbers per pixel
+            #[cfg(feature = "monte_carlo")]
+            if routine == ComputeRoutineEntryPoint::RayTracingMonteCarlo {
+                bind_group_builder
+                    .set_storage_entry(19, buffers.ray_tracing_frame_buffer.lighting_at_gpu())
+                    .set_storage_entry(20, buffers.random_seeds.clone())
+                ;
+            }
+            #[cfg(not(feature = "monte_carlo"))]
+            let _ = routine;
+        });
+    }
+
+    fn setup_frame_buffers_bindings_for_auto_exposure_compute(device: &wgpu::Device, buffers: &Buffers, auto_exposure_pipeline: &mut ComputePipeline, routine: ComputeRoutineEntryPoint) {
+        let label = Some("auto exposure compute pipeline frame buffers group");
+
+        auto_exposure_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
+            bind_group_builder.set_storage_entry(6, buffers.luminance_histogram.clone());
+            if routine == ComputeRoutineEntryPoint::LuminanceHistogram {
+                bind_group_builder.set_storage_entry(0, buffers.ray_tracing_frame_buffer.noisy_pixel_color());
+            } else {
+                bind_group_builder.set_storage_entry(7, buffers.auto_exposure.clone());
+            }
+        });
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    fn setup_frame_buffers_bindings_for_half_resolution_indirect_composition(device: &wgpu::Device, buffers: &Buffers, composition_pipeline: &mut ComputePipeline) {
+        let label = Some("half resolution indirect composition compute pipeline frame buffers group");
+
+        composition_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
+            bind_group_builder
+                .set_storage_entry(0, buffers.ray_tracing_frame_buffer.noisy_pixel_color())
+                .set_storage_entry(4, buffers.ray_tracing_frame_buffer.indirect_half_resolution_at_gpu())
+                .set_storage_entry(5, buffers.ray_tracing_frame_buffer.indirect_half_resolution_guide_at_gpu())
+                .set_storage_entry(20, buffers.random_seeds.clone())
+                .set_storage_entry(21, buffers.ray_tracing_frame_buffer.direct_full_resolution_at_gpu())
+            ;
+        });
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    fn setup_frame_buffers_bindings_for_indirect_lighting_half_resolution(device: &wgpu::Device, buffers: &Buffers, indirect_lighting_pipeline: &mut ComputePipeline) {
+        let label = Some("half resolution indirect lighting compute pipeline frame buffers group");
+
+        indirect_lighting_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
+            bind_group_builder
+                .set_storage_entry(4, buffers.ray_tracing_frame_buffer.indirect_half_resolution_at_gpu())
+                .set_storage_entry(5, buffers.ray_tracing_frame_buffer.indirect_half_resolution_guide_at_gpu())
+                .set_storage_entry(20, buffers.random_seeds.clone())
+            ;
+        });
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    fn setup_frame_buffers_bindings_for_debug_path(device: &wgpu::Device, buffers: &Buffers, debug_path_pipeline: &mut ComputePipeline) {
+        let label = Some("debug path compute pipeline frame buffers group");
+
+        debug_path_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
+            bind_group_builder
+                .set_storage_entry(9, buffers.debug_path.clone())
+                .set_storage_entry(20, buffers.random_seeds.clone())
+            ;
+        });
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    fn setup_frame_buffers_bindings_for_reprojection_history(device: &wgpu::Device, buffers: &Buffers, reprojection_history_pipeline: &mut ComputePipeline) {
+        let label = Some("reprojection history compute pipeline frame buffers group");
+
+        reprojection_history_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
+            bind_group_builder
+                .set_storage_entry(0, buffers.ray_tracing_frame_buffer.noisy_pixel_color())
+                .set_storage_entry(1, buffers.ray_tracing_frame_buffer.object_id_at_gpu())
+                .set_storage_entry(8, buffers.ray_tracing_frame_buffer.reprojection_history_at_gpu())
+            ;
+        });
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    fn setup_frame_buffers_bindings_for_temporal_reprojection(device: &wgpu::Device, buffers: &Buffers, temporal_reprojection_pipeline: &mut ComputePipeline) {
+        let label = Some("temporal reprojection compute pipeline frame buffers group");
+
+        temporal_reprojection_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
+            bind_group_builder
+                .set_storage_entry(0, buffers.ray_tracing_frame_buffer.noisy_pixel_color())
+                .set_storage_entry(8, buffers.ray_tracing_frame_buffer.reprojection_history_at_gpu())
+            ;
+        });
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    fn setup_frame_buffers_bindings_for_wavefront(device: &wgpu::Device, buffers: &Buffers, pipelines: &mut WavefrontPipelines) {
+        pipelines.setup_frame_buffers_bindings(Self::FRAME_BUFFERS_GROUP_INDEX, device, &buffers.wavefront, buffers.random_seeds.clone());
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    fn setup_frame_buffers_bindings_for_wavefront_resolve(device: &wgpu::Device, buffers: &Buffers, resolve_pipeline: &mut ComputePipeline) {
+        let label = Some("wavefront resolve compute pipeline frame buffers group");
+
+        resolve_pipeline.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, label, device, |bind_group_builder| {
+            bind_group_builder
+                .set_storage_entry(0, buffers.ray_tracing_frame_buffer.noisy_pixel_color())
+                .set_storage_entry(10, buffers.wavefront.paths())
+            ;
         });
     }
 
@@ -442,6 +1677,19 @@ impl Renderer {
         rasterization_pipeline
     }
     
+    // the image the output pass shows: with the denoiser, the Monte Carlo renders show the denoised one,
+    // blended with the samples accumulated since it was made
+    #[must_use]
+    fn displayed_pixel_color(gpu: &Gpu, flavour: RenderStrategyId) -> Rc<wgpu::Buffer> {
+        #[cfg(feature = "denoiser")]
+        if flavour.is_monte_carlo() && false == gpu.denoised_blend.bypassed() {
+            return gpu.denoised_blend.blended_buffer();
+        }
+        #[cfg(not(feature = "denoiser"))]
+        let _ = flavour;
+        gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color()
+    }
+
     fn setup_frame_buffers_bindings_for_rasterization(gpu: &Gpu, rasterization_pipeline: &mut RasterizationPipeline, flavour: RenderStrategyId) {
         let label = Some("rasterization pipeline frame buffers group");
 
@@ -449,180 +1697,932 @@ impl Renderer {
 
         let mut bind_group_builder = BindGroupBuilder::new(Self::FRAME_BUFFERS_GROUP_INDEX, label, bind_group_layout);
         
-        if cfg!(feature = "denoiser") {
-            if flavour == RenderStrategyId::Deterministic {
-                bind_group_builder
-                    .set_storage_entry(0, gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color())
-                ;   
-            } else {
-                bind_group_builder
-                    .set_storage_entry(0, gpu.buffers.denoised_beauty_image.gpu_render_target())
-                ;
-            }
-        } else {
-            bind_group_builder
-                .set_storage_entry(0, gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color())
-            ;
-        }
+        bind_group_builder.set_storage_entry(0, Self::displayed_pixel_color(gpu, flavour));
+        bind_group_builder.set_storage_entry(1, gpu.buffers.ray_tracing_frame_buffer.object_id_at_gpu());
+        bind_group_builder.set_storage_entry(7, gpu.buffers.auto_exposure.clone());
+        bind_group_builder.set_storage_entry(17, gpu.bloom.chain_buffer());
         
         rasterization_pipeline.commit_bind_group(gpu.context.device(), bind_group_builder);
     }
 
-    pub(crate) fn set_output_size(&mut self, new_size: PhysicalSize<u32>) {
+    // with a fixed aspect ratio, the frame buffer covers the fitting part of the output only
+    pub(crate) fn set_output_size(&mut self, output_size: PhysicalSize<u32>) {
+        self.output_size = output_size;
+        self.viewport = self.frame_buffer_size_policy.apply(Viewport::fit(output_size, self.fixed_aspect_ratio));
+        let new_size = self.viewport.size();
+
+        self.exported_textures = None;
+
         let previous_frame_size = self.uniforms.frame_buffer_area();
+        let previous_half_resolution_size = self.uniforms.frame_buffer_size().half_resolution().area();
         self.uniforms.set_frame_size(new_size);
         self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+        self.surface_attributes_outdated = true;
         
         let new_frame_size = self.uniforms.frame_buffer_area();
-        if previous_frame_size < new_frame_size {
-            let device = self.gpu.context.device();
-
-            self.gpu.buffers.ray_tracing_frame_buffer = FrameBuffer::new(device, self.uniforms.frame_buffer_size());
-            self.gpu.buffers.denoised_beauty_image = FrameBufferLayer::new(device, self.uniforms.frame_buffer_size(), SupportUpdateFromCpu::Yes, "denoised pixels");
-
-            Self::setup_frame_buffers_bindings_for_ray_tracing_compute(device, &self.gpu.buffers, self.pipeline_ray_tracing_monte_carlo.borrow_mut().deref_mut());
-            Self::setup_frame_buffers_bindings_for_ray_tracing_compute(device, &self.gpu.buffers, self.pipeline_ray_tracing_deterministic.borrow_mut().deref_mut());
-            Self::setup_frame_buffers_bindings_for_surface_attributes_compute(device, &self.gpu.buffers, &mut self.pipeline_surface_attributes);
-            Self::setup_frame_buffers_bindings_for_rasterization(&self.gpu, &mut self.pipeline_final_image_rasterization, self.color_buffer_evaluation.id());
+        let new_half_resolution_size = self.uniforms.frame_buffer_size().half_resolution().area();
+        if previous_frame_size < new_frame_size || previous_half_resolution_size < new_half_resolution_size {
+            self.reallocate_frame_buffers();
         } else {
+            // the copies on the way are of the previous size
+            self.wait_for_copies_from_gpu();
             self.gpu.buffers.ray_tracing_frame_buffer.invalidate_cpu_copies();
         }
     }
 
-    #[must_use]
-    pub(crate) fn object_in_pixel(&self, x: u32, y: u32) -> Option<ObjectUid> {
-        let map = self.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu();
-        let index = (self.uniforms.frame_buffer_size().width() * y + x) as usize;
-        assert!(index < map.len());
-        let uid = map[index];
-        
-        if 0 == uid {
-            return None;
-        }
-        
-        Some(ObjectUid(uid))
-    }
+    fn reallocate_frame_buffers(&mut self) {
+        let device = self.gpu.context.device();
 
-    pub(crate) fn start_new_frame(&mut self) {
-        self.objects.update_time();
-    }
-    
-    pub(crate) fn accumulate_more_rays(&mut self)  {
-        let mut rebuild_geometry_buffers = self.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu().is_empty();
-        let buffers_status = self.update_buffers_if_scene_changed();
-        let animated_texture = self.objects.any_objects_have_animated_texture();
+        self.gpu.buffers.ray_tracing_frame_buffer = FrameBuffer::new(device, self.uniforms.frame_buffer_size(), self.uniforms.lighting_aovs());
+        self.gpu.buffers.denoised_beauty_image = FrameBufferLayer::new(device, self.uniforms.frame_buffer_size(), SupportUpdateFromCpu::Yes, "denoised pixels");
+        #[cfg(feature = "denoiser")]
+        self.denoising_schedule.forget();
 
+        #[cfg(feature = "monte_carlo")]
         {
-            let camera_changed = self.uniforms.mutable_camera().check_and_clear_updated_status();
-            let geometry_changed = buffers_status.geometry_updated();
-            
-            if buffers_status.any_updated() || animated_texture {
-                self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
-            }
-            
-            if camera_changed || geometry_changed {
-                self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
-                rebuild_geometry_buffers = true;
-            }
-            
-            self.uniforms.next_frame(self.color_buffer_evaluation.frame_counter_increment());
-            self.uniforms.update_time(self.start_time.elapsed());
-            
-            // TODO: rewrite with 'write_buffer_with'? May be we need kind of ping-pong or circular buffer here?
-            let uniform_values = self.uniforms.serialize();
-            self.gpu.context.queue().write_buffer(&self.gpu.buffers.uniforms, 0, uniform_values.backend());
+            self.gpu.buffers.wavefront = WavefrontBuffers::new(device, self.uniforms.frame_buffer_area());
         }
+        self.rebind_frame_buffers();
+    }
 
-        let rebuild_albedo_buffer =
-               self.gpu.buffers.ray_tracing_frame_buffer.albedo_at_cpu_is_absent()
-            || rebuild_geometry_buffers
-            || buffers_status.any_updated()
-            || animated_texture;
+    fn rebind_frame_buffers(&mut self) {
+        let device = self.gpu.context.device();
 
-        let mut surface_properties_pass_or_none: Option<SubmissionIndex> = None;
-        if rebuild_geometry_buffers || rebuild_albedo_buffer {
-            let label = "nearest surface properties compute pass";
-            let encoder = self.begin_compute_pass();
-            surface_properties_pass_or_none = Some(
-                self.compute_pass(encoder, label, &self.pipeline_surface_attributes, |pass| {
-                    if rebuild_geometry_buffers {
-                        if cfg!(feature = "denoiser") {
-                            self.gpu.buffers.ray_tracing_frame_buffer.prepare_all_aux_buffers_copy_from_gpu(pass);
-                        } else {
-                            self.gpu.buffers.ray_tracing_frame_buffer.prepare_object_id_copy_from_gpu(pass);
-                        }
-                    } else if cfg!(feature = "denoiser") && rebuild_albedo_buffer {
-                        self.gpu.buffers.ray_tracing_frame_buffer.prepare_albedo_copy_from_gpu(pass);
-                    }
-                })
-            );
+        #[cfg(feature = "monte_carlo")]
+        {
+            Self::setup_frame_buffers_bindings_for_ray_tracing_compute(device, &self.gpu.buffers, self.pipeline_ray_tracing_monte_carlo.borrow_mut().deref_mut(), ComputeRoutineEntryPoint::RayTracingMonteCarlo);
+            Self::setup_frame_buffers_bindings_for_half_resolution_indirect_composition(device, &self.gpu.buffers, self.pipeline_ray_tracing_monte_carlo_half_resolution_indirect.borrow_mut().deref_mut());
+            Self::setup_frame_buffers_bindings_for_indirect_lighting_half_resolution(device, &self.gpu.buffers, self.pipeline_indirect_lighting_half_resolution.borrow_mut().deref_mut());
+            Self::setup_frame_buffers_bindings_for_reprojection_history(device, &self.gpu.buffers, &mut self.pipeline_reprojection_history);
+            Self::setup_frame_buffers_bindings_for_temporal_reprojection(device, &self.gpu.buffers, &mut self.pipeline_temporal_reprojection);
+            Self::setup_frame_buffers_bindings_for_wavefront(device, &self.gpu.buffers, &mut self.pipelines_wavefront);
+            Self::setup_frame_buffers_bindings_for_wavefront_resolve(device, &self.gpu.buffers, self.pipeline_wavefront_resolve.borrow_mut().deref_mut());
+            Self::setup_frame_buffers_bindings_for_debug_path(device, &self.gpu.buffers, &mut self.pipeline_debug_path);
         }
+        Self::setup_frame_buffers_bindings_for_ray_tracing_compute(device, &self.gpu.buffers, self.pipeline_ray_tracing_deterministic.borrow_mut().deref_mut(), ComputeRoutineEntryPoint::RayTracingDeterministic);
+        Self::setup_frame_buffers_bindings_for_surface_attributes_compute(device, &self.gpu.buffers, &mut self.pipeline_surface_attributes);
+        Self::setup_frame_buffers_bindings_for_auto_exposure_compute(device, &self.gpu.buffers, &mut self.pipeline_luminance_histogram, ComputeRoutineEntryPoint::LuminanceHistogram);
+        Self::setup_frame_buffers_bindings_for_rasterization(&self.gpu, &mut self.pipeline_final_image_rasterization, self.color_buffer_evaluation.id());
+    }
 
-        let label = "ray tracing compute pass";
-        let mut encoder = self.begin_compute_pass();
-        if (rebuild_geometry_buffers || buffers_status.any_updated() || animated_texture) && self.color_buffer_evaluation.frame_counter_increment() > 0 {
-            encoder.clear_buffer(self.gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color().as_ref(), BufferAddress::default(), None);
+    // the direct, the indirect and the emitted light of the Monte Carlo megakernel are accumulated in
+    // layers of their own; the other strategies leave them black
+    pub(crate) fn set_lighting_aovs(&mut self, enabled: bool) {
+        if self.uniforms.lighting_aovs() == enabled {
+            return;
         }
-        self.compute_pass(encoder, label, self.color_buffer_evaluation.pipeline().deref(), |pass|{
-            if cfg!(feature = "denoiser") {
-                self.prepare_pixel_color_copy_from_gpu(pass);   
-            }
-        });
+        self.wait_for_copies_from_gpu();
+        self.uniforms.set_lighting_aovs(enabled);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+        self.surface_attributes_outdated = true;
+        self.reallocate_frame_buffers();
+    }
 
-        if surface_properties_pass_or_none.is_some() {
-            if rebuild_geometry_buffers {
-                if cfg!(feature = "denoiser") {
-                    let copy_operation = self.gpu.buffers.ray_tracing_frame_buffer.copy_all_aux_buffers_from_gpu();
-                    self.gpu.context.wait(surface_properties_pass_or_none);
-                    pollster::block_on(copy_operation);
-                } else {
-                    let copy_operation = self.gpu.buffers.ray_tracing_frame_buffer.copy_object_id_from_gpu();
-                    self.gpu.context.wait(surface_properties_pass_or_none);
-                    pollster::block_on(copy_operation);
-                }
-            } else if cfg!(feature = "denoiser") && rebuild_albedo_buffer {
-                let copy_operation = self.gpu.buffers.ray_tracing_frame_buffer.copy_albedo_from_gpu();
-                self.gpu.context.wait(surface_properties_pass_or_none);
-                pollster::block_on(copy_operation);
-            }
+    #[must_use]
+    pub(crate) fn lighting_aovs(&self) -> bool {
+        self.uniforms.lighting_aovs()
+    }
+
+    // the sequences of the other generator make another image of the same scene, so the accumulation starts over
+    pub(crate) fn set_random_generator(&mut self, generator: RandomGenerator) {
+        if self.uniforms.random_generator() == generator {
+            return;
         }
+        self.uniforms.set_random_generator(generator);
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
     }
-    
-    fn prepare_pixel_color_copy_from_gpu(&self, pass: &mut wgpu::CommandEncoder) {
-        self.gpu.buffers.ray_tracing_frame_buffer.prepare_pixel_color_copy_from_gpu(pass);
+
+    #[must_use]
+    pub(crate) fn random_generator(&self) -> RandomGenerator {
+        self.uniforms.random_generator()
     }
 
-    #[cfg(any(test, feature = "denoiser"))]
-    fn copy_noisy_pixels_to_cpu(&mut self) {
-        let pixel_colors_buffer_gpu_to_cpu_transfer = self.gpu.buffers.ray_tracing_frame_buffer.copy_pixel_colors_from_gpu();
-        self.gpu.context.wait(None);
-        pollster::block_on(pixel_colors_buffer_gpu_to_cpu_transfer);
+    // the Monte Carlo pixels rotate their random numbers by the texel tiled over the frame buffer: a blue noise
+    // texture spreads the error of the first samples evenly over the image
+    pub(crate) fn set_random_seed_texture(&mut self, texture: Option<RandomSeedTexture>) {
+        self.uniforms.set_random_seeds_size(texture.as_ref().map(|texture| (texture.width(), texture.height())));
+        self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+        self.gpu.buffers.random_seeds = Self::make_random_seeds_buffer(&self.gpu.resources, texture.as_ref());
+        self.random_seed_texture = texture;
+        self.rebind_frame_buffers();
     }
 
-    #[cfg(feature = "denoiser")]
-    pub(crate) fn denoise_accumulated_image(&mut self, timer: &mut denoiser::MinMaxTimeMeasurer)
-    {
-        self.copy_noisy_pixels_to_cpu();
+    #[must_use]
+    pub(crate) fn random_seed_texture(&self) -> Option<&RandomSeedTexture> {
+        self.random_seed_texture.as_ref()
+    }
 
-        {
-            let frame_buffer_width = self.uniforms.frame_buffer_size().width() as usize;
-            let frame_buffer_height = self.uniforms.frame_buffer_size().height() as usize;
-            let (beauty, albedo, normal) = self.gpu.buffers.ray_tracing_frame_buffer.denoiser_input();
-            let beauty_floats: &mut [f32] = bytemuck::cast_slice_mut(beauty);
-            let albedo_floats: &[f32] = bytemuck::cast_slice(albedo);
-            let normal_floats: &[f32] = bytemuck::cast_slice(normal);
+    // the accumulated image is stretched over the new output, and the accumulation goes on at the frame buffer
+    // size; the next 'set_output_size' makes the frame buffer fit the output
+    pub(crate) fn keep_frame_buffer_size(&mut self, output_size: PhysicalSize<u32>) {
+        self.output_size = output_size;
+        self.viewport = Viewport::fit_frame(output_size, self.viewport.size());
+        self.exported_textures = None;
+    }
 
-            timer.start();
-            let mut executor = self.denoiser.begin_denoise(frame_buffer_width, frame_buffer_height);
-            executor.issue_albedo_write(albedo_floats);
-            executor.issue_normal_write(normal_floats);
+    // the accumulated image, resampled to the new size, seeds the accumulation instead of being dropped;
+    // the render strategies without a checkpoint just restart it
+    pub(crate) fn set_output_size_resampling_accumulation(&mut self, output_size: PhysicalSize<u32>) {
+        // a resampled image is blurry and skewed: it is outweighed by the new samples in a few passes
+        const RESAMPLED_ACCUMULATION_WEIGHT_PASSES: u32 = 4;
+
+        let accumulation = self.make_accumulation_checkpoint().ok();
+        self.set_output_size(output_size);
+        self.resumed_accumulation = accumulation
+            .filter(|checkpoint| checkpoint.frame_number() > 0)
+            .map(|checkpoint| checkpoint.resampled(self.uniforms.frame_buffer_size(), RESAMPLED_ACCUMULATION_WEIGHT_PASSES));
+    }
+
+    pub(crate) fn set_fixed_aspect_ratio(&mut self, aspect_ratio: Option<f64>) {
+        if let Some(aspect_ratio) = aspect_ratio {
+            assert_gt!(aspect_ratio, 0.0);
+        }
+        if self.fixed_aspect_ratio == aspect_ratio {
+            return;
+        }
+        self.fixed_aspect_ratio = aspect_ratio;
+        self.set_output_size(self.output_size);
+    }
+
+    pub(crate) fn set_frame_buffer_size_policy(&mut self, policy: FrameBufferSizePolicy) {
+        if let FrameBufferSizePolicy::Scaled(scale) = policy {
+            assert_gt!(scale, 0.0);
+            assert_le!(scale, FrameBufferSizePolicy::MAX_SCALE);
+        }
+        if self.frame_buffer_size_policy == policy {
+            return;
+        }
+        self.frame_buffer_size_policy = policy;
+        self.set_output_size(self.output_size);
+    }
+
+    pub(crate) fn set_upscale_sharpness(&mut self, sharpness: f64) {
+        assert!((0.0..=1.0).contains(&sharpness), "upscale sharpness {sharpness} is out of [0, 1]");
+        self.uniforms.set_upscale_sharpness(sharpness as f32);
+    }
+
+    pub(crate) fn set_output_dithering(&mut self, dithering: OutputDithering) {
+        self.uniforms.set_output_dithering(dithering);
+    }
+
+    #[must_use]
+    pub(crate) fn output_dithering(&self) -> OutputDithering {
+        self.uniforms.output_dithering()
+    }
+
+    #[must_use]
+    pub(crate) fn pipeline_cache_statistics(&self) -> PipelineCacheStatistics {
+        self.gpu.pipelines_factory.statistics()
+    }
+
+    #[must_use]
+    pub(crate) fn buffers_statistics(&self) -> SceneBuffersStatistics {
+        let limits = self.gpu.context.device().limits();
+        let binding_limit = (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size);
+        let buffers = &self.gpu.buffers;
+        let statistics = |kind: SceneBufferKind, buffer: &wgpu::Buffer| SceneBufferStatistics::new(kind, buffer.size(), binding_limit);
+        SceneBuffersStatistics::new(vec![
+            statistics(SceneBufferKind::Parallelograms, buffers.parallelograms.backend()),
+            statistics(SceneBufferKind::Sdf, buffers.sdf.backend()),
+            statistics(SceneBufferKind::Spheres, buffers.spheres.backend()),
+            statistics(SceneBufferKind::Triangles, buffers.triangles.backend()),
+            statistics(SceneBufferKind::Materials, buffers.materials.backend()),
+            statistics(SceneBufferKind::Bvh, buffers.bvh.backend()),
+            statistics(SceneBufferKind::BvhInflated, buffers.bvh_inflated.backend()),
+        ])
+    }
+
+    #[must_use]
+    pub(crate) fn statistics(&self) -> EngineStatistics {
+        let container = self.objects.container();
+        let objects = ObjectCounts::new(
+            container.count_of_a_kind(DataKind::Parallelogram),
+            container.count_of_a_kind(DataKind::Sdf),
+            container.count_of_a_kind(DataKind::TriangleMesh),
+            container.count_of_a_kind(DataKind::Sphere),
+        );
+        let pass_timer = self.gpu.pass_timer.borrow();
+        let pass_timings = pass_timer.supported().then(|| pass_timer.recent().to_vec());
+        EngineStatistics::new(objects, container.triangles_count(), self.uniforms.bvh_length(), self.scene_bvh.depth(), self.buffers_statistics(), pass_timings)
+    }
+
+    // of the last frame that reached the buffers update
+    #[must_use]
+    pub(crate) fn last_frame_statistics(&self) -> FrameStatistics {
+        self.last_frame_statistics
+    }
+
+    #[must_use]
+    pub(crate) fn gpu_passes_report(&self) -> Option<GpuPassesReport> {
+        self.gpu.pass_timer.borrow().report()
+    }
+
+    // the coordinates are in the output pixels: none for the letterbox bars and before the first frame;
+    // a recent answer is reused, otherwise blocks till the single id is read back
+    #[must_use]
+    pub(crate) fn object_in_pixel(&mut self, x: u32, y: u32) -> Option<ObjectUid> {
+        let pixel = self.viewport.frame_buffer_pixel(x, y)?;
+        if let Some(answer) = self.gpu.picking.answer(pixel) {
+            return answer;
+        }
+        self.request_pick(pixel);
+        self.gpu.picking.wait(&self.gpu.context);
+        self.gpu.picking.answer(pixel).flatten()
+    }
+
+    // the ray the tracer casts through the center of the output pixel; none on the letterbox bars
+    #[must_use]
+    pub(crate) fn pick_ray(&self, x: u32, y: u32) -> Option<Ray> {
+        let (s, t) = self.view_plane_point(x, y)?;
+        Some(self.uniforms.camera().ray_through(s, t))
+    }
+
+    // the orthographic view keeps the scene point under the output pixel in place; false on the letterbox bars
+    pub(crate) fn zoom_about_pixel(&mut self, x: u32, y: u32, factor: f64) -> bool {
+        let Some((s, t)) = self.view_plane_point(x, y) else {
+            return false;
+        };
+        self.uniforms.mutable_camera().zoom_about_view_point(factor, s, t);
+        true
+    }
+
+    // the center of the output pixel on the view plane, see 'Camera::ray_through'
+    #[must_use]
+    fn view_plane_point(&self, x: u32, y: u32) -> Option<(f64, f64)> {
+        let (pixel_x, pixel_y) = self.viewport.frame_buffer_pixel(x, y)?;
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        let (width, height) = (frame_buffer_size.width() as f64, frame_buffer_size.height() as f64);
+        let s = width / height * (2.0 * (pixel_x as f64 + 0.5) / width - 1.0);
+        let t = -(2.0 * (pixel_y as f64 + 0.5) / height - 1.0);
+        Some((s, t))
+    }
+
+    // the coordinates are in the output pixels; the answer arrives with a later device poll,
+    // so the following 'object_in_pixel' does not block (hovering)
+    pub(crate) fn request_object_in_pixel(&mut self, x: u32, y: u32) {
+        if let Some(pixel) = self.viewport.frame_buffer_pixel(x, y) {
+            self.request_pick(pixel);
+        }
+    }
+
+    fn request_pick(&mut self, pixel: (u32, u32)) {
+        if self.gpu.picking.requested(pixel) || self.gpu.picking.answer(pixel).is_some() {
+            return;
+        }
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        let pixel_index = frame_buffer_size.width() * pixel.1 + pixel.0;
+        let object_ids = self.gpu.buffers.ray_tracing_frame_buffer.object_id_at_gpu();
+        self.gpu.picking.request(pixel, pixel_index, frame_buffer_size.area(), object_ids, &self.gpu.context, &self.gpu.resources);
+    }
+
+    // with the readback off, the object ids are copied to the cpu only when the box selection asks for them
+    pub(crate) fn set_object_id_readback(&mut self, enabled: bool) {
+        if enabled && false == self.object_id_readback {
+            // the cpu copy may be of an older frame
+            self.surface_attributes_outdated = true;
+        }
+        self.object_id_readback = enabled;
+    }
+
+    // blocks till the object ids of the last frame are at the cpu
+    fn read_object_ids(&mut self) {
+        if false == self.gpu.buffers.ray_tracing_frame_buffer.can_prepare_copies_from_gpu() {
+            self.wait_for_copies_from_gpu();
+        }
+        let mut encoder = self.create_command_encoder("object ids copy encoder");
+        self.gpu.buffers.ray_tracing_frame_buffer.prepare_object_id_copy_from_gpu(&mut encoder);
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+        self.gpu.buffers.ray_tracing_frame_buffer.request_copies_from_gpu();
+        self.wait_for_copies_from_gpu();
+    }
+
+    // the coordinates are in the output pixels; traces the first sample of the last Monte Carlo pass
+    // once more, recording every bounce; none for the bars around the frame
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(crate) fn trace_pixel_path(&mut self, x: u32, y: u32) -> Option<PixelPath> {
+        let (x, y) = self.viewport.frame_buffer_pixel(x, y)?;
+        let queue = self.gpu.context.queue();
+        queue.write_buffer(&self.gpu.buffers.debug_path, 0, bytemuck::cast_slice(&PixelPath::make_request(x, y)));
+
+        let size = self.gpu.buffers.debug_path.size();
+        let cpu_mappable_mediator = self.gpu.context.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("debug path cpu mappable mediator"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.create_command_encoder("debug path encoder");
+        self.dispatch_compute_pass(&mut encoder, "debug path compute pass", &self.pipeline_debug_path, Vector3::new(1, 1, 1));
+        encoder.copy_buffer_to_buffer(&self.gpu.buffers.debug_path, 0, &cpu_mappable_mediator, 0, size);
+        let submission = queue.submit(Some(encoder.finish()));
+
+        let mediator_slice = cpu_mappable_mediator.slice(..);
+        mediator_slice.map_async(wgpu::MapMode::Read, |result| result.expect("'map' operation has failed"));
+        self.gpu.context.wait(Some(submission));
+
+        let result = PixelPath::from_gpu(bytemuck::cast_slice(&mediator_slice.get_mapped_range()));
+        cpu_mappable_mediator.unmap();
+        Some(result)
+    }
+
+    // the coordinates are in the output pixels; the objects covering the rectangle
+    // with the count of their pixels, the bars and the background are not counted;
+    // empty until the object id map of the first frame is read back
+    #[must_use]
+    pub(crate) fn objects_in_rect(&mut self, x: u32, y: u32, width: u32, height: u32) -> HashMap<ObjectUid, u32> {
+        let mut result = HashMap::new();
+        let Some((left, top, width, height)) = self.viewport.frame_buffer_rect(x, y, width, height) else {
+            return result;
+        };
+        if false == self.object_id_readback {
+            self.read_object_ids();
+        }
+        let map = self.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu();
+        let row_length = self.uniforms.frame_buffer_size().width();
+        for row in top..top + height {
+            let start = (row_length * row + left) as usize;
+            let Some(pixels) = map.get(start..start + width as usize) else {
+                break;
+            };
+            for &uid in pixels {
+                if 0 != uid {
+                    *result.entry(ObjectUid(uid)).or_insert(0) += 1;
+                }
+            }
+        }
+        result
+    }
+
+    // blocks till the surface attributes of the last frame are at the cpu
+    #[must_use]
+    pub(crate) fn read_g_buffer(&mut self) -> GBuffer<'_> {
+        if false == self.gpu.buffers.ray_tracing_frame_buffer.can_prepare_copies_from_gpu() {
+            self.wait_for_copies_from_gpu();
+        }
+        let mut encoder = self.create_command_encoder("g-buffer copy encoder");
+        self.gpu.buffers.ray_tracing_frame_buffer.prepare_all_aux_buffers_copy_from_gpu(&mut encoder);
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+        self.gpu.buffers.ray_tracing_frame_buffer.request_copies_from_gpu();
+        self.wait_for_copies_from_gpu();
+
+        let frame_buffer = &self.gpu.buffers.ray_tracing_frame_buffer;
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        GBuffer::new(
+            frame_buffer_size.width(),
+            frame_buffer_size.height(),
+            frame_buffer.object_id_at_cpu(),
+            frame_buffer.depth_at_cpu(),
+            bytemuck::cast_slice(frame_buffer.normal_at_cpu()),
+            bytemuck::cast_slice(frame_buffer.albedo_at_cpu()),
+        )
+    }
+
+    pub(crate) fn start_new_frame(&mut self) {
+        self.objects.update_time();
+        if let Some(pose) = self.objects.take_camera_pose() {
+            let camera = self.uniforms.mutable_camera();
+            camera.set_view(pose.eye(), pose.look_at());
+            camera.set_fov(pose.fov());
+        }
+        self.sub_frame = 1.0;
+    }
+
+    // animated sdf get the time between the previous and the current frame in each accumulation pass,
+    // so the Monte Carlo passes of one frame average the motion instead of strobing
+    pub(crate) fn set_sub_frame_animation(&mut self, enabled: bool) {
+        self.sub_frame_animation = enabled;
+    }
+
+    // fraction of the time elapsed since the previous frame, the next accumulation pass samples animations at
+    pub(crate) fn set_sub_frame(&mut self, fraction: f64) {
+        assert!(fraction > 0.0 && fraction <= 1.0, "sub frame fraction {fraction} is out of (0, 1]");
+        self.sub_frame = fraction;
+    }
+
+    fn write_sub_frame_animation_times(&self) {
+        let animator = self.objects.animator();
+        let mut per_sdf_time = vec![0.0_f32; std::cmp::max(1, animator.slots_count())];
+        animator.write_sub_frame_times(&mut per_sdf_time, self.sub_frame);
+        self.gpu.context.queue().write_buffer(self.gpu.buffers.sdf_time.backend(), 0, bytemuck::cast_slice(&per_sdf_time));
+    }
+    
+    pub(crate) fn accumulate_more_rays(&mut self)  {
+        let _ = self.poll_copies_from_gpu();
+        self.rebuild_if_scene_replaced();
+        self.recompose_shader_if_sdf_classes_changed();
+        if self.shader_compilation_pending() {
+            // the scene buffers may already hold the instances of the classes the kept pipelines do not know
+            return;
+        }
+        self.bake_sdf_distance_grids();
+        self.bake_procedural_textures();
+        let mut rebuild_geometry_buffers = std::mem::take(&mut self.surface_attributes_outdated)
+            || (self.object_id_readback && self.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu_is_absent());
+        let buffers_status = self.update_buffers_if_scene_changed();
+        self.last_frame_statistics = buffers_status.frame_statistics();
+        // overwrites the frame times just uploaded; the accumulation is not restarted within a frame
+        if self.sub_frame_animation && self.objects.animator().in_motion() {
+            self.write_sub_frame_animation_times();
+        }
+        let animated_texture = self.objects.any_objects_have_animated_texture()
+            || self.uniforms.background().is_some_and(|uid| self.objects.container().background_animated(uid));
+        // dropped when the frame buffer got resized after the resume was requested
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        let resumed_accumulation = self.resumed_accumulation.take().filter(|checkpoint| checkpoint.frame_buffer_size() == frame_buffer_size);
+        let reprojected_frames;
+
+        {
+            let camera_changed = self.uniforms.mutable_camera().check_and_clear_updated_status();
+            let geometry_changed = buffers_status.geometry_updated() || std::mem::take(&mut self.camera_layer_mask_changed);
+            if geometry_changed || buffers_status.any_updated() {
+                self.shadow_map_outdated = true;
+            }
+            
+            if buffers_status.any_updated() || animated_texture {
+                self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default());
+            }
+
+            // the scene changes above make the history useless: resetting the accumulation forgets it
+            reprojected_frames = self.uniforms.begin_temporal_reprojection(camera_changed && self.temporal_reprojection_applicable() && resumed_accumulation.is_none());
+            
+            if camera_changed || geometry_changed {
+                self.uniforms.reset_frame_accumulation(self.color_buffer_evaluation.frame_counter_default() + reprojected_frames);
+                rebuild_geometry_buffers = true;
+            }
+
+            // the first upload of the scene resets the accumulation above: the checkpoint overrides it
+            if let Some(checkpoint) = resumed_accumulation.as_ref() {
+                self.uniforms.reset_frame_accumulation(checkpoint.frame_number());
+            }
+
+            // a converged image gets no more samples till something restarts the accumulation
+            if camera_changed || geometry_changed || buffers_status.any_updated() || animated_texture || resumed_accumulation.is_some() {
+                self.convergence.forget();
+            }
+            self.convergence.forget_if_restarted(self.uniforms.frame_number());
+            if self.accumulation_converged() && false == rebuild_geometry_buffers {
+                // the output pass still applies the grading, the tone mapping and the rest of the settings
+                self.upload_uniforms();
+                return;
+            }
+            
+            self.uniforms.next_frame(self.color_buffer_evaluation.frame_counter_increment());
+            self.uniforms.update_time(self.start_time.elapsed());
+            self.upload_uniforms();
+            self.uniforms.remember_frame_for_reprojection();
+        }
+
+        #[cfg(feature = "monte_carlo")]
+        if reprojected_frames > 0 {
+            let mut encoder = self.begin_compute_pass();
+            self.dispatch_compute_pass(&mut encoder, "reprojection history compute pass", &self.pipeline_reprojection_history, self.uniforms.work_groups_count());
+            self.gpu.context.queue().submit(Some(encoder.finish()));
+        }
+
+        let rebuild_albedo_buffer =
+               self.gpu.buffers.ray_tracing_frame_buffer.albedo_at_cpu_is_absent()
+            || rebuild_geometry_buffers
+            || buffers_status.any_updated()
+            || animated_texture;
+
+        let surface_attributes_written = rebuild_geometry_buffers || rebuild_albedo_buffer;
+        // without the pass the camera stood still since the previous frame
+        let clear_motion_vectors = self.motion_vectors_stale && false == surface_attributes_written;
+        self.motion_vectors_stale = surface_attributes_written;
+
+        if surface_attributes_written {
+            // the gpu lags more than two frames behind: the oldest copies are waited for
+            if false == self.gpu.buffers.ray_tracing_frame_buffer.can_prepare_copies_from_gpu() {
+                self.wait_for_copies_from_gpu();
+            }
+            let label = "nearest surface properties compute pass";
+            let encoder = self.begin_compute_pass();
+            let _ = self.compute_pass(encoder, label, &self.pipeline_surface_attributes, |pass| {
+                if rebuild_geometry_buffers {
+                    if cfg!(feature = "denoiser") {
+                        self.gpu.buffers.ray_tracing_frame_buffer.prepare_all_aux_buffers_copy_from_gpu(pass);
+                    } else if self.object_id_readback {
+                        self.gpu.buffers.ray_tracing_frame_buffer.prepare_object_id_copy_from_gpu(pass);
+                    }
+                } else if cfg!(feature = "denoiser") && rebuild_albedo_buffer {
+                    self.gpu.buffers.ray_tracing_frame_buffer.prepare_albedo_copy_from_gpu(pass);
+                }
+            });
+            self.gpu.buffers.ray_tracing_frame_buffer.request_copies_from_gpu();
+            if rebuild_geometry_buffers {
+                self.gpu.picking.invalidate();
+            }
+        }
+
+        let label = "ray tracing compute pass";
+        let mut encoder = self.begin_compute_pass();
+        if clear_motion_vectors {
+            encoder.clear_buffer(self.gpu.buffers.ray_tracing_frame_buffer.motion_vector_at_gpu().as_ref(), BufferAddress::default(), None);
+        }
+        let restart_accumulation = (rebuild_geometry_buffers || buffers_status.any_updated() || animated_texture) && self.color_buffer_evaluation.frame_counter_increment() > 0;
+        // the checkpoint keeps the beauty image only: the lighting layers start over from it
+        if (resumed_accumulation.is_some() || restart_accumulation) && self.gpu.buffers.ray_tracing_frame_buffer.lighting_allocated() {
+            encoder.clear_buffer(self.gpu.buffers.ray_tracing_frame_buffer.lighting_at_gpu().as_ref(), BufferAddress::default(), None);
+        }
+        if let Some(checkpoint) = resumed_accumulation {
+            // the queue writes land before the commands of the encoder
+            self.gpu.buffers.ray_tracing_frame_buffer.fill_noisy_pixel_color(self.gpu.context.queue(), checkpoint.pixel_color());
+        } else if restart_accumulation {
+            encoder.clear_buffer(self.gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color().as_ref(), BufferAddress::default(), None);
+            #[cfg(feature = "monte_carlo")]
+            encoder.clear_buffer(self.gpu.buffers.ray_tracing_frame_buffer.indirect_half_resolution_at_gpu().as_ref(), BufferAddress::default(), None);
+            #[cfg(feature = "monte_carlo")]
+            encoder.clear_buffer(self.gpu.buffers.ray_tracing_frame_buffer.direct_full_resolution_at_gpu().as_ref(), BufferAddress::default(), None);
+        }
+        if let Some(indirect_lighting_pipeline) = self.color_buffer_evaluation.indirect_lighting_pipeline() {
+            self.dispatch_compute_pass(&mut encoder, "half resolution indirect lighting compute pass", indirect_lighting_pipeline.deref(), self.uniforms.work_groups_count_half_resolution());
+        }
+        // before the probes, which shade by the map too
+        if self.color_buffer_evaluation.id() == RenderStrategyId::Deterministic && self.uniforms.shadow_map_resolution() > 0 && std::mem::take(&mut self.shadow_map_outdated) {
+            self.dispatch_compute_pass(&mut encoder, "shadow map compute pass", &self.pipeline_shadow_map, self.uniforms.work_groups_count_shadow_map());
+        }
+        if self.color_buffer_evaluation.id() == RenderStrategyId::Deterministic && self.uniforms.probe_grid().is_some() {
+            if buffers_status.any_updated() || animated_texture {
+                encoder.clear_buffer(self.gpu.buffers.irradiance_probes.backend().as_ref(), BufferAddress::default(), None);
+            }
+            self.dispatch_compute_pass(&mut encoder, "irradiance probes compute pass", &self.pipeline_irradiance_probes, self.uniforms.work_groups_count_irradiance_probes());
+        }
+        #[cfg(feature = "monte_carlo")]
+        if self.color_buffer_evaluation.id() == RenderStrategyId::Wavefront {
+            self.pipelines_wavefront.encode(&mut encoder, &self.gpu.buffers.wavefront, self.uniforms.work_groups_count());
+        }
+        self.compute_pass(encoder, label, self.color_buffer_evaluation.pipeline().deref(), |pass|{
+            #[cfg(feature = "monte_carlo")]
+            if reprojected_frames > 0 {
+                self.dispatch_compute_pass(pass, "temporal reprojection compute pass", &self.pipeline_temporal_reprojection, self.uniforms.work_groups_count());
+            }
+            #[cfg(not(feature = "monte_carlo"))]
+            let _ = pass;
+        });
+
+        let samples = self.uniforms.frame_number();
+        if self.convergence_tracked() && self.convergence.snapshot_due(samples) {
+            self.copy_noisy_pixels_to_cpu();
+            self.convergence.take_snapshot(samples, self.gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color_at_cpu());
+        }
+    }
+
+    // TODO: rewrite with 'write_buffer_with'? May be we need kind of ping-pong or circular buffer here?
+    fn upload_uniforms(&self) {
+        let uniform_values = self.uniforms.serialize();
+        self.gpu.context.queue().write_buffer(&self.gpu.buffers.uniforms, 0, uniform_values.backend());
+    }
+
+    // the deterministic strategy does not accumulate anything to converge
+    #[must_use]
+    fn convergence_tracked(&self) -> bool {
+        self.stop_criterion.is_some() && self.color_buffer_evaluation.frame_counter_increment() > 0
+    }
+
+    // the snapshots of the accumulated image are read back to the cpu as the samples count doubles
+    pub(crate) fn set_stop_criterion(&mut self, criterion: Option<StopCriterion>) {
+        self.stop_criterion = criterion;
+    }
+
+    #[must_use]
+    pub(crate) fn stop_criterion(&self) -> Option<StopCriterion> {
+        self.stop_criterion
+    }
+
+    // none till the accumulation has two snapshots to compare
+    #[must_use]
+    pub(crate) fn convergence_error(&self) -> Option<f64> {
+        self.convergence.relative_error()
+    }
+
+    #[must_use]
+    pub(crate) fn accumulation_converged(&self) -> bool {
+        self.convergence_tracked() && self.stop_criterion.is_some_and(|criterion| criterion.met(self.convergence.relative_error(), self.uniforms.frame_number()))
+    }
+
+    // once per class change; till then the tracer marches the classes without the grids
+    fn bake_sdf_distance_grids(&mut self) {
+        if std::mem::replace(&mut self.sdf_distance_grids_baked, true) {
+            return;
+        }
+        let grids = self.objects.container().sdf_distance_grids();
+        if grids.values_count() == 0 {
+            return;
+        }
+        let mut encoder = self.create_command_encoder("sdf distance grids bake encoder");
+        self.dispatch_compute_pass(&mut encoder, "sdf distance grids bake compute pass", &self.pipeline_sdf_distance_grids_bake, Uniforms::work_groups_count_of(grids.bake_grid()));
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+    }
+
+    // the albedo of a baked material gets into the atlas before the scene buffers are updated, so the
+    // materials are serialized with the bitmap; a failed bake turns the baking of the material off
+    fn bake_procedural_textures(&mut self) {
+        for (material, properties) in self.objects.container().materials().pending_albedo_bakings() {
+            let Some((texture, baking)) = properties.pending_albedo_baking() else {
+                continue;
+            };
+            let texels = self.bake_procedural_texture(texture, &baking);
+            let bitmap = baking.encode_texels(&texels, properties.albedo());
+            let texels = ImmutableBitmapReference::new(bitmap.as_slice(), baking.resolution());
+            if let Err(error) = self.objects.put_baked_albedo(material, texels, baking.mapping()) {
+                log::warn!("procedural albedo baking has failed: {error}");
+            }
+        }
+    }
+
+    // the linear colors of the texels, four floats each
+    #[must_use]
+    fn bake_procedural_texture(&mut self, texture: ProceduralTextureUid, baking: &ProceduralTextureBaking) -> Vec<f32> {
+        let resolution = baking.resolution();
+        let request_size = (BAKE_REQUEST_QUARTETS * size_of::<PodVector>()) as BufferAddress;
+        let texels_size = (resolution.width() * resolution.height() * size_of::<PodVector>()) as BufferAddress;
+
+        let mut content = bytemuck::cast_slice(&baking.make_request(texture)).to_vec();
+        content.resize((request_size + texels_size) as usize, 0);
+        let buffer = self.gpu.resources.create_buffer("procedural texture bake", BufferUsages::STORAGE | BufferUsages::COPY_SRC, &content);
+
+        let device = self.gpu.context.device();
+        self.pipeline_procedural_texture_bake.setup_bind_group(Self::FRAME_BUFFERS_GROUP_INDEX, Some("procedural texture bake compute pipeline frame buffers group"), device, |bind_group_builder| {
+            bind_group_builder.set_storage_entry(14, buffer.clone());
+        });
+
+        let cpu_mappable_mediator = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("procedural texture bake cpu mappable mediator"),
+            size: texels_size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let grid = FrameBufferSize::new(resolution.width() as u32, resolution.height() as u32);
+        let mut encoder = self.create_command_encoder("procedural texture bake encoder");
+        self.dispatch_compute_pass(&mut encoder, "procedural texture bake compute pass", &self.pipeline_procedural_texture_bake, Uniforms::work_groups_count_of(grid));
+        encoder.copy_buffer_to_buffer(&buffer, request_size, &cpu_mappable_mediator, 0, texels_size);
+        let submission = self.gpu.context.queue().submit(Some(encoder.finish()));
+
+        let mediator_slice = cpu_mappable_mediator.slice(..);
+        mediator_slice.map_async(wgpu::MapMode::Read, |result| result.expect("'map' operation has failed"));
+        self.gpu.context.wait(Some(submission));
+
+        let result = bytemuck::cast_slice(&mediator_slice.get_mapped_range()).to_vec();
+        cpu_mappable_mediator.unmap();
+        result
+    }
+
+    // the cpu copies of the frame buffer layers arrive a frame or so after the gpu has rendered them;
+    // true once no copy is on the way
+    pub(crate) fn poll_copies_from_gpu(&mut self) -> bool {
+        self.gpu.context.poll();
+        self.gpu.buffers.ray_tracing_frame_buffer.collect_copies_from_gpu();
+        self.gpu.picking.collect();
+        self.gpu.pass_timer.borrow_mut().collect(&self.gpu.context);
+        false == self.gpu.buffers.ray_tracing_frame_buffer.copies_from_gpu_pending() && false == self.gpu.picking.answers_pending()
+    }
+
+    pub(crate) fn wait_for_copies_from_gpu(&mut self) {
+        self.gpu.context.wait(None);
+        self.gpu.buffers.ray_tracing_frame_buffer.collect_copies_from_gpu();
+    }
+    
+    // the deterministic render does not accumulate, the half resolution indirect one accumulates in its own buffers
+    #[must_use]
+    fn temporal_reprojection_applicable(&self) -> bool {
+        #[cfg(feature = "monte_carlo")]
+        return self.temporal_reprojection_enabled
+            && matches!(self.color_buffer_evaluation.id(), RenderStrategyId::MonteCarlo | RenderStrategyId::Wavefront);
+        #[cfg(not(feature = "monte_carlo"))]
+        return false;
+    }
+
+    fn prepare_pixel_color_copy_from_gpu(&self, pass: &mut wgpu::CommandEncoder) {
+        self.gpu.buffers.ray_tracing_frame_buffer.prepare_pixel_color_copy_from_gpu(pass);
+    }
+
+    // blocks till the accumulated pixels (and the copies of the other layers on the way) are at the cpu
+    fn copy_noisy_pixels_to_cpu(&mut self) {
+        if false == self.gpu.buffers.ray_tracing_frame_buffer.can_prepare_copies_from_gpu() {
+            self.wait_for_copies_from_gpu();
+        }
+        let mut encoder = self.create_command_encoder("noisy pixels copy encoder");
+        self.prepare_pixel_color_copy_from_gpu(&mut encoder);
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+        self.gpu.buffers.ray_tracing_frame_buffer.request_copies_from_gpu();
+        self.wait_for_copies_from_gpu();
+    }
+
+    // blocks till the albedo, the normals, the depth and the object ids of the last traced frame
+    // (and, on request, its lighting layers) are at the cpu
+    fn copy_aux_buffers_to_cpu(&mut self, lighting: bool) {
+        if false == self.gpu.buffers.ray_tracing_frame_buffer.can_prepare_copies_from_gpu() {
+            self.wait_for_copies_from_gpu();
+        }
+        let mut encoder = self.create_command_encoder("aux buffers copy encoder");
+        self.gpu.buffers.ray_tracing_frame_buffer.prepare_all_aux_buffers_copy_from_gpu(&mut encoder);
+        if lighting {
+            self.gpu.buffers.ray_tracing_frame_buffer.prepare_lighting_copy_from_gpu(&mut encoder);
+        }
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+        self.gpu.buffers.ray_tracing_frame_buffer.request_copies_from_gpu();
+        self.wait_for_copies_from_gpu();
+    }
+
+    // the output pass is run anew into memory, the layers are read back on demand
+    pub(crate) fn capture_frame(&mut self, aovs: CapturedAovs) -> anyhow::Result<CapturedFrame> {
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        let pixels = self.present_into_memory();
+        let image = CapturedFrame::decode_presented(&pixels, self.gpu.pipelines_factory.presentation_format())?;
+        let mut frame = CapturedFrame::new(frame_buffer_size.width(), frame_buffer_size.height(), image);
+        if aovs.is_empty() {
+            return Ok(frame);
+        }
+
+        let lighting = aovs.intersects(CapturedAovs::LIGHTING);
+        if lighting && false == self.gpu.buffers.ray_tracing_frame_buffer.lighting_allocated() {
+            bail!("the lighting aovs are off");
+        }
+
+        self.copy_aux_buffers_to_cpu(lighting);
+        let frame_buffer = &self.gpu.buffers.ray_tracing_frame_buffer;
+        let colors = |vectors: &[PodVector]| vectors.iter().map(|vector| [vector.x, vector.y, vector.z]).collect::<Vec<_>>();
+        if aovs.contains(CapturedAovs::ALBEDO) {
+            frame = frame.with_albedo(colors(frame_buffer.albedo_at_cpu()));
+        }
+        if aovs.contains(CapturedAovs::NORMAL) {
+            frame = frame.with_normal(colors(frame_buffer.normal_at_cpu()));
+        }
+        if aovs.contains(CapturedAovs::OBJECT_ID) {
+            frame = frame.with_object_id(frame_buffer.object_id_at_cpu().clone());
+        }
+        if aovs.contains(CapturedAovs::DEPTH) {
+            frame = frame.with_depth(frame_buffer.depth_at_cpu().clone());
+        }
+        if lighting {
+            frame = Self::with_averaged_lighting(frame, frame_buffer.lighting_at_cpu());
+        }
+        Ok(frame)
+    }
+
+    // the w of a lighting texel counts the samples accumulated into it
+    #[must_use]
+    fn with_averaged_lighting(frame: CapturedFrame, accumulated: &[[PodVector; LIGHTING_AOVS_PER_PIXEL]]) -> CapturedFrame {
+        let average = |sum: &PodVector| {
+            if sum.w > 0.0 { [sum.x / sum.w, sum.y / sum.w, sum.z / sum.w] } else { [0.0; 3] }
+        };
+        let mut direct = Vec::with_capacity(accumulated.len());
+        let mut indirect = Vec::with_capacity(accumulated.len());
+        let mut emission = Vec::with_capacity(accumulated.len());
+        for [direct_sum, indirect_sum, emission_sum] in accumulated {
+            direct.push(average(direct_sum));
+            indirect.push(average(indirect_sum));
+            emission.push(average(emission_sum));
+        }
+        frame.with_lighting(direct, indirect, emission)
+    }
+
+    // the half resolution strategy keeps its indirect samples in a buffer of its own, and the deterministic
+    // one accumulates nothing: there is no checkpoint of theirs
+    #[must_use]
+    fn accumulation_checkpoint_applicable(&self) -> bool {
+        #[cfg(feature = "monte_carlo")]
+        return matches!(self.color_buffer_evaluation.id(), RenderStrategyId::MonteCarlo | RenderStrategyId::Wavefront);
+        #[cfg(not(feature = "monte_carlo"))]
+        return false;
+    }
+
+    pub(crate) fn make_accumulation_checkpoint(&mut self) -> anyhow::Result<AccumulationCheckpoint> {
+        if false == self.accumulation_checkpoint_applicable() {
+            bail!("the render strategy has no accumulation to checkpoint");
+        }
+        self.copy_noisy_pixels_to_cpu();
+        let pixel_color = self.gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color_at_cpu().clone();
+        Ok(AccumulationCheckpoint::new(self.uniforms.frame_buffer_size(), self.uniforms.frame_number(), pixel_color))
+    }
+
+    // the next pass adds its samples to the checkpoint ones; the scene and the camera are the caller's
+    // business: the renderer cannot tell they are the ones of the checkpoint
+    pub(crate) fn resume_accumulation(&mut self, checkpoint: AccumulationCheckpoint) -> anyhow::Result<()> {
+        if false == self.accumulation_checkpoint_applicable() {
+            bail!("the render strategy can not resume an accumulation");
+        }
+        if checkpoint.frame_buffer_size() != self.uniforms.frame_buffer_size() {
+            bail!("the checkpoint frame buffer is {:?}, the renderer one is {:?}", checkpoint.frame_buffer_size(), self.uniforms.frame_buffer_size());
+        }
+        self.resumed_accumulation = Some(checkpoint);
+        Ok(())
+    }
+
+    #[cfg(feature = "denoiser")]
+    pub(crate) fn set_denoiser_device(&mut self, device_type: denoiser::DenoiserDeviceType) {
+        self.denoiser = denoiser::Denoiser::new(device_type, self.denoise_mode);
+        self.denoiser_device_type = device_type;
+        if let Some(settings) = self.denoiser_worker.as_ref().map(|worker| worker.settings()) {
+            self.denoiser_worker = Some(denoiser::DenoiserWorker::new(device_type, self.denoise_mode, settings));
+        }
+    }
+
+    // the filter quality is changed by making the denoiser anew; 'Off' skips the denoising along with
+    // the blending, the output pass reads the accumulated image then
+    #[cfg(feature = "denoiser")]
+    pub(crate) fn set_denoise_mode(&mut self, mode: denoiser::DenoiseMode) {
+        if self.denoise_mode == mode {
+            return;
+        }
+        self.denoise_mode = mode;
+        if self.denoising() {
+            self.set_denoiser_device(self.denoiser_device_type);
+        }
+        // the image in the denoised buffer is of the previous mode
+        self.denoising_schedule.forget();
+
+        let bypassed = denoiser::DenoiseMode::Off == mode;
+        if self.gpu.denoised_blend.bypassed() != bypassed {
+            self.gpu.denoised_blend.set_bypassed(bypassed);
+            Self::setup_frame_buffers_bindings_for_rasterization(&self.gpu, &mut self.pipeline_final_image_rasterization, self.color_buffer_evaluation.id());
+        }
+    }
+
+    #[must_use]
+    #[cfg(feature = "denoiser")]
+    pub(crate) fn denoising(&self) -> bool {
+        denoiser::DenoiseMode::Off != self.denoise_mode
+    }
+
+    // with a queue, the denoising runs on a thread of its own and the latest denoised frame is
+    // shown (a frame or so behind); without, each frame waits for the denoiser
+    #[cfg(feature = "denoiser")]
+    pub(crate) fn set_denoiser_queue(&mut self, queue: Option<denoiser::DenoiserQueue>) {
+        self.denoiser_worker = queue.map(|settings| denoiser::DenoiserWorker::new(self.denoiser_device_type, self.denoise_mode, settings));
+    }
+
+    #[must_use]
+    #[cfg(feature = "denoiser")]
+    pub(crate) fn denoiser_dropped_frames_count(&self) -> u64 {
+        self.denoiser_worker.as_ref().map_or(0, |worker| worker.dropped_frames_count())
+    }
+
+    // every 'interval' frames, and when the accumulation restarts; the frames in between blend the
+    // last denoised image with the samples accumulated since on the gpu
+    #[cfg(feature = "denoiser")]
+    pub(crate) fn denoise_accumulated_image(&mut self, interval: u32, timer: &mut denoiser::MinMaxTimeMeasurer)
+    {
+        let due = self.denoising_schedule.begin_frame(interval, self.uniforms.accumulation_restarts());
+        if due {
+            self.copy_noisy_pixels_to_cpu();
+        }
+
+        let frame_buffer_width = self.uniforms.frame_buffer_size().width() as usize;
+        let frame_buffer_height = self.uniforms.frame_buffer_size().height() as usize;
+        let frame_number = self.uniforms.frame_number();
+
+        if let Some(worker) = self.denoiser_worker.as_mut() {
+            if due {
+                let (beauty, albedo, normal) = self.gpu.buffers.ray_tracing_frame_buffer.denoiser_input();
+                worker.submit(denoiser::NoisyFrame::new(frame_buffer_width, frame_buffer_height, frame_number, beauty.clone(), albedo.clone(), normal.clone()));
+            }
+
+            // a frame of the size before a resize does not fit the buffer
+            let latest = worker.take_latest_denoised()
+                .filter(|denoised| denoised.width == frame_buffer_width && denoised.height == frame_buffer_height);
+            if let Some(denoised) = latest {
+                timer.add_measurement(denoised.denoising_time);
+                self.gpu.buffers.denoised_beauty_image.fill_render_target(self.gpu.context.queue(), &denoised.beauty);
+                self.denoising_schedule.denoised(denoised.frame_number);
+            }
+        } else if due {
+            let (beauty, albedo, normal) = self.gpu.buffers.ray_tracing_frame_buffer.denoiser_input();
+            let beauty_floats: &mut [f32] = bytemuck::cast_slice_mut(beauty);
+            let albedo_floats: &[f32] = bytemuck::cast_slice(albedo);
+            let normal_floats: &[f32] = bytemuck::cast_slice(normal);
+
+            timer.start();
+            let mut executor = self.denoiser.begin_denoise(frame_buffer_width, frame_buffer_height);
+            executor.issue_albedo_write(albedo_floats);
+            executor.issue_normal_write(normal_floats);
             executor.issue_noisy_beauty_write(beauty_floats);
             executor.filter(beauty_floats);
             timer.stop();
             
             self.gpu.buffers.denoised_beauty_image.fill_render_target(self.gpu.context.queue(), beauty);
-            self.gpu.context.queue().submit([]);
+            self.denoising_schedule.denoised(frame_number);
+        }
+
+        self.blend_denoised_image();
+    }
+
+    // no denoised image yet: the noisy one is shown as is
+    #[cfg(feature = "denoiser")]
+    fn blend_denoised_image(&mut self) {
+        if self.gpu.denoised_blend.fit(self.uniforms.frame_buffer_size(), self.gpu.context.device()) {
+            Self::setup_frame_buffers_bindings_for_rasterization(&self.gpu, &mut self.pipeline_final_image_rasterization, self.color_buffer_evaluation.id());
         }
+        let noisy_weight = self.denoising_schedule.denoised_frame_number()
+            .map_or(1.0, |denoised| denoiser::DenoisedBlendPass::noisy_weight(denoised, self.uniforms.frame_number()));
+        let noisy = self.gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color();
+        let denoised = self.gpu.buffers.denoised_beauty_image.gpu_render_target();
+        let mut encoder = self.create_command_encoder("denoised blend encoder");
+        self.gpu.denoised_blend.run(&mut encoder, self.gpu.context.device(), self.gpu.context.queue(), noisy, denoised, noisy_weight);
+        self.gpu.context.queue().submit(Some(encoder.finish()));
     }
 
     #[allow(dead_code)] 
@@ -670,14 +2670,100 @@ impl Renderer {
     
     pub(crate) fn present(&mut self, surface_texture: &wgpu::SurfaceTexture) {
         let view = &surface_texture.texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let mut render_pass_descriptor = wgpu::RenderPassDescriptor {
+        let target_size = PhysicalSize::new(surface_texture.texture.width(), surface_texture.texture.height());
+        self.present_into_view(view, target_size, Some(self.viewport));
+    }
+
+    // runs the same output pass as 'present', but into an offscreen texture; returns tightly packed rows
+    #[must_use]
+    pub(crate) fn present_into_memory(&mut self) -> Vec<u8> {
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        let format = self.gpu.pipelines_factory.presentation_format();
+        let bytes_per_pixel = format.block_copy_size(None).expect("presentation format must be copyable");
+        let unpadded_bytes_per_row = frame_buffer_size.width() * bytes_per_pixel;
+        let padded_bytes_per_row = unpadded_bytes_per_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let texture_size = wgpu::Extent3d { width: frame_buffer_size.width(), height: frame_buffer_size.height(), depth_or_array_layers: 1, };
+        let texture = self.gpu.context.device().create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen output"),
+            size: texture_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let cpu_mappable_mediator = self.gpu.context.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("offscreen output cpu mappable mediator"),
+            size: (padded_bytes_per_row * frame_buffer_size.height()) as BufferAddress,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        self.present_into_view(&texture.create_view(&wgpu::TextureViewDescriptor::default()), PhysicalSize::new(frame_buffer_size.width(), frame_buffer_size.height()), None);
+
+        let mut encoder = self.create_command_encoder("offscreen output copy encoder");
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &cpu_mappable_mediator,
+                layout: wgpu::TexelCopyBufferLayout { offset: 0, bytes_per_row: Some(padded_bytes_per_row), rows_per_image: None, },
+            },
+            texture_size,
+        );
+        let copy_submission = self.gpu.context.queue().submit(Some(encoder.finish()));
+
+        let mediator_slice = cpu_mappable_mediator.slice(..);
+        mediator_slice.map_async(wgpu::MapMode::Read, |result| result.expect("'map' operation has failed"));
+        self.gpu.context.wait(Some(copy_submission));
+
+        let mut result = Vec::with_capacity((unpadded_bytes_per_row * frame_buffer_size.height()) as usize);
+        {
+            let raw_data = mediator_slice.get_mapped_range();
+            for row in raw_data.chunks_exact(padded_bytes_per_row as usize) {
+                result.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        cpu_mappable_mediator.unmap();
+
+        result
+    }
+
+    // the copies and the output pass are submitted to the renderer's queue: work submitted
+    // to the same queue afterward observes the current frame; the views stay valid until resize
+    pub(crate) fn export_frame_textures(&mut self) -> &ExportedTextures {
+        let frame_buffer_size = self.uniforms.frame_buffer_size();
+        let exported_textures = self.exported_textures.take()
+            .filter(|textures| textures.frame_buffer_size() == frame_buffer_size)
+            .unwrap_or_else(|| ExportedTextures::new(self.gpu.context.device(), frame_buffer_size, self.gpu.pipelines_factory.presentation_format()));
+
+        let mut encoder = self.create_command_encoder("frame textures export encoder");
+        exported_textures.issue_copy_from(&mut encoder, &self.gpu.buffers.ray_tracing_frame_buffer);
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+        self.present_into_view(exported_textures.final_image(), PhysicalSize::new(frame_buffer_size.width(), frame_buffer_size.height()), None);
+
+        self.exported_textures.insert(exported_textures)
+    }
+
+    // the clear color covers the bars around the viewport, if any; the overlay spans the whole target
+    fn present_into_view(&mut self, view: &wgpu::TextureView, target_size: PhysicalSize<u32>, viewport: Option<Viewport>) {
+        if self.uniforms.auto_exposure().is_some() {
+            self.measure_exposure();
+        }
+        if let Some(bloom) = self.uniforms.bloom() {
+            self.spread_bloom(bloom);
+        }
+
+        let [r, g, b, a] = self.uniforms.clear_color();
+        let render_pass_descriptor = wgpu::RenderPassDescriptor {
             label: Some("rasterization pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view,
                 depth_slice: None,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0, }),
+                    load: wgpu::LoadOp::Clear(wgpu::Color { r: r as f64, g: g as f64, b: b as f64, a: a as f64, }),
                     store: StoreOp::Store,
                 },
             })],
@@ -686,7 +2772,48 @@ impl Renderer {
             timestamp_writes: None,
         };
 
-        self.final_image_rasterization_pass(&mut render_pass_descriptor, &self.pipeline_final_image_rasterization,);
+        self.final_image_rasterization_pass(&render_pass_descriptor, &self.pipeline_final_image_rasterization, viewport);
+        self.selection_outline_pass(view, viewport);
+        self.overlay_pass(view, target_size);
+        self.gpu.pass_timer.borrow_mut().end_frame(&self.gpu.context);
+    }
+
+    fn selection_outline_pass(&mut self, view: &wgpu::TextureView, viewport: Option<Viewport>) {
+        self.gpu.selection_outline.update(&self.highlighted_objects, self.highlight_color, self.uniforms.frame_buffer_size(), self.gpu.context.queue(), &self.gpu.resources);
+        if self.highlighted_objects.is_empty() {
+            return;
+        }
+        let object_ids = self.gpu.buffers.ray_tracing_frame_buffer.object_id_at_gpu();
+        let mut encoder = self.create_command_encoder("selection outline pass encoder");
+        self.gpu.selection_outline.draw(&mut encoder, self.gpu.context.device(), view, object_ids, viewport);
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+    }
+
+    fn overlay_pass(&mut self, view: &wgpu::TextureView, target_size: PhysicalSize<u32>) {
+        self.gpu.overlay.update(&self.overlay, self.gpu.context.device(), self.gpu.context.queue(), &self.gpu.resources);
+        if self.overlay.vertices().is_empty() {
+            return;
+        }
+        let mut encoder = self.create_command_encoder("overlay pass encoder");
+        self.gpu.overlay.draw(&mut encoder, self.gpu.context.queue(), view, target_size.width, target_size.height);
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+    }
+
+    fn spread_bloom(&mut self, bloom: Bloom) {
+        if self.gpu.bloom.fit(self.uniforms.frame_buffer_size(), self.gpu.context.device(), &self.gpu.resources) {
+            Self::setup_frame_buffers_bindings_for_rasterization(&self.gpu, &mut self.pipeline_final_image_rasterization, self.color_buffer_evaluation.id());
+        }
+        let pixel_color = Self::displayed_pixel_color(&self.gpu, self.color_buffer_evaluation.id());
+        let mut encoder = self.create_command_encoder("bloom encoder");
+        self.gpu.bloom.run(&mut encoder, self.gpu.context.device(), self.gpu.context.queue(), pixel_color, bloom.threshold(), self.uniforms.frame_number());
+        self.gpu.context.queue().submit(Some(encoder.finish()));
+    }
+
+    fn measure_exposure(&self) {
+        let mut encoder = self.begin_compute_pass();
+        self.dispatch_compute_pass(&mut encoder, "luminance histogram compute pass", &self.pipeline_luminance_histogram, self.uniforms.work_groups_count());
+        self.dispatch_compute_pass(&mut encoder, "auto exposure compute pass", &self.pipeline_auto_exposure, Vector3::new(1, 1, 1));
+        self.gpu.context.queue().submit(Some(encoder.finish()));
     }
 
     #[must_use]
@@ -694,14 +2821,15 @@ impl Renderer {
         self.create_command_encoder("compute pass encoder")
     }
 
-    fn compute_pass<CustomizationDelegate>(&self, encoder: CommandEncoder, label: &str, compute_pipeline: &ComputePipeline, customize: CustomizationDelegate) -> SubmissionIndex
+    fn compute_pass<CustomizationDelegate>(&self, encoder: CommandEncoder, label: &'static str, compute_pipeline: &ComputePipeline, customize: CustomizationDelegate) -> SubmissionIndex
     where CustomizationDelegate : FnOnce(&mut CommandEncoder){
         
         let mut encoder = encoder; {
 
+            let timestamps = self.gpu.pass_timer.borrow_mut().begin_pass(label);
             {let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some(label),
-                timestamp_writes: None,
+                timestamp_writes: timestamps.as_ref().map(PassTimestamps::compute_pass_writes),
             });
                 
             compute_pipeline.set_into_pass(&mut pass);
@@ -714,9 +2842,28 @@ impl Renderer {
         self.gpu.context.queue().submit(Some(command_buffer))
     }
 
-    fn final_image_rasterization_pass(&self, rasterization_pass_descriptor: &mut wgpu::RenderPassDescriptor, rasterization_pipeline: &RasterizationPipeline) {
+    fn dispatch_compute_pass(&self, encoder: &mut CommandEncoder, label: &'static str, compute_pipeline: &ComputePipeline, work_groups_needed: Vector3<u32>) {
+        let timestamps = self.gpu.pass_timer.borrow_mut().begin_pass(label);
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some(label),
+            timestamp_writes: timestamps.as_ref().map(PassTimestamps::compute_pass_writes),
+        });
+
+        compute_pipeline.set_into_pass(&mut pass);
+        pass.dispatch_workgroups(work_groups_needed.x, work_groups_needed.y, work_groups_needed.z);
+    }
+
+    fn final_image_rasterization_pass(&self, rasterization_pass_descriptor: &wgpu::RenderPassDescriptor, rasterization_pipeline: &RasterizationPipeline, viewport: Option<Viewport>) {
+        let timestamps = self.gpu.pass_timer.borrow_mut().begin_pass("rasterization pass");
+        let rasterization_pass_descriptor = wgpu::RenderPassDescriptor {
+            timestamp_writes: timestamps.as_ref().map(PassTimestamps::render_pass_writes),
+            ..rasterization_pass_descriptor.clone()
+        };
         let mut encoder = self.create_command_encoder("rasterization pass encoder"); {
-            let mut rasterization_pass = encoder.begin_render_pass(rasterization_pass_descriptor);
+            let mut rasterization_pass = encoder.begin_render_pass(&rasterization_pass_descriptor);
+            if let Some(viewport) = viewport {
+                viewport.set_into_pass(&mut rasterization_pass);
+            }
             rasterization_pipeline.set_into_pass(&mut rasterization_pass);
             rasterization_pass.draw(0..6, 0..1); // TODO: magic const
         }
@@ -729,118 +2876,511 @@ impl Renderer {
         self.gpu.context.device().create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some(label) })
     }
 
-    #[must_use]
-    pub fn camera(&mut self) -> &mut Camera {
-        self.uniforms.mutable_camera()
+    #[must_use]
+    pub fn camera(&mut self) -> &mut Camera {
+        self.uniforms.mutable_camera()
+    }
+}
+
+pub(crate) const WHOLE_TRACER_GPU_CODE: &str = include_str!("../../shader/_tracer.wgsl");
+
+struct Buffers {
+    uniforms: Rc<wgpu::Buffer>,
+
+    ray_tracing_frame_buffer: FrameBuffer,
+    denoised_beauty_image: FrameBufferLayer<PodVector>,
+    
+    parallelograms: VersionedBuffer,
+    sdf: VersionedBuffer,
+    spheres: VersionedBuffer,
+    object_layers: VersionedBuffer,
+    object_uv_transforms: VersionedBuffer,
+    object_opacities: VersionedBuffer,
+    triangles: VersionedBuffer,
+    materials: VersionedBuffer,
+    texture_atlases_mapping: VersionedBuffer,
+
+    bvh: ResizableBuffer,
+    bvh_inflated: ResizableBuffer,
+    
+    sdf_time: VersionedBuffer,
+    sdf_distance_grids: Rc<wgpu::Buffer>,
+
+    irradiance_probes: ResizableBuffer,
+    shadow_map: ResizableBuffer,
+    emissive_parallelograms: VersionedBuffer,
+    light_tree: VersionedBuffer,
+
+    luminance_histogram: Rc<wgpu::Buffer>,
+    auto_exposure: Rc<wgpu::Buffer>,
+    random_seeds: Rc<wgpu::Buffer>,
+
+    #[cfg(feature = "monte_carlo")]
+    wavefront: WavefrontBuffers,
+    #[cfg(feature = "monte_carlo")]
+    debug_path: Rc<wgpu::Buffer>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::geometry::alias::{Point, Vector};
+    use crate::geometry::transform::Affine;
+    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
+    use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+    use crate::sdf::framework::sdf_registrator::SdfRegistrator;
+    use crate::material::atlas_region_mapping::AtlasRegionMappingBuilder;
+    use crate::material::procedural_textures::ProceduralTextures;
+    use crate::material::texture_procedural_3d::TextureProcedural3D;
+    use crate::material::texture_reference::TextureReference;
+    use crate::sdf::object::sdf_box::SdfBox;
+    use crate::shader::code::{FunctionBody, ShaderCode};
+    use crate::scene::frame_capture::CaptureFormat;
+    use crate::scene::overlay::OverlayPoint;
+    use crate::utils::bitmap_utils::BitmapSize;
+    use crate::utils::tests::assert_utils::tests::assert_all_items_equal;
+    use crate::utils::tests::common_values::tests::COMMON_PRESENTATION_FORMAT;
+    use cgmath::{AbsDiffEq, SquareMatrix, Vector4};
+    use image::{ImageBuffer, Rgba};
+    #[cfg(feature = "monte_carlo")]
+    use more_asserts::assert_ge;
+    use std::fs;
+    use std::path::Path;
+
+    #[cfg(feature = "denoiser")]
+    use crate::serialization::pod_vector::PodVector;
+    #[cfg(feature = "denoiser")]
+    use exr::prelude::write_rgba_file;
+
+    const TEST_FRAME_BUFFER_WIDTH: u32 = 256;
+    const TEST_FRAME_BUFFER_HEIGHT: u32 = 256;
+    const TEST_FRAME_BUFFER_SIZE: FrameBufferSize = FrameBufferSize::new(TEST_FRAME_BUFFER_WIDTH, TEST_FRAME_BUFFER_HEIGHT);
+
+    const TEST_COLOR_R: f32 = 0.25;
+    const TEST_COLOR_G: f32 = 0.5;
+    const TEST_COLOR_B: f32 = 1.0;
+
+    const NO_ANTIALIASING_LEVEL: u32 = 1;
+
+    #[must_use]
+    pub(crate) fn test_folder_path() -> PathBuf {
+        PathBuf::from("tests")
+    }
+
+    #[must_use]
+    pub(crate) fn out_folder_path() -> PathBuf {
+        test_folder_path().join("out")
+    }
+
+    #[must_use]
+    pub(crate) fn data_folder_path() -> PathBuf {
+        test_folder_path().join("data")
+    }
+
+    #[must_use]
+    fn make_render(scene: VisualObjects, camera: Camera, strategy: RenderStrategyId, antialiasing_level: u32, context: Rc<Context>) -> Renderer {
+        let frame_buffer_settings = FrameBufferSettings::new(COMMON_PRESENTATION_FORMAT, TEST_FRAME_BUFFER_SIZE, antialiasing_level);
+        Renderer::new(context.clone(), scene, camera, frame_buffer_settings, strategy, None)
+            .expect("render instantiation has failed")
+    }
+
+    #[test]
+    fn test_empty_scene_rendering_deterministic() {
+        test_empty_scene_rendering(RenderStrategyId::Deterministic);
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_empty_scene_rendering_monte_carlo() {
+        test_empty_scene_rendering(RenderStrategyId::MonteCarlo);
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_empty_scene_rendering_monte_carlo_half_resolution_indirect() {
+        test_empty_scene_rendering(RenderStrategyId::MonteCarloHalfResolutionIndirect);
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_empty_scene_rendering_wavefront() {
+        test_empty_scene_rendering(RenderStrategyId::Wavefront);
+    }
+
+    fn test_empty_scene_rendering(strategy: RenderStrategyId) {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let context = create_headless_wgpu_vulkan_context();
+
+        let mut system_under_test = make_render(scene, camera, strategy, NO_ANTIALIASING_LEVEL, context.clone());
+
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+        
+        assert_empty_color_buffer(&mut system_under_test);
+        assert_empty_ids_buffer(&mut system_under_test);
+        
+        #[cfg(feature = "denoiser")]
+        {
+            assert_normals_and_albedo_are_empty(&mut system_under_test);
+        }
+    }
+    
+    #[test]
+    fn test_present_into_memory() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
+
+        system_under_test.accumulate_more_rays();
+        let pixels = system_under_test.present_into_memory();
+
+        assert_eq!(pixels.len(), (TEST_FRAME_BUFFER_SIZE.area() * 4) as usize);
+        assert!(pixels.chunks_exact(4).all(|pixel| pixel[3] == u8::MAX));
+    }
+
+    #[test]
+    fn test_overlay_over_presented_image() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.set_clear_color([0.0, 0.0, 0.0, 1.0]);
+        let half_width = TEST_FRAME_BUFFER_WIDTH / 2;
+        system_under_test.overlay().add_rect(OverlayPoint::new(0.0, 0.0), half_width as f32, TEST_FRAME_BUFFER_HEIGHT as f32, [1.0, 1.0, 1.0, 1.0]);
+
+        system_under_test.accumulate_more_rays();
+        let pixels = system_under_test.present_into_memory();
+
+        for (index, pixel) in pixels.chunks_exact(4).enumerate() {
+            let covered = (index as u32 % TEST_FRAME_BUFFER_WIDTH) < half_width;
+            assert_eq!(pixel[0] == u8::MAX, covered, "unexpected pixel {index}: {pixel:?}");
+        }
+    }
+
+    #[test]
+    fn test_pick_presentation_format() {
+        use wgpu::TextureFormat;
+
+        assert_eq!(FrameBufferSettings::pick_presentation_format(&[TextureFormat::Bgra8Unorm, TextureFormat::Rgba16Float]), TextureFormat::Rgba16Float);
+        assert_eq!(FrameBufferSettings::pick_presentation_format(&[TextureFormat::Bgra8Unorm, TextureFormat::Rgba8Unorm]), TextureFormat::Bgra8Unorm);
+    }
+
+    #[test]
+    fn test_selection_outline_around_highlighted_object() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        let sphere = scene.add_sphere(Point::new(0.0, 0.0, 0.0), 0.5, test_material);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        const OUTLINE_COLOR: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+        system_under_test.set_highlighted_objects(&[sphere], OUTLINE_COLOR);
+
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+        let pixels = system_under_test.present_into_memory();
+
+        let middle_row = (TEST_FRAME_BUFFER_WIDTH * (TEST_FRAME_BUFFER_HEIGHT / 2)) as usize;
+        let object_id_map = system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu();
+        let sphere_left_edge = (0..TEST_FRAME_BUFFER_WIDTH as usize).find(|column| object_id_map[middle_row + column] == sphere.0).unwrap();
+        let is_outline = |index: usize| pixels[index * 4..index * 4 + 4] == [0, u8::MAX, 0, u8::MAX];
+        assert!(is_outline(middle_row + sphere_left_edge - 1));
+        assert!(false == is_outline(middle_row + sphere_left_edge));
+        assert!(false == is_outline(0));
+    }
+
+    #[test]
+    fn test_bloom_brightens_neighbourhood_of_emissive_object() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let emissive_material = scene.materials_mutable().add(&MaterialProperties::new().with_emission(8.0, 8.0, 8.0));
+        let sphere = scene.add_sphere(Point::new(0.0, 0.0, 0.0), 0.25, emissive_material);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+        let without_bloom = system_under_test.present_into_memory();
+        system_under_test.set_bloom(Some(Bloom::new().with_threshold(0.5).with_intensity(1.0)));
+        let with_bloom = system_under_test.present_into_memory();
+
+        let middle_row = (TEST_FRAME_BUFFER_WIDTH * (TEST_FRAME_BUFFER_HEIGHT / 2)) as usize;
+        let object_id_map = system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu();
+        let sphere_left_edge = (0..TEST_FRAME_BUFFER_WIDTH as usize).find(|column| object_id_map[middle_row + column] == sphere.0).unwrap();
+        let red_at = |pixels: &[u8], index: usize| pixels[index * 4];
+        let next_to_sphere = middle_row + sphere_left_edge - 2;
+        assert!(red_at(&with_bloom, next_to_sphere) > red_at(&without_bloom, next_to_sphere));
+    }
+
+    #[test]
+    fn test_object_ids_arrive_without_blocking() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
+
+        system_under_test.accumulate_more_rays();
+        let copies_were_pending = false == system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu_is_absent();
+        context.wait(None);
+
+        assert!(copies_were_pending);
+        assert!(system_under_test.poll_copies_from_gpu());
+        assert_eq!(system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu().len(), TEST_FRAME_BUFFER_SIZE.area() as usize);
+        assert_eq!(system_under_test.object_in_pixel(0, 0), None);
+    }
+
+    #[test]
+    fn test_picking_without_object_id_readback() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        let sphere = scene.add_sphere(Point::new(0.0, 0.0, 0.0), 0.5, test_material);
+        let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
+        system_under_test.set_object_id_readback(false);
+
+        system_under_test.accumulate_more_rays();
+        let (center_x, center_y) = (TEST_FRAME_BUFFER_WIDTH / 2, TEST_FRAME_BUFFER_HEIGHT / 2);
+        system_under_test.request_object_in_pixel(center_x, center_y);
+        context.wait(None);
+
+        assert!(system_under_test.poll_copies_from_gpu());
+        assert!(system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu().is_empty());
+        assert_eq!(system_under_test.object_in_pixel(center_x, center_y), Some(sphere));
+        assert_eq!(system_under_test.object_in_pixel(0, 0), None);
     }
-}
 
-pub(crate) const WHOLE_TRACER_GPU_CODE: &str = include_str!("../../shader/_tracer.wgsl");
+    #[test]
+    fn test_buffers_statistics() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let context = create_headless_wgpu_vulkan_context();
+        let system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
 
-struct Buffers {
-    uniforms: Rc<wgpu::Buffer>,
+        let statistics = system_under_test.buffers_statistics();
 
-    ray_tracing_frame_buffer: FrameBuffer,
-    denoised_beauty_image: FrameBufferLayer<PodVector>,
-    
-    parallelograms: VersionedBuffer,
-    sdf: VersionedBuffer,
-    triangles: VersionedBuffer,
-    materials: VersionedBuffer,
-    texture_atlases_mapping: VersionedBuffer,
+        assert_eq!(statistics.buffers().len(), 7);
+        let bvh = statistics.of_kind(SceneBufferKind::Bvh).unwrap();
+        assert_eq!(bvh.binding_limit(), context.device().limits().max_storage_buffer_binding_size as u64);
+        assert!(statistics.buffers().iter().all(|buffer| buffer.bytes() > 0));
+        assert!(statistics.overflow_warning().is_none());
+    }
 
-    bvh: ResizableBuffer,
-    bvh_inflated: ResizableBuffer,
-    
-    sdf_time: VersionedBuffer,
-}
+    #[test]
+    fn test_statistics() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let material = scene.materials_mutable().add(&MaterialProperties::new());
+        scene.add_parallelogram(Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), material);
+        scene.add_sphere(Point::new(0.0, 0.0, 2.0), 1.0, material);
+        let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
 
-#[cfg(test)]
-pub(crate) mod tests {
-    use super::*;
-    use crate::geometry::alias::{Point, Vector};
-    use crate::geometry::transform::Affine;
-    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
-    use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
-    use crate::sdf::framework::sdf_registrator::SdfRegistrator;
-    use crate::sdf::object::sdf_box::SdfBox;
-    use crate::utils::tests::assert_utils::tests::assert_all_items_equal;
-    use crate::utils::tests::common_values::tests::COMMON_PRESENTATION_FORMAT;
-    use cgmath::{AbsDiffEq, SquareMatrix};
-    use image::{ImageBuffer, Rgba};
-    use std::fs;
-    use std::path::Path;
+        system_under_test.accumulate_more_rays();
+        let _ = system_under_test.present_into_memory();
+        system_under_test.poll_copies_from_gpu();
+        let statistics = system_under_test.statistics();
+
+        assert_eq!(statistics.objects(), ObjectCounts::new(1, 0, 0, 1));
+        assert_eq!(statistics.triangles(), 0);
+        assert_eq!(statistics.buffers(), &system_under_test.buffers_statistics());
+        assert_eq!(statistics.pass_timings().is_some(), context.timestamp_queries_supported());
+    }
 
-    #[cfg(feature = "denoiser")]
-    use crate::serialization::pod_vector::PodVector;
-    #[cfg(feature = "denoiser")]
-    use exr::prelude::write_rgba_file;
+    #[test]
+    fn test_capture_frame() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.accumulate_more_rays();
+        let directory = tempfile::tempdir().unwrap();
 
-    const TEST_FRAME_BUFFER_WIDTH: u32 = 256;
-    const TEST_FRAME_BUFFER_HEIGHT: u32 = 256;
-    const TEST_FRAME_BUFFER_SIZE: FrameBufferSize = FrameBufferSize::new(TEST_FRAME_BUFFER_WIDTH, TEST_FRAME_BUFFER_HEIGHT);
+        let frame = system_under_test.capture_frame(CapturedAovs::ALBEDO | CapturedAovs::OBJECT_ID).unwrap();
+        frame.save(&directory.path().join("shot.png"), CaptureFormat::Png).unwrap();
 
-    const TEST_COLOR_R: f32 = 0.25;
-    const TEST_COLOR_G: f32 = 0.5;
-    const TEST_COLOR_B: f32 = 1.0;
+        assert!(directory.path().join("shot.png").exists());
+        assert!(directory.path().join("shot_albedo.png").exists());
+        assert!(directory.path().join("shot_object_id.png").exists());
+        assert!(false == directory.path().join("shot_normal.png").exists());
+    }
 
-    const NO_ANTIALIASING_LEVEL: u32 = 1;
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_temporal_reprojection_is_opt_in() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        assert_eq!(system_under_test.temporal_reprojection_applicable(), false);
 
-    #[must_use]
-    pub(crate) fn test_folder_path() -> PathBuf {
-        PathBuf::from("tests")
-    }
+        system_under_test.set_temporal_reprojection(true);
 
-    #[must_use]
-    pub(crate) fn out_folder_path() -> PathBuf {
-        test_folder_path().join("out")
+        assert!(system_under_test.temporal_reprojection_applicable());
     }
 
-    #[must_use]
-    pub(crate) fn data_folder_path() -> PathBuf {
-        test_folder_path().join("data")
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_capture_lighting_aovs() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        assert!(system_under_test.capture_frame(CapturedAovs::LIGHTING).is_err());
+
+        system_under_test.set_lighting_aovs(true);
+        system_under_test.accumulate_more_rays();
+        let frame = system_under_test.capture_frame(CapturedAovs::LIGHTING | CapturedAovs::DEPTH).unwrap();
+
+        let area = TEST_FRAME_BUFFER_SIZE.area() as usize;
+        assert_eq!(frame.direct_light().unwrap().len(), area);
+        assert_eq!(frame.indirect_light().unwrap().len(), area);
+        assert_eq!(frame.depth().unwrap().len(), area);
+        // nothing is hit: the background is all the emitted light
+        assert!(frame.direct_light().unwrap().iter().all(|texel| *texel == [0.0; 3]));
+        assert!(frame.emission().unwrap().iter().any(|texel| *texel != [0.0; 3]));
     }
 
-    #[must_use]
-    fn make_render(scene: VisualObjects, camera: Camera, strategy: RenderStrategyId, antialiasing_level: u32, context: Rc<Context>) -> Renderer {
-        let frame_buffer_settings = FrameBufferSettings::new(COMMON_PRESENTATION_FORMAT, TEST_FRAME_BUFFER_SIZE, antialiasing_level);
-        Renderer::new(context.clone(), scene, camera, frame_buffer_settings, strategy, None)
-            .expect("render instantiation has failed")
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_random_seed_texture() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.set_random_generator(RandomGenerator::XxHash);
+        system_under_test.set_random_seed_texture(Some(RandomSeedTexture::new(2, 2, vec![0.0, 0.25, 0.5, 0.75]).unwrap()));
+        system_under_test.accumulate_more_rays();
+
+        assert_eq!(system_under_test.random_generator(), RandomGenerator::XxHash);
+        assert_eq!(system_under_test.random_seed_texture().map(|texture| texture.width()), Some(2));
+
+        system_under_test.set_random_seed_texture(None);
+        system_under_test.accumulate_more_rays();
+        assert!(system_under_test.random_seed_texture().is_none());
     }
 
     #[test]
-    fn test_empty_scene_rendering_deterministic() {
-        test_empty_scene_rendering(RenderStrategyId::Deterministic);
+    fn test_last_frame_statistics() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let material = scene.materials_mutable().add(&MaterialProperties::new());
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.accumulate_more_rays();
+
+        system_under_test.objects().add_sphere(Point::new(0.0, 0.0, 2.0), 1.0, material);
+        system_under_test.accumulate_more_rays();
+        let changed = system_under_test.last_frame_statistics();
+        system_under_test.accumulate_more_rays();
+        let unchanged = system_under_test.last_frame_statistics();
+
+        assert!(changed.uploaded_buffers() > 0);
+        assert!(changed.uploaded_bytes() >= changed.peak_staging_bytes());
+        assert!(changed.peak_staging_bytes() > 0);
+        assert_eq!(unchanged, FrameStatistics::default());
     }
 
     #[test]
-    fn test_empty_scene_rendering_monte_carlo() {
-        test_empty_scene_rendering(RenderStrategyId::MonteCarlo);
+    fn test_gpu_passes_report() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
+
+        for _ in 0..3 {
+            system_under_test.accumulate_more_rays();
+            let _ = system_under_test.present_into_memory();
+            system_under_test.poll_copies_from_gpu();
+        }
+        let report = system_under_test.gpu_passes_report();
+
+        assert_eq!(report.is_some(), context.timestamp_queries_supported());
+        if let Some(report) = report {
+            assert!(report.frames() > 0);
+            assert!(report.of_label("ray tracing compute pass").is_some());
+            assert!(report.of_label("rasterization pass").is_some());
+        }
     }
 
-    fn test_empty_scene_rendering(strategy: RenderStrategyId) {
+    #[test]
+    fn test_export_frame_textures() {
         let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
-        let scene = VisualObjects::new(None, None, None);
+        let scene = VisualObjects::new(None, None, None, None, None);
         let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
 
-        let mut system_under_test = make_render(scene, camera, strategy, NO_ANTIALIASING_LEVEL, context.clone());
+        system_under_test.accumulate_more_rays();
+        let exported = system_under_test.export_frame_textures();
 
-        shoot_rays_and_transfer_data_to_cpu(context.deref(), &mut system_under_test);
-        
-        assert_empty_color_buffer(&mut system_under_test);
-        assert_empty_ids_buffer(&mut system_under_test);
-        
-        #[cfg(feature = "denoiser")]
-        {
-            assert_normals_and_albedo_are_empty(&mut system_under_test);
+        assert_eq!(exported.frame_buffer_size(), TEST_FRAME_BUFFER_SIZE);
+        assert_eq!(exported.object_id().texture().format(), wgpu::TextureFormat::R32Uint);
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_stop_criterion() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.set_stop_criterion(Some(StopCriterion::new(1e-3, 1000)));
+
+        for _ in 0..4 * Convergence::FIRST_SNAPSHOT_SAMPLES {
+            system_under_test.accumulate_more_rays();
         }
+
+        // the background alone is all the same in every sample: the second snapshot finds no error
+        assert!(system_under_test.accumulation_converged());
+        assert_eq!(system_under_test.uniforms.frame_number(), 2 * Convergence::FIRST_SNAPSHOT_SAMPLES);
+        assert!(system_under_test.convergence_error().is_some_and(|error| error < 1e-5));
+
+        system_under_test.camera().move_horizontally(1.0);
+        system_under_test.accumulate_more_rays();
+        assert!(false == system_under_test.accumulation_converged());
+        assert_eq!(system_under_test.convergence_error(), None);
     }
-    
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_converged_frame_uploads_uniforms() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.set_stop_criterion(Some(StopCriterion::new(1e-3, 1000)));
+        for _ in 0..4 * Convergence::FIRST_SNAPSHOT_SAMPLES {
+            system_under_test.accumulate_more_rays();
+        }
+        assert!(system_under_test.accumulation_converged());
+        let samples = system_under_test.uniforms.frame_number();
+
+        system_under_test.set_color_grading(ColorGrading::new().with_exposure(1.5));
+        system_under_test.accumulate_more_rays();
+
+        assert_eq!(system_under_test.uniforms.frame_number(), samples);
+        assert_eq!(&uniforms_at_gpu(&system_under_test), system_under_test.uniforms.serialize().backend());
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    fn uniforms_at_gpu(system_under_test: &Renderer) -> Vec<u8> {
+        let uniforms = &system_under_test.gpu.buffers.uniforms;
+        let cpu_mappable_mediator = system_under_test.gpu.context.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("uniforms cpu mappable mediator"),
+            size: uniforms.size(),
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = system_under_test.create_command_encoder("uniforms copy encoder");
+        encoder.copy_buffer_to_buffer(uniforms, 0, &cpu_mappable_mediator, 0, uniforms.size());
+        let submission = system_under_test.gpu.context.queue().submit(Some(encoder.finish()));
+
+        let mediator_slice = cpu_mappable_mediator.slice(..);
+        mediator_slice.map_async(wgpu::MapMode::Read, |result| result.expect("'map' operation has failed"));
+        system_under_test.gpu.context.wait(Some(submission));
+
+        let result = mediator_slice.get_mapped_range().to_vec();
+        cpu_mappable_mediator.unmap();
+        result
+    }
+
+    #[cfg(feature = "monte_carlo")]
     #[test]
     fn test_single_parallelogram_rendering() {
         let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
         
-        let mut scene = VisualObjects::new(None, None, None);
+        let mut scene = VisualObjects::new(None, None, None, None, None);
         let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
         
         scene.add_parallelogram(
@@ -854,6 +3394,7 @@ pub(crate) mod tests {
         let mut system_under_test = make_render(scene, camera, RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, context.clone());
 
         system_under_test.accumulate_more_rays();
+        system_under_test.wait_for_copies_from_gpu();
         
         assert_parallelogram_ids_in_center(&mut system_under_test, "single_parallelogram");
         
@@ -872,7 +3413,7 @@ pub(crate) mod tests {
         let test_box_name = UniqueSdfClassName::new("specimen".to_string());
         registrator.add(&NamedSdf::new(SdfBox::new(Vector::new(0.5, 0.5, 0.5)), test_box_name.clone()));
         
-        let mut scene = VisualObjects::new(None, Some(registrator), None);
+        let mut scene = VisualObjects::new(None, Some(registrator), None, None, None);
         let test_material = MaterialProperties::new()
             .with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B)
             .with_emission(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B);
@@ -883,25 +3424,288 @@ pub(crate) mod tests {
 
         let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
 
-        shoot_rays_and_transfer_data_to_cpu(context.deref(), &mut system_under_test);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
 
         assert_parallelogram_ids_in_center(&mut system_under_test, "sdf_box");
         assert_parallelogram_colors_in_center(&mut system_under_test, "sdf_box");
     }
 
-    pub(crate) fn shoot_rays_and_transfer_data_to_cpu(context: &Context, system_under_test: &mut Renderer) {
+    #[test]
+    fn test_single_sphere_rendering() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        let sphere = scene.add_sphere(Point::new(0.0, 0.0, 0.0), 0.5, test_material);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        let object_id_map = system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu();
+        let center = (TEST_FRAME_BUFFER_WIDTH * (TEST_FRAME_BUFFER_HEIGHT / 2) + TEST_FRAME_BUFFER_WIDTH / 2) as usize;
+        assert_eq!(object_id_map[center], sphere.0);
+        assert_eq!(object_id_map[0], 0);
+    }
+
+    #[test]
+    fn test_hidden_sphere_is_not_traced() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        let sphere = scene.add_sphere(Point::new(0.0, 0.0, 0.0), 0.5, test_material);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        let center = (TEST_FRAME_BUFFER_WIDTH * (TEST_FRAME_BUFFER_HEIGHT / 2) + TEST_FRAME_BUFFER_WIDTH / 2) as usize;
+
+        system_under_test.objects().set_visible(sphere, false);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+        assert_eq!(system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu()[center], 0);
+
+        system_under_test.objects().set_visible(sphere, true);
+        system_under_test.objects().set_layer_mask(sphere, LayerMask::layer(1));
+        system_under_test.set_camera_layer_mask(LayerMask::layer(0));
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+        assert_eq!(system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu()[center], 0);
+
+        system_under_test.set_camera_layer_mask(LayerMask::layer(0) | LayerMask::layer(1));
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+        assert_eq!(system_under_test.gpu.buffers.ray_tracing_frame_buffer.object_id_at_cpu()[center], sphere.0);
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_accumulation_resumed_from_checkpoint() {
+        let make_scene = || {
+            let mut scene = VisualObjects::new(None, None, None, None, None);
+            let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+            scene.add_sphere(Point::new(0.0, 0.0, 0.0), 0.5, test_material);
+            scene
+        };
+        let make_camera = || Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let context = create_headless_wgpu_vulkan_context();
+
+        let mut original = make_render(make_scene(), make_camera(), RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, context.clone());
+        for _ in 0..3 {
+            original.accumulate_more_rays();
+        }
+        let checkpoint = original.make_accumulation_checkpoint().unwrap();
+        assert_eq!(checkpoint.frame_number(), 3);
+        let saved_pixels = checkpoint.pixel_color().to_vec();
+
+        let mut system_under_test = make_render(make_scene(), make_camera(), RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, context);
+        system_under_test.resume_accumulation(checkpoint).unwrap();
         system_under_test.accumulate_more_rays();
-        issue_frame_buffer_transfer_if_needed(context, &system_under_test);
+
+        assert_eq!(system_under_test.uniforms.frame_number(), 4);
         system_under_test.copy_noisy_pixels_to_cpu();
+        // the samples are added to the saved sums instead of replacing them
+        let resumed_pixels = system_under_test.gpu.buffers.ray_tracing_frame_buffer.noisy_pixel_color_at_cpu();
+        for (resumed, saved) in resumed_pixels.iter().zip(saved_pixels.iter()) {
+            assert_ge!(resumed.x, saved.x);
+            assert_ge!(resumed.y, saved.y);
+            assert_ge!(resumed.z, saved.z);
+        }
+    }
+
+    #[test]
+    fn test_deterministic_render_has_no_checkpoint() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+
+        assert!(system_under_test.make_accumulation_checkpoint().is_err());
+        let checkpoint = AccumulationCheckpoint::new(TEST_FRAME_BUFFER_SIZE, 1, vec![PodVector::default(); TEST_FRAME_BUFFER_SIZE.area() as usize]);
+        assert!(system_under_test.resume_accumulation(checkpoint).is_err());
+    }
+
+    #[test]
+    fn test_g_buffer_reading() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        let sphere = scene.add_sphere(Point::new(0.0, 0.0, 0.0), 0.5, test_material);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.accumulate_more_rays();
+        let g_buffer = system_under_test.read_g_buffer();
+
+        assert_eq!(g_buffer.object_ids().len(), TEST_FRAME_BUFFER_SIZE.area() as usize);
+        let center = g_buffer.texel(TEST_FRAME_BUFFER_WIDTH / 2, TEST_FRAME_BUFFER_HEIGHT / 2).unwrap();
+        assert_eq!(center.object(), Some(sphere));
+        assert_gt!(center.normal()[2], 0.0);
+        assert_eq!(center.albedo(), [TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B]);
+        let corner = g_buffer.texel(0, 0).unwrap();
+        assert_eq!(corner.object(), None);
+        assert_gt!(corner.depth(), center.depth());
+    }
+
+    #[test]
+    fn test_sdf_class_added_to_live_scene() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = MaterialProperties::new()
+            .with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B)
+            .with_emission(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B);
+        let test_material_uid = scene.materials_mutable().add(&test_material);
+
+        let context = create_headless_wgpu_vulkan_context();
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, context.clone());
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        let test_box_name = UniqueSdfClassName::new("late_specimen".to_string());
+        system_under_test.objects().add_sdf_class(&NamedSdf::new(SdfBox::new(Vector::new(0.5, 0.5, 0.5)), test_box_name.clone()));
+        system_under_test.objects().add_sdf(&Affine::identity(), &test_box_name, test_material_uid);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        assert_parallelogram_ids_in_center(&mut system_under_test, "late_sdf_box");
+    }
+
+    #[test]
+    fn test_sdf_class_added_with_background_shader_compilation() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = MaterialProperties::new()
+            .with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B)
+            .with_emission(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B);
+        let test_material_uid = scene.materials_mutable().add(&test_material);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.set_background_shader_compilation(true);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        let test_box_name = UniqueSdfClassName::new("background_specimen".to_string());
+        system_under_test.objects().add_sdf_class(&NamedSdf::new(SdfBox::new(Vector::new(0.5, 0.5, 0.5)), test_box_name.clone()));
+        system_under_test.objects().add_sdf(&Affine::identity(), &test_box_name, test_material_uid);
+        system_under_test.accumulate_more_rays();
+        while system_under_test.shader_compilation_pending() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            system_under_test.accumulate_more_rays();
+        }
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        assert_parallelogram_ids_in_center(&mut system_under_test, "background_sdf_box");
+    }
+
+    #[test]
+    fn test_sdf_class_with_distance_grid() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = MaterialProperties::new()
+            .with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B)
+            .with_emission(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B);
+        let test_material_uid = scene.materials_mutable().add(&test_material);
+        let test_box_name = UniqueSdfClassName::new("gridded_specimen".to_string());
+        scene.add_sdf_class(&NamedSdf::new(SdfBox::new(Vector::new(0.5, 0.5, 0.5)), test_box_name.clone()).with_distance_grid(8));
+        let _ = scene.add_sdf(&Affine::identity(), 1.0, &test_box_name, test_material_uid);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        assert!(system_under_test.sdf_distance_grids_baked);
+        assert_parallelogram_ids_in_center(&mut system_under_test, "sdf_box_with_distance_grid");
+    }
+
+    #[test]
+    fn test_recreate_on_device() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        scene.add_parallelogram(Point::new(-0.5, -0.5, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), test_material);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.set_clear_color([0.0, 0.0, 0.0, 1.0]);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        let new_context = create_headless_wgpu_vulkan_context();
+        system_under_test.recreate_on_device(new_context.clone());
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        assert!(Rc::ptr_eq(&system_under_test.gpu.context, &new_context));
+        assert_eq!(system_under_test.uniforms.clear_color(), [0.0, 0.0, 0.0, 1.0]);
+        assert_parallelogram_ids_in_center(&mut system_under_test, "recreated_on_device");
     }
 
-    pub(crate) fn issue_frame_buffer_transfer_if_needed(context: &Context, render: &Renderer) {
-        if cfg!(not(feature = "denoiser")) {
-            let label = Some("raw color buffer copy gpu -> cpu");
-            let mut pass = context.device().create_command_encoder(&wgpu::CommandEncoderDescriptor { label });
-            render.prepare_pixel_color_copy_from_gpu(&mut pass);
-            context.queue().submit(Some(pass.finish()));
+    #[test]
+    fn test_recreate_on_device_uploads_atlas_page_again() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let scene = VisualObjects::new(Some(BitmapSize::new(32, 32)), None, None, None, None);
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        let atlas_page_version = Version(3);
+        system_under_test.upload_texture_atlas_page(&[255; 32 * 32 * 4], Some(atlas_page_version));
+
+        system_under_test.recreate_on_device(create_headless_wgpu_vulkan_context());
+
+        assert_eq!(system_under_test.gpu.textures.last_seen_data_version(), Some(atlas_page_version));
+    }
+
+    #[test]
+    fn test_replace_scene() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let previous_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(1.0, 1.0, 1.0));
+        scene.add_parallelogram(Point::new(0.5, 0.5, 0.0), Vector::new(0.1, 0.0, 0.0), Vector::new(0.0, 0.1, 0.0), previous_material);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.set_clear_color([0.0, 0.0, 0.0, 1.0]);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        let mut next_scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = next_scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        next_scene.add_parallelogram(Point::new(-0.5, -0.5, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), test_material);
+        let _ = system_under_test.objects().replace_scene(next_scene);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
+
+        assert_parallelogram_ids_in_center(&mut system_under_test, "replaced_scene");
+    }
+
+    #[test]
+    fn test_procedural_albedo_baking() {
+        let mut textures = ProceduralTextures::new(None);
+        let texture_code = ShaderCode::<FunctionBody>::new("return vec3f(0.5, 0.25, 1.0);\n".to_string());
+        let texture = textures.add(TextureProcedural3D::from_simple_body(texture_code), None);
+        let mapping = AtlasRegionMappingBuilder::new()
+            .local_position_to_texture_u(Vector4::new(1.0, 0.0, 0.0, 0.5))
+            .local_position_to_texture_v(Vector4::new(0.0, 1.0, 0.0, 0.5));
+        let baking = ProceduralTextureBaking::new(BitmapSize::new(4, 4), mapping);
+
+        let mut scene = VisualObjects::new(Some(BitmapSize::new(32, 32)), None, Some(textures), None, None);
+        let material = MaterialProperties::new().with_albedo_texture(TextureReference::Procedural(texture)).with_albedo_baking(baking);
+        let test_material = scene.materials_mutable().add(&material);
+        scene.add_parallelogram(Point::new(-0.5, -0.5, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), test_material);
+
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::Deterministic, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+
+        let texels = system_under_test.bake_procedural_texture(texture, &baking);
+        assert_eq!(texels.len(), 4 * 4 * 4);
+        for texel in texels.chunks_exact(4) {
+            assert_eq!(texel[0..3], [0.5, 0.25, 1.0]);
         }
+
+        system_under_test.accumulate_more_rays();
+        assert!(system_under_test.objects.container().materials().pending_albedo_bakings().is_empty());
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_trace_pixel_path() {
+        let camera = Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let test_material = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(TEST_COLOR_R, TEST_COLOR_G, TEST_COLOR_B));
+        scene.add_parallelogram(Point::new(-0.5, -0.5, 0.0), Vector::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0), test_material);
+
+        let mut system_under_test = make_render(scene, camera, RenderStrategyId::MonteCarlo, NO_ANTIALIASING_LEVEL, create_headless_wgpu_vulkan_context());
+        system_under_test.accumulate_more_rays();
+
+        let path = system_under_test.trace_pixel_path(TEST_FRAME_BUFFER_WIDTH / 2, TEST_FRAME_BUFFER_HEIGHT / 2).unwrap();
+
+        assert_eq!((path.x(), path.y()), (TEST_FRAME_BUFFER_WIDTH / 2, TEST_FRAME_BUFFER_HEIGHT / 2));
+        let first_hit = path.segments()[0].hit().expect("center ray must hit the parallelogram");
+        assert_eq!(first_hit.material(), test_material);
+    }
+
+    pub(crate) fn shoot_rays_and_transfer_data_to_cpu(system_under_test: &mut Renderer) {
+        system_under_test.accumulate_more_rays();
+        system_under_test.copy_noisy_pixels_to_cpu();
     }
 
     #[cfg(feature = "denoiser")]
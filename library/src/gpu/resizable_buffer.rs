@@ -57,6 +57,16 @@ impl ResizableBuffer {
         self.update(resources, queue, new_content.backend())
     }
 
+    // rewrites the part of the buffer in place, the rest is kept
+    pub(super) fn update_part_with_generator<Generator>(&mut self, queue: &wgpu::Queue, offset_bytes: u64, generate_data: Generator)
+    where
+        Generator: FnOnce() -> GpuReadySerializationBuffer,
+    {
+        let new_content = generate_data();
+        assert!(offset_bytes + new_content.backend().len() as u64 <= self.backend.size(), "the part does not fit into the buffer");
+        queue.write_buffer(self.backend.as_ref(), offset_bytes, new_content.backend());
+    }
+
     #[must_use]
     pub(super) fn update_with_slice<T: Pod>(&mut self, resources: &Resources, queue: &wgpu::Queue, content: &[T]) -> ResizeStatus {
         self.update(resources, queue, bytemuck::cast_slice(content))
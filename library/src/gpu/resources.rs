@@ -1,6 +1,6 @@
 use crate::gpu::context::Context;
 use crate::utils::bitmap_utils::{BitmapSize, BYTES_IN_RGBA_QUARTET};
-use more_asserts::{assert_gt, assert_le};
+use more_asserts::{assert_gt, assert_le, assert_lt};
 use std::rc::Rc;
 use wgpu::util::DeviceExt;
 use wgpu::{BufferUsages, Sampler, SamplerBorderColor, Texture};
@@ -37,7 +37,8 @@ impl Resources {
 
     #[must_use]
     pub(super) fn create_uniform_buffer(&self, label: &str, buffer_data: &[u8]) -> Rc<wgpu::Buffer> {
-        self.create_buffer(label, BufferUsages::UNIFORM | BufferUsages::COPY_DST, buffer_data)
+        // the source of the copies is for the tests reading the uploaded values back
+        self.create_buffer(label, BufferUsages::UNIFORM | BufferUsages::COPY_DST | BufferUsages::COPY_SRC, buffer_data)
     }
 
     #[must_use]
@@ -86,28 +87,39 @@ impl Resources {
     }
 
     pub(crate) fn write_whole_srgba_texture_data(&self, texture: &Texture, data: &[u8]) {
-        assert_eq!(data.len(), BitmapSize::new(texture.size().width as usize, texture.size().height as usize).bytes_in_bitmap());
+        self.write_whole_srgba_texture_level(texture, 0, data);
+    }
+
+    pub(crate) fn write_whole_srgba_texture_level(&self, texture: &Texture, mip_level: u32, data: &[u8]) {
+        assert_lt!(mip_level, texture.mip_level_count(), "no such mip level");
+        let level_size = texture.size().mip_level_size(mip_level, texture.dimension());
+        assert_eq!(data.len(), BitmapSize::new(level_size.width as usize, level_size.height as usize).bytes_in_bitmap());
 
         self.context.queue().write_texture(
             wgpu::TexelCopyTextureInfo {
                 texture,
-                mip_level: 0,
+                mip_level,
                 origin: wgpu::Origin3d::ZERO,
                 aspect: wgpu::TextureAspect::All,
             },
             data,
             wgpu::TexelCopyBufferLayout {
                 offset: 0,
-                bytes_per_row: Some(texture.width() * BYTES_IN_RGBA_QUARTET as u32),
-                rows_per_image: Some(texture.height()),
+                bytes_per_row: Some(level_size.width * BYTES_IN_RGBA_QUARTET as u32),
+                rows_per_image: Some(level_size.height),
             },
             wgpu::Extent3d {
-                width: texture.width(),
-                height: texture.height(),
+                width: level_size.width,
+                height: level_size.height,
                 depth_or_array_layers: 1,
             },
         );
     }
+
+    #[must_use]
+    pub(crate) fn max_mip_levels(size: BitmapSize) -> u32 {
+        Self::calculate_max_mips(size.width(), size.height())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,205 @@
+use crate::bvh::builder::Bvh;
+use crate::bvh::dump::{load_serialized_bvh, save_serialized_bvh};
+use crate::bvh::node::BvhNode;
+use crate::container::visual_objects::VisualObjects;
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use crate::serialization::serializable_for_gpu::GpuSerializationSize;
+use crate::utils::version::Version;
+use log::info;
+use std::path::PathBuf;
+
+// keeps the tree between the scene changes: while objects are only mutated (moved, reshaped),
+// the node boxes are refitted in place; adding or removing objects leads to the full rebuild
+pub(crate) struct SceneBvh {
+    aabb_inflation_rate: f64,
+    tree: Option<Bvh>,
+    topology_version: (Version, Version, Version),
+    // consulted at the scene setup only: huge static scenes take seconds to build
+    dump_path: Option<PathBuf>,
+    quantized: bool,
+}
+
+impl SceneBvh {
+    #[must_use]
+    pub(crate) fn new(aabb_inflation_rate: f64) -> Self {
+        assert!(aabb_inflation_rate >= 0.0, "aabb_inflation is negative");
+        Self { aabb_inflation_rate, tree: None, topology_version: (Version(0), Version(0), Version(0)), dump_path: None, quantized: false }
+    }
+
+    #[must_use]
+    pub(crate) fn with_dump(mut self, path: PathBuf) -> Self {
+        self.dump_path = Some(path);
+        self
+    }
+
+    // the tree belongs to another scene now: the topology versions of the two may coincide
+    pub(crate) fn forget_tree(&mut self) {
+        self.tree = None;
+    }
+
+    // the next serialization uses the other node layout; the tree itself stays valid
+    pub(crate) fn set_quantized(&mut self, quantized: bool) {
+        self.quantized = quantized;
+    }
+
+    // none when the tree is not built (yet), or was loaded from the dump
+    #[must_use]
+    pub(crate) fn depth(&self) -> Option<usize> {
+        self.tree.as_ref().map(Bvh::depth)
+    }
+
+    #[must_use]
+    fn quartets_per_node(&self) -> usize {
+        if self.quantized { BvhNode::SERIALIZED_QUANTIZED_QUARTET_COUNT } else { BvhNode::SERIALIZED_QUARTET_COUNT }
+    }
+
+    // the quantized layout has the quantization frame after the nodes
+    #[must_use]
+    fn nodes_count(&self, serialized: &GpuReadySerializationBuffer) -> u32 {
+        let trailing_frame = if self.quantized { 1 } else { 0 };
+        (serialized.total_slots_count() - trailing_frame) as u32
+    }
+
+    // returns the serialized tree and the count of its nodes
+    #[must_use]
+    pub(crate) fn serialize(&mut self, scene: &VisualObjects) -> (GpuReadySerializationBuffer, u32) {
+        if false == scene.bvh_inhabited() {
+            self.tree = None;
+            let empty_marker = GpuReadySerializationBuffer::make_filled(1, self.quartets_per_node(), 0.0_f32);
+            return (empty_marker, 0);
+        }
+
+        if let Some(dump_path) = self.dump_path.take() {
+            return self.serialize_through_dump(scene, dump_path);
+        }
+
+        let actual_topology_version = scene.bvh_topology_version();
+        match self.tree.as_mut() {
+            Some(tree) if self.topology_version == actual_topology_version => {
+                scene.refit_bvh(tree, self.aabb_inflation_rate);
+            },
+            _ => {
+                self.tree = Some(scene.evaluate_bvh(self.aabb_inflation_rate));
+                self.topology_version = actual_topology_version;
+            },
+        }
+
+        let tree = self.tree.as_ref().unwrap();
+        let serialized = if self.quantized { tree.serialize_quantized() } else { tree.serialize() };
+        let count = self.nodes_count(&serialized);
+        (serialized, count)
+    }
+
+    // a loaded dump has no tree to refit, so the first mutation of the scene leads to the build
+    #[must_use]
+    fn serialize_through_dump(&mut self, scene: &VisualObjects, dump_path: PathBuf) -> (GpuReadySerializationBuffer, u32) {
+        let source_hash = scene.bvh_support_hash(self.aabb_inflation_rate);
+        if let Some(serialized) = load_serialized_bvh(&dump_path, source_hash, self.quartets_per_node()) {
+            let count = self.nodes_count(&serialized);
+            return (serialized, count);
+        }
+
+        let (serialized, count) = self.serialize(scene);
+        if save_serialized_bvh(&dump_path, source_hash, self.quartets_per_node(), &serialized).is_none() {
+            info!("failed to write bvh dump file {dump_path:?}");
+        }
+        (serialized, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::transform::Affine;
+    use crate::material::material_index::MaterialIndex;
+    use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+    use crate::sdf::framework::sdf_registrator::SdfRegistrator;
+    use crate::sdf::object::sdf_sphere::SdfSphere;
+    use cgmath::SquareMatrix;
+
+    #[must_use]
+    fn make_scene_with_spheres(class_name: &UniqueSdfClassName) -> VisualObjects {
+        let mut registrator = SdfRegistrator::new();
+        registrator.add(&NamedSdf::new(SdfSphere::new(1.0), class_name.clone()));
+        let mut scene = VisualObjects::new(None, Some(registrator), None, None, None);
+        let _ = scene.add_sdf(&Affine::identity(), 1.0, class_name, MaterialIndex(0));
+        let _ = scene.add_sdf(&Affine::from_translation(cgmath::Vector3::new(5.0, 0.0, 0.0)), 1.0, class_name, MaterialIndex(0));
+        scene
+    }
+
+    #[test]
+    fn test_refit_matches_rebuild() {
+        let class_name = UniqueSdfClassName::new("sphere".to_string());
+        let mut scene = make_scene_with_spheres(&class_name);
+        let mut system_under_test = SceneBvh::new(0.0);
+        let _ = system_under_test.serialize(&scene);
+
+        scene.replace_sdf_class(&NamedSdf::new(SdfSphere::new(2.0), class_name));
+        let (refitted, refitted_count) = system_under_test.serialize(&scene);
+
+        let (rebuilt, rebuilt_count) = SceneBvh::new(0.0).serialize(&scene);
+        assert_eq!(refitted_count, rebuilt_count);
+        assert_eq!(refitted.backend(), rebuilt.backend());
+    }
+
+    #[test]
+    fn test_rebuild_after_topology_change() {
+        let class_name = UniqueSdfClassName::new("sphere".to_string());
+        let mut scene = make_scene_with_spheres(&class_name);
+        let mut system_under_test = SceneBvh::new(0.0);
+        let (_, initial_count) = system_under_test.serialize(&scene);
+
+        let _ = scene.add_sdf(&Affine::from_translation(cgmath::Vector3::new(0.0, 5.0, 0.0)), 1.0, &class_name, MaterialIndex(0));
+        let (_, actual_count) = system_under_test.serialize(&scene);
+
+        assert_eq!(initial_count, 3);
+        assert_eq!(actual_count, 5);
+    }
+
+    #[test]
+    fn test_setup_from_dump() {
+        let directory = tempfile::tempdir().unwrap();
+        let dump_path = directory.path().join("bvh");
+        let class_name = UniqueSdfClassName::new("sphere".to_string());
+        let mut scene = make_scene_with_spheres(&class_name);
+        let (built, _) = SceneBvh::new(0.0).with_dump(dump_path.clone()).serialize(&scene);
+
+        let mut system_under_test = SceneBvh::new(0.0).with_dump(dump_path);
+        let (loaded, loaded_count) = system_under_test.serialize(&scene);
+
+        assert_eq!(loaded.backend(), built.backend());
+        assert_eq!(loaded_count, 3);
+        assert!(system_under_test.tree.is_none());
+
+        scene.replace_sdf_class(&NamedSdf::new(SdfSphere::new(2.0), class_name));
+        let (_, count_after_mutation) = system_under_test.serialize(&scene);
+        assert_eq!(count_after_mutation, 3);
+        assert!(system_under_test.tree.is_some());
+    }
+
+    #[test]
+    fn test_quantized_layout() {
+        let class_name = UniqueSdfClassName::new("sphere".to_string());
+        let scene = make_scene_with_spheres(&class_name);
+        let mut system_under_test = SceneBvh::new(0.0);
+        let (full, full_count) = system_under_test.serialize(&scene);
+
+        system_under_test.set_quantized(true);
+        let (quantized, quantized_count) = system_under_test.serialize(&scene);
+
+        assert_eq!(quantized_count, full_count);
+        assert_eq!(quantized.total_slots_count(), full.total_slots_count() + 1);
+        assert_eq!(quantized.backend().len(), (full_count as usize + 1) * BvhNode::SERIALIZED_QUANTIZED_QUARTET_COUNT * 16);
+    }
+
+    #[test]
+    fn test_empty_scene() {
+        let scene = VisualObjects::new(None, None, None, None, None);
+        let mut system_under_test = SceneBvh::new(0.0);
+
+        let (_, count) = system_under_test.serialize(&scene);
+
+        assert_eq!(count, 0);
+        assert!(system_under_test.tree.is_none());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::gpu::bind_group_builder::BindGroupBuilder;
+use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::gpu::pipeline_code::PipelineCode;
+use crate::gpu::pipelines_factory::PipelinesFactory;
+use crate::gpu::rasterization_pipeline::RasterizationPipeline;
+use crate::gpu::resources::Resources;
+use crate::gpu::viewport::Viewport;
+use crate::utils::object_uid::ObjectUid;
+use bytemuck::{Pod, Zeroable};
+use std::rc::Rc;
+use wgpu::{BufferUsages, StoreOp};
+
+pub(crate) const SELECTION_OUTLINE_GPU_CODE: &str = include_str!("../../shader/_selection_outline.wgsl");
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SelectionOutlineUniforms {
+    color: [f32; 4],
+    frame_buffer_size: [u32; 2],
+    highlighted_count: u32,
+    thickness: u32,
+}
+
+// edge detection on the object id buffer: outlines the highlighted objects over the final image
+pub(super) struct SelectionOutlinePass {
+    pipeline: RasterizationPipeline,
+    uniforms: Rc<wgpu::Buffer>,
+    highlighted: Rc<wgpu::Buffer>,
+    uploaded: Vec<u32>,
+}
+
+impl SelectionOutlinePass {
+    const BIND_GROUP_INDEX: u32 = 0;
+    const BIND_GROUP_UNIFORMS_SLOT: u32 = 0;
+    const BIND_GROUP_OBJECT_ID_SLOT: u32 = 1;
+    const BIND_GROUP_HIGHLIGHTED_SLOT: u32 = 2;
+
+    // in the frame buffer pixels
+    const THICKNESS: u32 = 2;
+
+    #[must_use]
+    pub(super) fn new(resources: &Resources, pipelines_factory: &mut PipelinesFactory) -> Self {
+        let module = resources.create_shader_module("selection outline shader", SELECTION_OUTLINE_GPU_CODE);
+        let code = PipelineCode::new(module, seahash::hash(SELECTION_OUTLINE_GPU_CODE.as_bytes()), "selection_outline_code".to_string());
+        Self {
+            pipeline: RasterizationPipeline::new(pipelines_factory.create_selection_outline_pipeline(&code)),
+            uniforms: resources.create_uniform_buffer("selection outline uniforms", bytemuck::bytes_of(&SelectionOutlineUniforms::zeroed())),
+            // an empty storage buffer can not be bound
+            highlighted: resources.create_storage_buffer_write_only("highlighted objects", bytemuck::cast_slice(&[0_u32])),
+            uploaded: Vec::new(),
+        }
+    }
+
+    // the uniforms are written for every frame, the highlighted uids when they change
+    pub(super) fn update(&mut self, highlighted: &[ObjectUid], color: [f32; 4], frame_buffer_size: FrameBufferSize, queue: &wgpu::Queue, resources: &Resources) {
+        let uids: Vec<u32> = highlighted.iter().map(|uid| uid.0).collect();
+        if uids != self.uploaded {
+            let data: &[u8] = bytemuck::cast_slice(&uids);
+            if self.highlighted.size() >= data.len() as wgpu::BufferAddress {
+                queue.write_buffer(&self.highlighted, 0, data);
+            } else {
+                self.highlighted = resources.create_buffer("highlighted objects", BufferUsages::STORAGE | BufferUsages::COPY_DST, data);
+            }
+            self.uploaded = uids;
+        }
+
+        let uniforms = SelectionOutlineUniforms {
+            color,
+            frame_buffer_size: [frame_buffer_size.width(), frame_buffer_size.height()],
+            highlighted_count: self.uploaded.len() as u32,
+            thickness: Self::THICKNESS,
+        };
+        queue.write_buffer(&self.uniforms, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    // the object id buffer follows the frame buffer size, so the bind group is made for each draw
+    pub(super) fn draw(&mut self, encoder: &mut wgpu::CommandEncoder, device: &wgpu::Device, view: &wgpu::TextureView, object_ids: Rc<wgpu::Buffer>, viewport: Option<Viewport>) {
+        if self.uploaded.is_empty() {
+            return;
+        }
+
+        let layout = self.pipeline.bind_group_layout(Self::BIND_GROUP_INDEX);
+        let mut bind_group_builder = BindGroupBuilder::new(Self::BIND_GROUP_INDEX, Some("selection outline pipeline group"), layout);
+        bind_group_builder
+            .set_storage_entry(Self::BIND_GROUP_UNIFORMS_SLOT, self.uniforms.clone())
+            .set_storage_entry(Self::BIND_GROUP_OBJECT_ID_SLOT, object_ids)
+            .set_storage_entry(Self::BIND_GROUP_HIGHLIGHTED_SLOT, self.highlighted.clone())
+        ;
+        self.pipeline.commit_bind_group(device, bind_group_builder);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("selection outline pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        if let Some(viewport) = viewport {
+            viewport.set_into_pass(&mut pass);
+        }
+        self.pipeline.set_into_pass(&mut pass);
+        pass.draw(0..6, 0..1);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::geometry::transform::Affine;
+use crate::scene::camera::Camera;
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use crate::serialization::serialize_matrix::serialize_matrix_4x4;
+use cgmath::SquareMatrix;
+use std::cmp::min;
+
+// the previous frame's camera: after the camera moves, the accumulated samples are
+// re-projected into the new view instead of being thrown away
+pub(crate) struct TemporalReprojection {
+    previous_world_to_camera_space: Affine,
+    previous_view_ray_origin: Affine,
+    previous_fov_factor: f64,
+    previous_frame_number: u32,
+
+    history_frames: u32,
+    history_scale: f32,
+}
+
+impl TemporalReprojection {
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 1 + 2 * 4;
+
+    // bounds the weight of the history: view dependent shading lags behind the camera otherwise
+    pub(crate) const MAX_HISTORY_FRAMES: u32 = 16;
+
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self {
+            previous_world_to_camera_space: Affine::identity(),
+            previous_view_ray_origin: Affine::identity(),
+            previous_fov_factor: 1.0,
+            previous_frame_number: 0,
+            history_frames: 0,
+            history_scale: 0.0,
+        }
+    }
+
+    pub(crate) fn remember_frame(&mut self, camera: &Camera, frame_number: u32) {
+        self.previous_world_to_camera_space = *camera.world_to_camera_space();
+        self.previous_view_ray_origin = *camera.view_ray_origin();
+        self.previous_fov_factor = camera.fov_factor();
+        self.previous_frame_number = frame_number;
+    }
+
+    // the accumulated image does not match the remembered camera anymore
+    pub(crate) fn forget_frame(&mut self) {
+        self.previous_frame_number = 0;
+    }
+
+    // returns the count of frames the history is worth; zero - nothing to re-project
+    pub(crate) fn begin_frame(&mut self, reproject: bool) -> u32 {
+        self.history_frames = if reproject { min(self.previous_frame_number, Self::MAX_HISTORY_FRAMES) } else { 0 };
+        self.history_scale = if self.history_frames > 0 { self.history_frames as f32 / self.previous_frame_number as f32 } else { 0.0 };
+        self.history_frames
+    }
+
+    pub(crate) fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= TemporalReprojection::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+
+        container.write_quartet_f32(self.history_frames as f32, self.history_scale, self.previous_fov_factor as f32, 0.0);
+        serialize_matrix_4x4(container, &self.previous_world_to_camera_space);
+        serialize_matrix_4x4(container, &self.previous_view_ray_origin);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::alias::Point;
+    use bytemuck::cast_slice;
+    use cgmath::EuclideanSpace;
+
+    #[test]
+    fn test_history_is_bounded() {
+        let camera = Camera::new_perspective_camera(1.0, Point::origin());
+        let mut system_under_test = TemporalReprojection::new();
+        system_under_test.remember_frame(&camera, 4 * TemporalReprojection::MAX_HISTORY_FRAMES);
+
+        let history_frames = system_under_test.begin_frame(true);
+
+        assert_eq!(history_frames, TemporalReprojection::MAX_HISTORY_FRAMES);
+        assert_eq!(system_under_test.history_scale, 0.25);
+    }
+
+    #[test]
+    fn test_forgotten_frame_is_not_reprojected() {
+        let camera = Camera::new_perspective_camera(1.0, Point::origin());
+        let mut system_under_test = TemporalReprojection::new();
+        system_under_test.remember_frame(&camera, 5);
+
+        system_under_test.forget_frame();
+
+        assert_eq!(system_under_test.begin_frame(true), 0);
+    }
+
+    #[test]
+    fn test_serialize_into() {
+        let camera = Camera::new_perspective_camera(1.0, Point::origin());
+        let mut system_under_test = TemporalReprojection::new();
+        system_under_test.remember_frame(&camera, 3);
+        let _ = system_under_test.begin_frame(true);
+        let mut container = GpuReadySerializationBuffer::new(1, TemporalReprojection::SERIALIZED_QUARTET_COUNT);
+
+        system_under_test.serialize_into(&mut container);
+
+        assert!(container.object_fully_written());
+        let floats: &[f32] = cast_slice(container.backend());
+        assert_eq!(&floats[0..4], &[3.0, 1.0, camera.fov_factor() as f32, 0.0]);
+        assert_eq!(floats[4 + 14], -1.0);
+    }
+}
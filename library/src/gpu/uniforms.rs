@@ -1,5 +1,19 @@
+use crate::background::background_uid::BackgroundUid;
+use crate::geometry::aabb::Aabb;
 use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::gpu::temporal_reprojection::TemporalReprojection;
+use crate::scene::auto_exposure::AutoExposure;
+use crate::scene::bloom::Bloom;
 use crate::scene::camera::Camera;
+use crate::scene::color_grading::ColorGrading;
+use crate::scene::display_mapping::DisplayMapping;
+use crate::scene::output_dithering::OutputDithering;
+use crate::scene::random_generator::RandomGenerator;
+use crate::scene::layer_mask::LayerMask;
+use crate::scene::magnifier::Magnifier;
+use crate::scene::probe_grid::ProbeGrid;
+use crate::scene::render_strategy::RenderStrategyConfig;
+use crate::scene::sdf_level_of_detail::SdfLevelOfDetail;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
 use cgmath::{Vector2, Vector3};
 use std::time::Duration;
@@ -15,6 +29,33 @@ pub(crate) struct Uniforms {
     pixel_side_subdivision: u32,
 
     global_time_seconds: f32,
+
+    probe_grid: Option<ProbeGrid>,
+    background: Option<BackgroundUid>,
+    color_grading: ColorGrading,
+    auto_exposure: Option<AutoExposure>,
+    temporal_reprojection: TemporalReprojection,
+    clear_color: [f32; 4],
+    geometry_quantized: bool,
+    magnifier: Option<Magnifier>,
+    sdf_level_of_detail: Option<SdfLevelOfDetail>,
+    emissive_parallelograms_count: u32,
+    next_event_estimation: bool,
+    camera_layer_mask: LayerMask,
+    max_ray_bounces: u32,
+    upscale_sharpness: f32,
+    shadow_map_resolution: u32,
+    output_dithering: OutputDithering,
+    display_mapping: DisplayMapping,
+    extended_range_output: bool,
+    bloom: Option<Bloom>,
+    lighting_aovs: bool,
+    random_generator: RandomGenerator,
+    random_seeds_size: Option<(u32, u32)>,
+    scene_bounds: Option<Aabb>,
+    // tells the denoised image of an earlier accumulation apart
+    #[cfg(feature = "denoiser")]
+    accumulation_restarts: u64,
 }
 
 impl Uniforms {
@@ -22,6 +63,12 @@ impl Uniforms {
     const WORK_GROUP_SIZE_X: u32 = 8;
     const WORK_GROUP_SIZE_Y: u32 = 8;
     const WORK_GROUP_SIZE: Vector2<u32> = Vector2::new(Self::WORK_GROUP_SIZE_X, Self::WORK_GROUP_SIZE_Y);
+    const IRRADIANCE_PROBES_WORK_GROUP_SIZE: u32 = 64;
+
+    pub(crate) const DEFAULT_CLEAR_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
+    pub(crate) const DEFAULT_UPSCALE_SHARPNESS: f32 = 0.5;
+    // the 'MAX_FLOAT' of the shader
+    const UNLIMITED_RAY_DISTANCE: f64 = 999_999_999.999;
 
     #[must_use]
     pub(crate) fn new(frame_buffer_size: FrameBufferSize, camera: Camera, pixel_side_subdivision: u32, current_time: Duration) -> Self {
@@ -33,11 +80,56 @@ impl Uniforms {
             bvh_length: 0,
             pixel_side_subdivision,
             global_time_seconds: current_time.as_secs_f32(),
+            probe_grid: None,
+            background: None,
+            color_grading: ColorGrading::default(),
+            auto_exposure: None,
+            temporal_reprojection: TemporalReprojection::new(),
+            clear_color: Self::DEFAULT_CLEAR_COLOR,
+            geometry_quantized: false,
+            magnifier: None,
+            sdf_level_of_detail: None,
+            emissive_parallelograms_count: 0,
+            next_event_estimation: false,
+            camera_layer_mask: LayerMask::ALL,
+            max_ray_bounces: RenderStrategyConfig::DETERMINISTIC_MAX_BOUNCES,
+            upscale_sharpness: Self::DEFAULT_UPSCALE_SHARPNESS,
+            shadow_map_resolution: 0,
+            output_dithering: OutputDithering::default(),
+            display_mapping: DisplayMapping::default(),
+            extended_range_output: false,
+            bloom: None,
+            lighting_aovs: false,
+            random_generator: RandomGenerator::default(),
+            random_seeds_size: None,
+            scene_bounds: None,
+            #[cfg(feature = "denoiser")]
+            accumulation_restarts: 0,
         }
     }
     
     pub(super) fn reset_frame_accumulation(&mut self, value: u32) {
         self.frame_number = value;
+        self.temporal_reprojection.forget_frame();
+        #[cfg(feature = "denoiser")] {
+            self.accumulation_restarts += 1;
+        }
+    }
+
+    #[must_use]
+    #[cfg(feature = "denoiser")]
+    pub(super) fn accumulation_restarts(&self) -> u64 {
+        self.accumulation_restarts
+    }
+
+    // to be called once the uniforms of a frame are uploaded
+    pub(super) fn remember_frame_for_reprojection(&mut self) {
+        self.temporal_reprojection.remember_frame(&self.camera, self.frame_number);
+    }
+
+    #[must_use]
+    pub(super) fn begin_temporal_reprojection(&mut self, reproject: bool) -> u32 {
+        self.temporal_reprojection.begin_frame(reproject)
     }
 
     pub(super) fn set_frame_size(&mut self, new_size: PhysicalSize<u32>) {
@@ -62,11 +154,33 @@ impl Uniforms {
         self.pixel_side_subdivision = level;
     }
 
+    #[must_use]
+    pub(super) fn pixel_side_subdivision(&self) -> u32 {
+        self.pixel_side_subdivision
+    }
+
     #[must_use]
     pub(crate) fn work_groups_count(&self) -> Vector3<u32> {
         self.frame_buffer_size.work_groups_count(Self::WORK_GROUP_SIZE)
     }
 
+    #[must_use]
+    pub(crate) fn work_groups_count_half_resolution(&self) -> Vector3<u32> {
+        self.frame_buffer_size.half_resolution().work_groups_count(Self::WORK_GROUP_SIZE)
+    }
+
+    // the grid of the size other than the frame buffer one, processed by the kernels of the same work group size
+    #[must_use]
+    pub(crate) fn work_groups_count_of(size: FrameBufferSize) -> Vector3<u32> {
+        size.work_groups_count(Self::WORK_GROUP_SIZE)
+    }
+
+    #[must_use]
+    pub(crate) fn work_groups_count_irradiance_probes(&self) -> Vector3<u32> {
+        let probes_count = self.probe_grid.map_or(0, |grid| grid.probes_count());
+        Vector3::new(probes_count.div_ceil(Self::IRRADIANCE_PROBES_WORK_GROUP_SIZE), 1, 1)
+    }
+
     pub(crate) fn set_parallelograms_count(&mut self, parallelograms_count: u32) {
         self.parallelograms_count = parallelograms_count;
     }
@@ -75,23 +189,219 @@ impl Uniforms {
         self.bvh_length = bvh_length;
     }
 
+    #[must_use]
+    pub(crate) fn bvh_length(&self) -> u32 {
+        self.bvh_length
+    }
+
+    pub(super) fn set_probe_grid(&mut self, probe_grid: Option<ProbeGrid>) {
+        self.probe_grid = probe_grid;
+    }
+
+    #[must_use]
+    pub(super) fn probe_grid(&self) -> Option<ProbeGrid> {
+        self.probe_grid
+    }
+
+    pub(super) fn set_background(&mut self, background: Option<BackgroundUid>) {
+        self.background = background;
+    }
+
+    #[must_use]
+    pub(super) fn background(&self) -> Option<BackgroundUid> {
+        self.background
+    }
+
+    pub(super) fn set_color_grading(&mut self, color_grading: ColorGrading) {
+        self.color_grading = color_grading;
+    }
+
+    pub(super) fn set_auto_exposure(&mut self, auto_exposure: Option<AutoExposure>) {
+        self.auto_exposure = auto_exposure;
+    }
+
+    #[must_use]
+    pub(super) fn auto_exposure(&self) -> Option<AutoExposure> {
+        self.auto_exposure
+    }
+
+    // rgb - radiance of the rays missing the scene without a background; alpha - of the pixels without objects
+    pub(super) fn set_clear_color(&mut self, clear_color: [f32; 4]) {
+        self.clear_color = clear_color;
+    }
+
+    #[must_use]
+    pub(super) fn clear_color(&self) -> [f32; 4] {
+        self.clear_color
+    }
+
+    // selects the layout the shader reads the triangles and the bvh nodes with
+    pub(super) fn set_geometry_quantized(&mut self, quantized: bool) {
+        self.geometry_quantized = quantized;
+    }
+
+    #[must_use]
+    pub(super) fn geometry_quantized(&self) -> bool {
+        self.geometry_quantized
+    }
+
+    pub(super) fn set_magnifier(&mut self, magnifier: Option<Magnifier>) {
+        self.magnifier = magnifier;
+    }
+
+    #[must_use]
+    pub(super) fn magnifier(&self) -> Option<Magnifier> {
+        self.magnifier
+    }
+
+    pub(super) fn set_sdf_level_of_detail(&mut self, sdf_level_of_detail: Option<SdfLevelOfDetail>) {
+        self.sdf_level_of_detail = sdf_level_of_detail;
+    }
+
+    #[must_use]
+    pub(super) fn sdf_level_of_detail(&self) -> Option<SdfLevelOfDetail> {
+        self.sdf_level_of_detail
+    }
+
+    pub(super) fn set_emissive_parallelograms_count(&mut self, count: u32) {
+        self.emissive_parallelograms_count = count;
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    pub(super) fn set_next_event_estimation(&mut self, enabled: bool) {
+        self.next_event_estimation = enabled;
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub(super) fn next_event_estimation(&self) -> bool {
+        self.next_event_estimation
+    }
+
+    pub(super) fn set_camera_layer_mask(&mut self, mask: LayerMask) {
+        self.camera_layer_mask = mask;
+    }
+
+    #[must_use]
+    pub(super) fn camera_layer_mask(&self) -> LayerMask {
+        self.camera_layer_mask
+    }
+
+    pub(super) fn set_max_ray_bounces(&mut self, bounces: u32) {
+        self.max_ray_bounces = bounces;
+    }
+
+    #[must_use]
+    pub(super) fn max_ray_bounces(&self) -> u32 {
+        self.max_ray_bounces
+    }
+
+    pub(super) fn set_upscale_sharpness(&mut self, sharpness: f32) {
+        self.upscale_sharpness = sharpness;
+    }
+
+    // zero: the deterministic shading traces the key light's shadow rays
+    pub(super) fn set_shadow_map_resolution(&mut self, resolution: u32) {
+        self.shadow_map_resolution = resolution;
+    }
+
+    #[must_use]
+    pub(super) fn shadow_map_resolution(&self) -> u32 {
+        self.shadow_map_resolution
+    }
+
+    pub(super) fn set_output_dithering(&mut self, dithering: OutputDithering) {
+        self.output_dithering = dithering;
+    }
+
+    #[must_use]
+    pub(super) fn output_dithering(&self) -> OutputDithering {
+        self.output_dithering
+    }
+
+    // the Monte Carlo pass sums the lighting of its samples into the frame buffer layer of theirs
+    pub(super) fn set_lighting_aovs(&mut self, enabled: bool) {
+        self.lighting_aovs = enabled;
+    }
+
+    #[must_use]
+    pub(super) fn lighting_aovs(&self) -> bool {
+        self.lighting_aovs
+    }
+
+    pub(super) fn set_random_generator(&mut self, generator: RandomGenerator) {
+        self.random_generator = generator;
+    }
+
+    #[must_use]
+    pub(super) fn random_generator(&self) -> RandomGenerator {
+        self.random_generator
+    }
+
+    // the width and the height of the seed texture tile, none - the random numbers are not rotated
+    pub(super) fn set_random_seeds_size(&mut self, size: Option<(u32, u32)>) {
+        self.random_seeds_size = size;
+    }
+
+    // the far clip of the camera falls back to the distance the bounds are in reach of
+    pub(super) fn set_scene_bounds(&mut self, bounds: Option<Aabb>) {
+        self.scene_bounds = bounds;
+    }
+
+    #[must_use]
+    pub(crate) fn work_groups_count_shadow_map(&self) -> Vector3<u32> {
+        let side = self.shadow_map_resolution.max(1);
+        Self::work_groups_count_of(FrameBufferSize::new(side, side))
+    }
+
+    pub(super) fn set_display_mapping(&mut self, display_mapping: DisplayMapping) {
+        self.display_mapping = display_mapping;
+    }
+
+    #[must_use]
+    pub(super) fn display_mapping(&self) -> DisplayMapping {
+        self.display_mapping
+    }
+
+    pub(super) fn set_extended_range_output(&mut self, extended_range_output: bool) {
+        self.extended_range_output = extended_range_output;
+    }
+
+    #[must_use]
+    pub(super) fn extended_range_output(&self) -> bool {
+        self.extended_range_output
+    }
+
+    pub(super) fn set_bloom(&mut self, bloom: Option<Bloom>) {
+        self.bloom = bloom;
+    }
+
+    #[must_use]
+    pub(super) fn bloom(&self) -> Option<Bloom> {
+        self.bloom
+    }
+
     #[must_use]
     pub(super) fn frame_buffer_size(&self) -> FrameBufferSize {
         self.frame_buffer_size
     }
 
-    #[cfg(feature = "denoiser")]
     #[must_use]
     pub(super) fn frame_number(&self) -> u32 {
         self.frame_number
     }
 
+    #[must_use]
+    pub(super) fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
     #[must_use]
     pub(super) fn mutable_camera(&mut self) -> &mut Camera {
         &mut self.camera
     }
 
-    const SERIALIZED_QUARTET_COUNT: usize = 4 + Camera::SERIALIZED_QUARTET_COUNT;
+    const SERIALIZED_QUARTET_COUNT: usize = 4 + Camera::SERIALIZED_QUARTET_COUNT + ProbeGrid::SERIALIZED_QUARTET_COUNT + ColorGrading::SERIALIZED_QUARTET_COUNT + AutoExposure::SERIALIZED_QUARTET_COUNT + TemporalReprojection::SERIALIZED_QUARTET_COUNT + 4 + DisplayMapping::SERIALIZED_QUARTET_COUNT + Bloom::SERIALIZED_QUARTET_COUNT + 2;
 
     #[must_use]
     pub(crate) fn serialize(&self) -> GpuReadySerializationBuffer {
@@ -104,12 +414,12 @@ impl Uniforms {
             writer.write_float_32(self.frame_buffer_size.aspect());
         });
         
-        result.write_quartet_f32(
-           1.0 / self.frame_buffer_size.width() as f32,
-           1.0 / self.frame_buffer_size.height() as f32,
-           self.frame_number as f32,
-           0.0,
-        );
+        result.write_quartet(|writer| {
+            writer.write_float_32(1.0 / self.frame_buffer_size.width() as f32);
+            writer.write_float_32(1.0 / self.frame_buffer_size.height() as f32);
+            writer.write_float_32(self.frame_number as f32);
+            writer.write_unsigned(self.background.map_or(0, |uid| uid.0 as u32));
+        });
         
         self.camera.serialize_into(&mut result);
 
@@ -125,6 +435,69 @@ impl Uniforms {
             writer.write_unsigned(workgroup_count.x * Self::WORK_GROUP_SIZE.x);
             writer.write_unsigned(workgroup_count.y * Self::WORK_GROUP_SIZE.y);
             writer.write_unsigned(workgroup_count.z);
+            writer.write_float_64(self.camera.fov_factor());
+        });
+
+        match &self.probe_grid {
+            Some(probe_grid) => probe_grid.serialize_into(&mut result),
+            None => ProbeGrid::serialize_disabled_into(&mut result),
+        }
+
+        self.color_grading.serialize_into(&mut result);
+
+        match &self.auto_exposure {
+            Some(auto_exposure) => auto_exposure.serialize_into(&mut result),
+            None => AutoExposure::serialize_disabled_into(&mut result),
+        }
+
+        self.temporal_reprojection.serialize_into(&mut result);
+
+        result.write_quartet_f32(self.clear_color[0], self.clear_color[1], self.clear_color[2], self.clear_color[3]);
+
+        // zero zoom hides the magnifier inset
+        let (magnifier_x, magnifier_y, magnifier_zoom) = self.magnifier.map_or((0, 0, 0), |magnifier| (magnifier.center_x(), magnifier.center_y(), magnifier.zoom()));
+        result.write_quartet(|writer| {
+            writer.write_unsigned(u32::from(self.geometry_quantized));
+            writer.write_unsigned(magnifier_x);
+            writer.write_unsigned(magnifier_y);
+            writer.write_unsigned(magnifier_zoom);
+        });
+
+        // zero threshold: all the sdf are sphere traced
+        let sdf_lod_threshold = self.sdf_level_of_detail.map_or(0.0, |level_of_detail| level_of_detail.threshold_pixels());
+        result.write_quartet(|writer| {
+            writer.write_float_32(sdf_lod_threshold);
+            writer.write_unsigned(self.emissive_parallelograms_count);
+            writer.write_unsigned(u32::from(self.next_event_estimation));
+            writer.write_unsigned(self.camera_layer_mask.0);
+        });
+
+        result.write_quartet(|writer| {
+            writer.write_unsigned(self.max_ray_bounces);
+            writer.write_float_32(self.upscale_sharpness);
+            writer.write_unsigned(self.shadow_map_resolution);
+            writer.write_unsigned(self.output_dithering.gpu_id());
+        });
+
+        self.display_mapping.serialize_into(self.extended_range_output, &mut result);
+
+        match &self.bloom {
+            Some(bloom) => bloom.serialize_into(&mut result),
+            None => Bloom::serialize_disabled_into(&mut result),
+        }
+
+        result.write_quartet(|writer| {
+            writer.write_unsigned(u32::from(self.lighting_aovs));
+            writer.write_unsigned(self.random_generator.gpu_id());
+            let (seeds_width, seeds_height) = self.random_seeds_size.unwrap_or((0, 0));
+            writer.write_unsigned(seeds_width);
+            writer.write_unsigned(seeds_height);
+        });
+
+        let max_ray_distance = self.camera.max_ray_distance(self.scene_bounds.as_ref()).unwrap_or(Self::UNLIMITED_RAY_DISTANCE);
+        result.write_quartet(|writer| {
+            writer.write_float_64(self.camera.near_clip());
+            writer.write_float_64(max_ray_distance);
         });
         
         debug_assert!(result.object_fully_written());
@@ -135,7 +508,9 @@ impl Uniforms {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geometry::aabb::Aabb;
     use crate::geometry::alias::Point;
+    use crate::scene::display_mapping::ToneMapping;
     use cgmath::EuclideanSpace;
     use std::time::Instant;
     use test_context::{test_context, TestContext};
@@ -156,6 +531,7 @@ mod tests {
     const SLOT_FRAME_INVERTED_WIDTH: usize = 4;
     const SLOT_FRAME_INVERTED_HEIGHT: usize = 5;
     const SLOT_FRAME_NUMBER: usize = 6;
+    const SLOT_BACKGROUND_UID: usize = 7;
 
     const SLOT_PARALLELOGRAMS_COUNT: usize = 40;
     const SLOT_BVH_LENGTH: usize = 41;
@@ -166,6 +542,50 @@ mod tests {
     const SLOT_THREAD_GRID_SIZE_Y: usize = 45;
     const SLOT_THREAD_GRID_SIZE_Z: usize = 46;
 
+    const SLOT_PROBE_GRID_PROBES_COUNT: usize = 51;
+
+    const SLOT_GRADING_WHITE_BALANCE_R: usize = 60;
+    const SLOT_GRADING_EXPOSURE_SCALE: usize = 63;
+    const SLOT_GRADING_CONTRAST: usize = 64;
+    const SLOT_GRADING_SATURATION: usize = 65;
+
+    const SLOT_AUTO_EXPOSURE_ENABLED: usize = 68;
+    const SLOT_AUTO_EXPOSURE_MAX_SCALE: usize = 70;
+
+    const SLOT_REPROJECTION_HISTORY_FRAMES: usize = 72;
+
+    const SLOT_CLEAR_COLOR_R: usize = 108;
+    const SLOT_CLEAR_COLOR_A: usize = 111;
+
+    const SLOT_MAGNIFIER_CENTER_X: usize = 113;
+    const SLOT_MAGNIFIER_ZOOM: usize = 115;
+
+    const SLOT_SDF_LOD_THRESHOLD: usize = 116;
+    #[cfg(feature = "monte_carlo")]
+    const SLOT_EMISSIVE_PARALLELOGRAMS_COUNT: usize = 117;
+    #[cfg(feature = "monte_carlo")]
+    const SLOT_NEXT_EVENT_ESTIMATION: usize = 118;
+    const SLOT_CAMERA_LAYER_MASK: usize = 119;
+
+    const SLOT_MAX_RAY_BOUNCES: usize = 120;
+    const SLOT_UPSCALE_SHARPNESS: usize = 121;
+    const SLOT_SHADOW_MAP_RESOLUTION: usize = 122;
+    const SLOT_OUTPUT_DITHERING: usize = 123;
+
+    const SLOT_DISPLAY_TONE_MAPPING: usize = 124;
+    const SLOT_DISPLAY_EXPOSURE_SCALE: usize = 125;
+    const SLOT_DISPLAY_INVERSE_GAMMA: usize = 126;
+    const SLOT_DISPLAY_EXTENDED_RANGE: usize = 127;
+
+    const SLOT_BLOOM_INTENSITY: usize = 128;
+
+    const SLOT_LIGHTING_AOVS: usize = 132;
+    const SLOT_RANDOM_GENERATOR: usize = 133;
+    const SLOT_RANDOM_SEEDS_WIDTH: usize = 134;
+
+    const SLOT_CAMERA_NEAR_CLIP: usize = 136;
+    const SLOT_MAX_RAY_DISTANCE: usize = 137;
+
     struct Context {
         system_under_test: Uniforms
     }
@@ -184,6 +604,31 @@ mod tests {
                 bvh_length: DEFAULT_BVH_LENGTH,
                 pixel_side_subdivision: DEFAULT_PIXEL_SIDE_SUBDIVISION,
                 global_time_seconds: DEFAULT_GLOBAL_TIME_SECONDS,
+                probe_grid: None,
+                background: None,
+                color_grading: ColorGrading::default(),
+                auto_exposure: None,
+                temporal_reprojection: TemporalReprojection::new(),
+                clear_color: Uniforms::DEFAULT_CLEAR_COLOR,
+                geometry_quantized: false,
+                magnifier: None,
+                sdf_level_of_detail: None,
+                emissive_parallelograms_count: 0,
+                next_event_estimation: false,
+                camera_layer_mask: LayerMask::ALL,
+                max_ray_bounces: RenderStrategyConfig::DETERMINISTIC_MAX_BOUNCES,
+                upscale_sharpness: Uniforms::DEFAULT_UPSCALE_SHARPNESS,
+                shadow_map_resolution: 0,
+            output_dithering: OutputDithering::default(),
+                display_mapping: DisplayMapping::default(),
+            extended_range_output: false,
+                bloom: None,
+                lighting_aovs: false,
+                random_generator: RandomGenerator::default(),
+                random_seeds_size: None,
+                scene_bounds: None,
+                #[cfg(feature = "denoiser")]
+                accumulation_restarts: 0,
             };
 
             Context {  system_under_test }
@@ -205,6 +650,44 @@ mod tests {
         assert_eq!(actual_state_floats[SLOT_FRAME_NUMBER], 0.0);
     }
 
+    #[test_context(Context)]
+    #[test]
+    #[cfg(feature = "denoiser")]
+    fn test_uniforms_reset_frame_accumulation_counts_restarts(fixture: &mut Context) {
+        let before = fixture.system_under_test.accumulation_restarts();
+
+        fixture.system_under_test.reset_frame_accumulation(0);
+
+        assert_eq!(fixture.system_under_test.accumulation_restarts(), before + 1);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_reset_frame_accumulation_forgets_reprojection_history(fixture: &mut Context) {
+        fixture.system_under_test.next_frame(1);
+        fixture.system_under_test.remember_frame_for_reprojection();
+        fixture.system_under_test.reset_frame_accumulation(0);
+
+        let history_frames = fixture.system_under_test.begin_temporal_reprojection(true);
+
+        assert_eq!(history_frames, 0);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_temporal_reprojection(fixture: &mut Context) {
+        fixture.system_under_test.next_frame(1);
+        fixture.system_under_test.next_frame(1);
+        fixture.system_under_test.remember_frame_for_reprojection();
+
+        let history_frames = fixture.system_under_test.begin_temporal_reprojection(true);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+        assert_eq!(history_frames, 2);
+        assert_eq!(actual_state_floats[SLOT_REPROJECTION_HISTORY_FRAMES], 2.0);
+    }
+
     #[test_context(Context)]
     #[test]
     fn test_uniforms_set_frame_size(fixture: &mut Context) {
@@ -246,6 +729,18 @@ mod tests {
         assert_eq!(fixture.system_under_test.frame_buffer_area(), expected_area);
     }
 
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_work_groups_count_half_resolution(fixture: &mut Context) {
+        let actual_count = fixture.system_under_test.work_groups_count_half_resolution();
+
+        let expected_count = Vector3::new(
+            (DEFAULT_FRAME_WIDTH / 2).div_ceil(Uniforms::WORK_GROUP_SIZE_X),
+            (DEFAULT_FRAME_HEIGHT / 2).div_ceil(Uniforms::WORK_GROUP_SIZE_Y),
+            1);
+        assert_eq!(actual_count, expected_count);
+    }
+
     #[test_context(Context)]
     #[test]
     fn test_uniforms_serialize(fixture: &mut Context) {
@@ -262,6 +757,7 @@ mod tests {
         assert_eq!(actual_state_floats[SLOT_FRAME_INVERTED_WIDTH], 1.0 / DEFAULT_FRAME_WIDTH as f32);
         assert_eq!(actual_state_floats[SLOT_FRAME_INVERTED_HEIGHT], 1.0 / DEFAULT_FRAME_HEIGHT as f32);
         assert_eq!(actual_state_floats[SLOT_FRAME_NUMBER], 0.0);
+        assert_eq!(actual_state_floats[SLOT_BACKGROUND_UID].to_bits(), 0);
 
         assert_eq!(actual_state_floats[SLOT_PARALLELOGRAMS_COUNT].to_bits(), DEFAULT_PARALLELOGRAMS_COUNT);
         assert_eq!(actual_state_floats[SLOT_BVH_LENGTH].to_bits(), DEFAULT_BVH_LENGTH);
@@ -271,5 +767,287 @@ mod tests {
         assert_eq!(actual_state_floats[SLOT_THREAD_GRID_SIZE_X].to_bits(), DEFAULT_FRAME_WIDTH.next_multiple_of(Uniforms::WORK_GROUP_SIZE_X));
         assert_eq!(actual_state_floats[SLOT_THREAD_GRID_SIZE_Y].to_bits(), DEFAULT_FRAME_HEIGHT.next_multiple_of(Uniforms::WORK_GROUP_SIZE_Y));
         assert_eq!(actual_state_floats[SLOT_THREAD_GRID_SIZE_Z].to_bits(), 1);
+
+        assert_eq!(actual_state_floats[SLOT_PROBE_GRID_PROBES_COUNT].to_bits(), 0);
+
+        assert_eq!(actual_state_floats[SLOT_AUTO_EXPOSURE_ENABLED].to_bits(), 0);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_probe_grid(fixture: &mut Context) {
+        let probe_grid = ProbeGrid::new(Aabb::from_points(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0)), Vector3::new(10, 10, 2));
+        fixture.system_under_test.set_probe_grid(Some(probe_grid));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_floats[SLOT_PROBE_GRID_PROBES_COUNT].to_bits(), 200);
+        assert_eq!(fixture.system_under_test.work_groups_count_irradiance_probes(), Vector3::new(4, 1, 1));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_background(fixture: &mut Context) {
+        fixture.system_under_test.set_background(Some(BackgroundUid(3)));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_floats[SLOT_BACKGROUND_UID].to_bits(), 3);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_set_pixel_side_subdivision(fixture: &mut Context) {
+        fixture.system_under_test.set_pixel_side_subdivision(3);
+        assert_eq!(fixture.system_under_test.pixel_side_subdivision(), 3);
+
+        fixture.system_under_test.set_pixel_side_subdivision(0);
+        assert_eq!(fixture.system_under_test.pixel_side_subdivision(), 1);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+        assert_eq!(actual_state_floats[SLOT_PIXEL_SIDE_SUBDIVISION].to_bits(), 1);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_color_grading(fixture: &mut Context) {
+        fixture.system_under_test.set_color_grading(ColorGrading::new().with_exposure(-1.0).with_contrast(1.25).with_saturation(0.5));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_floats[SLOT_GRADING_WHITE_BALANCE_R], 1.0);
+        assert_eq!(actual_state_floats[SLOT_GRADING_EXPOSURE_SCALE], 0.5);
+        assert_eq!(actual_state_floats[SLOT_GRADING_CONTRAST], 1.25);
+        assert_eq!(actual_state_floats[SLOT_GRADING_SATURATION], 0.5);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_auto_exposure(fixture: &mut Context) {
+        fixture.system_under_test.set_auto_exposure(Some(AutoExposure::new().with_exposure_range(-1.0, 2.0)));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_floats[SLOT_AUTO_EXPOSURE_ENABLED].to_bits(), 1);
+        assert_eq!(actual_state_floats[SLOT_AUTO_EXPOSURE_MAX_SCALE], 4.0);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_clear_color(fixture: &mut Context) {
+        fixture.system_under_test.set_clear_color([0.25, 0.5, 0.75, 0.0]);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(fixture.system_under_test.clear_color(), [0.25, 0.5, 0.75, 0.0]);
+        assert_eq!(&actual_state_floats[SLOT_CLEAR_COLOR_R..=SLOT_CLEAR_COLOR_A], &[0.25, 0.5, 0.75, 0.0]);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_magnifier(fixture: &mut Context) {
+        let hidden_state = fixture.system_under_test.serialize();
+        fixture.system_under_test.set_magnifier(Some(Magnifier::new(7, 9, 4)));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+        let hidden_state_words: &[u32] = bytemuck::cast_slice(hidden_state.backend());
+
+        assert_eq!(hidden_state_words[SLOT_MAGNIFIER_ZOOM], 0);
+        assert_eq!(&actual_state_words[SLOT_MAGNIFIER_CENTER_X..=SLOT_MAGNIFIER_ZOOM], &[7, 9, 4]);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_sdf_level_of_detail(fixture: &mut Context) {
+        let disabled_state = fixture.system_under_test.serialize();
+        fixture.system_under_test.set_sdf_level_of_detail(Some(SdfLevelOfDetail::new(1.5)));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+        let disabled_state_floats: &[f32] = bytemuck::cast_slice(disabled_state.backend());
+
+        assert_eq!(disabled_state_floats[SLOT_SDF_LOD_THRESHOLD], 0.0);
+        assert_eq!(actual_state_floats[SLOT_SDF_LOD_THRESHOLD], 1.5);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_camera_layer_mask(fixture: &mut Context) {
+        let default_state = fixture.system_under_test.serialize();
+        fixture.system_under_test.set_camera_layer_mask(LayerMask(0b101));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+        let default_state_words: &[u32] = bytemuck::cast_slice(default_state.backend());
+
+        assert_eq!(default_state_words[SLOT_CAMERA_LAYER_MASK], u32::MAX);
+        assert_eq!(actual_state_words[SLOT_CAMERA_LAYER_MASK], 0b101);
+        assert_eq!(fixture.system_under_test.camera_layer_mask(), LayerMask(0b101));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_max_ray_bounces(fixture: &mut Context) {
+        fixture.system_under_test.set_max_ray_bounces(13);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_words[SLOT_MAX_RAY_BOUNCES], 13);
+        assert_eq!(fixture.system_under_test.max_ray_bounces(), 13);
     }
-}
\ No newline at end of file
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_upscale_sharpness(fixture: &mut Context) {
+        let default_state = fixture.system_under_test.serialize();
+        fixture.system_under_test.set_upscale_sharpness(0.25);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let default_state_floats: &[f32] = bytemuck::cast_slice(default_state.backend());
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(default_state_floats[SLOT_UPSCALE_SHARPNESS], Uniforms::DEFAULT_UPSCALE_SHARPNESS);
+        assert_eq!(actual_state_floats[SLOT_UPSCALE_SHARPNESS], 0.25);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_shadow_map_resolution(fixture: &mut Context) {
+        fixture.system_under_test.set_shadow_map_resolution(20);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_words[SLOT_SHADOW_MAP_RESOLUTION], 20);
+        assert_eq!(fixture.system_under_test.shadow_map_resolution(), 20);
+        assert_eq!(fixture.system_under_test.work_groups_count_shadow_map(), Vector3::new(3, 3, 1));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_output_dithering(fixture: &mut Context) {
+        let default_state = fixture.system_under_test.serialize();
+        let default_state_words: &[u32] = bytemuck::cast_slice(default_state.backend());
+        assert_eq!(default_state_words[SLOT_OUTPUT_DITHERING], OutputDithering::GradientNoise.gpu_id());
+
+        fixture.system_under_test.set_output_dithering(OutputDithering::Ordered);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+        assert_eq!(actual_state_words[SLOT_OUTPUT_DITHERING], 2);
+        assert_eq!(fixture.system_under_test.output_dithering(), OutputDithering::Ordered);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_display_mapping(fixture: &mut Context) {
+        fixture.system_under_test.set_display_mapping(DisplayMapping::new().with_tone_mapping(ToneMapping::None).with_exposure(1.0).with_gamma(1.0));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_floats[SLOT_DISPLAY_TONE_MAPPING].to_bits(), 2);
+        assert_eq!(actual_state_floats[SLOT_DISPLAY_EXPOSURE_SCALE], 2.0);
+        assert_eq!(actual_state_floats[SLOT_DISPLAY_INVERSE_GAMMA], 1.0);
+        assert_eq!(actual_state_floats[SLOT_DISPLAY_EXTENDED_RANGE].to_bits(), 0);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_extended_range_output(fixture: &mut Context) {
+        fixture.system_under_test.set_extended_range_output(true);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+
+        assert_eq!(actual_state_floats[SLOT_DISPLAY_EXTENDED_RANGE].to_bits(), 1);
+        assert!(fixture.system_under_test.extended_range_output());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_bloom(fixture: &mut Context) {
+        let disabled_state = fixture.system_under_test.serialize();
+        let disabled_state_floats: &[f32] = bytemuck::cast_slice(disabled_state.backend());
+        assert_eq!(disabled_state_floats[SLOT_BLOOM_INTENSITY], 0.0);
+
+        fixture.system_under_test.set_bloom(Some(Bloom::new().with_intensity(0.25)));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_floats: &[f32] = bytemuck::cast_slice(actual_state.backend());
+        assert_eq!(actual_state_floats[SLOT_BLOOM_INTENSITY], 0.25);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_lighting_aovs(fixture: &mut Context) {
+        let disabled_state = fixture.system_under_test.serialize();
+        let disabled_state_words: &[u32] = bytemuck::cast_slice(disabled_state.backend());
+        assert_eq!(disabled_state_words[SLOT_LIGHTING_AOVS], 0);
+
+        fixture.system_under_test.set_lighting_aovs(true);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+        assert_eq!(actual_state_words[SLOT_LIGHTING_AOVS], 1);
+        assert!(fixture.system_under_test.lighting_aovs());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_random_numbers(fixture: &mut Context) {
+        fixture.system_under_test.set_random_generator(RandomGenerator::XxHash);
+        fixture.system_under_test.set_random_seeds_size(Some((64, 32)));
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+        assert_eq!(actual_state_words[SLOT_RANDOM_GENERATOR], RandomGenerator::XxHash.gpu_id());
+        assert_eq!(actual_state_words[SLOT_RANDOM_SEEDS_WIDTH..SLOT_RANDOM_SEEDS_WIDTH + 2], [64, 32]);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_ray_distance_limits(fixture: &mut Context) {
+        let unlimited_state = fixture.system_under_test.serialize();
+        let unlimited_state_floats: &[f32] = bytemuck::cast_slice(unlimited_state.backend());
+        assert_eq!(unlimited_state_floats[SLOT_CAMERA_NEAR_CLIP], 0.0);
+        assert_eq!(unlimited_state_floats[SLOT_MAX_RAY_DISTANCE], Uniforms::UNLIMITED_RAY_DISTANCE as f32);
+
+        fixture.system_under_test.set_scene_bounds(Some(Aabb::from_points(Point::new(0.0, 0.0, -11.0), Point::new(3.0, 4.0, -11.0))));
+        fixture.system_under_test.mutable_camera().set_near_clip(0.25);
+        let bounded_state = fixture.system_under_test.serialize();
+        let bounded_state_floats: &[f32] = bytemuck::cast_slice(bounded_state.backend());
+        assert_eq!(bounded_state_floats[SLOT_CAMERA_NEAR_CLIP], 0.25);
+        assert_eq!(bounded_state_floats[SLOT_MAX_RAY_DISTANCE], 27.0);
+
+        fixture.system_under_test.mutable_camera().set_far_clip(Some(5.0));
+        let clipped_state = fixture.system_under_test.serialize();
+        let clipped_state_floats: &[f32] = bytemuck::cast_slice(clipped_state.backend());
+        assert_eq!(clipped_state_floats[SLOT_MAX_RAY_DISTANCE], 5.0);
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test_context(Context)]
+    #[test]
+    fn test_uniforms_next_event_estimation(fixture: &mut Context) {
+        let disabled_state = fixture.system_under_test.serialize();
+        fixture.system_under_test.set_emissive_parallelograms_count(3);
+        fixture.system_under_test.set_next_event_estimation(true);
+
+        let actual_state = fixture.system_under_test.serialize();
+        let actual_state_words: &[u32] = bytemuck::cast_slice(actual_state.backend());
+        let disabled_state_words: &[u32] = bytemuck::cast_slice(disabled_state.backend());
+
+        assert_eq!(&disabled_state_words[SLOT_EMISSIVE_PARALLELOGRAMS_COUNT..=SLOT_NEXT_EVENT_ESTIMATION], &[0, 0]);
+        assert_eq!(&actual_state_words[SLOT_EMISSIVE_PARALLELOGRAMS_COUNT..=SLOT_NEXT_EVENT_ESTIMATION], &[3, 1]);
+        assert!(fixture.system_under_test.next_event_estimation());
+    }
+}
@@ -3,7 +3,9 @@ use bytemuck::Pod;
 use crate::utils::version::Version;
 use crate::gpu::resizable_buffer::{ResizableBuffer, ResizeStatus};
 use crate::gpu::resources::Resources;
+use crate::scene::frame_statistics::FrameStatistics;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use std::time::{Duration, Instant};
 
 pub(super) struct VersionedBuffer {
     content_version: Version,
@@ -13,6 +15,7 @@ pub(super) struct VersionedBuffer {
 pub(super) struct BufferUpdateStatus {
     resized: bool,
     updated: bool,
+    uploads: FrameStatistics,
 }
 
 impl BufferUpdateStatus {
@@ -26,21 +29,33 @@ impl BufferUpdateStatus {
         self.updated
     }
 
+    #[must_use]
+    pub(super) fn uploads(&self) -> FrameStatistics {
+        self.uploads
+    }
+
     #[must_use]
     pub(super) fn merge(&self, another: BufferUpdateStatus) -> Self {
         let resized = self.resized || another.resized;
         let updated = self.updated || another.updated;
-        Self { resized, updated }
+        let uploads = self.uploads.merge(another.uploads);
+        Self { resized, updated, uploads }
     }
 
     #[must_use]
     pub(super) fn new_updated(updated: bool) -> Self {
-        Self { resized: false, updated }
+        Self { resized: false, updated, uploads: FrameStatistics::default() }
     }
 
     #[must_use]
     pub(super) fn new(resized: bool, updated: bool) -> Self {
-        Self { resized, updated }
+        Self { resized, updated, uploads: FrameStatistics::default() }
+    }
+
+    #[must_use]
+    pub(super) fn with_upload(mut self, bytes: u64, serialization_time: Duration) -> Self {
+        self.uploads = self.uploads.with_upload(bytes, serialization_time);
+        self
     }
 }
 
@@ -58,6 +73,11 @@ impl VersionedBuffer {
         Self { content_version, backend: ResizableBuffer::from_slice(resources, label, slice) }
     }
 
+    #[must_use]
+    pub(super) fn content_version(&self) -> Version {
+        self.content_version
+    }
+
     #[must_use]
     pub(super) fn version_diverges(&self, another: Version) -> bool {
         self.content_version != another
@@ -69,25 +89,61 @@ impl VersionedBuffer {
         Generator: FnOnce() -> GpuReadySerializationBuffer,
     {
         if new_version == self.content_version {
-            return BufferUpdateStatus { resized: false, updated: false };
+            return BufferUpdateStatus::new(false, false);
         }
+        self.update_with_generator(new_version, resources, queue, generate_data)
+    }
 
+    // for the content changes not reflected by the version, like a new serialization layout
+    #[must_use]
+    pub(super) fn update_with_generator<Generator>(&mut self, new_version: Version, resources: &Resources, queue: &wgpu::Queue, generate_data: Generator) -> BufferUpdateStatus
+    where
+        Generator: FnOnce() -> GpuReadySerializationBuffer,
+    {
+        self.content_version = new_version;
+
+        let (content, serialization_time) = Self::timed(generate_data);
+        let bytes = content.backend().len() as u64;
+        let resized = self.backend.update_with_generator(resources, queue, || content);
+        BufferUpdateStatus::new(ResizeStatus::Resized == resized, true).with_upload(bytes, serialization_time)
+    }
+
+    // the objects from 'first_object' on are serialized anew, the others are kept as they are
+    #[must_use]
+    pub(super) fn update_objects_with_generator<Generator>(&mut self, new_version: Version, queue: &wgpu::Queue, first_object: usize, generate_data: Generator) -> BufferUpdateStatus
+    where
+        Generator: FnOnce() -> GpuReadySerializationBuffer,
+    {
         self.content_version = new_version;
 
-        let resized = self.backend.update_with_generator(resources, queue, generate_data);
-        BufferUpdateStatus { resized: ResizeStatus::Resized == resized, updated: true }
+        let (content, serialization_time) = Self::timed(generate_data);
+        let bytes = content.backend().len() as u64;
+        let offset_bytes = (first_object * content.bytes_per_object()) as u64;
+        self.backend.update_part_with_generator(queue, offset_bytes, || content);
+        BufferUpdateStatus::new(false, true).with_upload(bytes, serialization_time)
     }
 
     #[must_use]
     pub(super) fn try_update_with_slice<T: Pod>(&mut self, new_version: Version, resources: &Resources, queue: &wgpu::Queue, slice: &[T]) -> BufferUpdateStatus {
         if new_version == self.content_version {
-            return BufferUpdateStatus { resized: false, updated: false };
+            return BufferUpdateStatus::new(false, false);
         }
 
         self.content_version = new_version;
 
         let resized = self.backend.update_with_slice(resources, queue, slice);
-        BufferUpdateStatus { resized: ResizeStatus::Resized == resized, updated: true }
+        // the slice is serialized by the caller
+        BufferUpdateStatus::new(ResizeStatus::Resized == resized, true).with_upload(size_of_val(slice) as u64, Duration::ZERO)
+    }
+
+    #[must_use]
+    fn timed<Generator>(generate_data: Generator) -> (GpuReadySerializationBuffer, Duration)
+    where
+        Generator: FnOnce() -> GpuReadySerializationBuffer,
+    {
+        let started = Instant::now();
+        let content = generate_data();
+        (content, started.elapsed())
     }
 
     #[must_use]
@@ -106,7 +162,7 @@ mod tests {
     impl BufferUpdateStatus {
         #[must_use]
         pub(crate) fn new_resized(resized: bool) -> Self {
-            Self { resized, updated: true }
+            Self::new(resized, true)
         }
     }
     
@@ -193,4 +249,21 @@ mod tests {
         let expected_content = make_new_data();
         assert_eq!(fixture.system_under_test.backend().size(), expected_content.backend().len() as u64);
     }
-}
\ No newline at end of file
+
+    #[test_context(Fixture)]
+    #[test]
+    fn test_update_objects(fixture: &mut Fixture) {
+        let size_before = fixture.system_under_test.backend().size();
+
+        let status = fixture.system_under_test.update_objects_with_generator(
+            SYSTEM_UNDER_TEST_INITIAL_VERSION + 1,
+            fixture.context.queue(),
+            1,
+            || make_test_content(1));
+
+        assert!(status.updated());
+        assert!(!status.resized());
+        assert_eq!(fixture.system_under_test.backend().size(), size_before);
+        assert_eq!(fixture.system_under_test.content_version(), SYSTEM_UNDER_TEST_INITIAL_VERSION + 1);
+    }
+}
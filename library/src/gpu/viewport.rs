@@ -0,0 +1,177 @@
+use winit::dpi::PhysicalSize;
+
+// placement of the frame buffer inside the output surface: with a fixed aspect ratio
+// the rest of the surface is covered by letterbox (or pillarbox) bars; the frame buffer
+// is stretched over the viewport when their sizes differ
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Viewport {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    frame_width: u32,
+    frame_height: u32,
+}
+
+impl Viewport {
+    #[must_use]
+    pub(crate) fn fit(output_size: PhysicalSize<u32>, aspect_ratio: Option<f64>) -> Self {
+        let output_width = output_size.width.max(1);
+        let output_height = output_size.height.max(1);
+
+        let Some(aspect_ratio) = aspect_ratio else {
+            return Self::new(0, 0, output_width, output_height);
+        };
+
+        if (output_width as f64) > (output_height as f64) * aspect_ratio {
+            let width = ((output_height as f64 * aspect_ratio).round() as u32).clamp(1, output_width);
+            Self::new((output_width - width) / 2, 0, width, output_height)
+        } else {
+            let height = ((output_width as f64 / aspect_ratio).round() as u32).clamp(1, output_height);
+            Self::new(0, (output_height - height) / 2, output_width, height)
+        }
+    }
+
+    // the frame buffer keeps its size (and aspect ratio), scaled to fit the output
+    #[must_use]
+    pub(crate) fn fit_frame(output_size: PhysicalSize<u32>, frame_size: PhysicalSize<u32>) -> Self {
+        let frame_width = frame_size.width.max(1);
+        let frame_height = frame_size.height.max(1);
+        let fitted = Self::fit(output_size, Some(frame_width as f64 / frame_height as f64));
+        Self { frame_width, frame_height, ..fitted }
+    }
+
+    // the frame buffer of 'scale' pixels per viewport pixel along each axis
+    #[must_use]
+    pub(crate) fn with_frame_scale(self, scale: f64) -> Self {
+        let scaled = |extent: u32| ((extent as f64 * scale).round() as u32).max(1);
+        Self { frame_width: scaled(self.width), frame_height: scaled(self.height), ..self }
+    }
+
+    #[must_use]
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height, frame_width: width, frame_height: height }
+    }
+
+    // of the frame buffer
+    #[must_use]
+    pub(crate) fn size(&self) -> PhysicalSize<u32> {
+        PhysicalSize::new(self.frame_width, self.frame_height)
+    }
+
+    #[must_use]
+    fn to_frame(offset: u32, extent: u32, frame_extent: u32) -> u32 {
+        (offset as u64 * frame_extent as u64 / extent as u64) as u32
+    }
+
+    // none for the pixels covered by the bars
+    #[must_use]
+    pub(crate) fn frame_buffer_pixel(&self, x: u32, y: u32) -> Option<(u32, u32)> {
+        if x < self.x || y < self.y {
+            return None;
+        }
+        let (offset_x, offset_y) = (x - self.x, y - self.y);
+        if offset_x >= self.width || offset_y >= self.height {
+            return None;
+        }
+        Some((Self::to_frame(offset_x, self.width, self.frame_width), Self::to_frame(offset_y, self.height, self.frame_height)))
+    }
+
+    // the part of the rectangle over the frame buffer as (left, top, width, height)
+    // in the frame buffer pixels; none when the rectangle lies on the bars only
+    #[must_use]
+    pub(crate) fn frame_buffer_rect(&self, x: u32, y: u32, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        let clip = |start: u32, extent: u32, viewport_start: u32, viewport_extent: u32, frame_extent: u32| {
+            let low = start.max(viewport_start);
+            let high = start.saturating_add(extent).min(viewport_start + viewport_extent);
+            (low < high).then(|| {
+                let frame_low = Self::to_frame(low - viewport_start, viewport_extent, frame_extent);
+                // the edge rounded up: a supersampled frame buffer has several pixels under an output one
+                let frame_high = (high - viewport_start) as u64 * frame_extent as u64;
+                let frame_high = frame_high.div_ceil(viewport_extent as u64) as u32;
+                (frame_low, frame_high - frame_low)
+            })
+        };
+        let (left, width) = clip(x, width, self.x, self.width, self.frame_width)?;
+        let (top, height) = clip(y, height, self.y, self.height, self.frame_height)?;
+        Some((left, top, width, height))
+    }
+
+    pub(crate) fn set_into_pass(&self, pass: &mut wgpu::RenderPass) {
+        pass.set_viewport(self.x as f32, self.y as f32, self.width as f32, self.height as f32, 0.0, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_without_aspect_ratio() {
+        let system_under_test = Viewport::fit(PhysicalSize::new(640, 480), None);
+
+        assert_eq!(system_under_test, Viewport::new(0, 0, 640, 480));
+    }
+
+    #[test]
+    fn test_fit_letterbox() {
+        let system_under_test = Viewport::fit(PhysicalSize::new(800, 800), Some(16.0 / 9.0));
+
+        assert_eq!(system_under_test, Viewport::new(0, 175, 800, 450));
+    }
+
+    #[test]
+    fn test_fit_pillarbox() {
+        let system_under_test = Viewport::fit(PhysicalSize::new(1000, 300), Some(2.0));
+
+        assert_eq!(system_under_test, Viewport::new(200, 0, 600, 300));
+    }
+
+    #[test]
+    fn test_frame_buffer_pixel() {
+        let system_under_test = Viewport::fit(PhysicalSize::new(1000, 300), Some(2.0));
+
+        assert_eq!(system_under_test.frame_buffer_pixel(199, 10), None);
+        assert_eq!(system_under_test.frame_buffer_pixel(200, 10), Some((0, 10)));
+        assert_eq!(system_under_test.frame_buffer_pixel(799, 299), Some((599, 299)));
+        assert_eq!(system_under_test.frame_buffer_pixel(800, 10), None);
+    }
+
+    #[test]
+    fn test_frame_buffer_rect() {
+        let system_under_test = Viewport::fit(PhysicalSize::new(1000, 300), Some(2.0));
+
+        assert_eq!(system_under_test.frame_buffer_rect(100, 10, 150, 20), Some((0, 10, 50, 20)));
+        assert_eq!(system_under_test.frame_buffer_rect(300, 250, 1000, 1000), Some((100, 250, 500, 50)));
+        assert_eq!(system_under_test.frame_buffer_rect(0, 0, 200, 300), None);
+        assert_eq!(system_under_test.frame_buffer_rect(300, 10, 0, 20), None);
+    }
+
+    #[test]
+    fn test_with_frame_scale() {
+        let system_under_test = Viewport::fit(PhysicalSize::new(1000, 300), Some(2.0)).with_frame_scale(2.0);
+
+        assert_eq!(system_under_test.size(), PhysicalSize::new(1200, 600));
+        assert_eq!(system_under_test.frame_buffer_pixel(200, 10), Some((0, 20)));
+        assert_eq!(system_under_test.frame_buffer_pixel(799, 299), Some((1198, 598)));
+        assert_eq!(system_under_test.frame_buffer_rect(200, 0, 1, 1), Some((0, 0, 2, 2)));
+    }
+
+    #[test]
+    fn test_with_frame_scale_keeps_pixel() {
+        let system_under_test = Viewport::fit(PhysicalSize::new(3, 1), None).with_frame_scale(0.1);
+
+        assert_eq!(system_under_test.size(), PhysicalSize::new(1, 1));
+    }
+
+    #[test]
+    fn test_fit_frame() {
+        let system_under_test = Viewport::fit_frame(PhysicalSize::new(1000, 300), PhysicalSize::new(400, 200));
+
+        assert_eq!(system_under_test.size(), PhysicalSize::new(400, 200));
+        assert_eq!(system_under_test.frame_buffer_pixel(199, 10), None);
+        assert_eq!(system_under_test.frame_buffer_pixel(200, 0), Some((0, 0)));
+        assert_eq!(system_under_test.frame_buffer_pixel(799, 299), Some((399, 199)));
+        assert_eq!(system_under_test.frame_buffer_rect(100, 0, 250, 300), Some((0, 0, 100, 200)));
+    }
+}
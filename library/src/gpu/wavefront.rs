@@ -0,0 +1,159 @@
+use crate::gpu::compute_pipeline::ComputePipeline;
+use cgmath::Vector3;
+use std::rc::Rc;
+use wgpu::{BufferAddress, BufferUsages, CommandEncoder};
+
+// the layouts are described next to 'compute_wavefront_generate' in the shader
+const PATH_QUARTETS: u64 = 4;
+const HIT_QUARTETS: u64 = 4;
+const QUEUES_HEADER_WORDS: u64 = 2;
+const QUARTET_SIZE_BYTES: u64 = 4 * size_of::<f32>() as u64;
+const WORD_SIZE_BYTES: u64 = size_of::<u32>() as u64;
+const DISPATCH_ARGUMENTS_WORDS: u64 = 3;
+
+// the same as 'MONTE_CARLO_MAX_RAY_BOUNCES' of the shader: the paths still alive are cut there
+const MAX_BOUNCES: u32 = 50;
+
+// per pixel path states and hits along with the queues linking the kernels; sized by the frame buffer area
+pub(super) struct WavefrontBuffers {
+    paths: Rc<wgpu::Buffer>,
+    hits: Rc<wgpu::Buffer>,
+    queues: Rc<wgpu::Buffer>,
+    dispatch: Rc<wgpu::Buffer>,
+}
+
+impl WavefrontBuffers {
+    #[must_use]
+    pub(super) fn new(device: &wgpu::Device, frame_buffer_area: u32) -> Self {
+        let storage = BufferUsages::STORAGE | BufferUsages::COPY_DST;
+        Self {
+            paths: Self::create(device, "wavefront paths", storage, paths_size_bytes(frame_buffer_area)),
+            hits: Self::create(device, "wavefront hits", storage, hits_size_bytes(frame_buffer_area)),
+            queues: Self::create(device, "wavefront queues", storage, queues_size_bytes(frame_buffer_area)),
+            dispatch: Self::create(device, "wavefront dispatch", storage | BufferUsages::INDIRECT, DISPATCH_ARGUMENTS_WORDS * WORD_SIZE_BYTES),
+        }
+    }
+
+    #[must_use]
+    fn create(device: &wgpu::Device, label: &str, usage: BufferUsages, size: BufferAddress) -> Rc<wgpu::Buffer> {
+        Rc::new(device.create_buffer(&wgpu::BufferDescriptor { label: Some(label), size, usage, mapped_at_creation: false }))
+    }
+
+    #[must_use]
+    pub(super) fn paths(&self) -> Rc<wgpu::Buffer> {
+        self.paths.clone()
+    }
+}
+
+#[must_use]
+fn paths_size_bytes(frame_buffer_area: u32) -> BufferAddress {
+    frame_buffer_area as u64 * PATH_QUARTETS * QUARTET_SIZE_BYTES
+}
+
+#[must_use]
+fn hits_size_bytes(frame_buffer_area: u32) -> BufferAddress {
+    frame_buffer_area as u64 * HIT_QUARTETS * QUARTET_SIZE_BYTES
+}
+
+// the lengths of the ray and the hit queues, then the queues themselves
+#[must_use]
+fn queues_size_bytes(frame_buffer_area: u32) -> BufferAddress {
+    (QUEUES_HEADER_WORDS + 2 * frame_buffer_area as u64) * WORD_SIZE_BYTES
+}
+
+// the kernels of a Monte Carlo sample: the camera rays generation, then the intersection and the shading
+// of the queued paths bounce after bounce; the resolution into the pixels is the strategy's own pipeline
+pub(super) struct WavefrontPipelines {
+    generate: ComputePipeline,
+    prepare_intersection: ComputePipeline,
+    intersect: ComputePipeline,
+    prepare_shading: ComputePipeline,
+    shade: ComputePipeline,
+}
+
+impl WavefrontPipelines {
+    #[must_use]
+    pub(super) fn new(generate: ComputePipeline, prepare_intersection: ComputePipeline, intersect: ComputePipeline, prepare_shading: ComputePipeline, shade: ComputePipeline) -> Self {
+        Self { generate, prepare_intersection, intersect, prepare_shading, shade }
+    }
+
+    #[must_use]
+    pub(super) fn intersect_mut(&mut self) -> &mut ComputePipeline {
+        &mut self.intersect
+    }
+
+    #[must_use]
+    pub(super) fn shade_mut(&mut self) -> &mut ComputePipeline {
+        &mut self.shade
+    }
+
+    // the generation seeds the random numbers of the paths, the rest continue the sequences stored in them
+    pub(super) fn setup_frame_buffers_bindings(&mut self, group_index: u32, device: &wgpu::Device, buffers: &WavefrontBuffers, random_seeds: Rc<wgpu::Buffer>) {
+        let label = Some("wavefront compute pipeline frame buffers group");
+
+        self.generate.setup_bind_group(group_index, label, device, |bind_group_builder| {
+            bind_group_builder
+                .set_storage_entry(10, buffers.paths.clone())
+                .set_storage_entry(12, buffers.queues.clone())
+                .set_storage_entry(20, random_seeds)
+            ;
+        });
+        for pipeline in [&mut self.prepare_intersection, &mut self.prepare_shading] {
+            pipeline.setup_bind_group(group_index, label, device, |bind_group_builder| {
+                bind_group_builder
+                    .set_storage_entry(12, buffers.queues.clone())
+                    .set_storage_entry(13, buffers.dispatch.clone())
+                ;
+            });
+        }
+        for pipeline in [&mut self.intersect, &mut self.shade] {
+            pipeline.setup_bind_group(group_index, label, device, |bind_group_builder| {
+                bind_group_builder
+                    .set_storage_entry(10, buffers.paths.clone())
+                    .set_storage_entry(11, buffers.hits.clone())
+                    .set_storage_entry(12, buffers.queues.clone())
+                ;
+            });
+        }
+    }
+
+    // the host does not know the queue lengths: every bounce is recorded, the dispatches of the emptied
+    // queues have no work groups; wgpu synchronizes the storage accesses of the dispatches within the pass
+    pub(super) fn encode(&self, encoder: &mut CommandEncoder, buffers: &WavefrontBuffers, work_groups_needed: Vector3<u32>) {
+        encoder.clear_buffer(&buffers.queues, BufferAddress::default(), Some(QUEUES_HEADER_WORDS * WORD_SIZE_BYTES));
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("wavefront compute pass"),
+            timestamp_writes: None,
+        });
+
+        self.generate.set_into_pass(&mut pass);
+        pass.dispatch_workgroups(work_groups_needed.x, work_groups_needed.y, work_groups_needed.z);
+
+        for _ in 0..MAX_BOUNCES {
+            self.prepare_intersection.set_into_pass(&mut pass);
+            pass.dispatch_workgroups(1, 1, 1);
+            self.intersect.set_into_pass(&mut pass);
+            pass.dispatch_workgroups_indirect(&buffers.dispatch, BufferAddress::default());
+
+            self.prepare_shading.set_into_pass(&mut pass);
+            pass.dispatch_workgroups(1, 1, 1);
+            self.shade.set_into_pass(&mut pass);
+            pass.dispatch_workgroups_indirect(&buffers.dispatch, BufferAddress::default());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_sizes() {
+        let frame_buffer_area = 7;
+
+        assert_eq!(paths_size_bytes(frame_buffer_area), 7 * 4 * 16);
+        assert_eq!(hits_size_bytes(frame_buffer_area), 7 * 4 * 16);
+        assert_eq!(queues_size_bytes(frame_buffer_area), (2 + 7 + 7) * 4);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::geometry::alias::Point;
+use crate::input::camera_input::CameraInput;
+use crate::input::input_bindings::{CameraAction, InputBindings};
+use crate::input::orbit_controller::OrbitController;
+use crate::scene::camera::Camera;
+use cgmath::EuclideanSpace;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::Key;
+
+// turns the window events into the 'CameraInput' of an 'OrbitController' per the bindings: a drag with the orbit
+// button turns the camera around the point it looks at, a drag with the pan button moves it, the wheel zooms;
+// the sensitivities of the orbit controller scale the motions, the keys move the camera by its linear speed
+pub struct CameraController {
+    bindings: InputBindings,
+    orbit: OrbitController,
+    viewport_height: Option<u32>,
+    cursor_position: Option<PhysicalPosition<f64>>,
+    orbiting: bool,
+    panning: bool,
+}
+
+impl CameraController {
+    // the scroll of the touchpads comes in pixels
+    const SCROLL_PIXELS_PER_LINE: f64 = 20.0;
+
+    #[must_use]
+    pub fn new(bindings: InputBindings) -> Self {
+        Self { bindings, orbit: OrbitController::around(Point::origin()), viewport_height: None, cursor_position: None, orbiting: false, panning: false }
+    }
+
+    // the sensitivities of the drags and the zoom; the pivot is the point the camera looks at
+    #[must_use]
+    pub fn with_orbit_controller(mut self, orbit: OrbitController) -> Self {
+        self.orbit = orbit;
+        self
+    }
+
+    // the drags are measured in its heights; the 'Resized' events keep it up to date, till the first
+    // one or this call the drags move nothing
+    #[must_use]
+    pub fn with_viewport_size(mut self, size: PhysicalSize<u32>) -> Self {
+        self.viewport_height = Some(size.height);
+        self
+    }
+
+    #[must_use]
+    pub fn bindings(&self) -> &InputBindings {
+        &self.bindings
+    }
+
+    // the drags in progress are dropped
+    pub fn set_bindings(&mut self, bindings: InputBindings) {
+        self.bindings = bindings;
+        self.orbiting = false;
+        self.panning = false;
+    }
+
+    // the latest one in the window pixels, e.g. for 'Engine::object_in_pixel' and 'Engine::pick_ray'
+    #[must_use]
+    pub fn cursor_position(&self) -> Option<PhysicalPosition<f64>> {
+        self.cursor_position
+    }
+
+    // true when the event has moved the camera or has been taken by a bound button or key; the cursor
+    // moves and the resizes are tracked in any case, the rest is left to the application
+    pub fn handle_window_event(&mut self, event: &WindowEvent, camera: &mut Camera) -> bool {
+        match event {
+            WindowEvent::Resized(size) => {
+                self.viewport_height = Some(size.height);
+                false
+            }
+            WindowEvent::CursorMoved { position, .. } => self.on_cursor_moved(*position, camera),
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor_position = None;
+                false
+            }
+            WindowEvent::MouseInput { state, button, .. } => self.on_mouse_button(*state, *button),
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y as f64,
+                    MouseScrollDelta::PixelDelta(position) => position.y / Self::SCROLL_PIXELS_PER_LINE,
+                };
+                self.orbit(CameraInput::Zoom(lines), camera)
+            }
+            WindowEvent::KeyboardInput { event, .. } => self.on_key(&event.logical_key, event.state, camera),
+            WindowEvent::Focused(false) => {
+                // the release of a button may never arrive
+                self.orbiting = false;
+                self.panning = false;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    // the keys and the application may have moved the camera since the last input
+    fn orbit(&mut self, input: CameraInput, camera: &mut Camera) -> bool {
+        self.orbit.set_pivot(camera.look_at());
+        self.orbit.handle(input, camera)
+    }
+
+    fn on_cursor_moved(&mut self, position: PhysicalPosition<f64>, camera: &mut Camera) -> bool {
+        let previous = self.cursor_position.replace(position);
+        let Some(previous) = previous else {
+            return false;
+        };
+        let Some(viewport_height) = self.viewport_height.filter(|height| *height > 0).map(f64::from) else {
+            return self.orbiting || self.panning;
+        };
+        let (horizontal, vertical) = ((position.x - previous.x) / viewport_height, (position.y - previous.y) / viewport_height);
+        if self.orbiting {
+            let _ = self.orbit(CameraInput::Rotate { horizontal, vertical }, camera);
+        }
+        if self.panning {
+            let _ = self.orbit(CameraInput::Pan { horizontal, vertical }, camera);
+        }
+        self.orbiting || self.panning
+    }
+
+    fn on_mouse_button(&mut self, state: ElementState, button: MouseButton) -> bool {
+        let pressed = ElementState::Pressed == state;
+        if self.bindings.orbit_button() == Some(button) {
+            self.orbiting = pressed;
+            return true;
+        }
+        if self.bindings.pan_button() == Some(button) {
+            self.panning = pressed;
+            return true;
+        }
+        false
+    }
+
+    // the repeats of a held key keep moving the camera
+    fn on_key(&mut self, key: &Key, state: ElementState, camera: &mut Camera) -> bool {
+        let Some(action) = self.bindings.action_of(key) else {
+            return false;
+        };
+        if ElementState::Pressed == state {
+            match action {
+                CameraAction::MoveLeft => camera.move_horizontally(-1.0),
+                CameraAction::MoveRight => camera.move_horizontally(1.0),
+                CameraAction::MoveUp => camera.move_vertically(1.0),
+                CameraAction::MoveDown => camera.move_vertically(-1.0),
+                CameraAction::MoveForward => camera.move_depth_wise(-1.0),
+                CameraAction::MoveBackward => camera.move_depth_wise(1.0),
+            }
+        }
+        true
+    }
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self::new(InputBindings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::assert_abs_diff_eq;
+    use winit::keyboard::NamedKey;
+
+    #[must_use]
+    fn eye_of(camera: &Camera) -> Point {
+        Point::from_vec(camera.camera_space_to_world().w.truncate())
+    }
+
+    #[test]
+    fn test_orbit_drag() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        let mut system_under_test = CameraController::default().with_viewport_size(PhysicalSize::new(400, 200));
+        assert!(false == system_under_test.on_cursor_moved(PhysicalPosition::new(10.0, 10.0), &mut camera));
+
+        assert!(false == system_under_test.on_cursor_moved(PhysicalPosition::new(20.0, 10.0), &mut camera));
+        assert!(system_under_test.on_mouse_button(ElementState::Pressed, MouseButton::Left));
+        // half of the viewport height turns the default orbit controller by 90 degrees
+        assert!(system_under_test.on_cursor_moved(PhysicalPosition::new(120.0, 10.0), &mut camera));
+
+        assert_abs_diff_eq!(eye_of(&camera), Point::new(2.0, 0.0, 0.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(camera.look_at(), Point::origin(), epsilon = 1e-12);
+        assert_eq!(system_under_test.cursor_position(), Some(PhysicalPosition::new(120.0, 10.0)));
+    }
+
+    #[test]
+    fn test_orbit_around_moved_camera() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        let mut system_under_test = CameraController::default().with_viewport_size(PhysicalSize::new(400, 200));
+        assert!(system_under_test.on_key(&Key::Named(NamedKey::ArrowRight), ElementState::Pressed, &mut camera));
+
+        let _ = system_under_test.on_cursor_moved(PhysicalPosition::new(10.0, 10.0), &mut camera);
+        let _ = system_under_test.on_mouse_button(ElementState::Pressed, MouseButton::Left);
+        assert!(system_under_test.on_cursor_moved(PhysicalPosition::new(110.0, 10.0), &mut camera));
+
+        assert_abs_diff_eq!(eye_of(&camera), Point::new(3.0, 0.0, 0.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(camera.look_at(), Point::new(1.0, 0.0, 0.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_released_button_stops_orbit() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        let mut system_under_test = CameraController::default().with_viewport_size(PhysicalSize::new(400, 200));
+        let _ = system_under_test.on_cursor_moved(PhysicalPosition::new(10.0, 10.0), &mut camera);
+        let _ = system_under_test.on_mouse_button(ElementState::Pressed, MouseButton::Left);
+        let _ = system_under_test.on_mouse_button(ElementState::Released, MouseButton::Left);
+
+        assert!(false == system_under_test.on_cursor_moved(PhysicalPosition::new(90.0, 10.0), &mut camera));
+        assert_abs_diff_eq!(eye_of(&camera), Point::new(0.0, 0.0, 2.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_unbound_button_is_left_to_application() {
+        let mut system_under_test = CameraController::new(InputBindings::default().with_pan_button(None));
+
+        assert!(false == system_under_test.on_mouse_button(ElementState::Pressed, MouseButton::Middle));
+        assert!(false == system_under_test.on_mouse_button(ElementState::Pressed, MouseButton::Right));
+    }
+
+    #[test]
+    fn test_key_moves_camera() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        let mut system_under_test = CameraController::default();
+
+        assert!(system_under_test.on_key(&Key::Named(NamedKey::ArrowRight), ElementState::Pressed, &mut camera));
+        assert!(system_under_test.on_key(&Key::Named(NamedKey::ArrowRight), ElementState::Released, &mut camera));
+        assert!(false == system_under_test.on_key(&Key::Named(NamedKey::Enter), ElementState::Pressed, &mut camera));
+
+        assert_abs_diff_eq!(eye_of(&camera), Point::new(1.0, 0.0, 2.0), epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,18 @@
+use crate::geometry::alias::Vector;
+
+// the input of the 'OrbitController' and the 'FlyController', independent of the windowing: the pointer motions
+// are in the fractions of the viewport height, so the speeds do not depend on the window size
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CameraInput {
+    // turns the view: around the pivot for the orbit, around the eye for the fly; to the right and down
+    // for the positive values
+    Rotate { horizontal: f64, vertical: f64 },
+    // shifts the view to the right and up for the positive values
+    Pan { horizontal: f64, vertical: f64 },
+    // the wheel steps, the positive ones bring the orbit closer or speed the fly up
+    Zoom(f64),
+    // the direction the fly accelerates along, in the view axes: x to the right, y up, z backward;
+    // the zero one lets it slow down
+    Thrust(Vector),
+}
+
@@ -0,0 +1,132 @@
+use crate::geometry::alias::Vector;
+use crate::input::camera_input::CameraInput;
+use crate::scene::camera::Camera;
+use cgmath::{Deg, InnerSpace, Transform, Zero};
+use std::time::Duration;
+
+// flies the camera: the thrust accelerates it towards the speed, the damping slows it down once the thrust
+// is off; the rotation turns the view around the eye; 'update' moves it with each frame
+pub struct FlyController {
+    speed: f64,
+    damping: f64,
+    rotation_sensitivity: Deg<f64>,
+    thrust: Vector,
+    velocity: Vector,
+}
+
+impl FlyController {
+    // the velocity below that fraction of the speed stops the camera
+    const REST_FRACTION: f64 = 1e-3;
+    // the zoom step changes the speed by that factor
+    const SPEED_PER_STEP: f64 = 1.25;
+
+    #[must_use]
+    pub fn new(units_per_second: f64) -> Self {
+        assert!(units_per_second > 0.0, "fly speed {units_per_second} is not positive");
+        Self { speed: units_per_second, damping: 8.0, rotation_sensitivity: Deg(90.0), thrust: Vector::zero(), velocity: Vector::zero() }
+    }
+
+    // how fast the velocity approaches the thrust one: the remaining difference is 'exp(-damping * seconds)'
+    #[must_use]
+    pub fn with_damping(mut self, per_second: f64) -> Self {
+        assert!(per_second > 0.0, "damping {per_second} is not positive");
+        self.damping = per_second;
+        self
+    }
+
+    // the turn of a drag over the whole viewport height
+    #[must_use]
+    pub fn with_rotation_sensitivity(mut self, per_viewport_height: Deg<f64>) -> Self {
+        self.rotation_sensitivity = per_viewport_height;
+        self
+    }
+
+    #[must_use]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    #[must_use]
+    pub fn velocity(&self) -> Vector {
+        self.velocity
+    }
+
+    // the camera keeps moving till the velocity drops to rest
+    #[must_use]
+    pub fn moving(&self) -> bool {
+        false == self.velocity.is_zero() || false == self.thrust.is_zero()
+    }
+
+    // true when the input is taken: the rotation turns the camera at once, the thrust and the zoom (the speed)
+    // take effect with the next 'update'; the pan is left to the application
+    pub fn handle(&mut self, input: CameraInput, camera: &mut Camera) -> bool {
+        match input {
+            CameraInput::Rotate { horizontal, vertical } => {
+                // the camera turns around the look at point: the view is shifted back to the eye
+                let eye = camera.eye();
+                camera.turn(-(self.rotation_sensitivity * horizontal), -(self.rotation_sensitivity * vertical));
+                let shift = eye - camera.eye();
+                camera.set_view(eye, camera.look_at() + shift);
+            }
+            CameraInput::Zoom(steps) => self.speed *= Self::SPEED_PER_STEP.powf(steps),
+            CameraInput::Thrust(direction) => self.thrust = direction,
+            CameraInput::Pan { .. } => return false,
+        }
+        true
+    }
+
+    // true when the camera has moved
+    pub fn update(&mut self, elapsed: Duration, camera: &mut Camera) -> bool {
+        let camera_space_to_world = camera.camera_space_to_world();
+        let thrust = camera_space_to_world.transform_vector(self.thrust);
+        let target = if thrust.magnitude2() > 0.0 { thrust.normalize() * self.speed * self.thrust.magnitude().min(1.0) } else { Vector::zero() };
+
+        let seconds = elapsed.as_secs_f64();
+        self.velocity = target + (self.velocity - target) * (-self.damping * seconds).exp();
+        if target.is_zero() && self.velocity.magnitude() < self.speed * Self::REST_FRACTION {
+            self.velocity = Vector::zero();
+            return false;
+        }
+
+        let offset = self.velocity * seconds;
+        camera.set_view(camera.eye() + offset, camera.look_at() + offset);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::alias::Point;
+    use cgmath::{assert_abs_diff_eq, EuclideanSpace};
+
+    #[test]
+    fn test_thrust_and_damping() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        let mut system_under_test = FlyController::new(4.0).with_damping(1000.0);
+
+        assert!(system_under_test.handle(CameraInput::Thrust(-Vector::unit_z()), &mut camera));
+        assert!(system_under_test.update(Duration::from_millis(500), &mut camera));
+
+        assert_abs_diff_eq!(system_under_test.velocity(), Vector::new(0.0, 0.0, -4.0), epsilon = 1e-9);
+        assert_abs_diff_eq!(camera.eye(), Point::new(0.0, 0.0, 0.0), epsilon = 1e-9);
+        assert_abs_diff_eq!(camera.look_at(), Point::new(0.0, 0.0, -2.0), epsilon = 1e-9);
+
+        system_under_test.handle(CameraInput::Thrust(Vector::zero()), &mut camera);
+        let _ = system_under_test.update(Duration::from_millis(500), &mut camera);
+        assert!(false == system_under_test.update(Duration::from_millis(500), &mut camera));
+        assert!(false == system_under_test.moving());
+    }
+
+    #[test]
+    fn test_rotate_around_eye() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        let mut system_under_test = FlyController::new(1.0);
+
+        assert!(system_under_test.handle(CameraInput::Rotate { horizontal: 1.0, vertical: 0.0 }, &mut camera));
+
+        assert_abs_diff_eq!(camera.eye(), Point::new(0.0, 0.0, 2.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(camera.forward(), Vector::unit_x(), epsilon = 1e-12);
+        assert_eq!(false, system_under_test.handle(CameraInput::Pan { horizontal: 1.0, vertical: 0.0 }, &mut camera));
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+// a step of the camera per key press, scaled by the camera linear speed
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CameraAction {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    MoveForward,
+    MoveBackward,
+}
+
+// the buttons and the keys the 'CameraController' takes; an unbound button is left to the application
+#[derive(Clone, PartialEq, Debug)]
+pub struct InputBindings {
+    orbit_button: Option<MouseButton>,
+    pan_button: Option<MouseButton>,
+    keys: HashMap<Key, CameraAction>,
+}
+
+impl InputBindings {
+    // no buttons and no keys bound
+    #[must_use]
+    pub fn empty() -> Self {
+        Self { orbit_button: None, pan_button: None, keys: HashMap::new() }
+    }
+
+    #[must_use]
+    pub fn with_orbit_button(mut self, button: Option<MouseButton>) -> Self {
+        self.orbit_button = button;
+        self
+    }
+
+    #[must_use]
+    pub fn with_pan_button(mut self, button: Option<MouseButton>) -> Self {
+        self.pan_button = button;
+        self
+    }
+
+    // replaces the action the key was bound to, if any
+    #[must_use]
+    pub fn with_key(mut self, key: Key, action: CameraAction) -> Self {
+        self.keys.insert(key, action);
+        self
+    }
+
+    #[must_use]
+    pub fn without_key(mut self, key: &Key) -> Self {
+        self.keys.remove(key);
+        self
+    }
+
+    #[must_use]
+    pub fn orbit_button(&self) -> Option<MouseButton> {
+        self.orbit_button
+    }
+
+    #[must_use]
+    pub fn pan_button(&self) -> Option<MouseButton> {
+        self.pan_button
+    }
+
+    #[must_use]
+    pub fn action_of(&self, key: &Key) -> Option<CameraAction> {
+        self.keys.get(key).copied()
+    }
+}
+
+impl Default for InputBindings {
+    // the left button orbits, the middle one pans, the arrows and the page keys move the camera
+    fn default() -> Self {
+        Self::empty()
+            .with_orbit_button(Some(MouseButton::Left))
+            .with_pan_button(Some(MouseButton::Middle))
+            .with_key(Key::Named(NamedKey::ArrowLeft), CameraAction::MoveLeft)
+            .with_key(Key::Named(NamedKey::ArrowRight), CameraAction::MoveRight)
+            .with_key(Key::Named(NamedKey::ArrowUp), CameraAction::MoveUp)
+            .with_key(Key::Named(NamedKey::ArrowDown), CameraAction::MoveDown)
+            .with_key(Key::Named(NamedKey::PageUp), CameraAction::MoveForward)
+            .with_key(Key::Named(NamedKey::PageDown), CameraAction::MoveBackward)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebinding() {
+        let system_under_test = InputBindings::default()
+            .with_pan_button(None)
+            .with_key(Key::Character("w".into()), CameraAction::MoveForward)
+            .without_key(&Key::Named(NamedKey::PageUp));
+
+        assert_eq!(system_under_test.orbit_button(), Some(MouseButton::Left));
+        assert_eq!(system_under_test.pan_button(), None);
+        assert_eq!(system_under_test.action_of(&Key::Character("w".into())), Some(CameraAction::MoveForward));
+        assert_eq!(system_under_test.action_of(&Key::Named(NamedKey::PageUp)), None);
+        assert_eq!(system_under_test.action_of(&Key::Named(NamedKey::PageDown)), Some(CameraAction::MoveBackward));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod camera_controller;
+pub mod camera_input;
+pub mod fly_controller;
+pub mod input_bindings;
+pub mod object_drag;
+pub mod orbit_controller;
@@ -0,0 +1,236 @@
+use crate::geometry::alias::{Point, Vector};
+use crate::scene::hub::Hub;
+use crate::scene::ray_hit::RayHit;
+use crate::utils::object_uid::ObjectUid;
+use cgmath::{InnerSpace, Zero};
+
+// where the grab point may go
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DragConstraint {
+    // over the plane through the grab point
+    Plane { normal: Vector },
+    // along the line through the grab point, to the point closest to the cursor's ray
+    Axis { direction: Vector },
+}
+
+// moves a grabbed object after the pick rays of the cursor (see 'Engine::pick_ray' and 'Hub::cast_ray'):
+// either the translation is applied the way the object is made, or 'apply' moves it with 'Hub::translate'
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ObjectDrag {
+    object: ObjectUid,
+    grab_point: Point,
+    constraint: DragConstraint,
+    snapping_step: Option<f64>,
+    // the translation 'apply' has moved the object by so far
+    applied: Vector,
+}
+
+impl ObjectDrag {
+    // the normal is, say, the up axis to slide the objects over the floor, or the view direction to move them
+    // parallel to the screen
+    #[must_use]
+    pub fn new(grabbed: &RayHit, plane_normal: Vector) -> Self {
+        assert!(false == plane_normal.is_zero(), "plane normal is expected to be non-zero");
+        Self::constrained(grabbed, DragConstraint::Plane { normal: plane_normal.normalize() })
+    }
+
+    // the view direction is that of the camera or of the grabbing pick ray
+    #[must_use]
+    pub fn parallel_to_screen(grabbed: &RayHit, view_direction: Vector) -> Self {
+        Self::new(grabbed, view_direction)
+    }
+
+    // over the horizontal plane, the y axis being the up one
+    #[must_use]
+    pub fn over_ground(grabbed: &RayHit) -> Self {
+        Self::new(grabbed, Vector::unit_y())
+    }
+
+    // e.g. along a world axis picked by a gizmo handle
+    #[must_use]
+    pub fn along_axis(grabbed: &RayHit, direction: Vector) -> Self {
+        assert!(false == direction.is_zero(), "axis direction is expected to be non-zero");
+        Self::constrained(grabbed, DragConstraint::Axis { direction: direction.normalize() })
+    }
+
+    #[must_use]
+    fn constrained(grabbed: &RayHit, constraint: DragConstraint) -> Self {
+        Self { object: grabbed.object(), grab_point: grabbed.position(), constraint, snapping_step: None, applied: Vector::zero() }
+    }
+
+    // the translation is rounded to the multiples of the step: the distance along the axis, or each of the
+    // world coordinates over the plane
+    #[must_use]
+    pub fn with_snapping(mut self, step: f64) -> Self {
+        assert!(step > 0.0, "snapping step is expected to be positive");
+        self.snapping_step = Some(step);
+        self
+    }
+
+    #[must_use]
+    pub fn object(&self) -> ObjectUid {
+        self.object
+    }
+
+    #[must_use]
+    pub fn constraint(&self) -> DragConstraint {
+        self.constraint
+    }
+
+    // from the grab point to where the ray leads it; none when the ray runs along the plane (or the axis) or
+    // away from it (the cursor is above the horizon of a floor plane)
+    #[must_use]
+    pub fn translation(&self, ray_origin: Point, ray_direction: Vector) -> Option<Vector> {
+        let translation = match self.constraint {
+            DragConstraint::Plane { normal } => self.over_plane(normal, ray_origin, ray_direction)?,
+            DragConstraint::Axis { direction } => direction * self.along_axis_distance(direction, ray_origin, ray_direction)?,
+        };
+        Some(self.snapped(translation))
+    }
+
+    // moves the object by what the translation changed since the last call; false when the ray leads nowhere
+    // or the object is gone
+    pub fn apply(&mut self, hub: &mut Hub, ray_origin: Point, ray_direction: Vector) -> bool {
+        let Some(translation) = self.translation(ray_origin, ray_direction) else {
+            return false;
+        };
+        let step = translation - self.applied;
+        if step.is_zero() {
+            return true;
+        }
+        if false == hub.translate(self.object, step) {
+            return false;
+        }
+        self.applied = translation;
+        true
+    }
+
+    #[must_use]
+    fn over_plane(&self, normal: Vector, ray_origin: Point, ray_direction: Vector) -> Option<Vector> {
+        const PARALLEL_THRESHOLD: f64 = 1e-9;
+        let approach = ray_direction.dot(normal);
+        if approach.abs() < PARALLEL_THRESHOLD {
+            return None;
+        }
+        let distance = (self.grab_point - ray_origin).dot(normal) / approach;
+        if distance < 0.0 {
+            return None;
+        }
+        Some(ray_origin + ray_direction * distance - self.grab_point)
+    }
+
+    // of the axis point closest to the ray
+    #[must_use]
+    fn along_axis_distance(&self, direction: Vector, ray_origin: Point, ray_direction: Vector) -> Option<f64> {
+        const PARALLEL_THRESHOLD: f64 = 1e-9;
+        let ray_direction = ray_direction.normalize();
+        let alignment = direction.dot(ray_direction);
+        let denominator = 1.0 - alignment * alignment;
+        if denominator < PARALLEL_THRESHOLD {
+            return None;
+        }
+        let to_grab = self.grab_point - ray_origin;
+        let distance = (alignment * to_grab.dot(ray_direction) - to_grab.dot(direction)) / denominator;
+        let along_ray = to_grab.dot(ray_direction) + alignment * distance;
+        if along_ray < 0.0 {
+            return None;
+        }
+        Some(distance)
+    }
+
+    #[must_use]
+    fn snapped(&self, translation: Vector) -> Vector {
+        let Some(step) = self.snapping_step else {
+            return translation;
+        };
+        let snap = |value: f64| (value / step).round() * step;
+        match self.constraint {
+            DragConstraint::Plane { .. } => translation.map(snap),
+            DragConstraint::Axis { direction } => direction * snap(translation.dot(direction)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::visual_objects::VisualObjects;
+    use crate::material::material_properties::MaterialProperties;
+    use crate::scene::layer_mask::LayerMask;
+    use cgmath::{assert_abs_diff_eq, EuclideanSpace};
+
+    #[must_use]
+    fn make_grab(plane_normal: Vector) -> ObjectDrag {
+        ObjectDrag::new(&make_hit(), plane_normal)
+    }
+
+    #[must_use]
+    fn make_hit() -> RayHit {
+        RayHit::new(ObjectUid(7), Point::new(1.0, 0.0, 1.0), Vector::unit_y(), 3.0, true)
+    }
+
+    #[test]
+    fn test_translation_over_floor() {
+        let system_under_test = make_grab(Vector::new(0.0, 2.0, 0.0));
+
+        let translation = system_under_test.translation(Point::new(3.0, 4.0, -2.0), Vector::new(0.0, -1.0, 0.0));
+
+        assert_eq!(system_under_test.object(), ObjectUid(7));
+        assert_abs_diff_eq!(translation.unwrap(), Vector::new(2.0, 0.0, -3.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_ray_along_plane() {
+        let system_under_test = make_grab(Vector::unit_y());
+
+        assert_eq!(system_under_test.translation(Point::new(0.0, 1.0, 0.0), Vector::unit_x()), None);
+    }
+
+    #[test]
+    fn test_ray_away_from_plane() {
+        let system_under_test = make_grab(Vector::unit_y());
+
+        assert_eq!(system_under_test.translation(Point::new(0.0, 1.0, 0.0), Vector::unit_y()), None);
+    }
+
+    #[test]
+    fn test_translation_along_axis() {
+        let system_under_test = ObjectDrag::along_axis(&make_hit(), Vector::new(3.0, 0.0, 0.0));
+
+        let translation = system_under_test.translation(Point::new(4.0, 5.0, 1.0), Vector::new(0.0, -2.0, 0.0));
+
+        assert_eq!(system_under_test.constraint(), DragConstraint::Axis { direction: Vector::unit_x() });
+        assert_abs_diff_eq!(translation.unwrap(), Vector::new(3.0, 0.0, 0.0), epsilon = 1e-12);
+        assert_eq!(system_under_test.translation(Point::new(0.0, 0.0, 1.0), Vector::unit_x()), None);
+    }
+
+    #[test]
+    fn test_snapping() {
+        let over_ground = ObjectDrag::over_ground(&make_hit()).with_snapping(0.5);
+        let along_axis = ObjectDrag::along_axis(&make_hit(), Vector::unit_z()).with_snapping(0.5);
+
+        let on_floor = over_ground.translation(Point::new(1.7, 4.0, 2.2), -Vector::unit_y());
+        let on_axis = along_axis.translation(Point::new(1.0, 4.0, 2.2), -Vector::unit_y());
+
+        assert_abs_diff_eq!(on_floor.unwrap(), Vector::new(0.5, 0.0, 1.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(on_axis.unwrap(), Vector::new(0.0, 0.0, 1.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut container = VisualObjects::new(None, None, None, None, None);
+        let material = container.materials_mutable().add(&MaterialProperties::default());
+        let mut hub = Hub::new(container);
+        let object = hub.add_sphere(Point::origin(), 1.0, material);
+        let grabbed = RayHit::new(object, Point::new(0.0, 1.0, 0.0), Vector::unit_y(), 1.0, true);
+        let mut system_under_test = ObjectDrag::over_ground(&grabbed);
+
+        assert!(system_under_test.apply(&mut hub, Point::new(2.0, 5.0, 0.0), -Vector::unit_y()));
+        assert!(system_under_test.apply(&mut hub, Point::new(3.0, 5.0, 0.0), -Vector::unit_y()));
+        assert_eq!(system_under_test.apply(&mut hub, Point::new(3.0, 5.0, 0.0), Vector::unit_y()), false);
+
+        let hit = hub.cast_ray(Point::new(3.0, 5.0, 0.0), -Vector::unit_y(), LayerMask::ALL).unwrap();
+        assert_eq!(hit.object(), object);
+        assert_abs_diff_eq!(hit.position(), Point::new(3.0, 1.0, 0.0), epsilon = 1e-9);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::geometry::alias::{Point, Vector};
+use crate::input::camera_input::CameraInput;
+use crate::scene::camera::Camera;
+use cgmath::{Deg, InnerSpace, Transform};
+
+// turns, pans and zooms the camera around the pivot; the distance and the direction to the eye are read
+// from the camera with each input, so its other changes (e.g. by 'Camera::set_view') are kept
+pub struct OrbitController {
+    pivot: Point,
+    rotation_sensitivity: Deg<f64>,
+    zoom_per_step: f64,
+}
+
+impl OrbitController {
+    const MIN_DISTANCE: f64 = 0.01;
+
+    // around the point the camera looks at
+    #[must_use]
+    pub fn new(camera: &Camera) -> Self {
+        Self::around(camera.look_at())
+    }
+
+    #[must_use]
+    pub fn around(pivot: Point) -> Self {
+        Self { pivot, rotation_sensitivity: Deg(180.0), zoom_per_step: 0.1 }
+    }
+
+    // the turn of a drag over the whole viewport height
+    #[must_use]
+    pub fn with_rotation_sensitivity(mut self, per_viewport_height: Deg<f64>) -> Self {
+        self.rotation_sensitivity = per_viewport_height;
+        self
+    }
+
+    // the fraction of the distance to the pivot each zoom step takes; the orthographic extent shrinks alike
+    #[must_use]
+    pub fn with_zoom_per_step(mut self, fraction: f64) -> Self {
+        assert!(fraction > 0.0 && fraction < 1.0, "zoom fraction {fraction} is out of (0, 1)");
+        self.zoom_per_step = fraction;
+        self
+    }
+
+    #[must_use]
+    pub fn pivot(&self) -> Point {
+        self.pivot
+    }
+
+    // the camera turns to it with the next input
+    pub fn set_pivot(&mut self, pivot: Point) {
+        self.pivot = pivot;
+    }
+
+    // true when the input has moved the camera; the thrust is left to the application
+    pub fn handle(&mut self, input: CameraInput, camera: &mut Camera) -> bool {
+        let to_eye = camera.eye() - self.pivot;
+        let distance = to_eye.magnitude().max(Self::MIN_DISTANCE);
+        let direction = if to_eye.magnitude2() > 0.0 { to_eye / to_eye.magnitude() } else { -camera.forward() };
+        match input {
+            CameraInput::Rotate { horizontal, vertical } => {
+                camera.set_view(self.pivot + direction * distance, self.pivot);
+                camera.turn(self.rotation_sensitivity * horizontal, self.rotation_sensitivity * vertical);
+            }
+            CameraInput::Pan { horizontal, vertical } => {
+                // the pivot plane follows the pointer
+                let view_height = camera.view_height_at(distance);
+                let camera_space_to_world = camera.camera_space_to_world();
+                let right = camera_space_to_world.transform_vector(Vector::unit_x()).normalize();
+                let up = camera_space_to_world.transform_vector(Vector::unit_y()).normalize();
+                let offset = (up * vertical - right * horizontal) * view_height;
+                self.pivot += offset;
+                camera.set_view(self.pivot + direction * distance, self.pivot);
+            }
+            CameraInput::Zoom(steps) => {
+                let ratio = (1.0 - self.zoom_per_step).powf(steps);
+                let distance = (distance * ratio).max(Self::MIN_DISTANCE);
+                camera.set_view(self.pivot + direction * distance, self.pivot);
+                camera.zoom_about_view_point(1.0 / ratio, 0.0, 0.0);
+            }
+            CameraInput::Thrust(_) => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{assert_abs_diff_eq, EuclideanSpace};
+
+    #[test]
+    fn test_rotate_around_pivot() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        let mut system_under_test = OrbitController::around(Point::new(0.0, 0.0, -1.0));
+
+        assert!(system_under_test.handle(CameraInput::Rotate { horizontal: 0.5, vertical: 0.0 }, &mut camera));
+
+        assert_abs_diff_eq!(camera.eye(), Point::new(3.0, 0.0, -1.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(camera.look_at(), Point::new(0.0, 0.0, -1.0), epsilon = 1e-12);
+        assert!(camera.check_and_clear_updated_status());
+    }
+
+    #[test]
+    fn test_pan_moves_pivot() {
+        let mut camera = Camera::new_perspective_camera(2.0, Point::origin());
+        camera.set_fov(Deg(90.0));
+        let mut system_under_test = OrbitController::new(&camera);
+
+        assert!(system_under_test.handle(CameraInput::Pan { horizontal: 0.25, vertical: 0.5 }, &mut camera));
+
+        assert_abs_diff_eq!(system_under_test.pivot(), Point::new(-1.0, 2.0, 0.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(camera.eye(), Point::new(-1.0, 2.0, 2.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_zoom() {
+        let mut camera = Camera::new_orthographic_camera(2.0, Point::origin());
+        let mut system_under_test = OrbitController::new(&camera).with_zoom_per_step(0.5);
+
+        assert!(system_under_test.handle(CameraInput::Zoom(2.0), &mut camera));
+
+        assert_abs_diff_eq!(camera.eye().to_vec().magnitude(), 0.5, epsilon = 1e-12);
+        assert_abs_diff_eq!(camera.ortho_extent(), 0.5, epsilon = 1e-12);
+        assert_eq!(false, system_under_test.handle(CameraInput::Thrust(Vector::unit_x()), &mut camera));
+    }
+}
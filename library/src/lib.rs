@@ -7,6 +7,7 @@
 pub mod geometry;
 pub mod objects;
 pub mod scene;
+pub mod input;
 pub mod utils;
 pub mod sdf;
 pub mod container;
@@ -20,63 +21,123 @@ pub mod animation;
 pub mod material;
 pub mod shader;
 pub mod palette;
+pub mod background;
+pub mod material_preview;
+pub mod sdf_preview;
+pub mod self_test;
+pub mod prelude;
 
+#[cfg(feature = "gpu_testing")]
+pub use crate::gpu::headless_device::HeadlessContext;
+pub use crate::gpu::pipelines_warm_up::PipelinesWarmUp;
+#[cfg(feature = "denoiser")]
+pub use crate::denoiser::{denoise_mode::DenoiseMode, device_type::DenoiserDeviceType, worker::DenoiserQueue};
+
+use crate::background::background_uid::BackgroundUid;
+use crate::geometry::alias::{Point, Vector};
+use crate::gpu::accumulation_checkpoint::AccumulationCheckpoint;
 use crate::gpu::adapter_features::{log_adapter_info, AdapterFeatures};
-use crate::gpu::color_buffer_evaluation::RenderStrategyId;
 use crate::gpu::context::Context;
 use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::gpu::headless_device::create_headless_wgpu_device;
+use crate::gpu::output::exported_textures::ExportedTextures;
 use crate::gpu::render::{FrameBufferSettings, Renderer};
 use crate::gpu::scaffolding::backend_vulkan_or_primary;
+use crate::scene::auto_exposure::AutoExposure;
+use crate::scene::bloom::Bloom;
+use crate::scene::buffers_statistics::SceneBuffersStatistics;
+use crate::scene::engine_statistics::EngineStatistics;
+use crate::scene::frame_capture::{CaptureFormat, CapturedAovs, CapturedFrame};
+use crate::scene::frame_statistics::FrameStatistics;
+use crate::scene::gpu_passes_report::GpuPassesReport;
 use crate::scene::camera::Camera;
+use crate::scene::color_grading::ColorGrading;
+use crate::scene::display_mapping::DisplayMapping;
+use crate::scene::g_buffer::GBuffer;
+use crate::scene::magnifier::{Magnifier, MagnifierReadout};
+use crate::scene::output_dithering::OutputDithering;
+use crate::scene::overlay::Overlay;
+use crate::scene::pipeline_cache_statistics::PipelineCacheStatistics;
+use crate::scene::random_generator::RandomGenerator;
+use crate::scene::random_seed_texture::RandomSeedTexture;
+use crate::scene::render_strategy::{RenderStrategyConfig, RenderStrategyId};
+#[cfg(feature = "monte_carlo")]
+use crate::scene::pixel_path::PixelPath;
+use crate::utils::frame_pacer::FramePacer;
 use crate::utils::min_max_time_measurer::MinMaxTimeMeasurer;
 use crate::utils::object_uid::ObjectUid;
 use crate::utils::sliding_time_frame::SlidingTimeFrame;
 use crate::utils::time_throttled_logger::TimeThrottledInfoLogger;
-use log::info;
+use log::{info, warn};
 use std::cmp::max;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use more_asserts::assert_lt;
+use std::time::{Duration, Instant};
+use more_asserts::{assert_ge, assert_le, assert_lt};
 use thiserror::Error;
 use wgpu::Trace;
 use winit::window::Window;
 use crate::container::visual_objects::VisualObjects;
+use crate::scene::frame_buffer_size_policy::FrameBufferSizePolicy;
 use crate::scene::hub::Hub;
+use crate::scene::key_light_shadows::KeyLightShadows;
+use crate::scene::layer_mask::LayerMask;
+use crate::scene::probe_grid::ProbeGrid;
+use crate::scene::resize_policy::ResizePolicy;
+use crate::scene::sdf_level_of_detail::SdfLevelOfDetail;
+use crate::scene::stop_criterion::StopCriterion;
+use crate::self_test::{SelfTest, SelfTestReport};
 
 const DEVICE_LABEL: &str = "Rust Tracer Library";
+const OFFSCREEN_DEVICE_LABEL: &str = "Rust Tracer Library Offscreen";
+
+const OFFSCREEN_OUTPUT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
 
 const FPS_MEASUREMENT_SAMPLES: usize = 15;
 const FPS_WRITE_INTERVAL: Duration = Duration::from_secs(2);
+const BUFFERS_WARNING_INTERVAL: Duration = Duration::from_secs(10);
+// the device creation blocks the frame, so a failed recovery is not retried each frame
+const DEVICE_RECOVERY_RETRY_INTERVAL: Duration = Duration::from_secs(1);
 
+// the default passes a Monte Carlo frame accumulates, see 'Engine::set_accumulation_pacing'
 #[cfg(feature = "denoiser")]
 pub const RAYS_ACCUMULATIONS_PER_FRAME: usize = 10;
 #[cfg(not(feature = "denoiser"))]
 pub const RAYS_ACCUMULATIONS_PER_FRAME: usize = 1;
 
-const PIXEL_SUBDIVISION_MONTE_CARLO: u32 = 2;
-const PIXEL_SUBDIVISION_DETERMINISTIC: u32 = 4;
+pub const MIN_ANTIALIASING_LEVEL: u32 = 1;
+pub const MAX_ANTIALIASING_LEVEL: u32 = 16;
 
 pub struct Engine {
     /*Actually, we do not need any synchronization stuff; our code is
     single-threaded. But due to the design of the wgpu function, we
     are obliged to use thread-safe types to bypass compiler checks.*/
     device_was_lost: Arc<AtomicBool>,
+    device_recoveries: u32,
+    last_device_recovery_attempt: Option<Instant>,
+    gpu_capture_requested: bool,
 
+    wgpu_instance: wgpu::Instance,
     window_pixels_size: winit::dpi::PhysicalSize<u32>,
     ignore_render_requests: bool,
+    resize_policy: ResizePolicy,
+    deferred_resize_since: Option<Instant>,
 
     context: Rc<Context>,
-    renderer: Renderer,
+    controls: RendererControls,
     
     window_output_surface: wgpu::Surface<'static>, // TODO: actually this object is not quite 'static; in fact here we do not know anything about that, how static it is
     window_surface_format: wgpu::TextureFormat,
     
     fps_measurer: SlidingTimeFrame,
+    frame_pacer: FramePacer,
     denoising_measurer: MinMaxTimeMeasurer,
     performance_reporter: TimeThrottledInfoLogger,
+    buffers_reporter: TimeThrottledInfoLogger,
 }
 
 #[derive(Error, Debug)]
@@ -119,53 +180,18 @@ impl Engine {
         let window_surface = wgpu_instance.create_surface(window.clone())
             .map_err(|e| EngineInstantiationError::SurfaceCreationError{what: e.to_string()})?;
 
-        let graphics_adapter = wgpu_instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&window_surface),
-                ..Default::default()
-            })
-            .await
-            .map_err(|error| EngineInstantiationError::AdapterRequisitionError{what: error.to_string()})?;
-
-        let adapter_info = graphics_adapter.get_info();
-        log_adapter_info(&adapter_info);
-
-        let features = AdapterFeatures::new(&graphics_adapter);
-        
-        let (graphics_device, commands_queue) = graphics_adapter
-            .request_device(&wgpu::DeviceDescriptor {
-                label: Some(DEVICE_LABEL),
-                required_features: features.desired_features(),
-                required_limits: wgpu::Limits::default(),
-                memory_hints: wgpu::MemoryHints::default(),
-                trace: Trace::Off,
-            })
-            .await
-            .map_err(|e| EngineInstantiationError::DeviceSelectionError {what: e.to_string()})?;
-
-        let surface_capabilities = window_surface.get_capabilities(&graphics_adapter);
+        let device_was_lost_flag = Arc::new(AtomicBool::new(false));
+        let (context, surface_capabilities) = create_window_device(&wgpu_instance, &window_surface, &device_was_lost_flag).await?;
         if surface_capabilities.formats.is_empty() {
             return Err(EngineInstantiationError::SurfaceCompatibilityError);
         }
 
-        let device_was_lost_flag = Arc::new(AtomicBool::new(false));
-
-        let lost_device_handler = {
-            let device_was_lost = Arc::clone(&device_was_lost_flag);
-            move |reason, message| {
-                info!("device was lost: {reason:?}, {message}");
-                device_was_lost.store(true, Ordering::SeqCst);
-            }
-        };
-        graphics_device.set_device_lost_callback(lost_device_handler);
-        
-        let context = Rc::new(Context::new(graphics_device, commands_queue, features.pipeline_caching_supported(), adapter_info));
-        let output_surface_format = surface_capabilities.formats[0];
+        let context = Rc::new(context);
+        let output_surface_format = FrameBufferSettings::pick_presentation_format(&surface_capabilities.formats);
 
         let frame_buffer_size = FrameBufferSize::new(max(1, window_pixels_size.width), max(1, window_pixels_size.height));
-        let frame_buffer_settings = FrameBufferSettings::new(output_surface_format, frame_buffer_size, PIXEL_SUBDIVISION_DETERMINISTIC,);
-        let renderer 
+        let frame_buffer_settings = FrameBufferSettings::new(output_surface_format, frame_buffer_size, RenderStrategyConfig::PIXEL_SUBDIVISION_DETERMINISTIC,);
+        let mut renderer 
             = Renderer::new(
                 context.clone(),
                 scene,
@@ -175,19 +201,31 @@ impl Engine {
                 caches_path,
             )
             .map_err(|e| EngineInstantiationError::InternalError {what: e.to_string()})?;
+        // the picking reads single ids back, the box selection the whole map when asked
+        renderer.set_object_id_readback(false);
+        // the ui keeps going with the last image while the shader of new sdf classes compiles
+        renderer.set_background_shader_compilation(true);
 
         let ware = Engine {
-            device_was_lost: device_was_lost_flag.clone(),
+            device_was_lost: device_was_lost_flag,
+            device_recoveries: 0,
+            last_device_recovery_attempt: None,
+            gpu_capture_requested: false,
+            wgpu_instance,
             context: context.clone(),
             window_pixels_size,
             ignore_render_requests: false,
+            resize_policy: ResizePolicy::default(),
+            deferred_resize_since: None,
             window_output_surface: window_surface,
             window_surface_format: output_surface_format,
-            renderer,
+            controls: RendererControls { renderer },
 
             fps_measurer: SlidingTimeFrame::new(FPS_MEASUREMENT_SAMPLES),
+            frame_pacer: FramePacer::new(),
             denoising_measurer: MinMaxTimeMeasurer::default(),
             performance_reporter: TimeThrottledInfoLogger::new(FPS_WRITE_INTERVAL),
+            buffers_reporter: TimeThrottledInfoLogger::new(BUFFERS_WARNING_INTERVAL),
         };
 
         ware.configure_surface();
@@ -211,8 +249,9 @@ impl Engine {
     }
 
     fn configure_render(&mut self) {
-        self.renderer.set_output_size(self.window_pixels_size);
+        self.controls.renderer.set_output_size(self.window_pixels_size);
         self.fps_measurer.start();
+        self.frame_pacer.forget();
     }
 
     // TODO: add handling of window obscuring → request to unload all occupied resources (iOS)
@@ -234,7 +273,35 @@ impl Engine {
         }
         self.window_pixels_size = new_size;
         self.configure_surface();
-        self.configure_render();
+        match self.resize_policy {
+            ResizePolicy::ResetAccumulation => self.configure_render(),
+            ResizePolicy::SeedWithScaledImage => {
+                self.controls.renderer.set_output_size_resampling_accumulation(self.window_pixels_size);
+                self.fps_measurer.start();
+            },
+            ResizePolicy::KeepResolutionUntilIdle(_) => {
+                self.controls.renderer.keep_frame_buffer_size(self.window_pixels_size);
+                self.deferred_resize_since = Some(Instant::now());
+            },
+        }
+    }
+
+    // the resize deferred by the previous policy is applied at once
+    pub fn set_resize_policy(&mut self, policy: ResizePolicy) {
+        self.resize_policy = policy;
+        if self.deferred_resize_since.take().is_some() {
+            self.configure_render();
+        }
+    }
+
+    fn apply_deferred_resize_when_idle(&mut self) {
+        let ResizePolicy::KeepResolutionUntilIdle(idle_period) = self.resize_policy else {
+            return;
+        };
+        if self.deferred_resize_since.is_some_and(|since| since.elapsed() >= idle_period) {
+            self.deferred_resize_since = None;
+            self.configure_render();
+        }
     }
 
     pub fn render_frame<Code: Fn()>(&mut self, pre_present_notify: Code) {
@@ -242,70 +309,482 @@ impl Engine {
             return;
         }
 
-        if self.device_was_lost.load(Ordering::SeqCst) {
-            // TODO: handle lost device
+        if self.device_was_lost.load(Ordering::SeqCst) && false == self.recover_lost_device() {
+            return;
         }
 
-        let surface_texture = self
-            .window_output_surface
-            .get_current_texture()
-            .expect("failed to acquire next image in the swapchain");
+        self.apply_deferred_resize_when_idle();
+
+        let surface_texture = match self.window_output_surface.get_current_texture() {
+            Ok(surface_texture) => surface_texture,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                // the frame is skipped: the next one goes to the reconfigured swapchain
+                self.configure_surface();
+                return;
+            }
+            Err(error) => panic!("failed to acquire next image in the swapchain: {error}"),
+        };
 
         if surface_texture.suboptimal {
             // TODO: schedule surface reconfigure?
         }
 
-        self.renderer.start_new_frame();
+        let capture_gpu = std::mem::take(&mut self.gpu_capture_requested);
+        if capture_gpu {
+            info!("capturing the gpu work of the frame");
+            self.context.start_graphics_debugger_capture();
+        }
+
+        self.controls.renderer.start_new_frame();
         
-        if self.renderer.is_monte_carlo() {
-            for _ in 0..RAYS_ACCUMULATIONS_PER_FRAME {
-                self.renderer.accumulate_more_rays();
-            }   
+        let config = self.controls.renderer.render_strategy_config(self.controls.renderer.render_strategy());
+        if self.controls.renderer.is_monte_carlo() {
+            let passes = match config.frame_time_budget() {
+                Some(budget) => self.frame_pacer.passes(budget, config.samples_per_pass()),
+                None => config.samples_per_pass(),
+            };
+            let passes_start = Instant::now();
+            for pass in 0..passes {
+                self.controls.renderer.set_sub_frame(f64::from(pass + 1) / f64::from(passes));
+                self.controls.renderer.accumulate_more_rays();
+            }
+            if config.frame_time_budget().is_some() {
+                let _ = self.context.wait(None);
+                self.frame_pacer.record(passes, passes_start.elapsed());
+            }
         } else {
-            self.renderer.accumulate_more_rays();
+            self.controls.renderer.accumulate_more_rays();
         } 
 
         #[cfg(feature = "denoiser")] {
-            if self.renderer.is_monte_carlo() {
-                self.renderer.denoise_accumulated_image(&mut self.denoising_measurer);
+            if self.controls.renderer.is_monte_carlo() && config.denoise() && self.controls.renderer.denoising() {
+                self.controls.renderer.denoise_accumulated_image(config.denoise_interval(), &mut self.denoising_measurer);
             }
         }
 
-        self.renderer.present(&surface_texture);
+        self.controls.renderer.present(&surface_texture);
 
         pre_present_notify();
         surface_texture.present();
 
+        if capture_gpu {
+            self.context.stop_graphics_debugger_capture();
+        }
+
         self.fps_measurer.sample();
 
         self.write_performance_report();
+        write_buffers_warning(&self.controls.renderer, &mut self.buffers_reporter);
+    }
+
+    // the window and the surface outlive the device, so the renderer is rebuilt on a new one;
+    // until that succeeds, the frames are skipped
+    fn recover_lost_device(&mut self) -> bool {
+        if self.last_device_recovery_attempt.is_some_and(|attempt| attempt.elapsed() < DEVICE_RECOVERY_RETRY_INTERVAL) {
+            return false;
+        }
+        self.last_device_recovery_attempt = Some(Instant::now());
+        info!("recovering from the device loss");
+        let device_was_lost = Arc::new(AtomicBool::new(false));
+        let (context, surface_capabilities) = match pollster::block_on(create_window_device(&self.wgpu_instance, &self.window_output_surface, &device_was_lost)) {
+            Ok(created) => created,
+            Err(error) => {
+                warn!("failed to recover from the device loss: {error}");
+                return false;
+            }
+        };
+        if false == surface_capabilities.formats.contains(&self.window_surface_format) {
+            warn!("failed to recover from the device loss: {}", EngineInstantiationError::SurfaceCompatibilityError);
+            return false;
+        }
+
+        self.context = Rc::new(context);
+        self.device_was_lost = device_was_lost;
+        self.device_recoveries += 1;
+        self.last_device_recovery_attempt = None;
+
+        self.configure_surface();
+        self.controls.renderer.recreate_on_device(self.context.clone());
+        true
+    }
+
+    // the device, the queue and the exported textures are replaced on each recovery; the last texture
+    // atlas page is uploaded again by the engine
+    #[must_use]
+    pub fn device_recoveries(&self) -> u32 {
+        self.device_recoveries
+    }
+
+    // debugging aid: the next rendered frame, up to the presentation, is captured by the graphics debugger
+    // the application runs under (RenderDoc or Xcode); without one the request changes nothing
+    pub fn trigger_gpu_capture(&mut self) {
+        self.gpu_capture_requested = true;
     }
 
     fn write_performance_report(&mut self) {
         let average_frame_time = self.fps_measurer.average_delta();
         let fps = 1.0 / average_frame_time.as_secs_f32();
 
+        #[cfg(feature = "denoiser")]
+        let denoiser_dropped_frames = self.controls.renderer.denoiser_dropped_frames_count();
+        #[cfg(not(feature = "denoiser"))]
+        let denoiser_dropped_frames = 0;
+
         let performance_report = 
             if cfg!(feature = "denoiser") {
                 format!(
-                    "CPU observed FPS: {}; Denoising (ms): min={}, max={}, current={}",
+                    "CPU observed FPS: {}; Denoising (ms): min={}, max={}, current={}; dropped frames: {}",
                     fps,
                     self.denoising_measurer.min_time().as_millis(),
                     self.denoising_measurer.max_time().as_millis(),
                     self.denoising_measurer.last_time().as_millis(),
+                    denoiser_dropped_frames,
                 )
             } else {
                 format!("CPU observed FPS: {fps}")
             };
+        let performance_report = format!("{performance_report}; {}", self.controls.renderer.last_frame_statistics());
+        let performance_report = match self.controls.renderer.gpu_passes_report() {
+            Some(passes_report) => format!("{performance_report}; {passes_report}"),
+            None => performance_report,
+        };
         
         self.performance_reporter.do_write(performance_report);
     }
 
+    // renders the built-in test scene into memory on the device of the window and checks the image; see 'SelfTest'
+    pub fn run_self_test(&self) -> Result<SelfTestReport, EngineInstantiationError> {
+        SelfTest::run_on(self.context.clone())
+    }
+
+    // the sdf classes have changed and the new shader is still compiling: the frames show the last traced image
     #[must_use]
-    pub fn object_in_pixel(&self, x: u32, y: u32) -> Option<ObjectUid> {
+    pub fn shader_compilation_pending(&self) -> bool {
+        self.controls.renderer.shader_compilation_pending()
+    }
+
+    // width over height; the image is centered in the window and the rest is filled with the clear color.
+    // None - the image covers the whole window
+    pub fn set_fixed_aspect_ratio(&mut self, aspect_ratio: Option<f64>) {
+        self.controls.renderer.set_fixed_aspect_ratio(aspect_ratio);
+    }
+
+    // a frame buffer larger than the window trades the speed for the antialiasing, say for a small window; a smaller
+    // one keeps the frame rate on a large display, see 'set_upscale_sharpness'
+    pub fn set_frame_buffer_size_policy(&mut self, policy: FrameBufferSizePolicy) {
+        self.controls.renderer.set_frame_buffer_size_policy(policy);
+    }
+
+    // of the frame buffer scaled below the window, the output pass upscales: zero is the plain bilinear filtering,
+    // one sharpens the most
+    pub fn set_upscale_sharpness(&mut self, sharpness: f64) {
+        self.controls.renderer.set_upscale_sharpness(sharpness);
+    }
+
+    // the origin and the unit direction of the ray the tracer casts through the window pixel (e.g. for 'Hub::cast_ray'
+    // or 'ObjectDrag'); none for the pixels on the letterbox bars
+    #[must_use]
+    pub fn pick_ray(&self, x: u32, y: u32) -> Option<(Point, Vector)> {
+        self.controls.renderer.pick_ray(x, y).map(|ray| (ray.origin(), ray.direction()))
+    }
+
+    // divides the orthographic extent by the factor (above one zooms in) keeping the scene point under the window
+    // pixel in place; false, with the camera unchanged, for the pixels on the letterbox bars
+    pub fn zoom_about_pixel(&mut self, x: u32, y: u32, factor: f64) -> bool {
+        self.controls.renderer.zoom_about_pixel(x, y, factor)
+    }
+
+    // the id under the pixel is read back alone; the recent answers are kept till the scene or the camera changes,
+    // without one this blocks for the read back
+    #[must_use]
+    pub fn object_in_pixel(&mut self, x: u32, y: u32) -> Option<ObjectUid> {
+        assert_lt!(x, self.window_pixels_size.width);
+        assert_lt!(y, self.window_pixels_size.height);
+        self.controls.renderer.object_in_pixel(x, y)
+    }
+
+    // starts reading the id under the pixel back (say, on the cursor movement), so the 'object_in_pixel'
+    // after the next poll answers without blocking
+    pub fn request_object_in_pixel(&mut self, x: u32, y: u32) {
+        assert_lt!(x, self.window_pixels_size.width);
+        assert_lt!(y, self.window_pixels_size.height);
+        self.controls.renderer.request_object_in_pixel(x, y);
+    }
+
+    // the requested object ids (and the denoiser inputs) are read back from the gpu a frame or so later;
+    // true when all of them are at the cpu; the frame rendering polls as well
+    pub fn poll_readbacks(&mut self) -> bool {
+        self.controls.renderer.poll_copies_from_gpu()
+    }
+
+    // the objects visible in the rectangle of the window with the count of their pixels (box selection);
+    // blocks till the object id map is read back
+    #[must_use]
+    pub fn objects_in_rect(&mut self, x: u32, y: u32, width: u32, height: u32) -> HashMap<ObjectUid, u32> {
         assert_lt!(x, self.window_pixels_size.width);
         assert_lt!(y, self.window_pixels_size.height);
-        self.renderer.object_in_pixel(x, y)
+        self.controls.renderer.objects_in_rect(x, y, width, height)
+    }
+
+    // host subsystems sampling the exported textures must use the engine's device and queue
+    #[must_use]
+    pub fn device(&self) -> &wgpu::Device {
+        self.context.device()
+    }
+
+    #[must_use]
+    pub fn queue(&self) -> &wgpu::Queue {
+        self.context.queue()
+    }
+
+    // blocks till the object ids, the depths, the normals and the albedo of the last frame are read back;
+    // the layers are in the frame buffer pixels, 'export_frame_textures' has them on the gpu
+    #[must_use]
+    pub fn read_g_buffer(&mut self) -> GBuffer<'_> {
+        self.controls.renderer.read_g_buffer()
+    }
+
+    // copies the last rendered frame into textures; see 'FrameTextureViews' for synchronization
+    #[must_use]
+    pub fn export_frame_textures(&mut self) -> FrameTextureViews {
+        FrameTextureViews::new(self.controls.renderer.export_frame_textures())
+    }
+    
+    #[cfg(feature = "monte_carlo")]
+    pub fn use_monte_carlo_render_with_half_resolution_indirect(&mut self) {
+        self.controls.renderer.set_render_strategy(RenderStrategyId::MonteCarloHalfResolutionIndirect);
+    }
+
+    // see 'RendererControls::set_render_strategy_config'; the passes fitting into a frame are measured anew
+    pub fn set_render_strategy_config(&mut self, strategy: RenderStrategyId, config: RenderStrategyConfig) {
+        self.controls.set_render_strategy_config(strategy, config);
+        self.frame_pacer.forget();
+    }
+
+    // the Monte Carlo passes a frame accumulates: a fixed count, or as many as fit into the budget,
+    // 'max_passes' at most; applies to the strategy in use. To time the passes, a frame with the budget
+    // blocks in 'wait' till the gpu is done, so the cpu does not prepare the next frame meanwhile
+    pub fn set_accumulation_pacing(&mut self, max_passes: u32, frame_time_budget: Option<Duration>) {
+        let strategy = self.controls.renderer.render_strategy();
+        let config = self.controls.renderer.render_strategy_config(strategy)
+            .with_samples_per_pass(max_passes)
+            .with_frame_time_budget(frame_time_budget);
+        self.set_render_strategy_config(strategy, config);
+    }
+
+    // GPU device types (CUDA, HIP, SYCL, Metal) run the filter itself on the video card; the images
+    // still make a round trip through the cpu memory, wgpu exports no buffers for the OIDN to share
+    #[cfg(feature = "denoiser")]
+    pub fn use_denoiser_device(&mut self, device_type: DenoiserDeviceType) {
+        self.controls.renderer.set_denoiser_device(device_type);
+    }
+
+    // 'Off' shows the accumulated image as is, skipping the denoiser; the render strategies
+    // without denoising are not affected by the other modes
+    #[cfg(feature = "denoiser")]
+    pub fn set_denoising(&mut self, mode: DenoiseMode) {
+        self.controls.renderer.set_denoise_mode(mode);
+    }
+
+    // for the cpus slower than the gpu: the accumulation does not wait for the denoiser, the shown
+    // image is the latest denoised frame; 'None' brings the per-frame denoising back
+    #[cfg(feature = "denoiser")]
+    pub fn use_denoiser_queue(&mut self, queue: Option<DenoiserQueue>) {
+        self.controls.renderer.set_denoiser_queue(queue);
+    }
+
+    // the window surface takes the radiance beyond the SDR white: the output is neither tone mapped nor gamma encoded,
+    // the display maps the highlights itself; picked on the start when the surface supports it
+    #[must_use]
+    pub fn hdr_output_enabled(&self) -> bool {
+        self.controls.renderer.extended_range_output()
+    }
+
+    // debugging aid: the bounces of the path through the window pixel (the first sample of the last
+    // Monte Carlo frame traced once more); waits for the gpu, none for the bars around the image
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub fn trace_pixel_path(&mut self, x: u32, y: u32) -> Option<PixelPath> {
+        assert_lt!(x, self.window_pixels_size.width);
+        assert_lt!(y, self.window_pixels_size.height);
+        self.controls.renderer.trace_pixel_path(x, y)
+    }
+}
+
+impl Deref for Engine {
+    type Target = RendererControls;
+
+    fn deref(&self) -> &RendererControls {
+        &self.controls
+    }
+}
+
+impl DerefMut for Engine {
+    fn deref_mut(&mut self) -> &mut RendererControls {
+        &mut self.controls
+    }
+}
+
+// The textures are written by commands submitted to the engine's queue: any work submitted
+// to the same queue after 'Engine::export_frame_textures' observes the frame, no extra fences
+// are needed. The next export overwrites the contents, a resize or a device recovery invalidates the views.
+// Albedo and normal are Rgba32Float, depth is R32Float (not filterable: use 'textureLoad' or a non-filtering
+// sampler), object id is R32Uint, the final image has the window surface format.
+#[derive(Clone, Debug)]
+pub struct FrameTextureViews {
+    final_image: wgpu::TextureView,
+    albedo: wgpu::TextureView,
+    normal: wgpu::TextureView,
+    depth: wgpu::TextureView,
+    object_id: wgpu::TextureView,
+}
+
+impl FrameTextureViews {
+    #[must_use]
+    fn new(exported: &ExportedTextures) -> Self {
+        Self {
+            final_image: exported.final_image().clone(),
+            albedo: exported.albedo().clone(),
+            normal: exported.normal().clone(),
+            depth: exported.depth().clone(),
+            object_id: exported.object_id().clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn final_image(&self) -> &wgpu::TextureView {
+        &self.final_image
+    }
+
+    #[must_use]
+    pub fn albedo(&self) -> &wgpu::TextureView {
+        &self.albedo
+    }
+
+    #[must_use]
+    pub fn normal(&self) -> &wgpu::TextureView {
+        &self.normal
+    }
+
+    #[must_use]
+    pub fn depth(&self) -> &wgpu::TextureView {
+        &self.depth
+    }
+
+    #[must_use]
+    pub fn object_id(&self) -> &wgpu::TextureView {
+        &self.object_id
+    }
+}
+
+// the device of an adapter able to present to the surface; the flag is raised once the device is lost
+async fn create_window_device(wgpu_instance: &wgpu::Instance, window_surface: &wgpu::Surface<'static>, device_was_lost: &Arc<AtomicBool>) -> Result<(Context, wgpu::SurfaceCapabilities), EngineInstantiationError> {
+    let graphics_adapter = wgpu_instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: Some(window_surface),
+            ..Default::default()
+        })
+        .await
+        .map_err(|error| EngineInstantiationError::AdapterRequisitionError{what: error.to_string()})?;
+
+    let adapter_info = graphics_adapter.get_info();
+    log_adapter_info(&adapter_info);
+
+    let features = AdapterFeatures::new(&graphics_adapter);
+
+    let (graphics_device, commands_queue) = graphics_adapter
+        .request_device(&wgpu::DeviceDescriptor {
+            label: Some(DEVICE_LABEL),
+            required_features: features.desired_features(),
+            required_limits: wgpu::Limits::default(),
+            memory_hints: wgpu::MemoryHints::default(),
+            trace: Trace::Off,
+        })
+        .await
+        .map_err(|e| EngineInstantiationError::DeviceSelectionError {what: e.to_string()})?;
+
+    let lost_device_handler = {
+        let device_was_lost = Arc::clone(device_was_lost);
+        move |reason, message| {
+            info!("device was lost: {reason:?}, {message}");
+            device_was_lost.store(true, Ordering::SeqCst);
+        }
+    };
+    graphics_device.set_device_lost_callback(lost_device_handler);
+
+    let surface_capabilities = window_surface.get_capabilities(&graphics_adapter);
+    Ok((Context::new(graphics_device, commands_queue, features.pipeline_caching_supported(), features.timestamp_queries_supported(), adapter_info), surface_capabilities))
+}
+
+fn assert_antialiasing_level_supported(level: u32) {
+    assert_ge!(level, MIN_ANTIALIASING_LEVEL, "antialiasing level is too low");
+    assert_le!(level, MAX_ANTIALIASING_LEVEL, "antialiasing level is too high");
+}
+
+fn write_buffers_warning(renderer: &Renderer, reporter: &mut TimeThrottledInfoLogger) {
+    if let Some(warning) = renderer.buffers_statistics().overflow_warning() {
+        reporter.do_write_at(log::Level::Warn, warning);
+    }
+}
+
+// the scene, the camera and the render settings, the same for 'Engine' and 'OffscreenEngine': both
+// dereference to it
+pub struct RendererControls {
+    renderer: Renderer,
+}
+
+impl RendererControls {
+    // the presented image at the frame buffer size, without the letterbox bars, and the frame buffer layers
+    // asked for, into the files next to it; blocks till the gpu is done
+    pub fn capture_frame(&mut self, path: impl AsRef<Path>, format: CaptureFormat, aovs: CapturedAovs) -> anyhow::Result<()> {
+        self.read_frame(aovs)?.save(path.as_ref(), format)
+    }
+
+    // the same layers, kept in memory
+    pub fn read_frame(&mut self, aovs: CapturedAovs) -> anyhow::Result<CapturedFrame> {
+        self.renderer.capture_frame(aovs)
+    }
+
+    // the Monte Carlo strategy accumulates the direct, the indirect and the emitted light apart, for
+    // 'CapturedAovs::LIGHTING'; the layers take three more pixel buffers, and the accumulation restarts
+    pub fn set_lighting_aovs(&mut self, enabled: bool) {
+        self.renderer.set_lighting_aovs(enabled);
+    }
+
+    #[must_use]
+    pub fn lighting_aovs(&self) -> bool {
+        self.renderer.lighting_aovs()
+    }
+
+    // the byte sizes of the scene buffers against the adapter binding limits
+    #[must_use]
+    pub fn buffers_statistics(&self) -> SceneBuffersStatistics {
+        self.renderer.buffers_statistics()
+    }
+
+    // the object counts, the bvh shape, the buffer sizes and the gpu time of the compute passes, for debug overlays
+    #[must_use]
+    pub fn statistics(&self) -> EngineStatistics {
+        self.renderer.statistics()
+    }
+
+    // the gpu time per pass, averaged over the recent frames; none when the adapter has no timestamp queries
+    #[must_use]
+    pub fn gpu_passes_report(&self) -> Option<GpuPassesReport> {
+        self.renderer.gpu_passes_report()
+    }
+
+    // the byte counts and the times of the scene buffer updates of the last rendered frame
+    #[must_use]
+    pub fn last_frame_stats(&self) -> FrameStatistics {
+        self.renderer.last_frame_statistics()
+    }
+
+    // of the pipelines made since the engine (or its device, after a loss) was created; see 'PipelinesWarmUp'
+    #[must_use]
+    pub fn pipeline_cache_statistics(&self) -> PipelineCacheStatistics {
+        self.renderer.pipeline_cache_statistics()
     }
 
     #[must_use]
@@ -317,16 +796,333 @@ impl Engine {
     pub fn objects(&mut self) -> &mut Hub {
         self.renderer.objects()
     }
-    
+
+    // the 2d primitives drawn over the presented image, in the output pixels
+    #[must_use]
+    pub fn overlay(&mut self) -> &mut Overlay {
+        self.renderer.overlay()
+    }
+
     pub fn upload_texture_atlas_page(&mut self, data: &[u8]) {
         self.renderer.upload_texture_atlas_page(data, None);
     }
-    
+
+    #[cfg(feature = "monte_carlo")]
     pub fn use_monte_carlo_render(&mut self) {
-        self.renderer.set_render_strategy(RenderStrategyId::MonteCarlo, PIXEL_SUBDIVISION_MONTE_CARLO);
+        self.renderer.set_render_strategy(RenderStrategyId::MonteCarlo);
     }
-    
+
+    // the same paths split between small kernels linked by gpu queues; the wavefront keeps a single path
+    // per pixel, so a frame takes one sample per pixel whatever the antialiasing level
+    #[cfg(feature = "monte_carlo")]
+    pub fn use_wavefront_monte_carlo_render(&mut self) {
+        self.renderer.set_render_strategy(RenderStrategyId::Wavefront);
+    }
+
     pub fn use_deterministic_render(&mut self) {
-        self.renderer.set_render_strategy(RenderStrategyId::Deterministic, PIXEL_SUBDIVISION_DETERMINISTIC);
+        self.renderer.set_render_strategy(RenderStrategyId::Deterministic);
+    }
+
+    #[must_use]
+    pub fn render_strategy(&self) -> RenderStrategyId {
+        self.renderer.render_strategy()
+    }
+
+    // the stored config of the strategy, or its default one when it has not been set
+    #[must_use]
+    pub fn render_strategy_config(&self, strategy: RenderStrategyId) -> RenderStrategyConfig {
+        self.renderer.render_strategy_config(strategy)
+    }
+
+    // takes effect at once for the strategy in use, or when the strategy is switched to
+    pub fn set_render_strategy_config(&mut self, strategy: RenderStrategyId, config: RenderStrategyConfig) {
+        self.renderer.set_render_strategy_config(strategy, config);
+    }
+
+    // the level is the pixel side subdivision of the strategy in use: each pixel gets 'level * level' samples
+    pub fn set_antialiasing(&mut self, level: u32) {
+        assert_antialiasing_level_supported(level);
+        self.renderer.set_antialiasing_level(level);
+    }
+
+    pub fn set_color_grading(&mut self, color_grading: ColorGrading) {
+        self.renderer.set_color_grading(color_grading);
+    }
+
+    // the tone mapping operator, the exposure and the gamma of the output; the accumulation goes on
+    pub fn set_display_mapping(&mut self, display_mapping: DisplayMapping) {
+        self.renderer.set_display_mapping(display_mapping);
+    }
+
+    // of the 8 bit outputs, against the banding of the smooth gradients; the accumulation goes on
+    pub fn set_output_dithering(&mut self, dithering: OutputDithering) {
+        self.renderer.set_output_dithering(dithering);
+    }
+
+    #[must_use]
+    pub fn output_dithering(&self) -> OutputDithering {
+        self.renderer.output_dithering()
+    }
+
+    // the algorithm of the random numbers the tracing samples with; the accumulation starts over
+    pub fn set_random_generator(&mut self, generator: RandomGenerator) {
+        self.renderer.set_random_generator(generator);
+    }
+
+    #[must_use]
+    pub fn random_generator(&self) -> RandomGenerator {
+        self.renderer.random_generator()
+    }
+
+    // the tile (a blue noise one, typically) rotating the random numbers of the Monte Carlo pixels;
+    // 'None' goes back to the plain sequences, the accumulation starts over
+    pub fn set_random_seed_texture(&mut self, texture: Option<RandomSeedTexture>) {
+        self.renderer.set_random_seed_texture(texture);
+    }
+
+    #[must_use]
+    pub fn random_seed_texture(&self) -> Option<&RandomSeedTexture> {
+        self.renderer.random_seed_texture()
+    }
+
+    #[must_use]
+    pub fn display_mapping(&self) -> DisplayMapping {
+        self.renderer.display_mapping()
+    }
+
+    pub fn use_auto_exposure(&mut self, auto_exposure: Option<AutoExposure>) {
+        self.renderer.set_auto_exposure(auto_exposure);
+    }
+
+    #[must_use]
+    pub fn auto_exposure(&self) -> Option<AutoExposure> {
+        self.renderer.auto_exposure()
+    }
+
+    // the clamps of the measured exposure, in stops (EV) relative to the middle gray; turns the auto exposure
+    // on with the default adaptation speed, if it is off
+    pub fn set_auto_exposure_range(&mut self, min_stops: f32, max_stops: f32) {
+        let auto_exposure = self.renderer.auto_exposure().unwrap_or_default().with_exposure_range(min_stops, max_stops);
+        self.renderer.set_auto_exposure(Some(auto_exposure));
+    }
+
+    // the bright pixels glow over their neighbourhood; 'None' turns the bloom off
+    pub fn use_bloom(&mut self, bloom: Option<Bloom>) {
+        self.renderer.set_bloom(bloom);
+    }
+
+    // on camera movement, re-uses the Monte Carlo samples accumulated so far instead of restarting; off by default,
+    // the pixels the previous frame has not seen restart from a single noisy sample, the reflections lag behind
+    #[cfg(feature = "monte_carlo")]
+    pub fn use_temporal_reprojection(&mut self, enabled: bool) {
+        self.renderer.set_temporal_reprojection(enabled);
+    }
+
+    // the Monte Carlo paths connect each diffuse bounce to the emissive parallelograms by a shadow ray
+    #[cfg(feature = "monte_carlo")]
+    pub fn use_next_event_estimation(&mut self, enabled: bool) {
+        self.renderer.set_next_event_estimation(enabled);
+    }
+
+    // samples animated sdf at the different moments between frames in each accumulation pass:
+    // fast animations get motion blur instead of strobing at low frame rates
+    pub fn use_sub_frame_animation(&mut self, enabled: bool) {
+        self.renderer.set_sub_frame_animation(enabled);
+    }
+
+    // smaller scene buffers (a vertex takes half, a bvh node two thirds of the memory) at the cost
+    // of the geometry accuracy: vertices snap to 1/65535 of their mesh box, normals get 10 bits per axis
+    pub fn use_quantized_geometry(&mut self, enabled: bool) {
+        self.renderer.set_quantized_geometry(enabled);
+    }
+
+    pub fn use_probe_grid_global_illumination(&mut self, probe_grid: Option<ProbeGrid>) {
+        self.renderer.set_probe_grid(probe_grid);
+    }
+
+    // the deterministic strategy lights the scene by its key light alone (the first traced emissive parallelogram)
+    pub fn use_key_light_shadows(&mut self, shadows: KeyLightShadows) {
+        self.renderer.set_key_light_shadows(shadows);
+    }
+
+    pub fn use_background(&mut self, background: Option<BackgroundUid>) {
+        self.renderer.set_background(background);
+    }
+
+    // rgb is the radiance of the rays missing the scene while no background is used;
+    // alpha is written to the pixels where no object is visible
+    pub fn set_clear_color(&mut self, r: f32, g: f32, b: f32, alpha: f32) {
+        self.renderer.set_clear_color([r, g, b, alpha]);
+    }
+
+    // the Monte Carlo accumulation stops sampling the converged image, which the frames keep showing;
+    // 'None' accumulates for as long as nothing changes
+    pub fn set_stop_criterion(&mut self, criterion: Option<StopCriterion>) {
+        self.renderer.set_stop_criterion(criterion);
+    }
+
+    #[must_use]
+    pub fn stop_criterion(&self) -> Option<StopCriterion> {
+        self.renderer.stop_criterion()
+    }
+
+    // the relative error estimated at the last doubling of the samples count, see 'StopCriterion'
+    #[must_use]
+    pub fn convergence_error(&self) -> Option<f64> {
+        self.renderer.convergence_error()
+    }
+
+    #[must_use]
+    pub fn converged(&self) -> bool {
+        self.renderer.accumulation_converged()
+    }
+
+    // debugging aid: an inset with the zoomed pixels around the given frame buffer pixel
+    pub fn set_magnifier(&mut self, magnifier: Option<Magnifier>) {
+        self.renderer.set_magnifier(magnifier);
+    }
+
+    // the objects get an outline of the color (straight alpha) drawn over the final image; an empty slice removes it
+    pub fn set_highlighted_objects(&mut self, objects: &[ObjectUid], color: [f32; 4]) {
+        self.renderer.set_highlighted_objects(objects, color);
+    }
+
+    // the sdf instances smaller on screen than the threshold are shaded as their bounding spheres
+    pub fn set_sdf_level_of_detail(&mut self, sdf_level_of_detail: Option<SdfLevelOfDetail>) {
+        self.renderer.set_sdf_level_of_detail(sdf_level_of_detail);
+    }
+
+    // only the objects on the layers of the mask are traced (all of them by default), see 'Hub::set_layer_mask'
+    pub fn set_camera_layer_mask(&mut self, mask: LayerMask) {
+        self.renderer.set_camera_layer_mask(mask);
+    }
+
+    // the unprocessed accumulated values of the magnified pixels; none while the magnifier is off
+    #[must_use]
+    pub fn magnifier_readout(&mut self) -> Option<MagnifierReadout> {
+        self.renderer.magnifier_readout()
+    }
+}
+
+// batch rendering without a window: the final (tone mapped and graded) image is read back into memory
+pub struct OffscreenEngine {
+    frame_buffer_size: FrameBufferSize,
+    controls: RendererControls,
+    buffers_reporter: TimeThrottledInfoLogger,
+}
+
+impl OffscreenEngine {
+    pub async fn new(width: u32, height: u32, scene: VisualObjects, camera: Camera, caches_path: Option<PathBuf>) -> Result<OffscreenEngine, EngineInstantiationError> {
+        assert!(width > 0 && height > 0, "offscreen image must not be empty");
+        let context = Self::create_context().await?;
+        Self::with_context(context, width, height, scene, camera, caches_path)
+    }
+
+    async fn create_context() -> Result<Rc<Context>, EngineInstantiationError> {
+        let wgpu_instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: backend_vulkan_or_primary(),
+            ..Default::default()
+        });
+        Ok(Rc::new(create_headless_wgpu_device(&wgpu_instance, OFFSCREEN_DEVICE_LABEL).await?))
+    }
+
+    // renders on the device of the test context instead of a private one
+    #[cfg(feature = "gpu_testing")]
+    pub fn with_headless_context(context: &HeadlessContext, width: u32, height: u32, scene: VisualObjects, camera: Camera, caches_path: Option<PathBuf>) -> Result<OffscreenEngine, EngineInstantiationError> {
+        assert!(width > 0 && height > 0, "offscreen image must not be empty");
+        Self::with_context(context.context(), width, height, scene, camera, caches_path)
+    }
+
+    fn with_context(context: Rc<Context>, width: u32, height: u32, scene: VisualObjects, camera: Camera, caches_path: Option<PathBuf>) -> Result<OffscreenEngine, EngineInstantiationError> {
+        let frame_buffer_size = FrameBufferSize::new(width, height);
+        let frame_buffer_settings = FrameBufferSettings::new(OFFSCREEN_OUTPUT_FORMAT, frame_buffer_size, RenderStrategyConfig::PIXEL_SUBDIVISION_DETERMINISTIC,);
+        let renderer
+            = Renderer::new(
+                context,
+                scene,
+                camera,
+                frame_buffer_settings,
+                RenderStrategyId::Deterministic,
+                caches_path,
+            )
+            .map_err(|e| EngineInstantiationError::InternalError {what: e.to_string()})?;
+
+        Ok(OffscreenEngine { frame_buffer_size, controls: RendererControls { renderer }, buffers_reporter: TimeThrottledInfoLogger::new(BUFFERS_WARNING_INTERVAL) })
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.frame_buffer_size.width()
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.frame_buffer_size.height()
+    }
+
+    // returns 'width * height' pixels, 4 bytes (RGBA) each, rows go from top to bottom
+    #[must_use]
+    pub fn render(&mut self, accumulation_passes: usize) -> Vec<u8> {
+        assert_ge!(accumulation_passes, 1, "at least one accumulation pass expected");
+
+        self.controls.renderer.start_new_frame();
+        for pass in 0..accumulation_passes {
+            self.controls.renderer.set_sub_frame((pass + 1) as f64 / accumulation_passes as f64);
+            self.controls.renderer.accumulate_more_rays();
+        }
+
+        #[cfg(feature = "denoiser")] {
+            if self.controls.renderer.is_monte_carlo() && self.controls.renderer.render_strategy_config(self.controls.renderer.render_strategy()).denoise() && self.controls.renderer.denoising() {
+                // an image rendered into memory is a final one: it is always denoised
+                let every_frame = 1;
+                self.controls.renderer.denoise_accumulated_image(every_frame, &mut MinMaxTimeMeasurer::default());
+            }
+        }
+
+        write_buffers_warning(&self.controls.renderer, &mut self.buffers_reporter);
+
+        self.controls.renderer.present_into_memory()
+    }
+
+    // the Monte Carlo samples accumulated so far and their count; blocks till the gpu is done,
+    // fails for the deterministic and the half resolution indirect renders
+    pub fn save_accumulation_checkpoint(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.controls.renderer.make_accumulation_checkpoint()?.save(path.as_ref())
+    }
+
+    // the next 'render' continues the saved accumulation: the engine is to have the scene, the camera,
+    // the size and the render strategy of the one that has saved it
+    pub fn resume_accumulation_from(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let checkpoint = AccumulationCheckpoint::load(path.as_ref())?;
+        self.controls.renderer.resume_accumulation(checkpoint)
+    }
+
+    pub fn render_to_png(&mut self, accumulation_passes: usize, path: impl AsRef<Path>) -> image::ImageResult<()> {
+        let pixels = self.render(accumulation_passes);
+        image::save_buffer(path, &pixels, self.width(), self.height(), image::ExtendedColorType::Rgba8)
+    }
+
+    // debugging aid: the bounces of the path through the frame pixel (the first sample of the last
+    // Monte Carlo frame traced once more); waits for the gpu
+    #[cfg(feature = "monte_carlo")]
+    #[must_use]
+    pub fn trace_pixel_path(&mut self, x: u32, y: u32) -> PixelPath {
+        assert_lt!(x, self.width());
+        assert_lt!(y, self.height());
+        self.controls.renderer.trace_pixel_path(x, y).expect("offscreen frame has no bars")
+    }
+}
+
+impl Deref for OffscreenEngine {
+    type Target = RendererControls;
+
+    fn deref(&self) -> &RendererControls {
+        &self.controls
+    }
+}
+
+impl DerefMut for OffscreenEngine {
+    fn deref_mut(&mut self) -> &mut RendererControls {
+        &mut self.controls
     }
 }
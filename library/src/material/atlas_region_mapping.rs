@@ -5,7 +5,7 @@ use crate::serialization::serializable_for_gpu::{GpuSerializable, GpuSerializati
 use cgmath::Vector4;
 
 #[repr(i32)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum WrapMode {
     Repeat = 0,
     Clamp = 1,
@@ -20,6 +20,7 @@ pub(crate) struct AtlasRegionMapping {
     wrap_mode: [WrapMode; COMPONENTS_IN_TEXTURE_COORDINATE],
 }
 
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct AtlasRegionMappingBuilder {
     local_position_to_texture_u: Vector4<f32>,
     local_position_to_texture_v: Vector4<f32>,
@@ -56,6 +57,11 @@ impl AtlasRegionMappingBuilder {
         self
     }
 
+    #[must_use]
+    pub(crate) fn local_position_to_texture(&self) -> (Vector4<f32>, Vector4<f32>) {
+        (self.local_position_to_texture_u, self.local_position_to_texture_v)
+    }
+
     #[must_use]
     pub(crate) fn build(self, area: TextureRegion) -> AtlasRegionMapping {
         AtlasRegionMapping {
@@ -0,0 +1,113 @@
+use crate::material::material_properties::{MaterialClass, MaterialProperties};
+use crate::material::texture_reference::TextureReference;
+
+// presets of the common materials; the chained calls tune them further
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MaterialBuilder {
+    properties: MaterialProperties,
+}
+
+impl MaterialBuilder {
+    const GLASS_REFRACTIVE_INDEX: f64 = 1.5;
+
+    #[must_use]
+    pub fn matte(r: f32, g: f32, b: f32) -> Self {
+        Self { properties: MaterialProperties::new().with_albedo(r, g, b) }
+    }
+
+    #[must_use]
+    pub fn mirror(r: f32, g: f32, b: f32) -> Self {
+        Self { properties: MaterialProperties::new().with_class(MaterialClass::Mirror).with_albedo(r, g, b) }
+    }
+
+    // the refractive index is the one of a window glass; 'refractive_index' changes it
+    #[must_use]
+    pub fn glass(r: f32, g: f32, b: f32) -> Self {
+        Self {
+            properties: MaterialProperties::new()
+                .with_class(MaterialClass::Glass)
+                .with_albedo(r, g, b)
+                .with_refractive_index_eta(Self::GLASS_REFRACTIVE_INDEX)
+        }
+    }
+
+    // reflects nothing: the surface is the light source only
+    #[must_use]
+    pub fn light(r: f32, g: f32, b: f32) -> Self {
+        Self { properties: MaterialProperties::new().with_emission(r, g, b) }
+    }
+
+    #[must_use]
+    pub fn albedo(self, r: f32, g: f32, b: f32) -> Self {
+        Self { properties: self.properties.with_albedo(r, g, b) }
+    }
+
+    #[must_use]
+    pub fn specular(self, r: f32, g: f32, b: f32, strength: f64) -> Self {
+        Self { properties: self.properties.with_specular(r, g, b).with_specular_strength(strength) }
+    }
+
+    #[must_use]
+    pub fn emission(self, r: f32, g: f32, b: f32) -> Self {
+        Self { properties: self.properties.with_emission(r, g, b) }
+    }
+
+    #[must_use]
+    pub fn roughness(self, roughness: f64) -> Self {
+        Self { properties: self.properties.with_roughness(roughness) }
+    }
+
+    #[must_use]
+    pub fn refractive_index(self, refractive_index_eta: f64) -> Self {
+        Self { properties: self.properties.with_refractive_index_eta(refractive_index_eta) }
+    }
+
+    #[must_use]
+    pub fn absorption(self, r: f32, g: f32, b: f32) -> Self {
+        Self { properties: self.properties.with_absorption(r, g, b) }
+    }
+
+    #[must_use]
+    pub fn albedo_texture(self, reference: TextureReference) -> Self {
+        Self { properties: self.properties.with_albedo_texture(reference) }
+    }
+
+    #[must_use]
+    pub fn build(self) -> MaterialProperties {
+        self.properties
+    }
+}
+
+impl From<MaterialBuilder> for MaterialProperties {
+    fn from(builder: MaterialBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(MaterialBuilder::matte(0.5, 0.25, 0.125).build(), MaterialProperties::new().with_albedo(0.5, 0.25, 0.125));
+        assert_eq!(MaterialBuilder::light(2.0, 2.0, 2.0).build(), MaterialProperties::new().with_emission(2.0, 2.0, 2.0));
+        assert_eq!(
+            MaterialBuilder::glass(0.0, 0.5, 0.9).refractive_index(1.4).build(),
+            MaterialProperties::new().with_class(MaterialClass::Glass).with_albedo(0.0, 0.5, 0.9).with_refractive_index_eta(1.4),
+        );
+    }
+
+    #[test]
+    fn test_tuning() {
+        let actual: MaterialProperties = MaterialBuilder::mirror(1.0, 0.5, 0.0).roughness(0.25).specular(0.2, 0.2, 0.2, 0.5).into();
+
+        let expected = MaterialProperties::new()
+            .with_class(MaterialClass::Mirror)
+            .with_albedo(1.0, 0.5, 0.0)
+            .with_roughness(0.25)
+            .with_specular(0.2, 0.2, 0.2)
+            .with_specular_strength(0.5);
+        assert_eq!(actual, expected);
+    }
+}
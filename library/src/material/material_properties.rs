@@ -1,5 +1,9 @@
+use crate::material::bitmap_texture_index::BitmapTextureIndex;
+use crate::material::procedural_texture_baking::ProceduralTextureBaking;
+use crate::material::procedural_texture_index::ProceduralTextureUid;
+use crate::material::surface_shader_uid::SurfaceShaderUid;
 use crate::material::texture_reference::TextureReference;
-use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use crate::serialization::gpu_ready_serialization_buffer::{GpuReadySerializationBuffer, DEFAULT_PAD_VALUE};
 use crate::serialization::serializable_for_gpu::{GpuSerializable, GpuSerializationSize};
 use more_asserts::{assert_ge, assert_le};
 use palette::Srgb;
@@ -11,6 +15,7 @@ pub enum MaterialClass {
     Lambert,
     Mirror,
     Glass,
+    SurfaceShader = 4,
 }
 
 impl Default for MaterialClass {
@@ -34,7 +39,12 @@ pub struct MaterialProperties {
     specular_strength: f64,
     roughness: f64,
     refractive_index_eta: f64,
+    absorption: [f32; 3],
     albedo_texture: TextureReference,
+    albedo_baking: Option<ProceduralTextureBaking>,
+    baked_albedo_texture: Option<BitmapTextureIndex>,
+    normal_texture: TextureReference,
+    surface_shader: Option<SurfaceShaderUid>,
     class: MaterialClass,
 }
 
@@ -51,6 +61,11 @@ impl MaterialProperties {
         self.albedo_texture
     }
 
+    #[must_use]
+    pub fn normal_texture(&self) -> TextureReference {
+        self.normal_texture
+    }
+
     pub fn with_albedo(mut self, r: f32, g: f32, b: f32) -> Self {
         assert_ge!(r, 0.0);
         assert_ge!(g, 0.0);
@@ -93,6 +108,16 @@ impl MaterialProperties {
         self
     }
 
+    // the share of the light the glass absorbs per unit of the distance travelled inside it (Beer–Lambert):
+    // a thick glass gets the darker and the more saturated
+    pub fn with_absorption(mut self, r: f32, g: f32, b: f32) -> Self {
+        assert_ge!(r, 0.0);
+        assert_ge!(g, 0.0);
+        assert_ge!(b, 0.0);
+        self.absorption = [r, g, b];
+        self
+    }
+
     pub fn with_class(mut self, class: MaterialClass) -> Self {
         self.class = class;
         self
@@ -105,27 +130,104 @@ impl MaterialProperties {
 
     pub fn set_albedo_texture(&mut self, reference: TextureReference) {
         self.albedo_texture = reference;
+        self.baked_albedo_texture = None;
+    }
+
+    // the procedural albedo texture gets baked into an atlas region before the next frame
+    pub fn with_albedo_baking(mut self, baking: ProceduralTextureBaking) -> Self {
+        self.set_albedo_baking(Some(baking));
+        self
+    }
+
+    // none: the texture is evaluated at every hit again; the region of the previous baking stays allocated
+    pub fn set_albedo_baking(&mut self, baking: Option<ProceduralTextureBaking>) {
+        self.albedo_baking = baking;
+        self.baked_albedo_texture = None;
+    }
+
+    #[must_use]
+    pub(crate) fn pending_albedo_baking(&self) -> Option<(ProceduralTextureUid, ProceduralTextureBaking)> {
+        let TextureReference::Procedural(texture) = self.albedo_texture else {
+            return None;
+        };
+        if self.baked_albedo_texture.is_some() {
+            return None;
+        }
+        self.albedo_baking.map(|baking| (texture, baking))
+    }
+
+    pub(crate) fn set_baked_albedo_texture(&mut self, region: BitmapTextureIndex) {
+        assert!(self.pending_albedo_baking().is_some(), "material does not wait for a baking");
+        self.baked_albedo_texture = Some(region);
+    }
+
+    // the one the gpu samples: the baked region stands in for the procedural texture
+    #[must_use]
+    pub(crate) fn effective_albedo_texture(&self) -> TextureReference {
+        match (self.albedo_baking, self.baked_albedo_texture) {
+            (Some(_), Some(baked)) => TextureReference::Bitmap(baked),
+            _ => self.albedo_texture,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn albedo(&self) -> Srgb {
+        self.albedo
+    }
+
+    // tangent space normals in the OpenGL convention: the green channel points against the growing v
+    pub fn with_normal_texture(mut self, reference: TextureReference) -> Self {
+        self.set_normal_texture(reference);
+        self
+    }
+
+    pub fn set_normal_texture(&mut self, reference: TextureReference) {
+        assert!(false == matches!(reference, TextureReference::Procedural(_)), "normal maps can be bitmaps only");
+        self.normal_texture = reference;
+    }
+
+    pub fn with_surface_shader(mut self, shader: SurfaceShaderUid) -> Self {
+        self.surface_shader = Some(shader);
+        self.class = MaterialClass::SurfaceShader;
+        self
+    }
+
+    #[must_use]
+    pub fn surface_shader(&self) -> Option<SurfaceShaderUid> {
+        self.surface_shader
+    }
+
+    #[must_use]
+    pub(crate) fn emission(&self) -> Srgb {
+        self.emission
+    }
+
+    #[must_use]
+    pub(crate) fn emissive(&self) -> bool {
+        self.emission != Self::ZERO_COLOR
     }
 }
 
 impl GpuSerializationSize for MaterialProperties {
-    const SERIALIZED_QUARTET_COUNT: usize = 4;
+    const SERIALIZED_QUARTET_COUNT: usize = 5;
 }
 
 impl GpuSerializable for MaterialProperties {
     fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
         debug_assert!(container.has_free_slot(), "buffer overflow");
 
-        container.write_padded_quartet_f32(
-            self.albedo.red,
-            self.albedo.green,
-            self.albedo.blue,
-        );
-        container.write_padded_quartet_f32(
-            self.emission.red,
-            self.emission.green,
-            self.emission.blue,
-        );
+        container.write_quartet(|writer| {
+            writer.write_float_32(self.albedo.red);
+            writer.write_float_32(self.albedo.green);
+            writer.write_float_32(self.albedo.blue);
+            writer.write_signed(self.surface_shader.map_or(0, |uid| uid.0 as i32));
+        });
+        container.write_quartet(|writer| {
+            writer.write_float_32(self.emission.red);
+            writer.write_float_32(self.emission.green);
+            writer.write_float_32(self.emission.blue);
+            writer.write_signed(self.normal_texture.as_gpu_readable_index());
+        });
         container.write_quartet_f32(
             self.specular.red,
             self.specular.green,
@@ -135,9 +237,10 @@ impl GpuSerializable for MaterialProperties {
         container.write_quartet(|writer| {
             writer.write_float_64(self.roughness);
             writer.write_float_64(self.refractive_index_eta);
-            writer.write_signed(self.albedo_texture.as_gpu_readable_index());
+            writer.write_signed(self.effective_albedo_texture().as_gpu_readable_index());
             writer.write_signed(self.class.as_i32());
         });
+        container.write_quartet_f32(self.absorption[0], self.absorption[1], self.absorption[2], DEFAULT_PAD_VALUE);
 
         debug_assert!(container.object_fully_written());
     }
@@ -152,7 +255,12 @@ impl Default for MaterialProperties {
             specular_strength: 0.0,
             roughness: 0.0,
             refractive_index_eta: 0.0,
+            absorption: [0.0; 3],
             albedo_texture: TextureReference::None,
+            albedo_baking: None,
+            baked_albedo_texture: None,
+            normal_texture: TextureReference::None,
+            surface_shader: None,
             class: MaterialClass::Lambert,
         }
     }
@@ -162,7 +270,7 @@ impl Default for MaterialProperties {
 mod tests {
     use super::*;
     use crate::material::procedural_texture_index::ProceduralTextureUid;
-    use crate::serialization::gpu_ready_serialization_buffer::DEFAULT_PAD_VALUE;
+    use crate::material::bitmap_texture_index::BitmapTextureIndex;
     use bytemuck::cast_slice;
     use strum::IntoEnumIterator;
 
@@ -174,9 +282,11 @@ mod tests {
         let expected_specular_strength = 0.5;
         let expected_roughness = 0.7;
         let expected_refractive_index = 1.5;
+        let expected_absorption = [0.1, 0.2, 0.3];
         let expected_class = MaterialClass::Glass;
         let expected_texture_reference = TextureReference::Procedural(ProceduralTextureUid(13));
-        
+        let expected_normal_texture_reference = TextureReference::Bitmap(BitmapTextureIndex(5));
+
         let system_under_test = MaterialProperties::new()
             .with_albedo(expected_albedo.red, expected_albedo.green, expected_albedo.blue)
             .with_specular(expected_specular.red, expected_specular.green, expected_specular.blue)
@@ -184,7 +294,9 @@ mod tests {
             .with_specular_strength(expected_specular_strength)
             .with_roughness(expected_roughness)
             .with_refractive_index_eta(expected_refractive_index)
+            .with_absorption(expected_absorption[0], expected_absorption[1], expected_absorption[2])
             .with_albedo_texture(expected_texture_reference)
+            .with_normal_texture(expected_normal_texture_reference)
             .with_class(expected_class);
 
         let mut container = GpuReadySerializationBuffer::new(1, MaterialProperties::SERIALIZED_QUARTET_COUNT);
@@ -195,12 +307,12 @@ mod tests {
         assert_eq!(f32::from_bits(serialized[ 0]),  expected_albedo.red);
         assert_eq!(f32::from_bits(serialized[ 1]),  expected_albedo.green);
         assert_eq!(f32::from_bits(serialized[ 2]),  expected_albedo.blue);
-        assert_eq!(f32::from_bits(serialized[ 3]),  DEFAULT_PAD_VALUE);
+        assert_eq!(i32::from_ne_bytes(serialized[ 3].to_ne_bytes()), 0);
 
         assert_eq!(f32::from_bits(serialized[4]),  expected_emission.red);
         assert_eq!(f32::from_bits(serialized[5]),  expected_emission.green);
         assert_eq!(f32::from_bits(serialized[6]),  expected_emission.blue);
-        assert_eq!(f32::from_bits(serialized[7]),  DEFAULT_PAD_VALUE);
+        assert_eq!(i32::from_ne_bytes(serialized[7].to_ne_bytes()), expected_normal_texture_reference.as_gpu_readable_index());
 
         assert_eq!(f32::from_bits(serialized[ 8]),  expected_specular.red);
         assert_eq!(f32::from_bits(serialized[ 9]),  expected_specular.green);
@@ -211,6 +323,11 @@ mod tests {
         assert_eq!(f32::from_bits(serialized[13]), expected_refractive_index as f32);
         assert_eq!(i32::from_ne_bytes(serialized[14].to_ne_bytes()), expected_texture_reference.as_gpu_readable_index());
         assert_eq!(i32::from_ne_bytes(serialized[15].to_ne_bytes()), expected_class.as_i32());
+
+        assert_eq!(f32::from_bits(serialized[16]), expected_absorption[0]);
+        assert_eq!(f32::from_bits(serialized[17]), expected_absorption[1]);
+        assert_eq!(f32::from_bits(serialized[18]), expected_absorption[2]);
+        assert_eq!(f32::from_bits(serialized[19]), DEFAULT_PAD_VALUE);
     }
 
     #[test]
@@ -230,9 +347,27 @@ mod tests {
         assert_eq!(system_under_test.specular_strength, 0.0);
         assert_eq!(system_under_test.roughness, 0.0);
         assert_eq!(system_under_test.refractive_index_eta, 0.0);
+        assert_eq!(system_under_test.absorption, [0.0; 3]);
+        assert_eq!(system_under_test.normal_texture, TextureReference::None);
+        assert_eq!(system_under_test.surface_shader, None);
         assert_eq!(system_under_test.class, MaterialClass::Lambert);
     }
 
+    #[test]
+    fn test_material_with_surface_shader() {
+        let expected_shader = SurfaceShaderUid(3);
+        let system_under_test = MaterialProperties::default().with_surface_shader(expected_shader);
+
+        assert_eq!(system_under_test.surface_shader(), Some(expected_shader));
+        assert_eq!(system_under_test.class, MaterialClass::SurfaceShader);
+
+        let mut container = GpuReadySerializationBuffer::new(1, MaterialProperties::SERIALIZED_QUARTET_COUNT);
+        system_under_test.serialize_into(&mut container);
+        let serialized: &[i32] = cast_slice(container.backend());
+        assert_eq!(serialized[3], expected_shader.0 as i32);
+        assert_eq!(serialized[15], MaterialClass::SurfaceShader.as_i32());
+    }
+
     #[test]
     fn test_material_with_albedo() {
         let expected_albedo = Srgb::new(0.5, 0.6, 0.7);
@@ -275,10 +410,44 @@ mod tests {
         assert_eq!(system_under_test, MaterialProperties { refractive_index_eta: expected_refractive_index, ..Default::default() });
     }
 
+    #[test]
+    fn test_material_with_absorption() {
+        let system_under_test = MaterialProperties::default().with_absorption(0.1, 0.2, 0.3);
+        assert_eq!(system_under_test, MaterialProperties { absorption: [0.1, 0.2, 0.3], ..Default::default() });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_material_with_negative_absorption() {
+        let _ = MaterialProperties::default().with_absorption(0.1, -0.2, 0.3);
+    }
+
     #[test]
     fn test_material_with_class() {
         let expected_class = MaterialClass::Glass;
         let system_under_test = MaterialProperties::default().with_class(expected_class);
         assert_eq!(system_under_test, MaterialProperties { class: expected_class, ..Default::default() });
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[should_panic]
+    fn test_procedural_normal_texture() {
+        let _ = MaterialProperties::default().with_normal_texture(TextureReference::Procedural(ProceduralTextureUid(1)));
+    }
+
+    #[test]
+    fn test_albedo_baking() {
+        let procedural = TextureReference::Procedural(ProceduralTextureUid(3));
+        let baking = ProceduralTextureBaking::new(crate::utils::bitmap_utils::BitmapSize::new(4, 4), Default::default());
+        let mut system_under_test = MaterialProperties::default().with_albedo_texture(procedural).with_albedo_baking(baking);
+        assert_eq!(system_under_test.pending_albedo_baking(), Some((ProceduralTextureUid(3), baking)));
+        assert_eq!(system_under_test.effective_albedo_texture(), procedural);
+
+        system_under_test.set_baked_albedo_texture(BitmapTextureIndex(2));
+        assert_eq!(system_under_test.pending_albedo_baking(), None);
+        assert_eq!(system_under_test.effective_albedo_texture(), TextureReference::Bitmap(BitmapTextureIndex(2)));
+
+        system_under_test.set_albedo_baking(None);
+        assert_eq!(system_under_test.effective_albedo_texture(), procedural);
+    }
+}
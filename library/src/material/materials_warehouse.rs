@@ -1,6 +1,9 @@
 use crate::material::material_index::MaterialIndex;
+use crate::material::bitmap_texture_index::BitmapTextureIndex;
 use crate::material::material_properties::MaterialProperties;
+use crate::material::procedural_texture_baking::ProceduralTextureBaking;
 use crate::material::procedural_textures::ProceduralTextures;
+use crate::material::surface_shaders::SurfaceShaders;
 use crate::material::texture_atlas_regions_warehouse::TextureAtlasRegionsWarehouse;
 use crate::material::texture_reference::TextureReference;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
@@ -13,16 +16,18 @@ use std::rc::Rc;
 pub struct MaterialsWarehouse {
     materials: Vec<MaterialProperties>,
     procedural_textures: Option<ProceduralTextures>,
+    surface_shaders: Option<SurfaceShaders>,
     texture_atlas_regions: Rc<RefCell<TextureAtlasRegionsWarehouse>>,
     data_version: Version,
 }
 
 impl MaterialsWarehouse {
     #[must_use]
-    pub(crate) fn new(procedural_textures: Option<ProceduralTextures>) -> Self {
+    pub(crate) fn new(procedural_textures: Option<ProceduralTextures>, surface_shaders: Option<SurfaceShaders>) -> Self {
         Self {
             materials: Vec::new(),
             procedural_textures,
+            surface_shaders,
             texture_atlas_regions: Rc::new(RefCell::new(TextureAtlasRegionsWarehouse::new())),
             data_version: Version(0),
         }
@@ -30,8 +35,13 @@ impl MaterialsWarehouse {
 
     #[must_use]
     pub(crate) fn animated(&self, index: MaterialIndex) -> bool {
-        let albedo_texture = self.materials[index.0].albedo_texture();
-        if let TextureReference::Procedural(id) = albedo_texture
+        let material = &self.materials[index.0];
+        if let Some(id) = material.surface_shader()
+            && let Some(shaders) = &self.surface_shaders
+            && shaders.animated(id) {
+                return true;
+            }
+        if let TextureReference::Procedural(id) = material.effective_albedo_texture()
             && let Some(textures) = &self.procedural_textures {
                 return textures.animated(id);
             }
@@ -45,6 +55,37 @@ impl MaterialsWarehouse {
         MaterialIndex(self.materials.len() - 1)
     }
 
+    // the per material toggle, see 'MaterialProperties::set_albedo_baking'
+    pub fn set_albedo_baking(&mut self, index: MaterialIndex, baking: Option<ProceduralTextureBaking>) {
+        self.materials[index.0].set_albedo_baking(baking);
+        self.data_version += 1;
+    }
+
+    #[must_use]
+    pub(crate) fn pending_albedo_bakings(&self) -> Vec<(MaterialIndex, MaterialProperties)> {
+        self.materials.iter().enumerate()
+            .filter(|(_, material)| material.pending_albedo_baking().is_some())
+            .map(|(index, material)| (MaterialIndex(index), *material))
+            .collect()
+    }
+
+    pub(crate) fn set_baked_albedo_texture(&mut self, index: MaterialIndex, region: BitmapTextureIndex) {
+        self.materials[index.0].set_baked_albedo_texture(region);
+        self.data_version += 1;
+    }
+
+    #[must_use]
+    pub(crate) fn emissive(&self, index: MaterialIndex) -> bool {
+        self.materials[index.0].emissive()
+    }
+
+    // the luminance of the emitted radiance
+    #[must_use]
+    pub(crate) fn emitted_luminance(&self, index: MaterialIndex) -> f64 {
+        let emission = self.materials[index.0].emission();
+        f64::from(0.2126 * emission.red + 0.7152 * emission.green + 0.0722 * emission.blue)
+    }
+
     #[must_use]
     pub(crate) fn count(&self) -> usize {
         self.materials.len()
@@ -69,6 +110,15 @@ impl MaterialsWarehouse {
         }
     }
 
+    #[must_use]
+    pub(crate) fn surface_shaders_code(&self) -> ShaderCode {
+        if let Some(surface_shaders) = &self.surface_shaders {
+            surface_shaders.generate_gpu_code()
+        } else {
+            SurfaceShaders::make_dummy_selection_function()
+        }
+    }
+
     #[must_use]
     pub(crate) fn texture_atlas_regions(&self) -> Rc<RefCell<TextureAtlasRegionsWarehouse>> {
         self.texture_atlas_regions.clone()
@@ -85,7 +135,7 @@ mod tests {
 
     #[test]
     fn test_data_version_materials() {
-        let mut system_under_test = MaterialsWarehouse::new(None);
+        let mut system_under_test = MaterialsWarehouse::new(None, None);
 
         let version_before = system_under_test.data_version();
         let _ = system_under_test.add(&MaterialProperties::default());
@@ -98,7 +148,7 @@ mod tests {
 
     #[test]
     fn test_add_material() {
-        let mut system_under_test = MaterialsWarehouse::new(None);
+        let mut system_under_test = MaterialsWarehouse::new(None, None);
 
         let dummy_material = system_under_test.add(&MaterialProperties::default());
         assert_eq!(system_under_test.count(), 1);
@@ -131,6 +181,38 @@ mod tests {
         assert!(system_under_test.animated(material_with_texture));
     }
 
+    #[test]
+    fn test_baked_texture_is_not_animated() {
+        let texture_body = format!("return vec3f({}, 0.0, 0.0);\n", conventions::PARAMETER_NAME_THE_TIME);
+        let (texture_uid, mut system_under_test) = make_warehouse_with_a_texture(texture_body);
+        let baking = ProceduralTextureBaking::new(crate::utils::bitmap_utils::BitmapSize::new(2, 2), Default::default());
+        let material = system_under_test.add(&MaterialProperties::default()
+            .with_albedo_texture(TextureReference::Procedural(texture_uid))
+            .with_albedo_baking(baking));
+        assert_eq!(system_under_test.pending_albedo_bakings().len(), 1);
+
+        let version_before = system_under_test.data_version();
+        system_under_test.set_baked_albedo_texture(material, BitmapTextureIndex(1));
+
+        assert_ne!(system_under_test.data_version(), version_before);
+        assert!(system_under_test.pending_albedo_bakings().is_empty());
+        assert_eq!(system_under_test.animated(material), false);
+    }
+
+    #[test]
+    fn test_animated_surface_shader() {
+        let mut shaders = SurfaceShaders::new(None);
+        let still = shaders.add(ShaderCode::<FunctionBody>::new("return albedo;".to_string()), None);
+        let animated = shaders.add(ShaderCode::<FunctionBody>::new(format!("return albedo * {};", conventions::PARAMETER_NAME_THE_TIME)), None);
+        let mut system_under_test = MaterialsWarehouse::new(None, Some(shaders));
+
+        let still_material = system_under_test.add(&MaterialProperties::default().with_surface_shader(still));
+        let animated_material = system_under_test.add(&MaterialProperties::default().with_surface_shader(animated));
+
+        assert_eq!(system_under_test.animated(still_material), false);
+        assert!(system_under_test.animated(animated_material));
+    }
+
     #[must_use]
     fn make_warehouse_with_a_texture(texture_body: String) -> (ProceduralTextureUid, MaterialsWarehouse) {
         let mut textures = ProceduralTextures::new(None);
@@ -138,6 +220,6 @@ mod tests {
         let texture = TextureProcedural3D::from_simple_body(ShaderCode::<FunctionBody>::new(texture_code));
         let texture_uid = textures.add(texture, None);
 
-        (texture_uid, MaterialsWarehouse::new(Some(textures)))
+        (texture_uid, MaterialsWarehouse::new(Some(textures), None))
     }
 }
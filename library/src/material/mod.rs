@@ -1,10 +1,14 @@
 pub(crate) mod texture_procedural_3d;
 pub mod texture_reference;
 pub mod material_properties;
+pub mod material_builder;
 pub mod material_index;
 pub mod procedural_texture_index;
 mod bitmap_texture_index;
 pub mod procedural_textures;
+pub mod procedural_texture_baking;
+pub mod surface_shaders;
+pub mod surface_shader_uid;
 pub(crate) mod texture_shader_code;
 pub mod materials_warehouse;
 pub mod texture_procedural_2d;
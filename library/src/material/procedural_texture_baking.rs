@@ -0,0 +1,115 @@
+use crate::material::atlas_region_mapping::AtlasRegionMappingBuilder;
+use crate::material::procedural_texture_index::ProceduralTextureUid;
+use crate::utils::bitmap_utils::{BitmapSize, BYTES_IN_RGBA_QUARTET};
+use cgmath::{Vector3, Zero};
+use palette::{LinSrgb, Srgb};
+
+// the quartets in front of the texels in the gpu buffer, see 'compute_procedural_texture_bake'
+pub(crate) const BAKE_REQUEST_QUARTETS: usize = 3;
+
+const COMPONENTS_IN_BAKED_TEXEL: usize = 4;
+
+// a procedural albedo evaluated once into an atlas region of the resolution, then sampled as a bitmap;
+// the mapping lays the region over the surfaces, so the texels hold the slice of the texture through
+// the plane of the mapping, projected along its normal; an animated texture is frozen at the baking time
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ProceduralTextureBaking {
+    resolution: BitmapSize,
+    mapping: AtlasRegionMappingBuilder,
+}
+
+impl ProceduralTextureBaking {
+    #[must_use]
+    pub fn new(resolution: BitmapSize, mapping: AtlasRegionMappingBuilder) -> Self {
+        let (to_u, to_v) = mapping.local_position_to_texture();
+        assert_ne!(to_u.truncate().cross(to_v.truncate()), Vector3::zero(), "the mapping does not span a plane");
+        Self { resolution, mapping }
+    }
+
+    #[must_use]
+    pub(crate) fn resolution(&self) -> BitmapSize {
+        self.resolution
+    }
+
+    #[must_use]
+    pub(crate) fn mapping(&self) -> AtlasRegionMappingBuilder {
+        self.mapping
+    }
+
+    // the texture uid and the resolution (bits), then the rows of the mapping
+    #[must_use]
+    pub(crate) fn make_request(&self, texture: ProceduralTextureUid) -> [u32; BAKE_REQUEST_QUARTETS * COMPONENTS_IN_BAKED_TEXEL] {
+        let (to_u, to_v) = self.mapping.local_position_to_texture();
+        let texture = i32::try_from(texture.0).expect("texture uid is too big");
+        [
+            texture as u32, self.resolution.width() as u32, self.resolution.height() as u32, 0,
+            to_u.x.to_bits(), to_u.y.to_bits(), to_u.z.to_bits(), to_u.w.to_bits(),
+            to_v.x.to_bits(), to_v.y.to_bits(), to_v.z.to_bits(), to_v.w.to_bits(),
+        ]
+    }
+
+    // the gpu writes the linear colors of the texture, the atlas keeps the srgb texels of the albedo
+    // (the material albedo is the multiplier of the procedural texture, but is replaced by the bitmap one)
+    #[must_use]
+    pub(crate) fn encode_texels(&self, texels: &[f32], albedo: Srgb) -> Vec<u8> {
+        let texels_count = self.resolution.width() * self.resolution.height();
+        assert_eq!(texels.len(), texels_count * COMPONENTS_IN_BAKED_TEXEL);
+
+        let tint = |value: f32, factor: f32| (value * factor).clamp(0.0, 1.0);
+        let mut result = Vec::with_capacity(texels_count * BYTES_IN_RGBA_QUARTET);
+        for texel in texels.chunks_exact(COMPONENTS_IN_BAKED_TEXEL) {
+            let linear = LinSrgb::new(tint(texel[0], albedo.red), tint(texel[1], albedo.green), tint(texel[2], albedo.blue));
+            let encoded: Srgb<u8> = Srgb::from_linear(linear);
+            result.extend_from_slice(&[encoded.red, encoded.green, encoded.blue, u8::MAX]);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Vector4;
+
+    #[must_use]
+    fn make_system_under_test() -> ProceduralTextureBaking {
+        let mapping = AtlasRegionMappingBuilder::new()
+            .local_position_to_texture_u(Vector4::new(0.5, 0.0, 0.0, 0.25))
+            .local_position_to_texture_v(Vector4::new(0.0, 0.0, 2.0, 0.0));
+        ProceduralTextureBaking::new(BitmapSize::new(3, 2), mapping)
+    }
+
+    #[test]
+    fn test_make_request() {
+        let system_under_test = make_system_under_test();
+
+        let request = system_under_test.make_request(ProceduralTextureUid(7));
+
+        assert_eq!(request[0..3], [7, 3, 2]);
+        assert_eq!(f32::from_bits(request[4]), 0.5);
+        assert_eq!(f32::from_bits(request[7]), 0.25);
+        assert_eq!(f32::from_bits(request[10]), 2.0);
+    }
+
+    #[test]
+    fn test_encode_texels() {
+        let system_under_test = make_system_under_test();
+        let mut texels = vec![0.0; 6 * COMPONENTS_IN_BAKED_TEXEL];
+        texels[0..4].copy_from_slice(&[1.0, 0.5, 7.0, 1.0]);
+
+        let encoded = system_under_test.encode_texels(&texels, Srgb::new(1.0, 1.0, 0.5));
+
+        assert_eq!(encoded.len(), 6 * BYTES_IN_RGBA_QUARTET);
+        assert_eq!(encoded[0..4], [255, 188, 255, 255]);
+        assert_eq!(encoded[4..8], [0, 0, 0, 255]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_degenerate_mapping() {
+        let mapping = AtlasRegionMappingBuilder::new()
+            .local_position_to_texture_u(Vector4::new(1.0, 0.0, 0.0, 0.0))
+            .local_position_to_texture_v(Vector4::new(2.0, 0.0, 0.0, 0.0));
+        let _ = ProceduralTextureBaking::new(BitmapSize::new(1, 1), mapping);
+    }
+}
@@ -0,0 +1,23 @@
+use derive_more::Display;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Display, Hash)]
+pub struct SurfaceShaderUid(pub usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_shader_uid_display() {
+        let expected_value = 5;
+        let system_under_test = SurfaceShaderUid(expected_value);
+        assert_eq!(format!("{}", system_under_test), expected_value.to_string());
+    }
+
+    #[test]
+    fn test_surface_shader_uid_equality() {
+        let system_under_test = SurfaceShaderUid(8);
+        assert_eq!(system_under_test, SurfaceShaderUid(8));
+        assert_ne!(system_under_test, SurfaceShaderUid(9));
+    }
+}
\ No newline at end of file
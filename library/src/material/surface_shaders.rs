@@ -0,0 +1,85 @@
+use crate::material::surface_shader_uid::SurfaceShaderUid;
+use crate::shader::conventions;
+use crate::shader::user_function_registrator::{UserFunctionConventions, UserFunctionRegistrator, UserFunctionUid};
+
+// user functions shading the hits of 'MaterialClass::SurfaceShader' materials: the returned
+// radiance is what the viewer sees, the path is terminated at such surfaces
+pub type SurfaceShaders = UserFunctionRegistrator<SurfaceShaderUid>;
+
+// the body of a shader sees 'point: vec3f', 'normal: vec3f' (both in object space), 'uv: vec2f', 'time: f32'
+// and 'albedo: vec3f' (the possibly textured albedo of the material) and returns the 'vec3f' radiance
+impl UserFunctionUid for SurfaceShaderUid {
+    const CONVENTIONS: UserFunctionConventions = UserFunctionConventions {
+        selection_function_name: "surface_shader_select",
+        function_name_prefix: "surface",
+        default_name: "shader",
+        parameter_name_index: "shader_index",
+        parameters: &[
+            (conventions::PARAMETER_NAME_THE_POINT, "vec3f"),
+            (conventions::PARAMETER_NAME_THE_NORMAL, "vec3f"),
+            (conventions::PARAMETER_NAME_2D_TEXTURE_COORDINATES, "vec2f"),
+            (conventions::PARAMETER_NAME_THE_TIME, "f32"),
+            (conventions::PARAMETER_NAME_THE_ALBEDO, "vec3f"),
+        ],
+        return_type: "vec3f",
+        default_value: conventions::PARAMETER_NAME_THE_ALBEDO,
+    };
+
+    fn from_ordinal(ordinal: usize) -> Self {
+        SurfaceShaderUid(ordinal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::code::{FunctionBody, Generic, ShaderCode};
+
+    #[must_use]
+    fn surface_shader(body: &str) -> ShaderCode<FunctionBody> {
+        ShaderCode::<FunctionBody>::new(body.to_string())
+    }
+
+    #[test]
+    fn test_dummy_selection_function() {
+        assert_eq!(
+            SurfaceShaders::make_dummy_selection_function().to_string(),
+            "fn surface_shader_select(shader_index: i32, point: vec3f, normal: vec3f, uv: vec2f, time: f32, albedo: vec3f) -> vec3f {\nreturn albedo;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_new_with_shared_code() {
+        let system_under_test = SurfaceShaders::new(Some(ShaderCode::<Generic>::new("shared surface code".to_string())));
+
+        let generated_code = system_under_test.generate_gpu_code();
+
+        assert_eq!(
+            generated_code.to_string(),
+            format!("shared surface code\n{}", SurfaceShaders::make_dummy_selection_function())
+        );
+    }
+
+    #[test]
+    fn test_generate_gpu_code_multiple_shaders() {
+        let mut system_under_test = SurfaceShaders::new(None);
+
+        let hologram = system_under_test.add(surface_shader("return albedo * fract(uv.y * 10.0);"), Some("hologram"));
+        let unlit = system_under_test.add(surface_shader("return albedo;"), Some("unlit"));
+
+        assert_ne!(hologram, unlit);
+        let expected_code = "fn surface_hologram(point: vec3f, normal: vec3f, uv: vec2f, time: f32, albedo: vec3f)->vec3f{\nreturn albedo * fract(uv.y * 10.0);\n}\nfn surface_unlit(point: vec3f, normal: vec3f, uv: vec2f, time: f32, albedo: vec3f)->vec3f{\nreturn albedo;\n}\nfn surface_shader_select(shader_index: i32, point: vec3f, normal: vec3f, uv: vec2f, time: f32, albedo: vec3f) -> vec3f {\nif (shader_index == 1) { return surface_hologram(point,normal,uv,time,albedo); }\nif (shader_index == 2) { return surface_unlit(point,normal,uv,time,albedo); }\nreturn albedo;\n}\n";
+        assert_eq!(system_under_test.generate_gpu_code().to_string(), expected_code);
+    }
+
+    #[test]
+    fn test_animated() {
+        let mut system_under_test = SurfaceShaders::new(None);
+        let still = system_under_test.add(surface_shader("return albedo;"), None);
+        let animated = system_under_test.add(surface_shader("return albedo * abs(sin(time));"), None);
+
+        assert_eq!(system_under_test.animated(still), false);
+        assert!(system_under_test.animated(animated));
+        assert_eq!(system_under_test.animated(SurfaceShaderUid(42)), false);
+    }
+}
\ No newline at end of file
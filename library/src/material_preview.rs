@@ -0,0 +1,152 @@
+use crate::container::visual_objects::VisualObjects;
+use crate::geometry::alias::{Point, Vector};
+use crate::geometry::transform::Affine;
+#[cfg(any(test, feature = "gpu_testing"))]
+use crate::gpu::context::Context;
+use crate::material::material_index::MaterialIndex;
+use crate::material::material_properties::MaterialProperties;
+use crate::scene::camera::Camera;
+use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+use crate::sdf::object::sdf_sphere::SdfSphere;
+use crate::utils::object_uid::ObjectUid;
+use crate::{EngineInstantiationError, OffscreenEngine};
+use cgmath::SquareMatrix;
+#[cfg(any(test, feature = "gpu_testing"))]
+use std::rc::Rc;
+
+#[cfg(feature = "gpu_testing")]
+use crate::HeadlessContext;
+
+// thumbnails for material editors: a sphere over a checkered floor under a fixed light; the scene passed in
+// supplies the materials (with their procedural textures and surface shaders), its objects are dropped
+pub struct MaterialPreview {
+    engine: OffscreenEngine,
+    sphere: ObjectUid,
+    materials_count: usize,
+}
+
+impl MaterialPreview {
+    pub const DEFAULT_SIZE: u32 = 128;
+
+    const SPHERE_CLASS_NAME: &'static str = "material_preview_sphere";
+    const SPHERE_RADIUS: f64 = 1.0;
+    const FLOOR_TILES_PER_SIDE: i32 = 8;
+    const FLOOR_TILE_SIDE: f64 = 1.0;
+    const LIGHT_SIDE: f64 = 2.0;
+    const LIGHT_HEIGHT: f64 = 4.0;
+    const LIGHT_EMISSION: f32 = 5.0;
+    const EYE: Point = Point::new(0.0, 1.5, 4.5);
+
+    pub async fn new(size: u32, materials_source: VisualObjects) -> Result<MaterialPreview, EngineInstantiationError> {
+        assert!(size > 0, "preview must not be empty");
+        let (scene, sphere, materials_count) = Self::make_stage(materials_source);
+        let engine = OffscreenEngine::new(size, size, scene, Self::make_camera(), None).await?;
+        Ok(Self { engine, sphere, materials_count })
+    }
+
+    // renders on the device of the test context instead of a private one
+    #[cfg(feature = "gpu_testing")]
+    pub fn with_headless_context(context: &HeadlessContext, size: u32, materials_source: VisualObjects) -> Result<MaterialPreview, EngineInstantiationError> {
+        Self::with_context(context.context(), size, materials_source)
+    }
+
+    #[cfg(any(test, feature = "gpu_testing"))]
+    fn with_context(context: Rc<Context>, size: u32, materials_source: VisualObjects) -> Result<MaterialPreview, EngineInstantiationError> {
+        assert!(size > 0, "preview must not be empty");
+        let (scene, sphere, materials_count) = Self::make_stage(materials_source);
+        let engine = OffscreenEngine::with_context(context, size, size, scene, Self::make_camera(), None)?;
+        Ok(Self { engine, sphere, materials_count })
+    }
+
+    #[must_use]
+    fn make_camera() -> Camera {
+        let mut camera = Camera::new_perspective_camera(1.0, Point::new(0.0, 0.0, 0.0));
+        camera.set_view(Self::EYE, Point::new(0.0, 0.0, 0.0));
+        camera
+    }
+
+    // the stage materials go after the source ones, so the indices of the latter stay valid
+    #[must_use]
+    fn make_stage(mut scene: VisualObjects) -> (VisualObjects, ObjectUid, usize) {
+        scene.clear_objects();
+        let materials_count = scene.materials().count();
+
+        let materials = scene.materials_mutable();
+        let light_tile = materials.add(&MaterialProperties::new().with_albedo(0.8, 0.8, 0.8));
+        let dark_tile = materials.add(&MaterialProperties::new().with_albedo(0.2, 0.2, 0.2));
+        let light = materials.add(&MaterialProperties::new().with_albedo(1.0, 1.0, 1.0)
+            .with_emission(Self::LIGHT_EMISSION, Self::LIGHT_EMISSION, Self::LIGHT_EMISSION));
+
+        let half_floor = Self::FLOOR_TILES_PER_SIDE / 2;
+        for row in -half_floor..half_floor {
+            for column in -half_floor..half_floor {
+                let material = if 0 == (row + column).rem_euclid(2) { light_tile } else { dark_tile };
+                let origin = Point::new(column as f64 * Self::FLOOR_TILE_SIDE, -Self::SPHERE_RADIUS, row as f64 * Self::FLOOR_TILE_SIDE);
+                let _ = scene.add_parallelogram(origin, Vector::new(0.0, 0.0, Self::FLOOR_TILE_SIDE), Vector::new(Self::FLOOR_TILE_SIDE, 0.0, 0.0), material);
+            }
+        }
+
+        // facing down
+        let half_light = Self::LIGHT_SIDE / 2.0;
+        let _ = scene.add_parallelogram(Point::new(-half_light, Self::LIGHT_HEIGHT, -half_light), Vector::new(Self::LIGHT_SIDE, 0.0, 0.0), Vector::new(0.0, 0.0, Self::LIGHT_SIDE), light);
+
+        let sphere_class = UniqueSdfClassName::new(Self::SPHERE_CLASS_NAME.to_string());
+        scene.add_sdf_class(&NamedSdf::new(SdfSphere::new(Self::SPHERE_RADIUS), sphere_class.clone()));
+        let sphere = scene.add_sdf(&Affine::identity(), 1.0, &sphere_class, light_tile);
+
+        (scene, sphere, materials_count)
+    }
+
+    #[must_use]
+    pub fn size(&self) -> u32 {
+        self.engine.width()
+    }
+
+    // the index refers to the materials of the source scene; returns 'size * size' pixels,
+    // 4 bytes (RGBA) each, rows go from top to bottom
+    #[must_use]
+    pub fn render(&mut self, material: MaterialIndex) -> Vec<u8> {
+        assert!(material.0 < self.materials_count, "material {} is out of the source materials", material.0);
+        self.engine.objects().set_material(self.sphere, material);
+        self.engine.render(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
+
+    const TEST_SIZE: u32 = 32;
+
+    #[must_use]
+    fn pixel_at(pixels: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * TEST_SIZE + x) * 4) as usize;
+        [pixels[offset], pixels[offset + 1], pixels[offset + 2], pixels[offset + 3]]
+    }
+
+    #[test]
+    fn test_sphere_takes_material() {
+        let mut materials_source = VisualObjects::new(None, None, None, None, None);
+        let red = materials_source.materials_mutable().add(&MaterialProperties::new().with_albedo(1.0, 0.0, 0.0));
+        let blue = materials_source.materials_mutable().add(&MaterialProperties::new().with_albedo(0.0, 0.0, 1.0));
+        let mut system_under_test = MaterialPreview::with_context(create_headless_wgpu_vulkan_context(), TEST_SIZE, materials_source).unwrap();
+
+        let red_preview = system_under_test.render(red);
+        let blue_preview = system_under_test.render(blue);
+
+        assert_eq!(red_preview.len(), (TEST_SIZE * TEST_SIZE * 4) as usize);
+        let [r, _, b, _] = pixel_at(&red_preview, TEST_SIZE / 2, TEST_SIZE / 2);
+        assert!(r > b, "red sphere expected, got r={r} b={b}");
+        let [r, _, b, _] = pixel_at(&blue_preview, TEST_SIZE / 2, TEST_SIZE / 2);
+        assert!(b > r, "blue sphere expected, got r={r} b={b}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stage_material_is_rejected() {
+        let mut system_under_test = MaterialPreview::with_context(create_headless_wgpu_vulkan_context(), TEST_SIZE, VisualObjects::new(None, None, None, None, None)).unwrap();
+
+        let _ = system_under_test.render(MaterialIndex(0));
+    }
+}
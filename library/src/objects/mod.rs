@@ -1,5 +1,6 @@
 pub(crate) mod common_properties;
 pub(crate) mod parallelogram;
+pub(crate) mod sphere;
 pub(crate) mod triangle;
 pub(crate) mod triangle_mesh;
 pub(crate) mod sdf_instance;
@@ -1,6 +1,10 @@
+use crate::geometry::aabb::Aabb;
 use crate::geometry::alias;
+use crate::geometry::epsilon::DEFAULT_EPSILON_F64;
+use crate::geometry::ray::{Ray, RayIntersection};
 use cgmath::EuclideanSpace;
 use cgmath::InnerSpace;
+use cgmath::Zero;
 
 use crate::objects::common_properties::Linkage;
 use crate::objects::ray_traceable::RayTraceable;
@@ -22,6 +26,26 @@ impl Parallelogram {
     pub const fn new(origin: Point, local_x: Vector, local_y: Vector, links: Linkage) -> Self {
         Parallelogram { origin, local_x, local_y, links }
     }
+
+    #[must_use]
+    pub(crate) fn area(&self) -> f64 {
+        self.local_x.cross(self.local_y).magnitude()
+    }
+
+    // no area (e.g. a zero or parallel sides) or non-finite coordinates: nothing to hit or to sample as a light
+    #[must_use]
+    pub(crate) fn is_degenerate(&self) -> bool {
+        let area = self.area();
+        let origin = self.origin;
+        false == (area > 0.0 && area.is_finite() && origin.x.is_finite() && origin.y.is_finite() && origin.z.is_finite())
+    }
+
+    #[must_use]
+    pub(crate) fn bounding_box(&self) -> Aabb {
+        let diagonal = Aabb::from_points(self.origin, self.origin + self.local_x + self.local_y);
+        let other_diagonal = Aabb::from_points(self.origin + self.local_x, self.origin + self.local_y);
+        Aabb::make_union(diagonal, other_diagonal)
+    }
 }
 
 impl GpuSerializationSize for Parallelogram {
@@ -32,11 +56,15 @@ impl GpuSerializable for Parallelogram {
     fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
         debug_assert!(container.has_free_slot(), "buffer overflow");
 
-        let orth = self.local_x.cross(self.local_y);
-        let orth_square = orth.dot(orth);
-        let normal = orth / orth_square.sqrt();
-        let distance_to_origin = normal.dot(self.origin.to_vec()); // d from plane's equation ax+by+cz+d = 0, where (a,b,c) is normal
-        let w = orth / orth_square; //TODO: geometry meaning?
+        // the zero normal of a degenerate one keeps the NaNs off the gpu: no ray hits it, see 'hit_quad'
+        let (normal, w) = if self.is_degenerate() {
+            (Vector::zero(), Vector::zero())
+        } else {
+            let orth = self.local_x.cross(self.local_y);
+            let orth_square = orth.dot(orth);
+            (orth / orth_square.sqrt(), orth / orth_square) //TODO: geometry meaning of w?
+        };
+        let distance_to_origin = if self.is_degenerate() { 0.0 } else { normal.dot(self.origin.to_vec()) }; // d from plane's equation ax+by+cz+d = 0, where (a,b,c) is normal
 
         container.write_padded_quartet_f64(
             self.origin.x,
@@ -58,7 +86,6 @@ impl GpuSerializable for Parallelogram {
             distance_to_origin,
         );
 
-        let normal = normal.normalize();
         container.write_padded_quartet_f64(
             normal.x,
             normal.y,
@@ -85,9 +112,35 @@ impl RayTraceable for Parallelogram {
         self.links.set_material_index(new_material_index)
     }
 
+    fn translate(&mut self, offset: Vector) {
+        self.origin += offset;
+    }
+
+    fn intersect(&self, ray: &Ray) -> Option<RayIntersection> {
+        let orth = self.local_x.cross(self.local_y);
+        let approach = orth.dot(ray.direction());
+        if approach.abs() < DEFAULT_EPSILON_F64 {
+            return None;
+        }
+        let distance = orth.dot(self.origin - ray.origin()) / approach;
+        if distance <= 0.0 {
+            return None;
+        }
+        let planar = ray.at(distance) - self.origin;
+        let w = orth / orth.dot(orth);
+        let alpha = w.dot(planar.cross(self.local_y));
+        let beta = w.dot(self.local_x.cross(planar));
+        let inside = (0.0..=1.0).contains(&alpha) && (0.0..=1.0).contains(&beta);
+        inside.then(|| RayIntersection::facing(ray, distance, orth))
+    }
+
     fn serialized_quartet_count(&self) -> usize {
         Parallelogram::SERIALIZED_QUARTET_COUNT
     }
+
+    fn parallelogram(&self) -> Option<&Parallelogram> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -124,7 +177,7 @@ mod tests {
         let mut container = GpuReadySerializationBuffer::new(1, Parallelogram::SERIALIZED_QUARTET_COUNT);
         system_under_test.serialize_into(&mut container);
 
-        let serialized: &[f32] = cast_slice(&container.backend());
+        let serialized: &[f32] = cast_slice(container.backend());
 
         assert_eq!(serialized[0 ], origin.x as f32);
         assert_eq!(serialized[1 ], origin.y as f32);
@@ -150,4 +203,50 @@ mod tests {
         assert_eq!(serialized[18], -4.0 / 16.0);
         assert_eq!(serialized[19].to_bits(), expected_material_index.0 as u32);
     }
+
+    #[test]
+    fn test_degenerate_serialized_without_nans() {
+        let system_under_test = Parallelogram::new(Point::new(1.0, 2.0, 3.0), Vector::new(1.0, 0.0, 0.0), Vector::new(-2.0, 0.0, 0.0), Linkage::new(ObjectUid(1), MaterialIndex(0)));
+        assert!(system_under_test.is_degenerate());
+
+        let mut container = GpuReadySerializationBuffer::new(1, Parallelogram::SERIALIZED_QUARTET_COUNT);
+        system_under_test.serialize_into(&mut container);
+
+        let serialized: &[f32] = cast_slice(container.backend());
+        // but the uid and the material bits
+        assert!(serialized.iter().enumerate().filter(|(index, _)| *index != 7 && *index != 19).all(|(_, value)| value.is_finite()));
+        assert_eq!(&serialized[12..15], &[0.0; 3]);
+        assert_eq!(&serialized[16..19], &[0.0; 3]);
+    }
+
+    #[test]
+    fn test_is_degenerate() {
+        let make = |origin: Point, local_x: Vector| Parallelogram::new(origin, local_x, Vector::unit_y(), Linkage::new(ObjectUid(1), MaterialIndex(0)));
+
+        assert_eq!(false, make(Point::origin(), Vector::unit_x()).is_degenerate());
+        assert!(make(Point::origin(), Vector::zero()).is_degenerate());
+        assert!(make(Point::new(f64::NAN, 0.0, 0.0), Vector::unit_x()).is_degenerate());
+        assert!(make(Point::origin(), Vector::new(f64::INFINITY, 0.0, 0.0)).is_degenerate());
+    }
+
+    #[test]
+    fn test_intersect() {
+        let system_under_test = Parallelogram::new(Point::new(0.0, 0.0, 1.0), Vector::new(2.0, 0.0, 0.0), Vector::new(1.0, 1.0, 0.0), Linkage::new(ObjectUid(1), MaterialIndex(0)));
+
+        let hit = system_under_test.intersect(&Ray::new(Point::new(2.5, 0.5, 3.0), -Vector::unit_z())).unwrap();
+        assert_eq!(hit.distance(), 2.0);
+        assert_eq!(hit.normal(), Vector::unit_z());
+
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(0.0, 0.5, 3.0), -Vector::unit_z())), None);
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(2.5, 0.5, 3.0), Vector::unit_z())), None);
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(2.5, 0.5, 3.0), Vector::unit_x())), None);
+    }
+
+    #[test]
+    fn test_area_and_bounding_box() {
+        let system_under_test = Parallelogram::new(Point::new(0.0, 0.0, 1.0), Vector::new(2.0, 0.0, 0.0), Vector::new(-1.0, 1.0, 0.0), Linkage::new(ObjectUid(1), MaterialIndex(0)));
+
+        assert_eq!(system_under_test.area(), 2.0);
+        assert_eq!(system_under_test.bounding_box(), Aabb::from_points(Point::new(-1.0, 0.0, 1.0), Point::new(2.0, 1.0, 1.0)));
+    }
 }
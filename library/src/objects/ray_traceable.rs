@@ -1,9 +1,33 @@
+use crate::geometry::alias::Vector;
+use crate::geometry::ray::{Ray, RayIntersection};
 use crate::material::material_index::MaterialIndex;
+use crate::objects::parallelogram::Parallelogram;
+use crate::sdf::framework::sdf_parameters::SdfParameterValues;
 use crate::serialization::serializable_for_gpu::GpuSerializable;
 
-pub(crate) trait RayTraceable: GpuSerializable {
+pub(crate) trait RayTraceable: GpuSerializable + Sync {
     fn material(&self) -> MaterialIndex;
     fn set_material(&mut self, material_index: MaterialIndex);
+
+    fn translate(&mut self, offset: Vector);
     
     fn serialized_quartet_count(&self) -> usize;
+
+    // the sdf instances only
+    #[must_use]
+    fn sdf_parameters(&self) -> Option<SdfParameterValues> {
+        None
+    }
+    fn set_sdf_parameters(&mut self, _parameters: SdfParameterValues) {
+        panic!("the object has no sdf parameters");
+    }
+
+    #[must_use]
+    fn intersect(&self, ray: &Ray) -> Option<RayIntersection>;
+
+    // the parallelograms only: those may emit the light the next event estimation samples
+    #[must_use]
+    fn parallelogram(&self) -> Option<&Parallelogram> {
+        None
+    }
 }
\ No newline at end of file
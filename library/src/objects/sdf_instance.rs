@@ -1,3 +1,5 @@
+use crate::geometry::alias::Vector;
+use crate::geometry::ray::{Ray, RayIntersection};
 use crate::geometry::transform::Affine;
 use crate::geometry::utils::is_affine;
 use crate::objects::common_properties::Linkage;
@@ -10,26 +12,28 @@ use cgmath::num_traits::abs;
 use cgmath::SquareMatrix;
 use more_asserts::assert_gt;
 use crate::material::material_index::MaterialIndex;
+use crate::sdf::framework::sdf_parameters::SdfParameterValues;
 
 pub(crate) struct SdfInstance {
     location: Affine,
     ray_marching_step_scale: f64,
     class: SdfClassIndex,
     links: Linkage,
+    parameters: SdfParameterValues,
 }
 
 impl SdfInstance {
     #[must_use]
-    pub(crate) fn new(location: Affine, ray_marching_step_scale: f64, class: SdfClassIndex, links: Linkage) -> Self {
+    pub(crate) fn new(location: Affine, ray_marching_step_scale: f64, class: SdfClassIndex, links: Linkage, parameters: SdfParameterValues) -> Self {
         assert_gt!(abs(location.determinant()), 0.0, "location should not change basis orientation, or ray marching will break");
         assert_gt!(ray_marching_step_scale, 0.0);
         assert!(is_affine(&location), "projection matrices are not supported");
-        Self { location, ray_marching_step_scale, class, links }
+        Self { location, ray_marching_step_scale, class, links, parameters }
     }
 }
 
 impl GpuSerializationSize for SdfInstance {
-    const SERIALIZED_QUARTET_COUNT: usize = 7;
+    const SERIALIZED_QUARTET_COUNT: usize = 8;
 }
 
 impl GpuSerializable for SdfInstance {
@@ -46,6 +50,8 @@ impl GpuSerializable for SdfInstance {
             writer.write_unsigned(self.links.uid().0);
         });
 
+        container.write_quartet_f32(self.parameters[0], self.parameters[1], self.parameters[2], self.parameters[3]);
+
         debug_assert!(container.object_fully_written());
     }
 }
@@ -59,9 +65,26 @@ impl RayTraceable for SdfInstance {
         self.links.set_material_index(new_material_index)
     }
 
+    fn translate(&mut self, offset: Vector) {
+        self.location = Affine::from_translation(offset) * self.location;
+    }
+
     fn serialized_quartet_count(&self) -> usize {
         SdfInstance::SERIALIZED_QUARTET_COUNT
     }
+
+    fn sdf_parameters(&self) -> Option<SdfParameterValues> {
+        Some(self.parameters)
+    }
+
+    fn set_sdf_parameters(&mut self, parameters: SdfParameterValues) {
+        self.parameters = parameters;
+    }
+
+    // the distance field exists as the shader code only: the container hits the box of the class instead
+    fn intersect(&self, _ray: &Ray) -> Option<RayIntersection> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -80,7 +103,8 @@ mod tests {
         let expected_object_uid = ObjectUid(7);
         let expected_ray_marching_scale = 5.0;
         
-        let system_under_test = SdfInstance::new(expected_location, expected_ray_marching_scale, expected_class, Linkage::new(expected_object_uid, expected_material_index));
+        let expected_parameters = [1.0, 2.0, 3.0, 4.0];
+        let system_under_test = SdfInstance::new(expected_location, expected_ray_marching_scale, expected_class, Linkage::new(expected_object_uid, expected_material_index), expected_parameters);
 
         let mut container = GpuReadySerializationBuffer::new(1, SdfInstance::SERIALIZED_QUARTET_COUNT);
         system_under_test.serialize_into(&mut container);
@@ -112,6 +136,11 @@ mod tests {
         values_checked += 1;
         assert_eq!(serialized[values_checked], expected_object_uid.0 as u32);
         values_checked += 1;
+
+        let serialized: &[f32] = cast_slice(&container.backend());
+
+        assert_eq!(&serialized[values_checked..values_checked + 4], &expected_parameters);
+        values_checked += 4;
         
         assert_eq!(values_checked, SdfInstance::SERIALIZED_QUARTET_COUNT * ELEMENTS_IN_QUARTET);
     }
@@ -0,0 +1,130 @@
+use crate::geometry::alias::{Point, Vector};
+use crate::geometry::ray::{Ray, RayIntersection};
+use crate::material::material_index::MaterialIndex;
+use crate::objects::common_properties::Linkage;
+use crate::objects::ray_traceable::RayTraceable;
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use crate::serialization::serializable_for_gpu::{GpuSerializable, GpuSerializationSize};
+use cgmath::InnerSpace;
+
+pub(crate) struct Sphere {
+    center: Point,
+    radius: f64,
+    links: Linkage,
+}
+
+impl Sphere {
+    #[must_use]
+    pub fn new(center: Point, radius: f64, links: Linkage) -> Self {
+        assert!(radius > 0.0, "sphere radius must be positive");
+        Sphere { center, radius, links }
+    }
+}
+
+impl GpuSerializationSize for Sphere {
+    const SERIALIZED_QUARTET_COUNT: usize = 2;
+}
+
+impl GpuSerializable for Sphere {
+    fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
+        debug_assert!(container.has_free_slot(), "buffer overflow");
+
+        container.write_quartet_f64(
+            self.center.x,
+            self.center.y,
+            self.center.z,
+            self.radius,
+        );
+
+        container.write_quartet(|writer| {
+            writer.write_unsigned(self.links.uid().0);
+            writer.write_unsigned(self.links.material_index().0 as u32);
+        });
+
+        debug_assert!(container.object_fully_written());
+    }
+}
+
+impl RayTraceable for Sphere {
+    fn material(&self) -> MaterialIndex {
+        self.links.material_index()
+    }
+
+    fn set_material(&mut self, new_material_index: MaterialIndex) {
+        self.links.set_material_index(new_material_index)
+    }
+
+    fn translate(&mut self, offset: Vector) {
+        self.center += offset;
+    }
+
+    fn serialized_quartet_count(&self) -> usize {
+        Sphere::SERIALIZED_QUARTET_COUNT
+    }
+
+    // the closest of the two roots in front of the ray: the far one for the origin inside
+    fn intersect(&self, ray: &Ray) -> Option<RayIntersection> {
+        let to_center = self.center - ray.origin();
+        let projection = to_center.dot(ray.direction());
+        let discriminant = projection * projection - to_center.magnitude2() + self.radius * self.radius;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let half_chord = discriminant.sqrt();
+        let distance = if projection - half_chord > 0.0 { projection - half_chord } else { projection + half_chord };
+        (distance > 0.0).then(|| RayIntersection::facing(ray, distance, ray.at(distance) - self.center))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::common_properties::ObjectUid;
+    use crate::serialization::gpu_ready_serialization_buffer::DEFAULT_PAD_VALUE;
+    use bytemuck::cast_slice;
+
+    #[test]
+    fn test_serialize_into() {
+        let center = Point::new(1.0, 2.0, 3.0);
+        let expected_uid = ObjectUid(11);
+        let expected_material_index = MaterialIndex(17);
+        let system_under_test = Sphere::new(center, 0.5, Linkage::new(expected_uid, expected_material_index));
+
+        let mut container = GpuReadySerializationBuffer::new(1, Sphere::SERIALIZED_QUARTET_COUNT);
+        system_under_test.serialize_into(&mut container);
+
+        let serialized: &[f32] = cast_slice(container.backend());
+
+        assert_eq!(serialized[0], center.x as f32);
+        assert_eq!(serialized[1], center.y as f32);
+        assert_eq!(serialized[2], center.z as f32);
+        assert_eq!(serialized[3], 0.5);
+
+        assert_eq!(serialized[4].to_bits(), expected_uid.0);
+        assert_eq!(serialized[5].to_bits(), expected_material_index.0 as u32);
+        assert_eq!(serialized[6], DEFAULT_PAD_VALUE);
+        assert_eq!(serialized[7], DEFAULT_PAD_VALUE);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_radius() {
+        let _ = Sphere::new(Point::new(0.0, 0.0, 0.0), 0.0, Linkage::new(ObjectUid(1), MaterialIndex(0)));
+    }
+
+    #[test]
+    fn test_intersect() {
+        let system_under_test = Sphere::new(Point::new(0.0, 0.0, 5.0), 2.0, Linkage::new(ObjectUid(1), MaterialIndex(0)));
+
+        let outside = system_under_test.intersect(&Ray::new(Point::new(0.0, 0.0, 0.0), Vector::unit_z())).unwrap();
+        assert_eq!(outside.distance(), 3.0);
+        assert_eq!(outside.normal(), -Vector::unit_z());
+
+        let inside = system_under_test.intersect(&Ray::new(Point::new(0.0, 0.0, 5.0), Vector::unit_z())).unwrap();
+        assert_eq!(inside.distance(), 2.0);
+        assert_eq!(inside.normal(), -Vector::unit_z());
+
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(0.0, 0.0, 0.0), -Vector::unit_z())), None);
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(3.0, 0.0, 0.0), Vector::unit_z())), None);
+    }
+}
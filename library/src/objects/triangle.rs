@@ -1,11 +1,12 @@
 use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::Vector;
 use crate::geometry::epsilon::DEFAULT_EPSILON_F64;
+use crate::geometry::fundamental_constants::VERTICES_IN_TRIANGLE;
+use crate::geometry::ray::{Ray, RayIntersection};
 use crate::geometry::vertex::Vertex;
 use crate::objects::common_properties::Linkage;
-use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
-use crate::serialization::serializable_for_gpu::{GpuSerializable, GpuSerializationSize};
 use crate::utils::object_uid::ObjectUid;
-use cgmath::AbsDiffEq;
+use cgmath::{AbsDiffEq, InnerSpace};
 use std::ops::Add;
 use crate::material::material_index::MaterialIndex;
 
@@ -30,6 +31,8 @@ pub(crate) struct Triangle {
     a: Vertex,
     b: Vertex,
     c: Vertex,
+    // of the hosting mesh vertices: the gpu gets the shared vertices and the indices only
+    vertex_indices: [u32; VERTICES_IN_TRIANGLE],
     links: Linkage,
 }
 
@@ -40,10 +43,22 @@ impl Triangle {
             a,
             b,
             c,
+            vertex_indices: [0, 1, 2],
             links,
         }
     }
 
+    #[must_use]
+    pub(crate) fn with_vertex_indices(mut self, vertex_indices: [u32; VERTICES_IN_TRIANGLE]) -> Self {
+        self.vertex_indices = vertex_indices;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn vertex_indices(&self) -> [u32; VERTICES_IN_TRIANGLE] {
+        self.vertex_indices
+    }
+
     #[must_use]
     pub(crate) fn bounding_box(&self) -> Aabb {
         let result = Aabb::from_triangle(self.a.position(), self.b.position(), self.c.position());
@@ -58,6 +73,37 @@ impl Triangle {
     pub(crate) fn set_material(&mut self, new_material: MaterialIndex) {
         self.links.set_material_index(new_material);
     }
+
+    pub(crate) fn translate(&mut self, offset: Vector) {
+        self.a = self.a.translated(offset);
+        self.b = self.b.translated(offset);
+        self.c = self.c.translated(offset);
+    }
+
+    // "Fast, Minimum Storage Ray/Triangle Intersection" by Tomas Möller and Ben Trumbore
+    #[must_use]
+    pub(crate) fn intersect(&self, ray: &Ray) -> Option<RayIntersection> {
+        let edge_ab = self.b.position() - self.a.position();
+        let edge_ac = self.c.position() - self.a.position();
+        let p = ray.direction().cross(edge_ac);
+        let determinant = edge_ab.dot(p);
+        if determinant.abs() < DEFAULT_EPSILON_F64 {
+            return None;
+        }
+        let inverse_determinant = 1.0 / determinant;
+        let t = ray.origin() - self.a.position();
+        let u = t.dot(p) * inverse_determinant;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = t.cross(edge_ab);
+        let v = ray.direction().dot(q) * inverse_determinant;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = edge_ac.dot(q) * inverse_determinant;
+        (distance > 0.0).then(|| RayIntersection::facing(ray, distance, edge_ab.cross(edge_ac)))
+    }
 }
 
 impl AbsDiffEq for Triangle {
@@ -71,51 +117,16 @@ impl AbsDiffEq for Triangle {
            Vertex::abs_diff_eq(&self.a, &other.a, epsilon)
         && Vertex::abs_diff_eq(&self.b, &other.b, epsilon)
         && Vertex::abs_diff_eq(&self.c, &other.c, epsilon)
+        && self.vertex_indices == other.vertex_indices
         && self.links == other.links
     }
 }
 
-impl GpuSerializationSize for Triangle {
-    const SERIALIZED_QUARTET_COUNT: usize = 6;
-}
-
-impl GpuSerializable for Triangle {
-    fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
-        debug_assert!(container.has_free_slot(), "buffer overflow");
-
-        container.write_padded_quartet_f64(self.a.position().x, self.a.position().y, self.a.position().z);
-        container.write_padded_quartet_f64(self.b.position().x, self.b.position().y, self.b.position().z);
-        container.write_padded_quartet_f64(self.c.position().x, self.c.position().y, self.c.position().z);
-
-        container.write_padded_quartet_f64(self.a.normal().x, self.a.normal().y, self.a.normal().z);
-
-        container.write_quartet(|writer|{
-            writer
-                .write_float_64(self.b.normal().x)
-                .write_float_64(self.b.normal().y)
-                .write_float_64(self.b.normal().z)
-                .write_unsigned(self.links.uid().0);
-        });
-
-        container.write_quartet(|writer|{
-            writer
-                .write_float_64(self.c.normal().x)
-                .write_float_64(self.c.normal().y)
-                .write_float_64(self.c.normal().z)
-                .write_unsigned(self.links.material_index().0 as u32);
-        });
-
-        debug_assert!(container.object_fully_written());
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::geometry::alias::{Point, Vector};
     use crate::objects::common_properties::ObjectUid;
-    use crate::serialization::gpu_ready_serialization_buffer::DEFAULT_PAD_VALUE;
-    use bytemuck::cast_slice;
 
     #[test]
     fn test_triangle_creation() {
@@ -148,43 +159,21 @@ mod tests {
     }
 
     #[test]
-    fn test_triangle_serialization() {
-        let a = Vertex::new(Point::new(1.0, 2.0, 3.0), Vector::new(10.0, 11.0, 12.0));
-        let b = Vertex::new(Point::new(4.0, 5.0, 6.0), Vector::new(13.0, 14.0, 15.0));
-        let c = Vertex::new(Point::new(7.0, 8.0, 9.0), Vector::new(16.0, 17.0, 18.0));
-        let expected_linkage = Linkage::new(ObjectUid(19), MaterialIndex(20));
-        let system_under_test = Triangle::new(a, b, c, expected_linkage);
-
-        let mut actual_state = GpuReadySerializationBuffer::new(1, Triangle::SERIALIZED_QUARTET_COUNT);
-        system_under_test.serialize_into(&mut actual_state);
-
-        let expected: Vec<f32> = vec![
-            a.position().x as f32,
-            a.position().y as f32,
-            a.position().z as f32,
-            DEFAULT_PAD_VALUE,
-            b.position().x as f32,
-            b.position().y as f32,
-            b.position().z as f32,
-            DEFAULT_PAD_VALUE,
-            c.position().x as f32,
-            c.position().y as f32,
-            c.position().z as f32,
-            DEFAULT_PAD_VALUE,
-            a.normal().x as f32,
-            a.normal().y as f32,
-            a.normal().z as f32,
-            DEFAULT_PAD_VALUE,
-            b.normal().x as f32,
-            b.normal().y as f32,
-            b.normal().z as f32,
-            f32::from_bits(expected_linkage.uid().0),
-            c.normal().x as f32,
-            c.normal().y as f32,
-            c.normal().z as f32,
-            f32::from_bits(expected_linkage.material_index().0 as u32),
-        ];
-
-        assert_eq!(actual_state.backend(), cast_slice::<f32, u8>(&expected));
+    fn test_intersect() {
+        let a = Vertex::new(Point::new(0.0, 0.0, 0.0), Vector::unit_z());
+        let b = Vertex::new(Point::new(1.0, 0.0, 0.0), Vector::unit_z());
+        let c = Vertex::new(Point::new(0.0, 1.0, 0.0), Vector::unit_z());
+        let system_under_test = Triangle::new(a, b, c, Linkage::new(ObjectUid(3), MaterialIndex(1)));
+
+        let from_above = system_under_test.intersect(&Ray::new(Point::new(0.25, 0.25, 2.0), -Vector::unit_z())).unwrap();
+        assert_eq!(from_above.distance(), 2.0);
+        assert_eq!(from_above.normal(), Vector::unit_z());
+
+        let from_below = system_under_test.intersect(&Ray::new(Point::new(0.25, 0.25, -1.0), Vector::unit_z())).unwrap();
+        assert_eq!(from_below.normal(), -Vector::unit_z());
+
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(0.75, 0.75, 2.0), -Vector::unit_z())), None);
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(0.25, 0.25, 2.0), Vector::unit_z())), None);
+        assert_eq!(system_under_test.intersect(&Ray::new(Point::new(0.25, 0.25, 2.0), Vector::unit_x())), None);
     }
 }
@@ -12,6 +12,7 @@ pub(crate) struct VertexData {
 }
 
 pub(crate) struct TriangleMesh {
+    vertices: Vec<Vertex>,
     triangles: Vec<Triangle>,
 }
 
@@ -25,10 +26,12 @@ impl TriangleMesh {
             let a = vertices[triangle[TriangleVertex::A as usize] as usize];
             let b = vertices[triangle[TriangleVertex::B as usize] as usize];
             let c = vertices[triangle[TriangleVertex::C as usize] as usize];
-            triangles.push(Triangle::new(a, b, c, mesh_links));
+            let vertex_indices = [triangle[TriangleVertex::A as usize], triangle[TriangleVertex::B as usize], triangle[TriangleVertex::C as usize]];
+            triangles.push(Triangle::new(a, b, c, mesh_links).with_vertex_indices(vertex_indices));
         }
 
         TriangleMesh {
+            vertices: vertices.to_vec(),
             triangles,
         }
     }
@@ -36,6 +39,12 @@ impl TriangleMesh {
     pub(crate) fn put_triangles_into(&self, target: &mut Vec<Triangle>) {
         target.extend(&self.triangles);
     }
+
+    // the vertices shared by the triangles; consumes the mesh, as the triangles are put elsewhere by then
+    #[must_use]
+    pub(crate) fn into_vertices(self) -> Vec<Vertex> {
+        self.vertices
+    }
 }
 
 #[cfg(test)]
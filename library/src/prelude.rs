@@ -0,0 +1,21 @@
+// the types most scenes are made of, for a single glob import: 'use library::prelude::*;'
+
+pub use crate::container::mesh_warehouse::{MeshShading, MeshWarehouse, WarehouseSlot};
+pub use crate::container::scene_builder::SceneBuilder;
+pub use crate::container::visual_objects::VisualObjects;
+pub use crate::geometry::alias::{Point, Vector};
+pub use crate::geometry::axis::Axis;
+pub use crate::geometry::transform::{Affine, Transformation};
+pub use crate::material::material_builder::MaterialBuilder;
+pub use crate::material::material_index::MaterialIndex;
+pub use crate::material::material_properties::{MaterialClass, MaterialProperties};
+pub use crate::material::texture_reference::TextureReference;
+pub use crate::scene::camera::Camera;
+pub use crate::scene::hub::Hub;
+pub use crate::scene::layer_mask::LayerMask;
+pub use crate::scene::ray_hit::RayHit;
+pub use crate::scene::uv_transform::UvTransform;
+pub use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+pub use crate::sdf::framework::sdf_registrator::SdfRegistrator;
+pub use crate::utils::object_uid::ObjectUid;
+pub use crate::{Engine, OffscreenEngine, RendererControls};
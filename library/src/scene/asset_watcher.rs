@@ -0,0 +1,241 @@
+use crate::container::mesh_warehouse::{MeshWarehouse, WarehouseSlot};
+use crate::container::texture_atlas_page_composer::AtlasRegionUid;
+use crate::container::texture_helpers::reload_bitmap;
+use crate::objects::common_properties::ObjectUid;
+use crate::scene::hub::Hub;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AssetReload {
+    Reloaded { path: PathBuf },
+    Failed { path: PathBuf, reason: String },
+}
+
+// modification time and length: some editors keep the time while rewriting the file quickly
+type FileStamp = (SystemTime, u64);
+
+#[must_use]
+fn read_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+struct WatchedFile {
+    path: PathBuf,
+    stamp: Option<FileStamp>,
+}
+
+impl WatchedFile {
+    #[must_use]
+    fn new(path: PathBuf) -> Self {
+        let stamp = read_stamp(&path);
+        Self { path, stamp }
+    }
+
+    // a missing file is not a change: editors often save through a temporary file and a rename
+    #[must_use]
+    fn take_change(&mut self) -> bool {
+        let Some(current) = read_stamp(&self.path) else {
+            return false;
+        };
+        if self.stamp == Some(current) {
+            return false;
+        }
+        self.stamp = Some(current);
+        true
+    }
+}
+
+struct WatchedMesh {
+    file: WatchedFile,
+    slot: WarehouseSlot,
+    instances: Vec<ObjectUid>,
+}
+
+struct WatchedBitmap {
+    file: WatchedFile,
+    region: AtlasRegionUid,
+}
+
+// polls the files behind the mesh prototypes and the atlas regions, re-imports the edited ones and
+// updates the scene in place; the caller decides how often to poll (e.g. once per frame)
+#[derive(Default)]
+pub struct AssetWatcher {
+    meshes: Vec<WatchedMesh>,
+    bitmaps: Vec<WatchedBitmap>,
+}
+
+impl AssetWatcher {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn watch_mesh(&mut self, meshes: &MeshWarehouse, slot: WarehouseSlot, instance: ObjectUid) {
+        if let Some(watched) = self.meshes.iter_mut().find(|watched| watched.slot == slot) {
+            if false == watched.instances.contains(&instance) {
+                watched.instances.push(instance);
+            }
+            return;
+        }
+        let file = WatchedFile::new(meshes.source_of(slot).to_path_buf());
+        self.meshes.push(WatchedMesh { file, slot, instances: vec![instance] });
+    }
+
+    // the edited bitmap has to keep its dimensions: the atlas region is rewritten in place
+    pub fn watch_bitmap(&mut self, file_path: PathBuf, region: AtlasRegionUid) {
+        self.bitmaps.retain(|watched| watched.region != region);
+        self.bitmaps.push(WatchedBitmap { file: WatchedFile::new(file_path), region });
+    }
+
+    // object uids get reused by the next scene, so the mesh instances are dropped along with the scene
+    pub fn unwatch_meshes(&mut self) {
+        self.meshes.clear();
+    }
+
+    pub fn unwatch_bitmaps(&mut self) {
+        self.bitmaps.clear();
+    }
+
+    pub fn poll(&mut self, meshes: &mut MeshWarehouse, scene: &mut Hub) -> Vec<AssetReload> {
+        let mut reloads = Vec::new();
+
+        for watched in self.meshes.iter_mut() {
+            if false == watched.file.take_change() {
+                continue;
+            }
+            let path = watched.file.path.clone();
+            match meshes.reload(watched.slot) {
+                Ok(()) => {
+                    // deleted instances are forgotten; the reload is reported even if none is left
+                    watched.instances.retain(|instance| scene.reshape_mesh(*instance, meshes, watched.slot));
+                    reloads.push(AssetReload::Reloaded { path });
+                }
+                Err(error) => reloads.push(AssetReload::Failed { path, reason: error.to_string() }),
+            }
+        }
+
+        for watched in self.bitmaps.iter_mut() {
+            if false == watched.file.take_change() {
+                continue;
+            }
+            let path = watched.file.path.clone();
+            match reload_bitmap(&path, watched.region, scene.mutable_texture_atlas_page_composer()) {
+                Ok(()) => reloads.push(AssetReload::Reloaded { path }),
+                Err(error) => reloads.push(AssetReload::Failed { path, reason: error.to_string() }),
+            }
+        }
+
+        reloads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::mesh_warehouse::MeshShading;
+    use crate::container::texture_helpers::load_bitmap;
+    use crate::container::visual_objects::VisualObjects;
+    use crate::geometry::transform::Transformation;
+    use crate::material::material_properties::MaterialProperties;
+    use crate::material::material_index::MaterialIndex;
+    use crate::utils::bitmap_utils::BitmapSize;
+    use image::{Rgba, RgbaImage};
+    use std::io::{Seek, Write};
+    use tempfile::NamedTempFile;
+
+    const TRIANGLE_OBJ_FILE: &[u8] = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    const QUAD_OBJ_FILE: &[u8] = b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3\nf 1 3 4\n";
+
+    #[must_use]
+    fn make_scene() -> (Hub, MaterialIndex) {
+        let mut container = VisualObjects::new(Some(BitmapSize::new(32, 32)), None, None, None, None);
+        let material = container.materials_mutable().add(&MaterialProperties::default());
+        (Hub::new(container), material)
+    }
+
+    fn overwrite(file: &mut NamedTempFile, content: &[u8]) {
+        file.as_file_mut().set_len(0).unwrap();
+        file.as_file_mut().rewind().unwrap();
+        file.write_all(content).unwrap();
+        file.flush().unwrap();
+    }
+
+    fn save_single_color_png(path: &Path, width: u32, color: [u8; 4]) {
+        RgbaImage::from_pixel(width, 1, Rgba(color)).save_with_format(path, image::ImageFormat::Png).unwrap();
+    }
+
+    #[test]
+    fn test_untouched_files_are_not_reloaded() {
+        let mut mesh_file = NamedTempFile::new_in("./").unwrap();
+        mesh_file.write_all(TRIANGLE_OBJ_FILE).unwrap();
+        let mut meshes = MeshWarehouse::new();
+        let slot = meshes.load(mesh_file.path()).unwrap();
+        let (mut scene, material) = make_scene();
+        let instance = scene.add_mesh(&meshes, slot, &Transformation::identity(), material, MeshShading::Flat);
+        let mut system_under_test = AssetWatcher::new();
+        system_under_test.watch_mesh(&meshes, slot, instance);
+
+        assert!(system_under_test.poll(&mut meshes, &mut scene).is_empty());
+    }
+
+    #[test]
+    fn test_edited_mesh_reshapes_instances() {
+        let mut mesh_file = NamedTempFile::new_in("./").unwrap();
+        mesh_file.write_all(TRIANGLE_OBJ_FILE).unwrap();
+        let mut meshes = MeshWarehouse::new();
+        let slot = meshes.load(mesh_file.path()).unwrap();
+        let (mut scene, material) = make_scene();
+        let first = scene.add_mesh(&meshes, slot, &Transformation::identity(), material, MeshShading::Flat);
+        let second = scene.add_mesh(&meshes, slot, &Transformation::identity(), material, MeshShading::Smooth);
+        let mut system_under_test = AssetWatcher::new();
+        system_under_test.watch_mesh(&meshes, slot, first);
+        system_under_test.watch_mesh(&meshes, slot, second);
+
+        overwrite(&mut mesh_file, QUAD_OBJ_FILE);
+        let reloads = system_under_test.poll(&mut meshes, &mut scene);
+
+        assert_eq!(reloads, vec![AssetReload::Reloaded { path: mesh_file.path().to_path_buf() }]);
+        assert_eq!(scene.container().triangles_count(), 4);
+        assert!(system_under_test.poll(&mut meshes, &mut scene).is_empty());
+    }
+
+    #[test]
+    fn test_broken_mesh_is_reported() {
+        let mut mesh_file = NamedTempFile::new_in("./").unwrap();
+        mesh_file.write_all(TRIANGLE_OBJ_FILE).unwrap();
+        let mut meshes = MeshWarehouse::new();
+        let slot = meshes.load(mesh_file.path()).unwrap();
+        let (mut scene, material) = make_scene();
+        let instance = scene.add_mesh(&meshes, slot, &Transformation::identity(), material, MeshShading::Flat);
+        let mut system_under_test = AssetWatcher::new();
+        system_under_test.watch_mesh(&meshes, slot, instance);
+
+        overwrite(&mut mesh_file, b"f 1 2 3 and some garbage\n");
+        let reloads = system_under_test.poll(&mut meshes, &mut scene);
+
+        assert!(matches!(reloads.as_slice(), [AssetReload::Failed { .. }]));
+        assert_eq!(scene.container().triangles_count(), 1);
+    }
+
+    #[test]
+    fn test_edited_bitmap_rewrites_region_of_same_size_only() {
+        let bitmap_file = tempfile::Builder::new().suffix(".png").tempfile_in("./").unwrap();
+        save_single_color_png(bitmap_file.path(), 1, [255, 0, 0, 255]);
+        let mut meshes = MeshWarehouse::new();
+        let (mut scene, _) = make_scene();
+        let region = load_bitmap(bitmap_file.path().to_path_buf(), scene.mutable_texture_atlas_page_composer()).unwrap();
+        let mut system_under_test = AssetWatcher::new();
+        system_under_test.watch_bitmap(bitmap_file.path().to_path_buf(), region);
+
+        save_single_color_png(bitmap_file.path(), 2, [0, 0, 255, 255]);
+        let resized = system_under_test.poll(&mut meshes, &mut scene);
+        assert!(matches!(resized.as_slice(), [AssetReload::Failed { .. }]));
+
+        save_single_color_png(bitmap_file.path(), 1, [0, 255, 0, 255]);
+        let restored = system_under_test.poll(&mut meshes, &mut scene);
+        assert_eq!(restored, vec![AssetReload::Reloaded { path: bitmap_file.path().to_path_buf() }]);
+    }
+}
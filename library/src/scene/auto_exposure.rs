@@ -0,0 +1,118 @@
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use more_asserts::{assert_gt, assert_le};
+
+// the exposure is measured from the luminance histogram of the accumulated image every frame;
+// the clamps are in stops, like the manual exposure of the color grading
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AutoExposure {
+    min_exposure_stops: f32,
+    max_exposure_stops: f32,
+    adaptation_speed: f32,
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        Self {
+            min_exposure_stops: -4.0,
+            max_exposure_stops: 4.0,
+            adaptation_speed: 1.5,
+        }
+    }
+}
+
+impl AutoExposure {
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 1;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self { ..Self::default() }
+    }
+
+    #[must_use]
+    pub fn with_exposure_range(mut self, min_stops: f32, max_stops: f32) -> Self {
+        assert_le!(min_stops, max_stops);
+        self.min_exposure_stops = min_stops;
+        self.max_exposure_stops = max_stops;
+        self
+    }
+
+    // per second: bigger values make the exposure follow the scene luminance faster
+    #[must_use]
+    pub fn with_adaptation_speed(mut self, adaptation_speed: f32) -> Self {
+        assert_gt!(adaptation_speed, 0.0);
+        self.adaptation_speed = adaptation_speed;
+        self
+    }
+
+    #[must_use]
+    pub fn min_exposure(&self) -> f32 {
+        self.min_exposure_stops
+    }
+
+    #[must_use]
+    pub fn max_exposure(&self) -> f32 {
+        self.max_exposure_stops
+    }
+
+    #[must_use]
+    pub fn adaptation_speed(&self) -> f32 {
+        self.adaptation_speed
+    }
+
+    pub(crate) fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= AutoExposure::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+        container.write_quartet(|writer| {
+            writer.write_unsigned(1);
+            writer.write_float_32(self.min_exposure_stops.exp2());
+            writer.write_float_32(self.max_exposure_stops.exp2());
+            writer.write_float_32(self.adaptation_speed);
+        });
+    }
+
+    pub(crate) fn serialize_disabled_into(container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= AutoExposure::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+        container.write_quartet_f32(0.0, 1.0, 1.0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::cast_slice;
+
+    #[test]
+    #[should_panic]
+    fn test_inverted_exposure_range() {
+        let _ = AutoExposure::new().with_exposure_range(1.0, -1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_adaptation_speed() {
+        let _ = AutoExposure::new().with_adaptation_speed(0.0);
+    }
+
+    #[test]
+    fn test_serialize_into() {
+        let system_under_test = AutoExposure::new().with_exposure_range(-2.0, 3.0).with_adaptation_speed(0.5);
+        let mut container = GpuReadySerializationBuffer::new(1, AutoExposure::SERIALIZED_QUARTET_COUNT);
+
+        system_under_test.serialize_into(&mut container);
+
+        assert!(container.object_fully_written());
+        let words: &[u32] = cast_slice(container.backend());
+        let floats: &[f32] = cast_slice(container.backend());
+        assert_eq!(words[0], 1);
+        assert_eq!(&floats[1..4], &[0.25, 8.0, 0.5]);
+    }
+
+    #[test]
+    fn test_serialize_disabled_into() {
+        let mut container = GpuReadySerializationBuffer::new(1, AutoExposure::SERIALIZED_QUARTET_COUNT);
+
+        AutoExposure::serialize_disabled_into(&mut container);
+
+        let words: &[u32] = cast_slice(container.backend());
+        assert_eq!(words[0], 0);
+    }
+}
\ No newline at end of file
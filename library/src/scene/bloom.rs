@@ -0,0 +1,100 @@
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use more_asserts::assert_ge;
+
+// the light of the bright pixels bleeding into their neighbourhood: the radiance above the threshold
+// is blurred over a chain of ever smaller images and added to the accumulated one before the tone mapping;
+// like the color grading, it is applied at the output stage and does not restart the accumulation
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Bloom {
+    intensity: f32,
+    threshold: f32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self {
+            intensity: 0.15,
+            threshold: 1.0,
+        }
+    }
+}
+
+impl Bloom {
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 1;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self { ..Self::default() }
+    }
+
+    // the weight of the blurred light added to the image
+    #[must_use]
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        assert_ge!(intensity, 0.0);
+        self.intensity = intensity;
+        self
+    }
+
+    // the luminance, in the linear radiance of the accumulated image, the pixels start to glow above
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        assert_ge!(threshold, 0.0);
+        self.threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    #[must_use]
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    pub(crate) fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= Bloom::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+        container.write_quartet_f32(self.intensity, 0.0, 0.0, 0.0);
+    }
+
+    // zero intensity: the output pass does not read the bloom chain
+    pub(crate) fn serialize_disabled_into(container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= Bloom::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+        container.write_quartet_f32(0.0, 0.0, 0.0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::cast_slice;
+
+    #[test]
+    #[should_panic]
+    fn test_negative_threshold() {
+        let _ = Bloom::new().with_threshold(-1.0);
+    }
+
+    #[test]
+    fn test_serialize_into() {
+        let system_under_test = Bloom::new().with_intensity(0.5).with_threshold(2.0);
+        let mut container = GpuReadySerializationBuffer::new(1, Bloom::SERIALIZED_QUARTET_COUNT);
+
+        system_under_test.serialize_into(&mut container);
+
+        assert!(container.object_fully_written());
+        let floats: &[f32] = cast_slice(container.backend());
+        assert_eq!(floats, &[0.5, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_serialize_disabled_into() {
+        let mut container = GpuReadySerializationBuffer::new(1, Bloom::SERIALIZED_QUARTET_COUNT);
+
+        Bloom::serialize_disabled_into(&mut container);
+
+        let floats: &[f32] = cast_slice(container.backend());
+        assert_eq!(floats[0], 0.0);
+    }
+}
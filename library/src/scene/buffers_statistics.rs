@@ -0,0 +1,146 @@
+use derive_more::Display;
+use std::fmt::Write;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Display)]
+pub enum SceneBufferKind {
+    #[display("parallelograms")]
+    Parallelograms,
+    #[display("sdf")]
+    Sdf,
+    #[display("spheres")]
+    Spheres,
+    #[display("triangles")]
+    Triangles,
+    #[display("materials")]
+    Materials,
+    #[display("bvh")]
+    Bvh,
+    #[display("bvh inflated")]
+    BvhInflated,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SceneBufferStatistics {
+    kind: SceneBufferKind,
+    bytes: u64,
+    binding_limit: u64,
+}
+
+impl SceneBufferStatistics {
+    // a buffer is reported once it occupies this share of the largest binding the adapter allows
+    pub const APPROACHING_LIMIT_FRACTION: f64 = 0.8;
+
+    #[must_use]
+    pub(crate) fn new(kind: SceneBufferKind, bytes: u64, binding_limit: u64) -> Self {
+        assert!(binding_limit > 0, "binding limit is zero");
+        Self { kind, bytes, binding_limit }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> SceneBufferKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    #[must_use]
+    pub fn binding_limit(&self) -> u64 {
+        self.binding_limit
+    }
+
+    #[must_use]
+    pub fn limit_fraction(&self) -> f64 {
+        self.bytes as f64 / self.binding_limit as f64
+    }
+
+    #[must_use]
+    pub fn approaches_limit(&self) -> bool {
+        self.limit_fraction() >= Self::APPROACHING_LIMIT_FRACTION
+    }
+}
+
+// the sizes of the gpu buffers holding the scene; they do not shrink, so these are the high-water marks
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SceneBuffersStatistics {
+    buffers: Vec<SceneBufferStatistics>,
+}
+
+impl SceneBuffersStatistics {
+    #[must_use]
+    pub(crate) fn new(buffers: Vec<SceneBufferStatistics>) -> Self {
+        Self { buffers }
+    }
+
+    #[must_use]
+    pub fn buffers(&self) -> &[SceneBufferStatistics] {
+        &self.buffers
+    }
+
+    #[must_use]
+    pub fn of_kind(&self, kind: SceneBufferKind) -> Option<&SceneBufferStatistics> {
+        self.buffers.iter().find(|buffer| buffer.kind == kind)
+    }
+
+    #[must_use]
+    pub fn total_bytes(&self) -> u64 {
+        self.buffers.iter().map(|buffer| buffer.bytes).sum()
+    }
+
+    pub fn approaching_limit(&self) -> impl Iterator<Item = &SceneBufferStatistics> {
+        self.buffers.iter().filter(|buffer| buffer.approaches_limit())
+    }
+
+    // none when all the buffers are far from the limits
+    #[must_use]
+    pub(crate) fn overflow_warning(&self) -> Option<String> {
+        let mut warning = String::new();
+        for buffer in self.approaching_limit() {
+            let separator = if warning.is_empty() { "scene buffers approach the binding limits:" } else { ";" };
+            write!(warning, "{} {} takes {} of {} bytes ({:.0}%)", separator, buffer.kind, buffer.bytes, buffer.binding_limit, buffer.limit_fraction() * 100.0).unwrap();
+        }
+        if warning.is_empty() { None } else { Some(warning) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[must_use]
+    fn make_statistics() -> SceneBuffersStatistics {
+        SceneBuffersStatistics::new(vec![
+            SceneBufferStatistics::new(SceneBufferKind::Triangles, 900, 1000),
+            SceneBufferStatistics::new(SceneBufferKind::Materials, 100, 1000),
+            SceneBufferStatistics::new(SceneBufferKind::Bvh, 800, 1000),
+        ])
+    }
+
+    #[test]
+    fn test_approaching_limit() {
+        let system_under_test = make_statistics();
+
+        let kinds: Vec<SceneBufferKind> = system_under_test.approaching_limit().map(|buffer| buffer.kind()).collect();
+
+        assert_eq!(kinds, [SceneBufferKind::Triangles, SceneBufferKind::Bvh]);
+        assert_eq!(system_under_test.total_bytes(), 1800);
+        assert_eq!(system_under_test.of_kind(SceneBufferKind::Materials).unwrap().bytes(), 100);
+        assert!(system_under_test.of_kind(SceneBufferKind::Sdf).is_none());
+    }
+
+    #[test]
+    fn test_overflow_warning() {
+        let warning = make_statistics().overflow_warning().unwrap();
+
+        assert_eq!(warning, "scene buffers approach the binding limits: triangles takes 900 of 1000 bytes (90%); bvh takes 800 of 1000 bytes (80%)");
+    }
+
+    #[test]
+    fn test_no_warning_below_limits() {
+        let system_under_test = SceneBuffersStatistics::new(vec![SceneBufferStatistics::new(SceneBufferKind::Sdf, 10, 1000)]);
+
+        assert!(system_under_test.overflow_warning().is_none());
+    }
+}
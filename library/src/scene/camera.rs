@@ -1,7 +1,9 @@
+use crate::geometry::aabb::Aabb;
 use crate::geometry::alias::{Point, Vector};
+use crate::geometry::ray::Ray;
 use crate::geometry::transform::Affine;
 use crate::serialization::serialize_matrix::serialize_matrix_4x4;
-use cgmath::{Deg, EuclideanSpace, InnerSpace, SquareMatrix, Transform, Vector3, Zero};
+use cgmath::{Angle, Deg, EuclideanSpace, InnerSpace, Rad, SquareMatrix, Transform, Vector3, Zero};
 use std::ops::Mul;
 use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
 
@@ -37,6 +39,11 @@ pub trait CameraKind {
     fn ray_origin(&self, eye: Point, look_at: Point) -> Affine;
     #[must_use]
     fn box_clone(&self) -> Box<dyn CameraKind>;
+    // how many world units the view plane unit spans, given the height of the orthographic view
+    #[must_use]
+    fn view_plane_scale(&self, _ortho_extent: f64) -> f64 {
+        1.0
+    }
 }
 
 pub struct PerspectiveCamera;
@@ -57,6 +64,9 @@ impl CameraKind for OrthographicCamera {
     fn box_clone(&self) -> Box<dyn CameraKind> {
         Box::new(Self{})
     }
+    fn view_plane_scale(&self, ortho_extent: f64) -> f64 {
+        ortho_extent / 2.0
+    }
 }
 
 pub struct Camera {
@@ -70,6 +80,10 @@ pub struct Camera {
     eye_rod_length: f64,
     look_at: Point,
     eye_offset: Vector3<f64>,
+    fov: Deg<f64>,
+    ortho_extent: f64,
+    near_clip: f64,
+    far_clip: Option<f64>,
 
     updated: bool,
     zoom_speed: f64,
@@ -78,6 +92,11 @@ pub struct Camera {
 }
 
 const MIN_ROD_LENGTH: f64 = 0.01;
+const DEFAULT_FOV: Deg<f64> = Deg(60.0);
+// the view plane spans [-1, 1] vertically
+const DEFAULT_ORTHO_EXTENT: f64 = 2.0;
+const FAR_CLIP_MARGIN: f64 = 2.0;
+const MIN_FAR_CLIP: f64 = 1.0;
 
 impl Camera {
     #[must_use]
@@ -92,6 +111,10 @@ impl Camera {
             eye_rod_length,
             look_at,
             eye_offset: Vector3::zero(),
+            fov: DEFAULT_FOV,
+            ortho_extent: DEFAULT_ORTHO_EXTENT,
+            near_clip: 0.0,
+            far_clip: None,
             updated: false,
             zoom_speed: 1.0,
             linear_speed: 1.0,
@@ -112,6 +135,10 @@ impl Camera {
         self.eye_rod_length = other.eye_rod_length;
         self.look_at = other.look_at;
         self.eye_offset = other.eye_offset;
+        self.fov = other.fov;
+        self.ortho_extent = other.ortho_extent;
+        self.near_clip = other.near_clip;
+        self.far_clip = other.far_clip;
 
         self.updated = other.updated;
         self.zoom_speed = other.zoom_speed;
@@ -155,7 +182,11 @@ impl Camera {
 
         let look_at = self.look_at;
 
-        self.world_to_camera_space = Affine::look_at_rh(eye, look_at, up);
+        // the camera space x and y are of the view plane, so the orthographic extent scales them
+        let view_plane_scale = self.kind.view_plane_scale(self.ortho_extent);
+        let into_view_plane = Affine::from_nonuniform_scale(1.0 / view_plane_scale, 1.0 / view_plane_scale, 1.0);
+
+        self.world_to_camera_space = into_view_plane * Affine::look_at_rh(eye, look_at, up);
         self.view_ray_origin = self.kind.ray_origin(eye, look_at);
     }
     
@@ -164,11 +195,32 @@ impl Camera {
         self.world_to_camera_space.invert().unwrap()
     }
 
+    #[must_use]
+    pub(crate) fn world_to_camera_space(&self) -> &Affine {
+        &self.world_to_camera_space
+    }
+
     #[must_use]
     pub fn view_ray_origin(&self) -> &Affine {
         &self.view_ray_origin
     }
-    
+
+    #[must_use]
+    pub fn eye(&self) -> Point {
+        Point::from_vec(self.camera_space_to_world().w.truncate())
+    }
+
+    #[must_use]
+    pub fn look_at(&self) -> Point {
+        self.look_at
+    }
+
+    // the unit direction the camera looks along
+    #[must_use]
+    pub fn forward(&self) -> Vector {
+        self.camera_space_to_world().transform_vector(-Vector::unit_z()).normalize()
+    }
+
     fn mark_updated_and_build(&mut self) {
         self.updated = true;
         self.build();
@@ -217,12 +269,18 @@ impl Camera {
     }
 
     pub fn rotate_horizontal(&mut self, units: f64) {
-        self.horizontal_rotation += self.rotation_speed.mul(units);
-        self.mark_updated_and_build();
+        self.turn(self.rotation_speed.mul(units), Deg::zero());
     }
 
     pub fn rotate_vertical(&mut self, units: f64) {
-        self.vertical_rotation += self.rotation_speed.mul(units);
+        self.turn(Deg::zero(), self.rotation_speed.mul(units));
+    }
+
+    // around the look at point, regardless of the rotation speed: the positive angles move the eye
+    // to the right and down
+    pub fn turn(&mut self, horizontal: Deg<f64>, vertical: Deg<f64>) {
+        self.horizontal_rotation += horizontal;
+        self.vertical_rotation += vertical;
         self.mark_updated_and_build();
     }
 
@@ -231,6 +289,148 @@ impl Camera {
         self.mark_updated_and_build();
     }
 
+    // places the eye keeping the camera's up direction in the vertical plane (no roll)
+    pub fn set_view(&mut self, eye: Point, look_at: Point) {
+        let to_eye = eye - look_at;
+        let rod_length = to_eye.magnitude();
+        assert!(rod_length >= MIN_ROD_LENGTH, "eye is too close to the look at point");
+
+        let direction = to_eye / rod_length;
+        self.horizontal_rotation = Rad::atan2(direction.x, direction.z).into();
+        self.vertical_rotation = -Deg::from(Rad::asin(direction.y.clamp(-1.0, 1.0)));
+        self.eye_rod_length = rod_length;
+        self.look_at = look_at;
+        self.eye_offset = look_at.to_vec();
+        self.mark_updated_and_build();
+    }
+
+    // vertical field of view of the perspective camera
+    pub fn set_fov(&mut self, fov: Deg<f64>) {
+        assert!(fov.0 > 0.0 && fov.0 < 180.0, "fov {fov:?} is out of (0, 180) degrees");
+        if self.fov == fov {
+            return;
+        }
+        self.fov = fov;
+        self.updated = true;
+    }
+
+    #[must_use]
+    pub fn fov(&self) -> Deg<f64> {
+        self.fov
+    }
+
+    // the height of the orthographic view in world units, the width follows the aspect ratio of the frame;
+    // the perspective camera keeps it for the case it is switched to the orthographic kind
+    pub fn set_ortho_extent(&mut self, extent: f64) {
+        assert!(extent > 0.0, "ortho extent {extent} is not positive");
+        if self.ortho_extent == extent {
+            return;
+        }
+        self.ortho_extent = extent;
+        self.mark_updated_and_build();
+    }
+
+    #[must_use]
+    pub fn ortho_extent(&self) -> f64 {
+        self.ortho_extent
+    }
+
+    // the traced rays start that far from the view ray origins, in world units
+    pub fn set_near_clip(&mut self, distance: f64) {
+        assert!(distance >= 0.0 && distance.is_finite(), "near clip {distance} is not a finite non negative distance");
+        if self.near_clip == distance {
+            return;
+        }
+        self.near_clip = distance;
+        self.updated = true;
+    }
+
+    #[must_use]
+    pub fn near_clip(&self) -> f64 {
+        self.near_clip
+    }
+
+    // the farthest hit distance of the traced rays, in world units; none derives it from the scene bounds
+    pub fn set_far_clip(&mut self, distance: Option<f64>) {
+        if let Some(distance) = distance {
+            assert!(distance > 0.0, "far clip {distance} is not positive");
+        }
+        if self.far_clip == distance {
+            return;
+        }
+        self.far_clip = distance;
+        self.updated = true;
+    }
+
+    #[must_use]
+    pub fn far_clip(&self) -> Option<f64> {
+        self.far_clip
+    }
+
+    // the far clip derived from the bounds keeps the whole scene in reach of both the view rays and the
+    // rays bouncing inside it, with a margin for the objects moving a bit before the bounds are updated
+    #[must_use]
+    pub(crate) fn max_ray_distance(&self, scene_bounds: Option<&Aabb>) -> Option<f64> {
+        if self.far_clip.is_some() {
+            return self.far_clip;
+        }
+        let bounds = scene_bounds?;
+        let eye = self.eye();
+        let farthest_corner = (eye - bounds.min()).map(f64::abs).zip((eye - bounds.max()).map(f64::abs), f64::max);
+        let reach = farthest_corner.magnitude().max(bounds.extent().magnitude());
+        Some(reach * FAR_CLIP_MARGIN + MIN_FAR_CLIP)
+    }
+
+    // moves the eye and the look at point along the right and the up directions of the view, in world units
+    pub fn pan(&mut self, right: f64, up: f64) {
+        let camera_space_to_world = self.camera_space_to_world();
+        let right_direction = camera_space_to_world.transform_vector(Vector::unit_x()).normalize();
+        let up_direction = camera_space_to_world.transform_vector(Vector::unit_y()).normalize();
+        let delta = right_direction * right + up_direction * up;
+        self.eye_offset += delta;
+        self.look_at += delta;
+        self.mark_updated_and_build();
+    }
+
+    // divides the orthographic extent by the factor keeping the scene point seen through the view plane
+    // point ('s' and 't' as of 'ray_through') in place; the perspective view does not change, see 'set_fov'
+    pub fn zoom_about_view_point(&mut self, factor: f64, s: f64, t: f64) {
+        assert!(factor > 0.0, "zoom factor {factor} is not positive");
+        let scale_before = self.kind.view_plane_scale(self.ortho_extent);
+        self.ortho_extent /= factor;
+        let scale_after = self.kind.view_plane_scale(self.ortho_extent);
+        self.pan(s * (scale_before - scale_after), t * (scale_before - scale_after));
+    }
+
+    // in world units, at the distance from the eye along the view direction
+    #[must_use]
+    pub(crate) fn view_height_at(&self, distance: f64) -> f64 {
+        let forward = self.forward();
+        let [top, bottom] = [1.0, -1.0].map(|t| {
+            let ray = self.ray_through(0.0, t);
+            let eye_to_origin = ray.origin() - self.eye();
+            ray.at((distance - eye_to_origin.dot(forward)) / ray.direction().dot(forward))
+        });
+        (top - bottom).magnitude()
+    }
+
+    // distance from the eye to the view plane spanning [-1, 1] vertically
+    #[must_use]
+    pub(crate) fn fov_factor(&self) -> f64 {
+        1.0 / (self.fov / 2.0).tan()
+    }
+
+    // the ray the tracer casts through the view plane point: 's' from -aspect (left) to aspect (right),
+    // 't' from -1 (bottom) to 1 (top); must match 'get_camera_ray' of the shader
+    #[must_use]
+    pub(crate) fn ray_through(&self, s: f64, t: f64) -> Ray {
+        let camera_space_to_world = self.camera_space_to_world();
+        let eye = Point::from_vec(camera_space_to_world.w.truncate());
+        let view_plane_point = eye + camera_space_to_world.transform_vector(Vector::new(s, t, -self.fov_factor()));
+        let origin = self.view_ray_origin.transform_point(view_plane_point);
+        Ray::new(origin, (view_plane_point - origin).normalize())
+    }
+
     pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 8;
 
     pub(crate) fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
@@ -368,6 +568,104 @@ mod tests {
         assert_camera_serialized_data(&system_under_test, expected_serialized_camera);
     }
 
+    #[test]
+    fn test_set_view() {
+        let eye = Point::new(1.0, 3.0, -2.0);
+        let look_at = Point::new(-1.0, 0.5, 4.0);
+        let mut system_under_test = Camera::new_perspective_camera(1.0, Point::origin());
+
+        system_under_test.set_view(eye, look_at);
+
+        let camera_to_world = system_under_test.camera_space_to_world();
+        let expected_forward = (look_at - eye).normalize();
+        assert_abs_diff_eq!(camera_to_world.transform_point(Point::origin()), eye, epsilon = 1e-12);
+        assert_abs_diff_eq!(camera_to_world.transform_vector(Vector::new(0.0, 0.0, -1.0)), expected_forward, epsilon = 1e-12);
+        assert_abs_diff_eq!(camera_to_world.transform_vector(Vector::new(1.0, 0.0, 0.0)).y, 0.0, epsilon = 1e-12);
+        assert!(system_under_test.check_and_clear_updated_status());
+    }
+
+    #[test]
+    fn test_set_fov() {
+        let mut system_under_test = Camera::new_perspective_camera(1.0, Point::origin());
+        assert_abs_diff_eq!(system_under_test.fov_factor(), 3.0_f64.sqrt(), epsilon = 1e-12);
+
+        system_under_test.set_fov(Deg(90.0));
+
+        assert_abs_diff_eq!(system_under_test.fov_factor(), 1.0, epsilon = 1e-12);
+        assert!(system_under_test.check_and_clear_updated_status());
+    }
+
+    #[test]
+    fn test_set_ortho_extent() {
+        let mut system_under_test = Camera::new_orthographic_camera(2.0, Point::origin());
+
+        system_under_test.set_ortho_extent(8.0);
+
+        let ray = system_under_test.ray_through(0.5, -0.25);
+        assert_abs_diff_eq!(ray.origin(), Point::new(2.0, -1.0, 2.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(ray.direction(), Vector::new(0.0, 0.0, -1.0), epsilon = 1e-12);
+        assert!(system_under_test.check_and_clear_updated_status());
+
+        system_under_test.set_ortho_extent(8.0);
+        assert_eq!(false, system_under_test.check_and_clear_updated_status());
+    }
+
+    #[test]
+    fn test_max_ray_distance() {
+        let mut system_under_test = Camera::new_perspective_camera(2.0, Point::origin());
+        let bounds = Aabb::from_points(Point::new(0.0, 0.0, -10.0), Point::new(1.0, 1.0, -10.0));
+
+        assert_eq!(system_under_test.max_ray_distance(None), None);
+        assert_abs_diff_eq!(system_under_test.max_ray_distance(Some(&bounds)).unwrap(), 2.0 * 146.0_f64.sqrt() + 1.0, epsilon = 1e-12);
+
+        system_under_test.set_far_clip(Some(3.0));
+        assert_eq!(system_under_test.max_ray_distance(Some(&bounds)), Some(3.0));
+        assert!(system_under_test.check_and_clear_updated_status());
+
+        system_under_test.set_near_clip(0.5);
+        assert_eq!(system_under_test.near_clip(), 0.5);
+        assert!(system_under_test.check_and_clear_updated_status());
+        system_under_test.set_near_clip(0.5);
+        assert_eq!(false, system_under_test.check_and_clear_updated_status());
+    }
+
+    #[test]
+    fn test_pan() {
+        let mut system_under_test = Camera::new_orthographic_camera(2.0, Point::origin());
+        system_under_test.rotate_horizontal(90.0);
+        system_under_test.set_ortho_extent(4.0);
+
+        system_under_test.pan(1.0, 0.5);
+
+        let center = system_under_test.ray_through(0.0, 0.0);
+        assert_abs_diff_eq!(center.origin(), Point::new(2.0, 0.5, -1.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(center.direction(), Vector::new(-1.0, 0.0, 0.0), epsilon = 1e-12);
+        assert!(system_under_test.check_and_clear_updated_status());
+    }
+
+    #[test]
+    fn test_zoom_about_view_point() {
+        let mut system_under_test = Camera::new_orthographic_camera(2.0, Point::origin());
+        let anchor = system_under_test.ray_through(0.5, -0.25).origin();
+
+        system_under_test.zoom_about_view_point(4.0, 0.5, -0.25);
+
+        assert_abs_diff_eq!(system_under_test.ortho_extent(), 0.5, epsilon = 1e-12);
+        assert_abs_diff_eq!(system_under_test.ray_through(0.5, -0.25).origin(), anchor, epsilon = 1e-12);
+        assert_abs_diff_eq!(system_under_test.ray_through(-1.0, 1.0).origin(), Point::new(0.125, 0.0625, 2.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_view_height_at() {
+        let mut system_under_test = Camera::new_perspective_camera(2.0, Point::origin());
+        system_under_test.set_fov(Deg(90.0));
+        assert_abs_diff_eq!(system_under_test.view_height_at(3.0), 6.0, epsilon = 1e-12);
+
+        system_under_test.set_kind(Box::new(OrthographicCamera));
+        system_under_test.set_ortho_extent(5.0);
+        assert_abs_diff_eq!(system_under_test.view_height_at(3.0), 5.0, epsilon = 1e-12);
+    }
+
     #[test]
     fn test_projection_into_point() {
         let projection_target = Point::new(1.0, 2.0, 3.0);
@@ -388,4 +686,27 @@ mod tests {
         assert_eq!(projection.transform_point(Point::new(1.0, 1.0, 1.0)), Point::new(1.0, 1.0, 1.0));
         assert_eq!(projection.transform_point(Point::new(2.0, 2.0, 2.0)), Point::new(1.0, 2.0, 2.0));
     }
+
+    #[test]
+    fn test_perspective_ray_through() {
+        let system_under_test = Camera::new_perspective_camera(2.0, Point::origin());
+
+        let center = system_under_test.ray_through(0.0, 0.0);
+        assert_abs_diff_eq!(center.origin(), Point::new(0.0, 0.0, 2.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(center.direction(), Vector::new(0.0, 0.0, -1.0), epsilon = 1e-12);
+
+        let top_right = system_under_test.ray_through(1.0, 1.0);
+        assert_abs_diff_eq!(top_right.origin(), Point::new(0.0, 0.0, 2.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(top_right.direction(), Vector::new(1.0, 1.0, -3.0_f64.sqrt()).normalize(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_orthographic_ray_through() {
+        let system_under_test = Camera::new_orthographic_camera(2.0, Point::origin());
+
+        let ray = system_under_test.ray_through(0.5, -0.25);
+
+        assert_abs_diff_eq!(ray.origin(), Point::new(0.5, -0.25, 2.0), epsilon = 1e-12);
+        assert_abs_diff_eq!(ray.direction(), Vector::new(0.0, 0.0, -1.0), epsilon = 1e-12);
+    }
 }
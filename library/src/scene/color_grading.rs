@@ -0,0 +1,128 @@
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use more_asserts::assert_ge;
+
+// applied to the accumulated image at the output stage, so changing it does not restart the accumulation
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ColorGrading {
+    exposure_stops: f32,
+    contrast: f32,
+    saturation: f32,
+    white_balance: [f32; 3],
+}
+
+impl Default for ColorGrading {
+    fn default() -> Self {
+        Self {
+            exposure_stops: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            white_balance: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl ColorGrading {
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 2;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self { ..Self::default() }
+    }
+
+    // each stop doubles (or halves, if negative) the brightness
+    #[must_use]
+    pub fn with_exposure(mut self, stops: f32) -> Self {
+        self.exposure_stops = stops;
+        self
+    }
+
+    #[must_use]
+    pub fn with_contrast(mut self, contrast: f32) -> Self {
+        assert_ge!(contrast, 0.0);
+        self.contrast = contrast;
+        self
+    }
+
+    // zero gives grayscale, one keeps the colors unchanged
+    #[must_use]
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        assert_ge!(saturation, 0.0);
+        self.saturation = saturation;
+        self
+    }
+
+    // per-channel gains applied before the tone mapping
+    #[must_use]
+    pub fn with_white_balance(mut self, r: f32, g: f32, b: f32) -> Self {
+        assert_ge!(r, 0.0);
+        assert_ge!(g, 0.0);
+        assert_ge!(b, 0.0);
+        self.white_balance = [r, g, b];
+        self
+    }
+
+    #[must_use]
+    pub fn exposure(&self) -> f32 {
+        self.exposure_stops
+    }
+
+    #[must_use]
+    pub fn contrast(&self) -> f32 {
+        self.contrast
+    }
+
+    #[must_use]
+    pub fn saturation(&self) -> f32 {
+        self.saturation
+    }
+
+    #[must_use]
+    pub fn white_balance(&self) -> [f32; 3] {
+        self.white_balance
+    }
+
+    pub(crate) fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= ColorGrading::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+
+        container.write_quartet_f32(self.white_balance[0], self.white_balance[1], self.white_balance[2], self.exposure_stops.exp2());
+        container.write_quartet_f32(self.contrast, self.saturation, 0.0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::cast_slice;
+
+    #[test]
+    fn test_default_is_identity() {
+        let system_under_test = ColorGrading::new();
+
+        assert_eq!(system_under_test.exposure(), 0.0);
+        assert_eq!(system_under_test.contrast(), 1.0);
+        assert_eq!(system_under_test.saturation(), 1.0);
+        assert_eq!(system_under_test.white_balance(), [1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_saturation() {
+        let _ = ColorGrading::new().with_saturation(-0.5);
+    }
+
+    #[test]
+    fn test_serialize_into() {
+        let system_under_test = ColorGrading::new()
+            .with_exposure(2.0)
+            .with_contrast(1.5)
+            .with_saturation(0.25)
+            .with_white_balance(1.0, 0.5, 2.0);
+        let mut container = GpuReadySerializationBuffer::new(1, ColorGrading::SERIALIZED_QUARTET_COUNT);
+
+        system_under_test.serialize_into(&mut container);
+
+        assert!(container.object_fully_written());
+        let floats: &[f32] = cast_slice(container.backend());
+        assert_eq!(floats, &[1.0, 0.5, 2.0, 4.0, 1.5, 0.25, 0.0, 0.0]);
+    }
+}
\ No newline at end of file
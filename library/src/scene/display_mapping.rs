@@ -0,0 +1,140 @@
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use more_asserts::assert_gt;
+
+// the curve compressing the hdr radiance into the displayable range
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub enum ToneMapping {
+    // filmic: desaturates and rolls the highlights off smoothly
+    #[default]
+    Aces,
+    // 'x / (1 + x)' per channel: keeps the hues, flattens the contrast
+    Reinhard,
+    // the values above one are clipped
+    None,
+}
+
+impl ToneMapping {
+    // must match 'tone_map' in the shader
+    #[must_use]
+    fn gpu_id(self) -> u32 {
+        match self {
+            ToneMapping::Aces => 0,
+            ToneMapping::Reinhard => 1,
+            ToneMapping::None => 2,
+        }
+    }
+}
+
+// how the accumulated radiance is turned into the displayed color; applied at the output stage,
+// so changing it does not restart the accumulation
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DisplayMapping {
+    tone_mapping: ToneMapping,
+    exposure_stops: f32,
+    gamma: f32,
+}
+
+impl Default for DisplayMapping {
+    fn default() -> Self {
+        Self {
+            tone_mapping: ToneMapping::default(),
+            exposure_stops: 0.0,
+            gamma: 2.2,
+        }
+    }
+}
+
+impl DisplayMapping {
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 1;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self { ..Self::default() }
+    }
+
+    #[must_use]
+    pub fn with_tone_mapping(mut self, tone_mapping: ToneMapping) -> Self {
+        self.tone_mapping = tone_mapping;
+        self
+    }
+
+    // applied before the tone mapping, on top of the color grading one: each stop doubles (or halves, if negative) the brightness
+    #[must_use]
+    pub fn with_exposure(mut self, stops: f32) -> Self {
+        self.exposure_stops = stops;
+        self
+    }
+
+    // of the display: the tone mapped color is raised to '1 / gamma'
+    #[must_use]
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        assert_gt!(gamma, 0.0);
+        self.gamma = gamma;
+        self
+    }
+
+    #[must_use]
+    pub fn tone_mapping(&self) -> ToneMapping {
+        self.tone_mapping
+    }
+
+    #[must_use]
+    pub fn exposure(&self) -> f32 {
+        self.exposure_stops
+    }
+
+    #[must_use]
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    // 'extended_range': the surface takes the linear radiance beyond one, so neither the tone mapping nor the gamma apply
+    pub(crate) fn serialize_into(&self, extended_range: bool, container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= DisplayMapping::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+
+        container.write_quartet(|writer| {
+            writer.write_unsigned(self.tone_mapping.gpu_id());
+            writer.write_float_32(self.exposure_stops.exp2());
+            writer.write_float_32(1.0 / self.gamma);
+            writer.write_unsigned(u32::from(extended_range));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::cast_slice;
+
+    #[test]
+    fn test_default_matches_legacy_output() {
+        let system_under_test = DisplayMapping::new();
+
+        assert_eq!(system_under_test.tone_mapping(), ToneMapping::Aces);
+        assert_eq!(system_under_test.exposure(), 0.0);
+        assert_eq!(system_under_test.gamma(), 2.2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_gamma() {
+        let _ = DisplayMapping::new().with_gamma(0.0);
+    }
+
+    #[test]
+    fn test_serialize_into() {
+        let system_under_test = DisplayMapping::new()
+            .with_tone_mapping(ToneMapping::Reinhard)
+            .with_exposure(-1.0)
+            .with_gamma(2.0);
+        let mut container = GpuReadySerializationBuffer::new(1, DisplayMapping::SERIALIZED_QUARTET_COUNT);
+
+        system_under_test.serialize_into(true, &mut container);
+
+        assert!(container.object_fully_written());
+        let floats: &[f32] = cast_slice(container.backend());
+        assert_eq!(floats[0].to_bits(), 1);
+        assert_eq!(&floats[1..3], &[0.5, 0.5]);
+        assert_eq!(floats[3].to_bits(), 1);
+    }
+}
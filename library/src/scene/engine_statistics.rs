@@ -0,0 +1,149 @@
+use crate::scene::buffers_statistics::SceneBuffersStatistics;
+use std::time::Duration;
+
+// the gpu time of the passes of a label within a frame
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PassTiming {
+    label: &'static str,
+    duration: Duration,
+}
+
+impl PassTiming {
+    #[must_use]
+    pub(crate) fn new(label: &'static str, duration: Duration) -> Self {
+        Self { label, duration }
+    }
+
+    pub(crate) fn add(&mut self, duration: Duration) {
+        self.duration += duration;
+    }
+
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+// the objects of the scene by their kind
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ObjectCounts {
+    parallelograms: usize,
+    sdf: usize,
+    triangle_meshes: usize,
+    spheres: usize,
+}
+
+impl ObjectCounts {
+    #[must_use]
+    pub(crate) fn new(parallelograms: usize, sdf: usize, triangle_meshes: usize, spheres: usize) -> Self {
+        Self { parallelograms, sdf, triangle_meshes, spheres }
+    }
+
+    #[must_use]
+    pub fn parallelograms(&self) -> usize {
+        self.parallelograms
+    }
+
+    #[must_use]
+    pub fn sdf(&self) -> usize {
+        self.sdf
+    }
+
+    #[must_use]
+    pub fn triangle_meshes(&self) -> usize {
+        self.triangle_meshes
+    }
+
+    #[must_use]
+    pub fn spheres(&self) -> usize {
+        self.spheres
+    }
+
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.parallelograms + self.sdf + self.triangle_meshes + self.spheres
+    }
+}
+
+// a snapshot for debug overlays: what the scene is made of, what the gpu holds of it and how long the passes take
+#[derive(Clone, PartialEq, Debug)]
+pub struct EngineStatistics {
+    objects: ObjectCounts,
+    triangles: usize,
+    bvh_nodes: u32,
+    bvh_depth: Option<usize>,
+    buffers: SceneBuffersStatistics,
+    pass_timings: Option<Vec<PassTiming>>,
+}
+
+impl EngineStatistics {
+    #[must_use]
+    pub(crate) fn new(objects: ObjectCounts, triangles: usize, bvh_nodes: u32, bvh_depth: Option<usize>, buffers: SceneBuffersStatistics, pass_timings: Option<Vec<PassTiming>>) -> Self {
+        Self { objects, triangles, bvh_nodes, bvh_depth, buffers, pass_timings }
+    }
+
+    #[must_use]
+    pub fn objects(&self) -> ObjectCounts {
+        self.objects
+    }
+
+    // of all the meshes
+    #[must_use]
+    pub fn triangles(&self) -> usize {
+        self.triangles
+    }
+
+    #[must_use]
+    pub fn bvh_nodes(&self) -> u32 {
+        self.bvh_nodes
+    }
+
+    // none till the tree is built, or when it was loaded from a dump
+    #[must_use]
+    pub fn bvh_depth(&self) -> Option<usize> {
+        self.bvh_depth
+    }
+
+    #[must_use]
+    pub fn buffers(&self) -> &SceneBuffersStatistics {
+        &self.buffers
+    }
+
+    // of a recent frame, the gpu time of the compute passes; none when the adapter has no timestamp queries
+    #[must_use]
+    pub fn pass_timings(&self) -> Option<&[PassTiming]> {
+        self.pass_timings.as_deref()
+    }
+
+    #[must_use]
+    pub fn passes_total_time(&self) -> Option<Duration> {
+        self.pass_timings.as_ref().map(|timings| timings.iter().map(PassTiming::duration).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passes_total_time() {
+        let timings = vec![PassTiming::new("trace", Duration::from_micros(700)), PassTiming::new("bake", Duration::from_micros(300))];
+        let system_under_test = EngineStatistics::new(ObjectCounts::default(), 0, 0, None, SceneBuffersStatistics::new(Vec::new()), Some(timings));
+
+        assert_eq!(system_under_test.passes_total_time(), Some(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn test_no_timings_without_timestamp_queries() {
+        let system_under_test = EngineStatistics::new(ObjectCounts::new(0, 1, 0, 2), 0, 5, Some(3), SceneBuffersStatistics::new(Vec::new()), None);
+
+        assert_eq!(system_under_test.pass_timings(), None);
+        assert_eq!(system_under_test.passes_total_time(), None);
+        assert_eq!(system_under_test.objects().total(), 3);
+    }
+}
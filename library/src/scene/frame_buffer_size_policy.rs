@@ -0,0 +1,41 @@
+use crate::gpu::viewport::Viewport;
+
+// the size of the frame buffer against the part of the output (the window or the texture) it covers
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum FrameBufferSizePolicy {
+    // a frame buffer pixel per output pixel
+    #[default]
+    MatchOutput,
+    // the frame buffer is the output part scaled along each axis, up to 'MAX_SCALE'; above one it is supersampled
+    // and the output pass averages the pixels down (2.0 traces four pixels per output one, at four times the cost),
+    // below one it is upscaled (0.5 traces a quarter of the pixels)
+    Scaled(f64),
+}
+
+impl FrameBufferSizePolicy {
+    // must match 'MAX_DOWNSAMPLE_SIDE' of the shader
+    pub const MAX_SCALE: f64 = 4.0;
+
+    #[must_use]
+    pub(crate) fn apply(&self, viewport: Viewport) -> Viewport {
+        match self {
+            FrameBufferSizePolicy::MatchOutput => viewport,
+            FrameBufferSizePolicy::Scaled(scale) => viewport.with_frame_scale(*scale),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::dpi::PhysicalSize;
+
+    #[test]
+    fn test_apply() {
+        let viewport = Viewport::fit(PhysicalSize::new(640, 480), None);
+
+        assert_eq!(FrameBufferSizePolicy::MatchOutput.apply(viewport).size(), PhysicalSize::new(640, 480));
+        assert_eq!(FrameBufferSizePolicy::Scaled(2.0).apply(viewport).size(), PhysicalSize::new(1280, 960));
+        assert_eq!(FrameBufferSizePolicy::Scaled(0.5).apply(viewport).size(), PhysicalSize::new(320, 240));
+    }
+}
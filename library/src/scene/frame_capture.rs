@@ -0,0 +1,370 @@
+use anyhow::bail;
+use bitflags::bitflags;
+use exr::prelude::{f16, write_rgba_file, Image, SpecificChannels, WritableImage};
+use std::path::{Path, PathBuf};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CaptureFormat {
+    // 8 bits per channel; the aovs are quantized to fit
+    Png,
+    // 32 bit floats, the object ids are unsigned integers
+    Exr,
+}
+
+impl CaptureFormat {
+    #[must_use]
+    fn extension(&self) -> &'static str {
+        match self {
+            CaptureFormat::Png => "png",
+            CaptureFormat::Exr => "exr",
+        }
+    }
+}
+
+bitflags! {
+    // the frame buffer layers saved next to the presented image, into the files suffixed with the layer name;
+    // the lighting ones are there only while the renderer keeps them, see 'Engine::set_lighting_aovs'
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    pub struct CapturedAovs: u32 {
+        const ALBEDO = 0b000_0001;
+        const NORMAL = 0b000_0010;
+        const OBJECT_ID = 0b000_0100;
+        const DEPTH = 0b000_1000;
+        const DIRECT_LIGHT = 0b001_0000;
+        const INDIRECT_LIGHT = 0b010_0000;
+        const EMISSION = 0b100_0000;
+        const LIGHTING = Self::DIRECT_LIGHT.bits() | Self::INDIRECT_LIGHT.bits() | Self::EMISSION.bits();
+    }
+}
+
+// the presented image and the asked for layers, read back from the gpu; the layers are of the frame buffer size,
+// row by row from the top left pixel
+pub struct CapturedFrame {
+    width: u32,
+    height: u32,
+    // as stored by the presentation format, the display mapping applied
+    image: Vec<[f32; 4]>,
+    albedo: Option<Vec<[f32; 3]>>,
+    normal: Option<Vec<[f32; 3]>>,
+    object_id: Option<Vec<u32>>,
+    depth: Option<Vec<f32>>,
+    direct_light: Option<Vec<[f32; 3]>>,
+    indirect_light: Option<Vec<[f32; 3]>>,
+    emission: Option<Vec<[f32; 3]>>,
+}
+
+impl CapturedFrame {
+    // the depth of the rays missing the scene is the tracer's 'MAX_FLOAT'
+    const BACKGROUND_DEPTH: f32 = 1.0e9;
+
+    #[must_use]
+    pub(crate) fn new(width: u32, height: u32, image: Vec<[f32; 4]>) -> Self {
+        assert_eq!(image.len(), (width * height) as usize);
+        Self { width, height, image, albedo: None, normal: None, object_id: None, depth: None, direct_light: None, indirect_light: None, emission: None }
+    }
+
+    #[must_use]
+    pub(crate) fn with_albedo(mut self, albedo: Vec<[f32; 3]>) -> Self {
+        self.albedo = Some(albedo);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_normal(mut self, normal: Vec<[f32; 3]>) -> Self {
+        self.normal = Some(normal);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_object_id(mut self, object_id: Vec<u32>) -> Self {
+        self.object_id = Some(object_id);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_depth(mut self, depth: Vec<f32>) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_lighting(mut self, direct_light: Vec<[f32; 3]>, indirect_light: Vec<[f32; 3]>, emission: Vec<[f32; 3]>) -> Self {
+        self.direct_light = Some(direct_light);
+        self.indirect_light = Some(indirect_light);
+        self.emission = Some(emission);
+        self
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[must_use]
+    pub fn image(&self) -> &[[f32; 4]] {
+        &self.image
+    }
+
+    #[must_use]
+    pub fn albedo(&self) -> Option<&[[f32; 3]]> {
+        self.albedo.as_deref()
+    }
+
+    // of the world space, unit length; zero for the background
+    #[must_use]
+    pub fn normal(&self) -> Option<&[[f32; 3]]> {
+        self.normal.as_deref()
+    }
+
+    // zero for the background
+    #[must_use]
+    pub fn object_id(&self) -> Option<&[u32]> {
+        self.object_id.as_deref()
+    }
+
+    // the distance along the camera ray, a huge one for the background
+    #[must_use]
+    pub fn depth(&self) -> Option<&[f32]> {
+        self.depth.as_deref()
+    }
+
+    // the light the visible surfaces scatter straight from the emitters and the sky; the direct, the indirect
+    // light and the emission of a pixel add up to its radiance, before the display mapping
+    #[must_use]
+    pub fn direct_light(&self) -> Option<&[[f32; 3]]> {
+        self.direct_light.as_deref()
+    }
+
+    // the light the visible surfaces scatter after it has bounced off the others
+    #[must_use]
+    pub fn indirect_light(&self) -> Option<&[[f32; 3]]> {
+        self.indirect_light.as_deref()
+    }
+
+    // the radiance of the visible surfaces themselves, and of the sky where nothing is hit
+    #[must_use]
+    pub fn emission(&self) -> Option<&[[f32; 3]]> {
+        self.emission.as_deref()
+    }
+
+    // the pixels the output pass has written into a texture of the format, tightly packed
+    pub(crate) fn decode_presented(pixels: &[u8], format: wgpu::TextureFormat) -> anyhow::Result<Vec<[f32; 4]>> {
+        let unorm = |channel: u8| f32::from(channel) / 255.0;
+        let decoded = match format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => {
+                pixels.chunks_exact(4).map(|pixel| [unorm(pixel[0]), unorm(pixel[1]), unorm(pixel[2]), unorm(pixel[3])]).collect()
+            }
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => {
+                pixels.chunks_exact(4).map(|pixel| [unorm(pixel[2]), unorm(pixel[1]), unorm(pixel[0]), unorm(pixel[3])]).collect()
+            }
+            wgpu::TextureFormat::Rgb10a2Unorm => {
+                pixels.chunks_exact(4).map(|pixel| {
+                    let bits = u32::from_le_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                    let ten_bits = |shift: u32| ((bits >> shift) & 0x3FF) as f32 / 1023.0;
+                    [ten_bits(0), ten_bits(10), ten_bits(20), (bits >> 30) as f32 / 3.0]
+                }).collect()
+            }
+            wgpu::TextureFormat::Rgba16Float => {
+                pixels.chunks_exact(8).map(|pixel| {
+                    let half = |at: usize| f16::from_le_bytes([pixel[at], pixel[at + 1]]).to_f32();
+                    [half(0), half(2), half(4), half(6)]
+                }).collect()
+            }
+            unsupported => bail!("the presentation format {unsupported:?} can not be captured"),
+        };
+        Ok(decoded)
+    }
+
+    // the layers go into the files named after the image one, e.g. 'shot_albedo.png' for 'shot.png'
+    pub(crate) fn save(&self, path: &Path, format: CaptureFormat) -> anyhow::Result<()> {
+        self.save_colors(path, format, &self.image)?;
+        let opaque = |colors: &[[f32; 3]]| -> Vec<[f32; 4]> { colors.iter().map(|[r, g, b]| [*r, *g, *b, 1.0]).collect() };
+        if let Some(albedo) = &self.albedo {
+            self.save_colors(&Self::layer_path(path, "albedo", format), format, &opaque(albedo))?;
+        }
+        if let Some(normal) = &self.normal {
+            // the png keeps the usual normal map encoding, the exr the vectors as they are
+            let normal: Vec<[f32; 4]> = match format {
+                CaptureFormat::Png => normal.iter().map(|[x, y, z]| [x * 0.5 + 0.5, y * 0.5 + 0.5, z * 0.5 + 0.5, 1.0]).collect(),
+                CaptureFormat::Exr => opaque(normal),
+            };
+            self.save_colors(&Self::layer_path(path, "normal", format), format, &normal)?;
+        }
+        if let Some(object_id) = &self.object_id {
+            self.save_object_id(&Self::layer_path(path, "object_id", format), format, object_id)?;
+        }
+        if let Some(depth) = &self.depth {
+            self.save_depth(&Self::layer_path(path, "depth", format), format, depth)?;
+        }
+        for (layer, colors) in [("direct_light", &self.direct_light), ("indirect_light", &self.indirect_light), ("emission", &self.emission)] {
+            if let Some(colors) = colors {
+                self.save_colors(&Self::layer_path(path, layer, format), format, &opaque(colors))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn save_colors(&self, path: &Path, format: CaptureFormat, colors: &[[f32; 4]]) -> anyhow::Result<()> {
+        let width = self.width as usize;
+        match format {
+            CaptureFormat::Png => {
+                let quantize = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+                let bytes: Vec<u8> = colors.iter().flat_map(|[r, g, b, a]| [quantize(*r), quantize(*g), quantize(*b), quantize(*a)]).collect();
+                image::save_buffer(path, &bytes, self.width, self.height, image::ExtendedColorType::Rgba8)?;
+            }
+            CaptureFormat::Exr => {
+                write_rgba_file(path, width, self.height as usize, |x, y| {
+                    let [r, g, b, a] = colors[y * width + x];
+                    (r, g, b, a)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    // the png spreads an id over the four channels, little endian, so that it survives the 8 bit quantization
+    fn save_object_id(&self, path: &Path, format: CaptureFormat, object_id: &[u32]) -> anyhow::Result<()> {
+        let width = self.width as usize;
+        match format {
+            CaptureFormat::Png => {
+                let bytes: Vec<u8> = object_id.iter().flat_map(|id| id.to_le_bytes()).collect();
+                image::save_buffer(path, &bytes, self.width, self.height, image::ExtendedColorType::Rgba8)?;
+            }
+            CaptureFormat::Exr => {
+                let channels = SpecificChannels::build()
+                    .with_channel("id")
+                    .with_pixel_fn(|position| (object_id[position.y() * width + position.x()],));
+                Image::from_channels((width, self.height as usize), channels).write().to_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    // the png scales the distances by the farthest surface one, the background is white
+    fn save_depth(&self, path: &Path, format: CaptureFormat, depth: &[f32]) -> anyhow::Result<()> {
+        let width = self.width as usize;
+        match format {
+            CaptureFormat::Png => {
+                let farthest = depth.iter().copied().filter(|distance| *distance < Self::BACKGROUND_DEPTH).fold(0.0_f32, f32::max);
+                let scale = if farthest > 0.0 { 255.0 / farthest } else { 0.0 };
+                let bytes: Vec<u8> = depth.iter().map(|distance| {
+                    if *distance < Self::BACKGROUND_DEPTH { (distance * scale).round().min(255.0) as u8 } else { 255 }
+                }).collect();
+                image::save_buffer(path, &bytes, self.width, self.height, image::ExtendedColorType::L8)?;
+            }
+            CaptureFormat::Exr => {
+                let channels = SpecificChannels::build()
+                    .with_channel("Z")
+                    .with_pixel_fn(|position| (depth[position.y() * width + position.x()],));
+                Image::from_channels((width, self.height as usize), channels).write().to_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    fn layer_path(path: &Path, layer: &str, format: CaptureFormat) -> PathBuf {
+        let stem = path.file_stem().map_or_else(|| "frame".into(), |stem| stem.to_string_lossy());
+        path.with_file_name(format!("{stem}_{layer}.{}", format.extension()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_bgra() {
+        let system_under_test = CapturedFrame::decode_presented(&[0, 51, 255, 255], wgpu::TextureFormat::Bgra8UnormSrgb).unwrap();
+
+        assert_eq!(system_under_test, vec![[1.0, 0.2, 0.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_decode_half_float() {
+        let mut pixel = Vec::new();
+        for channel in [0.5_f32, 2.0, 0.0, 1.0] {
+            pixel.extend_from_slice(&f16::from_f32(channel).to_le_bytes());
+        }
+
+        let system_under_test = CapturedFrame::decode_presented(&pixel, wgpu::TextureFormat::Rgba16Float).unwrap();
+
+        assert_eq!(system_under_test, vec![[0.5, 2.0, 0.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_decode_unsupported_format() {
+        assert!(CapturedFrame::decode_presented(&[0; 4], wgpu::TextureFormat::R32Uint).is_err());
+    }
+
+    #[test]
+    fn test_layer_path() {
+        let system_under_test = CapturedFrame::layer_path(Path::new("/tmp/shots/shot.png"), "albedo", CaptureFormat::Exr);
+
+        assert_eq!(system_under_test, PathBuf::from("/tmp/shots/shot_albedo.exr"));
+    }
+
+    #[test]
+    fn test_save_png_with_layers() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("shot.png");
+        let system_under_test = CapturedFrame::new(2, 1, vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]])
+            .with_normal(vec![[0.0, 0.0, 1.0]; 2])
+            .with_object_id(vec![7, 0x01020304]);
+
+        system_under_test.save(&path, CaptureFormat::Png).unwrap();
+
+        let image = image::open(&path).unwrap().to_rgba8();
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 255, 255]);
+        let normal = image::open(directory.path().join("shot_normal.png")).unwrap().to_rgba8();
+        assert_eq!(normal.get_pixel(0, 0).0, [128, 128, 255, 255]);
+        let object_id = image::open(directory.path().join("shot_object_id.png")).unwrap().to_rgba8();
+        assert_eq!(object_id.get_pixel(1, 0).0, [4, 3, 2, 1]);
+        assert!(false == directory.path().join("shot_albedo.png").exists());
+    }
+
+    #[test]
+    fn test_save_png_depth() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("shot.png");
+        let system_under_test = CapturedFrame::new(3, 1, vec![[0.0; 4]; 3]).with_depth(vec![2.0, 4.0, 1.0e9]);
+
+        system_under_test.save(&path, CaptureFormat::Png).unwrap();
+
+        let depth = image::open(directory.path().join("shot_depth.png")).unwrap().to_luma8();
+        assert_eq!(depth.as_raw(), &vec![128, 255, 255]);
+    }
+
+    #[test]
+    fn test_save_lighting() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("shot.exr");
+        let system_under_test = CapturedFrame::new(1, 1, vec![[1.0; 4]])
+            .with_lighting(vec![[0.5, 0.5, 0.5]], vec![[0.25, 0.25, 0.25]], vec![[0.0; 3]]);
+
+        system_under_test.save(&path, CaptureFormat::Exr).unwrap();
+
+        for layer in ["direct_light", "indirect_light", "emission"] {
+            assert!(directory.path().join(format!("shot_{layer}.exr")).exists());
+        }
+        assert_eq!(system_under_test.direct_light(), Some([[0.5, 0.5, 0.5]].as_slice()));
+        assert_eq!(system_under_test.depth(), None);
+    }
+
+    #[test]
+    fn test_save_exr() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("shot.exr");
+        let system_under_test = CapturedFrame::new(1, 1, vec![[2.5, 0.5, 0.25, 1.0]]).with_object_id(vec![42]);
+
+        system_under_test.save(&path, CaptureFormat::Exr).unwrap();
+
+        assert!(path.exists());
+        assert!(directory.path().join("shot_object_id.exr").exists());
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+use std::time::Duration;
+
+// what updating the scene buffers took in a frame; all zero for the frames the scene did not change in
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct FrameStatistics {
+    uploaded_buffers: u32,
+    uploaded_bytes: u64,
+    peak_staging_bytes: u64,
+    serialization_time: Duration,
+    bvh_build_time: Duration,
+}
+
+impl FrameStatistics {
+    #[must_use]
+    pub(crate) fn with_upload(mut self, bytes: u64, serialization_time: Duration) -> Self {
+        self.uploaded_buffers += 1;
+        self.uploaded_bytes += bytes;
+        self.peak_staging_bytes = self.peak_staging_bytes.max(bytes);
+        self.serialization_time += serialization_time;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_bvh_build_time(mut self, bvh_build_time: Duration) -> Self {
+        self.bvh_build_time += bvh_build_time;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn merge(&self, another: FrameStatistics) -> Self {
+        Self {
+            uploaded_buffers: self.uploaded_buffers + another.uploaded_buffers,
+            uploaded_bytes: self.uploaded_bytes + another.uploaded_bytes,
+            peak_staging_bytes: self.peak_staging_bytes.max(another.peak_staging_bytes),
+            serialization_time: self.serialization_time + another.serialization_time,
+            bvh_build_time: self.bvh_build_time + another.bvh_build_time,
+        }
+    }
+
+    // the buffers written whole or in part, and the new ones made for the grown content
+    #[must_use]
+    pub fn uploaded_buffers(&self) -> u32 {
+        self.uploaded_buffers
+    }
+
+    #[must_use]
+    pub fn uploaded_bytes(&self) -> u64 {
+        self.uploaded_bytes
+    }
+
+    // the largest single upload: the buffers are serialized and handed to the queue one by one
+    #[must_use]
+    pub fn peak_staging_bytes(&self) -> u64 {
+        self.peak_staging_bytes
+    }
+
+    // of the objects into the gpu layout; the bvh is timed apart
+    #[must_use]
+    pub fn serialization_time(&self) -> Duration {
+        self.serialization_time
+    }
+
+    // of both the plain and the inflated trees, along with their serialization
+    #[must_use]
+    pub fn bvh_build_time(&self) -> Duration {
+        self.bvh_build_time
+    }
+}
+
+impl fmt::Display for FrameStatistics {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "scene uploads: {} buffers, {} bytes (peak staging {} bytes); serialization (ms): {:.3}; BVH build (ms): {:.3}",
+            self.uploaded_buffers,
+            self.uploaded_bytes,
+            self.peak_staging_bytes,
+            self.serialization_time.as_secs_f64() * 1000.0,
+            self.bvh_build_time.as_secs_f64() * 1000.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let left = FrameStatistics::default().with_upload(100, Duration::from_millis(1)).with_upload(300, Duration::ZERO);
+        let right = FrameStatistics::default().with_upload(200, Duration::from_millis(2)).with_bvh_build_time(Duration::from_millis(5));
+
+        let system_under_test = left.merge(right);
+
+        assert_eq!(system_under_test.uploaded_buffers(), 3);
+        assert_eq!(system_under_test.uploaded_bytes(), 600);
+        assert_eq!(system_under_test.peak_staging_bytes(), 300);
+        assert_eq!(system_under_test.serialization_time(), Duration::from_millis(3));
+        assert_eq!(system_under_test.bvh_build_time(), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_display() {
+        let system_under_test = FrameStatistics::default().with_upload(64, Duration::from_micros(250));
+
+        assert_eq!(system_under_test.to_string(), "scene uploads: 1 buffers, 64 bytes (peak staging 64 bytes); serialization (ms): 0.250; BVH build (ms): 0.000");
+    }
+}
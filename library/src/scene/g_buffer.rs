@@ -0,0 +1,140 @@
+use crate::utils::object_uid::ObjectUid;
+
+// the surface attributes of a pixel, sampled at its center by the primary ray
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GBufferTexel {
+    object: Option<ObjectUid>,
+    depth: f32,
+    normal: [f32; 3],
+    albedo: [f32; 3],
+}
+
+impl GBufferTexel {
+    // none for the background
+    #[must_use]
+    pub fn object(&self) -> Option<ObjectUid> {
+        self.object
+    }
+
+    // the distance along the camera ray; huge for the background
+    #[must_use]
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    // the shading one, in world space; zero for the background
+    #[must_use]
+    pub fn normal(&self) -> [f32; 3] {
+        self.normal
+    }
+
+    // linear, before the lighting; zero for the background
+    #[must_use]
+    pub fn albedo(&self) -> [f32; 3] {
+        self.albedo
+    }
+}
+
+// the surface attributes of the last frame in the frame buffer pixels (the bars around the viewport
+// are not there), the pixels go row by row; the layers borrow the renderer's cpu copies, the 'w'
+// of the normals is zero and the one of the albedo is one
+#[derive(Copy, Clone, Debug)]
+pub struct GBuffer<'a> {
+    width: u32,
+    height: u32,
+    object_ids: &'a [u32],
+    depths: &'a [f32],
+    normals: &'a [[f32; 4]],
+    albedo: &'a [[f32; 4]],
+}
+
+impl<'a> GBuffer<'a> {
+    #[must_use]
+    pub(crate) fn new(width: u32, height: u32, object_ids: &'a [u32], depths: &'a [f32], normals: &'a [[f32; 4]], albedo: &'a [[f32; 4]]) -> Self {
+        let area = (width * height) as usize;
+        assert_eq!(object_ids.len(), area);
+        assert_eq!(depths.len(), area);
+        assert_eq!(normals.len(), area);
+        assert_eq!(albedo.len(), area);
+        Self { width, height, object_ids, depths, normals, albedo }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // zero for the background
+    #[must_use]
+    pub fn object_ids(&self) -> &'a [u32] {
+        self.object_ids
+    }
+
+    #[must_use]
+    pub fn depths(&self) -> &'a [f32] {
+        self.depths
+    }
+
+    #[must_use]
+    pub fn normals(&self) -> &'a [[f32; 4]] {
+        self.normals
+    }
+
+    #[must_use]
+    pub fn albedo(&self) -> &'a [[f32; 4]] {
+        self.albedo
+    }
+
+    // none outside the frame buffer
+    #[must_use]
+    pub fn texel(&self, x: u32, y: u32) -> Option<GBufferTexel> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = (self.width * y + x) as usize;
+        let [normal_x, normal_y, normal_z, _] = self.normals[index];
+        let [red, green, blue, _] = self.albedo[index];
+        Some(GBufferTexel {
+            object: (0 != self.object_ids[index]).then_some(ObjectUid(self.object_ids[index])),
+            depth: self.depths[index],
+            normal: [normal_x, normal_y, normal_z],
+            albedo: [red, green, blue],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_texel() {
+        let object_ids = [0, 7];
+        let depths = [1e9, 2.5];
+        let normals = [[0.0; 4], [0.0, 0.0, 1.0, 0.0]];
+        let albedo = [[0.0, 0.0, 0.0, 1.0], [0.5, 0.25, 0.125, 1.0]];
+        let system_under_test = GBuffer::new(1, 2, &object_ids, &depths, &normals, &albedo);
+
+        let background = system_under_test.texel(0, 0).unwrap();
+        assert_eq!(background.object(), None);
+
+        let surface = system_under_test.texel(0, 1).unwrap();
+        assert_eq!(surface.object(), Some(ObjectUid(7)));
+        assert_eq!(surface.depth(), 2.5);
+        assert_eq!(surface.normal(), [0.0, 0.0, 1.0]);
+        assert_eq!(surface.albedo(), [0.5, 0.25, 0.125]);
+
+        assert_eq!(system_under_test.texel(1, 0), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_layers_size_mismatch() {
+        let _ = GBuffer::new(2, 1, &[0], &[0.0], &[[0.0; 4]], &[[0.0; 4]]);
+    }
+}
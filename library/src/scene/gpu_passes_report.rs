@@ -0,0 +1,104 @@
+use std::fmt;
+use std::time::Duration;
+
+// the gpu time of the passes of a label, over the frames of the window the passes ran in
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GpuPassTimes {
+    label: &'static str,
+    frames: usize,
+    average: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl GpuPassTimes {
+    #[must_use]
+    pub(crate) fn new(label: &'static str, frames: usize, average: Duration, min: Duration, max: Duration) -> Self {
+        Self { label, frames, average, min, max }
+    }
+
+    #[must_use]
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    // some passes are skipped, e.g. the surface attributes one while the camera stands still
+    #[must_use]
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    #[must_use]
+    pub fn average(&self) -> Duration {
+        self.average
+    }
+
+    #[must_use]
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    #[must_use]
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+}
+
+// the rolling per-pass gpu time, of the last read back frames; the text form is for the log
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GpuPassesReport {
+    frames: usize,
+    passes: Vec<GpuPassTimes>,
+}
+
+impl GpuPassesReport {
+    #[must_use]
+    pub(crate) fn new(frames: usize, passes: Vec<GpuPassTimes>) -> Self {
+        Self { frames, passes }
+    }
+
+    #[must_use]
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    // in the order the passes first ran
+    #[must_use]
+    pub fn passes(&self) -> &[GpuPassTimes] {
+        &self.passes
+    }
+
+    #[must_use]
+    pub fn of_label(&self, label: &str) -> Option<&GpuPassTimes> {
+        self.passes.iter().find(|pass| pass.label == label)
+    }
+}
+
+impl fmt::Display for GpuPassesReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "GPU passes over {} frames (ms)", self.frames)?;
+        for pass in &self.passes {
+            write!(formatter, "; {}: avg={:.3}, min={:.3}, max={:.3}",
+                pass.label,
+                pass.average.as_secs_f64() * 1000.0,
+                pass.min.as_secs_f64() * 1000.0,
+                pass.max.as_secs_f64() * 1000.0,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let system_under_test = GpuPassesReport::new(2, vec![
+            GpuPassTimes::new("ray tracing compute pass", 2, Duration::from_micros(1500), Duration::from_millis(1), Duration::from_millis(2)),
+        ]);
+
+        assert_eq!(system_under_test.to_string(), "GPU passes over 2 frames (ms); ray tracing compute pass: avg=1.500, min=1.000, max=2.000");
+    }
+}
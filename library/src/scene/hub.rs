@@ -1,12 +1,23 @@
+use crate::animation::camera_track::{CameraPose, CameraTrack};
+use crate::animation::play_mode::ObjectAnimation;
 use crate::animation::time_tracker::TimeTracker;
-use crate::container::mesh_warehouse::{MeshWarehouse, WarehouseSlot};
+use crate::container::mesh_warehouse::{MeshShading, MeshWarehouse, WarehouseSlot};
+use crate::container::texture_atlas_page_composer::TextureAtlasPageComposer;
 use crate::container::visual_objects::VisualObjects;
 use crate::geometry::alias::{Point, Vector};
+use crate::geometry::ray::Ray;
 use crate::geometry::transform::{Affine, Transformation};
 use crate::geometry::utils::is_affine;
+use crate::material::atlas_region_mapping::AtlasRegionMappingBuilder;
 use crate::material::material_index::MaterialIndex;
+use crate::material::procedural_texture_baking::ProceduralTextureBaking;
 use crate::objects::common_properties::ObjectUid;
-use crate::sdf::framework::named_sdf::UniqueSdfClassName;
+use crate::scene::layer_mask::LayerMask;
+use crate::scene::ray_hit::RayHit;
+use crate::scene::scene_event::{ObjectChange, SceneEvent, SceneObserverId, SceneObservers};
+use crate::scene::uv_transform::UvTransform;
+use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
+use crate::utils::bitmap_utils::ImmutableBitmapReference;
 use more_asserts::assert_gt;
 use std::io::Error;
 use std::path::Path;
@@ -14,6 +25,9 @@ use std::path::Path;
 pub struct Hub {
     container: VisualObjects,
     time_tracker: TimeTracker,
+    // the renderer builds the buffers of a replaced scene anew instead of diffing its versions
+    scene_replaced: bool,
+    observers: SceneObservers,
 }
 
 impl Hub {
@@ -22,9 +36,23 @@ impl Hub {
         Self {
             container,
             time_tracker: TimeTracker::new(),
+            scene_replaced: false,
+            observers: SceneObservers::new(),
         }
     }
 
+    // for the external mirrors of the scene (ui trees, persistence, network sync): the observer is called
+    // on each change made through the hub (and the batches), before the change reaches the gpu
+    #[must_use]
+    pub fn observe(&mut self, observer: impl FnMut(&SceneEvent) + 'static) -> SceneObserverId {
+        self.observers.add(Box::new(observer))
+    }
+
+    // returns false if the observer is unknown
+    pub fn stop_observing(&mut self, observer: SceneObserverId) -> bool {
+        self.observers.remove(observer)
+    }
+
     #[must_use]
     pub(crate) fn container(&self) -> &VisualObjects {
         &self.container
@@ -45,39 +73,188 @@ impl Hub {
         &mut self.time_tracker
     }
 
+    // the object has to be animatable (an sdf); the animation starts with 'play_animation'
+    pub fn configure_animation(&mut self, target: ObjectUid, animation: ObjectAnimation) {
+        self.time_tracker.configure(target, animation);
+    }
+
+    // returns false if the target has no configured animation
+    pub fn play_animation(&mut self, target: ObjectUid) -> bool {
+        self.time_tracker.play(target)
+    }
+
     pub fn update_time(&mut self) {
         self.time_tracker.update_time();
     }
+
+    pub fn play_camera_track(&mut self, track: CameraTrack, animation: ObjectAnimation) {
+        self.time_tracker.play_camera_track(track, animation);
+    }
+
+    pub fn stop_camera_track(&mut self) {
+        self.time_tracker.stop_camera_track();
+    }
+
+    #[must_use]
+    pub(crate) fn take_camera_pose(&mut self) -> Option<CameraPose> {
+        self.time_tracker.take_camera_pose()
+    }
     
     pub fn clear_objects(&mut self) {
         self.container.clear_objects();
         self.time_tracker.clear();
+        self.observers.notify(SceneEvent::ObjectsCleared);
+    }
+
+    // the objects, the materials, the sdf classes and the textures all come from the given scene; the
+    // next frame is rendered from it as a whole. The animations of the previous objects are dropped
+    pub fn replace_scene(&mut self, scene: VisualObjects) -> VisualObjects {
+        self.time_tracker.clear();
+        self.scene_replaced = true;
+        let previous = std::mem::replace(&mut self.container, scene);
+        self.observers.notify(SceneEvent::SceneReplaced);
+        previous
+    }
+
+    // the same for the objects only: the materials and the sdf classes are kept
+    pub fn replace_objects<Outcome>(&mut self, populate: impl FnOnce(&mut Hub) -> Outcome) -> Outcome {
+        self.clear_objects();
+        let outcome = populate(self);
+        self.scene_replaced = true;
+        outcome
+    }
+
+    #[must_use]
+    pub(crate) fn take_scene_replacement(&mut self) -> bool {
+        std::mem::take(&mut self.scene_replaced)
+    }
+
+    // populates the scene with a single version bump per object kind (instead of one per object):
+    // the bvh and the gpu buffers are rebuilt once, after the closure completes
+    pub fn batch<Outcome>(&mut self, populate: impl FnOnce(&mut HubBatch) -> Outcome) -> Outcome {
+        self.container.defer_version_bumps();
+        let outcome = populate(&mut HubBatch { hub: self });
+        self.container.resume_version_bumps();
+        outcome
     }
 
     pub fn add_sdf_with_ray_march_fix(&mut self, location: &Affine, ray_marching_step_scale: f64, class_uid: &UniqueSdfClassName, material: MaterialIndex) -> ObjectUid {
         assert!(is_affine(location), "projection matrices are not supported");
         assert_gt!(ray_marching_step_scale, 0.0);
         let added = self.container.add_sdf(location, ray_marching_step_scale, class_uid, material);
-        self.time_tracker.track(added, &self.container.morphable());
+        self.time_tracker.track(added, self.container.slot_of(added).expect("added sdf has no slot"));
+        self.observers.notify(SceneEvent::ObjectAdded(added));
         added
     }
     
+    // the steps are shrunk for the classes the distance of which changes faster than the point moves
+    // (e.g. displaced ones, see 'Sdf::lipschitz_bound'); the others are marched with the full steps
     pub fn add_sdf(&mut self, location: &Affine, class_uid: &UniqueSdfClassName, material: MaterialIndex) -> ObjectUid {
-        const RAY_MARCHING_STEP_ID_SCALE: f64 = 1.0;
-        self.add_sdf_with_ray_march_fix(location, RAY_MARCHING_STEP_ID_SCALE, class_uid, material)
+        let ray_marching_step_scale = self.container.sdf_class_ray_marching_step_scale(class_uid);
+        self.add_sdf_with_ray_march_fix(location, ray_marching_step_scale, class_uid, material)
     }
     
+    // the parameter declared by the class of the instance, see 'NamedSdf::parametric'; the value
+    // has to be within the declared range
+    pub fn set_sdf_parameter(&mut self, target: ObjectUid, name: &str, value: f64) {
+        self.container.set_sdf_parameter(target, name, value);
+        self.observers.notify(SceneEvent::ObjectChanged(target, ObjectChange::SdfParameter));
+    }
+
+    #[must_use]
+    pub fn sdf_parameter(&self, target: ObjectUid, name: &str) -> f64 {
+        self.container.sdf_parameter(target, name)
+    }
+
+    pub fn add_sdf_class(&mut self, class: &NamedSdf) {
+        self.container.add_sdf_class(class);
+    }
+
+    pub fn replace_sdf_class(&mut self, class: &NamedSdf) {
+        self.container.replace_sdf_class(class);
+    }
+
+    pub fn remove_sdf_class(&mut self, name: &UniqueSdfClassName) {
+        self.container.remove_sdf_class(name);
+    }
+
     pub fn add_parallelogram(&mut self, origin: Point, local_x: Vector, local_y: Vector, material: MaterialIndex) -> ObjectUid {
-        self.container.add_parallelogram(origin, local_x, local_y, material)
+        let added = self.container.add_parallelogram(origin, local_x, local_y, material);
+        self.observers.notify(SceneEvent::ObjectAdded(added));
+        added
+    }
+
+    // moves the parallelogram (e.g. a dragged light panel) in place: unlike the delete and the add, it keeps
+    // the uid and uploads the single slot; returns false if the target is not a parallelogram
+    pub fn reshape_parallelogram(&mut self, target: ObjectUid, origin: Point, local_x: Vector, local_y: Vector) -> bool {
+        let reshaped = self.container.reshape_parallelogram(target, origin, local_x, local_y);
+        self.notify_reshaped(target, reshaped)
+    }
+
+    #[must_use]
+    pub fn degenerate_parallelogram(&self, target: ObjectUid) -> bool {
+        self.container.degenerate_parallelogram(target)
+    }
+
+    // moves the object of any kind (e.g. the one of 'ObjectDrag') by the offset in place; returns false if
+    // there is no target
+    pub fn translate(&mut self, target: ObjectUid, offset: Vector) -> bool {
+        let translated = self.container.translate(target, offset);
+        self.notify_reshaped(target, translated)
+    }
+
+    pub fn add_sphere(&mut self, center: Point, radius: f64, material: MaterialIndex) -> ObjectUid {
+        let added = self.container.add_sphere(center, radius, material);
+        self.observers.notify(SceneEvent::ObjectAdded(added));
+        added
     }
 
-    pub fn add_mesh(&mut self, source: &MeshWarehouse, slot: WarehouseSlot, transformation: &Transformation, material: MaterialIndex) -> ObjectUid {
-        self.container.add_mesh(source, slot, transformation, material)
+    pub fn add_mesh(&mut self, source: &MeshWarehouse, slot: WarehouseSlot, transformation: &Transformation, material: MaterialIndex, shading: MeshShading) -> ObjectUid {
+        let added = self.container.add_mesh(source, slot, transformation, material, shading);
+        self.observers.notify(SceneEvent::ObjectAdded(added));
+        added
     }
     
+    // re-instantiates the mesh from its (reloaded) prototype; returns false if the target is not a mesh
+    pub fn reshape_mesh(&mut self, target: ObjectUid, source: &MeshWarehouse, slot: WarehouseSlot) -> bool {
+        let reshaped = self.container.reshape_mesh(target, source, slot);
+        self.notify_reshaped(target, reshaped)
+    }
+
+    #[must_use]
+    fn notify_reshaped(&mut self, target: ObjectUid, reshaped: bool) -> bool {
+        if reshaped {
+            self.observers.notify(SceneEvent::ObjectChanged(target, ObjectChange::Shape));
+        }
+        reshaped
+    }
+
+    #[must_use]
+    pub fn mutable_texture_atlas_page_composer(&mut self) -> &mut TextureAtlasPageComposer {
+        self.container.mutable_texture_atlas_page_composer()
+    }
+
+    // the procedural albedo of the material gets baked into an atlas region before the next frame; none
+    // turns the baking off, see 'MaterialProperties::set_albedo_baking'
+    pub fn set_albedo_baking(&mut self, material: MaterialIndex, baking: Option<ProceduralTextureBaking>) {
+        self.container.materials_mutable().set_albedo_baking(material, baking);
+        self.observers.notify(SceneEvent::MaterialChanged(material));
+    }
+
+    pub(crate) fn put_baked_albedo(&mut self, material: MaterialIndex, texels: ImmutableBitmapReference, mapping: AtlasRegionMappingBuilder) -> anyhow::Result<()> {
+        let baked = self.container.put_baked_albedo(material, texels, mapping);
+        // a failed baking is turned off for the material
+        self.observers.notify(SceneEvent::MaterialChanged(material));
+        baked
+    }
+
     pub fn delete(&mut self, target: ObjectUid) {
+        let existed = self.container.slot_of(target).is_some();
         self.container.delete(target);
-        self.time_tracker.forget(target, &self.container.morphable());
+        self.time_tracker.forget(target);
+        if existed {
+            self.observers.notify(SceneEvent::ObjectRemoved(target));
+        }
     }
 
     pub fn dump_scene_bvh(&self, destination: impl AsRef<Path>) -> Result<(), Error> {
@@ -85,11 +262,242 @@ impl Hub {
     }
 
     pub fn set_material(&mut self, victim: ObjectUid, material: MaterialIndex) {
-        self.container.set_material(victim, material)
+        let changed = self.container.material_of(victim) != material;
+        self.container.set_material(victim, material);
+        if changed {
+            self.observers.notify(SceneEvent::ObjectChanged(victim, ObjectChange::Material));
+        }
     }
 
     #[must_use]
     pub fn material_of(&self, victim: ObjectUid) -> MaterialIndex {
         self.container.material_of(victim)
     }
+
+    // a hidden object keeps its uid and everything else, it is just not traced until shown again
+    pub fn set_visible(&mut self, target: ObjectUid, visible: bool) {
+        let changed = self.container.visible(target) != visible;
+        self.container.set_visible(target, visible);
+        if changed {
+            self.observers.notify(SceneEvent::ObjectChanged(target, ObjectChange::Visibility));
+        }
+    }
+
+    #[must_use]
+    pub fn visible(&self, target: ObjectUid) -> bool {
+        self.container.visible(target)
+    }
+
+    // the object is traced when the mask overlaps the camera one, see 'Engine::set_camera_layer_mask';
+    // the new objects are on 'LayerMask::DEFAULT_OBJECT_LAYER'
+    pub fn set_layer_mask(&mut self, target: ObjectUid, mask: LayerMask) {
+        let changed = self.container.layer_mask(target) != mask;
+        self.container.set_layer_mask(target, mask);
+        if changed {
+            self.observers.notify(SceneEvent::ObjectChanged(target, ObjectChange::LayerMask));
+        }
+    }
+
+    #[must_use]
+    pub fn layer_mask(&self, target: ObjectUid) -> LayerMask {
+        self.container.layer_mask(target)
+    }
+
+    // the texture coordinates of the object are transformed before its atlas regions are sampled; the new
+    // objects have 'UvTransform::IDENTITY', the materials and their mappings are shared as they are
+    pub fn set_uv_transform(&mut self, target: ObjectUid, transform: UvTransform) {
+        let changed = self.container.uv_transform(target) != transform;
+        self.container.set_uv_transform(target, transform);
+        if changed {
+            self.observers.notify(SceneEvent::ObjectChanged(target, ObjectChange::UvTransform));
+        }
+    }
+
+    #[must_use]
+    pub fn uv_transform(&self, target: ObjectUid) -> UvTransform {
+        self.container.uv_transform(target)
+    }
+
+    // a ghosted object (an opacity below one, in editor previews of the hidden or the reference geometry)
+    // is blended with what is behind it; unlike the glass, it bends no rays. The blend settles as the
+    // samples accumulate, the deterministic strategy dithers it, and the surface attributes (the picking
+    // included), the probes and the shadow map take the object for opaque
+    pub fn set_opacity(&mut self, target: ObjectUid, opacity: f32) {
+        assert!((0.0..=1.0).contains(&opacity), "opacity {opacity} is out of [0, 1]");
+        let changed = self.container.opacity(target) != opacity;
+        self.container.set_opacity(target, opacity);
+        if changed {
+            self.observers.notify(SceneEvent::ObjectChanged(target, ObjectChange::Opacity));
+        }
+    }
+
+    #[must_use]
+    pub fn opacity(&self, target: ObjectUid) -> f32 {
+        self.container.opacity(target)
+    }
+
+    // the closest object along the ray (e.g. to place a dragged one onto), among the objects a camera seeing the
+    // 'layers' sees; the sdf are hit by the boxes of their classes, the shapes themselves are known to the gpu only:
+    // such a hit is not 'RayHit::exact'
+    #[must_use]
+    pub fn cast_ray(&self, origin: Point, direction: Vector, layers: LayerMask) -> Option<RayHit> {
+        self.container.cast_ray(&Ray::new(origin, direction), layers)
+    }
+}
+
+pub struct HubBatch<'a> {
+    hub: &'a mut Hub,
+}
+
+impl HubBatch<'_> {
+    pub fn add_sdf_with_ray_march_fix(&mut self, location: &Affine, ray_marching_step_scale: f64, class_uid: &UniqueSdfClassName, material: MaterialIndex) -> ObjectUid {
+        self.hub.add_sdf_with_ray_march_fix(location, ray_marching_step_scale, class_uid, material)
+    }
+
+    pub fn add_sdf(&mut self, location: &Affine, class_uid: &UniqueSdfClassName, material: MaterialIndex) -> ObjectUid {
+        self.hub.add_sdf(location, class_uid, material)
+    }
+
+    pub fn add_parallelogram(&mut self, origin: Point, local_x: Vector, local_y: Vector, material: MaterialIndex) -> ObjectUid {
+        self.hub.add_parallelogram(origin, local_x, local_y, material)
+    }
+
+    pub fn reshape_parallelogram(&mut self, target: ObjectUid, origin: Point, local_x: Vector, local_y: Vector) -> bool {
+        self.hub.reshape_parallelogram(target, origin, local_x, local_y)
+    }
+
+    pub fn translate(&mut self, target: ObjectUid, offset: Vector) -> bool {
+        self.hub.translate(target, offset)
+    }
+
+    pub fn add_sphere(&mut self, center: Point, radius: f64, material: MaterialIndex) -> ObjectUid {
+        self.hub.add_sphere(center, radius, material)
+    }
+
+    pub fn add_mesh(&mut self, source: &MeshWarehouse, slot: WarehouseSlot, transformation: &Transformation, material: MaterialIndex, shading: MeshShading) -> ObjectUid {
+        self.hub.add_mesh(source, slot, transformation, material, shading)
+    }
+
+    pub fn set_material(&mut self, victim: ObjectUid, material: MaterialIndex) {
+        self.hub.set_material(victim, material)
+    }
+
+    pub fn set_sdf_parameter(&mut self, target: ObjectUid, name: &str, value: f64) {
+        self.hub.set_sdf_parameter(target, name, value)
+    }
+
+    pub fn set_visible(&mut self, target: ObjectUid, visible: bool) {
+        self.hub.set_visible(target, visible)
+    }
+
+    pub fn set_layer_mask(&mut self, target: ObjectUid, mask: LayerMask) {
+        self.hub.set_layer_mask(target, mask)
+    }
+
+    pub fn set_uv_transform(&mut self, target: ObjectUid, transform: UvTransform) {
+        self.hub.set_uv_transform(target, transform)
+    }
+
+    pub fn set_opacity(&mut self, target: ObjectUid, opacity: f32) {
+        self.hub.set_opacity(target, opacity)
+    }
+
+    pub fn delete(&mut self, target: ObjectUid) {
+        self.hub.delete(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::visual_objects::DataKind;
+    use crate::material::material_properties::MaterialProperties;
+    use cgmath::EuclideanSpace;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_batch_bumps_versions_once() {
+        let mut container = VisualObjects::new(None, None, None, None, None);
+        let material = container.materials_mutable().add(&MaterialProperties::default());
+        let mut system_under_test = Hub::new(container);
+        let data_version_before = system_under_test.container().data_version(DataKind::Parallelogram);
+        let mesh_data_version_before = system_under_test.container().data_version(DataKind::TriangleMesh);
+
+        let added = system_under_test.batch(|batch| {
+            (0..8).map(|i| batch.add_parallelogram(Point::new(i as f64, 0.0, 0.0), Vector::unit_x(), Vector::unit_y(), material)).collect::<Vec<_>>()
+        });
+
+        assert_eq!(added.len(), 8);
+        assert_eq!(system_under_test.container().count_of_a_kind(DataKind::Parallelogram), 8);
+        assert_eq!(system_under_test.container().data_version(DataKind::Parallelogram), data_version_before + 1);
+        assert_eq!(system_under_test.container().data_version(DataKind::TriangleMesh), mesh_data_version_before);
+    }
+
+    #[test]
+    fn test_replace_scene() {
+        let mut previous = VisualObjects::new(None, None, None, None, None);
+        let previous_material = previous.materials_mutable().add(&MaterialProperties::default());
+        previous.add_parallelogram(Point::origin(), Vector::unit_x(), Vector::unit_y(), previous_material);
+        let mut system_under_test = Hub::new(previous);
+
+        let mut next = VisualObjects::new(None, None, None, None, None);
+        let next_material = next.materials_mutable().add(&MaterialProperties::default());
+        next.add_sphere(Point::origin(), 1.0, next_material);
+        next.add_sphere(Point::new(3.0, 0.0, 0.0), 1.0, next_material);
+
+        let replaced = system_under_test.replace_scene(next);
+
+        assert_eq!(replaced.count_of_a_kind(DataKind::Parallelogram), 1);
+        assert_eq!(system_under_test.container().count_of_a_kind(DataKind::Parallelogram), 0);
+        assert_eq!(system_under_test.container().count_of_a_kind(DataKind::Sphere), 2);
+        assert!(system_under_test.take_scene_replacement());
+        assert!(false == system_under_test.take_scene_replacement());
+    }
+
+    #[test]
+    fn test_replace_objects() {
+        let mut container = VisualObjects::new(None, None, None, None, None);
+        let material = container.materials_mutable().add(&MaterialProperties::default());
+        let mut system_under_test = Hub::new(container);
+        system_under_test.add_parallelogram(Point::origin(), Vector::unit_x(), Vector::unit_y(), material);
+
+        system_under_test.replace_objects(|scene| {
+            scene.add_sphere(Point::origin(), 1.0, material);
+        });
+
+        assert_eq!(system_under_test.container().count_of_a_kind(DataKind::Parallelogram), 0);
+        assert_eq!(system_under_test.container().count_of_a_kind(DataKind::Sphere), 1);
+        assert!(system_under_test.take_scene_replacement());
+    }
+
+    #[test]
+    fn test_observed_events() {
+        let mut container = VisualObjects::new(None, None, None, None, None);
+        let material = container.materials_mutable().add(&MaterialProperties::default());
+        let other_material = container.materials_mutable().add(&MaterialProperties::default());
+        let mut system_under_test = Hub::new(container);
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let observer_received = received.clone();
+        let observer = system_under_test.observe(move |event| observer_received.borrow_mut().push(*event));
+
+        let added = system_under_test.batch(|batch| batch.add_sphere(Point::origin(), 1.0, material));
+        system_under_test.set_material(added, other_material);
+        system_under_test.set_material(added, other_material);
+        system_under_test.set_visible(added, false);
+        system_under_test.set_opacity(added, 0.5);
+        system_under_test.set_opacity(added, 0.5);
+        system_under_test.delete(added);
+        system_under_test.delete(added);
+        assert!(system_under_test.stop_observing(observer));
+        system_under_test.clear_objects();
+
+        assert_eq!(*received.borrow(), vec![
+            SceneEvent::ObjectAdded(added),
+            SceneEvent::ObjectChanged(added, ObjectChange::Material),
+            SceneEvent::ObjectChanged(added, ObjectChange::Visibility),
+            SceneEvent::ObjectChanged(added, ObjectChange::Opacity),
+            SceneEvent::ObjectRemoved(added),
+        ]);
+    }
 }
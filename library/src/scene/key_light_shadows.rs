@@ -0,0 +1,44 @@
+// how the deterministic strategy shadows its key light: the first traced emissive parallelogram
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum KeyLightShadows {
+    // a shadow ray per shaded point
+    #[default]
+    RayTraced,
+    // an orthographic depth map from the light, fit around the scene bounds and cast again once the scene changes;
+    // sampled with the 3x3 percentage closer filtering, it is cheaper on the low end gpus at the cost of the softened,
+    // biased shadows, while the points outside the map still trace the shadow rays
+    ShadowMap { resolution: u32 },
+}
+
+impl KeyLightShadows {
+    pub const MAX_SHADOW_MAP_RESOLUTION: u32 = 4096;
+
+    // zero for the traced shadow rays
+    #[must_use]
+    pub(crate) fn shadow_map_resolution(&self) -> u32 {
+        match self {
+            KeyLightShadows::RayTraced => 0,
+            KeyLightShadows::ShadowMap { resolution } => {
+                assert!((1..=Self::MAX_SHADOW_MAP_RESOLUTION).contains(resolution), "shadow map resolution {resolution} is out of [1, {}]", Self::MAX_SHADOW_MAP_RESOLUTION);
+                *resolution
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadow_map_resolution() {
+        assert_eq!(KeyLightShadows::default().shadow_map_resolution(), 0);
+        assert_eq!(KeyLightShadows::ShadowMap { resolution: 512 }.shadow_map_resolution(), 512);
+    }
+
+    #[test]
+    #[should_panic(expected = "shadow map resolution 0 is out of [1, 4096]")]
+    fn test_empty_shadow_map() {
+        let _ = KeyLightShadows::ShadowMap { resolution: 0 }.shadow_map_resolution();
+    }
+}
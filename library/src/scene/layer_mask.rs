@@ -0,0 +1,58 @@
+use more_asserts::assert_lt;
+use std::ops::BitOr;
+
+// a bit per layer: an object is traced when its layers overlap the ones of the camera
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LayerMask(pub u32);
+
+impl LayerMask {
+    pub const NONE: LayerMask = LayerMask(0);
+    pub const ALL: LayerMask = LayerMask(u32::MAX);
+
+    // the layer of the newly added objects
+    pub const DEFAULT_OBJECT_LAYER: LayerMask = LayerMask(1);
+
+    #[must_use]
+    pub fn layer(index: u32) -> Self {
+        assert_lt!(index, u32::BITS, "there are {} layers", u32::BITS);
+        LayerMask(1 << index)
+    }
+
+    #[must_use]
+    pub fn overlaps(self, other: LayerMask) -> bool {
+        0 != self.0 & other.0
+    }
+}
+
+impl BitOr for LayerMask {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        LayerMask(self.0 | other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer() {
+        assert_eq!(LayerMask::layer(0), LayerMask::DEFAULT_OBJECT_LAYER);
+        assert_eq!(LayerMask::layer(31), LayerMask(0x8000_0000));
+        assert_eq!(LayerMask::layer(1) | LayerMask::layer(3), LayerMask(0b1010));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_layer_out_of_range() {
+        let _ = LayerMask::layer(32);
+    }
+
+    #[test]
+    fn test_overlaps() {
+        assert!(LayerMask(0b0110).overlaps(LayerMask(0b0100)));
+        assert!(false == LayerMask(0b0110).overlaps(LayerMask(0b1001)));
+        assert!(false == LayerMask::NONE.overlaps(LayerMask::ALL));
+    }
+}
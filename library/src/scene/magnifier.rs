@@ -0,0 +1,160 @@
+use more_asserts::{assert_ge, assert_le};
+
+// a debug inset in the top right corner of the frame: the accumulated image around the center,
+// zoomed with the nearest neighbor filtering; the coordinates are in the frame buffer pixels
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Magnifier {
+    center_x: u32,
+    center_y: u32,
+    zoom: u32,
+}
+
+impl Magnifier {
+    // the side of the inset in the output pixels (shader keeps the same value)
+    pub const INSET_SIZE: u32 = 256;
+    pub const MAX_ZOOM: u32 = 64;
+
+    #[must_use]
+    pub fn new(center_x: u32, center_y: u32, zoom: u32) -> Self {
+        assert_ge!(zoom, 1);
+        assert_le!(zoom, Self::MAX_ZOOM);
+        Self { center_x, center_y, zoom }
+    }
+
+    #[must_use]
+    pub fn center_x(&self) -> u32 {
+        self.center_x
+    }
+
+    #[must_use]
+    pub fn center_y(&self) -> u32 {
+        self.center_y
+    }
+
+    #[must_use]
+    pub fn zoom(&self) -> u32 {
+        self.zoom
+    }
+
+    // the frame buffer pixel shown in the top left corner of the inset; may lie outside the frame
+    #[must_use]
+    pub fn window_origin(&self) -> (i64, i64) {
+        let half_side = (Self::INSET_SIZE / self.zoom / 2) as i64;
+        (self.center_x as i64 - half_side, self.center_y as i64 - half_side)
+    }
+
+    // the count of the frame buffer pixels along a side of the inset (the last one may be partially visible)
+    #[must_use]
+    pub fn window_side(&self) -> u32 {
+        Self::INSET_SIZE.div_ceil(self.zoom)
+    }
+}
+
+// the accumulated radiance (hdr, before the exposure and the tone mapping) of the magnified pixels
+// lying inside the frame; the pixels go row by row
+#[derive(Clone, PartialEq, Debug)]
+pub struct MagnifierReadout {
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+    radiance: Vec<[f32; 3]>,
+}
+
+impl MagnifierReadout {
+    #[must_use]
+    pub(crate) fn new(magnifier: &Magnifier, frame_width: u32, frame_height: u32, radiance_at: impl Fn(u32, u32) -> [f32; 3]) -> Self {
+        let (origin_x, origin_y) = magnifier.window_origin();
+        let side = magnifier.window_side() as i64;
+        let clip = |origin: i64, extent: u32| (origin.clamp(0, extent as i64) as u32, (origin + side).clamp(0, extent as i64) as u32);
+        let (left, right) = clip(origin_x, frame_width);
+        let (top, bottom) = clip(origin_y, frame_height);
+
+        let mut radiance = Vec::with_capacity(((right - left) * (bottom - top)) as usize);
+        for y in top..bottom {
+            for x in left..right {
+                radiance.push(radiance_at(x, y));
+            }
+        }
+        Self { left, top, width: right - left, height: bottom - top, radiance }
+    }
+
+    #[must_use]
+    pub fn left(&self) -> u32 {
+        self.left
+    }
+
+    #[must_use]
+    pub fn top(&self) -> u32 {
+        self.top
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // none for the pixels out of the magnified window
+    #[must_use]
+    pub fn radiance(&self, x: u32, y: u32) -> Option<[f32; 3]> {
+        if x < self.left || y < self.top || x >= self.left + self.width || y >= self.top + self.height {
+            return None;
+        }
+        Some(self.radiance[((y - self.top) * self.width + (x - self.left)) as usize])
+    }
+
+    // the frame buffer coordinates of the pixels with nan or infinite components
+    pub fn non_finite_pixels(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.radiance.iter().enumerate()
+            .filter(|(_, radiance)| radiance.iter().any(|component| false == component.is_finite()))
+            .map(|(index, _)| (self.left + index as u32 % self.width, self.top + index as u32 / self.width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window() {
+        let system_under_test = Magnifier::new(100, 50, 8);
+
+        assert_eq!(system_under_test.window_origin(), (84, 34));
+        assert_eq!(system_under_test.window_side(), 32);
+        assert_eq!(Magnifier::new(0, 0, 3).window_side(), 86);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_zoom() {
+        let _ = Magnifier::new(0, 0, 0);
+    }
+
+    #[test]
+    fn test_readout_is_clipped_by_frame() {
+        let magnifier = Magnifier::new(2, 1, 32);
+
+        let system_under_test = MagnifierReadout::new(&magnifier, 10, 4, |x, y| [x as f32, y as f32, 0.0]);
+
+        assert_eq!((system_under_test.left(), system_under_test.top()), (0, 0));
+        assert_eq!((system_under_test.width(), system_under_test.height()), (6, 4));
+        assert_eq!(system_under_test.radiance(5, 3), Some([5.0, 3.0, 0.0]));
+        assert_eq!(system_under_test.radiance(6, 0), None);
+    }
+
+    #[test]
+    fn test_non_finite_pixels() {
+        let magnifier = Magnifier::new(10, 10, 64);
+
+        let system_under_test = MagnifierReadout::new(&magnifier, 100, 100, |x, y| {
+            if (9, 11) == (x, y) { [0.0, f32::NAN, 0.0] } else if (11, 8) == (x, y) { [f32::INFINITY, 0.0, 0.0] } else { [1.0, 1.0, 1.0] }
+        });
+
+        assert_eq!(system_under_test.non_finite_pixels().collect::<Vec<_>>(), [(11, 8), (9, 11)]);
+    }
+}
@@ -1,2 +1,32 @@
+pub mod asset_watcher;
+pub mod auto_exposure;
+pub mod bloom;
+pub mod buffers_statistics;
 pub mod camera;
+pub mod color_grading;
+pub mod display_mapping;
+pub mod engine_statistics;
+pub mod frame_buffer_size_policy;
+pub mod frame_capture;
+pub mod frame_statistics;
+pub mod g_buffer;
+pub mod gpu_passes_report;
 pub mod hub;
+pub mod key_light_shadows;
+pub mod layer_mask;
+pub mod magnifier;
+pub mod output_dithering;
+pub mod overlay;
+pub mod pipeline_cache_statistics;
+#[cfg(feature = "monte_carlo")]
+pub mod pixel_path;
+pub mod probe_grid;
+pub mod random_generator;
+pub mod random_seed_texture;
+pub mod ray_hit;
+pub mod render_strategy;
+pub mod resize_policy;
+pub mod scene_event;
+pub mod sdf_level_of_detail;
+pub mod stop_criterion;
+pub mod uv_transform;
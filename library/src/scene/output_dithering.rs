@@ -0,0 +1,23 @@
+// breaks the banding of the smooth dark gradients on the 8 bit surfaces: up to half a step of noise is added to the
+// output color before the surface quantizes it; the extended range surfaces are never dithered
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub enum OutputDithering {
+    None,
+    // interleaved gradient noise: no visible pattern, a faint grain
+    #[default]
+    GradientNoise,
+    // the 8x8 Bayer matrix: a regular fine pattern, the same in every frame
+    Ordered,
+}
+
+impl OutputDithering {
+    // must match 'dither' in the shader
+    #[must_use]
+    pub(crate) fn gpu_id(self) -> u32 {
+        match self {
+            OutputDithering::None => 0,
+            OutputDithering::GradientNoise => 1,
+            OutputDithering::Ordered => 2,
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::utils::bitmap_utils::BitmapSize;
+use crate::utils::version::Version;
+use bytemuck::{Pod, Zeroable};
+use cgmath::{InnerSpace, Point2, Vector2};
+use more_asserts::{assert_gt, assert_le};
+use std::collections::HashMap;
+
+// straight alpha; written over the final image as is: no exposure, tone mapping or gamma correction
+pub type OverlayColor = [f32; 4];
+
+// the coordinates are in the output pixels, from the top left corner
+pub type OverlayPoint = Point2<f32>;
+
+// the rectangle of the glyph in the atlas and its placement against the pen on the baseline, in pixels
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Glyph {
+    atlas_left: u32,
+    atlas_top: u32,
+    width: u32,
+    height: u32,
+    bearing: Vector2<f32>,
+    advance: f32,
+}
+
+impl Glyph {
+    // the bearing goes from the pen to the top left corner of the glyph (y grows downwards)
+    #[must_use]
+    pub fn new(atlas_left: u32, atlas_top: u32, width: u32, height: u32, bearing: Vector2<f32>, advance: f32) -> Self {
+        Self { atlas_left, atlas_top, width, height, bearing, advance }
+    }
+
+    #[must_use]
+    pub fn advance(&self) -> f32 {
+        self.advance
+    }
+}
+
+// the glyphs of a font rasterized by the application: the alpha of the texels is the coverage
+pub struct GlyphAtlas {
+    texels: Vec<u8>,
+    size: BitmapSize,
+    glyphs: HashMap<char, Glyph>,
+}
+
+impl GlyphAtlas {
+    #[must_use]
+    pub fn new(texels: Vec<u8>, size: BitmapSize) -> Self {
+        assert_eq!(texels.len(), size.bytes_in_bitmap(), "size mismatch");
+        Self { texels, size, glyphs: HashMap::new() }
+    }
+
+    pub fn add_glyph(&mut self, symbol: char, glyph: Glyph) {
+        assert_le!((glyph.atlas_left + glyph.width) as usize, self.size.width(), "glyph '{}' is outside the atlas", symbol);
+        assert_le!((glyph.atlas_top + glyph.height) as usize, self.size.height(), "glyph '{}' is outside the atlas", symbol);
+        self.glyphs.insert(symbol, glyph);
+    }
+
+    #[must_use]
+    pub fn glyph(&self, symbol: char) -> Option<&Glyph> {
+        self.glyphs.get(&symbol)
+    }
+
+    #[must_use]
+    pub(crate) fn texels(&self) -> &[u8] {
+        &self.texels
+    }
+
+    #[must_use]
+    pub(crate) fn size(&self) -> BitmapSize {
+        self.size
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable, PartialEq, Debug)]
+pub(crate) struct OverlayVertex {
+    pub(crate) position: [f32; 2],
+    // negative for the solid primitives
+    pub(crate) uv: [f32; 2],
+    pub(crate) color: OverlayColor,
+}
+
+// the 2d primitives drawn over the traced image (crosshairs, selection outlines, labels); everything
+// is turned into triangles at once, the gpu copy is updated when the version changes
+#[derive(Default)]
+pub struct Overlay {
+    vertices: Vec<OverlayVertex>,
+    version: Version,
+    glyph_atlas: Option<GlyphAtlas>,
+    glyph_atlas_version: Version,
+}
+
+impl Overlay {
+    const SOLID_UV: [f32; 2] = [-1.0, -1.0];
+
+    pub fn clear(&mut self) {
+        if self.vertices.is_empty() {
+            return;
+        }
+        self.vertices.clear();
+        self.version += 1;
+    }
+
+    pub fn add_rect(&mut self, top_left: OverlayPoint, width: f32, height: f32, color: OverlayColor) {
+        let corners = [top_left, top_left + Vector2::new(width, 0.0), top_left + Vector2::new(0.0, height), top_left + Vector2::new(width, height)];
+        self.add_quad(corners, [Self::SOLID_UV; 4], color);
+    }
+
+    // the outline lies inside the rectangle
+    pub fn add_rect_outline(&mut self, top_left: OverlayPoint, width: f32, height: f32, thickness: f32, color: OverlayColor) {
+        assert_gt!(thickness, 0.0);
+        let inner_height = (height - 2.0 * thickness).max(0.0);
+        self.add_rect(top_left, width, thickness, color);
+        self.add_rect(top_left + Vector2::new(0.0, height - thickness), width, thickness, color);
+        self.add_rect(top_left + Vector2::new(0.0, thickness), thickness, inner_height, color);
+        self.add_rect(top_left + Vector2::new(width - thickness, thickness), thickness, inner_height, color);
+    }
+
+    pub fn add_line(&mut self, from: OverlayPoint, to: OverlayPoint, thickness: f32, color: OverlayColor) {
+        assert_gt!(thickness, 0.0);
+        let direction = to - from;
+        if direction.magnitude2() == 0.0 {
+            return;
+        }
+        let side = Vector2::new(-direction.y, direction.x).normalize() * (thickness * 0.5);
+        self.add_quad([from + side, to + side, from - side, to - side], [Self::SOLID_UV; 4], color);
+    }
+
+    // the atlas is uploaded to the gpu once, the text quads refer to it
+    pub fn set_glyph_atlas(&mut self, atlas: GlyphAtlas) {
+        self.glyph_atlas = Some(atlas);
+        self.glyph_atlas_version += 1;
+    }
+
+    // the symbols missing in the atlas are skipped; returns the pen position after the text
+    pub fn add_text(&mut self, pen: OverlayPoint, text: &str, color: OverlayColor) -> OverlayPoint {
+        let atlas = self.glyph_atlas.as_ref().expect("glyph atlas has not been set");
+        let atlas_width = atlas.size().width() as f32;
+        let atlas_height = atlas.size().height() as f32;
+
+        let mut quads = Vec::new();
+        let mut pen = pen;
+        for symbol in text.chars() {
+            let Some(glyph) = atlas.glyph(symbol) else {
+                continue;
+            };
+            let top_left = pen + glyph.bearing;
+            let (width, height) = (glyph.width as f32, glyph.height as f32);
+            let corners = [top_left, top_left + Vector2::new(width, 0.0), top_left + Vector2::new(0.0, height), top_left + Vector2::new(width, height)];
+
+            let (left, top) = (glyph.atlas_left as f32 / atlas_width, glyph.atlas_top as f32 / atlas_height);
+            let (right, bottom) = (left + width / atlas_width, top + height / atlas_height);
+            quads.push((corners, [[left, top], [right, top], [left, bottom], [right, bottom]]));
+
+            pen.x += glyph.advance;
+        }
+        for (corners, uvs) in quads {
+            self.add_quad(corners, uvs, color);
+        }
+        pen
+    }
+
+    // the corners go as: top left, top right, bottom left, bottom right
+    fn add_quad(&mut self, corners: [OverlayPoint; 4], uvs: [[f32; 2]; 4], color: OverlayColor) {
+        const TRIANGLES: [usize; 6] = [0, 1, 2, 2, 1, 3];
+        for index in TRIANGLES {
+            self.vertices.push(OverlayVertex { position: [corners[index].x, corners[index].y], uv: uvs[index], color });
+        }
+        self.version += 1;
+    }
+
+    #[must_use]
+    pub(crate) fn vertices(&self) -> &[OverlayVertex] {
+        &self.vertices
+    }
+
+    #[must_use]
+    pub(crate) fn version(&self) -> Version {
+        self.version
+    }
+
+    #[must_use]
+    pub(crate) fn glyph_atlas(&self) -> Option<&GlyphAtlas> {
+        self.glyph_atlas.as_ref()
+    }
+
+    #[must_use]
+    pub(crate) fn glyph_atlas_version(&self) -> Version {
+        self.glyph_atlas_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: OverlayColor = [1.0, 1.0, 1.0, 1.0];
+
+    #[must_use]
+    fn make_atlas() -> GlyphAtlas {
+        let mut atlas = GlyphAtlas::new(vec![255; BitmapSize::new(16, 8).bytes_in_bitmap()], BitmapSize::new(16, 8));
+        atlas.add_glyph('a', Glyph::new(0, 0, 8, 8, Vector2::new(1.0, -8.0), 9.0));
+        atlas.add_glyph('b', Glyph::new(8, 0, 8, 8, Vector2::new(0.0, -8.0), 10.0));
+        atlas
+    }
+
+    #[test]
+    fn test_add_rect() {
+        let mut system_under_test = Overlay::default();
+        let version_before = system_under_test.version();
+
+        system_under_test.add_rect(OverlayPoint::new(10.0, 20.0), 4.0, 2.0, WHITE);
+
+        let positions: Vec<[f32; 2]> = system_under_test.vertices().iter().map(|vertex| vertex.position).collect();
+        assert_eq!(positions, vec![[10.0, 20.0], [14.0, 20.0], [10.0, 22.0], [10.0, 22.0], [14.0, 20.0], [14.0, 22.0]]);
+        assert!(system_under_test.vertices().iter().all(|vertex| vertex.uv == Overlay::SOLID_UV));
+        assert_ne!(system_under_test.version(), version_before);
+    }
+
+    #[test]
+    fn test_add_rect_outline() {
+        let mut system_under_test = Overlay::default();
+        system_under_test.add_rect_outline(OverlayPoint::new(0.0, 0.0), 10.0, 10.0, 1.0, WHITE);
+        assert_eq!(system_under_test.vertices().len(), 4 * 6);
+    }
+
+    #[test]
+    fn test_add_line() {
+        let mut system_under_test = Overlay::default();
+
+        system_under_test.add_line(OverlayPoint::new(0.0, 0.0), OverlayPoint::new(10.0, 0.0), 2.0, WHITE);
+        system_under_test.add_line(OverlayPoint::new(5.0, 5.0), OverlayPoint::new(5.0, 5.0), 2.0, WHITE);
+
+        let positions: Vec<[f32; 2]> = system_under_test.vertices().iter().map(|vertex| vertex.position).collect();
+        assert_eq!(positions, vec![[0.0, 1.0], [10.0, 1.0], [0.0, -1.0], [0.0, -1.0], [10.0, 1.0], [10.0, -1.0]]);
+    }
+
+    #[test]
+    fn test_add_text() {
+        let mut system_under_test = Overlay::default();
+        system_under_test.set_glyph_atlas(make_atlas());
+
+        let pen = system_under_test.add_text(OverlayPoint::new(100.0, 50.0), "a?b", WHITE);
+
+        assert_eq!(pen, OverlayPoint::new(119.0, 50.0));
+        let vertices = system_under_test.vertices();
+        assert_eq!(vertices.len(), 2 * 6);
+        assert_eq!(vertices[0].position, [101.0, 42.0]);
+        assert_eq!(vertices[0].uv, [0.0, 0.0]);
+        assert_eq!(vertices[6].position, [109.0, 42.0]);
+        assert_eq!(vertices[6].uv, [0.5, 0.0]);
+        assert_eq!(vertices[11].uv, [1.0, 1.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_text_without_atlas() {
+        let _ = Overlay::default().add_text(OverlayPoint::new(0.0, 0.0), "a", WHITE);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_glyph_outside_atlas() {
+        make_atlas().add_glyph('c', Glyph::new(12, 0, 8, 8, Vector2::new(0.0, 0.0), 8.0));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut system_under_test = Overlay::default();
+        system_under_test.add_rect(OverlayPoint::new(0.0, 0.0), 1.0, 1.0, WHITE);
+        let version_before = system_under_test.version();
+
+        system_under_test.clear();
+
+        assert!(system_under_test.vertices().is_empty());
+        assert_ne!(system_under_test.version(), version_before);
+    }
+}
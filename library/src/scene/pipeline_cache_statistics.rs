@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+// where the pipeline creations found the driver caches
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum PipelineCacheLookup {
+    // made earlier for the same code by the same device
+    MemoryHit,
+    // saved by an earlier run on the same adapter
+    DiskHit,
+    // compiled from scratch, as on the devices without the pipeline caches
+    Miss,
+}
+
+// the pipelines created so far (compiled, or found in the caches) and the time all the creations took
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct PipelineCacheStatistics {
+    memory_hits: u32,
+    disk_hits: u32,
+    misses: u32,
+    creation_time: Duration,
+}
+
+impl PipelineCacheStatistics {
+    pub(crate) fn record(&mut self, lookup: PipelineCacheLookup, creation_time: Duration) {
+        match lookup {
+            PipelineCacheLookup::MemoryHit => self.memory_hits += 1,
+            PipelineCacheLookup::DiskHit => self.disk_hits += 1,
+            PipelineCacheLookup::Miss => self.misses += 1,
+        }
+        self.creation_time += creation_time;
+    }
+
+    #[must_use]
+    pub fn memory_hits(&self) -> u32 {
+        self.memory_hits
+    }
+
+    #[must_use]
+    pub fn disk_hits(&self) -> u32 {
+        self.disk_hits
+    }
+
+    #[must_use]
+    pub fn misses(&self) -> u32 {
+        self.misses
+    }
+
+    #[must_use]
+    pub fn pipelines_created(&self) -> u32 {
+        self.memory_hits + self.disk_hits + self.misses
+    }
+
+    // the share of the pipelines created from a cache; none before the first one
+    #[must_use]
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let created = self.pipelines_created();
+        if 0 == created {
+            return None;
+        }
+        Some(f64::from(self.memory_hits + self.disk_hits) / f64::from(created))
+    }
+
+    #[must_use]
+    pub fn creation_time(&self) -> Duration {
+        self.creation_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut system_under_test = PipelineCacheStatistics::default();
+
+        system_under_test.record(PipelineCacheLookup::Miss, Duration::from_millis(30));
+        system_under_test.record(PipelineCacheLookup::DiskHit, Duration::from_millis(5));
+        system_under_test.record(PipelineCacheLookup::MemoryHit, Duration::from_millis(1));
+        system_under_test.record(PipelineCacheLookup::DiskHit, Duration::from_millis(4));
+
+        assert_eq!(system_under_test.memory_hits(), 1);
+        assert_eq!(system_under_test.disk_hits(), 2);
+        assert_eq!(system_under_test.misses(), 1);
+        assert_eq!(system_under_test.pipelines_created(), 4);
+        assert_eq!(system_under_test.hit_ratio(), Some(0.75));
+        assert_eq!(system_under_test.creation_time(), Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_no_hit_ratio_before_first_pipeline() {
+        assert_eq!(PipelineCacheStatistics::default().hit_ratio(), None);
+    }
+}
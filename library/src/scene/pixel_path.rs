@@ -0,0 +1,259 @@
+use crate::geometry::alias::{Point, Vector};
+use crate::material::material_index::MaterialIndex;
+use num_enum::TryFromPrimitive;
+
+// what the path did at a bounce; the last four only end the path
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, TryFromPrimitive)]
+pub enum PathEvent {
+    Miss = 0,
+    SurfaceShader = 1,
+    Specular = 2,
+    BsdfSampled = 3,
+    LightSampled = 4,
+    Absorbed = 5,
+    RussianRoulette = 6,
+    BouncesLimit = 7,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PathHit {
+    position: Point,
+    ray_parameter: f32,
+    normal: Vector,
+    material: MaterialIndex,
+    back_face: bool,
+}
+
+impl PathHit {
+    #[must_use]
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    #[must_use]
+    pub fn ray_parameter(&self) -> f32 {
+        self.ray_parameter
+    }
+
+    // the shading one: perturbed by the normal map and flipped for the back faces
+    #[must_use]
+    pub fn normal(&self) -> Vector {
+        self.normal
+    }
+
+    #[must_use]
+    pub fn material(&self) -> MaterialIndex {
+        self.material
+    }
+
+    #[must_use]
+    pub fn back_face(&self) -> bool {
+        self.back_face
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PathSegment {
+    hit: Option<PathHit>,
+    incident_direction: Vector,
+    event: PathEvent,
+    throughput: [f32; 3],
+    sdf_march_steps: u32,
+}
+
+impl PathSegment {
+    // none for the segment missing the scene
+    #[must_use]
+    pub fn hit(&self) -> Option<PathHit> {
+        self.hit
+    }
+
+    #[must_use]
+    pub fn incident_direction(&self) -> Vector {
+        self.incident_direction
+    }
+
+    #[must_use]
+    pub fn event(&self) -> PathEvent {
+        self.event
+    }
+
+    // after the event
+    #[must_use]
+    pub fn throughput(&self) -> [f32; 3] {
+        self.throughput
+    }
+
+    #[must_use]
+    pub fn sdf_march_steps(&self) -> u32 {
+        self.sdf_march_steps
+    }
+}
+
+// the first Monte Carlo sample of a pixel traced once more with the same random numbers, bounce by bounce;
+// only the first 'MAX_SEGMENTS' segments are kept, the rest are counted
+#[derive(Clone, PartialEq, Debug)]
+pub struct PixelPath {
+    x: u32,
+    y: u32,
+    segments: Vec<PathSegment>,
+    segments_count: u32,
+    termination: PathEvent,
+    radiance: [f32; 3],
+    sdf_march_steps: u32,
+}
+
+impl PixelPath {
+    // the shader keeps the same values
+    pub const MAX_SEGMENTS: usize = 16;
+    const HEADER_QUARTETS: usize = 2;
+    const SEGMENT_QUARTETS: usize = 4;
+    const BACK_FACE_FLAG: u32 = 0x100;
+    const EVENT_MASK: u32 = 0xFF;
+
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = Self::HEADER_QUARTETS + Self::MAX_SEGMENTS * Self::SEGMENT_QUARTETS;
+
+    // the header the shader reads the pixel from
+    #[must_use]
+    pub(crate) fn make_request(x: u32, y: u32) -> [u32; 4] {
+        [x, y, 0, 0]
+    }
+
+    #[must_use]
+    pub(crate) fn from_gpu(words: &[u32]) -> Self {
+        assert_eq!(words.len(), Self::SERIALIZED_QUARTET_COUNT * 4, "unexpected size of the path dump");
+        let float = |index: usize| f32::from_bits(words[index]);
+        let vector = |index: usize| Vector::new(float(index) as f64, float(index + 1) as f64, float(index + 2) as f64);
+        let event = |word: u32| PathEvent::try_from(word & Self::EVENT_MASK).unwrap_or_else(|_| panic!("unknown path event {word}"));
+
+        let segments_count = words[2];
+        let stored_segments = (segments_count as usize).min(Self::MAX_SEGMENTS);
+        let segments = (0..stored_segments).map(|segment| {
+            let base = (Self::HEADER_QUARTETS + segment * Self::SEGMENT_QUARTETS) * 4;
+            let event_word = words[base + 11];
+            let event = event(event_word);
+            let hit = (PathEvent::Miss != event).then(|| PathHit {
+                position: Point::new(float(base) as f64, float(base + 1) as f64, float(base + 2) as f64),
+                ray_parameter: float(base + 3),
+                normal: vector(base + 4),
+                material: MaterialIndex(words[base + 7] as usize),
+                back_face: 0 != event_word & Self::BACK_FACE_FLAG,
+            });
+            PathSegment {
+                hit,
+                incident_direction: vector(base + 8),
+                event,
+                throughput: [float(base + 12), float(base + 13), float(base + 14)],
+                sdf_march_steps: words[base + 15],
+            }
+        }).collect();
+
+        Self {
+            x: words[0],
+            y: words[1],
+            segments,
+            segments_count,
+            termination: event(words[3]),
+            radiance: [float(4), float(5), float(6)],
+            sdf_march_steps: words[7],
+        }
+    }
+
+    #[must_use]
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    #[must_use]
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    #[must_use]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    // may exceed the count of the kept segments
+    #[must_use]
+    pub fn segments_count(&self) -> u32 {
+        self.segments_count
+    }
+
+    #[must_use]
+    pub fn termination(&self) -> PathEvent {
+        self.termination
+    }
+
+    // of this sample only, before the exposure and the tone mapping
+    #[must_use]
+    pub fn radiance(&self) -> [f32; 3] {
+        self.radiance
+    }
+
+    #[must_use]
+    pub fn sdf_march_steps(&self) -> u32 {
+        self.sdf_march_steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[must_use]
+    fn make_dump(segments: &[[[f32; 4]; 4]], segments_count: u32, termination: PathEvent) -> Vec<u32> {
+        let mut result = vec![0_u32; PixelPath::SERIALIZED_QUARTET_COUNT * 4];
+        result[0..8].copy_from_slice(&[3, 5, segments_count, termination as u32, 0.5_f32.to_bits(), 0.25_f32.to_bits(), 0.0_f32.to_bits(), 42]);
+        for (index, segment) in segments.iter().enumerate() {
+            let base = (PixelPath::HEADER_QUARTETS + index * PixelPath::SEGMENT_QUARTETS) * 4;
+            for (offset, value) in segment.iter().flatten().enumerate() {
+                result[base + offset] = value.to_bits();
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_from_gpu() {
+        let bounce = [
+            [1.0, 2.0, 3.0, 4.0],
+            [0.0, 1.0, 0.0, f32::from_bits(7)],
+            [0.0, -1.0, 0.0, f32::from_bits(PathEvent::BsdfSampled as u32 | PixelPath::BACK_FACE_FLAG)],
+            [0.5, 0.5, 0.5, f32::from_bits(40)],
+        ];
+        let miss = [[0.0; 4], [0.0; 4], [0.0, 1.0, 0.0, f32::from_bits(PathEvent::Miss as u32)], [0.5, 0.5, 0.5, f32::from_bits(2)]];
+
+        let system_under_test = PixelPath::from_gpu(&make_dump(&[bounce, miss], 2, PathEvent::Miss));
+
+        assert_eq!((system_under_test.x(), system_under_test.y()), (3, 5));
+        assert_eq!(system_under_test.termination(), PathEvent::Miss);
+        assert_eq!(system_under_test.radiance(), [0.5, 0.25, 0.0]);
+        assert_eq!(system_under_test.sdf_march_steps(), 42);
+        assert_eq!(system_under_test.segments().len(), 2);
+
+        let first = system_under_test.segments()[0];
+        let hit = first.hit().unwrap();
+        assert_eq!(hit.position(), Point::new(1.0, 2.0, 3.0));
+        assert_eq!(hit.ray_parameter(), 4.0);
+        assert_eq!(hit.material(), MaterialIndex(7));
+        assert!(hit.back_face());
+        assert_eq!(first.event(), PathEvent::BsdfSampled);
+        assert_eq!(first.incident_direction(), Vector::new(0.0, -1.0, 0.0));
+        assert_eq!(first.sdf_march_steps(), 40);
+
+        assert_eq!(system_under_test.segments()[1].hit(), None);
+    }
+
+    #[test]
+    fn test_long_path_is_truncated() {
+        let specular = [[0.0; 4], [0.0; 4], [0.0, 0.0, 1.0, f32::from_bits(PathEvent::Specular as u32)], [1.0; 4]];
+
+        let system_under_test = PixelPath::from_gpu(&make_dump(&[specular; PixelPath::MAX_SEGMENTS], 50, PathEvent::BouncesLimit));
+
+        assert_eq!(system_under_test.segments().len(), PixelPath::MAX_SEGMENTS);
+        assert_eq!(system_under_test.segments_count(), 50);
+        assert_eq!(system_under_test.termination(), PathEvent::BouncesLimit);
+    }
+}
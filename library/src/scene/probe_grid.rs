@@ -0,0 +1,136 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::Vector;
+use crate::serialization::gpu_ready_serialization_buffer::GpuReadySerializationBuffer;
+use cgmath::Vector3;
+
+// probes sit in the grid nodes, so each axis needs at least two of them
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ProbeGrid {
+    bounds: Aabb,
+    resolution: Vector3<u32>,
+}
+
+impl ProbeGrid {
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 3;
+
+    #[must_use]
+    pub fn new(bounds: Aabb, resolution: Vector3<u32>) -> Self {
+        assert!(resolution.x >= 2 && resolution.y >= 2 && resolution.z >= 2, "at least two probes per axis expected");
+        let extent = bounds.extent();
+        assert!(extent.x > 0.0 && extent.y > 0.0 && extent.z > 0.0, "degenerate probe grid bounds");
+        Self { bounds, resolution }
+    }
+
+    #[must_use]
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    #[must_use]
+    pub fn resolution(&self) -> Vector3<u32> {
+        self.resolution
+    }
+
+    #[must_use]
+    pub fn probes_count(&self) -> u32 {
+        self.resolution.x * self.resolution.y * self.resolution.z
+    }
+
+    #[must_use]
+    pub fn cell_size(&self) -> Vector {
+        let extent = self.bounds.extent();
+        Vector::new(
+            extent.x / (self.resolution.x - 1) as f64,
+            extent.y / (self.resolution.y - 1) as f64,
+            extent.z / (self.resolution.z - 1) as f64,
+        )
+    }
+
+    pub(crate) fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= ProbeGrid::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+
+        let min = self.bounds.min();
+        container.write_quartet(|writer| {
+            writer.write_float_64(min.x);
+            writer.write_float_64(min.y);
+            writer.write_float_64(min.z);
+            writer.write_unsigned(self.probes_count());
+        });
+
+        let cell_size = self.cell_size();
+        container.write_padded_quartet_f64(cell_size.x, cell_size.y, cell_size.z);
+
+        container.write_quartet(|writer| {
+            writer.write_unsigned(self.resolution.x);
+            writer.write_unsigned(self.resolution.y);
+            writer.write_unsigned(self.resolution.z);
+        });
+    }
+
+    pub(crate) fn serialize_disabled_into(container: &mut GpuReadySerializationBuffer) {
+        assert!(container.free_quartets_of_current_object() >= ProbeGrid::SERIALIZED_QUARTET_COUNT, "buffer size is too small");
+        for _ in 0..ProbeGrid::SERIALIZED_QUARTET_COUNT {
+            container.write_quartet_f32(0.0, 0.0, 0.0, 0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::alias::Point;
+    use bytemuck::cast_slice;
+
+    #[must_use]
+    fn make_test_grid() -> ProbeGrid {
+        ProbeGrid::new(Aabb::from_points(Point::new(-1.0, 0.0, 2.0), Point::new(3.0, 2.0, 8.0)), Vector3::new(5, 3, 4))
+    }
+
+    #[test]
+    fn test_probes_count() {
+        assert_eq!(make_test_grid().probes_count(), 5 * 3 * 4);
+    }
+
+    #[test]
+    fn test_cell_size() {
+        assert_eq!(make_test_grid().cell_size(), Vector::new(1.0, 1.0, 2.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least two probes per axis expected")]
+    fn test_single_probe_axis() {
+        let _ = ProbeGrid::new(Aabb::from_points(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0)), Vector3::new(2, 1, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "degenerate probe grid bounds")]
+    fn test_flat_bounds() {
+        let _ = ProbeGrid::new(Aabb::from_points(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 1.0)), Vector3::new(2, 2, 2));
+    }
+
+    #[test]
+    fn test_serialize_into() {
+        let mut container = GpuReadySerializationBuffer::new(1, ProbeGrid::SERIALIZED_QUARTET_COUNT);
+
+        make_test_grid().serialize_into(&mut container);
+
+        assert!(container.object_fully_written());
+        let words: &[u32] = cast_slice(container.backend());
+        let floats: &[f32] = cast_slice(container.backend());
+        assert_eq!(&floats[0..3], &[-1.0, 0.0, 2.0]);
+        assert_eq!(words[3], 5 * 3 * 4);
+        assert_eq!(&floats[4..7], &[1.0, 1.0, 2.0]);
+        assert_eq!(&words[8..11], &[5, 3, 4]);
+    }
+
+    #[test]
+    fn test_serialize_disabled_into() {
+        let mut container = GpuReadySerializationBuffer::new(1, ProbeGrid::SERIALIZED_QUARTET_COUNT);
+
+        ProbeGrid::serialize_disabled_into(&mut container);
+
+        assert!(container.object_fully_written());
+        let words: &[u32] = cast_slice(container.backend());
+        assert!(words.iter().all(|word| *word == 0));
+    }
+}
\ No newline at end of file
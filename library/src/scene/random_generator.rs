@@ -0,0 +1,22 @@
+// the pseudo random numbers of the stochastic sampling (the sub-pixel offsets, the bounces, the light picks)
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub enum RandomGenerator {
+    // the permuted congruential generator seeded by the pixel and the frame numbers as they are: the
+    // sequences of the far apart pixels of consecutive frames may coincide
+    #[default]
+    Pcg,
+    // the xxHash32 of a counter; the seeds are hashed too, so the sequences of the pixels and the frames
+    // start far apart: slower, with no correlation between the pixels
+    XxHash,
+}
+
+impl RandomGenerator {
+    // must match 'rand_0_1' in the shader
+    #[must_use]
+    pub(crate) fn gpu_id(self) -> u32 {
+        match self {
+            RandomGenerator::Pcg => 0,
+            RandomGenerator::XxHash => 1,
+        }
+    }
+}
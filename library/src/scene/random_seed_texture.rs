@@ -0,0 +1,75 @@
+use anyhow::{bail, Context};
+use std::path::Path;
+
+// a tile of values in [0, 1) repeated over the frame buffer, e.g. a blue noise texture: the random numbers
+// of each pixel are rotated by its value (moved further by the golden ratio every frame), so the errors of
+// the neighbour pixels differ the way the values do; with blue noise the noise of the first samples goes
+// into the high frequencies, which the eye and the denoiser forgive
+#[derive(Clone, PartialEq, Debug)]
+pub struct RandomSeedTexture {
+    width: u32,
+    height: u32,
+    values: Vec<f32>,
+}
+
+impl RandomSeedTexture {
+    pub fn new(width: u32, height: u32, values: Vec<f32>) -> anyhow::Result<Self> {
+        if 0 == width || 0 == height {
+            bail!("the seed texture is empty");
+        }
+        if values.len() != (width * height) as usize {
+            bail!("{} seed values do not fill {width}x{height} pixels", values.len());
+        }
+        if let Some(outlier) = values.iter().find(|value| false == (0.0..=1.0).contains(*value)) {
+            bail!("the seed value {outlier} is out of [0, 1]");
+        }
+        Ok(Self { width, height, values })
+    }
+
+    // the luminance of the image, typically one of the published blue noise tiles
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let image = image::open(path).with_context(|| format!("failed to load the seed texture {}", path.display()))?.to_luma32f();
+        Self::new(image.width(), image.height(), image.into_raw())
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[must_use]
+    pub(crate) fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_validates_values() {
+        assert!(RandomSeedTexture::new(2, 1, vec![0.0, 0.5]).is_ok());
+        assert!(RandomSeedTexture::new(2, 2, vec![0.0, 0.5]).is_err());
+        assert!(RandomSeedTexture::new(0, 0, vec![]).is_err());
+        assert!(RandomSeedTexture::new(1, 1, vec![1.5]).is_err());
+    }
+
+    #[test]
+    fn test_load_luminance() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("noise.png");
+        image::GrayImage::from_raw(2, 1, vec![0, 255]).unwrap().save(&path).unwrap();
+
+        let system_under_test = RandomSeedTexture::load(&path).unwrap();
+
+        assert_eq!((system_under_test.width(), system_under_test.height()), (2, 1));
+        assert_eq!(system_under_test.values(), &[0.0, 1.0]);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::geometry::alias::{Point, Vector};
+use crate::utils::object_uid::ObjectUid;
+
+// the closest object along a ray cast on the cpu, see 'Hub::cast_ray'
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RayHit {
+    object: ObjectUid,
+    position: Point,
+    normal: Vector,
+    distance: f64,
+    exact: bool,
+}
+
+impl RayHit {
+    #[must_use]
+    pub(crate) fn new(object: ObjectUid, position: Point, normal: Vector, distance: f64, exact: bool) -> Self {
+        Self { object, position, normal, distance, exact }
+    }
+
+    #[must_use]
+    pub fn object(&self) -> ObjectUid {
+        self.object
+    }
+
+    #[must_use]
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    // the geometric one (not the interpolated mesh normal), unit length, faces the ray origin
+    #[must_use]
+    pub fn normal(&self) -> Vector {
+        self.normal
+    }
+
+    // from the ray origin, in the world units
+    #[must_use]
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    // false for an sdf: it is hit at the box of its class, the position may lie in front of the surface
+    #[must_use]
+    pub fn exact(&self) -> bool {
+        self.exact
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{MAX_ANTIALIASING_LEVEL, MIN_ANTIALIASING_LEVEL};
+#[cfg(feature = "monte_carlo")]
+use crate::RAYS_ACCUMULATIONS_PER_FRAME;
+use more_asserts::{assert_ge, assert_le};
+use std::time::Duration;
+
+// the ways the color buffer is evaluated: the Monte Carlo ones accumulate the samples over the frames
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum RenderStrategyId {
+    #[cfg(feature = "monte_carlo")] MonteCarlo,
+    #[cfg(feature = "monte_carlo")] MonteCarloHalfResolutionIndirect,
+    #[cfg(feature = "monte_carlo")] Wavefront,
+    Deterministic,
+}
+
+impl RenderStrategyId {
+    #[must_use]
+    pub fn is_monte_carlo(&self) -> bool {
+        false == matches!(self, RenderStrategyId::Deterministic)
+    }
+}
+
+// the engine keeps one per strategy: the strategy in use applies its own, the others wait for their turn
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct RenderStrategyConfig {
+    samples_per_pass: u32,
+    pixel_subdivision: u32,
+    max_bounces: u32,
+    denoise: bool,
+    denoise_interval: u32,
+    frame_time_budget: Option<Duration>,
+}
+
+impl RenderStrategyConfig {
+    pub(crate) const DETERMINISTIC_MAX_BOUNCES: u32 = 8;
+    #[cfg(feature = "monte_carlo")]
+    const MONTE_CARLO_MAX_BOUNCES: u32 = 50;
+
+    #[cfg(feature = "monte_carlo")]
+    const PIXEL_SUBDIVISION_MONTE_CARLO: u32 = 2;
+    pub(crate) const PIXEL_SUBDIVISION_DETERMINISTIC: u32 = 4;
+
+    #[must_use]
+    pub fn default_for(strategy: RenderStrategyId) -> Self {
+        match strategy {
+            #[cfg(feature = "monte_carlo")]
+            RenderStrategyId::MonteCarlo | RenderStrategyId::MonteCarloHalfResolutionIndirect | RenderStrategyId::Wavefront => Self {
+                samples_per_pass: RAYS_ACCUMULATIONS_PER_FRAME as u32,
+                pixel_subdivision: Self::PIXEL_SUBDIVISION_MONTE_CARLO,
+                max_bounces: Self::MONTE_CARLO_MAX_BOUNCES,
+                denoise: true,
+                denoise_interval: 1,
+                frame_time_budget: None,
+            },
+            RenderStrategyId::Deterministic => Self {
+                samples_per_pass: 1,
+                pixel_subdivision: Self::PIXEL_SUBDIVISION_DETERMINISTIC,
+                max_bounces: Self::DETERMINISTIC_MAX_BOUNCES,
+                denoise: false,
+                denoise_interval: 1,
+                frame_time_budget: None,
+            },
+        }
+    }
+
+    // the samples per pixel a presented frame accumulates, a compute pass each; the deterministic
+    // image does not get better with more, so it is always rendered in a single one; with the frame
+    // time budget, the most a frame accumulates
+    #[must_use]
+    pub fn with_samples_per_pass(mut self, samples: u32) -> Self {
+        assert_ge!(samples, 1, "at least one sample per pass expected");
+        self.samples_per_pass = samples;
+        self
+    }
+
+    // the antialiasing level: each pixel gets 'subdivision * subdivision' rays a pass
+    #[must_use]
+    pub fn with_pixel_subdivision(mut self, subdivision: u32) -> Self {
+        assert_ge!(subdivision, MIN_ANTIALIASING_LEVEL, "pixel subdivision is too low");
+        assert_le!(subdivision, MAX_ANTIALIASING_LEVEL, "pixel subdivision is too high");
+        self.pixel_subdivision = subdivision;
+        self
+    }
+
+    // the path is terminated after that many surface interactions
+    #[must_use]
+    pub fn with_max_bounces(mut self, bounces: u32) -> Self {
+        assert_ge!(bounces, 1, "at least one bounce expected");
+        self.max_bounces = bounces;
+        self
+    }
+
+    // the Monte Carlo images only, and with the 'denoiser' feature only
+    #[must_use]
+    pub fn with_denoise(mut self, denoise: bool) -> Self {
+        self.denoise = denoise;
+        self
+    }
+
+    // the denoiser runs every that many frames, and when the accumulation restarts; the frames in
+    // between show the last denoised image blended with the samples accumulated since
+    #[must_use]
+    pub fn with_denoise_interval(mut self, frames: u32) -> Self {
+        assert_ge!(frames, 1, "at least one frame denoising interval expected");
+        self.denoise_interval = frames;
+        self
+    }
+
+    // the window engine fits the passes of a frame into the budget, learning how long a pass takes
+    // from the previous frames; each frame waits for the gpu then, so the cpu no longer runs ahead
+    #[must_use]
+    pub fn with_frame_time_budget(mut self, budget: Option<Duration>) -> Self {
+        if let Some(budget) = budget {
+            assert!(false == budget.is_zero(), "empty frame time budget");
+        }
+        self.frame_time_budget = budget;
+        self
+    }
+
+    #[must_use]
+    pub fn samples_per_pass(&self) -> u32 {
+        self.samples_per_pass
+    }
+
+    #[must_use]
+    pub fn pixel_subdivision(&self) -> u32 {
+        self.pixel_subdivision
+    }
+
+    #[must_use]
+    pub fn max_bounces(&self) -> u32 {
+        self.max_bounces
+    }
+
+    #[must_use]
+    pub fn denoise(&self) -> bool {
+        self.denoise
+    }
+
+    #[must_use]
+    pub fn denoise_interval(&self) -> u32 {
+        self.denoise_interval
+    }
+
+    #[must_use]
+    pub fn frame_time_budget(&self) -> Option<Duration> {
+        self.frame_time_budget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic_defaults() {
+        let system_under_test = RenderStrategyConfig::default_for(RenderStrategyId::Deterministic);
+
+        assert_eq!(system_under_test.samples_per_pass(), 1);
+        assert_eq!(system_under_test.pixel_subdivision(), RenderStrategyConfig::PIXEL_SUBDIVISION_DETERMINISTIC);
+        assert_eq!(system_under_test.max_bounces(), RenderStrategyConfig::DETERMINISTIC_MAX_BOUNCES);
+        assert!(false == system_under_test.denoise());
+        assert_eq!(system_under_test.denoise_interval(), 1);
+        assert_eq!(system_under_test.frame_time_budget(), None);
+    }
+
+    #[cfg(feature = "monte_carlo")]
+    #[test]
+    fn test_monte_carlo_defaults() {
+        let system_under_test = RenderStrategyConfig::default_for(RenderStrategyId::Wavefront);
+
+        assert_eq!(system_under_test.samples_per_pass(), RAYS_ACCUMULATIONS_PER_FRAME as u32);
+        assert_eq!(system_under_test.max_bounces(), RenderStrategyConfig::MONTE_CARLO_MAX_BOUNCES);
+        assert!(system_under_test.denoise());
+        assert!(RenderStrategyId::Wavefront.is_monte_carlo());
+    }
+
+    #[test]
+    fn test_builder() {
+        let system_under_test = RenderStrategyConfig::default_for(RenderStrategyId::Deterministic)
+            .with_samples_per_pass(3)
+            .with_pixel_subdivision(2)
+            .with_max_bounces(5)
+            .with_denoise(true)
+            .with_denoise_interval(4)
+            .with_frame_time_budget(Some(Duration::from_millis(12)));
+
+        assert_eq!(system_under_test.samples_per_pass(), 3);
+        assert_eq!(system_under_test.pixel_subdivision(), 2);
+        assert_eq!(system_under_test.max_bounces(), 5);
+        assert!(system_under_test.denoise());
+        assert_eq!(system_under_test.denoise_interval(), 4);
+        assert_eq!(system_under_test.frame_time_budget(), Some(Duration::from_millis(12)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pixel_subdivision_out_of_range() {
+        let _ = RenderStrategyConfig::default_for(RenderStrategyId::Deterministic).with_pixel_subdivision(MAX_ANTIALIASING_LEVEL + 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_bounces() {
+        let _ = RenderStrategyConfig::default_for(RenderStrategyId::Deterministic).with_max_bounces(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_denoise_interval() {
+        let _ = RenderStrategyConfig::default_for(RenderStrategyId::Deterministic).with_denoise_interval(0);
+    }
+}
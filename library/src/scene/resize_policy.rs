@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+// what a window resize does to the image accumulated so far
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ResizePolicy {
+    // the accumulation restarts at the new size
+    #[default]
+    ResetAccumulation,
+    // the accumulated image, scaled to the new size, seeds the accumulation there; Monte Carlo strategies
+    // only (the others restart), and each resize waits for the image to be read back from the gpu
+    SeedWithScaledImage,
+    // the frame buffer keeps its size, stretched and letterboxed into the window, and the accumulation
+    // goes on; it gets resized (restarting the accumulation) once the window has not been resized for the period
+    KeepResolutionUntilIdle(Duration),
+}
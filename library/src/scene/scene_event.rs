@@ -0,0 +1,105 @@
+use crate::material::material_index::MaterialIndex;
+use crate::objects::common_properties::ObjectUid;
+
+// what of an object has been changed through the hub
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ObjectChange {
+    // reshaped in place, see 'Hub::reshape_parallelogram' and 'Hub::reshape_mesh'
+    Shape,
+    SdfParameter,
+    Material,
+    Visibility,
+    LayerMask,
+    UvTransform,
+    Opacity,
+}
+
+// the scene changes reported to the observers of the hub, in the order they are made; the per-frame
+// animation of the objects is not reported
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SceneEvent {
+    ObjectAdded(ObjectUid),
+    ObjectRemoved(ObjectUid),
+    ObjectChanged(ObjectUid, ObjectChange),
+    // the albedo baking of the material has been set, or the baked texture has arrived
+    MaterialChanged(MaterialIndex),
+    // all the objects are gone at once, with no removal reported for each
+    ObjectsCleared,
+    // everything is from the new scene now: the previously reported uids and materials mean nothing
+    SceneReplaced,
+}
+
+// returned by 'Hub::observe' to stop the observation
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SceneObserverId(u64);
+
+type SceneObserver = Box<dyn FnMut(&SceneEvent)>;
+
+// the observers of a hub, called synchronously as the changes are made
+pub(crate) struct SceneObservers {
+    observers: Vec<(SceneObserverId, SceneObserver)>,
+    next_id: u64,
+}
+
+impl SceneObservers {
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self { observers: Vec::new(), next_id: 0 }
+    }
+
+    #[must_use]
+    pub(crate) fn add(&mut self, observer: SceneObserver) -> SceneObserverId {
+        let id = SceneObserverId(self.next_id);
+        self.next_id += 1;
+        self.observers.push((id, observer));
+        id
+    }
+
+    // returns false if the observer is unknown (e.g. removed already)
+    pub(crate) fn remove(&mut self, id: SceneObserverId) -> bool {
+        let count_before = self.observers.len();
+        self.observers.retain(|(observer_id, _)| *observer_id != id);
+        self.observers.len() < count_before
+    }
+
+    pub(crate) fn notify(&mut self, event: SceneEvent) {
+        for (_, observer) in self.observers.iter_mut() {
+            observer(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_notify_reaches_observers_in_order() {
+        let mut system_under_test = SceneObservers::new();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let first_received = received.clone();
+        let second_received = received.clone();
+        let _ = system_under_test.add(Box::new(move |event| first_received.borrow_mut().push((0, *event))));
+        let _ = system_under_test.add(Box::new(move |event| second_received.borrow_mut().push((1, *event))));
+
+        system_under_test.notify(SceneEvent::ObjectAdded(ObjectUid(3)));
+
+        assert_eq!(*received.borrow(), vec![(0, SceneEvent::ObjectAdded(ObjectUid(3))), (1, SceneEvent::ObjectAdded(ObjectUid(3)))]);
+    }
+
+    #[test]
+    fn test_removed_observer_is_not_notified() {
+        let mut system_under_test = SceneObservers::new();
+        let received = Rc::new(RefCell::new(0));
+        let observer_received = received.clone();
+        let id = system_under_test.add(Box::new(move |_| *observer_received.borrow_mut() += 1));
+
+        assert!(system_under_test.remove(id));
+        assert!(false == system_under_test.remove(id));
+        system_under_test.notify(SceneEvent::ObjectsCleared);
+
+        assert_eq!(*received.borrow(), 0);
+    }
+}
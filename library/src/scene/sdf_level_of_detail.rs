@@ -0,0 +1,53 @@
+use more_asserts::assert_gt;
+
+// the sdf instances whose bounding box projects to fewer pixels than the threshold are not sphere traced:
+// the tracer shades the bounding sphere of the box instead, with the material of the instance
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SdfLevelOfDetail {
+    threshold_pixels: f32,
+}
+
+impl SdfLevelOfDetail {
+    #[must_use]
+    pub fn new(threshold_pixels: f32) -> Self {
+        assert!(threshold_pixels.is_finite(), "threshold is not finite: {threshold_pixels}");
+        assert_gt!(threshold_pixels, 0.0);
+        Self { threshold_pixels }
+    }
+
+    #[must_use]
+    pub fn threshold_pixels(&self) -> f32 {
+        self.threshold_pixels
+    }
+
+    // the estimate the shader uses: the box diagonal seen from the distance to the box center
+    #[must_use]
+    pub fn projected_pixels(box_diagonal: f64, distance: f64, camera_fov_factor: f64, frame_height: u32) -> f64 {
+        box_diagonal * camera_fov_factor * 0.5 * frame_height as f64 / distance
+    }
+
+    #[must_use]
+    pub fn replaces(&self, box_diagonal: f64, distance: f64, camera_fov_factor: f64, frame_height: u32) -> bool {
+        Self::projected_pixels(box_diagonal, distance, camera_fov_factor, frame_height) < self.threshold_pixels as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distant_instance_is_replaced() {
+        let system_under_test = SdfLevelOfDetail::new(2.0);
+
+        assert_eq!(SdfLevelOfDetail::projected_pixels(1.0, 100.0, 1.0, 600), 3.0);
+        assert!(false == system_under_test.replaces(1.0, 100.0, 1.0, 600));
+        assert!(system_under_test.replaces(1.0, 200.0, 1.0, 600));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_threshold() {
+        let _ = SdfLevelOfDetail::new(0.0);
+    }
+}
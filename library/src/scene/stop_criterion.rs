@@ -0,0 +1,56 @@
+use more_asserts::assert_gt;
+
+// the Monte Carlo accumulation stops once the estimated relative error of the image falls below the
+// threshold, or once the pixels have accumulated 'max_samples' samples, whichever comes first; any
+// change restarting the accumulation resumes the sampling
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StopCriterion {
+    error_threshold: f64,
+    max_samples: u32,
+}
+
+impl StopCriterion {
+    #[must_use]
+    pub fn new(error_threshold: f64, max_samples: u32) -> Self {
+        assert!(error_threshold.is_finite() && error_threshold >= 0.0, "error threshold {error_threshold} is not a finite non negative value");
+        assert_gt!(max_samples, 0, "at least one sample expected");
+        Self { error_threshold, max_samples }
+    }
+
+    // the root mean square of the luminance error relative to the one of the luminance itself
+    #[must_use]
+    pub fn error_threshold(&self) -> f64 {
+        self.error_threshold
+    }
+
+    #[must_use]
+    pub fn max_samples(&self) -> u32 {
+        self.max_samples
+    }
+
+    #[must_use]
+    pub fn met(&self, relative_error: Option<f64>, samples: u32) -> bool {
+        samples >= self.max_samples || relative_error.is_some_and(|error| error <= self.error_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_met() {
+        let system_under_test = StopCriterion::new(0.01, 64);
+
+        assert!(false == system_under_test.met(None, 63));
+        assert!(false == system_under_test.met(Some(0.02), 63));
+        assert!(system_under_test.met(Some(0.01), 16));
+        assert!(system_under_test.met(None, 64));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_max_samples() {
+        let _ = StopCriterion::new(0.01, 0);
+    }
+}
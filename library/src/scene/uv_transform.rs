@@ -0,0 +1,96 @@
+use cgmath::{Matrix2, Rad, Vector2};
+
+// the 2d affine applied to the texture coordinates of an object before the sampling of its atlas regions
+// (the baked procedural textures included): the coordinates are scaled, then rotated around the origin,
+// then offset; a scale of 2 repeats a 'WrapMode::Repeat' texture twice over the same surface
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct UvTransform {
+    offset: Vector2<f32>,
+    scale: Vector2<f32>,
+    rotation: Rad<f32>,
+}
+
+impl Default for UvTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl UvTransform {
+    pub const IDENTITY: UvTransform = UvTransform { offset: Vector2::new(0.0, 0.0), scale: Vector2::new(1.0, 1.0), rotation: Rad(0.0) };
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::IDENTITY
+    }
+
+    #[must_use]
+    pub fn with_offset(mut self, u: f32, v: f32) -> Self {
+        self.offset = Vector2::new(u, v);
+        self
+    }
+
+    #[must_use]
+    pub fn with_scale(mut self, u: f32, v: f32) -> Self {
+        self.scale = Vector2::new(u, v);
+        self
+    }
+
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: impl Into<Rad<f32>>) -> Self {
+        self.rotation = rotation.into();
+        self
+    }
+
+    #[must_use]
+    pub fn offset(&self) -> Vector2<f32> {
+        self.offset
+    }
+
+    #[must_use]
+    pub fn scale(&self) -> Vector2<f32> {
+        self.scale
+    }
+
+    #[must_use]
+    pub fn rotation(&self) -> Rad<f32> {
+        self.rotation
+    }
+
+    // the linear part, rotation after scale; the columns are the images of the u and the v axes
+    #[must_use]
+    pub(crate) fn linear(&self) -> Matrix2<f32> {
+        let (sine, cosine) = self.rotation.0.sin_cos();
+        Matrix2::new(
+            cosine * self.scale.x, sine * self.scale.x,
+            -sine * self.scale.y, cosine * self.scale.y,
+        )
+    }
+
+    // where the texture coordinate is sampled from
+    #[must_use]
+    pub fn apply(&self, uv: Vector2<f32>) -> Vector2<f32> {
+        self.linear() * uv + self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Deg;
+
+    #[test]
+    fn test_identity() {
+        assert_eq!(UvTransform::new().apply(Vector2::new(0.25, 0.75)), Vector2::new(0.25, 0.75));
+    }
+
+    #[test]
+    fn test_scale_rotation_offset_order() {
+        let system_under_test = UvTransform::new().with_scale(2.0, 3.0).with_rotation(Deg(90.0)).with_offset(0.5, 0.0);
+
+        let actual = system_under_test.apply(Vector2::new(1.0, 1.0));
+
+        assert!((actual.x - (0.5 - 3.0)).abs() < 1e-6);
+        assert!((actual.y - 2.0).abs() < 1e-6);
+    }
+}
@@ -4,4 +4,7 @@ pub mod sdf_subtraction;
 pub mod sdf_subtraction_smooth;
 pub mod sdf_union;
 pub mod sdf_union_smooth;
-mod intersection;
\ No newline at end of file
+pub mod sdf_repetition_finite;
+pub mod sdf_repetition_infinite;
+mod intersection;
+mod repetition;
\ No newline at end of file
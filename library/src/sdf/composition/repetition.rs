@@ -0,0 +1,38 @@
+use crate::geometry::alias::Vector;
+use crate::geometry::axis::Axis;
+use crate::sdf::morphing::morphing_swizzle::axis_address;
+use crate::shader::conventions;
+use crate::shader::formatting_utils::format_scalar;
+
+// folds the point into the cell around the origin; the axes without the limit (none) repeat endlessly,
+// the axes of zero period or zero limit are not repeated
+#[must_use]
+pub(in crate::sdf) fn format_repetition(period: Vector, copies_per_side: Option<[u32; 3]>) -> String {
+    let components: Vec<String> = [Axis::X, Axis::Y, Axis::Z].into_iter().map(|axis| {
+        let component = format!("{parameter}.{axis}", parameter = conventions::PARAMETER_NAME_THE_POINT, axis = axis_address(axis));
+        let axis_period = period[axis.as_index()];
+        let limit = copies_per_side.map(|copies| copies[axis.as_index()]);
+        if axis_period <= 0.0 || limit == Some(0) {
+            return component;
+        }
+        let period = format_scalar(axis_period);
+        match limit {
+            Some(limit) => {
+                let limit = format_scalar(limit as f64);
+                format!("{component}-{period}*clamp(round({component}/{period}),-{limit},{limit})")
+            }
+            None => format!("{component}-{period}*round({component}/{period})"),
+        }
+    }).collect();
+
+    format!(
+        "let {parameter} = vec3f({components});",
+        parameter = conventions::PARAMETER_NAME_THE_POINT,
+        components = components.join(", "),
+    )
+}
+
+pub(in crate::sdf) fn assert_period_valid(period: Vector) {
+    assert!(period.x >= 0.0 && period.y >= 0.0 && period.z >= 0.0, "period must be >= 0");
+    assert!(period.x > 0.0 || period.y > 0.0 || period.z > 0.0, "at least one axis must be repeated");
+}
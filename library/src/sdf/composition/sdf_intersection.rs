@@ -1,6 +1,6 @@
 use crate::geometry::aabb::Aabb;
 use crate::sdf::composition::intersection::intersection_aabb;
-use crate::sdf::framework::n_ary_operations_utils::produce_binary_operation_body;
+use crate::sdf::framework::n_ary_operations_utils::{produce_binary_operation_body, produce_binary_gradient_body, produce_gradient_max};
 use crate::sdf::framework::sdf_base::Sdf;
 use crate::sdf::framework::stack::Stack;
 use std::rc::Rc;
@@ -27,6 +27,12 @@ impl Sdf for SdfIntersection {
             , |left_name, right_name| format!("max({left_name},{right_name})"))
     }
 
+    fn produce_gradient_body(&self, children_gradients: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(produce_binary_gradient_body(children_gradients, level
+            , |_, _| "".to_string()
+            , |left_name, right_name| produce_gradient_max(&left_name.into(), &right_name.into())))
+    }
+
     fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
         vec![self.left.clone(), self.right.clone()]
     }
@@ -39,7 +45,7 @@ impl Sdf for SdfIntersection {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sdf::framework::n_ary_operations_utils::tests::{test_binary_operator_body_production, test_binary_operator_descendants};
+    use crate::sdf::framework::n_ary_operations_utils::tests::{test_binary_operator_body_production, test_binary_operator_descendants, test_binary_operator_gradient_production};
 
     #[test]
     fn test_children() {
@@ -54,4 +60,13 @@ mod tests {
             expected_body,
         );
     }
+
+    #[test]
+    fn test_produce_gradient_body() {
+        let expected_body = "var left_0: vec4f;\n{\nleft_0 = ?_left;\n}\nvar right_0: vec4f;\n{\nright_0 = !_right;\n}\n\nreturn select(right_0, left_0, left_0.w>right_0.w);";
+        test_binary_operator_gradient_production(
+            |left, right| SdfIntersection::new(left, right),
+            expected_body,
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use crate::geometry::aabb::Aabb;
 use crate::sdf::composition::intersection::intersection_aabb;
-use crate::sdf::framework::n_ary_operations_utils::{produce_binary_operation_body, produce_smooth_union_preparation, produce_smooth_union_return};
+use crate::sdf::framework::n_ary_operations_utils::{produce_binary_operation_body, produce_smooth_union_preparation, produce_smooth_union_return, produce_binary_gradient_body, produce_smooth_union_gradient_preparation, produce_smooth_union_gradient_return};
 use crate::sdf::framework::sdf_base::Sdf;
 use crate::shader::formatting_utils::format_scalar;
 use crate::sdf::framework::stack::Stack;
@@ -34,6 +34,16 @@ impl Sdf for SdfIntersectionSmooth {
         )
     }
 
+    fn produce_gradient_body(&self, children_gradients: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(produce_binary_gradient_body(children_gradients, level
+            , |left_name, right_name| produce_smooth_union_gradient_preparation(&format!("(-{left_name})"), &format!("(-{right_name})"), &self.smooth_size)
+            , |left_name, right_name| {
+                let union = produce_smooth_union_gradient_return(&format!("(-{left_name})"), &format!("(-{right_name})"), &self.smooth_size);
+                format!("-({union})")
+            }
+        ))
+    }
+
     fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
         vec![self.left.clone(), self.right.clone()]
     }
@@ -47,7 +57,7 @@ impl Sdf for SdfIntersectionSmooth {
 mod tests {
     use super::*;
     use crate::sdf::framework::dummy_sdf::tests::DummySdf;
-    use crate::sdf::framework::n_ary_operations_utils::tests::test_binary_operator_descendants;
+    use crate::sdf::framework::n_ary_operations_utils::tests::{test_binary_operator_descendants, test_binary_operator_gradient_production};
     use crate::sdf::framework::sdf_base::Sdf;
     use crate::sdf::framework::stack::Stack;
 
@@ -71,4 +81,13 @@ mod tests {
         let expected_body = "var left_0: f32;\n{\nleft_0 = ?_left;\n}\nvar right_0: f32;\n{\nright_0 = !_right;\n}\nlet h = max(0.25-abs((-left_0)-(-right_0)),0.0);\nreturn -(min((-left_0), (-right_0)) - h*h*0.25/0.25);";
         assert_eq!(actual_body.to_string(), expected_body.to_string());
     }
+
+    #[test]
+    fn test_produce_gradient_body() {
+        let expected_body = "var left_0: vec4f;\n{\nleft_0 = ?_left;\n}\nvar right_0: vec4f;\n{\nright_0 = !_right;\n}\nlet h = max(0.25-abs((-left_0).w-(-right_0).w),0.0);\nreturn -(select((-right_0), (-left_0), (-left_0).w<(-right_0).w) + vec4f(0.5*h/0.25*sign((-left_0).w-(-right_0).w)*((-left_0).xyz-(-right_0).xyz), -h*h*0.25/0.25));";
+        test_binary_operator_gradient_production(
+            |left, right| SdfIntersectionSmooth::new(left, right, 0.25),
+            expected_body,
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::Vector;
+use crate::sdf::composition::repetition::{assert_period_valid, format_repetition};
+use crate::sdf::framework::n_ary_operations_utils::{produce_parameter_transform_body, produce_parameter_transform_gradient_body};
+use crate::sdf::framework::sdf_base::Sdf;
+use crate::sdf::framework::stack::Stack;
+use crate::shader::code::{FunctionBody, ShaderCode};
+use cgmath::ElementWise;
+use std::rc::Rc;
+
+// the target and 'copies_per_side' of its copies on both sides of it along each axis, 'period' apart;
+// the axes of zero period or zero copies are not repeated
+pub struct SdfRepetitionFinite {
+    target: Rc<dyn Sdf>,
+    period: Vector,
+    copies_per_side: [u32; 3],
+}
+
+impl SdfRepetitionFinite {
+    #[must_use]
+    pub fn new(target: Rc<dyn Sdf>, period: Vector, copies_per_side: [u32; 3]) -> Rc<Self> {
+        assert_period_valid(period);
+        Rc::new(Self { target, period, copies_per_side })
+    }
+
+    #[must_use]
+    fn reach(&self) -> Vector {
+        let copies = Vector::new(self.copies_per_side[0] as f64, self.copies_per_side[1] as f64, self.copies_per_side[2] as f64);
+        self.period.mul_element_wise(copies)
+    }
+}
+
+impl Sdf for SdfRepetitionFinite {
+    fn produce_body(&self, children_bodies: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> ShaderCode<FunctionBody> {
+        produce_parameter_transform_body(children_bodies, level, || format_repetition(self.period, Some(self.copies_per_side)))
+    }
+
+    fn produce_gradient_body(&self, children_gradients: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(produce_parameter_transform_gradient_body(children_gradients, level, || format_repetition(self.period, Some(self.copies_per_side))))
+    }
+
+    fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
+        vec![self.target.clone()]
+    }
+
+    fn aabb(&self) -> Aabb {
+        let cell = self.target.aabb();
+        let reach = self.reach();
+        Aabb::from_points(cell.min() - reach, cell.max() + reach)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::alias::Point;
+    use crate::sdf::framework::n_ary_operations_utils::tests::{test_unary_operator_body_production, test_unary_operator_descendants};
+    use crate::sdf::object::sdf_box::SdfBox;
+    use crate::sdf::transformation::sdf_translation::SdfTranslation;
+
+    #[test]
+    fn test_children() {
+        test_unary_operator_descendants(|descendant| SdfRepetitionFinite::new(descendant, Vector::new(1.0, 1.0, 1.0), [1, 1, 1]));
+    }
+
+    #[test]
+    fn test_produce_body() {
+        let expected_body = "var operand_0: f32;\n{\nlet point = vec3f(point.x-2.0*clamp(round(point.x/2.0),-3.0,3.0), point.y, point.z);\n{\noperand_0 = ?_left;\n}\n}\nreturn operand_0;";
+        test_unary_operator_body_production(
+            |descendant| SdfRepetitionFinite::new(descendant, Vector::new(2.0, 5.0, 0.0), [3, 0, 4]),
+            expected_body,
+        );
+    }
+
+    #[test]
+    fn test_aabb() {
+        let target = SdfTranslation::new(Vector::new(1.0, 0.0, 0.0), SdfBox::new(Vector::new(1.0, 2.0, 3.0)));
+        let system_under_test = SdfRepetitionFinite::new(target, Vector::new(4.0, 5.0, 0.0), [2, 0, 7]);
+
+        assert_eq!(system_under_test.aabb(), Aabb::from_points(Point::new(-8.0, -2.0, -3.0), Point::new(10.0, 2.0, 3.0)));
+    }
+}
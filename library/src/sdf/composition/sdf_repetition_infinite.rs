@@ -0,0 +1,98 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::Vector;
+use crate::sdf::composition::repetition::{assert_period_valid, format_repetition};
+use crate::sdf::framework::n_ary_operations_utils::{produce_parameter_transform_body, produce_parameter_transform_gradient_body};
+use crate::sdf::framework::sdf_base::Sdf;
+use crate::sdf::framework::stack::Stack;
+use crate::shader::code::{FunctionBody, ShaderCode};
+use std::rc::Rc;
+
+// the copies of the target are 'period' apart; a zero period component leaves the axis as is.
+// The target is expected to fit its cell: the distances to the neighbour copies are not evaluated
+pub struct SdfRepetitionInfinite {
+    target: Rc<dyn Sdf>,
+    period: Vector,
+}
+
+impl SdfRepetitionInfinite {
+    // the bvh cannot hold endless boxes: along the repeated axes the box spans this far from the origin
+    pub const BOUNDING_HALF_EXTENT: f64 = 1000.0;
+
+    #[must_use]
+    pub fn new(target: Rc<dyn Sdf>, period: Vector) -> Rc<Self> {
+        assert_period_valid(period);
+        Rc::new(Self { target, period })
+    }
+}
+
+impl Sdf for SdfRepetitionInfinite {
+    fn produce_body(&self, children_bodies: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> ShaderCode<FunctionBody> {
+        produce_parameter_transform_body(children_bodies, level, || format_repetition(self.period, None))
+    }
+
+    fn produce_gradient_body(&self, children_gradients: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(produce_parameter_transform_gradient_body(children_gradients, level, || format_repetition(self.period, None)))
+    }
+
+    fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
+        vec![self.target.clone()]
+    }
+
+    fn aabb(&self) -> Aabb {
+        let cell = self.target.aabb();
+        let mut min = cell.min();
+        let mut max = cell.max();
+        for axis in 0..3 {
+            if self.period[axis] > 0.0 {
+                min[axis] = -Self::BOUNDING_HALF_EXTENT;
+                max[axis] = Self::BOUNDING_HALF_EXTENT;
+            }
+        }
+        Aabb::from_points(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdf::framework::n_ary_operations_utils::tests::{test_unary_operator_body_production, test_unary_operator_descendants, test_unary_operator_gradient_production};
+    use crate::geometry::alias::Point;
+    use crate::sdf::object::sdf_box::SdfBox;
+
+    #[test]
+    fn test_children() {
+        test_unary_operator_descendants(|descendant| SdfRepetitionInfinite::new(descendant, Vector::new(1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_produce_body() {
+        let expected_body = "var operand_0: f32;\n{\nlet point = vec3f(point.x-2.0*round(point.x/2.0), point.y, point.z-3.0*round(point.z/3.0));\n{\noperand_0 = ?_left;\n}\n}\nreturn operand_0;";
+        test_unary_operator_body_production(
+            |descendant| SdfRepetitionInfinite::new(descendant, Vector::new(2.0, 0.0, 3.0)),
+            expected_body,
+        );
+    }
+
+    #[test]
+    fn test_produce_gradient_body() {
+        let expected_body = "var operand_0: vec4f;\n{\nlet point = vec3f(point.x-2.0*round(point.x/2.0), point.y, point.z);\n{\noperand_0 = ?_left;\n}\n}\nreturn operand_0;";
+        test_unary_operator_gradient_production(
+            |descendant| SdfRepetitionInfinite::new(descendant, Vector::new(2.0, 0.0, 0.0)),
+            expected_body,
+        );
+    }
+
+    #[test]
+    fn test_aabb() {
+        let system_under_test = SdfRepetitionInfinite::new(SdfBox::new(Vector::new(1.0, 2.0, 3.0)), Vector::new(4.0, 0.0, 0.0));
+
+        let extent = SdfRepetitionInfinite::BOUNDING_HALF_EXTENT;
+        assert_eq!(system_under_test.aabb(), Aabb::from_points(Point::new(-extent, -2.0, -3.0), Point::new(extent, 2.0, 3.0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nothing_repeated() {
+        let _ = SdfRepetitionInfinite::new(SdfBox::new(Vector::new(1.0, 1.0, 1.0)), Vector::new(0.0, 0.0, 0.0));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::geometry::aabb::Aabb;
-use crate::sdf::framework::n_ary_operations_utils::{produce_binary_operation_body, produce_smooth_union_preparation, produce_smooth_union_return};
+use crate::sdf::framework::n_ary_operations_utils::{produce_binary_operation_body, produce_smooth_union_preparation, produce_smooth_union_return, produce_binary_gradient_body, produce_smooth_union_gradient_preparation, produce_smooth_union_gradient_return};
 use crate::sdf::framework::sdf_base::Sdf;
 use crate::shader::formatting_utils::format_scalar;
 use crate::sdf::framework::stack::Stack;
@@ -36,6 +36,15 @@ impl Sdf for SdfUnionSmooth {
         )
     }
 
+    fn produce_gradient_body(&self, children_gradients: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(produce_binary_gradient_body(
+            children_gradients,
+            level,
+            |left_name, right_name| produce_smooth_union_gradient_preparation(&left_name.into(), &right_name.into(), &self.smooth_size),
+            |left_name, right_name| produce_smooth_union_gradient_return(&left_name.into(), &right_name.into(), &self.smooth_size),
+        ))
+    }
+
     fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
         vec![self.left.clone(), self.right.clone()]
     }
@@ -48,7 +57,7 @@ impl Sdf for SdfUnionSmooth {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sdf::framework::n_ary_operations_utils::tests::{test_binary_operator_body_production, test_binary_operator_descendants};
+    use crate::sdf::framework::n_ary_operations_utils::tests::{test_binary_operator_body_production, test_binary_operator_descendants, test_binary_operator_gradient_production};
 
     #[test]
     fn test_children() {
@@ -63,4 +72,13 @@ mod tests {
             expected_body,
         );
     }
+
+    #[test]
+    fn test_produce_gradient_body() {
+        let expected_body = "var left_0: vec4f;\n{\nleft_0 = ?_left;\n}\nvar right_0: vec4f;\n{\nright_0 = !_right;\n}\nlet h = max(0.25-abs(left_0.w-right_0.w),0.0);\nreturn select(right_0, left_0, left_0.w<right_0.w) + vec4f(0.5*h/0.25*sign(left_0.w-right_0.w)*(left_0.xyz-right_0.xyz), -h*h*0.25/0.25);";
+        test_binary_operator_gradient_production(
+            |left, right| SdfUnionSmooth::new(left, right, 0.25),
+            expected_body,
+        );
+    }
 }
@@ -9,6 +9,7 @@ mod equality_sets;
 pub mod sdf_registrator;
 pub mod named_sdf;
 pub mod sdf_base;
+pub mod sdf_parameters;
 pub mod stack;
 pub(crate) mod selection_generator;
 pub(crate) mod animation_undo_generator;
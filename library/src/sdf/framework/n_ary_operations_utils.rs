@@ -2,6 +2,7 @@ use crate::shader::variable_name::VariableName;
 use crate::sdf::framework::stack::Stack;
 use crate::shader::code::{FunctionBody, ShaderCode};
 
+// the value of each operand (and the result) is 'f32' distance
 #[must_use]
 pub fn produce_binary_operation_body<Operation, Preparation>(
     children_bodies: &mut Stack<ShaderCode<FunctionBody>>,
@@ -9,6 +10,64 @@ pub fn produce_binary_operation_body<Operation, Preparation>(
     preparation: Preparation,
     operation: Operation,
 ) -> ShaderCode<FunctionBody>
+where
+    Operation: FnOnce(&VariableName, &VariableName) -> String,
+    Preparation: FnOnce(&VariableName, &VariableName) -> String,
+{
+    produce_typed_binary_operation_body(children_bodies, level, SCALAR_TYPE, preparation, operation)
+}
+
+// the value of each operand (and the result) is 'vec4f(gradient, distance)'
+#[must_use]
+pub fn produce_binary_gradient_body<Operation, Preparation>(
+    children_gradients: &mut Stack<ShaderCode<FunctionBody>>,
+    level: Option<usize>,
+    preparation: Preparation,
+    operation: Operation,
+) -> ShaderCode<FunctionBody>
+where
+    Operation: FnOnce(&VariableName, &VariableName) -> String,
+    Preparation: FnOnce(&VariableName, &VariableName) -> String,
+{
+    produce_typed_binary_operation_body(children_gradients, level, GRADIENT_TYPE, preparation, operation)
+}
+
+#[must_use]
+pub fn produce_parameter_transform_body<Transform>(
+    children_bodies: &mut Stack<ShaderCode<FunctionBody>>,
+    level: Option<usize>,
+    transform: Transform,
+) -> ShaderCode<FunctionBody>
+where
+    Transform: FnOnce() -> String,
+{
+    produce_typed_parameter_transform_body(children_bodies, level, SCALAR_TYPE, transform)
+}
+
+// fits the transforms with the identity jacobian only: the gradient of the child is returned as is
+#[must_use]
+pub fn produce_parameter_transform_gradient_body<Transform>(
+    children_gradients: &mut Stack<ShaderCode<FunctionBody>>,
+    level: Option<usize>,
+    transform: Transform,
+) -> ShaderCode<FunctionBody>
+where
+    Transform: FnOnce() -> String,
+{
+    produce_typed_parameter_transform_body(children_gradients, level, GRADIENT_TYPE, transform)
+}
+
+const SCALAR_TYPE: &str = "f32";
+const GRADIENT_TYPE: &str = "vec4f";
+
+#[must_use]
+fn produce_typed_binary_operation_body<Operation, Preparation>(
+    children_bodies: &mut Stack<ShaderCode<FunctionBody>>,
+    level: Option<usize>,
+    value_type: &str,
+    preparation: Preparation,
+    operation: Operation,
+) -> ShaderCode<FunctionBody>
 where
     Operation: FnOnce(&VariableName, &VariableName) -> String,
     Preparation: FnOnce(&VariableName, &VariableName) -> String,
@@ -16,10 +75,10 @@ where
     assert!(children_bodies.size() >= 2);
 
     let right_name = VariableName::new("right", level);
-    let right_sdf = children_bodies.pop().to_scalar_declaration_assignment(&right_name);
+    let right_sdf = children_bodies.pop().to_declaration_assignment(&right_name, value_type);
 
     let left_name = VariableName::new("left", level);
-    let left_sdf = children_bodies.pop().to_scalar_declaration_assignment(&left_name);
+    let left_sdf = children_bodies.pop().to_declaration_assignment(&left_name, value_type);
 
     ShaderCode::<FunctionBody>::new(format!(
         "{left}\n\
@@ -35,9 +94,10 @@ where
 }
 
 #[must_use]
-pub fn produce_parameter_transform_body<Transform>(
+fn produce_typed_parameter_transform_body<Transform>(
     children_bodies: &mut Stack<ShaderCode<FunctionBody>>,
     level: Option<usize>,
+    value_type: &str,
     transform: Transform,
 ) -> ShaderCode<FunctionBody>
 where
@@ -46,10 +106,10 @@ where
     assert!(children_bodies.size() >= 1);
 
     let child_name = VariableName::new("operand", level);
-    let child_assignment = children_bodies.pop().to_scalar_assignment(&child_name);
+    let child_assignment = children_bodies.pop().to_assignment(&child_name);
 
     ShaderCode::<FunctionBody>::new(format!(
-        "var {child_name}: f32;\n\
+        "var {child_name}: {value_type};\n\
         {{\n\
         {transform}\n\
         {child_assignment}\n\
@@ -80,6 +140,40 @@ pub fn produce_smooth_union_return(
     format!("min({left_value}, {right_value}) - h*h*0.25/{smooth_size}")
 }
 
+// the gradient operands are 'vec4f(gradient, distance)': the negation of the whole vector negates both
+
+#[must_use]
+pub fn produce_gradient_min(left_value: &String, right_value: &String) -> String {
+    format!("select({right_value}, {left_value}, {left_value}.w<{right_value}.w)")
+}
+
+#[must_use]
+pub fn produce_gradient_max(left_value: &String, right_value: &String) -> String {
+    format!("select({right_value}, {left_value}, {left_value}.w>{right_value}.w)")
+}
+
+#[must_use]
+pub fn produce_smooth_union_gradient_preparation(
+    left_value: &String,
+    right_value: &String,
+    smooth_size: &String,
+) -> String {
+    format!("let h = max({smooth_size}-abs({left_value}.w-{right_value}.w),0.0);")
+}
+
+// the derivative of 'h' is '-sign(left-right)*(left'-right')' where 'h' is positive, so is the blend term's
+#[must_use]
+pub fn produce_smooth_union_gradient_return(
+    left_value: &String,
+    right_value: &String,
+    smooth_size: &String,
+) -> String {
+    format!(
+        "{min} + vec4f(0.5*h/{smooth_size}*sign({left_value}.w-{right_value}.w)*({left_value}.xyz-{right_value}.xyz), -h*h*0.25/{smooth_size})",
+        min = produce_gradient_min(left_value, right_value),
+    )
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use std::rc::Rc;
@@ -122,6 +216,32 @@ pub(crate) mod tests {
         assert_eq!(actual_body.to_string(), expected_body.to_string());
     }
 
+    pub(crate) fn test_unary_operator_gradient_production(constructor: impl FnOnce(Rc<dyn Sdf>) -> Rc<dyn Sdf>, expected_body: &str) {
+        let descendant: Rc<dyn Sdf> = Rc::new(DummySdf::new("right_23"));
+        let system_under_test = constructor(descendant);
+
+        let mut descendant_gradients = Stack::<ShaderCode<FunctionBody>>::new();
+        descendant_gradients.push(ShaderCode::<FunctionBody>::new("return ?_left;".to_string()),);
+
+        let actual_body = system_under_test.produce_gradient_body(&mut descendant_gradients, Some(0)).unwrap();
+
+        assert_eq!(actual_body.to_string(), expected_body.to_string());
+    }
+
+    pub(crate) fn test_binary_operator_gradient_production(constructor: impl FnOnce(Rc<dyn Sdf>, Rc<dyn Sdf>) -> Rc<dyn Sdf>, expected_body: &str) {
+        let left: Rc<dyn Sdf> = Rc::new(DummySdf::new("left_17"));
+        let right: Rc<dyn Sdf> = Rc::new(DummySdf::new("right_23"));
+        let system_under_test = constructor(left.clone(), right.clone());
+
+        let mut descendant_gradients = Stack::<ShaderCode<FunctionBody>>::new();
+        descendant_gradients.push(ShaderCode::<FunctionBody>::new("return ?_left;".to_string()),);
+        descendant_gradients.push(ShaderCode::<FunctionBody>::new("return !_right;".to_string()),);
+
+        let actual_body = system_under_test.produce_gradient_body(&mut descendant_gradients, Some(0)).unwrap();
+
+        assert_eq!(actual_body.to_string(), expected_body.to_string());
+    }
+
     pub(crate) fn test_binary_operator_body_production(constructor: impl FnOnce(Rc<dyn Sdf>, Rc<dyn Sdf>) -> Rc<dyn Sdf>, expected_body: &str) {
         let left: Rc<dyn Sdf> = Rc::new(DummySdf::new("left_17"));
         let right: Rc<dyn Sdf> = Rc::new(DummySdf::new("right_23"));
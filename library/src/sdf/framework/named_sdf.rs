@@ -1,17 +1,48 @@
 use std::rc::Rc;
 use std::fmt::{Display, Formatter};
 use crate::sdf::framework::sdf_base::Sdf;
+use crate::sdf::framework::sdf_parameters::{SdfParameters, SdfParametersBuilder};
 
 #[derive(Clone)]
 pub struct NamedSdf {
     sdf: Rc<dyn Sdf>, 
     name: UniqueSdfClassName,
+    parameters: SdfParameters,
+    distance_grid_resolution: Option<u32>,
 }
 
 impl NamedSdf {
+    pub const MAX_DISTANCE_GRID_RESOLUTION: u32 = 64;
+
     #[must_use]
     pub const fn new(sdf: Rc<dyn Sdf>, name: UniqueSdfClassName) -> Self {
-        Self { sdf, name }
+        Self { sdf, name, parameters: SdfParameters::empty(), distance_grid_resolution: None }
+    }
+
+    // the tree gets the scalars declared by the builder: the instances can change them without
+    // a shader recompilation, see 'Hub::set_sdf_parameter'
+    #[must_use]
+    pub fn parametric(name: UniqueSdfClassName, make_sdf: impl FnOnce(&mut SdfParametersBuilder) -> Rc<dyn Sdf>) -> Self {
+        let mut builder = SdfParametersBuilder::default();
+        let sdf = make_sdf(&mut builder);
+        Self { sdf, name, parameters: builder.build(), distance_grid_resolution: None }
+    }
+
+    // the distances sampled over the class box (resolution cells per side) when the class gets registered;
+    // the ray marching skips the empty space by them and evaluates the class near the surface only.
+    // worth it for the expensive classes; the shape must not depend on the parameters or the time
+    #[must_use]
+    pub fn with_distance_grid(mut self, resolution: u32) -> Self {
+        assert!((1..=Self::MAX_DISTANCE_GRID_RESOLUTION).contains(&resolution), "distance grid resolution {resolution} is out of range");
+        assert_eq!(self.parameters.count(), 0, "the shape of the parametric class '{}' varies per instance", self.name);
+        assert!(false == Self::animated(&self.sdf), "the shape of the animated class '{}' varies in time", self.name);
+        self.distance_grid_resolution = Some(resolution);
+        self
+    }
+
+    #[must_use]
+    fn animated(sdf: &Rc<dyn Sdf>) -> bool {
+        sdf.animation_only().is_some() || sdf.descendants().iter().any(Self::animated)
     }
     
     #[must_use]
@@ -23,6 +54,16 @@ impl NamedSdf {
     pub(crate) fn sdf(&self) -> Rc<dyn Sdf> {
         self.sdf.clone()
     }
+
+    #[must_use]
+    pub fn parameters(&self) -> &SdfParameters {
+        &self.parameters
+    }
+
+    #[must_use]
+    pub(crate) fn distance_grid_resolution(&self) -> Option<u32> {
+        self.distance_grid_resolution
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -53,6 +94,43 @@ impl Display for UniqueSdfClassName {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geometry::axis::Axis;
+    use crate::sdf::morphing::sdf_twister_along_axis::SdfTwisterAlongAxis;
+    use crate::sdf::object::sdf_sphere::SdfSphere;
+
+    #[must_use]
+    fn make_name() -> UniqueSdfClassName {
+        UniqueSdfClassName::new("class".to_string())
+    }
+
+    #[test]
+    fn test_with_distance_grid() {
+        let system_under_test = NamedSdf::new(SdfSphere::new(1.0), make_name());
+        assert_eq!(system_under_test.distance_grid_resolution(), None);
+
+        let system_under_test = system_under_test.with_distance_grid(16);
+
+        assert_eq!(system_under_test.distance_grid_resolution(), Some(16));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_distance_grid_of_parametric_class() {
+        let _ = NamedSdf::parametric(make_name(), |parameters| SdfSphere::new_parametric(parameters.declare("radius", 1.0, 0.5..=2.0)))
+            .with_distance_grid(16);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_distance_grid_of_animated_class() {
+        let _ = NamedSdf::new(SdfTwisterAlongAxis::new(SdfSphere::new(1.0), Axis::Z, 1.0, 1.0), make_name()).with_distance_grid(16);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_distance_grid_resolution_out_of_range() {
+        let _ = NamedSdf::new(SdfSphere::new(1.0), make_name()).with_distance_grid(NamedSdf::MAX_DISTANCE_GRID_RESOLUTION + 1);
+    }
 
     #[test]
     fn test_display() {
@@ -7,11 +7,25 @@ pub trait Sdf {
     #[must_use]
     fn produce_body(&self, children_bodies: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> ShaderCode<FunctionBody>;
 
+    // the body returns 'vec4f(gradient, distance)' evaluated together (as a dual number would do);
+    // none - the normals of the sdf class are taken by the finite differences of the distance
+    #[must_use]
+    fn produce_gradient_body(&self, _children_gradients: &mut Stack<ShaderCode<FunctionBody>>, _level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        None
+    }
+
     #[must_use]
     fn animation_only(&self) -> Option<ShaderCode<FunctionBody>> {
         None
     }
     
+    // the bound of the distance change per unit of the point movement: above one, the ray marching
+    // overshoots the surface, unless the steps are shrunk by the bound (see 'Hub::add_sdf')
+    #[must_use]
+    fn lipschitz_bound(&self) -> f64 {
+        self.descendants().iter().map(|descendant| descendant.lipschitz_bound()).fold(1.0, f64::max)
+    }
+
     #[must_use]
     fn descendants(&self) -> Vec<Rc<dyn Sdf>>;
     
@@ -2,7 +2,7 @@ use crate::sdf::framework::dfs;
 use crate::sdf::framework::function_body_dossier::FunctionBodyDossier;
 use crate::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
 use crate::sdf::framework::sdf_registrator::SdfRegistrator;
-use crate::sdf::framework::sdf_shader_code::{format_sdf_declaration, format_sdf_invocation};
+use crate::sdf::framework::sdf_shader_code::{format_sdf_declaration, format_sdf_gradient_declaration, format_sdf_invocation};
 use crate::sdf::framework::stack::Stack;
 use crate::shader::code::{FunctionBody, ShaderCode};
 use crate::shader::function_name::FunctionName;
@@ -77,6 +77,41 @@ impl SdfCodeGenerator {
             sdf_name
         }
     }
+
+    // none if any node of the tree lacks the analytic gradient; unlike the distances, the gradient
+    // code is not shared between the classes: it is evaluated once per ray hit, not per marching step
+    pub(crate) fn generate_gradient_code_for(&self, target: &NamedSdf, buffer: &mut String) -> Option<FunctionName> {
+        assert!(self.registered.contains_key(target.name()));
+
+        struct Context {
+            descendant_gradients: Stack<ShaderCode<FunctionBody>>,
+            analytic: bool,
+        }
+
+        let mut context = Context {
+            descendant_gradients: Stack::new(),
+            analytic: true,
+        };
+
+        dfs::depth_first_search(target.sdf(), &mut context, |candidate, context, levels_below| {
+            if false == context.analytic {
+                return;
+            }
+            match candidate.produce_gradient_body(&mut context.descendant_gradients, Some(levels_below)) {
+                Some(gradient) => context.descendant_gradients.push(gradient),
+                None => context.analytic = false,
+            }
+        });
+
+        if false == context.analytic {
+            return None;
+        }
+        debug_assert_eq!(context.descendant_gradients.size(), 1);
+
+        let gradient_name = FunctionName(format!("gradient_of_{}", FunctionName::from(target.name())));
+        format_sdf_gradient_declaration(&context.descendant_gradients.pop(), &gradient_name, buffer);
+        Some(gradient_name)
+    }
 }
 
 #[cfg(test)]
@@ -89,6 +124,7 @@ mod tests {
     use crate::sdf::transformation::sdf_translation::SdfTranslation;
     use std::rc::Rc;
     use crate::sdf::framework::sdf_base::Sdf;
+    use crate::sdf::framework::dummy_sdf::tests::DummySdf;
 
     #[test]
     fn test_single_one_node_sdf() {
@@ -218,6 +254,40 @@ mod tests {
         assert_eq!(actual_code, expected_code, "unique code differs");
     }
 
+    #[test]
+    fn test_gradient_of_tree() {
+        let tree = SdfUnion::new(
+            SdfSphere::new(17.0),
+            SdfTranslation::new(Vector::new(1.0, 2.0, 3.0), SdfSphere::new(5.0)),
+        );
+        let named = NamedSdf::new(tree, UniqueSdfClassName::new("test".to_string()));
+        let mut registrator = SdfRegistrator::default();
+        registrator.add(&named);
+        let generator_under_test = SdfCodeGenerator::new(registrator);
+
+        let mut actual_code = String::new();
+        let actual_name = generator_under_test.generate_gradient_code_for(&named, &mut actual_code);
+
+        let expected_code = "fn gradient_of_sdf_test(point: vec3f, time: f32) -> vec4f {\nvar left_2: vec4f;\n{\nlet l = length(point);\nleft_2 = vec4f(point/max(l,1e-20), l-17.0);\n}\nvar right_2: vec4f;\n{\nvar operand_1: vec4f;\n{\nlet point = point-vec3f(1.0,2.0,3.0);\n{\nlet l = length(point);\noperand_1 = vec4f(point/max(l,1e-20), l-5.0);\n}\n}\nright_2 = operand_1;\n}\n\nreturn select(right_2, left_2, left_2.w<right_2.w);\n}\n";
+        assert_eq!(actual_name, Some(FunctionName("gradient_of_sdf_test".to_string())));
+        assert_eq!(actual_code, expected_code);
+    }
+
+    #[test]
+    fn test_no_gradient_with_numeric_only_node() {
+        let tree = SdfUnion::new(SdfSphere::new(17.0), Rc::new(DummySdf::default()));
+        let named = NamedSdf::new(tree, UniqueSdfClassName::new("test".to_string()));
+        let mut registrator = SdfRegistrator::default();
+        registrator.add(&named);
+        let generator_under_test = SdfCodeGenerator::new(registrator);
+
+        let mut actual_code = String::new();
+        let actual_name = generator_under_test.generate_gradient_code_for(&named, &mut actual_code);
+
+        assert_eq!(actual_name, None);
+        assert!(actual_code.is_empty());
+    }
+
     #[must_use]
     fn generate_code(sdf: Rc<dyn Sdf>, name: UniqueSdfClassName) -> (FunctionName, String, SdfCodeGenerator) {
         let named = NamedSdf::new(sdf, name);
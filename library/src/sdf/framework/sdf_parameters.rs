@@ -0,0 +1,191 @@
+use crate::shader::conventions;
+use crate::shader::formatting_utils::format_scalar;
+use more_asserts::{assert_le, assert_lt};
+use std::ops::RangeInclusive;
+
+// the values each sdf instance keeps for the code of its class (a single gpu quartet)
+pub const SDF_PARAMETERS_COUNT: usize = 4;
+
+pub(crate) type SdfParameterValues = [f32; SDF_PARAMETERS_COUNT];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SdfParameter {
+    index: usize,
+}
+
+impl SdfParameter {
+    #[must_use]
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+
+    #[must_use]
+    fn format(&self) -> String {
+        const COMPONENTS: [char; SDF_PARAMETERS_COUNT] = ['x', 'y', 'z', 'w'];
+        format!("{}.{}", conventions::GLOBAL_NAME_SDF_PARAMETERS, COMPONENTS[self.index])
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct SdfParameterDeclaration {
+    name: String,
+    default: f64,
+    range: RangeInclusive<f64>,
+}
+
+// declared by the class, valued by each instance; the range bounds the class box (the bvh does not
+// follow the parameter changes), so the values out of it are rejected
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SdfParameters {
+    declarations: Vec<SdfParameterDeclaration>,
+}
+
+impl SdfParameters {
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self { declarations: Vec::new() }
+    }
+
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<SdfParameter> {
+        self.declarations.iter().position(|declaration| declaration.name == name).map(|index| SdfParameter { index })
+    }
+
+    #[must_use]
+    pub fn range(&self, parameter: SdfParameter) -> &RangeInclusive<f64> {
+        &self.declarations[parameter.index].range
+    }
+
+    #[must_use]
+    pub(crate) fn defaults(&self) -> SdfParameterValues {
+        let mut values = [0.0; SDF_PARAMETERS_COUNT];
+        for (value, declaration) in values.iter_mut().zip(self.declarations.iter()) {
+            *value = declaration.default as f32;
+        }
+        values
+    }
+
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.declarations.len()
+    }
+}
+
+#[derive(Default)]
+pub struct SdfParametersBuilder {
+    parameters: SdfParameters,
+}
+
+impl SdfParametersBuilder {
+    // the scalar is to be passed to the nodes of the class
+    #[must_use]
+    pub fn declare(&mut self, name: &str, default: f64, range: RangeInclusive<f64>) -> SdfScalar {
+        assert_lt!(self.parameters.count(), SDF_PARAMETERS_COUNT, "no more than {} parameters per sdf class", SDF_PARAMETERS_COUNT);
+        assert!(self.parameters.find(name).is_none(), "parameter '{name}' is declared twice");
+        assert_le!(*range.start(), *range.end(), "parameter '{}' range is empty", name);
+        assert!(range.contains(&default), "parameter '{name}' default {default} is out of the range {range:?}");
+
+        let parameter = SdfParameter { index: self.parameters.count() };
+        self.parameters.declarations.push(SdfParameterDeclaration { name: name.to_string(), default, range: range.clone() });
+        SdfScalar::Parameter { parameter, range }
+    }
+
+    #[must_use]
+    pub(crate) fn build(self) -> SdfParameters {
+        self.parameters
+    }
+}
+
+// an argument of the sdf nodes: baked into the shader or read from the instance
+#[derive(Clone, PartialEq, Debug)]
+pub enum SdfScalar {
+    Constant(f64),
+    Parameter { parameter: SdfParameter, range: RangeInclusive<f64> },
+}
+
+impl SdfScalar {
+    #[must_use]
+    pub(crate) fn format(&self) -> String {
+        match self {
+            SdfScalar::Constant(value) => format_scalar(*value),
+            SdfScalar::Parameter { parameter, .. } => parameter.format(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn minimum(&self) -> f64 {
+        match self {
+            SdfScalar::Constant(value) => *value,
+            SdfScalar::Parameter { range, .. } => *range.start(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn maximum(&self) -> f64 {
+        match self {
+            SdfScalar::Constant(value) => *value,
+            SdfScalar::Parameter { range, .. } => *range.end(),
+        }
+    }
+}
+
+impl From<f64> for SdfScalar {
+    fn from(value: f64) -> Self {
+        SdfScalar::Constant(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declare() {
+        let mut system_under_test = SdfParametersBuilder::default();
+
+        let _ = system_under_test.declare("width", 1.0, 0.5..=2.0);
+        let _ = system_under_test.declare("height", 3.0, 1.0..=4.0);
+        let parameters = system_under_test.build();
+
+        assert_eq!(parameters.find("height"), Some(SdfParameter { index: 1 }));
+        assert_eq!(parameters.find("depth"), None);
+        assert_eq!(parameters.range(SdfParameter { index: 0 }), &(0.5..=2.0));
+        assert_eq!(parameters.defaults(), [1.0, 3.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_declare_twice() {
+        let mut system_under_test = SdfParametersBuilder::default();
+        let _ = system_under_test.declare("width", 1.0, 0.5..=2.0);
+        let _ = system_under_test.declare("width", 1.0, 0.5..=2.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_declare_too_many() {
+        let mut system_under_test = SdfParametersBuilder::default();
+        for i in 0..=SDF_PARAMETERS_COUNT {
+            let _ = system_under_test.declare(&format!("parameter_{i}"), 0.0, 0.0..=1.0);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_default_out_of_range() {
+        let _ = SdfParametersBuilder::default().declare("width", 3.0, 0.5..=2.0);
+    }
+
+    #[test]
+    fn test_scalar_format() {
+        let mut parameters = SdfParametersBuilder::default();
+        let _ = parameters.declare("width", 1.0, 0.5..=2.0);
+
+        let system_under_test = parameters.declare("height", 3.0, 1.0..=4.0);
+
+        assert_eq!(system_under_test.format(), "sdf_parameters.y");
+        assert_eq!(system_under_test.minimum(), 1.0);
+        assert_eq!(system_under_test.maximum(), 4.0);
+        assert_eq!(SdfScalar::from(2.0).format(), "2.0");
+    }
+}
@@ -8,6 +8,9 @@ pub(crate) mod sdf_conventions {
     pub(crate) const FUNCTION_NAME_SELECTION: &str = "sdf_select";
     pub(super) const SDF_SELECTION_RETURN_TYPE: &str = "f32";
     
+    pub(crate) const FUNCTION_NAME_GRADIENT_SELECTION: &str = "sdf_select_gradient";
+    pub(super) const SDF_GRADIENT_RETURN_TYPE: &str = "vec4f";
+
     pub(crate) const FUNCTION_NAME_ANIMATION_APPLY: &str = "sdf_apply_animation";
     pub(super) const ANIMATION_UNDO_RETURN_TYPE: &str = "vec3f";
     
@@ -71,6 +74,29 @@ pub(crate) fn format_sdf_declaration(body: &ShaderCode<FunctionBody>, function_n
     .expect("failed to format sdf declaration");
 }
 
+#[must_use]
+pub(crate) fn format_sdf_gradient_selection_function_opening() -> String {
+    format!(
+        "fn {selection_function_name}({parameter_sdf_index}: i32, {common_parameters}) -> {return_type} {{\n",
+        selection_function_name = sdf_conventions::FUNCTION_NAME_GRADIENT_SELECTION,
+        parameter_sdf_index = sdf_conventions::PARAMETER_NAME_INDEX,
+        common_parameters = format_common_parameters(),
+        return_type = sdf_conventions::SDF_GRADIENT_RETURN_TYPE,
+    )
+}
+
+pub(crate) fn format_sdf_gradient_declaration(body: &ShaderCode<FunctionBody>, function_name: &FunctionName, buffer: &mut String) {
+    write!(
+        buffer,
+        "fn {name}({common_parameters}) -> {return_type} {{\n{body}\n}}\n",
+        name = function_name,
+        common_parameters = format_common_parameters(),
+        return_type = sdf_conventions::SDF_GRADIENT_RETURN_TYPE,
+        body = body,
+    )
+    .expect("failed to format sdf gradient declaration");
+}
+
 #[must_use]
 pub(crate) fn format_sdf_animation_undo_function_opening() -> String {
     format!(
@@ -114,6 +140,17 @@ mod tests {
         assert_eq!(formatted, expected);
     }
 
+    #[test]
+    fn test_format_sdf_gradient_declaration() {
+        let function_body = ShaderCode::<FunctionBody>::new("return vec4f(1.0, 0.0, 0.0, -7.0);".to_string());
+        let function_name = FunctionName("gradient_of_some_sdf".to_string());
+
+        let mut formatted: String = String::new();
+        format_sdf_gradient_declaration(&function_body, &function_name, &mut formatted);
+
+        assert_eq!(formatted, "fn gradient_of_some_sdf(point: vec3f, time: f32) -> vec4f {\nreturn vec4f(1.0, 0.0, 0.0, -7.0);\n}\n");
+    }
+
     #[test]
     fn test_format_sdf_animation_undo_function_opening() {
         let format = format_sdf_animation_undo_function_opening();
@@ -1,9 +1,10 @@
 use crate::objects::sdf_class_index::SdfClassIndex;
-use crate::sdf::framework::sdf_shader_code::{format_sdf_selection, format_sdf_selection_function_opening};
+use crate::sdf::framework::sdf_shader_code::{format_sdf_gradient_selection_function_opening, format_sdf_selection, format_sdf_selection_function_opening};
 use crate::shader::function_name::FunctionName;
 
 pub(crate) struct SelectionGenerator {
     sdf_selection_uber_function: String,
+    default_value: &'static str,
 }
 
 impl SelectionGenerator {
@@ -11,6 +12,16 @@ impl SelectionGenerator {
     pub(crate) fn new() -> Self {
         Self {
             sdf_selection_uber_function: format_sdf_selection_function_opening(),
+            default_value: "0.0",
+        }
+    }
+
+    // the zero gradient of the classes without the analytic one tells the tracer to use the finite differences
+    #[must_use]
+    pub(crate) fn new_for_gradients() -> Self {
+        Self {
+            sdf_selection_uber_function: format_sdf_gradient_selection_function_opening(),
+            default_value: "vec4f(0.0)",
         }
     }
 
@@ -20,7 +31,7 @@ impl SelectionGenerator {
 
     #[must_use]
     pub(crate) fn make(mut self) -> String {
-        self.sdf_selection_uber_function.push_str("return 0.0;\n}\n");
+        self.sdf_selection_uber_function.push_str(format!("return {};\n}}\n", self.default_value).as_str());
         self.sdf_selection_uber_function
     }
 }
@@ -44,4 +55,12 @@ mod tests {
         let empty_selection = system_under_test.make();
         assert_eq!(empty_selection, "fn sdf_select(sdf_index: i32, point: vec3f, time: f32) -> f32 {\nif (sdf_index == 7) { return a(point,time); }\nif (sdf_index == 5) { return b(point,time); }\nreturn 0.0;\n}\n");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_gradient_selection() {
+        let mut system_under_test = SelectionGenerator::new_for_gradients();
+        system_under_test.add_selection(&FunctionName("a".to_string()), SdfClassIndex(3));
+        let selection = system_under_test.make();
+        assert_eq!(selection, "fn sdf_select_gradient(sdf_index: i32, point: vec3f, time: f32) -> vec4f {\nif (sdf_index == 3) { return a(point,time); }\nreturn vec4f(0.0);\n}\n");
+    }
+}
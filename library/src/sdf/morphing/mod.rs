@@ -1,4 +1,5 @@
 pub mod morphing_swizzle;
 pub mod sdf_bender_along_axis;
+pub mod sdf_displacement;
 pub mod sdf_twister_along_axis;
 mod utils;
\ No newline at end of file
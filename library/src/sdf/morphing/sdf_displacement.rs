@@ -0,0 +1,168 @@
+use crate::geometry::aabb::Aabb;
+use crate::sdf::framework::sdf_base::Sdf;
+use crate::sdf::framework::stack::Stack;
+use crate::shader::code::{FunctionBody, ShaderCode};
+use crate::shader::conventions;
+use crate::shader::formatting_utils::format_scalar;
+use crate::shader::variable_name::VariableName;
+use more_asserts::assert_gt;
+use std::rc::Rc;
+
+// the body evaluates a scalar of the 'point' (and the 'time'), its magnitude is within 'magnitude_bound'
+// and its gradient length within 'slope_bound'; the bounds make the displaced shape safe to ray march
+pub struct DisplacementFunction {
+    body: ShaderCode<FunctionBody>,
+    magnitude_bound: f64,
+    slope_bound: f64,
+}
+
+impl DisplacementFunction {
+    #[must_use]
+    pub fn new(body: ShaderCode<FunctionBody>, magnitude_bound: f64, slope_bound: f64) -> Self {
+        assert_gt!(magnitude_bound, 0.0, "magnitude bound expected to be positive");
+        assert_gt!(slope_bound, 0.0, "slope bound expected to be positive");
+        Self { body, magnitude_bound, slope_bound }
+    }
+
+    // octaves of the value noise (in [-1, 1], the smoothstep interpolated lattice hashes), each of
+    // the double frequency and the half amplitude of the previous one
+    #[must_use]
+    pub fn fbm_value_noise(octaves: u32) -> Self {
+        assert_gt!(octaves, 0, "at least one octave expected");
+        let body = format!("\
+            var sum = 0.0;\n\
+            var weight = 0.5;\n\
+            var position = {point};\n\
+            for (var octave = 0u; octave < {octaves}u; octave++) {{\n\
+            let cell = floor(position);\n\
+            let fraction = fract(position);\n\
+            let blend = fraction*fraction*(3.0-2.0*fraction);\n\
+            var corners: array<f32, 8>;\n\
+            for (var corner = 0u; corner < 8u; corner++) {{\n\
+            let offset = vec3f(f32(corner & 1u), f32((corner >> 1u) & 1u), f32((corner >> 2u) & 1u));\n\
+            corners[corner] = fract(sin(dot(cell+offset, vec3f(127.1, 311.7, 74.7)))*43758.5453)*2.0-1.0;\n\
+            }}\n\
+            let bottom = mix(mix(corners[0], corners[1], blend.x), mix(corners[2], corners[3], blend.x), blend.y);\n\
+            let top = mix(mix(corners[4], corners[5], blend.x), mix(corners[6], corners[7], blend.x), blend.y);\n\
+            sum += weight*mix(bottom, top, blend.z);\n\
+            position = position*2.0+vec3f(19.1, 7.3, 13.7);\n\
+            weight *= 0.5;\n\
+            }}\n\
+            return sum;",
+            point = conventions::PARAMETER_NAME_THE_POINT,
+        );
+        // the smoothstep slope is up to 1.5 over the lattice cell, the hashes differ by up to 2 along
+        // each of the three axes; the octaves halve the amplitude while doubling the frequency
+        let octave_slope_bound = 0.5 * 1.5 * 2.0 * 3.0_f64.sqrt();
+        Self::new(ShaderCode::<FunctionBody>::new(body), 1.0, octave_slope_bound * octaves as f64)
+    }
+}
+
+// adds 'amplitude * function(point * frequency)' to the distance of the target, e.g. for a rough surface
+pub struct SdfDisplacement {
+    target: Rc<dyn Sdf>,
+    function: DisplacementFunction,
+    amplitude: f64,
+    frequency: f64,
+}
+
+impl SdfDisplacement {
+    #[must_use]
+    pub fn new(target: Rc<dyn Sdf>, function: DisplacementFunction, amplitude: f64, frequency: f64) -> Rc<Self> {
+        assert_gt!(amplitude, 0.0, "displacement amplitude expected to be positive");
+        assert_gt!(frequency, 0.0, "displacement frequency expected to be positive");
+        Rc::new(Self { target, function, amplitude, frequency })
+    }
+}
+
+impl Sdf for SdfDisplacement {
+    fn produce_body(&self, children_bodies: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> ShaderCode<FunctionBody> {
+        assert!(children_bodies.size() >= 1);
+
+        let child_name = VariableName::new("operand", level);
+        let child_assignment = children_bodies.pop().to_assignment(&child_name);
+        let displacement_name = VariableName::new("displacement", level);
+        let displacement_assignment = self.function.body.to_assignment(&displacement_name);
+
+        ShaderCode::<FunctionBody>::new(format!(
+            "var {child_name}: f32;\n\
+            {child_assignment}\n\
+            var {displacement_name}: f32;\n\
+            {{\n\
+            let {point} = {point}*{frequency};\n\
+            {displacement_assignment}\n\
+            }}\n\
+            return {child_name}+{amplitude}*{displacement_name};",
+            point = conventions::PARAMETER_NAME_THE_POINT,
+            frequency = format_scalar(self.frequency),
+            amplitude = format_scalar(self.amplitude),
+        ))
+    }
+
+    fn lipschitz_bound(&self) -> f64 {
+        self.target.lipschitz_bound() + self.amplitude * self.frequency * self.function.slope_bound
+    }
+
+    fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
+        vec![self.target.clone()]
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.target.aabb().offset(self.amplitude * self.function.magnitude_bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::geometry::alias::{Point, Vector};
+    use crate::sdf::framework::n_ary_operations_utils::tests::{test_unary_operator_body_production, test_unary_operator_descendants};
+    use crate::sdf::object::sdf_box::SdfBox;
+    use crate::sdf::object::sdf_sphere::SdfSphere;
+    use cgmath::Array;
+
+    #[must_use]
+    fn make_wave_function() -> DisplacementFunction {
+        DisplacementFunction::new(ShaderCode::<FunctionBody>::new("return sin(point.x);".to_string()), 1.0, 1.0)
+    }
+
+    #[test]
+    fn test_children() {
+        test_unary_operator_descendants(|descendant| SdfDisplacement::new(descendant, make_wave_function(), 0.1, 1.0));
+    }
+
+    #[test]
+    fn test_code_generation() {
+        test_unary_operator_body_production(
+            |child| SdfDisplacement::new(child, make_wave_function(), 0.25, 3.0),
+            "var operand_0: f32;\n{\noperand_0 = ?_left;\n}\nvar displacement_0: f32;\n{\nlet point = point*3.0;\n{\ndisplacement_0 = sin(point.x);\n}\n}\nreturn operand_0+0.25*displacement_0;",
+        );
+    }
+
+    #[test]
+    fn test_aabb() {
+        let system_under_test = SdfDisplacement::new(SdfBox::new(Vector::from_value(1.0)), make_wave_function(), 0.5, 2.0);
+
+        let actual_aabb = system_under_test.aabb();
+
+        assert_eq!(actual_aabb, Aabb::from_points(Point::from_value(-1.5), Point::from_value(1.5)));
+    }
+
+    #[test]
+    fn test_lipschitz_bound() {
+        let displaced_once = SdfDisplacement::new(SdfSphere::new(1.0), make_wave_function(), 0.5, 2.0);
+        let system_under_test = SdfDisplacement::new(displaced_once, make_wave_function(), 0.25, 2.0);
+
+        assert_eq!(system_under_test.lipschitz_bound(), 2.5);
+    }
+
+    #[test]
+    fn test_fbm_value_noise_bounds() {
+        let system_under_test = DisplacementFunction::fbm_value_noise(4);
+
+        assert_eq!(system_under_test.magnitude_bound, 1.0);
+        assert_approx_eq!(f64, system_under_test.slope_bound, 4.0 * 1.5 * 3.0_f64.sqrt(), ulps = 2, "slope bound mismatch");
+        assert_eq!(system_under_test.body.as_str().matches("return").count(), 1);
+    }
+}
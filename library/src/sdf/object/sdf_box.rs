@@ -1,7 +1,7 @@
 use crate::geometry::aabb::Aabb;
 use crate::geometry::alias::{Point, Vector};
 use crate::sdf::framework::sdf_base::Sdf;
-use crate::shader::formatting_utils::format_vector;
+use crate::sdf::framework::sdf_parameters::SdfScalar;
 use crate::sdf::framework::stack::Stack;
 use crate::shader::code::{FunctionBody, ShaderCode};
 use crate::shader::conventions;
@@ -9,15 +9,26 @@ use cgmath::EuclideanSpace;
 use std::rc::Rc;
 
 pub struct SdfBox {
-    half_size: Vector,
+    half_size: [SdfScalar; 3],
 }
 
 impl SdfBox {
     #[must_use]
     pub fn new(half_size: Vector) -> Rc<Self> {
-        assert!(half_size.x > 0.0 && half_size.y > 0.0 && half_size.z > 0.0, "half_size must be > 0");
+        Self::new_parametric([half_size.x.into(), half_size.y.into(), half_size.z.into()])
+    }
+
+    #[must_use]
+    pub fn new_parametric(half_size: [SdfScalar; 3]) -> Rc<Self> {
+        assert!(half_size.iter().all(|extent| extent.minimum() > 0.0), "half_size must be > 0");
         Rc::new(Self { half_size, })
     }
+
+    #[must_use]
+    fn format_extent(&self) -> String {
+        let [x, y, z] = &self.half_size;
+        format!("vec3f({},{},{})", x.format(), y.format(), z.format())
+    }
 }
 
 impl Sdf for SdfBox {
@@ -26,22 +37,40 @@ impl Sdf for SdfBox {
             "let q = abs({parameter})-{extent};\n\
             return length(max(q,vec3f(0.0))) + min(max(q.x,max(q.y,q.z)),0.0);",
             parameter = conventions::PARAMETER_NAME_THE_POINT,
-            extent = format_vector(self.half_size),
+            extent = self.format_extent(),
         ))
     }
 
+    // inside, the gradient is the normal of the closest face
+    fn produce_gradient_body(&self, _children_gradients: &mut Stack<ShaderCode<FunctionBody>>, _level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(ShaderCode::<FunctionBody>::new(format!(
+            "let w = abs({parameter})-{extent};\n\
+            let s = select(vec3f(1.0), vec3f(-1.0), {parameter}<vec3f(0.0));\n\
+            let g = max(w.x,max(w.y,w.z));\n\
+            let q = max(w,vec3f(0.0));\n\
+            let l = length(q);\n\
+            let face = select(select(vec3f(0.0,0.0,1.0), vec3f(0.0,1.0,0.0), w.y>w.z), vec3f(1.0,0.0,0.0), w.x>w.y && w.x>w.z);\n\
+            return vec4f(s*select(face, q/l, g>0.0), select(g, l, g>0.0));",
+            parameter = conventions::PARAMETER_NAME_THE_POINT,
+            extent = self.format_extent(),
+        )))
+    }
+
     fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
         Vec::new()
     }
 
     fn aabb(&self) -> Aabb {
-        Aabb::from_points(Point::from_vec(-self.half_size), Point::from_vec(self.half_size))
+        let [x, y, z] = &self.half_size;
+        let extent = Vector::new(x.maximum(), y.maximum(), z.maximum());
+        Aabb::from_points(Point::from_vec(-extent), Point::from_vec(extent))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sdf::framework::sdf_parameters::SdfParametersBuilder;
     use crate::sdf::framework::stack::Stack;
 
     #[test]
@@ -60,4 +89,26 @@ mod tests {
         return length(max(q,vec3f(0.0))) + min(max(q.x,max(q.y,q.z)),0.0);";
         assert_eq!(actual_body.as_str(), expected_body);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_gradient() {
+        let system_under_test = SdfBox::new(Vector::new(1.0,3.0,5.0));
+
+        let actual_body = system_under_test.produce_gradient_body(&mut Stack::new(), Some(0)).unwrap();
+
+        assert!(actual_body.as_str().starts_with("let w = abs(point)-vec3f(1.0,3.0,5.0);\n"));
+        assert!(actual_body.as_str().ends_with("return vec4f(s*select(face, q/l, g>0.0), select(g, l, g>0.0));"));
+    }
+
+    #[test]
+    fn test_parametric() {
+        let mut parameters = SdfParametersBuilder::default();
+        let width = parameters.declare("width", 1.0, 0.5..=2.0);
+        let system_under_test = SdfBox::new_parametric([width, 3.0.into(), 5.0.into()]);
+
+        let actual_body = system_under_test.produce_body(&mut Stack::new(), Some(0));
+
+        assert!(actual_body.as_str().starts_with("let q = abs(point)-vec3f(sdf_parameters.x,3.0,5.0);\n"));
+        assert_eq!(system_under_test.aabb(), Aabb::from_points(Point::new(-2.0, -3.0, -5.0), Point::new(2.0, 3.0, 5.0)));
+    }
+}
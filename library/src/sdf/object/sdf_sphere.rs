@@ -1,7 +1,7 @@
 use crate::geometry::aabb::Aabb;
 use crate::geometry::alias::Point;
 use crate::sdf::framework::sdf_base::Sdf;
-use crate::shader::formatting_utils::format_scalar;
+use crate::sdf::framework::sdf_parameters::SdfScalar;
 use crate::sdf::framework::stack::Stack;
 use crate::shader::code::{FunctionBody, ShaderCode};
 use crate::shader::conventions;
@@ -9,13 +9,18 @@ use cgmath::EuclideanSpace;
 use std::rc::Rc;
 
 pub struct SdfSphere {
-    radius: f64,
+    radius: SdfScalar,
 }
 
 impl SdfSphere {
     #[must_use]
     pub fn new(radius: f64) -> Rc<Self> {
-        assert!(radius > 0.0, "radius must be > 0");
+        Self::new_parametric(SdfScalar::Constant(radius))
+    }
+
+    #[must_use]
+    pub fn new_parametric(radius: SdfScalar) -> Rc<Self> {
+        assert!(radius.minimum() > 0.0, "radius must be > 0");
         Rc::new(Self { radius })
     }
 }
@@ -25,16 +30,26 @@ impl Sdf for SdfSphere {
         ShaderCode::<FunctionBody>::new(format!(
             "return length({parameter})-{radius};",
             parameter = conventions::PARAMETER_NAME_THE_POINT,
-            radius = format_scalar(self.radius),
+            radius = self.radius.format(),
         ))
     }
 
+    fn produce_gradient_body(&self, _children_gradients: &mut Stack<ShaderCode<FunctionBody>>, _level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(ShaderCode::<FunctionBody>::new(format!(
+            "let l = length({parameter});\n\
+            return vec4f({parameter}/max(l,1e-20), l-{radius});",
+            parameter = conventions::PARAMETER_NAME_THE_POINT,
+            radius = self.radius.format(),
+        )))
+    }
+
     fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
         Vec::new()
     }
 
     fn aabb(&self) -> Aabb {
-        let offset = Point::new(self.radius, self.radius, self.radius);
+        let radius = self.radius.maximum();
+        let offset = Point::new(radius, radius, radius);
         Aabb::from_points(Point::from_vec(-offset.to_vec()), offset)
     }
 }
@@ -42,6 +57,7 @@ impl Sdf for SdfSphere {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sdf::framework::sdf_parameters::SdfParametersBuilder;
     use crate::sdf::framework::stack::Stack;
 
     #[test]
@@ -60,4 +76,24 @@ mod tests {
         let expected_body = format!("return length({})-{:.1};", conventions::PARAMETER_NAME_THE_POINT, expected_radius);
         assert_eq!(actual_body.as_str(), expected_body);
     }
+
+    #[test]
+    fn test_gradient() {
+        let system_under_test = SdfSphere::new(7.0);
+
+        let actual_body = system_under_test.produce_gradient_body(&mut Stack::new(), Some(0)).unwrap();
+
+        assert_eq!(actual_body.as_str(), "let l = length(point);\nreturn vec4f(point/max(l,1e-20), l-7.0);");
+    }
+
+    #[test]
+    fn test_parametric() {
+        let mut parameters = SdfParametersBuilder::default();
+        let system_under_test = SdfSphere::new_parametric(parameters.declare("radius", 1.0, 0.5..=3.0));
+
+        let actual_body = system_under_test.produce_body(&mut Stack::new(), Some(0));
+
+        assert_eq!(actual_body.as_str(), "return length(point)-sdf_parameters.x;");
+        assert_eq!(system_under_test.aabb(), Aabb::from_points(Point::new(-3.0, -3.0, -3.0), Point::new(3.0, 3.0, 3.0)));
+    }
 }
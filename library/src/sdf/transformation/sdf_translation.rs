@@ -1,6 +1,6 @@
 use crate::geometry::aabb::Aabb;
 use crate::geometry::alias::Vector;
-use crate::sdf::framework::n_ary_operations_utils::produce_parameter_transform_body;
+use crate::sdf::framework::n_ary_operations_utils::{produce_parameter_transform_body, produce_parameter_transform_gradient_body};
 use crate::sdf::framework::sdf_base::Sdf;
 use crate::shader::formatting_utils::format_vector;
 use crate::sdf::framework::stack::Stack;
@@ -20,14 +20,23 @@ impl SdfTranslation {
     }
 }
 
+impl SdfTranslation {
+    #[must_use]
+    fn format_translation(&self) -> String {
+        format!("let {parameter} = {parameter}-{center};",
+                parameter = conventions::PARAMETER_NAME_THE_POINT,
+                center = format_vector(self.translation)
+        )
+    }
+}
+
 impl Sdf for SdfTranslation {
     fn produce_body(&self, children_bodies: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> ShaderCode<FunctionBody> {
-        produce_parameter_transform_body(children_bodies, level, || 
-            format!("let {parameter} = {parameter}-{center};",
-                    parameter = conventions::PARAMETER_NAME_THE_POINT,
-                    center = format_vector(self.translation)
-            )
-        )
+        produce_parameter_transform_body(children_bodies, level, || self.format_translation())
+    }
+
+    fn produce_gradient_body(&self, children_gradients: &mut Stack<ShaderCode<FunctionBody>>, level: Option<usize>) -> Option<ShaderCode<FunctionBody>> {
+        Some(produce_parameter_transform_gradient_body(children_gradients, level, || self.format_translation()))
     }
 
     fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
@@ -42,7 +51,7 @@ impl Sdf for SdfTranslation {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sdf::framework::n_ary_operations_utils::tests::{test_unary_operator_body_production, test_unary_operator_descendants};
+    use crate::sdf::framework::n_ary_operations_utils::tests::{test_unary_operator_body_production, test_unary_operator_descendants, test_unary_operator_gradient_production};
     use cgmath::Zero;
 
     #[test]
@@ -58,4 +67,13 @@ mod tests {
             expected_body,
         );
     }
+
+    #[test]
+    fn test_produce_gradient_body() {
+        let expected_body = "var operand_0: vec4f;\n{\nlet point = point-vec3f(1.0,2.0,3.0);\n{\noperand_0 = ?_left;\n}\n}\nreturn operand_0;";
+        test_unary_operator_gradient_production(
+            |descendant| SdfTranslation::new(Vector::new(1.0, 2.0, 3.0), descendant),
+            expected_body,
+        );
+    }
 }
\ No newline at end of file
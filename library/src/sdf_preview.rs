@@ -0,0 +1,326 @@
+use crate::container::visual_objects::VisualObjects;
+use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::{Point, Vector};
+use crate::geometry::epsilon::DEFAULT_EPSILON_F32;
+use crate::geometry::transform::Affine;
+use crate::gpu::compute_pipeline::ComputePipeline;
+use crate::gpu::context::Context;
+use crate::gpu::frame_buffer_size::FrameBufferSize;
+use crate::gpu::output::duplex_layer::DuplexLayer;
+use crate::gpu::output::frame_buffer_layer::SupportUpdateFromCpu;
+use crate::gpu::pipeline_code::PipelineCode;
+use crate::gpu::pipelines_factory::{ComputeRoutineEntryPoint, PipelinesFactory};
+use crate::gpu::resources::Resources;
+use crate::material::material_properties::MaterialProperties;
+use crate::scene::camera::Camera;
+use crate::sdf::framework::named_sdf::NamedSdf;
+use crate::sdf::framework::sdf_code_generator::SdfCodeGenerator;
+use crate::sdf::framework::sdf_registrator::SdfRegistrator;
+use crate::{EngineInstantiationError, OffscreenEngine, OFFSCREEN_OUTPUT_FORMAT};
+use cgmath::{Deg, InnerSpace, Rad, SquareMatrix};
+use std::f64::consts::TAU;
+use std::rc::Rc;
+
+#[cfg(feature = "gpu_testing")]
+use crate::HeadlessContext;
+
+// thumbnails and sanity checks for sdf classes: the class is rendered alone under a fixed overhead light,
+// the camera orbits around the declared aabb; the same aabb is probed with the distance field itself
+pub struct SdfPreview {
+    context: Rc<Context>,
+    engine: OffscreenEngine,
+    class: NamedSdf,
+}
+
+impl SdfPreview {
+    pub const DEFAULT_SIZE: u32 = 128;
+    pub const DEFAULT_TURNTABLE_FRAMES: usize = 8;
+    pub const DEFAULT_SAMPLES_PER_SIDE: usize = 32;
+
+    const CAMERA_ELEVATION: Deg<f64> = Deg(20.0);
+    const CAMERA_DISTANCE_MARGIN: f64 = 1.2;
+    const LIGHT_SIDE_IN_RADII: f64 = 4.0;
+    const LIGHT_HEIGHT_IN_RADII: f64 = 3.0;
+    const LIGHT_EMISSION: f32 = 4.0;
+
+    const SAMPLES_WORKGROUP_SIZE: usize = 64;
+    const SAMPLING_CODE_TEMPLATE: &'static str = r#"
+@group(0) @binding(0) var<storage, read> positions: array<vec4f>;
+@group(0) @binding(1) var<storage, read_write> distances: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn compute_sdf_samples(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= arrayLength(&positions)) {
+        return;
+    }
+    distances[index] = FUNCTION_NAME(positions[index].xyz, 0.0);
+}
+"#;
+
+    pub async fn new(size: u32, class: &NamedSdf) -> Result<SdfPreview, EngineInstantiationError> {
+        let context = OffscreenEngine::create_context().await?;
+        Self::with_context(context, size, class)
+    }
+
+    // renders on the device of the test context instead of a private one
+    #[cfg(feature = "gpu_testing")]
+    pub fn with_headless_context(context: &HeadlessContext, size: u32, class: &NamedSdf) -> Result<SdfPreview, EngineInstantiationError> {
+        Self::with_context(context.context(), size, class)
+    }
+
+    fn with_context(context: Rc<Context>, size: u32, class: &NamedSdf) -> Result<SdfPreview, EngineInstantiationError> {
+        assert!(size > 0, "preview must not be empty");
+        let scene = Self::make_stage(class);
+        let engine = OffscreenEngine::with_context(context.clone(), size, size, scene, Camera::new_perspective_camera(1.0, Point::new(0.0, 0.0, 0.0)), None)?;
+        Ok(Self { context, engine, class: class.clone() })
+    }
+
+    #[must_use]
+    fn bounding_radius(aabb: &Aabb) -> f64 {
+        (aabb.extent().magnitude() / 2.0).max(f64::from(DEFAULT_EPSILON_F32))
+    }
+
+    #[must_use]
+    fn make_stage(class: &NamedSdf) -> VisualObjects {
+        let aabb = class.sdf().aabb();
+        let radius = Self::bounding_radius(&aabb);
+        let center = aabb.center();
+
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let surface = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(0.8, 0.8, 0.8));
+        let light = scene.materials_mutable().add(&MaterialProperties::new().with_albedo(1.0, 1.0, 1.0)
+            .with_emission(Self::LIGHT_EMISSION, Self::LIGHT_EMISSION, Self::LIGHT_EMISSION));
+
+        // facing down, so the light is the same for every turntable angle
+        let light_side = Self::LIGHT_SIDE_IN_RADII * radius;
+        let light_origin = center + Vector::new(-light_side / 2.0, Self::LIGHT_HEIGHT_IN_RADII * radius, -light_side / 2.0);
+        let _ = scene.add_parallelogram(light_origin, Vector::new(light_side, 0.0, 0.0), Vector::new(0.0, 0.0, light_side), light);
+
+        scene.add_sdf_class(class);
+        let _ = scene.add_sdf(&Affine::identity(), 1.0, class.name(), surface);
+
+        scene
+    }
+
+    #[must_use]
+    pub fn size(&self) -> u32 {
+        self.engine.width()
+    }
+
+    // returns 'frames' images evenly spread over a full circle around the vertical axis; each one
+    // is 'size * size' pixels, 4 bytes (RGBA) each, rows go from top to bottom
+    #[must_use]
+    pub fn render_turntable(&mut self, frames: usize) -> Vec<Vec<u8>> {
+        assert!(frames > 0, "at least one turntable frame expected");
+
+        let aabb = self.class.sdf().aabb();
+        let center = aabb.center();
+        let half_fov: Rad<f64> = (self.engine.camera().fov() / 2.0).into();
+        let distance = Self::bounding_radius(&aabb) / half_fov.0.sin() * Self::CAMERA_DISTANCE_MARGIN;
+        let elevation: Rad<f64> = Self::CAMERA_ELEVATION.into();
+
+        (0..frames).map(|frame| {
+            let azimuth = TAU * frame as f64 / frames as f64;
+            let direction = Vector::new(azimuth.sin() * elevation.0.cos(), elevation.0.sin(), azimuth.cos() * elevation.0.cos());
+            self.engine.camera().set_view(center + direction * distance, center);
+            self.engine.render(1)
+        }).collect()
+    }
+
+    // probes a 'samples_per_side' square grid on every face of the declared aabb: a negative distance
+    // there means the surface leaks out of the box and gets clipped (or glitches) during rendering
+    #[must_use]
+    pub fn validate_aabb(&self, samples_per_side: usize) -> AabbValidation {
+        assert!(samples_per_side > 1, "at least two samples per side expected");
+
+        let aabb = self.class.sdf().aabb();
+        let positions = Self::sample_aabb_faces(&aabb, samples_per_side);
+        let distances = self.evaluate_distances(&positions);
+
+        let violations = positions.iter().zip(distances.iter())
+            .filter(|(_, distance)| **distance < -DEFAULT_EPSILON_F32)
+            .map(|(position, distance)| AabbViolation { position: *position, distance: f64::from(*distance) })
+            .collect();
+
+        AabbValidation { aabb, samples_count: positions.len(), violations }
+    }
+
+    #[must_use]
+    fn sample_aabb_faces(aabb: &Aabb, samples_per_side: usize) -> Vec<Point> {
+        let (min, max) = (aabb.min(), aabb.max());
+        let step = 1.0 / (samples_per_side - 1) as f64;
+
+        let mut result = Vec::with_capacity(6 * samples_per_side * samples_per_side);
+        for axis in 0..3 {
+            let (first, second) = ((axis + 1) % 3, (axis + 2) % 3);
+            for face in [min[axis], max[axis]] {
+                for i in 0..samples_per_side {
+                    for j in 0..samples_per_side {
+                        let mut sample = Point::new(0.0, 0.0, 0.0);
+                        sample[axis] = face;
+                        sample[first] = min[first] + (max[first] - min[first]) * (i as f64 * step);
+                        sample[second] = min[second] + (max[second] - min[second]) * (j as f64 * step);
+                        result.push(sample);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    #[must_use]
+    fn evaluate_distances(&self, positions: &[Point]) -> Vec<f32> {
+        let mut registrator = SdfRegistrator::new();
+        registrator.add(&self.class);
+        let generator = SdfCodeGenerator::new(registrator);
+
+        let mut sdf_code = String::new();
+        let function_name = generator.generate_unique_code_for(&self.class, &mut sdf_code);
+        generator.generate_shared_code(&mut sdf_code);
+        let gpu_code = Self::SAMPLING_CODE_TEMPLATE.replace("FUNCTION_NAME", function_name.0.as_str()) + sdf_code.as_str();
+
+        let device = self.context.device();
+        let resources = Resources::new(self.context.clone());
+        let module = resources.create_shader_module("sdf samples evaluation", &gpu_code);
+        let code = PipelineCode::new(module, seahash::hash(gpu_code.as_bytes()), "sdf_samples".to_string());
+
+        let gpu_positions: Vec<[f32; 4]> = positions.iter().map(|p| [p.x as f32, p.y as f32, p.z as f32, 0.0]).collect();
+        let positions_buffer = resources.create_storage_buffer_write_only("sdf sample positions", bytemuck::cast_slice(&gpu_positions));
+        let mut distances = DuplexLayer::<f32>::new(device, FrameBufferSize::new(positions.len() as u32, 1), SupportUpdateFromCpu::Yes, "sdf sample distances");
+
+        let mut pipelines_factory = PipelinesFactory::new(self.context.clone(), OFFSCREEN_OUTPUT_FORMAT, None);
+        let mut pipeline = ComputePipeline::new(pipelines_factory.create_compute_pipeline(ComputeRoutineEntryPoint::SdfSamples, &code));
+        pipeline.setup_bind_group(0, Some("sdf samples"), device, |bind_group| {
+            bind_group.set_storage_entry(0, positions_buffer.clone());
+            bind_group.set_storage_entry(1, distances.gpu_copy());
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pipeline.set_into_pass(&mut pass);
+            pass.dispatch_workgroups(positions.len().div_ceil(Self::SAMPLES_WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        distances.prepare_cpu_read(&mut encoder);
+        self.context.queue().submit(Some(encoder.finish()));
+
+        distances.request_cpu_read();
+        self.context.wait(None);
+        let _ = distances.collect_cpu_read();
+
+        distances.cpu_copy().clone()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AabbViolation {
+    pub position: Point,
+    pub distance: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct AabbValidation {
+    aabb: Aabb,
+    samples_count: usize,
+    violations: Vec<AabbViolation>,
+}
+
+impl AabbValidation {
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    #[must_use]
+    pub fn aabb(&self) -> Aabb {
+        self.aabb
+    }
+
+    #[must_use]
+    pub fn samples_count(&self) -> usize {
+        self.samples_count
+    }
+
+    #[must_use]
+    pub fn violations(&self) -> &[AabbViolation] {
+        &self.violations
+    }
+
+    // the sample deepest inside the surface: the aabb is short by about that much around the position
+    #[must_use]
+    pub fn worst_violation(&self) -> Option<AabbViolation> {
+        self.violations.iter().copied().min_by(|left, right| left.distance.total_cmp(&right.distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
+    use crate::sdf::framework::named_sdf::UniqueSdfClassName;
+    use crate::sdf::framework::sdf_base::Sdf;
+    use crate::sdf::framework::stack::Stack;
+    use crate::sdf::object::sdf_box::SdfBox;
+    use crate::sdf::object::sdf_sphere::SdfSphere;
+    use crate::shader::code::{FunctionBody, ShaderCode};
+    use crate::shader::conventions;
+
+    const TEST_SIZE: u32 = 32;
+    const TEST_SAMPLES_PER_SIDE: usize = 8;
+
+    struct SphereWithShrunkAabb;
+
+    impl Sdf for SphereWithShrunkAabb {
+        fn produce_body(&self, _children_bodies: &mut Stack<ShaderCode<FunctionBody>>, _level: Option<usize>) -> ShaderCode<FunctionBody> {
+            ShaderCode::<FunctionBody>::new(format!("return length({parameter})-1.0;", parameter = conventions::PARAMETER_NAME_THE_POINT))
+        }
+
+        fn descendants(&self) -> Vec<Rc<dyn Sdf>> {
+            Vec::new()
+        }
+
+        fn aabb(&self) -> Aabb {
+            Aabb::from_points(Point::new(-0.5, -0.5, -0.5), Point::new(0.5, 0.5, 0.5))
+        }
+    }
+
+    #[must_use]
+    fn make_system_under_test(sdf: Rc<dyn Sdf>, name: &str) -> SdfPreview {
+        let class = NamedSdf::new(sdf, UniqueSdfClassName::new(name.to_string()));
+        SdfPreview::with_context(create_headless_wgpu_vulkan_context(), TEST_SIZE, &class).unwrap()
+    }
+
+    #[test]
+    fn test_honest_aabb_is_valid() {
+        let system_under_test = make_system_under_test(SdfBox::new(Vector::new(1.0, 2.0, 3.0)), "honest_box");
+
+        let validation = system_under_test.validate_aabb(TEST_SAMPLES_PER_SIDE);
+
+        assert!(validation.is_valid(), "unexpected violations: {:?}", validation.violations());
+        assert_eq!(validation.samples_count(), 6 * TEST_SAMPLES_PER_SIDE * TEST_SAMPLES_PER_SIDE);
+    }
+
+    #[test]
+    fn test_shrunk_aabb_is_caught() {
+        let system_under_test = make_system_under_test(Rc::new(SphereWithShrunkAabb), "shrunk_sphere");
+
+        let validation = system_under_test.validate_aabb(TEST_SAMPLES_PER_SIDE);
+
+        assert!(!validation.is_valid());
+        let worst = validation.worst_violation().unwrap();
+        assert!((worst.distance + 0.5).abs() < 1e-4, "face centers are half a unit deep, got {}", worst.distance);
+    }
+
+    #[test]
+    fn test_turntable_frames() {
+        let mut system_under_test = make_system_under_test(SdfSphere::new(1.0), "turntable_sphere");
+
+        let frames = system_under_test.render_turntable(3);
+
+        assert_eq!(frames.len(), 3);
+        for frame in frames {
+            assert_eq!(frame.len(), (TEST_SIZE * TEST_SIZE * 4) as usize);
+        }
+    }
+}
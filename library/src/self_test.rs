@@ -0,0 +1,226 @@
+use crate::container::visual_objects::VisualObjects;
+use crate::geometry::alias::{Point, Vector};
+use crate::gpu::context::Context;
+use crate::material::material_index::MaterialIndex;
+use crate::material::material_properties::MaterialProperties;
+use crate::scene::camera::Camera;
+use crate::scene::display_mapping::{DisplayMapping, ToneMapping};
+use crate::scene::render_strategy::RenderStrategyId;
+use crate::utils::object_uid::ObjectUid;
+use crate::{EngineInstantiationError, OffscreenEngine};
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "gpu_testing")]
+use crate::HeadlessContext;
+
+// the color a probed pixel of the self test image is to have
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelfTestCheck {
+    name: String,
+    expected: [u8; 3],
+    actual: [u8; 3],
+}
+
+impl SelfTestCheck {
+    // per channel, of the 8 bit output
+    pub const TOLERANCE: u8 = 8;
+
+    #[must_use]
+    pub(crate) fn new(name: String, expected: [u8; 3], actual: [u8; 3]) -> Self {
+        Self { name, expected, actual }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn expected(&self) -> [u8; 3] {
+        self.expected
+    }
+
+    #[must_use]
+    pub fn actual(&self) -> [u8; 3] {
+        self.actual
+    }
+
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.expected.iter().zip(self.actual.iter()).all(|(expected, actual)| expected.abs_diff(*actual) <= Self::TOLERANCE)
+    }
+}
+
+// what the self test found on the adapter: the image checks and the timings; the text form is for bug reports
+#[derive(Clone, Debug)]
+pub struct SelfTestReport {
+    adapter: wgpu::AdapterInfo,
+    engine_creation_time: Duration,
+    first_frame_time: Duration,
+    average_frame_time: Duration,
+    checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    #[must_use]
+    pub fn adapter(&self) -> &wgpu::AdapterInfo {
+        &self.adapter
+    }
+
+    // the buffers and the pipelines (compiled or found in the caches) of the test scene
+    #[must_use]
+    pub fn engine_creation_time(&self) -> Duration {
+        self.engine_creation_time
+    }
+
+    // with the read back into memory, as are the other frames
+    #[must_use]
+    pub fn first_frame_time(&self) -> Duration {
+        self.first_frame_time
+    }
+
+    #[must_use]
+    pub fn average_frame_time(&self) -> Duration {
+        self.average_frame_time
+    }
+
+    #[must_use]
+    pub fn checks(&self) -> &[SelfTestCheck] {
+        &self.checks
+    }
+
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(SelfTestCheck::passed)
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "self test {}", if self.passed() { "passed" } else { "FAILED" })?;
+        writeln!(formatter, "adapter: {} ({:?}, {:?}), driver: {} {}", self.adapter.name, self.adapter.device_type, self.adapter.backend, self.adapter.driver, self.adapter.driver_info)?;
+        writeln!(formatter, "engine creation: {:?}, first frame: {:?}, average frame: {:?}", self.engine_creation_time, self.first_frame_time, self.average_frame_time)?;
+        for check in &self.checks {
+            let verdict = if check.passed() { "ok" } else { "mismatch" };
+            writeln!(formatter, "{}: expected {:?}, got {:?} — {verdict}", check.name, check.expected, check.actual)?;
+        }
+        Ok(())
+    }
+}
+
+// renders a tiny built-in scene into memory on the given adapter: a plane filling the view with an emissive
+// material of known color, shown without the tone mapping and the gamma, so that the output is the emission
+// itself; the probes are spread over the image, the materials are switched between the frames
+pub struct SelfTest;
+
+impl SelfTest {
+    const IMAGE_SIDE: u32 = 32;
+    const TIMED_FRAMES: u32 = 8;
+    const PLANE_HALF_SIDE: f64 = 100.0;
+    const EMISSIONS: [[f32; 3]; 2] = [[0.75, 0.25, 0.5], [0.1, 0.6, 0.9]];
+    const PROBES: [(&'static str, u32, u32); 5] = [
+        ("center", Self::IMAGE_SIDE / 2, Self::IMAGE_SIDE / 2),
+        ("top left", Self::IMAGE_SIDE / 4, Self::IMAGE_SIDE / 4),
+        ("top right", Self::IMAGE_SIDE * 3 / 4, Self::IMAGE_SIDE / 4),
+        ("bottom left", Self::IMAGE_SIDE / 4, Self::IMAGE_SIDE * 3 / 4),
+        ("bottom right", Self::IMAGE_SIDE * 3 / 4, Self::IMAGE_SIDE * 3 / 4),
+    ];
+
+    // on a device of its own, for the checks at the install time
+    pub async fn run() -> Result<SelfTestReport, EngineInstantiationError> {
+        let context = OffscreenEngine::create_context().await?;
+        Self::run_on(context)
+    }
+
+    // renders on the device of the test context instead of a private one
+    #[cfg(feature = "gpu_testing")]
+    pub fn run_with_headless_context(context: &HeadlessContext) -> Result<SelfTestReport, EngineInstantiationError> {
+        Self::run_on(context.context())
+    }
+
+    pub(crate) fn run_on(context: Rc<Context>) -> Result<SelfTestReport, EngineInstantiationError> {
+        let adapter = context.adapter_info().clone();
+        let (scene, plane, materials) = Self::make_stage();
+
+        let started = Instant::now();
+        let mut engine = OffscreenEngine::with_context(context, Self::IMAGE_SIDE, Self::IMAGE_SIDE, scene, Camera::new_orthographic_camera(1.0, Point::new(0.0, 0.0, 0.0)), None)?;
+        engine.set_display_mapping(DisplayMapping::new().with_tone_mapping(ToneMapping::None).with_gamma(1.0));
+        let engine_creation_time = started.elapsed();
+
+        let mut checks = Vec::new();
+        let mut first_frame_time = Duration::ZERO;
+        for (strategy, strategy_name) in Self::strategies() {
+            engine.controls.renderer.set_render_strategy(strategy);
+            for (material, emission) in materials.iter().zip(Self::EMISSIONS) {
+                engine.objects().set_material(plane, *material);
+                let started = Instant::now();
+                let pixels = engine.render(1);
+                if first_frame_time.is_zero() {
+                    first_frame_time = started.elapsed();
+                }
+                let expected = emission.map(|channel| (channel * 255.0).round() as u8);
+                checks.extend(Self::PROBES.iter().map(|(probe, x, y)| {
+                    SelfTestCheck::new(format!("{strategy_name}, emission {emission:?}, {probe}"), expected, Self::pixel_at(&pixels, *x, *y))
+                }));
+            }
+        }
+
+        let started = Instant::now();
+        for _ in 0..Self::TIMED_FRAMES {
+            let _ = engine.render(1);
+        }
+        let average_frame_time = started.elapsed() / Self::TIMED_FRAMES;
+
+        Ok(SelfTestReport { adapter, engine_creation_time, first_frame_time, average_frame_time, checks })
+    }
+
+    #[must_use]
+    fn strategies() -> Vec<(RenderStrategyId, &'static str)> {
+        vec![
+            (RenderStrategyId::Deterministic, "deterministic"),
+            #[cfg(feature = "monte_carlo")]
+            (RenderStrategyId::MonteCarlo, "monte carlo"),
+        ]
+    }
+
+    // a black, non-specular surface shows its emission only
+    #[must_use]
+    fn make_stage() -> (VisualObjects, ObjectUid, Vec<MaterialIndex>) {
+        let mut scene = VisualObjects::new(None, None, None, None, None);
+        let materials: Vec<MaterialIndex> = Self::EMISSIONS.iter()
+            .map(|[r, g, b]| scene.materials_mutable().add(&MaterialProperties::new().with_albedo(0.0, 0.0, 0.0).with_emission(*r, *g, *b)))
+            .collect();
+        let side = Self::PLANE_HALF_SIDE * 2.0;
+        let plane = scene.add_parallelogram(Point::new(-Self::PLANE_HALF_SIDE, -Self::PLANE_HALF_SIDE, 0.0), Vector::new(side, 0.0, 0.0), Vector::new(0.0, side, 0.0), materials[0]);
+        (scene, plane, materials)
+    }
+
+    #[must_use]
+    fn pixel_at(pixels: &[u8], x: u32, y: u32) -> [u8; 3] {
+        let offset = ((y * Self::IMAGE_SIDE + x) * 4) as usize;
+        [pixels[offset], pixels[offset + 1], pixels[offset + 2]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
+
+    #[test]
+    fn test_check_tolerance() {
+        assert!(SelfTestCheck::new("exact".to_string(), [10, 20, 30], [10, 20, 30]).passed());
+        assert!(SelfTestCheck::new("within".to_string(), [10, 20, 30], [10 + SelfTestCheck::TOLERANCE, 20, 30 - SelfTestCheck::TOLERANCE]).passed());
+        assert!(false == SelfTestCheck::new("beyond".to_string(), [10, 20, 30], [10, 21 + SelfTestCheck::TOLERANCE, 30]).passed());
+    }
+
+    #[test]
+    fn test_self_test_passes() {
+        let system_under_test = SelfTest::run_on(create_headless_wgpu_vulkan_context()).unwrap();
+
+        assert!(system_under_test.passed(), "{system_under_test}");
+        assert_eq!(system_under_test.checks().len(), SelfTest::strategies().len() * SelfTest::EMISSIONS.len() * SelfTest::PROBES.len());
+    }
+}
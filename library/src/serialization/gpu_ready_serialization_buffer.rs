@@ -1,4 +1,5 @@
 use std::vec;
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator, ParallelSlice, ParallelSliceMut};
 use crate::serialization::single_object_writer::SingleObjectWriter;
 use crate::serialization::single_quartet_writer::SingleQuartetWriter;
 
@@ -45,13 +46,25 @@ impl GpuReadySerializationBuffer {
         result
     }
     
+    // adopts previously serialized data; none if it does not consist of whole objects
+    #[must_use]
+    pub(crate) fn from_backend(backend: Vec<u8>, quartets_per_object: usize) -> Option<Self> {
+        assert!(quartets_per_object > 0);
+        let bytes_per_object = quartets_per_object * QUARTET_SIZE_BYTES;
+        if backend.is_empty() || 0 != backend.len() % bytes_per_object {
+            return None;
+        }
+        let write_pointer = backend.len();
+        Some(Self { backend, write_pointer, quartets_per_object })
+    }
+
     #[must_use]
     fn backend_size_bytes(objects_count_capacity: usize, quartets_per_object: usize) -> usize {
         objects_count_capacity * quartets_per_object * QUARTET_SIZE_BYTES
     }
 
     #[must_use]
-    fn bytes_per_object(&self) -> usize {
+    pub(crate) fn bytes_per_object(&self) -> usize {
         self.quartets_per_object * QUARTET_SIZE_BYTES
     }
 
@@ -98,6 +111,27 @@ impl GpuReadySerializationBuffer {
         assert!(writer.fully_written());
     }
 
+    // fills the empty buffer: an object per slot, the batches of 'batch_size' objects are serialized in parallel,
+    // each into a scratch buffer of its own, then copied into the region of the batch
+    pub(crate) fn write_batches_in_parallel<Object, WritingCode>(&mut self, objects: &[Object], batch_size: usize, write_object: WritingCode)
+    where
+        Object: Sync,
+        WritingCode: Fn(&Object, &mut GpuReadySerializationBuffer) + Sync,
+    {
+        assert_eq!(self.write_pointer, 0, "buffer has been written already");
+        assert_eq!(objects.len(), self.total_slots_count());
+        assert!(batch_size > 0);
+
+        let quartets_per_object = self.quartets_per_object;
+        let region_size_bytes = batch_size * self.bytes_per_object();
+        self.backend.par_chunks_mut(region_size_bytes).zip(objects.par_chunks(batch_size)).for_each(|(region, batch)| {
+            let mut scratch = Self::new(batch.len(), quartets_per_object);
+            batch.iter().for_each(|object| write_object(object, &mut scratch));
+            region.copy_from_slice(scratch.backend());
+        });
+        self.write_pointer = self.backend.len();
+    }
+
     pub(crate) fn write_quartet_f64(&mut self, x: f64, y: f64, z: f64, w: f64) {
         self.write_quartet_f32(x as f32, y as f32, z as f32, w as f32);
     }
@@ -112,10 +146,6 @@ impl GpuReadySerializationBuffer {
         });
     }
 
-    pub(crate) fn write_padded_quartet_f32(&mut self, x: f32, y: f32, z: f32) {
-        self.write_quartet_f32(x, y, z, DEFAULT_PAD_VALUE);
-    }
-
     pub(crate) fn write_quartet<WritingCode>(&mut self, execute_writing: WritingCode)
     where
         WritingCode: FnOnce(&mut SingleQuartetWriter),
@@ -146,6 +176,38 @@ mod tests {
         let _ = GpuReadySerializationBuffer::new(1, 0);
     }
 
+    #[test]
+    fn test_from_backend() {
+        let quartets_per_object = 2;
+        let whole = vec![7_u8; 2 * quartets_per_object * QUARTET_SIZE_BYTES];
+
+        let system_under_test = GpuReadySerializationBuffer::from_backend(whole.clone(), quartets_per_object).unwrap();
+
+        assert!(system_under_test.fully_written());
+        assert_eq!(system_under_test.total_slots_count(), 2);
+        assert_eq!(system_under_test.backend(), &whole);
+        assert!(GpuReadySerializationBuffer::from_backend(vec![7_u8; QUARTET_SIZE_BYTES], quartets_per_object).is_none());
+        assert!(GpuReadySerializationBuffer::from_backend(Vec::new(), quartets_per_object).is_none());
+    }
+
+    #[test]
+    fn test_write_batches_in_parallel_matches_sequential_writing() {
+        let quartets_per_object = 2;
+        let objects: Vec<f32> = (0..7).map(|object| object as f32).collect();
+        let write_object = |object: &f32, buffer: &mut GpuReadySerializationBuffer| {
+            buffer.write_quartet_f32(*object, 1.0, 2.0, 3.0);
+            buffer.write_quartet_f32(4.0, 5.0, 6.0, -*object);
+        };
+        let mut expected = GpuReadySerializationBuffer::new(objects.len(), quartets_per_object);
+        objects.iter().for_each(|object| write_object(object, &mut expected));
+
+        let mut system_under_test = GpuReadySerializationBuffer::new(objects.len(), quartets_per_object);
+        system_under_test.write_batches_in_parallel(&objects, 3, write_object);
+
+        assert!(system_under_test.fully_written());
+        assert_eq!(system_under_test.backend(), expected.backend());
+    }
+
     #[test]
     fn test_write_quartet() {
         let expected_quartets_per_object = 2;
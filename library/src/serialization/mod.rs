@@ -2,5 +2,6 @@ pub(crate) mod serializable_for_gpu;
 pub(crate) mod serialize_matrix;
 pub(crate) mod gpu_ready_serialization_buffer;
 pub(crate) mod pod_vector;
+pub(crate) mod quantization;
 mod single_object_writer;
 mod single_quartet_writer;
\ No newline at end of file
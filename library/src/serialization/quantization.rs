@@ -0,0 +1,168 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::alias::{Point, Vector};
+use crate::serialization::gpu_ready_serialization_buffer::{GpuReadySerializationBuffer, DEFAULT_PAD_VALUE};
+use cgmath::{EuclideanSpace, InnerSpace};
+
+const GRID_MAX: f64 = u16::MAX as f64;
+const FRAME_MARGIN_ULPS: f64 = 16.0;
+const NORMAL_COMPONENT_BITS: u32 = 10;
+const NORMAL_COMPONENT_MAX: f64 = ((1 << NORMAL_COMPONENT_BITS) - 1) as f64;
+
+// maps the points of a box onto the 16 bit integer grid spanning it; the gpu
+// restores a point as 'origin + grid * step', so the frame is serialized that way
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct QuantizationFrame {
+    origin: Point,
+    step: Vector,
+}
+
+impl QuantizationFrame {
+    pub(crate) const SERIALIZED_QUARTET_COUNT: usize = 2;
+
+    // the gpu restores the points in f32, so the box is widened by a few f32 ulps of its coordinates:
+    // the grid has to cover it after the rounding of the origin and the step
+    #[must_use]
+    pub(crate) fn new(aabb: &Aabb) -> Self {
+        let magnitude = [aabb.min().x, aabb.min().y, aabb.min().z, aabb.max().x, aabb.max().y, aabb.max().z]
+            .into_iter().fold(0.0, |record: f64, coordinate| record.max(coordinate.abs()));
+        let margin = magnitude * FRAME_MARGIN_ULPS * f32::EPSILON as f64;
+        let margin = Vector::new(margin, margin, margin);
+        Self { origin: aabb.min() - margin, step: (aabb.extent() + margin * 2.0) / GRID_MAX }
+    }
+
+    // the restored point is not farther than the half of the step from the original
+    #[must_use]
+    pub(crate) fn quantize_nearest(&self, point: Point) -> [u16; 3] {
+        self.grid_position(point, f64::round)
+    }
+
+    // 'quantize_down' of the box minimum and 'quantize_up' of its maximum give a box containing the original one;
+    // the extra step absorbs the rounding of the f32 restoration on the gpu
+    #[must_use]
+    pub(crate) fn quantize_down(&self, point: Point) -> [u16; 3] {
+        self.grid_position(point, |coordinate| coordinate.floor() - 1.0)
+    }
+
+    #[must_use]
+    pub(crate) fn quantize_up(&self, point: Point) -> [u16; 3] {
+        self.grid_position(point, |coordinate| coordinate.ceil() + 1.0)
+    }
+
+    #[cfg(test)]
+    #[must_use]
+    pub(crate) fn restore(&self, grid: [u16; 3]) -> Point {
+        Point::new(
+            self.origin.x + grid[0] as f64 * self.step.x,
+            self.origin.y + grid[1] as f64 * self.step.y,
+            self.origin.z + grid[2] as f64 * self.step.z,
+        )
+    }
+
+    #[must_use]
+    fn grid_position(&self, point: Point, round: impl Fn(f64) -> f64) -> [u16; 3] {
+        let offset = point - self.origin;
+        [(offset.x, self.step.x), (offset.y, self.step.y), (offset.z, self.step.z)].map(|(offset, step)| {
+            if 0.0 == step {
+                return 0;
+            }
+            round(offset / step).clamp(0.0, GRID_MAX) as u16
+        })
+    }
+
+    pub(crate) fn serialize_into(&self, container: &mut GpuReadySerializationBuffer) {
+        container.write_padded_quartet_f64(self.origin.x, self.origin.y, self.origin.z);
+        container.write_padded_quartet_f64(self.step.x, self.step.y, self.step.z);
+    }
+
+    pub(crate) fn serialize_as_object_into(&self, container: &mut GpuReadySerializationBuffer, index: usize) {
+        container.write_object(index, |writer| {
+            for vector in [self.origin.to_vec(), self.step] {
+                writer.write_quartet(|writer| {
+                    writer.write_float_64(vector.x).write_float_64(vector.y).write_float_64(vector.z).write_float_32(DEFAULT_PAD_VALUE);
+                });
+            }
+        });
+    }
+}
+
+#[must_use]
+pub(crate) fn pack_unorm16_pair(low: u16, high: u16) -> u32 {
+    low as u32 | ((high as u32) << 16)
+}
+
+// ten bits per component, mapped from [-1, 1]; the gpu re-normalizes the restored vector
+#[must_use]
+pub(crate) fn pack_normal(normal: Vector) -> u32 {
+    let normalized = normal.normalize();
+    let mut result = 0_u32;
+    for (index, component) in [normalized.x, normalized.y, normalized.z].into_iter().enumerate() {
+        let bits = ((component * 0.5 + 0.5) * NORMAL_COMPONENT_MAX).round().clamp(0.0, NORMAL_COMPONENT_MAX) as u32;
+        result |= bits << (NORMAL_COMPONENT_BITS * index as u32);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{assert_abs_diff_eq, ElementWise};
+    use more_asserts::{assert_ge, assert_le};
+
+    #[must_use]
+    fn make_frame() -> QuantizationFrame {
+        QuantizationFrame::new(&Aabb::from_points(Point::new(-1.0, 2.0, 0.0), Point::new(3.0, 10.0, 0.0)))
+    }
+
+    #[test]
+    fn test_nearest_round_trip() {
+        let system_under_test = make_frame();
+        let probe = Point::new(0.123, 7.777, 0.0);
+
+        let restored = system_under_test.restore(system_under_test.quantize_nearest(probe));
+
+        let half_step = (system_under_test.restore([1, 1, 1]) - system_under_test.restore([0, 0, 0])) * 0.5;
+        assert_le!((restored.x - probe.x).abs(), half_step.x);
+        assert_le!((restored.y - probe.y).abs(), half_step.y);
+        assert_le!(half_step.x, 4.0 / GRID_MAX);
+        assert_le!(restored.z.abs(), half_step.z);
+    }
+
+    #[test]
+    fn test_conservative_box() {
+        let system_under_test = make_frame();
+        let min = Point::new(0.3, 4.1, 0.0);
+        let max = Point::new(0.7, 4.2, 0.0);
+
+        let restored_min = system_under_test.restore(system_under_test.quantize_down(min));
+        let restored_max = system_under_test.restore(system_under_test.quantize_up(max));
+
+        assert_le!(restored_min.x, min.x);
+        assert_le!(restored_min.y, min.y);
+        assert_ge!(restored_max.x, max.x);
+        assert_ge!(restored_max.y, max.y);
+    }
+
+    #[test]
+    fn test_frame_corners_are_clamped() {
+        let system_under_test = make_frame();
+
+        assert_eq!(system_under_test.quantize_down(Point::new(-1.0, 2.0, 0.0))[0..2], [0, 0]);
+        assert_eq!(system_under_test.quantize_up(Point::new(3.0, 10.0, 0.0))[0..2], [u16::MAX, u16::MAX]);
+    }
+
+    #[test]
+    fn test_pack_normal() {
+        let packed = pack_normal(Vector::new(0.0, 0.0, -2.0));
+
+        let mask = (1 << NORMAL_COMPONENT_BITS) - 1;
+        let restored = Vector::new((packed & mask) as f64, ((packed >> 10) & mask) as f64, ((packed >> 20) & mask) as f64)
+            .div_element_wise(Vector::new(NORMAL_COMPONENT_MAX, NORMAL_COMPONENT_MAX, NORMAL_COMPONENT_MAX)) * 2.0
+            - Vector::new(1.0, 1.0, 1.0);
+        assert_abs_diff_eq!(restored, Vector::new(0.0, 0.0, -1.0), epsilon = 2.0 / NORMAL_COMPONENT_MAX);
+    }
+
+    #[test]
+    fn test_pack_unorm16_pair() {
+        assert_eq!(pack_unorm16_pair(0x1234, 0xABCD), 0xABCD_1234);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::gpu::pipeline_code::ShaderHash;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hasher;
+
+struct FunctionText<'code> {
+    text: &'code str,
+    callees: Vec<&'code str>,
+}
+
+// the top level functions of a wgsl text along with the functions each of them calls; the rest of
+// the text - the structs, the bindings, the constants - is the declarations any function may read
+pub(crate) struct CallGraph<'code> {
+    declarations: String,
+    functions: HashMap<&'code str, FunctionText<'code>>,
+}
+
+impl<'code> CallGraph<'code> {
+    const FUNCTION_KEYWORD: &'static str = "fn ";
+
+    // a function is expected to start with 'fn' at the line beginning, as the composed shader has it
+    #[must_use]
+    pub(crate) fn new(code: &'code str) -> Self {
+        let mut declarations = String::new();
+        let mut bodies: Vec<(&'code str, &'code str)> = Vec::new();
+
+        let mut function_start: Option<(&'code str, usize)> = None;
+        let mut depth = 0_i32;
+        let mut opened = false;
+        let mut offset = 0;
+        for line in code.split_inclusive('\n') {
+            let line_start = offset;
+            offset += line.len();
+
+            if function_start.is_none() {
+                match line.strip_prefix(Self::FUNCTION_KEYWORD) {
+                    Some(signature) => {
+                        let name = signature.split('(').next().unwrap_or_default().trim();
+                        function_start = Some((name, line_start));
+                        depth = 0;
+                        opened = false;
+                    }
+                    None => {
+                        declarations.push_str(line);
+                        continue;
+                    }
+                }
+            }
+
+            let meaningful = line.split("//").next().unwrap_or_default();
+            for symbol in meaningful.chars() {
+                match symbol {
+                    '{' => { depth += 1; opened = true; }
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            if opened && depth <= 0 && let Some((name, start)) = function_start.take() {
+                bodies.push((name, &code[start..offset]));
+            }
+        }
+
+        let names: BTreeSet<&'code str> = bodies.iter().map(|(name, _)| *name).collect();
+        let functions = bodies.into_iter()
+            .map(|(name, text)| {
+                let callees: BTreeSet<&'code str> = text
+                    .split(|symbol: char| false == (symbol.is_alphanumeric() || '_' == symbol))
+                    .filter(|word| *word != name && names.contains(word))
+                    .collect();
+                (name, FunctionText { text, callees: callees.into_iter().collect() })
+            })
+            .collect();
+
+        Self { declarations, functions }
+    }
+
+    // the entry points themselves are included
+    #[must_use]
+    pub(crate) fn reachable_from(&self, entry_points: &[&str]) -> BTreeSet<&'code str> {
+        let mut reached = BTreeSet::new();
+        let mut to_visit: Vec<&str> = entry_points.to_vec();
+        while let Some(name) = to_visit.pop() {
+            let Some((name, function)) = self.functions.get_key_value(name) else { continue };
+            if reached.insert(*name) {
+                to_visit.extend(function.callees.iter().copied());
+            }
+        }
+        reached
+    }
+
+    // changes only when the code a pipeline of the entry points is made of does
+    #[must_use]
+    pub(crate) fn fingerprint(&self, entry_points: &[&str]) -> ShaderHash {
+        for entry_point in entry_points {
+            assert!(self.functions.contains_key(entry_point), "entry point '{entry_point}' is not in the code");
+        }
+        let mut hasher = seahash::SeaHasher::new();
+        hasher.write(self.declarations.as_bytes());
+        for name in self.reachable_from(entry_points) {
+            hasher.write(self.functions[name].text.as_bytes());
+        }
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "\
+struct Point
+{
+    x: f32,
+};
+
+fn leaf(value: f32) -> f32
+{
+    return value;
+}
+
+fn middle(value: f32) -> f32 {
+    let nested = {
+value
+};
+    return leaf(nested);
+}
+
+@compute
+@workgroup_size(1, 1, 1)
+fn first_entry()
+{
+    middle(1.0); // a comment with a brace }
+}
+
+fn second_entry() {
+    leaf(2.0);
+}
+
+fn unused() { }
+";
+
+    #[test]
+    fn test_reachable_from() {
+        let system_under_test = CallGraph::new(CODE);
+
+        assert_eq!(system_under_test.reachable_from(&["first_entry"]), BTreeSet::from(["first_entry", "middle", "leaf"]));
+        assert_eq!(system_under_test.reachable_from(&["second_entry"]), BTreeSet::from(["second_entry", "leaf"]));
+        assert_eq!(system_under_test.reachable_from(&["unused"]), BTreeSet::from(["unused"]));
+        assert_eq!(system_under_test.reachable_from(&["first_entry", "second_entry"]).len(), 4);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_unreached_functions() {
+        let changed = CODE.replace("fn unused() { }", "fn unused() { leaf(3.0); }");
+        let original = CallGraph::new(CODE);
+        let system_under_test = CallGraph::new(changed.as_str());
+
+        assert_eq!(system_under_test.fingerprint(&["first_entry"]), original.fingerprint(&["first_entry"]));
+        assert_ne!(system_under_test.fingerprint(&["unused"]), original.fingerprint(&["unused"]));
+    }
+
+    #[test]
+    fn test_fingerprint_follows_reached_functions() {
+        let changed = CODE.replace("return value;", "return value * 2.0;");
+        let original = CallGraph::new(CODE);
+        let system_under_test = CallGraph::new(changed.as_str());
+
+        assert_ne!(system_under_test.fingerprint(&["first_entry"]), original.fingerprint(&["first_entry"]));
+        assert_ne!(system_under_test.fingerprint(&["second_entry"]), original.fingerprint(&["second_entry"]));
+    }
+
+    #[test]
+    fn test_fingerprint_follows_declarations() {
+        let changed = CODE.replace("x: f32,", "x: f32,\n    y: f32,");
+        let original = CallGraph::new(CODE);
+        let system_under_test = CallGraph::new(changed.as_str());
+
+        assert_ne!(system_under_test.fingerprint(&["second_entry"]), original.fingerprint(&["second_entry"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "entry point 'absent' is not in the code")]
+    fn test_fingerprint_of_absent_entry_point() {
+        let system_under_test = CallGraph::new(CODE);
+
+        let _ = system_under_test.fingerprint(&["absent"]);
+    }
+}
@@ -62,20 +62,20 @@ impl ShaderCode<FunctionBody> {
     }
 
     #[must_use]
-    pub(crate) fn to_scalar_declaration_assignment(&self, variable_name: &VariableName) -> ShaderCode<VariableAssignment> {
-        let assignment = self.make_scalar_assignment(variable_name);
-        let assignment = format!("var {variable_name}: f32;\n{assignment}");
+    pub(crate) fn to_declaration_assignment(&self, variable_name: &VariableName, variable_type: &str) -> ShaderCode<VariableAssignment> {
+        let assignment = self.make_assignment(variable_name);
+        let assignment = format!("var {variable_name}: {variable_type};\n{assignment}");
         ShaderCode::<VariableAssignment>::new(assignment.to_string())
     }
 
     #[must_use]
-    pub(crate) fn to_scalar_assignment(&self, variable_name: &VariableName) -> ShaderCode<VariableAssignment> {
-        let assignment = self.make_scalar_assignment(variable_name);
+    pub(crate) fn to_assignment(&self, variable_name: &VariableName) -> ShaderCode<VariableAssignment> {
+        let assignment = self.make_assignment(variable_name);
         ShaderCode::<VariableAssignment>::new(assignment.to_string())
     }
 
     #[must_use]
-    fn make_scalar_assignment(&self, variable_name: &VariableName) -> String {
+    fn make_assignment(&self, variable_name: &VariableName) -> String {
         let evaluation = self.value.replace("return", format!("{variable_name} =").as_str());
         let assignment = format!("{{\n{assignment}\n}}", assignment = evaluation.trim());
         assignment
@@ -100,13 +100,21 @@ mod tests {
     #[test]
     fn test_function_body_conversion_to_block_expression() {
         assert_eq!(
-            String::from(ShaderCode::<FunctionBody>::new("  return 13;  ".to_string()).to_scalar_declaration_assignment(&VariableName::new("foo", None))),
+            String::from(ShaderCode::<FunctionBody>::new("  return 13;  ".to_string()).to_declaration_assignment(&VariableName::new("foo", None), "f32")),
             String::from("var foo: f32;\n{\nfoo = 13;\n}"),
         );
 
         assert_eq!(
-            String::from(ShaderCode::<FunctionBody>::new(" return 17; ".to_string()).to_scalar_declaration_assignment(&VariableName::new("zig", None))),
+            String::from(ShaderCode::<FunctionBody>::new(" return 17; ".to_string()).to_declaration_assignment(&VariableName::new("zig", None), "f32")),
             String::from("var zig: f32;\n{\nzig = 17;\n}"),
         );
     }
+
+    #[test]
+    fn test_function_body_conversion_to_typed_block_expression() {
+        assert_eq!(
+            String::from(ShaderCode::<FunctionBody>::new("return vec4f(1.0);".to_string()).to_declaration_assignment(&VariableName::new("bar", Some(2)), "vec4f")),
+            String::from("var bar_2: vec4f;\n{\nbar_2 = vec4f(1.0);\n}"),
+        );
+    }
 }
@@ -1,7 +1,12 @@
 pub const PARAMETER_NAME_THE_POINT: &str = "point";
 pub const PARAMETER_NAME_THE_NORMAL: &str = "normal";
 pub const PARAMETER_NAME_THE_TIME: &str = "time";
+pub const PARAMETER_NAME_THE_DIRECTION: &str = "direction";
+pub const PARAMETER_NAME_THE_ALBEDO: &str = "albedo";
 
 pub const PARAMETER_NAME_2D_TEXTURE_COORDINATES: &str = "uv";
 pub const PARAMETER_DP_DX: &str = "dp_dx";
-pub const PARAMETER_DP_DY: &str = "dp_dy";
\ No newline at end of file
+pub const PARAMETER_DP_DY: &str = "dp_dy";
+
+// the values of the sdf instance being evaluated, see 'SdfParameters'
+pub const GLOBAL_NAME_SDF_PARAMETERS: &str = "sdf_parameters";
@@ -3,4 +3,6 @@ pub mod code;
 pub(crate) mod variable_name;
 pub mod conventions;
 pub mod formatting_utils;
-pub(crate) mod function_name_generator;
\ No newline at end of file
+pub(crate) mod function_name_generator;
+pub(crate) mod call_graph;
+pub mod user_function_registrator;
@@ -0,0 +1,207 @@
+use crate::shader::code::{FunctionBody, Generic, ShaderCode};
+use crate::shader::conventions;
+use crate::shader::function_name::FunctionName;
+use crate::shader::function_name_generator::FunctionNameGenerator;
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+// the names and the signature shared by the user functions of a kind and by the function selecting one
+// of them by the uid
+pub struct UserFunctionConventions {
+    pub(crate) selection_function_name: &'static str,
+    pub(crate) function_name_prefix: &'static str,
+    pub(crate) default_name: &'static str,
+    pub(crate) parameter_name_index: &'static str,
+    // the names and the types, in the order of the arguments
+    pub(crate) parameters: &'static [(&'static str, &'static str)],
+    pub(crate) return_type: &'static str,
+    // returned by the selection function for the unregistered uids
+    pub(crate) default_value: &'static str,
+}
+
+pub trait UserFunctionUid: Copy + Eq + Hash + Display {
+    const CONVENTIONS: UserFunctionConventions;
+
+    // the uids of the functions go from one in the order of the registration
+    #[must_use]
+    fn from_ordinal(ordinal: usize) -> Self;
+}
+
+pub struct UserFunctionRegistrator<Uid: UserFunctionUid> {
+    shared_code: ShaderCode,
+    functions: HashMap<Uid, NamedFunction>,
+    names_generator: FunctionNameGenerator,
+}
+
+struct NamedFunction {
+    body: ShaderCode<FunctionBody>,
+    name: FunctionName,
+}
+
+impl<Uid: UserFunctionUid> UserFunctionRegistrator<Uid> {
+    #[must_use]
+    pub fn new(shared_code: Option<ShaderCode>) -> Self {
+        Self {
+            shared_code: shared_code.unwrap_or(ShaderCode::<Generic>::new(String::new())),
+            functions: HashMap::new(),
+            names_generator: FunctionNameGenerator::new(),
+        }
+    }
+
+    // the body sees the parameters of the kind, see the 'CONVENTIONS' of the uid
+    #[must_use]
+    pub fn add(&mut self, body: ShaderCode<FunctionBody>, name: Option<&str>) -> Uid {
+        let suffix = name.filter(|candidate| false == candidate.trim().is_empty()).unwrap_or(Uid::CONVENTIONS.default_name);
+        let prefixed = format!("{}_{}", Uid::CONVENTIONS.function_name_prefix, suffix);
+        let name = self.names_generator.next_name(Some(prefixed.as_str()));
+        let uid = Uid::from_ordinal(self.functions.len() + 1);
+        self.functions.insert(uid, NamedFunction { body, name });
+        uid
+    }
+
+    #[must_use]
+    pub fn animated(&self, uid: Uid) -> bool {
+        if let Some(function) = self.functions.get(&uid) {
+            return function.body.as_str().contains(conventions::PARAMETER_NAME_THE_TIME);
+        }
+        false
+    }
+
+    #[must_use]
+    pub(crate) fn contains(&self, uid: Uid) -> bool {
+        self.functions.contains_key(&uid)
+    }
+
+    #[must_use]
+    pub(crate) fn generate_gpu_code(&self) -> ShaderCode {
+        let mut buffer: String = self.shared_code.to_string();
+
+        if false == buffer.is_empty() {
+            buffer.push('\n');
+        }
+        self.write_gpu_code(&mut buffer).expect("shader code formatting failed");
+
+        ShaderCode::<Generic>::new(buffer)
+    }
+
+    fn write_gpu_code(&self, buffer: &mut String) -> anyhow::Result<()> {
+        let mut sorted: Vec<(&Uid, &NamedFunction)> = self.functions.iter().collect();
+        sorted.sort_by_key(|(_, value)| &value.name.0);
+
+        for (_, candidate) in sorted.iter() {
+            write!(
+                buffer,
+                "fn {function_name}({common_parameters})->{return_type}{{\n{body}\n}}\n",
+                function_name = candidate.name,
+                common_parameters = Self::format_common_parameters(),
+                return_type = Uid::CONVENTIONS.return_type,
+                body = candidate.body,
+            )?;
+        }
+        Self::write_selection_function(&sorted, buffer)?;
+
+        Ok(())
+    }
+
+    #[must_use]
+    fn format_common_parameters() -> String {
+        Uid::CONVENTIONS.parameters.iter().map(|(name, type_name)| format!("{name}: {type_name}")).collect::<Vec<_>>().join(", ")
+    }
+
+    #[must_use]
+    fn format_common_arguments() -> String {
+        Uid::CONVENTIONS.parameters.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(",")
+    }
+
+    fn write_selection_function(variants: &Vec<(&Uid, &NamedFunction)>, buffer: &mut String) -> anyhow::Result<()> {
+        writeln!(
+            buffer,
+            "fn {selection_function_name}({parameter_index}: i32, {common_parameters}) -> {return_type} {{",
+            selection_function_name = Uid::CONVENTIONS.selection_function_name,
+            parameter_index = Uid::CONVENTIONS.parameter_name_index,
+            common_parameters = Self::format_common_parameters(),
+            return_type = Uid::CONVENTIONS.return_type,
+        )?;
+
+        for (uid, variant) in variants {
+            writeln!(
+                buffer,
+                "if ({parameter_index} == {index}) {{ return {function_name}({arguments}); }}",
+                parameter_index = Uid::CONVENTIONS.parameter_name_index,
+                index = uid,
+                function_name = variant.name,
+                arguments = Self::format_common_arguments(),
+            )?;
+        }
+
+        write!(buffer, "return {};\n}}\n", Uid::CONVENTIONS.default_value)?;
+        Ok(())
+    }
+
+    #[must_use]
+    pub(crate) fn make_dummy_selection_function() -> ShaderCode {
+        let mut result = String::new();
+        Self::write_selection_function(&Vec::new(), &mut result).expect("shader code formatting failed");
+        ShaderCode::<Generic>::new(result)
+    }
+}
+
+impl<Uid: UserFunctionUid> Default for UserFunctionRegistrator<Uid> {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use derive_more::Display;
+
+    #[derive(Copy, Clone, Debug, Eq, PartialEq, Display, Hash)]
+    struct TestUid(usize);
+
+    impl UserFunctionUid for TestUid {
+        const CONVENTIONS: UserFunctionConventions = UserFunctionConventions {
+            selection_function_name: "test_select",
+            function_name_prefix: "test",
+            default_name: "function",
+            parameter_name_index: "test_index",
+            parameters: &[("x", "f32"), ("time", "f32")],
+            return_type: "f32",
+            default_value: "x",
+        };
+
+        fn from_ordinal(ordinal: usize) -> Self {
+            TestUid(ordinal)
+        }
+    }
+
+    #[must_use]
+    fn function(body: &str) -> ShaderCode<FunctionBody> {
+        ShaderCode::<FunctionBody>::new(body.to_string())
+    }
+
+    #[test]
+    fn test_dummy_selection_function() {
+        assert_eq!(
+            UserFunctionRegistrator::<TestUid>::make_dummy_selection_function().to_string(),
+            "fn test_select(test_index: i32, x: f32, time: f32) -> f32 {\nreturn x;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_gpu_code() {
+        let mut system_under_test = UserFunctionRegistrator::<TestUid>::new(Some(ShaderCode::<Generic>::new("shared code".to_string())));
+
+        let unnamed = system_under_test.add(function("return x;"), None);
+        let named = system_under_test.add(function("return x * time;"), Some("scaled"));
+
+        assert_eq!(unnamed, TestUid(1));
+        assert_eq!(named, TestUid(2));
+        assert!(system_under_test.contains(named));
+        assert_eq!(system_under_test.contains(TestUid(3)), false);
+        let expected_code = "shared code\nfn test_function(x: f32, time: f32)->f32{\nreturn x;\n}\nfn test_scaled(x: f32, time: f32)->f32{\nreturn x * time;\n}\nfn test_select(test_index: i32, x: f32, time: f32) -> f32 {\nif (test_index == 1) { return test_function(x,time); }\nif (test_index == 2) { return test_scaled(x,time); }\nreturn x;\n}\n";
+        assert_eq!(system_under_test.generate_gpu_code().to_string(), expected_code);
+    }
+}
@@ -61,6 +61,13 @@ mod tests {
         check_material_data_probe(fixture, &template, PodVector::new_full(1.0, 0.0, 0.0, -7.0));
     }
 
+    #[test_context(GpuCodeExecutionContext)]
+    #[test]
+    fn test_material_packing_for_gpu_absorption(fixture: &mut GpuCodeExecutionContext) {
+        let template = make_shader_function("absorption_0", FieldKind::Vector3, DATA_SOURCE);
+        check_material_data_probe(fixture, &template, PodVector::new_full(13.0, 14.0, 15.0, -7.0));
+    }
+
     fn check_material_data_probe(fixture: &mut GpuCodeExecutionContext, template: &ShaderFunction, expected_data: PodVector) {
         let function_execution = make_executable(&template, create_argument_formatter!("{argument}"));
 
@@ -71,6 +78,7 @@ mod tests {
             .with_emission(8.0, 9.0, 10.0)
             .with_refractive_index_eta(11.0)
             .with_roughness(12.0)
+            .with_absorption(13.0, 14.0, 15.0)
             .with_class(MaterialClass::Mirror);
 
         let mut serialized_materials = GpuReadySerializationBuffer::new(1, MaterialProperties::SERIALIZED_QUARTET_COUNT);
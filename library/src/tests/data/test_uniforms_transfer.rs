@@ -155,6 +155,13 @@ mod tests {
         check_uniforms_data_probe(fixture, &template, PodVector::new_full(1.0, 0.0, 0.0, -7.0));
     }
 
+    #[test_context(GpuCodeExecutionContext)]
+    #[test]
+    fn test_uniforms_packing_camera_fov_factor(fixture: &mut GpuCodeExecutionContext) {
+        let template = make_shader_function("camera_fov_factor_0", FieldKind::Scalar, DATA_SOURCE);
+        check_uniforms_data_probe(fixture, &template, PodVector::new_full(stub_camera().fov_factor() as f32, 0.0, 0.0, -7.0));
+    }
+
     #[must_use]
     fn to_pod(expected: Vector4<f64>) -> PodVector {
         PodVector::new_full(expected.x as f32, expected.y as f32, expected.z as f32, expected.w as f32)
@@ -1,5 +1,6 @@
 mod test_deterministic_render;
 mod utils;
+#[cfg(feature = "monte_carlo")]
 mod test_monte_carlo_render;
 pub(crate) mod scene_setup;
 pub(crate) mod images_comparison;
\ No newline at end of file
@@ -4,7 +4,7 @@ pub(crate) mod tests {
     use crate::container::visual_objects::VisualObjects;
     use crate::geometry::alias::{Point, Vector};
     use crate::geometry::transform::Affine;
-    use crate::gpu::color_buffer_evaluation::RenderStrategyId;
+    use crate::scene::render_strategy::RenderStrategyId;
     use crate::gpu::context::Context;
     use crate::gpu::frame_buffer_size::FrameBufferSize;
     use crate::gpu::headless_device::tests::create_headless_wgpu_vulkan_context;
@@ -27,7 +27,6 @@ pub(crate) mod tests {
     use crate::utils::tests::common_values::tests::COMMON_PRESENTATION_FORMAT;
     use cgmath::{Deg, Vector4};
     use palette::Srgb;
-    use std::ops::Deref;
     use std::path::PathBuf;
     use std::rc::Rc;
 
@@ -68,7 +67,7 @@ pub(crate) mod tests {
         let capsule_sdf = UniqueSdfClassName::new("capsule_specimen".to_string());
         registrator.add(&NamedSdf::new(SdfCapsule::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0), 0.3), capsule_sdf.clone()));
 
-        let mut scene = VisualObjects::new(None, Some(registrator), None);
+        let mut scene = VisualObjects::new(None, Some(registrator), None, None, None);
 
         let emissive_material = scene.materials_mutable().add(&MaterialProperties::new()
             .with_emission(light_color.red, light_color.green, light_color.blue));
@@ -168,7 +167,7 @@ pub(crate) mod tests {
         let torus_xz_sdf = UniqueSdfClassName::new("torus_xz_specimen".to_string());
         registrator.add(&NamedSdf::new(SdfTorusXz::new(0.1, 0.05), torus_xz_sdf.clone()));
 
-        let mut scene = VisualObjects::new(Some(BitmapSize::new(256, 512)), Some(registrator), None);
+        let mut scene = VisualObjects::new(Some(BitmapSize::new(256, 512)), Some(registrator), None, None, None);
 
         let texture_atlas_page_composer = scene.mutable_texture_atlas_page_composer();
         let checkerboard_texture = load_bitmap(data_folder_path().join("bitmap_checkerboard_small.png"), texture_atlas_page_composer)
@@ -267,7 +266,7 @@ pub(crate) mod tests {
             = Renderer::new(context.clone(), scene, camera, frame_buffer_settings, render_strategy, None)
                 .expect("render instantiation has failed");
 
-        shoot_rays_and_transfer_data_to_cpu(context.deref(), &mut system_under_test);
+        shoot_rays_and_transfer_data_to_cpu(&mut system_under_test);
 
         let actual_render_path = out_folder_path().join(test_case_name.clone());
         save_colors_to_png(&mut system_under_test, TEST_FRAME_BUFFER_SIZE, actual_render_path.clone());
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     mod deterministic_render {
-        use crate::gpu::color_buffer_evaluation::RenderStrategyId;
+        use crate::scene::render_strategy::RenderStrategyId;
         use std::path::PathBuf;
 
         const RENDER_STRATEGY: RenderStrategyId = RenderStrategyId::Deterministic;
@@ -2,7 +2,7 @@
 mod tests {
     mod monte_carlo_render {
         use std::path::PathBuf;
-        use crate::gpu::color_buffer_evaluation::RenderStrategyId;
+        use crate::scene::render_strategy::RenderStrategyId;
 
         const RENDER_STRATEGY: RenderStrategyId = RenderStrategyId::MonteCarlo;
 
@@ -7,6 +7,18 @@ fn sdf_select(index: i32, position: vec3f, time: f32) -> f32 {
     return 0.0;
 }
 
+fn sdf_select_gradient(index: i32, position: vec3f, time: f32) -> vec4f {
+    return vec4f(0.0);
+}
+
 fn sdf_apply_animation(index: i32, position: vec3f, time: f32) -> vec3f {
     return vec3f(0.0);
+}
+
+fn background_select(index: i32, direction: vec3f, time: f32) -> vec3f {
+    return vec3f(0.0);
+}
+
+fn surface_shader_select(index: i32, position: vec3f, normal: vec3f, uv: vec2f, time: f32, albedo: vec3f) -> vec3f {
+    return vec3f(0.0);
 }
\ No newline at end of file
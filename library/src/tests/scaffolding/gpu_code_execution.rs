@@ -257,9 +257,9 @@ pub mod tests {
             output_buffer.prepare_cpu_read(&mut encoder);
             self.gpu_context.queue().submit(Some(encoder.finish()));
 
-            let copy_wait = output_buffer.read_cpu_copy();
+            output_buffer.request_cpu_read();
             self.gpu_context.wait(None);
-            pollster::block_on(copy_wait);
+            let _ = output_buffer.collect_cpu_read();
 
             output_buffer.cpu_copy().clone()
         }
@@ -518,7 +518,7 @@ mod tests {
             .with_additional_shader_code(shader_code)
             .with_additional_shader_code(
                 r#"fn sample_signed_distance_t(position: vec3f) -> vec3f {
-                    let sdf = Sdf_0(sdf[0].location_col_0_0, sdf[0].location_col_1_0, sdf[0].location_col_2_0, sdf[0].inverse_location_col_0_0, sdf[0].inverse_location_col_1_0, sdf[0].inverse_location_col_2_0, sdf[0].ray_marching_step_scale_0, sdf[0].class_index_0, sdf[0].material_id_2, sdf[0].object_uid_2);
+                    let sdf = Sdf_0(sdf[0].location_col_0_0, sdf[0].location_col_1_0, sdf[0].location_col_2_0, sdf[0].inverse_location_col_0_0, sdf[0].inverse_location_col_1_0, sdf[0].inverse_location_col_2_0, sdf[0].ray_marching_step_scale_0, sdf[0].class_index_0, sdf[0].material_id_2, sdf[0].object_uid_2, sdf[0].parameters_0);
                     return signed_distance_normal_0(sdf, position, 0.0);
                 }"#
             );
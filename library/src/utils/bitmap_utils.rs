@@ -6,7 +6,7 @@ use std::path::Path;
 
 pub(crate) const BYTES_IN_RGBA_QUARTET: usize = 4;
 
-#[derive(Clone, Copy, Display)]
+#[derive(Clone, Copy, PartialEq, Debug, Display)]
 #[display("{}x{}", width, height)]
 pub struct BitmapSize {
     width: usize,
@@ -142,6 +142,72 @@ pub(crate) fn write_sub_bitmap_row(
     );
 }
 
+// fills the area with the sub bitmap repeated in both directions, one of the copies is placed at [origin_u, origin_v]
+pub(crate) fn write_wrapped_sub_bitmap(
+    container: &mut MutableBitmapReference,
+    area_u: usize,
+    area_v: usize,
+    area_size: BitmapSize,
+    sub_bitmap: &ImmutableBitmapReference,
+    origin_u: usize,
+    origin_v: usize,
+) {
+    let width = sub_bitmap.size().width() as isize;
+    let height = sub_bitmap.size().height() as isize;
+    for v in area_v..area_v + area_size.height {
+        let from_v = (v as isize - origin_v as isize).rem_euclid(height) as usize;
+        for u in area_u..area_u + area_size.width {
+            let from_u = (u as isize - origin_u as isize).rem_euclid(width) as usize;
+            set_texel(container, u, v, sub_bitmap, from_u, from_v);
+        }
+    }
+}
+
+// the next level of the mip chain: each texel is the average of a 2x2 block (clamped at the odd edges);
+// the color is averaged in the linear space, the alpha as is
+#[must_use]
+pub(crate) fn downsample_srgba_bitmap(source: &ImmutableBitmapReference) -> (Vec<u8>, BitmapSize) {
+    let decoded: [f32; 256] = std::array::from_fn(|value| srgb_to_linear(value as f32 / 255.0));
+    let source_size = source.size();
+    let size = BitmapSize::new((source_size.width / 2).max(1), (source_size.height / 2).max(1));
+    let mut result = vec![0; size.bytes_in_bitmap()];
+
+    for v in 0..size.height {
+        for u in 0..size.width {
+            let columns = [(2 * u).min(source_size.width - 1), (2 * u + 1).min(source_size.width - 1)];
+            let rows = [(2 * v).min(source_size.height - 1), (2 * v + 1).min(source_size.height - 1)];
+            let mut sum = [0.0_f32; BYTES_IN_RGBA_QUARTET];
+            for row in rows {
+                for column in columns {
+                    let texel = &source.data()[texel_byte_index(column, row, source_size)..][..BYTES_IN_RGBA_QUARTET];
+                    for channel in 0..3 {
+                        sum[channel] += decoded[texel[channel] as usize];
+                    }
+                    sum[3] += texel[3] as f32 / 255.0;
+                }
+            }
+
+            let destination = &mut result[texel_byte_index(u, v, size)..][..BYTES_IN_RGBA_QUARTET];
+            for channel in 0..3 {
+                destination[channel] = (linear_to_srgb(sum[channel] * 0.25) * 255.0).round() as u8;
+            }
+            destination[3] = (sum[3] * 0.25 * 255.0).round() as u8;
+        }
+    }
+
+    (result, size)
+}
+
+#[must_use]
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+}
+
+#[must_use]
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 { value * 12.92 } else { 1.055 * value.powf(1.0 / 2.4) - 0.055 }
+}
+
 #[must_use]
 fn texel_byte_index(u: usize, v: usize, size: BitmapSize) -> usize {
     (v * size.width + u) * BYTES_IN_RGBA_QUARTET
@@ -509,6 +575,43 @@ mod tests {
             &BitmapReference::new(source_data.as_slice(), source_size), 0);
     }
 
+    #[test]
+    fn test_write_wrapped_sub_bitmap() {
+        let source_data: Vec<u8> = (0..2 * 2).flat_map(|texel| [texel, 0, 0, 255]).collect();
+        let source = BitmapReference::new(source_data.as_slice(), BitmapSize::new(2, 2));
+        let destination_size = BitmapSize::new(5, 3);
+        let mut destination_data = allocate_bitmap_of_size(destination_size, 0);
+
+        write_wrapped_sub_bitmap(&mut BitmapReference::new(destination_data.as_mut_slice(), destination_size), 0, 0, destination_size, &source, 1, 1);
+
+        let reds: Vec<u8> = destination_data.chunks(BYTES_IN_RGBA_QUARTET).map(|texel| texel[0]).collect();
+        assert_eq!(reds, [3, 2, 3, 2, 3, 1, 0, 1, 0, 1, 3, 2, 3, 2, 3]);
+    }
+
+    #[test]
+    fn test_downsample_averages_in_linear_space() {
+        let source_data = [
+            0, 0, 0, 0,         255, 255, 255, 255,
+            255, 255, 255, 255, 0, 0, 0, 0,
+        ];
+
+        let (downsampled, size) = downsample_srgba_bitmap(&BitmapReference::new(source_data.as_slice(), BitmapSize::new(2, 2)));
+
+        assert_eq!((size.width(), size.height()), (1, 1));
+        assert_eq!(downsampled, [188, 188, 188, 128]);
+    }
+
+    #[test]
+    fn test_downsample_odd_size() {
+        let source_size = BitmapSize::new(3, 1);
+        let source_data = [10, 20, 30, 40, 10, 20, 30, 40, 10, 20, 30, 40];
+
+        let (downsampled, size) = downsample_srgba_bitmap(&BitmapReference::new(source_data.as_slice(), source_size));
+
+        assert_eq!((size.width(), size.height()), (1, 1));
+        assert_eq!(downsampled, [10, 20, 30, 40]);
+    }
+
     #[test]
     fn test_multiple_texel_copies() {
         let source_data = vec![
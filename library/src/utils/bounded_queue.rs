@@ -0,0 +1,193 @@
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+
+// what happens to an item pushed while the queue is full
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    // the consumer gets the freshest items, the stale ones are lost
+    #[default]
+    DropOldest,
+    // the items already waiting are kept, the pushed one is lost
+    DropNewest,
+}
+
+struct State<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+// a producer-consumer queue that never blocks the producer: on overflow one item is dropped
+pub struct BoundedQueue<T> {
+    state: Mutex<State<T>>,
+    item_available: Condvar,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl<T> BoundedQueue<T> {
+    #[must_use]
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        assert!(capacity > 0);
+        Self {
+            state: Mutex::new(State { items: VecDeque::with_capacity(capacity), closed: false }),
+            item_available: Condvar::new(),
+            capacity,
+            policy,
+        }
+    }
+
+    // returns the dropped item, if any; after 'close' the pushed item is dropped
+    pub fn push(&self, item: T) -> Option<T> {
+        let mut state = self.state.lock().expect("bounded queue lock is poisoned");
+        if state.closed {
+            return Some(item);
+        }
+        let dropped = if state.items.len() < self.capacity {
+            None
+        } else {
+            match self.policy {
+                OverflowPolicy::DropOldest => state.items.pop_front(),
+                OverflowPolicy::DropNewest => return Some(item),
+            }
+        };
+        state.items.push_back(item);
+        self.item_available.notify_one();
+        dropped
+    }
+
+    // blocks until there is an item; 'None' once the queue is closed
+    #[must_use]
+    pub fn pop(&self) -> Option<T> {
+        let mut state = self.state.lock().expect("bounded queue lock is poisoned");
+        loop {
+            if state.closed {
+                return None;
+            }
+            if let Some(item) = state.items.pop_front() {
+                return Some(item);
+            }
+            state = self.item_available.wait(state).expect("bounded queue lock is poisoned");
+        }
+    }
+
+    #[must_use]
+    pub fn try_pop(&self) -> Option<T> {
+        let mut state = self.state.lock().expect("bounded queue lock is poisoned");
+        if state.closed {
+            return None;
+        }
+        state.items.pop_front()
+    }
+
+    // wakes the waiting consumer up; the items still in the queue are discarded
+    pub fn close(&self) {
+        let mut state = self.state.lock().expect("bounded queue lock is poisoned");
+        state.closed = true;
+        state.items.clear();
+        self.item_available.notify_all();
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state.lock().expect("bounded queue lock is poisoned").items.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_push_pop_in_order() {
+        let system_under_test = BoundedQueue::new(3, OverflowPolicy::DropOldest);
+
+        assert_eq!(system_under_test.push(1), None);
+        assert_eq!(system_under_test.push(2), None);
+
+        assert_eq!(system_under_test.len(), 2);
+        assert_eq!(system_under_test.pop(), Some(1));
+        assert_eq!(system_under_test.try_pop(), Some(2));
+        assert_eq!(system_under_test.try_pop(), None);
+        assert!(system_under_test.is_empty());
+    }
+
+    #[test]
+    fn test_overflow_drops_oldest() {
+        let system_under_test = BoundedQueue::new(2, OverflowPolicy::DropOldest);
+        let _ = system_under_test.push(1);
+        let _ = system_under_test.push(2);
+
+        assert_eq!(system_under_test.push(3), Some(1));
+
+        assert_eq!(system_under_test.len(), 2);
+        assert_eq!(system_under_test.try_pop(), Some(2));
+        assert_eq!(system_under_test.try_pop(), Some(3));
+    }
+
+    #[test]
+    fn test_overflow_drops_newest() {
+        let system_under_test = BoundedQueue::new(2, OverflowPolicy::DropNewest);
+        let _ = system_under_test.push(1);
+        let _ = system_under_test.push(2);
+
+        assert_eq!(system_under_test.push(3), Some(3));
+
+        assert_eq!(system_under_test.try_pop(), Some(1));
+        assert_eq!(system_under_test.try_pop(), Some(2));
+    }
+
+    #[test]
+    fn test_close_discards_items_and_rejects_pushes() {
+        let system_under_test = BoundedQueue::new(2, OverflowPolicy::DropOldest);
+        let _ = system_under_test.push(1);
+
+        system_under_test.close();
+
+        assert_eq!(system_under_test.pop(), None);
+        assert_eq!(system_under_test.push(2), Some(2));
+        assert!(system_under_test.is_empty());
+    }
+
+    #[test]
+    fn test_pop_waits_for_producer() {
+        let system_under_test = Arc::new(BoundedQueue::new(1, OverflowPolicy::DropOldest));
+        let consumer_queue = system_under_test.clone();
+        let consumer = thread::spawn(move || {
+            let mut received = Vec::new();
+            while let Some(item) = consumer_queue.pop() {
+                received.push(item);
+                if item == 7 {
+                    break;
+                }
+            }
+            received
+        });
+
+        let _ = system_under_test.push(7);
+
+        assert_eq!(consumer.join().unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn test_close_wakes_consumer_up() {
+        let system_under_test = Arc::new(BoundedQueue::<i32>::new(1, OverflowPolicy::DropOldest));
+        let consumer_queue = system_under_test.clone();
+        let consumer = thread::spawn(move || consumer_queue.pop());
+
+        system_under_test.close();
+
+        assert_eq!(consumer.join().unwrap(), None);
+    }
+}
@@ -0,0 +1,180 @@
+use log::info;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, EnumIter)]
+pub enum CacheKind {
+    Pipelines,
+    Bvh,
+    Shaders,
+}
+
+impl CacheKind {
+    #[must_use]
+    fn directory_name(self) -> &'static str {
+        match self {
+            CacheKind::Pipelines => "pipelines",
+            CacheKind::Bvh => "bvh",
+            CacheKind::Shaders => "shaders",
+        }
+    }
+}
+
+// the on-disk caches, a directory per kind; the manifest of a kind names the layout and the crate version
+// that have written it, so the caches of another version get wiped on the open instead of being misread
+pub struct CachesDirectory {
+    root: PathBuf,
+}
+
+impl CachesDirectory {
+    const LAYOUT_VERSION: u32 = 1;
+    const CRATE_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+    const MANIFEST_FILE_NAME: &'static str = "manifest";
+
+    // the failures are logged only: the caches just get rebuilt then
+    #[must_use]
+    pub fn open(root: impl Into<PathBuf>) -> Self {
+        let result = Self { root: root.into() };
+        for kind in CacheKind::iter() {
+            if let Err(error) = result.validate(kind) {
+                info!("failed to prepare {:?} caches in {:?}: {error}", kind, result.path_of(kind));
+            }
+        }
+        result.migrate_unstructured_caches();
+        result
+    }
+
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    #[must_use]
+    pub fn path_of(&self, kind: CacheKind) -> PathBuf {
+        self.root.join(kind.directory_name())
+    }
+
+    // deletes the cached files of all the kinds; the layout stays
+    pub fn purge(&self) -> io::Result<()> {
+        CacheKind::iter().try_for_each(|kind| self.purge_kind(kind))
+    }
+
+    pub fn purge_kind(&self, kind: CacheKind) -> io::Result<()> {
+        let directory = self.path_of(kind);
+        if directory.exists() {
+            fs::remove_dir_all(&directory)?;
+        }
+        fs::create_dir_all(&directory)?;
+        fs::write(directory.join(Self::MANIFEST_FILE_NAME), Self::manifest())
+    }
+
+    fn validate(&self, kind: CacheKind) -> io::Result<()> {
+        let manifest = fs::read_to_string(self.path_of(kind).join(Self::MANIFEST_FILE_NAME)).ok();
+        if manifest.as_deref() == Some(Self::manifest().as_str()) {
+            return Ok(());
+        }
+        if manifest.is_some() {
+            info!("{:?} caches in {:?} are of another version, purging", kind, self.path_of(kind));
+        }
+        self.purge_kind(kind)
+    }
+
+    #[must_use]
+    fn manifest() -> String {
+        format!("layout {}\ncrate {}\n", Self::LAYOUT_VERSION, Self::CRATE_VERSION)
+    }
+
+    // the caches used to lie in the root; their formats check their own versions, so they are moved as they are
+    fn migrate_unstructured_caches(&self) {
+        let Ok(entries) = fs::read_dir(&self.root) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if false == path.is_file() {
+                continue;
+            }
+            let Some(kind) = path.file_name().and_then(|name| name.to_str()).and_then(Self::unstructured_cache_kind) else {
+                continue;
+            };
+            let destination = self.path_of(kind).join(entry.file_name());
+            if let Err(error) = fs::rename(&path, &destination) {
+                info!("failed to move cache {path:?} to {destination:?}: {error}");
+            }
+        }
+    }
+
+    #[must_use]
+    fn unstructured_cache_kind(file_name: &str) -> Option<CacheKind> {
+        if file_name.ends_with("_cache") {
+            Some(CacheKind::Pipelines)
+        } else if file_name.starts_with("bvh_") && file_name.ends_with("dump") {
+            Some(CacheKind::Bvh)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_open_makes_layout() {
+        let root = tempdir().unwrap();
+
+        let system_under_test = CachesDirectory::open(root.path());
+
+        for kind in CacheKind::iter() {
+            let manifest = fs::read_to_string(system_under_test.path_of(kind).join(CachesDirectory::MANIFEST_FILE_NAME)).unwrap();
+            assert_eq!(manifest, CachesDirectory::manifest());
+        }
+    }
+
+    #[test]
+    fn test_caches_of_another_version_are_purged() {
+        let root = tempdir().unwrap();
+        let bvh = CachesDirectory::open(root.path()).path_of(CacheKind::Bvh);
+        fs::write(bvh.join("bvh_dump"), [1, 2, 3]).unwrap();
+        fs::write(bvh.join(CachesDirectory::MANIFEST_FILE_NAME), "layout 0\ncrate 0.0.0\n").unwrap();
+        let pipelines = CachesDirectory::open(root.path()).path_of(CacheKind::Pipelines);
+        fs::write(pipelines.join("0_uid_cache"), [1, 2, 3]).unwrap();
+
+        let _ = CachesDirectory::open(root.path());
+
+        assert!(false == bvh.join("bvh_dump").exists());
+        assert!(pipelines.join("0_uid_cache").exists());
+    }
+
+    #[test]
+    fn test_unstructured_caches_are_migrated() {
+        let root = tempdir().unwrap();
+        fs::write(root.path().join("0_monte_carlo_code_cache"), [1]).unwrap();
+        fs::write(root.path().join("bvh_dump"), [2]).unwrap();
+        fs::write(root.path().join("bvh_inflated_dump"), [3]).unwrap();
+        fs::write(root.path().join("notes.txt"), [4]).unwrap();
+
+        let system_under_test = CachesDirectory::open(root.path());
+
+        assert_eq!(fs::read(system_under_test.path_of(CacheKind::Pipelines).join("0_monte_carlo_code_cache")).unwrap(), vec![1]);
+        assert_eq!(fs::read(system_under_test.path_of(CacheKind::Bvh).join("bvh_dump")).unwrap(), vec![2]);
+        assert_eq!(fs::read(system_under_test.path_of(CacheKind::Bvh).join("bvh_inflated_dump")).unwrap(), vec![3]);
+        assert!(root.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_purge_keeps_layout() {
+        let root = tempdir().unwrap();
+        let system_under_test = CachesDirectory::open(root.path());
+        let shader = system_under_test.path_of(CacheKind::Shaders).join("shader");
+        fs::write(&shader, [1]).unwrap();
+
+        system_under_test.purge().unwrap();
+
+        assert!(false == shader.exists());
+        assert!(system_under_test.path_of(CacheKind::Shaders).join(CachesDirectory::MANIFEST_FILE_NAME).exists());
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+// picks the accumulation passes of the next frame for a frame time budget: the time a pass takes is
+// learned from the previous frames and smoothed, so a single slow frame does not halve the next one
+pub(crate) struct FramePacer {
+    pass_duration: Option<Duration>,
+}
+
+impl FramePacer {
+    // the weight of the newest measurement
+    const SMOOTHING: f64 = 0.25;
+
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self { pass_duration: None }
+    }
+
+    // a single pass till the first measurement, never more than 'max_passes'
+    #[must_use]
+    pub(crate) fn passes(&self, budget: Duration, max_passes: u32) -> u32 {
+        let Some(pass_duration) = self.pass_duration else {
+            return 1;
+        };
+        if pass_duration.is_zero() {
+            return max_passes;
+        }
+        let fitting = (budget.as_secs_f64() / pass_duration.as_secs_f64()).floor();
+        (fitting as u32).clamp(1, max_passes.max(1))
+    }
+
+    // 'elapsed' is from the first pass submission till the gpu has finished them all
+    pub(crate) fn record(&mut self, passes: u32, elapsed: Duration) {
+        assert!(passes > 0, "at least one pass expected");
+        let measured = elapsed / passes;
+        self.pass_duration = Some(match self.pass_duration {
+            Some(previous) => previous.mul_f64(1.0 - Self::SMOOTHING) + measured.mul_f64(Self::SMOOTHING),
+            None => measured,
+        });
+    }
+
+    // the pass time changes along with the frame buffer size and the strategy
+    pub(crate) fn forget(&mut self) {
+        self.pass_duration = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_pass_before_measurements() {
+        let system_under_test = FramePacer::new();
+
+        assert_eq!(system_under_test.passes(Duration::from_millis(12), 10), 1);
+    }
+
+    #[test]
+    fn test_passes_fit_budget() {
+        let mut system_under_test = FramePacer::new();
+
+        system_under_test.record(2, Duration::from_millis(6));
+
+        assert_eq!(system_under_test.passes(Duration::from_millis(12), 10), 4);
+        assert_eq!(system_under_test.passes(Duration::from_millis(12), 3), 3);
+        assert_eq!(system_under_test.passes(Duration::from_millis(1), 10), 1);
+    }
+
+    #[test]
+    fn test_measurements_are_smoothed() {
+        let mut system_under_test = FramePacer::new();
+
+        system_under_test.record(1, Duration::from_millis(2));
+        system_under_test.record(1, Duration::from_millis(10));
+
+        let pass_duration = system_under_test.pass_duration.unwrap().as_secs_f64();
+        assert!((pass_duration - 0.004).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_forget() {
+        let mut system_under_test = FramePacer::new();
+        system_under_test.record(1, Duration::from_millis(2));
+
+        system_under_test.forget();
+
+        assert_eq!(system_under_test.passes(Duration::from_millis(12), 10), 1);
+    }
+}
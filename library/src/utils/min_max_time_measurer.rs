@@ -24,7 +24,11 @@ impl MinMaxTimeMeasurer {
     }
     
     pub fn stop(&mut self) {
-        let delta = self.time_mark.elapsed();
+        self.add_measurement(self.time_mark.elapsed());
+    }
+
+    // for the work timed elsewhere, e.g. on another thread
+    pub fn add_measurement(&mut self, delta: Duration) {
         self.min_time = self.min_time.min(delta);
         self.max_time = self.max_time.max(delta);
         self.last_time = delta;
@@ -93,4 +97,16 @@ mod tests {
         assert!(system_under_test.max_time() >= long_duration_to_measure);
         assert!(system_under_test.min_time() <= system_under_test.max_time());
     }
+
+    #[test]
+    fn test_add_measurement() {
+        let mut system_under_test = MinMaxTimeMeasurer::new();
+
+        system_under_test.add_measurement(Duration::from_millis(20));
+        system_under_test.add_measurement(Duration::from_millis(10));
+
+        assert_eq!(system_under_test.min_time(), Duration::from_millis(10));
+        assert_eq!(system_under_test.max_time(), Duration::from_millis(20));
+        assert_eq!(system_under_test.last_time(), Duration::from_millis(10));
+    }
 }
@@ -1,10 +1,13 @@
 pub mod object_uid;
 pub mod min_max_time_measurer;
+pub mod bounded_queue;
 pub(crate) mod uid_generator;
 pub(crate) mod sliding_time_frame;
+pub(crate) mod frame_pacer;
 pub(crate) mod time_throttled_logger;
 pub(crate) mod remove_with_reorder;
 pub(crate) mod tests;
 pub(crate) mod version;
 pub mod bitmap_utils;
-pub mod file_system;
\ No newline at end of file
+pub mod file_system;
+pub mod caches_directory;
\ No newline at end of file
@@ -1,5 +1,5 @@
 use std::time::{Duration, Instant};
-use log::info;
+use log::{log, Level};
 
 pub(crate) struct TimeThrottledInfoLogger {
     interval: Duration,
@@ -16,10 +16,14 @@ impl TimeThrottledInfoLogger {
     }
 
     pub(crate) fn do_write(&mut self, message: impl Into<String>,) {
+        self.do_write_at(Level::Info, message);
+    }
+
+    pub(crate) fn do_write_at(&mut self, level: Level, message: impl Into<String>,) {
         let delta = self.last_log_action.elapsed();
         
         if delta > self.interval {
-            info!("{}", message.into());
+            log!(level, "{}", message.into());
             self.last_log_action = Instant::now() - (delta - self.interval);
         }
     }
@@ -7,7 +7,7 @@ mod beautiful_world;
 use std::env;
 use std::sync::Arc;
 use winit::application::ApplicationHandler;
-use winit::event::{MouseScrollDelta, WindowEvent};
+use winit::event::WindowEvent;
 use winit::event_loop::ControlFlow;
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::window::Window;
@@ -86,6 +86,7 @@ impl ApplicationHandler for Application {
     }
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        let taken_by_camera = self.demo.as_mut().is_some_and(|demo| demo.on_input(&event));
         match event {
             WindowEvent::CloseRequested => {
                 trace!("exiting the loop via close request");
@@ -109,16 +110,10 @@ impl ApplicationHandler for Application {
                 });
             }
             WindowEvent::MouseInput { state, button, .. } => {
+                // the left button both orbits and toggles the animation of the clicked object
                 self.demo.as_mut().map(|demo| demo.on_mouse_button(state, button));
             }
-            WindowEvent::CursorMoved { position, .. } => {
-                self.demo.as_mut().map(|demo| demo.on_mouse_move(position));
-            }
-            WindowEvent::MouseWheel { delta: MouseScrollDelta::LineDelta(_, y), .. } => {
-                self.demo.as_mut().map(|demo| demo.on_mouse_wheel(y as f64));
-            }
-
-            WindowEvent::KeyboardInput { event, .. } => {
+            WindowEvent::KeyboardInput { event, .. } if !taken_by_camera => {
                 self.demo.as_mut().map(|demo| demo.on_keyboard_event(event));
             }
             _ => (),
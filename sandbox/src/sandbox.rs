@@ -2,6 +2,11 @@ use crate::beautiful_world::{BeautifulWorldMaterials, BeautifulWorldSdfClasses,
 use crate::tech_world::{TechWorldMaterials, TechWorldSdfClasses, TechWorldProceduralTextures, TechWorld, TechWorldBitmapTextures};
 use cgmath::Deg;
 use library::geometry::alias::Point;
+use library::input::camera_controller::CameraController;
+use library::input::input_bindings::InputBindings;
+use library::input::orbit_controller::OrbitController;
+use library::scene::asset_watcher::AssetWatcher;
+use library::scene::resize_policy::ResizePolicy;
 use library::scene::camera::{Camera, OrthographicCamera, PerspectiveCamera};
 use library::utils::min_max_time_measurer::MinMaxTimeMeasurer;
 use library::utils::object_uid::ObjectUid;
@@ -10,11 +15,10 @@ use log::info;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use winit::dpi::{PhysicalPosition, PhysicalSize};
-use winit::event::{ElementState, KeyEvent, MouseButton};
-use winit::keyboard::{Key, NamedKey};
+use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, KeyEvent, MouseButton, WindowEvent};
+use winit::keyboard::Key;
 use winit::window::Window;
-use library::animation::clock_animation_act::{ClockAnimationAct, EndActionKind, Periodization, TimeDirection, WrapKind};
 use library::container::visual_objects::VisualObjects;
 use library::material::material_index::MaterialIndex;
 use library::material::procedural_textures::ProceduralTextures;
@@ -26,9 +30,7 @@ fn make_default_camera() -> Camera {
     let mut camera = Camera::new_perspective_camera(0.8, Point::new(0.0, 0.0, 0.0));
     camera.move_horizontally(0.5);
 
-    camera.set_zoom_speed(-0.3);
     camera.set_linear_speed(0.1);
-    camera.set_rotation_speed(Deg(-0.1));
 
     camera
 }
@@ -44,8 +46,7 @@ pub(super) struct Sandbox {
     tech_world: TechWorld,
     beautiful_world: BeautifulWorld,
     
-    left_mouse_down: bool,
-    last_cursor_position: Option<(f64, f64)>,
+    camera_controller: CameraController,
 
     selected_object: Option<SelectedObject>,
     selected_object_material: MaterialIndex,
@@ -57,24 +58,25 @@ impl Sandbox {
     }
 
     pub(super) fn on_redraw(&mut self, window: Arc<Window>) {
+        self.tech_world.poll_assets(self.engine.objects());
         self.engine.render_frame(|| {
             window.pre_present_notify();
         });
     }
     
-    pub(super) fn on_mouse_move(&mut self, position: PhysicalPosition<f64>) {
-        let (current_x, current_y) = (position.x, position.y);
-        if self.left_mouse_down
-            && let Some((last_x, _last_y)) = self.last_cursor_position {
-                let delta_x = current_x - last_x;
-                self.engine.camera().rotate_horizontal(delta_x);
-            }
-        self.last_cursor_position = Some((current_x, current_y));
+    // the camera takes its share of the input first: the orbit drag, the wheel and the arrows
+    pub(super) fn on_input(&mut self, event: &WindowEvent) -> bool {
+        self.camera_controller.handle_window_event(event, self.engine.camera())
+    }
+
+    #[must_use]
+    fn last_cursor_position(&self) -> Option<(f64, f64)> {
+        self.camera_controller.cursor_position().map(|position| (position.x, position.y))
     }
 
     pub(super) fn on_mouse_button(&mut self, state: ElementState, button: MouseButton) {
         if MouseButton::Right == button {
-            if let Some((last_x, last_y)) = self.last_cursor_position {
+            if let Some((last_x, last_y)) = self.last_cursor_position() {
                 let clicked_object_or_none = self.engine.object_in_pixel(last_x as u32, last_y as u32);
                 let scene = self.engine.objects();
                 
@@ -91,9 +93,8 @@ impl Sandbox {
                 }
             }
         } else if MouseButton::Left == button {
-            self.left_mouse_down = ElementState::Pressed == state;
             if ElementState::Pressed == state
-                && let Some((last_x, last_y)) = self.last_cursor_position {
+                && let Some((last_x, last_y)) = self.last_cursor_position() {
                     let clicked_object_or_none = self.engine.object_in_pixel(last_x as u32, last_y as u32);
                     let scene = self.engine.objects();
                     
@@ -101,43 +102,12 @@ impl Sandbox {
                         if scene.animator().animating(clicked_object) {
                             scene.animator_mutable().stop(clicked_object);
                         } else {
-                            let probe = Some(clicked_object);
-                            
-                            if probe == self.tech_world.infinitely_twisted_button() || probe == self.tech_world.infinitely_bent_button() {
-                                let animation = ClockAnimationAct::new()
-                                    .playback_speed_multiplier(10.0)
-                                    .make();
-                                scene.animator_mutable().launch(clicked_object, animation);
-                            }
-                            else if probe == self.tech_world.single_twisted_button() || probe == self.tech_world.single_bent_button() {
-                                let animation = ClockAnimationAct::new()
-                                    .playback_speed_multiplier(std::f64::consts::PI)
-                                    .with_global_finite_time_to_live(Duration::from_millis(1000), TimeDirection::Forward)
-                                    .make();
-                                scene.animator_mutable().launch(clicked_object, animation);
-                            }
-                            else if probe == self.tech_world.back_n_forth_twisted_button() || probe == self.tech_world.back_n_forth_bent_button() {
-                                let period = Duration::from_millis((std::f64::consts::PI * 1000.0) as u64);
-                                let animation = ClockAnimationAct::new()
-                                    .playback_speed_multiplier(2.0 * std::f64::consts::PI)
-                                    .periodization(Some(Periodization::new(WrapKind::Reverse, period)))
-                                    .with_global_finite_time_to_live(Duration::from_secs(1), TimeDirection::Forward)
-                                    .make();
-                                scene.animator_mutable().launch(clicked_object, animation);
-                            }
-                            else if probe == self.tech_world.very_slow_twisted_button() || probe == self.tech_world.very_slow_bent_button() {
-                                let animation = ClockAnimationAct::new()
-                                    .playback_speed_multiplier(std::f64::consts::PI / 3.0)
-                                    .with_global_finite_time_to_live(Duration::from_millis(2000), TimeDirection::Forward)
-                                    .end_action(EndActionKind::TeleportToZero)
-                                    .make();
-                                scene.animator_mutable().launch(clicked_object, animation);
-                            }
+                            let _ = scene.play_animation(clicked_object);
                         }
                     }
                 }
         } else if MouseButton::Middle == button && state == ElementState::Pressed
-            && let Some((last_x, last_y)) = self.last_cursor_position {
+            && let Some((last_x, last_y)) = self.last_cursor_position() {
                 let clicked_object_or_none = self.engine.object_in_pixel(last_x as u32, last_y as u32);
                 if let Some(clicked_object) = clicked_object_or_none {
                     self.engine.objects().delete(clicked_object);
@@ -150,85 +120,65 @@ impl Sandbox {
             }
     }
     
-    pub(super) fn on_mouse_wheel(&mut self, delta: f64) {
-        self.engine.camera().zoom(delta);
-    }
-    
     pub(super) fn on_keyboard_event(&mut self, event: KeyEvent) {
-        const CAMERA_MOVE_QUANT: f64 = 1.0;
         const LIGHT_MOVE_QUANT: f64 = 1.0;
-        match event.logical_key {
-            Key::Named(NamedKey::ArrowUp) => {
-                self.engine.camera().move_vertically(CAMERA_MOVE_QUANT);
-            },
-            Key::Named(NamedKey::ArrowDown) => {
-                self.engine.camera().move_vertically(-CAMERA_MOVE_QUANT);
-            },
-            Key::Named(NamedKey::ArrowRight) => {
-                self.engine.camera().move_horizontally(CAMERA_MOVE_QUANT);
-            },
-            Key::Named(NamedKey::ArrowLeft) => {
-                self.engine.camera().move_horizontally(-CAMERA_MOVE_QUANT);
-            },
-            Key::Named(NamedKey::PageUp) => {
-                self.engine.camera().move_depth_wise(-CAMERA_MOVE_QUANT);
-            },
-            Key::Named(NamedKey::PageDown) => {
-                self.engine.camera().move_depth_wise(CAMERA_MOVE_QUANT);
-            },
-            Key::Character(letter_key) => {
-                if "p" == letter_key {
-                    self.engine.camera().set_kind(Box::new(PerspectiveCamera {}));
-                } else if "o" == letter_key {
-                    self.engine.camera().set_kind(Box::new(OrthographicCamera {}));
-                } else if "d" == letter_key {
-                    self.engine.objects().dump_scene_bvh("scene_bvh.dot").unwrap_or_else(|e| {
-                        println!("Failed to dump scene_bvh.dot: {e}");
-                    });
-                } else if "r" == letter_key {
-                    self.engine.camera().set_from(&make_default_camera());
-                } else if "m" == letter_key {
-                    self.engine.use_monte_carlo_render();
-                } else if "n" == letter_key {
-                    self.engine.use_deterministic_render();
-                } else if "+" == letter_key {
-                    self.tech_world.move_light_z(LIGHT_MOVE_QUANT, self.engine.objects());
-                } else if "-" == letter_key {
-                    self.tech_world.move_light_z(-LIGHT_MOVE_QUANT, self.engine.objects());
-                } else if "*" == letter_key {
-                    self.tech_world.move_light_x(LIGHT_MOVE_QUANT, self.engine.objects());
-                } else if "/" == letter_key {
-                    self.tech_world.move_light_x(-LIGHT_MOVE_QUANT, self.engine.objects());
-                } else if "1" == letter_key {
-                    self.tech_world.load_ui_box_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "2" == letter_key {
-                    self.tech_world.load_sdf_exhibition_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "3" == letter_key {
-                    self.tech_world.load_smooth_operators_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "4" == letter_key {
-                    self.beautiful_world.load_crystal_palace_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "5" == letter_key {
-                    self.beautiful_world.load_underwater_treasure_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "6" == letter_key {
-                    self.beautiful_world.load_zen_garden_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "7" == letter_key {
-                    self.tech_world.load_triangle_mesh_testing_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "8" == letter_key {
-                    self.tech_world.load_morphing_demo_scene(self.engine.objects());
-                    self.selected_object = None;
-                } else if "9" == letter_key {
-                    self.tech_world.load_bitmap_texturing_demo_scene(self.engine.objects());
-                    self.selected_object = None;
-                }
+        if let Key::Character(letter_key) = event.logical_key {
+            if "p" == letter_key {
+                self.engine.camera().set_kind(Box::new(PerspectiveCamera {}));
+            } else if "o" == letter_key {
+                self.engine.camera().set_kind(Box::new(OrthographicCamera {}));
+            } else if "d" == letter_key {
+                self.engine.objects().dump_scene_bvh("scene_bvh.dot").unwrap_or_else(|e| {
+                    println!("Failed to dump scene_bvh.dot: {e}");
+                });
+            } else if "c" == letter_key {
+                self.engine.trigger_gpu_capture();
+            } else if "r" == letter_key {
+                self.engine.camera().set_from(&make_default_camera());
+            } else if "m" == letter_key {
+                self.engine.use_monte_carlo_render();
+            } else if "h" == letter_key {
+                self.engine.use_monte_carlo_render_with_half_resolution_indirect();
+            } else if "w" == letter_key {
+                self.engine.use_wavefront_monte_carlo_render();
+            } else if "n" == letter_key {
+                self.engine.use_deterministic_render();
+            } else if "+" == letter_key {
+                self.tech_world.move_light_z(LIGHT_MOVE_QUANT, self.engine.objects());
+            } else if "-" == letter_key {
+                self.tech_world.move_light_z(-LIGHT_MOVE_QUANT, self.engine.objects());
+            } else if "*" == letter_key {
+                self.tech_world.move_light_x(LIGHT_MOVE_QUANT, self.engine.objects());
+            } else if "/" == letter_key {
+                self.tech_world.move_light_x(-LIGHT_MOVE_QUANT, self.engine.objects());
+            } else if "1" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.tech_world.load_ui_box_scene(scene));
+                self.selected_object = None;
+            } else if "2" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.tech_world.load_sdf_exhibition_scene(scene));
+                self.selected_object = None;
+            } else if "3" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.tech_world.load_smooth_operators_scene(scene));
+                self.selected_object = None;
+            } else if "4" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.beautiful_world.load_crystal_palace_scene(scene));
+                self.selected_object = None;
+            } else if "5" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.beautiful_world.load_underwater_treasure_scene(scene));
+                self.selected_object = None;
+            } else if "6" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.beautiful_world.load_zen_garden_scene(scene));
+                self.selected_object = None;
+            } else if "7" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.tech_world.load_triangle_mesh_testing_scene(scene));
+                self.selected_object = None;
+            } else if "8" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.tech_world.load_morphing_demo_scene(scene));
+                self.selected_object = None;
+            } else if "9" == letter_key {
+                self.engine.objects().replace_objects(|scene| self.tech_world.load_bitmap_texturing_demo_scene(scene));
+                self.selected_object = None;
             }
-            _ => (),
         }
     }
     
@@ -247,31 +197,38 @@ impl Sandbox {
         let tech_world_procedural_textures = TechWorldProceduralTextures::new(&mut procedural_textures_registrator);
 
         let texture_atlas_page_size = BitmapSize::new(512, 512);
-        let mut scene = VisualObjects::new(Some(texture_atlas_page_size), Some(sdf_registrator), Some(procedural_textures_registrator));
+        let mut scene = VisualObjects::new(Some(texture_atlas_page_size), Some(sdf_registrator), Some(procedural_textures_registrator), None, None);
 
         let tech_world_bitmap_textures = TechWorldBitmapTextures::new(scene.mutable_texture_atlas_page_composer())?;
+        let mut tech_world_assets = AssetWatcher::new();
+        tech_world_bitmap_textures.watch(&mut tech_world_assets);
         let tech_materials = TechWorldMaterials::new(&mut scene, tech_world_procedural_textures, tech_world_bitmap_textures);
         let beautiful_materials = BeautifulWorldMaterials::new(&mut scene);
         
-        let mut tech_world = TechWorld::new(tech_sdf_classes, tech_materials);
+        let mut tech_world = TechWorld::new(tech_sdf_classes, tech_materials, tech_world_assets);
         let selected_object_material = tech_world.selected_object_material();
 
         let beautiful_world = BeautifulWorld::new(beautiful_sdf_classes, beautiful_materials);
 
         let caches_path = Some(PathBuf::from("./.caches"));
         let mut engine = pollster::block_on(Engine::new(window.clone(), scene, camera, caches_path))?;
+        // dragging the window border does not restart the image on every step
+        engine.set_resize_policy(ResizePolicy::KeepResolutionUntilIdle(Duration::from_millis(300)));
         
         tech_world.load_bitmap_texturing_demo_scene(engine.objects());
         
         timer.stop();
         info!("sandbox initialized in {} seconds", timer.max_time().as_secs_f64());
+        info!("hdr output: {}", engine.hdr_output_enabled());
         
         Ok(Self { 
             engine,
             tech_world,
             beautiful_world,
-            left_mouse_down: false, 
-            last_cursor_position: None, 
+            // the middle button deletes the clicked object; the drag turns the scene along with the pointer
+            camera_controller: CameraController::new(InputBindings::default().with_pan_button(None))
+                .with_orbit_controller(OrbitController::around(Point::new(0.0, 0.0, 0.0)).with_rotation_sensitivity(Deg(-100.0)))
+                .with_viewport_size(window.inner_size()),
             selected_object: None,
             selected_object_material,
         })
@@ -1,18 +1,14 @@
 use cgmath::{Deg, Vector4};
-use library::container::mesh_warehouse::MeshWarehouse;
+use library::animation::clock_animation_act::EndActionKind;
+use library::animation::play_mode::{ObjectAnimation, PlayMode};
+use library::container::mesh_warehouse::MeshLoadError;
 use library::container::texture_atlas_page_composer::{AtlasRegionUid, TextureAtlasPageComposer};
 use library::container::texture_helpers::load_bitmap;
-use library::container::visual_objects::VisualObjects;
-use library::geometry::alias::{Point, Vector};
-use library::geometry::axis::Axis;
-use library::geometry::transform::{Affine, Transformation};
 use library::material::atlas_region_mapping::{AtlasRegionMappingBuilder, WrapMode};
-use library::material::material_index::MaterialIndex;
-use library::material::material_properties::{MaterialClass, MaterialProperties};
 use library::material::procedural_texture_index::ProceduralTextureUid;
 use library::material::procedural_textures::ProceduralTextures;
 use library::material::texture_procedural_2d::TextureProcedural2D;
-use library::material::texture_reference::TextureReference;
+use library::prelude::*;
 use library::palette::material::procedural_texture_checkerboard::make_checkerboard_texture;
 use library::palette::sdf::sdf_box_frame::SdfBoxFrame;
 use library::palette::sdf::sdf_capped_cylinder_along_axis::SdfCappedCylinderAlongAxis;
@@ -31,15 +27,13 @@ use library::palette::sdf::sdf_solid_angle::SdfSolidAngle;
 use library::palette::sdf::sdf_torus_xz::SdfTorusXz;
 use library::palette::sdf::sdf_triangular_prism::SdfTriangularPrism;
 use library::palette::sdf::sdf_vesica_segment::SdfVesicaSegment;
-use library::scene::hub::Hub;
+use library::scene::asset_watcher::{AssetReload, AssetWatcher};
 use library::sdf::composition::sdf_intersection::SdfIntersection;
 use library::sdf::composition::sdf_intersection_smooth::SdfIntersectionSmooth;
 use library::sdf::composition::sdf_subtraction::SdfSubtraction;
 use library::sdf::composition::sdf_subtraction_smooth::SdfSubtractionSmooth;
 use library::sdf::composition::sdf_union::SdfUnion;
 use library::sdf::composition::sdf_union_smooth::SdfUnionSmooth;
-use library::sdf::framework::named_sdf::{NamedSdf, UniqueSdfClassName};
-use library::sdf::framework::sdf_registrator::SdfRegistrator;
 use library::sdf::morphing::sdf_bender_along_axis::SdfBenderAlongAxis;
 use library::sdf::morphing::sdf_twister_along_axis::SdfTwisterAlongAxis;
 use library::sdf::object::sdf_box::SdfBox;
@@ -47,11 +41,12 @@ use library::sdf::object::sdf_sphere::SdfSphere;
 use library::sdf::transformation::sdf_translation::SdfTranslation;
 use library::shader::code::{FunctionBody, Generic, ShaderCode};
 use library::shader::conventions;
-use library::utils::object_uid::ObjectUid;
-use log::error;
+use log::{error, info};
+use std::collections::HashMap;
 use std::env;
 use std::ops::DerefMut;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 const CONTENT_ROOT_FOLDER_NAME: &str = "assets";
 
@@ -62,6 +57,12 @@ fn get_resource_path(file_name: impl AsRef<Path>) -> PathBuf {
     exe_directory.join(file_name)
 }
 
+const BITMAP_CHECKERBOARD_SMALL_FILE: &str = "bitmap_checkerboard_small.png";
+const BITMAP_CHECKERBOARD_LARGE_FILE: &str = "bitmap_checkerboard_large.png";
+const BITMAP_HULY_FILE: &str = "bitmap_huly.png";
+const BITMAP_HULY_2_FILE: &str = "bitmap_huly_2.png";
+const BITMAP_RECT_GRID_FILE: &str = "bitmap_rect_grid.png";
+
 pub(super) struct TechWorldBitmapTextures {
     bitmap_checkerboard_small: AtlasRegionUid,
     bitmap_checkerboard_large: AtlasRegionUid,
@@ -72,11 +73,11 @@ pub(super) struct TechWorldBitmapTextures {
 
 impl TechWorldBitmapTextures {
     pub(super) fn new(composer: &mut TextureAtlasPageComposer) -> anyhow::Result<Self> {
-        let bitmap_checkerboard_small= load_bitmap(TechWorldBitmapTextures::path_to_bitmap("bitmap_checkerboard_small.png"), composer)?;
-        let bitmap_checkerboard_large= load_bitmap(TechWorldBitmapTextures::path_to_bitmap("bitmap_checkerboard_large.png"), composer)?;
-        let bitmap_huly= load_bitmap(TechWorldBitmapTextures::path_to_bitmap("bitmap_huly.png"), composer)?;
-        let bitmap_huly_2= load_bitmap(TechWorldBitmapTextures::path_to_bitmap("bitmap_huly_2.png"), composer)?;
-        let bitmap_rect_grid = load_bitmap(TechWorldBitmapTextures::path_to_bitmap("bitmap_rect_grid.png"), composer)?;
+        let bitmap_checkerboard_small= load_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_CHECKERBOARD_SMALL_FILE), composer)?;
+        let bitmap_checkerboard_large= load_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_CHECKERBOARD_LARGE_FILE), composer)?;
+        let bitmap_huly= load_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_HULY_FILE), composer)?;
+        let bitmap_huly_2= load_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_HULY_2_FILE), composer)?;
+        let bitmap_rect_grid = load_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_RECT_GRID_FILE), composer)?;
 
         composer.save_page_into(Path::new("debug_output").join("textures_atlas.png")).expect("failed to save texture atlas page");
 
@@ -89,6 +90,14 @@ impl TechWorldBitmapTextures {
         })
     }
 
+    pub(super) fn watch(&self, assets: &mut AssetWatcher) {
+        assets.watch_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_CHECKERBOARD_SMALL_FILE), self.bitmap_checkerboard_small);
+        assets.watch_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_CHECKERBOARD_LARGE_FILE), self.bitmap_checkerboard_large);
+        assets.watch_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_HULY_FILE), self.bitmap_huly);
+        assets.watch_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_HULY_2_FILE), self.bitmap_huly_2);
+        assets.watch_bitmap(TechWorldBitmapTextures::path_to_bitmap(BITMAP_RECT_GRID_FILE), self.bitmap_rect_grid);
+    }
+
     #[must_use]
     fn path_to_bitmap(file_name: &str) -> PathBuf {
         let image_path = Path::new(CONTENT_ROOT_FOLDER_NAME).join(file_name);
@@ -630,6 +639,9 @@ impl TechWorldProceduralTextures {
     }
 }
 
+const LIGHT_PANEL_LOCAL_X: Vector = Vector::new(3.0, 0.0, 0.0);
+const LIGHT_PANEL_LOCAL_Y: Vector = Vector::new(0.0, 0.0, 1.0);
+
 pub(super) struct TechWorld {
     sdf_classes: TechWorldSdfClasses,
     materials: TechWorldMaterials,
@@ -638,37 +650,25 @@ pub(super) struct TechWorld {
     light_panel_z: f64,
     light_panel_x: f64,
 
-    infinitely_twisted_button: Option<ObjectUid>,
-    single_twisted_button: Option<ObjectUid>,
-    back_n_forth_twisted_button: Option<ObjectUid>,
-    very_slow_twisted_button: Option<ObjectUid>,
-
-    infinitely_bent_button: Option<ObjectUid>,
-    single_bent_button: Option<ObjectUid>,
-    back_n_forth_bent_button: Option<ObjectUid>,
-    very_slow_bent_button: Option<ObjectUid>,
+    meshes: MeshWarehouse,
+    loaded_meshes: HashMap<&'static str, WarehouseSlot>,
+    assets: AssetWatcher,
 }
 
 impl TechWorld {
     #[must_use]
-    pub(super) fn new(sdf_classes: TechWorldSdfClasses, materials: TechWorldMaterials) -> Self {
+    pub(super) fn new(sdf_classes: TechWorldSdfClasses, materials: TechWorldMaterials, assets: AssetWatcher) -> Self {
         Self { 
             sdf_classes,
             materials,
 
+            meshes: MeshWarehouse::new(),
+            loaded_meshes: HashMap::new(),
+            assets,
+
             light_panel: None,
             light_panel_z: -1.0,
             light_panel_x: -1.0,
-
-            infinitely_twisted_button: None,
-            single_twisted_button: None,
-            back_n_forth_twisted_button: None,
-            very_slow_twisted_button: None,
-
-            infinitely_bent_button: None,
-            single_bent_button: None,
-            back_n_forth_bent_button: None,
-            very_slow_bent_button: None,
         }
     }
     
@@ -688,15 +688,23 @@ impl TechWorld {
     }
 
     fn invalidate_light_panel(&mut self, scene: &mut Hub) {
-        if let Some(light_panel) = self.light_panel {
-            scene.delete(light_panel);
+        match self.light_panel {
+            Some(light_panel) => {
+                let reshaped = scene.reshape_parallelogram(light_panel, self.light_panel_origin(), LIGHT_PANEL_LOCAL_X, LIGHT_PANEL_LOCAL_Y);
+                assert!(reshaped, "light panel is not a parallelogram");
+            },
+            None => self.make_light_panel(scene),
         }
-        self.make_light_panel(scene);
+    }
+
+    #[must_use]
+    fn light_panel_origin(&self) -> Point {
+        Point::new(self.light_panel_x, 1.0, self.light_panel_z)
     }
 
     fn make_light_panel(&mut self, scene: &mut Hub) {
         self.light_panel = Some(
-            scene.add_parallelogram(Point::new(self.light_panel_x, 1.0, self.light_panel_z), Vector::new(3.0, 0.0, 0.0), Vector::new(0.0, 0.0, 1.0), self.materials.light)
+            scene.add_parallelogram(self.light_panel_origin(), LIGHT_PANEL_LOCAL_X, LIGHT_PANEL_LOCAL_Y, self.materials.light)
         );
     }
 
@@ -709,18 +717,33 @@ impl TechWorld {
 
     fn clear_scene(&mut self, scene: &mut Hub) {
         scene.clear_objects();
-        
-        self.infinitely_twisted_button = None;
-        self.single_twisted_button = None;
-        self.back_n_forth_twisted_button = None;
-        self.very_slow_twisted_button = None;
-
-        self.infinitely_bent_button = None;
-        self.single_bent_button = None;
-        self.back_n_forth_bent_button = None;
-        self.very_slow_bent_button = None;
-        
         self.light_panel = None;
+        self.assets.unwatch_meshes();
+    }
+
+    // edited mesh and bitmap files show up in the scene without a restart
+    pub(super) fn poll_assets(&mut self, scene: &mut Hub) {
+        for reload in self.assets.poll(&mut self.meshes, scene) {
+            match reload {
+                AssetReload::Reloaded { path } => info!("reloaded {}", path.display()),
+                AssetReload::Failed { path, reason } => error!("failed to reload {}: {reason}", path.display()),
+            }
+        }
+    }
+
+    fn load_mesh(&mut self, file_name: &'static str) -> Result<WarehouseSlot, MeshLoadError> {
+        if let Some(slot) = self.loaded_meshes.get(file_name) {
+            return Ok(*slot);
+        }
+        let mesh_file = get_resource_path(Path::new(CONTENT_ROOT_FOLDER_NAME).join(file_name));
+        let slot = self.meshes.load(mesh_file)?;
+        self.loaded_meshes.insert(file_name, slot);
+        Ok(slot)
+    }
+
+    fn add_watched_mesh(&mut self, scene: &mut Hub, mesh: WarehouseSlot, location: &Transformation, material: MaterialIndex, shading: MeshShading) {
+        let instance = scene.add_mesh(&self.meshes, mesh, location, material, shading);
+        self.assets.watch_mesh(&self.meshes, mesh, instance);
     }
 
     pub(super) fn load_smooth_operators_scene(&mut self, scene: &mut Hub) {
@@ -859,9 +882,7 @@ impl TechWorld {
 
         self.make_common_scene_walls(scene);
 
-        let mut meshes = MeshWarehouse::new();
-        let mesh_file = get_resource_path(Path::new(CONTENT_ROOT_FOLDER_NAME).join("monkey.obj"));
-        let mesh_or_error = meshes.load(mesh_file);
+        let mesh_or_error = self.load_mesh("monkey.obj");
 
         match mesh_or_error {
             Ok(mesh) => {
@@ -870,7 +891,7 @@ impl TechWorld {
                     Affine::from_translation(Vector::new(0.5, 0.0, 0.0)) *
                         Affine::from_scale(1.0)
                     );
-                scene.add_mesh(&meshes, mesh, &location, self.materials.black);
+                self.add_watched_mesh(scene, mesh, &location, self.materials.black, MeshShading::Smooth);
             },
             Err(mesh_loading_error) => {
                 error!("failed to load mesh: {mesh_loading_error}");
@@ -887,61 +908,41 @@ impl TechWorld {
         scene.add_parallelogram(Point::new(-1.0, -1.1, -0.5), Vector::new(0.0, 0.0, -0.5), Vector::new(0.0, 2.1, 0.0), self.materials.red);
         scene.add_parallelogram(Point::new(2.0, -1.1, -1.0), Vector::new(0.0, 0.0, 0.5), Vector::new(0.0, 2.1, 0.0), self.materials.green);
 
+        // a click on a button plays its animation
+
+        let infinite = ObjectAnimation::new(PlayMode::Infinite)
+            .with_speed(10.0);
+        let single = ObjectAnimation::new(PlayMode::Single { duration: Duration::from_millis(1000), end_action: EndActionKind::LeaveAsIs })
+            .with_speed(std::f64::consts::PI);
+        let back_n_forth = ObjectAnimation::new(PlayMode::BackAndForth { period: Duration::from_millis((std::f64::consts::PI * 1000.0) as u64), duration: Some(Duration::from_secs(1)) })
+            .with_speed(2.0 * std::f64::consts::PI);
+        let very_slow = ObjectAnimation::new(PlayMode::Single { duration: Duration::from_millis(2000), end_action: EndActionKind::TeleportToZero })
+            .with_speed(std::f64::consts::PI / 3.0);
+
         // twist demo
 
         const TWIST_RAY_MARCH_FIX: f64 = 0.9;
 
-        self.infinitely_twisted_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.2, 0.3, 0.0)),
-            TWIST_RAY_MARCH_FIX,
-            self.sdf_classes.twisted_box.name(),
-            self.materials.red_glass));
-
-        self.single_twisted_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.2, 0.1, 0.0)),
-            TWIST_RAY_MARCH_FIX,
-            self.sdf_classes.twisted_box.name(),
-            self.materials.gold_metal));
-
-        self.back_n_forth_twisted_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.2, -0.1, 0.0)),
-            TWIST_RAY_MARCH_FIX,
-            self.sdf_classes.twisted_box.name(),
-            self.materials.blue));
-
-        self.very_slow_twisted_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.2, -0.3, 0.0)),
-            TWIST_RAY_MARCH_FIX,
-            self.sdf_classes.twisted_box.name(),
-            self.materials.green_mirror));
+        let twisted_box = self.sdf_classes.twisted_box.name().clone();
+        Self::add_animated_button(scene, Vector::new(0.2,  0.3, 0.0), TWIST_RAY_MARCH_FIX, &twisted_box, self.materials.red_glass, infinite);
+        Self::add_animated_button(scene, Vector::new(0.2,  0.1, 0.0), TWIST_RAY_MARCH_FIX, &twisted_box, self.materials.gold_metal, single);
+        Self::add_animated_button(scene, Vector::new(0.2, -0.1, 0.0), TWIST_RAY_MARCH_FIX, &twisted_box, self.materials.blue, back_n_forth);
+        Self::add_animated_button(scene, Vector::new(0.2, -0.3, 0.0), TWIST_RAY_MARCH_FIX, &twisted_box, self.materials.green_mirror, very_slow);
 
         // bend demo
 
         const BEND_RAY_MARCH_FIX: f64 = 0.7;
 
-        self.infinitely_bent_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.7, 0.3, 0.0)),
-            BEND_RAY_MARCH_FIX,
-            self.sdf_classes.bent_box.name(),
-            self.materials.green));
-
-        self.single_bent_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.7, 0.1, 0.0)),
-            BEND_RAY_MARCH_FIX,
-            self.sdf_classes.bent_box.name(),
-            self.materials.coral));
-
-        self.back_n_forth_bent_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.7, -0.1, 0.0)),
-            BEND_RAY_MARCH_FIX,
-            self.sdf_classes.bent_box.name(),
-            self.materials.purple_glass));
-
-        self.very_slow_bent_button = Some(scene.add_sdf_with_ray_march_fix(
-            &Affine::from_translation(Vector::new(0.7, -0.3, 0.0)),
-            BEND_RAY_MARCH_FIX,
-            self.sdf_classes.bent_box.name(),
-            self.materials.red));
+        let bent_box = self.sdf_classes.bent_box.name().clone();
+        Self::add_animated_button(scene, Vector::new(0.7,  0.3, 0.0), BEND_RAY_MARCH_FIX, &bent_box, self.materials.green, infinite);
+        Self::add_animated_button(scene, Vector::new(0.7,  0.1, 0.0), BEND_RAY_MARCH_FIX, &bent_box, self.materials.coral, single);
+        Self::add_animated_button(scene, Vector::new(0.7, -0.1, 0.0), BEND_RAY_MARCH_FIX, &bent_box, self.materials.purple_glass, back_n_forth);
+        Self::add_animated_button(scene, Vector::new(0.7, -0.3, 0.0), BEND_RAY_MARCH_FIX, &bent_box, self.materials.red, very_slow);
+    }
+
+    fn add_animated_button(scene: &mut Hub, location: Vector, ray_march_fix: f64, class: &UniqueSdfClassName, material: MaterialIndex, animation: ObjectAnimation) {
+        let button = scene.add_sdf_with_ray_march_fix(&Affine::from_translation(location), ray_march_fix, class, material);
+        scene.configure_animation(button, animation);
     }
 
     pub(super) fn load_ui_box_scene(&mut self, scene: &mut Hub) {
@@ -984,9 +985,7 @@ impl TechWorld {
 
         self.make_common_scene_walls(scene);
 
-        let mut meshes = MeshWarehouse::new();
-        let cube_mesh_file = get_resource_path(Path::new(CONTENT_ROOT_FOLDER_NAME).join("cube.obj"));
-        let cube_mesh_or_error = meshes.load(cube_mesh_file);
+        let cube_mesh_or_error = self.load_mesh("cube.obj");
         
         match cube_mesh_or_error {
             Ok(cube_mesh) => {
@@ -994,24 +993,24 @@ impl TechWorld {
                     Transformation::new(
                         Affine::from_translation(Vector::new(0.15, 0.6, -1.0)) *
                             Affine::from_nonuniform_scale(3.65, 0.8, 0.25));
-                scene.add_mesh(&meshes, cube_mesh, &large_box_location, self.materials.large_box_material);
+                self.add_watched_mesh(scene, cube_mesh, &large_box_location, self.materials.large_box_material, MeshShading::Flat);
         
                 {
                     let box_location =Transformation::new(
                         Affine::from_translation(Vector::new(-0.4, 0.1, -1.0)) * Affine::from_scale(0.4));
-                    scene.add_mesh(&meshes, cube_mesh, &box_location, self.materials.gold_metal);
+                    self.add_watched_mesh(scene, cube_mesh, &box_location, self.materials.gold_metal, MeshShading::Flat);
                 }
         
                 {
                     let box_location = Transformation::new(
                         Affine::from_translation(Vector::new(0.9, -0.4, -1.0)) * Affine::from_scale(0.4));
-                    scene.add_mesh(&meshes, cube_mesh, &box_location, self.materials.purple_glass);
+                    self.add_watched_mesh(scene, cube_mesh, &box_location, self.materials.purple_glass, MeshShading::Flat);
                 }
         
                 {
                     let box_location = Transformation::new(
                         Affine::from_translation(Vector::new(0.4, 0.1, 0.2)) * Affine::from_nonuniform_scale(0.9, 0.9, 0.1));
-                    scene.add_mesh(&meshes, cube_mesh, &box_location, self.materials.red_glass);
+                    self.add_watched_mesh(scene, cube_mesh, &box_location, self.materials.red_glass, MeshShading::Flat);
                 }
             },
             Err(mesh_loading_error) => {
@@ -1071,38 +1070,4 @@ impl TechWorld {
             self.materials.huly_icon_2_vertically_repeated
         );
     }
-
-    #[must_use]
-    pub(super) fn infinitely_twisted_button(&self) -> Option<ObjectUid> {
-        self.infinitely_twisted_button
-    }
-    #[must_use]
-    pub(super) fn single_twisted_button(&self) -> Option<ObjectUid> {
-        self.single_twisted_button
-    }
-    #[must_use]
-    pub(super) fn back_n_forth_twisted_button(&self) -> Option<ObjectUid> {
-        self.back_n_forth_twisted_button
-    }
-    #[must_use]
-    pub(super) fn very_slow_twisted_button(&self) -> Option<ObjectUid> {
-        self.very_slow_twisted_button
-    }
-
-    #[must_use]
-    pub(super) fn infinitely_bent_button(&self) -> Option<ObjectUid> {
-        self.infinitely_bent_button
-    }
-    #[must_use]
-    pub(super) fn single_bent_button(&self) -> Option<ObjectUid> {
-        self.single_bent_button
-    }
-    #[must_use]
-    pub(super) fn back_n_forth_bent_button(&self) -> Option<ObjectUid> {
-        self.back_n_forth_bent_button
-    }
-    #[must_use]
-    pub(super) fn very_slow_bent_button(&self) -> Option<ObjectUid> {
-        self.very_slow_bent_button
-    }
 }